@@ -79,6 +79,13 @@ define_static_channels! {
         (Result<(), ClientError>, 8),
         (Result<RawPayload, ClientError>, 4),
         (Result<RebootFlag, ClientError>, 4),
+        (Result<Option<Duration>, ClientError>, 4),
+        (Result<simple_someip::PeerTable, ClientError>, 4),
+        (Result<simple_someip::ServiceEndpointKey, ClientError>, 4),
+        (Result<simple_someip::JanitorStats, ClientError>, 4),
+        (Result<simple_someip::DropStats, ClientError>, 4),
+        (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+        (Result<simple_someip::client::SubscriptionState, ClientError>, 4),
     ],
     bounded: [
         ((ControlMessage<RawPayload, BareMetalChannels>, 4), 1),
@@ -309,6 +316,7 @@ async fn main() {
                 static POOL: BufferPool<10, UDP_BUFFER_SIZE> = BufferPool::new();
                 StaticBufferProvider(&POOL)
             },
+            stream_factory: simple_someip::NoStreams,
         },
         false, // multicast_loopback
     );
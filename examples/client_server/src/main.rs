@@ -51,7 +51,7 @@ fn build_sd_header(interface: Ipv4Addr) -> VecSdHeader {
     let find_remote = Entry::FindService(ServiceEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(0, 0),
+        options_count: OptionsCount::NONE,
         service_id: REMOTE_SERVICE_ID,
         instance_id: REMOTE_INSTANCE_ID,
         major_version: 1,
@@ -62,7 +62,7 @@ fn build_sd_header(interface: Ipv4Addr) -> VecSdHeader {
     let offer_mine = Entry::OfferService(ServiceEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(1, 0),
+        options_count: OptionsCount::ONE,
         service_id: MY_SERVER_SERVICE_ID,
         instance_id: MY_SERVER_INSTANCE_ID,
         major_version: 1,
@@ -176,6 +176,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             ClientUpdate::SenderRebooted(addr) => {
                 warn!("Sender {addr} rebooted");
             }
+            ClientUpdate::OfferExpiring { key, remaining, .. } => {
+                warn!("Offer for {key:?} expires in {remaining:?}");
+            }
+            ClientUpdate::ServiceAvailable { key, instance_id } => {
+                info!("Service {key:?} (instance 0x{instance_id:04X}) available");
+            }
+            ClientUpdate::ServiceUnavailable { key, reason, .. } => {
+                warn!("Service {key:?} unavailable: {reason:?}");
+            }
+            ClientUpdate::Reachability { key, reachable } => {
+                warn!("Endpoint {key:?} reachable: {reachable}");
+            }
+            ClientUpdate::Resubscribed {
+                key,
+                event_group_id,
+            } => {
+                info!("Resubscribed to group 0x{event_group_id:04X} of {key:?}");
+            }
             ClientUpdate::Unicast { message, .. } => {
                 info!(
                     "Received unicast: service=0x{:04X}",
@@ -321,6 +321,24 @@ async fn main() -> Result<(), Error> {
                 state.services.clear();
                 state.event_groups.clear();
             }
+            simple_someip::ClientUpdate::OfferExpiring { key, remaining, .. } => {
+                warn!("Offer for {key:?} expires in {remaining:?}");
+            }
+            simple_someip::ClientUpdate::ServiceAvailable { key, instance_id } => {
+                info!("Service {key:?} (instance 0x{instance_id:04X}) available");
+            }
+            simple_someip::ClientUpdate::ServiceUnavailable { key, reason, .. } => {
+                warn!("Service {key:?} unavailable: {reason:?}");
+            }
+            simple_someip::ClientUpdate::Reachability { key, reachable } => {
+                warn!("Endpoint {key:?} reachable: {reachable}");
+            }
+            simple_someip::ClientUpdate::Resubscribed {
+                key,
+                event_group_id,
+            } => {
+                info!("Resubscribed to group 0x{event_group_id:04X} of {key:?}");
+            }
             simple_someip::ClientUpdate::Unicast { message, .. } => {
                 info!("Unicast message: {:?}", message.header());
             }
@@ -57,7 +57,10 @@ use simple_someip::client::{ClientUpdate, ControlMessage, ReceivedMessage, SendM
 use simple_someip::define_static_channels;
 use simple_someip::e2e::E2ERegistry;
 use simple_someip::protocol::sd::RebootFlag;
-use simple_someip::server::{ServerConfig, SubscribeError, Subscriber, SubscriptionHandle};
+use simple_someip::server::{
+    ExpiredSubscribers, ServerConfig, SubscribeError, SubscribeOutcome, Subscriber,
+    SubscriptionHandle,
+};
 use simple_someip::static_channels::BufferPool;
 use simple_someip::transport::{LocalSpawner, StaticBufferProvider, Timer};
 use simple_someip::{Client, ClientDeps, RawPayload, Server, ServerDeps};
@@ -230,6 +233,13 @@ define_static_channels! {
         (Result<(), ClientError>, 8),
         (Result<RawPayload, ClientError>, 4),
         (Result<RebootFlag, ClientError>, 4),
+        (Result<Option<Duration>, ClientError>, 4),
+        (Result<simple_someip::PeerTable, ClientError>, 4),
+        (Result<simple_someip::ServiceEndpointKey, ClientError>, 4),
+        (Result<simple_someip::JanitorStats, ClientError>, 4),
+        (Result<simple_someip::DropStats, ClientError>, 4),
+        (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+        (Result<simple_someip::client::SubscriptionState, ClientError>, 4),
     ],
     bounded: [
         ((ControlMessage<RawPayload, ExampleChannels>, 4), 2),
@@ -271,8 +281,9 @@ struct InMemorySubscriptions(Arc<Mutex<Vec<SubKey>>>);
 
 impl SubscriptionHandle for InMemorySubscriptions {
     type SubscribeFuture<'a> =
-        core::pin::Pin<Box<dyn Future<Output = Result<(), SubscribeError>> + 'a>>;
+        core::pin::Pin<Box<dyn Future<Output = Result<SubscribeOutcome, SubscribeError>> + 'a>>;
     type UnsubscribeFuture<'a> = core::pin::Pin<Box<dyn Future<Output = ()> + 'a>>;
+    type ExpireFuture<'a> = core::future::Ready<ExpiredSubscribers>;
 
     fn subscribe(
         &self,
@@ -285,10 +296,11 @@ impl SubscriptionHandle for InMemorySubscriptions {
         Box::pin(async move {
             let mut g = this.lock().unwrap();
             let k = (service_id, instance_id, event_group_id, subscriber_addr);
-            if !g.contains(&k) {
-                g.push(k);
+            if g.contains(&k) {
+                return Ok(SubscribeOutcome::Refreshed);
             }
-            Ok(())
+            g.push(k);
+            Ok(SubscribeOutcome::New)
         })
     }
 
@@ -329,6 +341,10 @@ impl SubscriptionHandle for InMemorySubscriptions {
             n
         }
     }
+
+    fn expire(&self, _now: core::time::Duration) -> Self::ExpireFuture<'_> {
+        core::future::ready(ExpiredSubscribers::new())
+    }
 }
 
 // ── main ─────────────────────────────────────────────────────────────
@@ -421,6 +437,7 @@ async fn main() {
                 e2e_registry: client_e2e,
                 interface: client_iface,
                 buffer_provider: StaticBufferProvider(buf_pool),
+                stream_factory: simple_someip::NoStreams,
             };
 
             let (client, mut updates, run_fut) = Client::<
@@ -409,7 +409,10 @@ use simple_someip::protocol::sd::RebootFlag;
 use simple_someip::protocol::{
     Header as SomeIpHeader, Message, MessageId, MessageType, MessageTypeField, ReturnCode,
 };
-use simple_someip::server::{ServerConfig, SubscribeError, Subscriber, SubscriptionHandle};
+use simple_someip::server::{
+    ExpiredSubscribers, ServerConfig, SubscribeError, SubscribeOutcome, Subscriber,
+    SubscriptionHandle,
+};
 use simple_someip::transport::{LocalSpawner, Timer};
 use simple_someip::{Client, ClientDeps, RawPayload, Server, ServerDeps, ServiceEndpointKey};
 
@@ -426,6 +429,13 @@ define_static_channels! {
         (Result<(), ClientError>, 16),
         (Result<RawPayload, ClientError>, 8),
         (Result<RebootFlag, ClientError>, 8),
+        (Result<Option<Duration>, ClientError>, 8),
+        (Result<simple_someip::PeerTable, ClientError>, 8),
+        (Result<simple_someip::ServiceEndpointKey, ClientError>, 8),
+        (Result<simple_someip::JanitorStats, ClientError>, 8),
+        (Result<simple_someip::DropStats, ClientError>, 8),
+        (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+        (Result<simple_someip::client::SubscriptionState, ClientError>, 8),
     ],
     bounded: [
         ((ControlMessage<RawPayload, LoopbackTestChannels>, 4), 4),
@@ -474,9 +484,11 @@ impl SubscriptionHandle for MockSubscriptions {
     // Boxed `!Send` futures — the `spawn_local` paths that exercise
     // this loopback don't need `Send` and the `Mutex` is only used
     // synchronously inside.
-    type SubscribeFuture<'a> =
-        core::pin::Pin<Box<dyn core::future::Future<Output = Result<(), SubscribeError>> + 'a>>;
+    type SubscribeFuture<'a> = core::pin::Pin<
+        Box<dyn core::future::Future<Output = Result<SubscribeOutcome, SubscribeError>> + 'a>,
+    >;
     type UnsubscribeFuture<'a> = core::pin::Pin<Box<dyn core::future::Future<Output = ()> + 'a>>;
+    type ExpireFuture<'a> = core::future::Ready<ExpiredSubscribers>;
 
     fn subscribe(
         &self,
@@ -489,10 +501,11 @@ impl SubscriptionHandle for MockSubscriptions {
         Box::pin(async move {
             let mut guard = this.lock().unwrap();
             let key = (service_id, instance_id, event_group_id, subscriber_addr);
-            if !guard.contains(&key) {
-                guard.push(key);
+            if guard.contains(&key) {
+                return Ok(SubscribeOutcome::Refreshed);
             }
-            Ok(())
+            guard.push(key);
+            Ok(SubscribeOutcome::New)
         })
     }
 
@@ -534,6 +547,10 @@ impl SubscriptionHandle for MockSubscriptions {
             count
         }
     }
+
+    fn expire(&self, _now: core::time::Duration) -> Self::ExpireFuture<'_> {
+        core::future::ready(ExpiredSubscribers::new())
+    }
 }
 
 // `Poll` is imported above for `LocalSpawner` impls; flag it as
@@ -653,6 +670,7 @@ async fn client_receives_server_sd_announcement() {
                 e2e_registry: client_e2e,
                 interface: client_iface,
                 buffer_provider: StaticBufferProvider(buf_pool),
+                stream_factory: simple_someip::NoStreams,
             };
 
             let (client, mut updates, run_fut) =
@@ -777,6 +795,7 @@ async fn client_send_request_server_runloop_stable() {
                 e2e_registry: client_e2e,
                 interface: client_iface,
                 buffer_provider: StaticBufferProvider(buf_pool),
+                stream_factory: simple_someip::NoStreams,
             };
 
             let (client, _updates, run_fut) = Client::<
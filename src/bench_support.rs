@@ -0,0 +1,354 @@
+//! Message corpora and timing helpers for benchmarking payload codecs
+//! against the crate's own decode hot paths.
+//!
+//! Everything here is deterministic: the same arguments always produce
+//! the same bytes, so numbers from different machines or different runs
+//! compare like for like. The helpers do not depend on a benchmark
+//! framework; with criterion, build a [`Corpus`] once and hand one of
+//! the hot-path functions to the bencher:
+//!
+//! ```ignore
+//! use simple_someip::bench_support::{self, Corpus};
+//!
+//! let corpus = Corpus::mixed(256, 7);
+//! c.bench_function("someip/decode", |b| {
+//!     b.iter(|| bench_support::decode_messages(&corpus))
+//! });
+//! c.bench_function("my_codec/decode", |b| {
+//!     b.iter(|| corpus.iter().map(my_codec::decode).count())
+//! });
+//! ```
+//!
+//! Without a framework, [`measure`] gives a quick wall-clock reading.
+//!
+//! [`Corpus`]: crate::bench_support::Corpus
+//! [`measure`]: crate::bench_support::measure
+
+use core::hint::black_box;
+use core::net::Ipv4Addr;
+use core::time::Duration;
+use std::time::Instant;
+use std::vec::Vec;
+
+use crate::protocol::sd::{self, Entry, Flags, OptionsCount, RebootFlag, ServiceEntry};
+use crate::protocol::{
+    Header, HeaderView, MessageId, MessageType, MessageTypeField, MessageView, ReturnCode,
+};
+use crate::traits::WireFormat;
+
+/// Deterministic filler payload of `len` bytes derived from `seed`.
+#[must_use]
+pub fn payload(len: usize, seed: u32) -> Vec<u8> {
+    // xorshift32; a zero state would stay zero.
+    let mut state = seed | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+/// Encoded SOME/IP `Request` for `message_id` carrying `payload`.
+#[must_use]
+pub fn request(message_id: MessageId, request_id: u32, payload: &[u8]) -> Vec<u8> {
+    let header = Header::new(
+        message_id,
+        request_id,
+        0x01,
+        0x01,
+        MessageTypeField::new(MessageType::Request, false),
+        ReturnCode::Ok,
+        payload.len(),
+    );
+    with_payload(&header, payload)
+}
+
+/// Encoded SOME/IP event `Notification` carrying `payload`.
+#[must_use]
+pub fn notification(service_id: u16, event_id: u16, request_id: u32, payload: &[u8]) -> Vec<u8> {
+    let header = Header::new_event(service_id, event_id, request_id, 0x01, 0x01, payload.len());
+    with_payload(&header, payload)
+}
+
+/// Encoded SOME/IP-SD message with `entries` `OfferService` entries for
+/// consecutive service IDs, each with its own IPv4 endpoint option.
+///
+/// # Panics
+///
+/// Panics if `entries` exceeds 255, the most options an SD entry can
+/// index.
+#[must_use]
+pub fn sd_offer(session_id: u16, entries: usize) -> Vec<u8> {
+    let count = u8::try_from(entries).expect("at most 255 offer entries");
+    let entries: Vec<Entry> = (0..count)
+        .map(|i| {
+            Entry::OfferService(ServiceEntry {
+                index_first_options_run: i,
+                index_second_options_run: 0,
+                options_count: OptionsCount::ONE,
+                service_id: 0x1000 + u16::from(i),
+                instance_id: 1,
+                major_version: 1,
+                ttl: 3,
+                minor_version: 0,
+            })
+        })
+        .collect();
+    let options: Vec<sd::Options> = (0..count)
+        .map(|i| sd::Options::IpV4Endpoint {
+            ip: Ipv4Addr::new(192, 168, 0, 10),
+            port: 30_000 + u16::from(i),
+            protocol: sd::TransportProtocol::Udp,
+        })
+        .collect();
+    let sd_header = sd::Header::new(Flags::new_sd(RebootFlag::Continuous), &entries, &options);
+    let mut sd_bytes = std::vec![0u8; sd_header.required_size()];
+    // Cannot fail: the buffer is sized by `required_size`.
+    let _ = sd_header.encode_to_slice(&mut sd_bytes);
+    with_payload(
+        &Header::new_sd(u32::from(session_id), sd_bytes.len()),
+        &sd_bytes,
+    )
+}
+
+fn with_payload(header: &Header, payload: &[u8]) -> Vec<u8> {
+    let mut datagram = std::vec![0u8; header.required_size() + payload.len()];
+    // Cannot fail: the buffer is sized by `required_size`.
+    let _ = header.encode_to_slice(&mut datagram);
+    datagram[header.required_size()..].copy_from_slice(payload);
+    datagram
+}
+
+/// A set of encoded datagrams to run a codec over.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Corpus {
+    datagrams: Vec<Vec<u8>>,
+}
+
+impl Corpus {
+    /// An empty corpus.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            datagrams: Vec::new(),
+        }
+    }
+
+    /// `count` requests to method `0x1234.0x0001`, each with a
+    /// `payload_len`-byte payload.
+    #[must_use]
+    pub fn requests(count: u32, payload_len: usize) -> Self {
+        let message_id = MessageId::new_from_service_and_method(0x1234, 0x0001);
+        (0..count)
+            .map(|i| request(message_id, i + 1, &payload(payload_len, i)))
+            .collect()
+    }
+
+    /// `count` notifications of event `0x1234.0x8001`, each with a
+    /// `payload_len`-byte payload.
+    #[must_use]
+    pub fn notifications(count: u32, payload_len: usize) -> Self {
+        (0..count)
+            .map(|i| notification(0x1234, 0x8001, i + 1, &payload(payload_len, i)))
+            .collect()
+    }
+
+    /// `count` SD messages with `entries` offers each.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`sd_offer`].
+    #[must_use]
+    pub fn sd_offers(count: u16, entries: usize) -> Self {
+        (0..count).map(|i| sd_offer(i + 1, entries)).collect()
+    }
+
+    /// `count` datagrams resembling live traffic: mostly notifications,
+    /// some requests, and an SD offer every eighth message, with payload
+    /// sizes spread from 0 to 1400 bytes. The same `seed` always yields
+    /// the same corpus.
+    #[must_use]
+    pub fn mixed(count: u32, seed: u32) -> Self {
+        let sizes = payload(usize::try_from(count).unwrap_or(usize::MAX), seed);
+        let message_id = MessageId::new_from_service_and_method(0x1234, 0x0001);
+        (0..count)
+            .zip(sizes)
+            .map(|(i, size)| {
+                let len = usize::from(size) * 1400 / 255;
+                let body = payload(len, seed.wrapping_add(i));
+                match i % 8 {
+                    0 => sd_offer(u16::try_from(i / 8 % 0xFFFF + 1).unwrap_or(1), 2),
+                    1 | 5 => request(message_id, i + 1, &body),
+                    _ => notification(0x1234, 0x8001, i + 1, &body),
+                }
+            })
+            .collect()
+    }
+
+    /// Add one encoded datagram.
+    pub fn push(&mut self, datagram: Vec<u8>) {
+        self.datagrams.push(datagram);
+    }
+
+    /// The datagrams, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.datagrams.iter().map(Vec::as_slice)
+    }
+
+    /// Number of datagrams.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.datagrams.len()
+    }
+
+    /// `true` if the corpus holds no datagram.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.datagrams.is_empty()
+    }
+
+    /// Size of all datagrams together, for throughput figures.
+    #[must_use]
+    pub fn total_bytes(&self) -> usize {
+        self.datagrams.iter().map(Vec::len).sum()
+    }
+}
+
+impl FromIterator<Vec<u8>> for Corpus {
+    fn from_iter<I: IntoIterator<Item = Vec<u8>>>(iter: I) -> Self {
+        Self {
+            datagrams: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Parse only the 16-byte SOME/IP header of every datagram, as the
+/// receive loops do before dispatching. Returns the number of datagrams
+/// that parsed.
+#[must_use]
+pub fn decode_headers(corpus: &Corpus) -> usize {
+    corpus
+        .iter()
+        .filter(|datagram| HeaderView::parse(black_box(datagram)).is_ok())
+        .count()
+}
+
+/// Fully decode every datagram the way the client and server receive
+/// paths do: the SOME/IP message, and for SD messages every entry and
+/// option. Returns the number of datagrams that decoded.
+#[must_use]
+pub fn decode_messages(corpus: &Corpus) -> usize {
+    corpus
+        .iter()
+        .filter(|datagram| {
+            let Ok(view) = MessageView::parse(black_box(datagram)) else {
+                return false;
+            };
+            if !view.is_sd() {
+                black_box(view.payload_bytes());
+                return true;
+            }
+            let Ok(sd) = view.sd_header() else {
+                return false;
+            };
+            sd.entries()
+                .all(|entry| black_box(entry.to_owned()).is_ok())
+                && sd
+                    .options()
+                    .all(|option| black_box(option.to_owned()).is_ok())
+        })
+        .count()
+}
+
+/// Result of [`measure`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    /// Timed iterations, not counting the warm-up call.
+    pub iterations: u32,
+    /// Wall-clock time of all timed iterations.
+    pub elapsed: Duration,
+    /// Bytes processed by all timed iterations, as reported by the
+    /// measured closure.
+    pub bytes: u64,
+}
+
+impl Measurement {
+    /// Mean time per iteration.
+    #[must_use]
+    pub fn per_iteration(&self) -> Duration {
+        self.elapsed / self.iterations.max(1)
+    }
+
+    /// Bytes processed per second, or `0.0` if nothing was timed.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn bytes_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+        self.bytes as f64 / secs
+    }
+}
+
+/// Call `f` once to warm up, then `iterations` more times under a
+/// wall-clock timer. `f` returns the number of bytes it processed; the
+/// return value is passed through [`black_box`] so the work cannot be
+/// optimized away.
+pub fn measure(iterations: u32, mut f: impl FnMut() -> usize) -> Measurement {
+    black_box(f());
+    let mut bytes = 0u64;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        bytes += black_box(f()) as u64;
+    }
+    Measurement {
+        iterations,
+        elapsed: start.elapsed(),
+        bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corpora_decode_and_are_deterministic() {
+        let mixed = Corpus::mixed(64, 7);
+        assert_eq!(mixed, Corpus::mixed(64, 7));
+        assert_ne!(mixed, Corpus::mixed(64, 8));
+        assert_eq!(decode_headers(&mixed), 64);
+        assert_eq!(decode_messages(&mixed), 64);
+
+        let offers = Corpus::sd_offers(4, 3);
+        assert_eq!(decode_messages(&offers), 4);
+        let view = MessageView::parse(offers.iter().next().unwrap()).unwrap();
+        assert_eq!(view.sd_header().unwrap().entry_count(), 3);
+
+        let requests = Corpus::requests(3, 100);
+        assert_eq!(requests.total_bytes(), 3 * 116);
+
+        let mut broken = Corpus::notifications(2, 8);
+        broken.push(std::vec![0xFF; 4]);
+        assert_eq!(decode_messages(&broken), 2);
+    }
+
+    #[test]
+    fn measure_counts_bytes_of_timed_iterations() {
+        let corpus = Corpus::notifications(4, 32);
+        let calls = core::cell::Cell::new(0);
+        let m = measure(10, || {
+            calls.set(calls.get() + 1);
+            black_box(decode_messages(&corpus));
+            corpus.total_bytes()
+        });
+        assert_eq!(calls.get(), 11, "one warm-up call");
+        assert_eq!(m.iterations, 10);
+        assert_eq!(m.bytes, 10 * 4 * 48);
+        assert!(m.per_iteration() <= m.elapsed);
+    }
+}
@@ -140,6 +140,23 @@ impl<const SLOTS: usize, const LEN: usize> BufferPool<SLOTS, LEN> {
 
 #[cfg(feature = "_alloc")]
 impl<const SLOTS: usize, const LEN: usize> BufferPool<SLOTS, LEN> {
+    /// [`Self::new`] behind an `Arc`, built in place on the heap so a
+    /// pool of large slots never passes through the stack.
+    #[must_use]
+    pub fn new_arc() -> alloc::sync::Arc<Self> {
+        // Same floor as `Self::new`.
+        const {
+            assert!(
+                LEN >= 16,
+                "BufferPool slot must hold at least a 16-byte SOME/IP header"
+            );
+        };
+        // SAFETY: all-zero bytes are exactly the pool `Self::new` builds:
+        // zeroed `u8` slots and `AtomicBool`s that are `false`, none of
+        // them claimed.
+        unsafe { alloc::sync::Arc::<Self>::new_zeroed().assume_init() }
+    }
+
     /// Claim a free slot from an `Arc`-backed pool, returning a [`BufferLease`]
     /// that holds an `Arc` clone to keep the pool alive for the lease's
     /// lifetime, or `None` if all `SLOTS` are in use.
@@ -163,6 +180,56 @@ impl<const SLOTS: usize, const LEN: usize> BufferPool<SLOTS, LEN> {
             _owner: Some(self.clone()),
         })
     }
+
+    /// [`Self::claim_arc`], falling back to a freshly allocated, zeroed
+    /// `LEN`-byte buffer when every slot is in use, so callers beyond
+    /// `SLOTS` at once still proceed, only without the reuse.
+    pub fn claim_or_alloc(self: &alloc::sync::Arc<Self>) -> ScratchBuffer {
+        self.claim_arc().map_or_else(
+            || ScratchBuffer::Allocated(alloc::vec![0u8; LEN]),
+            ScratchBuffer::Leased,
+        )
+    }
+}
+
+/// A scratch buffer from [`BufferPool::claim_or_alloc`]: a pool slot when
+/// one was free, else a heap allocation. Derefs to `[u8]` either way.
+#[cfg(feature = "_alloc")]
+pub enum ScratchBuffer {
+    /// A pool slot, returned to the pool on drop.
+    Leased(BufferLease),
+    /// Allocated because the pool was exhausted.
+    Allocated(alloc::vec::Vec<u8>),
+}
+
+#[cfg(feature = "_alloc")]
+impl ScratchBuffer {
+    /// Whether the buffer is a pool slot.
+    #[must_use]
+    pub const fn is_leased(&self) -> bool {
+        matches!(self, Self::Leased(_))
+    }
+}
+
+#[cfg(feature = "_alloc")]
+impl Deref for ScratchBuffer {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Leased(lease) => lease,
+            Self::Allocated(buf) => buf,
+        }
+    }
+}
+
+#[cfg(feature = "_alloc")]
+impl DerefMut for ScratchBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Leased(lease) => lease,
+            Self::Allocated(buf) => buf,
+        }
+    }
 }
 
 impl<const SLOTS: usize, const LEN: usize> Default for BufferPool<SLOTS, LEN> {
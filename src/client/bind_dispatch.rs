@@ -1,8 +1,8 @@
 //! Spawner-agnostic bind dispatch for the `Client` run-loop.
 //!
 //! `Inner` needs to bind two kinds of UDP sockets — the SD multicast
-//! socket and per-port unicast sockets — and submit each socket's I/O
-//! loop to a task spawner. Multi-threaded executors (tokio default)
+//! socket and per-port unicast sockets — and TCP connections, and submit
+//! each socket's I/O loop to a task spawner. Multi-threaded executors (tokio default)
 //! require the spawned future to be `Send`; single-threaded executors
 //! (embassy with `task-arena = 0`, tokio's `LocalSet`) accept `!Send`
 //! futures via [`crate::LocalSpawner`].
@@ -20,14 +20,15 @@
 //! [`crate::LocalSpawner`], not by writing their own `BindDispatch`.
 
 use core::future::Future;
-use core::net::Ipv4Addr;
+use core::net::{Ipv4Addr, SocketAddrV4};
 
+use super::config::SocketSettings;
 use super::error::Error;
 use super::socket_manager::SocketManager;
 use crate::traits::PayloadWireFormat;
 use crate::transport::{
-    BufferProvider, ChannelFactory, E2ERegistryHandle, LocalSpawner, Spawner, TransportFactory,
-    TransportSocket,
+    BufferProvider, ChannelFactory, E2ERegistryHandle, LocalSpawner, Spawner, StreamFactory,
+    TransportFactory, TransportSocket,
 };
 
 /// Crate-private bind-and-spawn abstraction shared by Send and `!Send`
@@ -43,7 +44,9 @@ where
     Result<(), Error>: crate::transport::OneshotPooled<C>,
 {
     /// Bind a discovery socket and submit its I/O loop to the
-    /// configured task executor.
+    /// configured task executor. `settings` sizes the socket's channels
+    /// and buffers in every `bind_*` and `connect`, and names the SD
+    /// endpoint here.
     // `async move` body (rather than `async fn`) is required: the trait
     // method returns `impl Future`, and the block must capture `&self` to
     // claim a buffer (#125) before delegating to `SocketManager::bind_*`.
@@ -55,6 +58,7 @@ where
         session_id: u16,
         session_has_wrapped: bool,
         multicast_loopback: bool,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_;
 
     /// Bind a unicast socket on `port` (0 = ephemeral) and submit its
@@ -63,6 +67,7 @@ where
         &self,
         port: u16,
         e2e_registry: R,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_;
 
     /// Bind a receive-only unicast service-discovery socket on the
@@ -74,6 +79,16 @@ where
         &self,
         interface: Ipv4Addr,
         e2e_registry: R,
+        settings: SocketSettings,
+    ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_;
+
+    /// Open a TCP connection to `peer` through the configured
+    /// [`StreamFactory`] and submit its I/O loop.
+    fn connect(
+        &self,
+        peer: SocketAddrV4,
+        e2e_registry: R,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_;
 }
 
@@ -84,13 +99,14 @@ where
 /// socket-loop buffer from it and moves the lease into the spawned loop
 /// future. The lease frees its pool slot when that future drops (i.e. when
 /// the socket closes), so no explicit release is needed at eviction.
-pub(super) struct SpawnerDispatch<F, S, BP> {
+pub(super) struct SpawnerDispatch<F, S, BP, St> {
     pub factory: F,
     pub spawner: S,
     pub buffer_provider: BP,
+    pub stream_factory: St,
 }
 
-impl<MD, C, R, F, S, BP> BindDispatch<MD, C, R> for SpawnerDispatch<F, S, BP>
+impl<MD, C, R, F, S, BP, St> BindDispatch<MD, C, R> for SpawnerDispatch<F, S, BP, St>
 where
     MD: PayloadWireFormat + Clone + core::fmt::Debug + Send + 'static,
     C: ChannelFactory,
@@ -102,6 +118,11 @@ where
     for<'a> <F::Socket as TransportSocket>::RecvFuture<'a>: Send,
     S: Spawner + Send + Sync + 'static,
     BP: BufferProvider,
+    St: StreamFactory + Send + Sync + 'static,
+    St::Stream: Send + Sync + 'static,
+    for<'a> St::ConnectFuture<'a>: Send,
+    for<'a> <St::Stream as TransportSocket>::SendFuture<'a>: Send,
+    for<'a> <St::Stream as TransportSocket>::RecvFuture<'a>: Send,
     Result<super::socket_manager::ReceivedMessage<MD>, Error>:
         crate::transport::BoundedPooled<C, 16>,
     super::socket_manager::SendMessage<MD, C>: crate::transport::BoundedPooled<C, 16>,
@@ -118,6 +139,7 @@ where
         session_id: u16,
         session_has_wrapped: bool,
         multicast_loopback: bool,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
         async move {
             let buf = self
@@ -133,6 +155,7 @@ where
                 session_has_wrapped,
                 multicast_loopback,
                 buf,
+                settings,
             )
             .await
         }
@@ -143,6 +166,7 @@ where
         &self,
         port: u16,
         e2e_registry: R,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
         async move {
             let buf = self
@@ -155,6 +179,7 @@ where
                 port,
                 e2e_registry,
                 buf,
+                settings,
             )
             .await
         }
@@ -165,6 +190,7 @@ where
         &self,
         interface: Ipv4Addr,
         e2e_registry: R,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
         async move {
             let buf = self
@@ -177,6 +203,31 @@ where
                 interface,
                 e2e_registry,
                 buf,
+                settings,
+            )
+            .await
+        }
+    }
+
+    #[allow(clippy::manual_async_fn)]
+    fn connect(
+        &self,
+        peer: SocketAddrV4,
+        e2e_registry: R,
+        settings: SocketSettings,
+    ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
+        async move {
+            let buf = self
+                .buffer_provider
+                .claim()
+                .ok_or(Error::Capacity("udp_buffer"))?;
+            SocketManager::<MD, C>::connect_with_transport(
+                &self.stream_factory,
+                &self.spawner,
+                peer,
+                e2e_registry,
+                buf,
+                settings,
             )
             .await
         }
@@ -190,13 +241,14 @@ where
 /// Carries a [`BufferProvider`] for the same reason as [`SpawnerDispatch`]:
 /// each `bind_*` claims one socket-loop buffer and moves the lease into the
 /// spawned loop future, which frees the slot on drop.
-pub(super) struct LocalSpawnerDispatch<F, S, BP> {
+pub(super) struct LocalSpawnerDispatch<F, S, BP, St> {
     pub factory: F,
     pub spawner: S,
     pub buffer_provider: BP,
+    pub stream_factory: St,
 }
 
-impl<MD, C, R, F, S, BP> BindDispatch<MD, C, R> for LocalSpawnerDispatch<F, S, BP>
+impl<MD, C, R, F, S, BP, St> BindDispatch<MD, C, R> for LocalSpawnerDispatch<F, S, BP, St>
 where
     MD: PayloadWireFormat + Clone + core::fmt::Debug + Send + 'static,
     C: ChannelFactory,
@@ -205,6 +257,8 @@ where
     F::Socket: 'static,
     S: LocalSpawner + 'static,
     BP: BufferProvider,
+    St: StreamFactory + 'static,
+    St::Stream: 'static,
     Result<super::socket_manager::ReceivedMessage<MD>, Error>:
         crate::transport::BoundedPooled<C, 16>,
     super::socket_manager::SendMessage<MD, C>: crate::transport::BoundedPooled<C, 16>,
@@ -221,6 +275,7 @@ where
         session_id: u16,
         session_has_wrapped: bool,
         multicast_loopback: bool,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
         async move {
             let buf = self
@@ -236,6 +291,7 @@ where
                 session_has_wrapped,
                 multicast_loopback,
                 buf,
+                settings,
             )
             .await
         }
@@ -246,6 +302,7 @@ where
         &self,
         port: u16,
         e2e_registry: R,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
         async move {
             let buf = self
@@ -258,6 +315,7 @@ where
                 port,
                 e2e_registry,
                 buf,
+                settings,
             )
             .await
         }
@@ -268,6 +326,7 @@ where
         &self,
         interface: Ipv4Addr,
         e2e_registry: R,
+        settings: SocketSettings,
     ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
         async move {
             let buf = self
@@ -280,6 +339,31 @@ where
                 interface,
                 e2e_registry,
                 buf,
+                settings,
+            )
+            .await
+        }
+    }
+
+    #[allow(clippy::manual_async_fn)]
+    fn connect(
+        &self,
+        peer: SocketAddrV4,
+        e2e_registry: R,
+        settings: SocketSettings,
+    ) -> impl Future<Output = Result<SocketManager<MD, C>, Error>> + '_ {
+        async move {
+            let buf = self
+                .buffer_provider
+                .claim()
+                .ok_or(Error::Capacity("udp_buffer"))?;
+            SocketManager::<MD, C>::connect_with_transport_local(
+                &self.stream_factory,
+                &self.spawner,
+                peer,
+                e2e_registry,
+                buf,
+                settings,
             )
             .await
         }
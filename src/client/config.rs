@@ -0,0 +1,278 @@
+//! Construction-time settings of a [`Client`](super::Client).
+
+use core::net::Ipv4Addr;
+use core::time::Duration;
+
+use super::service_wait::FindPolicy;
+use crate::protocol::{DecodeLimits, sd};
+
+/// What a socket's receive loop does with a message when the channel to
+/// the client's run loop is full. Messages dropped are counted in
+/// [`DropStats::overflowed`](crate::DropStats::overflowed).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for room. The socket is not read meanwhile, so a burst the run
+    /// loop cannot keep up with overflows the OS receive queue instead.
+    #[default]
+    Block,
+    /// Keep reading, holding the newest message until there is room. A
+    /// message still held when a newer one arrives is dropped for it;
+    /// messages already in the channel are never dropped. The run loop
+    /// gets the queued messages, then the most recent state.
+    KeepLatest,
+    /// Keep reading, dropping messages that arrive while the channel is
+    /// full. The run loop gets the queued messages and nothing newer
+    /// until it catches up.
+    DropNewest,
+}
+
+/// Construction-time settings of a [`Client`](super::Client): the
+/// interface, the SD endpoint, how services are searched for, how
+/// sockets are bound and sized, and the channel capacities and overflow
+/// handling of its run loop. Passed to `Client::new`, which also takes a
+/// bare interface address, or to
+/// [`Client::new_with_deps_and_config`](super::Client::new_with_deps_and_config).
+///
+/// Capacities apply to channel factories that size channels at runtime,
+/// like `TokioChannels`. A static pool declared with
+/// `define_static_channels!` keeps the depth it was declared with (4 for
+/// control messages, 16 per socket for sent and received messages).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientConfig {
+    /// Interface the client's sockets use; [`Ipv4Addr::UNSPECIFIED`] by
+    /// default. The constructors taking a `ClientDeps` read it from
+    /// `ClientDeps::interface` instead.
+    pub interface: Ipv4Addr,
+    /// Loop back the SD multicast this client sends, for running a client
+    /// and a server on the same host, as `Client::new_with_loopback`
+    /// does.
+    pub multicast_loopback: bool,
+    /// Multicast group of service discovery; [`sd::MULTICAST_IP`] by
+    /// default.
+    pub sd_multicast_group: Ipv4Addr,
+    /// Port of service discovery, both the local one and the one of
+    /// peers; [`sd::MULTICAST_PORT`] by default.
+    pub sd_port: u16,
+    /// How [`Client::find_service`](super::Client::find_service) and
+    /// [`Client::wait_for_service`](super::Client::wait_for_service)
+    /// search; [`FindPolicy::new`] by default.
+    pub find_policy: FindPolicy,
+    /// Ports tried in order, first to last, for a unicast socket the
+    /// client binds without being given a port. `None` (the default)
+    /// lets the OS pick an ephemeral port.
+    pub unicast_ports: Option<(u16, u16)>,
+    /// OS receive buffer (`SO_RCVBUF`) of every UDP socket, in bytes.
+    /// `None` (the default) keeps the OS default, which a burst of
+    /// events may overflow.
+    pub recv_buffer_size: Option<usize>,
+    /// Bind the discovery sockets as soon as the run loop starts, instead
+    /// of on the first [`Client::bind_discovery`](super::Client::bind_discovery)
+    /// or call that needs them. `false` by default. A failed bind is
+    /// reported as a [`ClientUpdate::Error`](super::ClientUpdate::Error).
+    pub auto_bind_discovery: bool,
+    /// Requests and commands from [`Client`](super::Client) handles
+    /// queued for the run loop; 4 by default.
+    pub control_capacity: usize,
+    /// Outgoing messages queued per socket; 16 by default.
+    pub send_capacity: usize,
+    /// Received messages queued per socket for the run loop; 16 by
+    /// default.
+    pub receive_capacity: usize,
+    /// What a socket does with a received message once its
+    /// `receive_capacity` is reached; [`OverflowPolicy::Block`] by
+    /// default.
+    pub receive_overflow: OverflowPolicy,
+    /// How long opening a TCP connection to a provider may take before
+    /// the send fails with [`Error::Timeout`](super::Error::Timeout); 2 s
+    /// by default. The run loop waits on the connect, so an unreachable
+    /// peer stalls every other request for up to this long.
+    pub tcp_connect_timeout: Duration,
+    /// Bounds every socket applies to received messages before decoding
+    /// them, including the expected payload lengths of
+    /// [`DecodeLimits::payload_ranges`]; [`DecodeLimits::DEFAULT`] by
+    /// default. A message out of bounds is reported as a
+    /// [`ClientUpdate::Error`](super::ClientUpdate::Error).
+    pub decode_limits: DecodeLimits,
+}
+
+impl ClientConfig {
+    /// Settings for a client on [`Ipv4Addr::UNSPECIFIED`] using the
+    /// default SD endpoint and find policy, with discovery bound on
+    /// demand, queues of 4 control messages and 16 sent and received
+    /// messages per socket, and [`OverflowPolicy::Block`] when the
+    /// receive queue is full.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            interface: Ipv4Addr::UNSPECIFIED,
+            multicast_loopback: false,
+            sd_multicast_group: sd::MULTICAST_IP,
+            sd_port: sd::MULTICAST_PORT,
+            find_policy: FindPolicy::new(),
+            unicast_ports: None,
+            recv_buffer_size: None,
+            auto_bind_discovery: false,
+            control_capacity: 4,
+            send_capacity: 16,
+            receive_capacity: 16,
+            receive_overflow: OverflowPolicy::Block,
+            tcp_connect_timeout: Duration::from_secs(2),
+            decode_limits: DecodeLimits::DEFAULT,
+        }
+    }
+
+    /// Set [`Self::interface`]. Defaults to [`Ipv4Addr::UNSPECIFIED`]
+    /// from [`Self::new`].
+    #[must_use]
+    pub const fn with_interface(mut self, interface: Ipv4Addr) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    /// Set [`Self::multicast_loopback`]. Defaults to `false` from
+    /// [`Self::new`].
+    #[must_use]
+    pub const fn with_multicast_loopback(mut self, multicast_loopback: bool) -> Self {
+        self.multicast_loopback = multicast_loopback;
+        self
+    }
+
+    /// Run service discovery on `group` and `port`. Defaults to
+    /// [`sd::MULTICAST_IP`] and [`sd::MULTICAST_PORT`]
+    /// (`239.255.0.255:30490`) from [`Self::new`].
+    #[must_use]
+    pub const fn with_sd_endpoint(mut self, group: Ipv4Addr, port: u16) -> Self {
+        self.sd_multicast_group = group;
+        self.sd_port = port;
+        self
+    }
+
+    /// Set the [`FindPolicy`] of [`Self::find_policy`]. Defaults to
+    /// [`FindPolicy::new`] from [`Self::new`].
+    #[must_use]
+    pub const fn with_find_policy(mut self, policy: FindPolicy) -> Self {
+        self.find_policy = policy;
+        self
+    }
+
+    /// Bind unicast sockets given no port on the first free one of
+    /// `first..=last`. Defaults to `None` (an ephemeral port) from
+    /// [`Self::new`].
+    #[must_use]
+    pub const fn with_unicast_port_range(mut self, first: u16, last: u16) -> Self {
+        self.unicast_ports = Some((first, last));
+        self
+    }
+
+    /// Set [`Self::recv_buffer_size`] to `bytes`. Defaults to `None`
+    /// (the OS default) from [`Self::new`].
+    #[must_use]
+    pub const fn with_recv_buffer_size(mut self, bytes: usize) -> Self {
+        self.recv_buffer_size = Some(bytes);
+        self
+    }
+
+    /// Set [`Self::auto_bind_discovery`]. Defaults to `false` from
+    /// [`Self::new`].
+    #[must_use]
+    pub const fn with_auto_bind_discovery(mut self, auto_bind_discovery: bool) -> Self {
+        self.auto_bind_discovery = auto_bind_discovery;
+        self
+    }
+
+    /// Set [`Self::control_capacity`]. Defaults to 4 from [`Self::new`].
+    #[must_use]
+    pub const fn with_control_capacity(mut self, capacity: usize) -> Self {
+        self.control_capacity = capacity;
+        self
+    }
+
+    /// Set [`Self::send_capacity`]. Defaults to 16 from [`Self::new`].
+    #[must_use]
+    pub const fn with_send_capacity(mut self, capacity: usize) -> Self {
+        self.send_capacity = capacity;
+        self
+    }
+
+    /// Set [`Self::receive_capacity`]. Defaults to 16 from
+    /// [`Self::new`].
+    #[must_use]
+    pub const fn with_receive_capacity(mut self, capacity: usize) -> Self {
+        self.receive_capacity = capacity;
+        self
+    }
+
+    /// Set the [`OverflowPolicy`] of [`Self::receive_overflow`]:
+    /// [`OverflowPolicy::Block`] to stop reading the socket until the run
+    /// loop catches up, [`OverflowPolicy::KeepLatest`] to keep only the
+    /// newest message meanwhile, or [`OverflowPolicy::DropNewest`] to
+    /// drop what arrives. Defaults to [`OverflowPolicy::Block`] from
+    /// [`Self::new`].
+    #[must_use]
+    pub const fn with_receive_overflow(mut self, policy: OverflowPolicy) -> Self {
+        self.receive_overflow = policy;
+        self
+    }
+
+    /// Set [`Self::tcp_connect_timeout`]. Defaults to 2 s from
+    /// [`Self::new`].
+    #[must_use]
+    pub const fn with_tcp_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.tcp_connect_timeout = timeout;
+        self
+    }
+
+    /// Set the [`DecodeLimits`] every socket applies to received
+    /// messages. Defaults to [`DecodeLimits::DEFAULT`] from [`Self::new`].
+    #[must_use]
+    pub const fn with_decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.decode_limits = decode_limits;
+        self
+    }
+
+    /// The per-socket part handed to each socket as it is bound.
+    pub(super) const fn socket_settings(&self) -> SocketSettings {
+        SocketSettings {
+            send_capacity: self.send_capacity,
+            receive_capacity: self.receive_capacity,
+            receive_overflow: self.receive_overflow,
+            recv_buffer_size: self.recv_buffer_size,
+            sd_multicast_group: self.sd_multicast_group,
+            sd_port: self.sd_port,
+            decode_limits: self.decode_limits,
+        }
+    }
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Ipv4Addr> for ClientConfig {
+    /// The default settings on `interface`, so `Client::new` takes a
+    /// bare interface address too.
+    fn from(interface: Ipv4Addr) -> Self {
+        Self::new().with_interface(interface)
+    }
+}
+
+/// Settings of one socket, from [`ClientConfig`]. Only discovery sockets
+/// use the SD endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SocketSettings {
+    pub send_capacity: usize,
+    pub receive_capacity: usize,
+    pub receive_overflow: OverflowPolicy,
+    pub recv_buffer_size: Option<usize>,
+    pub sd_multicast_group: Ipv4Addr,
+    pub sd_port: u16,
+    pub decode_limits: DecodeLimits,
+}
+
+impl Default for SocketSettings {
+    fn default() -> Self {
+        ClientConfig::new().socket_settings()
+    }
+}
@@ -0,0 +1,99 @@
+use core::net::SocketAddr;
+
+use heapless::Vec;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_DUPLICATE_FILTER_CAP: usize = 16;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_DUPLICATE_FILTER_CAP: usize = 64;
+
+/// Number of recent notifications remembered by the duplicate filter
+/// enabled with [`Client::set_duplicate_filter`](crate::Client::set_duplicate_filter).
+/// Sized via `SIMPLE_SOMEIP_DUPLICATE_FILTER_CAP`; defaults to 16 on
+/// bare-metal, 64 otherwise.
+pub const DUPLICATE_FILTER_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_DUPLICATE_FILTER_CAP"),
+    _DEFAULT_DUPLICATE_FILTER_CAP,
+);
+
+/// `(sender, message_id, session_id)` of one delivered notification.
+type NotificationId = (SocketAddr, u32, u16);
+
+/// Bounded LRU of recently delivered notifications.
+///
+/// A notification is a duplicate if the same sender already delivered
+/// one with the same message ID and session ID, e.g. when an event
+/// reaches the client both by multicast and by unicast, or the network
+/// duplicated a datagram. Session ID `0` means the sender does not use
+/// session handling, so such notifications are never treated as
+/// duplicates.
+#[derive(Debug)]
+pub(super) struct DuplicateFilter {
+    /// Oldest first.
+    recent: Vec<NotificationId, DUPLICATE_FILTER_CAP>,
+}
+
+impl DuplicateFilter {
+    pub(super) const fn new() -> Self {
+        Self { recent: Vec::new() }
+    }
+
+    /// Record a notification and return `true` if it was seen before.
+    pub(super) fn is_duplicate(
+        &mut self,
+        source: SocketAddr,
+        message_id: u32,
+        session_id: u16,
+    ) -> bool {
+        if session_id == 0 {
+            return false;
+        }
+        let id = (source, message_id, session_id);
+        let position = self.recent.iter().position(|recent| *recent == id);
+        let seen = position.is_some();
+        if let Some(pos) = position {
+            self.recent.remove(pos);
+        } else if self.recent.is_full() {
+            self.recent.remove(0);
+        }
+        // Cannot fail: a slot was freed above when full.
+        let _ = self.recent.push(id);
+        seen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::{Ipv4Addr, SocketAddrV4};
+
+    fn peer(port: u16) -> SocketAddr {
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+    }
+
+    #[test]
+    fn drops_repeats_per_sender_and_ignores_session_zero() {
+        let mut filter = DuplicateFilter::new();
+        assert!(!filter.is_duplicate(peer(1), 0x1234_8001, 7));
+        assert!(filter.is_duplicate(peer(1), 0x1234_8001, 7));
+        assert!(!filter.is_duplicate(peer(2), 0x1234_8001, 7));
+        assert!(!filter.is_duplicate(peer(1), 0x1234_8002, 7));
+        assert!(!filter.is_duplicate(peer(1), 0x1234_8001, 8));
+        assert!(!filter.is_duplicate(peer(1), 0x1234_8001, 0));
+        assert!(!filter.is_duplicate(peer(1), 0x1234_8001, 0));
+    }
+
+    #[test]
+    fn evicts_least_recently_seen() {
+        let mut filter = DuplicateFilter::new();
+        let cap = u16::try_from(DUPLICATE_FILTER_CAP).unwrap();
+        for session in 1..=cap {
+            assert!(!filter.is_duplicate(peer(1), 1, session));
+        }
+        // Touching session 1 makes session 2 the oldest entry.
+        assert!(filter.is_duplicate(peer(1), 1, 1));
+        assert!(!filter.is_duplicate(peer(1), 1, cap + 1));
+        assert!(filter.is_duplicate(peer(1), 1, 1));
+        assert!(!filter.is_duplicate(peer(1), 1, 2), "evicted");
+    }
+}
@@ -0,0 +1,376 @@
+//! Aggregate readiness of the services an application needs, driven by
+//! [`Client::start_dependencies`](crate::Client::start_dependencies) and
+//! [`Client::track_dependencies`](crate::Client::track_dependencies).
+
+use heapless::Vec;
+
+use super::Error;
+use super::discovery_cache::UnavailableReason;
+use super::service_registry::ServiceEndpointKey;
+use super::service_wait::FindPolicy;
+use super::subscriptions::SUBSCRIBE_MANY_CAP;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_DEPENDENCIES_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_DEPENDENCIES_CAP: usize = 16;
+
+/// Maximum number of service instances one [`Dependencies`] tracks.
+/// Sized via `SIMPLE_SOMEIP_DEPENDENCIES_CAP`; defaults to 4 on
+/// bare-metal, 16 otherwise.
+pub const DEPENDENCIES_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_DEPENDENCIES_CAP"),
+    _DEFAULT_DEPENDENCIES_CAP,
+);
+
+/// A service instance an application needs, and the eventgroups of it
+/// to subscribe to once it is offered.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependency {
+    pub service_id: u16,
+    /// `0xFFFF` for whichever instance is offered first.
+    pub instance_id: u16,
+    pub event_group_ids: Vec<u16, SUBSCRIBE_MANY_CAP>,
+    /// TTL of the subscriptions, in seconds.
+    pub ttl: u32,
+}
+
+impl Dependency {
+    /// `service_id`/`instance_id`, with no eventgroups and a 3 s TTL.
+    #[must_use]
+    pub const fn new(service_id: u16, instance_id: u16) -> Self {
+        Self {
+            service_id,
+            instance_id,
+            event_group_ids: Vec::new(),
+            ttl: 3,
+        }
+    }
+
+    /// Also subscribe to `event_group_id`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"dependency_event_groups"`)
+    /// if [`SUBSCRIBE_MANY_CAP`] eventgroups are already listed.
+    pub fn with_event_group(mut self, event_group_id: u16) -> Result<Self, Error> {
+        self.event_group_ids
+            .push(event_group_id)
+            .map_err(|_| Error::Capacity("dependency_event_groups"))?;
+        Ok(self)
+    }
+
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    const fn matches(&self, service_id: u16, instance_id: u16) -> bool {
+        self.service_id == service_id
+            && (self.instance_id == 0xFFFF || self.instance_id == instance_id)
+    }
+}
+
+/// Where one [`Dependency`] stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependencyState {
+    /// No offer seen yet; a search is running.
+    Searching,
+    /// Offered by `key`, and every eventgroup subscribed.
+    Ready {
+        key: ServiceEndpointKey,
+        instance_id: u16,
+    },
+    /// Offered by `key`, but the provider rejected, or did not answer,
+    /// the subscription to `event_group_id`. Retried with the next new
+    /// offer.
+    SubscribeFailed {
+        key: ServiceEndpointKey,
+        instance_id: u16,
+        event_group_id: u16,
+    },
+    /// Was ready, until its offer went away for `reason`; searching
+    /// again.
+    Lost(UnavailableReason),
+}
+
+/// Readiness of every tracked [`Dependency`] together. The state of each
+/// one is in [`Dependencies::states`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Readiness {
+    /// Every dependency is [`Ready`](DependencyState::Ready).
+    AllAvailable,
+    /// Some but not all are.
+    Partial { ready: usize, total: usize },
+    /// None is, which is where tracking starts.
+    Lost,
+}
+
+/// The services an application needs and where each stands.
+///
+/// [`Client::start_dependencies`](crate::Client::start_dependencies)
+/// subscribes to those already offered and searches for the rest; feeding
+/// every [`ClientUpdate`](super::ClientUpdate) to
+/// [`Client::track_dependencies`](crate::Client::track_dependencies)
+/// keeps it current and reports each change of [`Readiness`].
+///
+/// ```no_run
+/// # #[cfg(feature = "client-tokio")]
+/// # async fn demo() -> Result<(), simple_someip::client::Error> {
+/// # use simple_someip::{Client, RawPayload};
+/// # use std::net::Ipv4Addr;
+/// use simple_someip::client::{Dependencies, Dependency};
+///
+/// let (client, mut updates, run) = Client::<RawPayload, _, _, _>::new(Ipv4Addr::LOCALHOST);
+/// tokio::spawn(run);
+/// let mut dependencies = Dependencies::new();
+/// dependencies.add(Dependency::new(0x1234, 1).with_event_group(0x01)?)?;
+/// client.start_dependencies(&mut dependencies).await?;
+/// while let Some(update) = updates.recv().await {
+///     if let Some(readiness) = client.track_dependencies(&mut dependencies, &update).await? {
+///         println!("{readiness:?}");
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Dependencies {
+    entries: Vec<(Dependency, DependencyState), DEPENDENCIES_CAP>,
+    find_policy: FindPolicy,
+    /// Last readiness reported.
+    readiness: Readiness,
+}
+
+impl Default for Dependencies {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dependencies {
+    /// No dependencies, searched for with [`FindPolicy::new`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            find_policy: FindPolicy::new(),
+            readiness: Readiness::Lost,
+        }
+    }
+
+    /// Search for missing dependencies with `policy`.
+    #[must_use]
+    pub const fn with_find_policy(mut self, policy: FindPolicy) -> Self {
+        self.find_policy = policy;
+        self
+    }
+
+    /// Track `dependency`, replacing one for the same service instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"dependencies"`) if
+    /// [`DEPENDENCIES_CAP`] dependencies are already tracked.
+    pub fn add(&mut self, dependency: Dependency) -> Result<(), Error> {
+        if let Some(entry) = self.entries.iter_mut().find(|(d, _)| {
+            d.service_id == dependency.service_id && d.instance_id == dependency.instance_id
+        }) {
+            *entry = (dependency, DependencyState::Searching);
+            return Ok(());
+        }
+        self.entries
+            .push((dependency, DependencyState::Searching))
+            .map_err(|_| Error::Capacity("dependencies"))
+    }
+
+    /// Readiness as last reported.
+    #[must_use]
+    pub const fn readiness(&self) -> Readiness {
+        self.readiness
+    }
+
+    /// Every dependency and its state, in the order they were added.
+    pub fn states(&self) -> impl Iterator<Item = (&Dependency, DependencyState)> {
+        self.entries.iter().map(|(d, state)| (d, *state))
+    }
+
+    /// State of the dependency added for `service_id`/`instance_id`.
+    #[must_use]
+    pub fn state(&self, service_id: u16, instance_id: u16) -> Option<DependencyState> {
+        self.entries
+            .iter()
+            .find(|(d, _)| d.service_id == service_id && d.instance_id == instance_id)
+            .map(|(_, state)| *state)
+    }
+
+    pub(super) const fn find_policy(&self) -> FindPolicy {
+        self.find_policy
+    }
+
+    pub(super) fn dependency(&self, index: usize) -> &Dependency {
+        &self.entries[index].0
+    }
+
+    /// Index of a dependency not yet ready that an offer of
+    /// `service_id`/`instance_id` serves.
+    pub(super) fn waiting_for(&self, service_id: u16, instance_id: u16) -> Option<usize> {
+        self.entries.iter().position(|(d, state)| {
+            !matches!(state, DependencyState::Ready { .. }) && d.matches(service_id, instance_id)
+        })
+    }
+
+    pub(super) fn set_state(&mut self, index: usize, state: DependencyState) {
+        self.entries[index].1 = state;
+    }
+
+    /// Mark the dependencies that were ready on `key`'s offer of
+    /// `instance_id` as lost. Returns whether there were any.
+    pub(super) fn withdraw(
+        &mut self,
+        key: ServiceEndpointKey,
+        instance_id: u16,
+        reason: UnavailableReason,
+    ) -> bool {
+        let mut withdrawn = false;
+        for (_, state) in &mut self.entries {
+            if *state == (DependencyState::Ready { key, instance_id }) {
+                *state = DependencyState::Lost(reason);
+                withdrawn = true;
+            }
+        }
+        withdrawn
+    }
+
+    /// `(service_id, instance_id)` of every dependency without an offer.
+    pub(super) fn searching(&self) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.entries
+            .iter()
+            .filter(|(_, state)| {
+                matches!(state, DependencyState::Searching | DependencyState::Lost(_))
+            })
+            .map(|(d, _)| (d.service_id, d.instance_id))
+    }
+
+    /// The readiness of the current states, if it differs from the one
+    /// last reported; it is reported from then on.
+    pub(super) fn transition(&mut self) -> Option<Readiness> {
+        let total = self.entries.len();
+        let ready = self
+            .entries
+            .iter()
+            .filter(|(_, state)| matches!(state, DependencyState::Ready { .. }))
+            .count();
+        let readiness = if ready == total && total > 0 {
+            Readiness::AllAvailable
+        } else if ready == 0 {
+            Readiness::Lost
+        } else {
+            Readiness::Partial { ready, total }
+        };
+        if readiness == self.readiness {
+            return None;
+        }
+        self.readiness = readiness;
+        Some(readiness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+    fn key(service_id: u16, host: u8) -> ServiceEndpointKey {
+        let addr = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, host), 30509);
+        ServiceEndpointKey::udp(service_id, SocketAddr::V4(addr))
+    }
+
+    fn two_dependencies() -> Dependencies {
+        let mut dependencies = Dependencies::new();
+        dependencies.add(Dependency::new(0x5B, 1)).unwrap();
+        dependencies.add(Dependency::new(0x5C, 0xFFFF)).unwrap();
+        dependencies
+    }
+
+    #[test]
+    fn readiness_follows_the_dependencies() {
+        let mut dependencies = two_dependencies();
+        assert_eq!(dependencies.transition(), None, "starts out lost");
+        assert_eq!(dependencies.searching().count(), 2);
+
+        let index = dependencies.waiting_for(0x5B, 1).unwrap();
+        let ready = DependencyState::Ready {
+            key: key(0x5B, 1),
+            instance_id: 1,
+        };
+        dependencies.set_state(index, ready);
+        assert_eq!(
+            dependencies.transition(),
+            Some(Readiness::Partial { ready: 1, total: 2 })
+        );
+        assert_eq!(dependencies.waiting_for(0x5B, 1), None, "already ready");
+
+        let index = dependencies.waiting_for(0x5C, 7).unwrap();
+        dependencies.set_state(
+            index,
+            DependencyState::Ready {
+                key: key(0x5C, 2),
+                instance_id: 7,
+            },
+        );
+        assert_eq!(dependencies.transition(), Some(Readiness::AllAvailable));
+        assert_eq!(dependencies.transition(), None, "reported once");
+        assert_eq!(dependencies.searching().count(), 0);
+    }
+
+    #[test]
+    fn a_withdrawn_offer_loses_only_its_dependency() {
+        let mut dependencies = two_dependencies();
+        dependencies.set_state(
+            0,
+            DependencyState::Ready {
+                key: key(0x5B, 1),
+                instance_id: 1,
+            },
+        );
+        dependencies.set_state(
+            1,
+            DependencyState::Ready {
+                key: key(0x5C, 2),
+                instance_id: 7,
+            },
+        );
+        dependencies.transition();
+
+        assert!(!dependencies.withdraw(key(0x5B, 3), 1, UnavailableReason::StopOffer));
+        assert!(dependencies.withdraw(key(0x5B, 1), 1, UnavailableReason::Expired));
+        assert_eq!(
+            dependencies.state(0x5B, 1),
+            Some(DependencyState::Lost(UnavailableReason::Expired))
+        );
+        assert_eq!(
+            dependencies.transition(),
+            Some(Readiness::Partial { ready: 1, total: 2 })
+        );
+        assert_eq!(
+            dependencies.searching().collect::<std::vec::Vec<_>>(),
+            [(0x5B, 1)]
+        );
+    }
+
+    #[test]
+    fn the_table_is_capped() {
+        let mut dependencies = Dependencies::new();
+        for service_id in 0..DEPENDENCIES_CAP {
+            let service_id = u16::try_from(service_id).unwrap();
+            dependencies.add(Dependency::new(service_id, 1)).unwrap();
+        }
+        dependencies.add(Dependency::new(0, 1)).unwrap();
+        assert!(matches!(
+            dependencies.add(Dependency::new(0xFFF0, 1)),
+            Err(Error::Capacity("dependencies"))
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+use core::time::Duration;
+
+use super::service_registry::{
+    ServiceEndpointInfo, ServiceEndpointKey, ServiceRegistry, ServiceRegistryFull,
+};
+
+/// Why a discovered service instance went away, as reported by
+/// [`ClientUpdate::ServiceUnavailable`](super::ClientUpdate::ServiceUnavailable).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnavailableReason {
+    /// The provider sent a `StopOfferService`.
+    StopOffer,
+    /// The offer's TTL lapsed without a renewal.
+    Expired,
+    /// The provider now offers a version the client's
+    /// [`VersionPolicy`](super::VersionPolicy) rejects.
+    VersionRejected,
+    /// The endpoint was removed with
+    /// [`Client::remove_endpoint`](crate::Client::remove_endpoint).
+    Removed,
+}
+
+/// Cache an offer, or a manual endpoint, for `key`, replacing any earlier
+/// one. Returns `true` if the instance was not cached before, i.e. it just
+/// became available.
+pub(super) fn offer(
+    registry: &mut ServiceRegistry,
+    key: ServiceEndpointKey,
+    info: ServiceEndpointInfo,
+) -> Result<bool, ServiceRegistryFull> {
+    let is_new = registry.get(key).is_none();
+    registry.insert(key, info)?;
+    Ok(is_new)
+}
+
+/// Drop every offer whose TTL has lapsed by `now`, handing each to
+/// `expired`. Manual endpoints and infinite TTLs never lapse.
+pub(super) fn expire(
+    registry: &mut ServiceRegistry,
+    now: Duration,
+    mut expired: impl FnMut(ServiceEndpointKey, &ServiceEndpointInfo),
+) {
+    registry.retain(|key, info| {
+        let lapsed = info.expires_at.is_some_and(|at| at <= now);
+        if lapsed {
+            expired(*key, info);
+        }
+        !lapsed
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::reachability::ProbeState;
+    use core::net::SocketAddr;
+
+    fn info(expires_at: Option<Duration>) -> ServiceEndpointInfo {
+        ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
+        }
+    }
+
+    #[test]
+    fn offer_reports_only_new_instances() {
+        let mut registry = ServiceRegistry::default();
+        let key = ServiceEndpointKey::udp(0x5B, SocketAddr::from(([192, 168, 1, 10], 30509)));
+        assert_eq!(offer(&mut registry, key, info(None)), Ok(true));
+        assert_eq!(
+            offer(&mut registry, key, info(Some(Duration::from_secs(3)))),
+            Ok(false),
+            "renewal"
+        );
+        assert_eq!(
+            registry.get(key).unwrap().expires_at,
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn expire_drops_only_lapsed_offers() {
+        let mut registry = ServiceRegistry::default();
+        let now = Duration::from_secs(10);
+        let lapsed = ServiceEndpointKey::udp(0x5B, SocketAddr::from(([192, 168, 1, 10], 1)));
+        let live = ServiceEndpointKey::udp(0x5B, SocketAddr::from(([192, 168, 1, 10], 2)));
+        let manual = ServiceEndpointKey::udp(0x5B, SocketAddr::from(([192, 168, 1, 10], 3)));
+        registry.insert(lapsed, info(Some(now))).unwrap();
+        registry
+            .insert(live, info(Some(now + Duration::from_secs(1))))
+            .unwrap();
+        registry.insert(manual, info(None)).unwrap();
+
+        let mut expired = heapless::Vec::<ServiceEndpointKey, 4>::new();
+        expire(&mut registry, now, |key, _| expired.push(key).unwrap());
+        assert_eq!(expired.as_slice(), &[lapsed]);
+        assert!(registry.get(lapsed).is_none());
+        assert!(registry.get(live).is_some());
+        assert!(registry.get(manual).is_some());
+    }
+}
@@ -28,11 +28,11 @@ pub enum Error {
     /// The requested service was not found in the endpoint registry.
     #[error("Service not found in endpoint registry")]
     ServiceNotFound,
-    /// The key's endpoint uses an address family or transport protocol
-    /// the client's transports cannot send to (currently IPv4 + UDP
-    /// only). The registry stores such keys without error; sending or
-    /// subscribing to them fails with this variant.
-    #[error("Endpoint not supported by this client's transports (IPv4/UDP only): {0:?}")]
+    /// The key's endpoint uses an address family the client's
+    /// transports cannot send to (currently IPv4 only). The registry
+    /// stores such keys without error; sending or subscribing to them
+    /// fails with this variant.
+    #[error("Endpoint not supported by this client's transports (IPv4 only): {0:?}")]
     UnsupportedEndpoint(crate::NetEndpoint),
     /// An E2E protection or checking error occurred.
     #[error(transparent)]
@@ -66,8 +66,98 @@ pub enum Error {
     /// - `"service_registry"` — bound by `SERVICE_REGISTRY_CAP`. A
     ///   new `(service_id, instance_id)` endpoint cannot be registered
     ///   because the registry is full.
+    /// - `"version_policies"` — bound by `VERSION_POLICIES_CAP`. A
+    ///   version policy cannot be pinned for another service because
+    ///   the policy table is full.
+    /// - `"sessionless_services"` — bound by `SESSIONLESS_SERVICES_CAP`.
+    ///   Session handling cannot be disabled for another service.
+    /// - `"service_waiters"` — bound by `SERVICE_WAITERS_CAP`. Too
+    ///   many `Client::wait_for_service` calls are already waiting.
+    /// - `"event_waiters"` — bound by `EVENT_WAITERS_CAP`. Too many
+    ///   `Client::subscribe_and_wait_first` calls are already waiting for
+    ///   their first notification.
+    /// - `"tcp_connections"` — bound by `TCP_CONNECTIONS_CAP`. A
+    ///   request to yet another TCP endpoint needs a new connection
+    ///   while the maximum are already open.
+    /// - `"tcp_in_flight"` — bound by `TCP_IN_FLIGHT_CAP`. A request
+    ///   over TCP was refused because as many requests are already
+    ///   awaiting a reply on that connection.
+    /// - `"timed_requests"` — bound by `TIMED_REQUESTS_CAP`. A request
+    ///   with a `RequestPolicy` was refused because as many are already
+    ///   awaiting their reply.
+    /// - `"subscriptions"` — bound by `SUBSCRIPTIONS_CAP`. A
+    ///   subscription cannot be tracked because as many are already kept
+    ///   alive.
+    /// - `"eventgroup_layout"` — bound by `EVENTGROUP_LAYOUT_CAP` /
+    ///   `EVENTGROUP_EVENTS_CAP`. An `EventgroupLayout` cannot take
+    ///   another eventgroup, or an eventgroup lists too many events.
+    /// - `"event_streams"` — bound by `EVENT_STREAMS_CAP`. Too many
+    ///   `Client::event_stream`s are already open.
+    /// - `"service_watches"` — bound by `SERVICE_WATCHES_CAP`. Too many
+    ///   `Client::watch_service` watches are already open.
+    /// - `"subscribe_many"` — bound by `SUBSCRIBE_MANY_CAP`. A
+    ///   `Client::subscribe_many` call names too many eventgroups.
+    /// - `"receive_ports"` — bound by `RECEIVE_PORTS_CAP`. Too many
+    ///   `Client::set_receive_port` ports are set aside.
+    /// - `"unicast_ports"` — bound by `ClientConfig::unicast_ports`.
+    ///   Every port of the range is already in use.
     #[error("internal capacity exceeded: {0}")]
     Capacity(&'static str),
+    /// An event asked for by
+    /// [`EventgroupLayout::select`](crate::client::EventgroupLayout::select)
+    /// is not carried by any eventgroup of the layout.
+    #[error("event 0x{0:04X} is not in any eventgroup of the layout")]
+    EventNotInLayout(u16),
+    /// An eventgroup asked for by
+    /// [`Client::event_stream`](crate::Client::event_stream) is not
+    /// declared in the layout.
+    #[error("eventgroup 0x{0:04X} is not declared in the layout")]
+    EventgroupNotInLayout(u16),
+    /// A request's [`Deadline`](crate::Deadline) passed while it was
+    /// still queued in the client; it was dropped rather than sent late.
+    #[error("send deadline exceeded")]
+    DeadlineExceeded,
+    /// A wait with a timeout, such as
+    /// [`Client::wait_for_service`](crate::Client::wait_for_service) or a
+    /// request sent with a [`RequestPolicy`](crate::client::RequestPolicy),
+    /// ran out.
+    #[error("timed out")]
+    Timeout,
+    /// A [`Client::find_service_with`](crate::Client::find_service_with)
+    /// was stopped by [`Client::cancel_find`](crate::Client::cancel_find)
+    /// before an offer arrived.
+    #[error("service search cancelled")]
+    FindCancelled,
+    /// The provider rejected a subscription with a
+    /// `SubscribeEventgroupNack`.
+    #[error(
+        "subscription to eventgroup 0x{event_group_id:04X} of service 0x{service_id:04X}.0x{instance_id:04X} rejected"
+    )]
+    SubscriptionRejected {
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    },
+    /// No subscription is tracked for the eventgroup, or it was stopped
+    /// before the provider answered it.
+    #[error("not subscribed")]
+    NotSubscribed,
+    /// A later subscription to the same eventgroup replaced this one
+    /// before the provider answered it.
+    #[error("subscription replaced")]
+    SubscriptionReplaced,
+    /// The provider answered a request with an `ERROR` message.
+    #[error("error response: {return_code:?}")]
+    ErrorResponse {
+        /// Return code of the `ERROR` message.
+        return_code: crate::protocol::ReturnCode,
+        /// Its payload, if it is an
+        /// [`ApplicationError`](crate::protocol::ApplicationError).
+        application_error: Option<crate::protocol::ApplicationError>,
+    },
+    /// A reply failed its E2E check, with the status the check gave.
+    #[error("reply failed its E2E check: {0:?}")]
+    E2ECheckFailed(crate::e2e::E2ECheckStatus),
     /// An error surfaced by the pluggable transport backend (see
     /// [`crate::transport::TransportError`]).
     #[error(transparent)]
@@ -147,6 +237,7 @@ mod tests {
             Error::SocketClosedUnexpectedly,
             Error::UnicastSocketNotBound,
             Error::ServiceNotFound,
+            Error::Timeout,
             Error::Shutdown,
         ] {
             let _ = format!("{err}");
@@ -0,0 +1,218 @@
+use core::net::IpAddr;
+
+use heapless::Vec;
+
+use super::{eventgroups::EVENTGROUP_EVENTS_CAP, service_registry::ServiceRegistry};
+use crate::log::{debug, warn};
+use crate::protocol::Message;
+use crate::traits::PayloadWireFormat;
+use crate::transport::UnboundedSend;
+
+/// Number of [`Client::event_stream`](crate::Client::event_stream)s that
+/// can be open at the same time. Sized via
+/// `SIMPLE_SOMEIP_EVENT_STREAMS_CAP`.
+pub const EVENT_STREAMS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_EVENT_STREAMS_CAP"), 8);
+
+/// Where the notifications of one eventgroup go: an open event stream,
+/// fed through `sender`.
+#[derive(Debug)]
+pub(super) struct EventRoute<S> {
+    service_id: u16,
+    /// `0xFFFF` for any instance.
+    instance_id: u16,
+    event_group_id: u16,
+    /// Events the eventgroup carries.
+    events: Vec<u16, EVENTGROUP_EVENTS_CAP>,
+    pub(super) sender: S,
+}
+
+impl<S> EventRoute<S> {
+    pub(super) fn new(
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        events: Vec<u16, EVENTGROUP_EVENTS_CAP>,
+        sender: S,
+    ) -> Self {
+        Self {
+            service_id,
+            instance_id,
+            event_group_id,
+            events,
+            sender,
+        }
+    }
+
+    /// `true` if `event_id` of `service_id`, sent from `source`, belongs
+    /// on this route. The instance is known by the address it is offered
+    /// at; only the IP is matched, since events may come from another
+    /// port than the offered one.
+    pub(super) fn matches(
+        &self,
+        registry: &ServiceRegistry,
+        service_id: u16,
+        event_id: u16,
+        source: IpAddr,
+    ) -> bool {
+        self.service_id == service_id
+            && self.events.contains(&event_id)
+            && registry
+                .find(|key, info| {
+                    key.service_id == service_id
+                        && (self.instance_id == 0xFFFF || info.instance_id == self.instance_id)
+                        && key.endpoint.addr.ip() == source
+                })
+                .is_some()
+    }
+}
+
+impl<S> core::fmt::Display for EventRoute<S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "eventgroup 0x{:04X} of 0x{:04X}.0x{:04X}",
+            self.event_group_id, self.service_id, self.instance_id
+        )
+    }
+}
+
+/// Open event streams of one client.
+pub(super) type EventRoutes<S> = Vec<EventRoute<S>, EVENT_STREAMS_CAP>;
+
+/// Hand a notification from `source` to every open stream it belongs
+/// on. Streams whose receiver is gone are closed on the way; a full
+/// stream misses the notification. Returns the message if no stream took
+/// it, for the update stream.
+pub(super) fn route<P, S>(
+    routes: &mut EventRoutes<S>,
+    registry: &ServiceRegistry,
+    message: Message<P>,
+    source: IpAddr,
+) -> Option<Message<P>>
+where
+    P: PayloadWireFormat + Clone + Send + 'static,
+    S: UnboundedSend<Message<P>>,
+{
+    let message_id = message.header().message_id();
+    let mut delivered = false;
+    let mut index = 0;
+    while index < routes.len() {
+        if !routes[index].matches(
+            registry,
+            message_id.service_id(),
+            message_id.method_id(),
+            source,
+        ) {
+            index += 1;
+            continue;
+        }
+        match routes[index].sender.send_now(message.clone()) {
+            Ok(()) => delivered = true,
+            Err(_) if routes[index].sender.is_closed() => {
+                let closed = routes.swap_remove(index);
+                debug!("Event stream for {} closed", closed);
+                continue;
+            }
+            Err(_) => warn!(
+                "Event stream for {} is full; skipping a notification",
+                routes[index]
+            ),
+        }
+        index += 1;
+    }
+    (!delivered).then_some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::reachability::ProbeState;
+    use crate::client::service_registry::{ServiceEndpointInfo, ServiceEndpointKey};
+    use core::net::SocketAddr;
+
+    fn registry_with(instance_id: u16, addr: SocketAddr) -> ServiceRegistry {
+        let mut registry = ServiceRegistry::default();
+        let info = ServiceEndpointInfo {
+            instance_id,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
+        };
+        registry
+            .insert(ServiceEndpointKey::udp(0x5B, addr), info)
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn route_matches_member_events_of_the_instance_provider() {
+        let provider = SocketAddr::from(([192, 168, 1, 10], 30509));
+        let registry = registry_with(1, provider);
+        let route = EventRoute::new(0x5B, 1, 0x01, Vec::from_slice(&[0x8001]).unwrap(), ());
+        assert!(route.matches(&registry, 0x5B, 0x8001, provider.ip()));
+        assert!(
+            !route.matches(&registry, 0x5B, 0x8002, provider.ip()),
+            "other event"
+        );
+        assert!(
+            !route.matches(&registry, 0x5C, 0x8001, provider.ip()),
+            "other service"
+        );
+        assert!(
+            !route.matches(&registry, 0x5B, 0x8001, IpAddr::from([192, 168, 1, 11])),
+            "other provider"
+        );
+
+        let other_instance =
+            EventRoute::new(0x5B, 2, 0x01, Vec::from_slice(&[0x8001]).unwrap(), ());
+        assert!(!other_instance.matches(&registry, 0x5B, 0x8001, provider.ip()));
+        let any_instance =
+            EventRoute::new(0x5B, 0xFFFF, 0x01, Vec::from_slice(&[0x8001]).unwrap(), ());
+        assert!(any_instance.matches(&registry, 0x5B, 0x8001, provider.ip()));
+    }
+
+    #[cfg(feature = "client-tokio")]
+    #[test]
+    fn route_delivers_to_every_matching_stream() {
+        use crate::protocol::Header;
+        use crate::protocol::sd::test_support::{TestPayload, empty_sd_header};
+
+        let provider = SocketAddr::from(([192, 168, 1, 10], 30509));
+        let registry = registry_with(1, provider);
+        let message = Message::new(
+            Header::new_event(0x5B, 0x8001, 1, 1, 1, 0),
+            TestPayload {
+                header: empty_sd_header(),
+            },
+        );
+        let events = || Vec::from_slice(&[0x8001]).unwrap();
+        let (first, mut first_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (closed, closed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (second, mut second_rx) = tokio::sync::mpsc::unbounded_channel();
+        drop(closed_rx);
+        let mut routes = EventRoutes::new();
+        for sender in [first, closed, second] {
+            routes
+                .push(EventRoute::new(0x5B, 1, 0x01, events(), sender))
+                .unwrap();
+        }
+
+        assert!(route(&mut routes, &registry, message.clone(), provider.ip()).is_none());
+        assert_eq!(first_rx.try_recv().unwrap(), message);
+        assert_eq!(second_rx.try_recv().unwrap(), message);
+        assert_eq!(routes.len(), 2, "the closed stream is removed");
+
+        let elsewhere = IpAddr::from([192, 168, 1, 11]);
+        assert_eq!(
+            route(&mut routes, &registry, message.clone(), elsewhere),
+            Some(message),
+            "no stream took it"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use heapless::Vec;
+
+use super::Error;
+
+/// Maximum number of eventgroups an [`EventgroupLayout`] describes.
+/// Selection searches subsets of these exhaustively, so the cap is also
+/// bounded at 32.
+pub const EVENTGROUP_LAYOUT_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_EVENTGROUP_LAYOUT_CAP"), 16);
+
+/// Maximum number of events one eventgroup in an [`EventgroupLayout`]
+/// may list.
+pub const EVENTGROUP_EVENTS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_EVENTGROUP_EVENTS_CAP"), 32);
+
+const _: () = assert!(
+    EVENTGROUP_LAYOUT_CAP <= 32,
+    "EVENTGROUP_LAYOUT_CAP must fit a u32 subset mask"
+);
+
+/// The eventgroups selected by [`EventgroupLayout::select`].
+pub type EventgroupSelection = Vec<u16, EVENTGROUP_LAYOUT_CAP>;
+
+/// Which events a provider groups into which eventgroups, as declared in
+/// its service interface (deployment configuration or ARXML).
+///
+/// SOME/IP subscriptions are per eventgroup, and one event may sit in
+/// several groups. [`Self::select`] turns "the events I want" into the
+/// smallest set of eventgroups that delivers all of them;
+/// [`Client::subscribe_events`](crate::Client::subscribe_events) then
+/// subscribes to that set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventgroupLayout {
+    groups: Vec<(u16, Vec<u16, EVENTGROUP_EVENTS_CAP>), EVENTGROUP_LAYOUT_CAP>,
+}
+
+impl EventgroupLayout {
+    /// An empty layout.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { groups: Vec::new() }
+    }
+
+    /// Declare that `eventgroup_id` carries `events`, replacing any
+    /// earlier declaration of the same eventgroup.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] with tag `"eventgroup_layout"` if the
+    /// layout already holds [`EVENTGROUP_LAYOUT_CAP`] other eventgroups
+    /// or `events` lists more than [`EVENTGROUP_EVENTS_CAP`] events.
+    pub fn insert(&mut self, eventgroup_id: u16, events: &[u16]) -> Result<(), Error> {
+        let events = Vec::from_slice(events).map_err(|_| Error::Capacity("eventgroup_layout"))?;
+        if let Some((_, existing)) = self.groups.iter_mut().find(|(id, _)| *id == eventgroup_id) {
+            *existing = events;
+            return Ok(());
+        }
+        self.groups
+            .push((eventgroup_id, events))
+            .map_err(|_| Error::Capacity("eventgroup_layout"))
+    }
+
+    /// Builder form of [`Self::insert`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::insert`].
+    pub fn with_eventgroup(mut self, eventgroup_id: u16, events: &[u16]) -> Result<Self, Error> {
+        self.insert(eventgroup_id, events)?;
+        Ok(self)
+    }
+
+    /// Events carried by `eventgroup_id`, if it is declared.
+    #[must_use]
+    pub fn events(&self, eventgroup_id: u16) -> Option<&[u16]> {
+        self.groups
+            .iter()
+            .find(|(id, _)| *id == eventgroup_id)
+            .map(|(_, events)| events.as_slice())
+    }
+
+    /// Eventgroups carrying `event_id`, in declaration order.
+    pub fn eventgroups_for(&self, event_id: u16) -> impl Iterator<Item = u16> + '_ {
+        self.groups
+            .iter()
+            .filter(move |(_, events)| events.contains(&event_id))
+            .map(|(id, _)| *id)
+    }
+
+    /// The smallest set of eventgroups that together carry every event
+    /// in `events`, in declaration order.
+    ///
+    /// The search is exhaustive, so the result is minimal, not merely a
+    /// greedy approximation. Among equally small sets the one carrying
+    /// the fewest events in total wins, keeping unwanted traffic down.
+    /// An empty `events` selects nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventNotInLayout`] for the first event no
+    /// declared eventgroup carries.
+    pub fn select(&self, events: &[u16]) -> Result<EventgroupSelection, Error> {
+        if let Some(&missing) = events
+            .iter()
+            .find(|event| self.eventgroups_for(**event).next().is_none())
+        {
+            return Err(Error::EventNotInLayout(missing));
+        }
+        // Only groups carrying at least one wanted event can be part of
+        // a minimal cover.
+        let relevant: Vec<usize, EVENTGROUP_LAYOUT_CAP> = (0..self.groups.len())
+            .filter(|&i| events.iter().any(|e| self.groups[i].1.contains(e)))
+            .collect();
+        let covers = |mask: u32| {
+            events.iter().all(|e| {
+                relevant
+                    .iter()
+                    .enumerate()
+                    .any(|(bit, &i)| mask & (1 << bit) != 0 && self.groups[i].1.contains(e))
+            })
+        };
+        let weight = |mask: u32| -> usize {
+            relevant
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| mask & (1 << bit) != 0)
+                .map(|(_, &i)| self.groups[i].1.len())
+                .sum()
+        };
+
+        let n = relevant.len();
+        let mut best = 0u32;
+        for size in 1..=n {
+            // Walk every `size`-element subset of the relevant groups in
+            // increasing mask order (Gosper's hack).
+            let mut mask = u32::MAX >> (32 - size);
+            let mut found = None::<(u32, usize)>;
+            while u64::from(mask) < (1u64 << n) {
+                if covers(mask) {
+                    let w = weight(mask);
+                    if found.is_none_or(|(_, best_w)| w < best_w) {
+                        found = Some((mask, w));
+                    }
+                }
+                let low = mask & mask.wrapping_neg();
+                let Some(ripple) = mask.checked_add(low) else {
+                    break;
+                };
+                mask = (((ripple ^ mask) >> 2) / low) | ripple;
+            }
+            if let Some((mask, _)) = found {
+                best = mask;
+                break;
+            }
+        }
+
+        Ok(relevant
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| best & (1 << bit) != 0)
+            .map(|(_, &i)| self.groups[i].0)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> EventgroupLayout {
+        EventgroupLayout::new()
+            .with_eventgroup(0x01, &[0x8001, 0x8002, 0x8003, 0x8004])
+            .unwrap()
+            .with_eventgroup(0x02, &[0x8001])
+            .unwrap()
+            .with_eventgroup(0x03, &[0x8002, 0x8005])
+            .unwrap()
+            .with_eventgroup(0x04, &[0x8005, 0x8006])
+            .unwrap()
+    }
+
+    #[test]
+    fn select_picks_smallest_then_lightest_cover() {
+        let layout = layout();
+        // Both 0x01 and 0x02 carry 0x8001; 0x02 carries less.
+        assert_eq!(layout.select(&[0x8001]).unwrap(), [0x02]);
+        // One group (0x01) beats the two-group cover 0x02 + 0x03.
+        assert_eq!(layout.select(&[0x8001, 0x8002]).unwrap(), [0x01]);
+        assert_eq!(layout.select(&[0x8001, 0x8006]).unwrap(), [0x02, 0x04]);
+        assert_eq!(layout.select(&[0x8002, 0x8006]).unwrap(), [0x03, 0x04]);
+        assert!(layout.select(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn select_rejects_unknown_event() {
+        assert!(matches!(
+            layout().select(&[0x8001, 0x9999]),
+            Err(Error::EventNotInLayout(0x9999))
+        ));
+    }
+
+    #[test]
+    fn insert_replaces_and_enforces_capacity() {
+        let mut layout = layout();
+        layout.insert(0x02, &[0x8007]).unwrap();
+        assert_eq!(layout.events(0x02), Some(&[0x8007][..]));
+        assert_eq!(
+            layout.eventgroups_for(0x8001).collect::<Vec<_, 4>>(),
+            [0x01]
+        );
+
+        let too_many = [0u16; EVENTGROUP_EVENTS_CAP + 1];
+        assert!(matches!(
+            layout.insert(0x10, &too_many),
+            Err(Error::Capacity("eventgroup_layout"))
+        ));
+        let mut full = EventgroupLayout::new();
+        for id in 0..u16::try_from(EVENTGROUP_LAYOUT_CAP).unwrap() {
+            full.insert(id, &[id]).unwrap();
+        }
+        assert!(full.insert(0xFFFF, &[1]).is_err());
+    }
+}
@@ -1,12 +1,14 @@
 use crate::log::{debug, error, info, trace, warn};
 use core::future;
-use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use core::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
 use core::task::Poll;
 use futures_util::{FutureExt, pin_mut, select_biased};
-use heapless::{Deque, index_map::FnvIndexMap};
+use heapless::index_map::FnvIndexMap;
 #[cfg(all(test, feature = "client-tokio"))]
 use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "client-tokio")]
+use crate::client::service_watch::{self, SERVICE_WATCHES_CAP, ServiceWatch, ServiceWatches};
 #[cfg(all(test, feature = "client-tokio"))]
 use crate::e2e::E2ERegistry;
 #[cfg(all(test, feature = "client-tokio"))]
@@ -14,13 +16,42 @@ use crate::tokio_transport::{
     TokioBufferProvider, TokioChannels, TokioSpawner, TokioTimer, TokioTransport,
 };
 use crate::{
-    Timer,
+    Deadline, E2ECheckStatus, PeerTable, Timer,
     client::{
         ClientUpdate, DiscoveryMessage,
+        config::{ClientConfig, SocketSettings},
+        dedup::DuplicateFilter,
+        discovery_cache::{self, UnavailableReason},
+        event_streams::{self, EVENT_STREAMS_CAP, EventRoute, EventRoutes},
+        eventgroups::EVENTGROUP_EVENTS_CAP,
+        provider_selection::{ProviderSelection, ProviderSelections},
+        reachability::{ProbeConfig, ProbeState, Prober},
+        receive_ports::{RECEIVE_PORTS_CAP, ReceivePorts},
+        request_queue::{FairQueue, Lane},
+        retry::{
+            RequestPolicy, Resend, TIMED_REQUESTS_CAP, TimedRequest, TimedRequests, Verdict,
+            into_resend, keep_for_resend,
+        },
         service_registry::{ServiceEndpointInfo, ServiceEndpointKey, ServiceRegistry},
-        session::{SessionTracker, SessionVerdict, TransportKind},
+        service_wait::{
+            self, EventWaiter, EventWaiters, FindPolicy, ServiceWaiter, ServiceWaiters,
+        },
+        session::{
+            RequestSessions, SESSIONLESS_SERVICES_CAP, SessionTracker, SessionVerdict,
+            SessionlessServices, TransportKind,
+        },
         socket_manager::{ReceivedMessage, SocketManager},
+        state_snapshot::{ClientSnapshot, DiscoveredOffer, PendingRequest, TrackedSubscription},
+        subscriptions::{
+            SUBSCRIBE_ACK_TIMEOUT, SUBSCRIBE_MANY_CAP, SUBSCRIPTIONS_CAP, Subscription,
+            SubscriptionState, Subscriptions, follow_offers, resubscribe_rebooted,
+        },
+        tcp_in_flight::{RequestKey, TCP_IN_FLIGHT_CAP, TcpInFlight},
+        version_policy::{VersionPolicies, VersionPolicy},
     },
+    drop_stats::DropStats,
+    event_validation::{EVENT_VALIDATORS_CAP, EventValidator, EventValidators},
+    janitor::{Janitor, JanitorConfig, JanitorStats},
     protocol::{self, Message},
     traits::PayloadWireFormat,
     transport::{ChannelFactory, E2ERegistryHandle, MpscRecv, OneshotSend, UnboundedSend},
@@ -30,17 +61,28 @@ use super::error::Error;
 
 /// Max depth of the internal control-message queue. Each entry is one
 /// in-flight `ControlMessage`. Must be generous enough to absorb bursts
-/// from `Client` callers between event-loop ticks.
+/// from `Client` callers between event-loop ticks. Shared by all
+/// services; the queue is drained round-robin across them.
 const REQUEST_QUEUE_CAP: usize = 32;
 
 /// Max number of outstanding unicast request-response pairs. Each entry is
 /// a `request_id` awaiting a reply. Must be a power of two.
-const PENDING_RESPONSES_CAP: usize = 64;
+pub(super) const PENDING_RESPONSES_CAP: usize = 64;
 
 /// Max number of bound unicast sockets tracked by port. Must be a power of
 /// two.
 const UNICAST_SOCKETS_CAP: usize = 8;
 
+/// Max number of open TCP connections, one per provider endpoint. Must be
+/// a power of two.
+const TCP_CONNECTIONS_CAP: usize = 4;
+
+/// Timeout and result sender of a `Client::subscribe_and_wait_first`.
+type FirstEventWait<C> = (
+    core::time::Duration,
+    <C as ChannelFactory>::OneshotSender<Result<(), Error>>,
+);
+
 pub enum ControlMessage<P: PayloadWireFormat + 'static, C: ChannelFactory> {
     SetInterface(Ipv4Addr, C::OneshotSender<Result<(), Error>>),
     BindDiscovery(C::OneshotSender<Result<(), Error>>),
@@ -56,7 +98,32 @@ pub enum ControlMessage<P: PayloadWireFormat + 'static, C: ChannelFactory> {
         u16, // local_port
         C::OneshotSender<Result<(), Error>>,
     ),
+    /// Register an endpoint that SD offers and stops leave alone.
+    AddStaticEndpoint(
+        ServiceEndpointKey,
+        u16, // instance_id
+        C::OneshotSender<Result<(), Error>>,
+    ),
     RemoveEndpoint(ServiceEndpointKey, C::OneshotSender<Result<(), Error>>),
+    SetVersionPolicy(
+        u16, // service_id
+        Option<VersionPolicy>,
+        C::OneshotSender<Result<(), Error>>,
+    ),
+    SetProviderSelection(
+        u16, // service_id
+        Option<ProviderSelection>,
+        C::OneshotSender<Result<(), Error>>,
+    ),
+    /// Set aside (`Some`, `0` for ephemeral) or release (`None`) a
+    /// unicast port for the traffic of `service_id`, or of one of its
+    /// eventgroups.
+    SetReceivePort {
+        service_id: u16,
+        event_group_id: Option<u16>,
+        port: Option<u16>,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
     SendToService {
         key: ServiceEndpointKey,
         message: Message<P>,
@@ -64,6 +131,19 @@ pub enum ControlMessage<P: PayloadWireFormat + 'static, C: ChannelFactory> {
         send_complete: C::OneshotSender<Result<(), Error>>,
         /// Fires when a matching unicast response arrives.
         response: C::OneshotSender<Result<P, Error>>,
+        /// Drop the request instead of sending it once this has passed.
+        deadline: Option<Deadline>,
+        /// Time out, and possibly resend, a request left unanswered.
+        policy: Option<RequestPolicy>,
+    },
+    /// [`Self::SendToService`] to the provider of `service_id`/`instance_id`
+    /// (`0xFFFF` for any instance) the client resolves and remembers.
+    SendToInstance {
+        service_id: u16,
+        instance_id: u16,
+        message: Message<P>,
+        send_complete: C::OneshotSender<Result<(), Error>>,
+        response: C::OneshotSender<Result<P, Error>>,
     },
     Subscribe {
         key: ServiceEndpointKey,
@@ -72,8 +152,143 @@ pub enum ControlMessage<P: PayloadWireFormat + 'static, C: ChannelFactory> {
         event_group_id: u16,
         client_port: u16,
         response: C::OneshotSender<Result<(), Error>>,
+        /// For `Client::subscribe_and_wait_first`: how long to wait for
+        /// the first notification once subscribed, and where to report
+        /// it. Dropped if the subscribe itself fails.
+        first_event: Option<FirstEventWait<C>>,
+        /// For `Client::subscribe`: track the subscription under the
+        /// given id, refresh it, and report the provider's Ack or Nack
+        /// here.
+        #[allow(clippy::type_complexity)]
+        ack: Option<(u32, C::OneshotSender<Result<(), Error>>)>,
+    },
+    /// Tracked subscriptions to several eventgroups of the service at
+    /// `key`, packed into as few `SubscribeEventgroup` messages as the
+    /// payload allows. `acks[i]` reports the provider's answer for
+    /// `event_group_ids[i]`.
+    SubscribeMany {
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_ids: heapless::Vec<u16, SUBSCRIBE_MANY_CAP>,
+        client_port: u16,
+        response: C::OneshotSender<Result<(), Error>>,
+        acks: heapless::Vec<C::OneshotSender<Result<(), Error>>, SUBSCRIBE_MANY_CAP>,
+        /// Id the subscriptions are tracked under.
+        id: u32,
+    },
+    /// Subscribe to an eventgroup, wait for its ack and keep it alive.
+    SubscribeEventgroup {
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        ttl: u32,
+        /// Fires once the provider acknowledges or rejects the
+        /// subscription.
+        response: C::OneshotSender<Result<(), Error>>,
+    },
+    /// Stop a subscription made with `SubscribeEventgroup`.
+    UnsubscribeEventgroup {
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
+    /// Stop the tracked subscription to `event_group_id` of the service
+    /// at `key`, from the port it was made from. With an `id`, only the
+    /// subscription tracked under it, not one that replaced it.
+    StopSubscription {
+        key: ServiceEndpointKey,
+        event_group_id: u16,
+        id: Option<u32>,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
+    /// Where the tracked subscription to `event_group_id` of the service
+    /// at `key` stands. With an `id`, only the subscription tracked under
+    /// it counts.
+    QuerySubscription {
+        key: ServiceEndpointKey,
+        event_group_id: u16,
+        id: Option<u32>,
+        response: C::OneshotSender<Result<SubscriptionState, Error>>,
+    },
+    /// `StopSubscribeEventgroup` for a subscription previously made from
+    /// the unicast socket on `client_port`.
+    Unsubscribe {
+        key: ServiceEndpointKey,
+        major_version: u8,
+        event_group_id: u16,
+        client_port: u16,
+        response: C::OneshotSender<Result<(), Error>>,
     },
     QueryRebootFlag(C::OneshotSender<Result<crate::protocol::sd::RebootFlag, Error>>),
+    SetOfferExpiryMargin(
+        Option<core::time::Duration>,
+        C::OneshotSender<Result<(), Error>>,
+    ),
+    SetDuplicateFilter(bool, C::OneshotSender<Result<(), Error>>),
+    SetEventValidator {
+        service_id: u16,
+        event_id: u16,
+        validator: Option<EventValidator<P>>,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
+    SetSessionHandling(u16, bool, C::OneshotSender<Result<(), Error>>),
+    SetReachabilityProbe(Option<ProbeConfig>, C::OneshotSender<Result<(), Error>>),
+    QueryOfferTtl {
+        service_id: u16,
+        instance_id: u16,
+        response: C::OneshotSender<Result<Option<core::time::Duration>, Error>>,
+    },
+    QueryPeerStats(C::OneshotSender<Result<PeerTable, Error>>),
+    SetJanitor(Option<JanitorConfig>, C::OneshotSender<Result<(), Error>>),
+    QueryJanitorStats(C::OneshotSender<Result<JanitorStats, Error>>),
+    QueryDropStats(C::OneshotSender<Result<DropStats, Error>>),
+    QueryStateSnapshot(C::OneshotSender<Result<ClientSnapshot, Error>>),
+    /// Resolve `service_id`/`instance_id` to an offered endpoint, sending
+    /// `FindService`s and waiting for an offer as `policy` says.
+    WaitForService {
+        service_id: u16,
+        instance_id: u16,
+        policy: FindPolicy,
+        response: C::OneshotSender<Result<ServiceEndpointKey, Error>>,
+    },
+    /// Pick a known provider of `service_id`/`instance_id` under its
+    /// [`ProviderSelection`]; with `failover` `Some((primary, attempt))`,
+    /// the `attempt`th alternative to `primary` instead.
+    SelectProvider {
+        service_id: u16,
+        instance_id: u16,
+        failover: Option<(ServiceEndpointKey, u8)>,
+        response: C::OneshotSender<Result<ServiceEndpointKey, Error>>,
+    },
+    /// Stop the pending `WaitForService`s for `service_id`/`instance_id`
+    /// (`0xFFFF` for all of the service's), failing them with
+    /// [`Error::FindCancelled`].
+    CancelFind {
+        service_id: u16,
+        instance_id: u16,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
+    /// Route the notifications of `events`, the members of
+    /// `event_group_id`, from `service_id`/`instance_id` to `sender`.
+    OpenEventStream {
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        events: heapless::Vec<u16, EVENTGROUP_EVENTS_CAP>,
+        sender: C::UnboundedSender<Message<P>>,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
+    /// Send `sender` where `service_id`/`instance_id` is offered each
+    /// time that changes, `None` once it is not.
+    #[cfg(feature = "client-tokio")]
+    WatchService {
+        service_id: u16,
+        instance_id: u16,
+        sender: tokio::sync::watch::Sender<Option<ServiceEndpointKey>>,
+        response: C::OneshotSender<Result<(), Error>>,
+    },
     /// Test-only: force `sd_session_has_wrapped` to simulate the state a
     /// long-running client reaches after its SD session counter wraps past
     /// `0xFFFF`, without actually sending 65k SD messages. Fires the
@@ -83,6 +298,7 @@ pub enum ControlMessage<P: PayloadWireFormat + 'static, C: ChannelFactory> {
 }
 
 impl<P: PayloadWireFormat + 'static, C: ChannelFactory> core::fmt::Debug for ControlMessage<P, C> {
+    #[allow(clippy::too_many_lines)]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::SetInterface(addr, _) => f.debug_tuple("SetInterface").field(addr).finish(),
@@ -97,22 +313,227 @@ impl<P: PayloadWireFormat + 'static, C: ChannelFactory> core::fmt::Debug for Con
                 .field(instance_id)
                 .field(local_port)
                 .finish(),
+            Self::AddStaticEndpoint(key, instance_id, _) => f
+                .debug_tuple("AddStaticEndpoint")
+                .field(key)
+                .field(instance_id)
+                .finish(),
             Self::RemoveEndpoint(key, _) => f.debug_tuple("RemoveEndpoint").field(key).finish(),
-            Self::SendToService { key, message, .. } => f
+            Self::SetReceivePort {
+                service_id,
+                event_group_id,
+                port,
+                ..
+            } => f
+                .debug_struct("SetReceivePort")
+                .field("service_id", service_id)
+                .field("event_group_id", event_group_id)
+                .field("port", port)
+                .finish_non_exhaustive(),
+            Self::SetVersionPolicy(service_id, policy, _) => f
+                .debug_tuple("SetVersionPolicy")
+                .field(service_id)
+                .field(policy)
+                .finish(),
+            Self::SetProviderSelection(service_id, selection, _) => f
+                .debug_tuple("SetProviderSelection")
+                .field(service_id)
+                .field(selection)
+                .finish(),
+            Self::SendToService {
+                key,
+                message,
+                deadline,
+                policy,
+                ..
+            } => f
                 .debug_struct("SendToService")
                 .field("key", key)
                 .field("message", message)
+                .field("deadline", deadline)
+                .field("policy", policy)
+                .finish_non_exhaustive(),
+            Self::SendToInstance {
+                service_id,
+                instance_id,
+                message,
+                ..
+            } => f
+                .debug_struct("SendToInstance")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .field("message", message)
                 .finish_non_exhaustive(),
             Self::Subscribe {
                 key,
                 event_group_id,
+                first_event,
+                ack,
                 ..
             } => f
                 .debug_struct("Subscribe")
                 .field("key", key)
                 .field("event_group_id", event_group_id)
+                .field(
+                    "first_event_timeout",
+                    &first_event.as_ref().map(|(timeout, _)| timeout),
+                )
+                .field("tracked", &ack.is_some())
+                .finish_non_exhaustive(),
+            Self::SubscribeMany {
+                key,
+                event_group_ids,
+                ..
+            } => f
+                .debug_struct("SubscribeMany")
+                .field("key", key)
+                .field("event_group_ids", event_group_ids)
+                .finish_non_exhaustive(),
+            Self::StopSubscription {
+                key,
+                event_group_id,
+                ..
+            } => f
+                .debug_struct("StopSubscription")
+                .field("key", key)
+                .field("event_group_id", event_group_id)
+                .finish_non_exhaustive(),
+            Self::QuerySubscription {
+                key,
+                event_group_id,
+                ..
+            } => f
+                .debug_struct("QuerySubscription")
+                .field("key", key)
+                .field("event_group_id", event_group_id)
+                .finish_non_exhaustive(),
+            Self::Unsubscribe {
+                key,
+                event_group_id,
+                ..
+            } => f
+                .debug_struct("Unsubscribe")
+                .field("key", key)
+                .field("event_group_id", event_group_id)
+                .finish_non_exhaustive(),
+            Self::SubscribeEventgroup {
+                service_id,
+                instance_id,
+                event_group_id,
+                ttl,
+                ..
+            } => f
+                .debug_struct("SubscribeEventgroup")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .field("event_group_id", event_group_id)
+                .field("ttl", ttl)
+                .finish_non_exhaustive(),
+            Self::UnsubscribeEventgroup {
+                service_id,
+                instance_id,
+                event_group_id,
+                ..
+            } => f
+                .debug_struct("UnsubscribeEventgroup")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .field("event_group_id", event_group_id)
                 .finish_non_exhaustive(),
             Self::QueryRebootFlag(_) => f.write_str("QueryRebootFlag"),
+            Self::SetOfferExpiryMargin(margin, _) => {
+                f.debug_tuple("SetOfferExpiryMargin").field(margin).finish()
+            }
+            Self::SetDuplicateFilter(enabled, _) => {
+                f.debug_tuple("SetDuplicateFilter").field(enabled).finish()
+            }
+            Self::SetEventValidator {
+                service_id,
+                event_id,
+                validator,
+                ..
+            } => f
+                .debug_struct("SetEventValidator")
+                .field("service_id", service_id)
+                .field("event_id", event_id)
+                .field("validator", &validator.is_some())
+                .finish_non_exhaustive(),
+            Self::SetSessionHandling(service_id, enabled, _) => f
+                .debug_tuple("SetSessionHandling")
+                .field(service_id)
+                .field(enabled)
+                .finish(),
+            Self::SetReachabilityProbe(config, _) => {
+                f.debug_tuple("SetReachabilityProbe").field(config).finish()
+            }
+            Self::QueryOfferTtl {
+                service_id,
+                instance_id,
+                ..
+            } => f
+                .debug_struct("QueryOfferTtl")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .finish_non_exhaustive(),
+            Self::QueryPeerStats(_) => f.write_str("QueryPeerStats"),
+            Self::SetJanitor(config, _) => f.debug_tuple("SetJanitor").field(config).finish(),
+            Self::QueryJanitorStats(_) => f.write_str("QueryJanitorStats"),
+            Self::QueryDropStats(_) => f.write_str("QueryDropStats"),
+            Self::QueryStateSnapshot(_) => f.write_str("QueryStateSnapshot"),
+            Self::WaitForService {
+                service_id,
+                instance_id,
+                policy,
+                ..
+            } => f
+                .debug_struct("WaitForService")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .field("policy", policy)
+                .finish_non_exhaustive(),
+            Self::SelectProvider {
+                service_id,
+                instance_id,
+                failover,
+                ..
+            } => f
+                .debug_struct("SelectProvider")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .field("failover", failover)
+                .finish_non_exhaustive(),
+            Self::CancelFind {
+                service_id,
+                instance_id,
+                ..
+            } => f
+                .debug_struct("CancelFind")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .finish_non_exhaustive(),
+            Self::OpenEventStream {
+                service_id,
+                instance_id,
+                event_group_id,
+                events,
+                ..
+            } => f
+                .debug_struct("OpenEventStream")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .field("event_group_id", event_group_id)
+                .field("events", events)
+                .finish_non_exhaustive(),
+            #[cfg(feature = "client-tokio")]
+            Self::WatchService {
+                service_id,
+                instance_id,
+                ..
+            } => f
+                .debug_struct("WatchService")
+                .field("service_id", service_id)
+                .field("instance_id", instance_id)
+                .finish_non_exhaustive(),
             #[cfg(all(test, feature = "client-tokio"))]
             Self::ForceSdSessionWrappedForTest(b, _) => f
                 .debug_tuple("ForceSdSessionWrappedForTest")
@@ -122,6 +543,35 @@ impl<P: PayloadWireFormat + 'static, C: ChannelFactory> core::fmt::Debug for Con
     }
 }
 
+impl<P: PayloadWireFormat + 'static, C: ChannelFactory> Lane for ControlMessage<P, C> {
+    fn lane(&self) -> Option<u16> {
+        match self {
+            Self::AddEndpoint(key, ..)
+            | Self::AddStaticEndpoint(key, ..)
+            | Self::RemoveEndpoint(key, _)
+            | Self::SendToService { key, .. }
+            | Self::Subscribe { key, .. }
+            | Self::SubscribeMany { key, .. }
+            | Self::StopSubscription { key, .. }
+            | Self::QuerySubscription { key, .. }
+            | Self::Unsubscribe { key, .. } => Some(key.service_id),
+            Self::SetVersionPolicy(service_id, ..)
+            | Self::SetProviderSelection(service_id, ..)
+            | Self::SetEventValidator { service_id, .. }
+            | Self::SetReceivePort { service_id, .. }
+            | Self::SubscribeEventgroup { service_id, .. }
+            | Self::UnsubscribeEventgroup { service_id, .. }
+            | Self::QueryOfferTtl { service_id, .. }
+            | Self::WaitForService { service_id, .. }
+            | Self::SelectProvider { service_id, .. }
+            | Self::SendToInstance { service_id, .. }
+            | Self::CancelFind { service_id, .. }
+            | Self::OpenEventStream { service_id, .. } => Some(*service_id),
+            _ => None,
+        }
+    }
+}
+
 impl<P, C> ControlMessage<P, C>
 where
     P: PayloadWireFormat + Send + 'static,
@@ -129,6 +579,13 @@ where
     Result<(), Error>: crate::transport::OneshotPooled<C>,
     Result<P, Error>: crate::transport::OneshotPooled<C>,
     Result<crate::protocol::sd::RebootFlag, Error>: crate::transport::OneshotPooled<C>,
+    Result<Option<core::time::Duration>, Error>: crate::transport::OneshotPooled<C>,
+    Result<PeerTable, Error>: crate::transport::OneshotPooled<C>,
+    Result<ServiceEndpointKey, Error>: crate::transport::OneshotPooled<C>,
+    Result<JanitorStats, Error>: crate::transport::OneshotPooled<C>,
+    Result<DropStats, Error>: crate::transport::OneshotPooled<C>,
+    Result<ClientSnapshot, Error>: crate::transport::OneshotPooled<C>,
+    Result<SubscriptionState, Error>: crate::transport::OneshotPooled<C>,
 {
     #[must_use]
     pub fn set_interface(interface: Ipv4Addr) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
@@ -167,6 +624,15 @@ where
         )
     }
 
+    #[must_use]
+    pub fn add_static_endpoint(
+        key: ServiceEndpointKey,
+        instance_id: u16,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::AddStaticEndpoint(key, instance_id, sender))
+    }
+
     #[must_use]
     pub fn remove_endpoint(
         key: ServiceEndpointKey,
@@ -175,6 +641,45 @@ where
         (receiver, Self::RemoveEndpoint(key, sender))
     }
 
+    #[must_use]
+    pub fn set_version_policy(
+        service_id: u16,
+        policy: Option<VersionPolicy>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::SetVersionPolicy(service_id, policy, sender))
+    }
+
+    #[must_use]
+    pub fn set_provider_selection(
+        service_id: u16,
+        selection: Option<ProviderSelection>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::SetProviderSelection(service_id, selection, sender),
+        )
+    }
+
+    #[must_use]
+    pub fn set_receive_port(
+        service_id: u16,
+        event_group_id: Option<u16>,
+        port: Option<u16>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::SetReceivePort {
+                service_id,
+                event_group_id,
+                port,
+                response: sender,
+            },
+        )
+    }
+
     #[allow(clippy::type_complexity)]
     #[must_use]
     pub fn send_to_service(
@@ -184,6 +689,35 @@ where
         C::OneshotReceiver<Result<(), Error>>,
         C::OneshotReceiver<Result<P, Error>>,
         Self,
+    ) {
+        Self::send_to_service_before(key, message, None)
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn send_to_service_before(
+        key: ServiceEndpointKey,
+        message: Message<P>,
+        deadline: Option<Deadline>,
+    ) -> (
+        C::OneshotReceiver<Result<(), Error>>,
+        C::OneshotReceiver<Result<P, Error>>,
+        Self,
+    ) {
+        Self::send_to_service_with(key, message, deadline, None)
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn send_to_service_with(
+        key: ServiceEndpointKey,
+        message: Message<P>,
+        deadline: Option<Deadline>,
+        policy: Option<RequestPolicy>,
+    ) -> (
+        C::OneshotReceiver<Result<(), Error>>,
+        C::OneshotReceiver<Result<P, Error>>,
+        Self,
     ) {
         let (send_complete_tx, send_complete_rx) = C::oneshot();
         let (response_tx, response_rx) = C::oneshot();
@@ -195,6 +729,34 @@ where
                 message,
                 send_complete: send_complete_tx,
                 response: response_tx,
+                deadline,
+                policy,
+            },
+        )
+    }
+
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn send_to_instance(
+        service_id: u16,
+        instance_id: u16,
+        message: Message<P>,
+    ) -> (
+        C::OneshotReceiver<Result<(), Error>>,
+        C::OneshotReceiver<Result<P, Error>>,
+        Self,
+    ) {
+        let (send_complete_tx, send_complete_rx) = C::oneshot();
+        let (response_tx, response_rx) = C::oneshot();
+        (
+            send_complete_rx,
+            response_rx,
+            Self::SendToInstance {
+                service_id,
+                instance_id,
+                message,
+                send_complete: send_complete_tx,
+                response: response_tx,
             },
         )
     }
@@ -217,23 +779,424 @@ where
                 event_group_id,
                 client_port,
                 response: sender,
+                first_event: None,
+                ack: None,
             },
         )
     }
 
+    /// A [`Self::subscribe`] that is tracked: the second receiver gets
+    /// the provider's answer.
+    #[allow(clippy::type_complexity)]
     #[must_use]
-    pub fn query_reboot_flag() -> (
-        C::OneshotReceiver<Result<crate::protocol::sd::RebootFlag, Error>>,
+    pub fn subscribe_tracked(
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_id: u16,
+        client_port: u16,
+        id: u32,
+    ) -> (
+        C::OneshotReceiver<Result<(), Error>>,
+        C::OneshotReceiver<Result<(), Error>>,
         Self,
     ) {
         let (sender, receiver) = C::oneshot();
-        (receiver, Self::QueryRebootFlag(sender))
+        let (ack_sender, ack_receiver) = C::oneshot();
+        (
+            receiver,
+            ack_receiver,
+            Self::Subscribe {
+                key,
+                major_version,
+                ttl,
+                event_group_id,
+                client_port,
+                response: sender,
+                first_event: None,
+                ack: Some((id, ack_sender)),
+            },
+        )
     }
 
-    #[cfg(all(test, feature = "client-tokio"))]
+    /// Returns the receivers for the send result and for each
+    /// eventgroup's ack, in `event_group_ids` order.
     #[must_use]
-    pub fn force_sd_session_wrapped_for_test(
-        wrapped: bool,
+    #[allow(clippy::type_complexity)]
+    pub fn subscribe_many(
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_ids: heapless::Vec<u16, SUBSCRIBE_MANY_CAP>,
+        client_port: u16,
+        id: u32,
+    ) -> (
+        C::OneshotReceiver<Result<(), Error>>,
+        heapless::Vec<C::OneshotReceiver<Result<(), Error>>, SUBSCRIBE_MANY_CAP>,
+        Self,
+    ) {
+        let (sender, receiver) = C::oneshot();
+        let mut acks = heapless::Vec::new();
+        let mut ack_receivers = heapless::Vec::new();
+        for _ in &event_group_ids {
+            let (ack_sender, ack_receiver) = C::oneshot();
+            // Cannot fail: one of each per eventgroup id.
+            let _ = acks.push(ack_sender);
+            let _ = ack_receivers.push(ack_receiver);
+        }
+        (
+            receiver,
+            ack_receivers,
+            Self::SubscribeMany {
+                key,
+                major_version,
+                ttl,
+                event_group_ids,
+                client_port,
+                response: sender,
+                acks,
+                id,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn stop_subscription(
+        key: ServiceEndpointKey,
+        event_group_id: u16,
+        id: Option<u32>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::StopSubscription {
+                key,
+                event_group_id,
+                id,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn query_subscription(
+        key: ServiceEndpointKey,
+        event_group_id: u16,
+        id: Option<u32>,
+    ) -> (C::OneshotReceiver<Result<SubscriptionState, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::QuerySubscription {
+                key,
+                event_group_id,
+                id,
+                response: sender,
+            },
+        )
+    }
+
+    /// A [`Self::subscribe`] that also reports the first notification
+    /// from the provider, through the second receiver.
+    #[allow(clippy::type_complexity)]
+    #[must_use]
+    pub fn subscribe_and_wait_first(
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_id: u16,
+        client_port: u16,
+        timeout: core::time::Duration,
+    ) -> (
+        C::OneshotReceiver<Result<(), Error>>,
+        C::OneshotReceiver<Result<(), Error>>,
+        Self,
+    ) {
+        let (sender, receiver) = C::oneshot();
+        let (first_sender, first_receiver) = C::oneshot();
+        (
+            receiver,
+            first_receiver,
+            Self::Subscribe {
+                key,
+                major_version,
+                ttl,
+                event_group_id,
+                client_port,
+                response: sender,
+                first_event: Some((timeout, first_sender)),
+                ack: None,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn unsubscribe(
+        key: ServiceEndpointKey,
+        major_version: u8,
+        event_group_id: u16,
+        client_port: u16,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::Unsubscribe {
+                key,
+                major_version,
+                event_group_id,
+                client_port,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn subscribe_eventgroup(
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        ttl: u32,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::SubscribeEventgroup {
+                service_id,
+                instance_id,
+                event_group_id,
+                ttl,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn unsubscribe_eventgroup(
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::UnsubscribeEventgroup {
+                service_id,
+                instance_id,
+                event_group_id,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn query_reboot_flag() -> (
+        C::OneshotReceiver<Result<crate::protocol::sd::RebootFlag, Error>>,
+        Self,
+    ) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::QueryRebootFlag(sender))
+    }
+
+    #[must_use]
+    pub fn set_offer_expiry_margin(
+        margin: Option<core::time::Duration>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::SetOfferExpiryMargin(margin, sender))
+    }
+
+    #[must_use]
+    pub fn set_duplicate_filter(enabled: bool) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::SetDuplicateFilter(enabled, sender))
+    }
+
+    #[must_use]
+    pub fn set_event_validator(
+        service_id: u16,
+        event_id: u16,
+        validator: Option<EventValidator<P>>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::SetEventValidator {
+                service_id,
+                event_id,
+                validator,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn set_session_handling(
+        service_id: u16,
+        enabled: bool,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::SetSessionHandling(service_id, enabled, sender),
+        )
+    }
+
+    #[must_use]
+    pub fn set_reachability_probe(
+        config: Option<ProbeConfig>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::SetReachabilityProbe(config, sender))
+    }
+
+    #[must_use]
+    pub fn query_offer_ttl(
+        service_id: u16,
+        instance_id: u16,
+    ) -> (
+        C::OneshotReceiver<Result<Option<core::time::Duration>, Error>>,
+        Self,
+    ) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::QueryOfferTtl {
+                service_id,
+                instance_id,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn query_peer_stats() -> (C::OneshotReceiver<Result<PeerTable, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::QueryPeerStats(sender))
+    }
+
+    #[must_use]
+    pub fn set_janitor(
+        config: Option<JanitorConfig>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::SetJanitor(config, sender))
+    }
+
+    #[must_use]
+    pub fn query_janitor_stats() -> (C::OneshotReceiver<Result<JanitorStats, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::QueryJanitorStats(sender))
+    }
+
+    #[must_use]
+    pub fn query_drop_stats() -> (C::OneshotReceiver<Result<DropStats, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::QueryDropStats(sender))
+    }
+
+    #[must_use]
+    pub fn query_state_snapshot() -> (C::OneshotReceiver<Result<ClientSnapshot, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (receiver, Self::QueryStateSnapshot(sender))
+    }
+
+    #[must_use]
+    pub fn wait_for_service(
+        service_id: u16,
+        instance_id: u16,
+        policy: FindPolicy,
+    ) -> (C::OneshotReceiver<Result<ServiceEndpointKey, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::WaitForService {
+                service_id,
+                instance_id,
+                policy,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn select_provider(
+        service_id: u16,
+        instance_id: u16,
+        failover: Option<(ServiceEndpointKey, u8)>,
+    ) -> (C::OneshotReceiver<Result<ServiceEndpointKey, Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::SelectProvider {
+                service_id,
+                instance_id,
+                failover,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn cancel_find(
+        service_id: u16,
+        instance_id: u16,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (sender, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::CancelFind {
+                service_id,
+                instance_id,
+                response: sender,
+            },
+        )
+    }
+
+    #[must_use]
+    pub fn open_event_stream(
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        events: heapless::Vec<u16, EVENTGROUP_EVENTS_CAP>,
+        sender: C::UnboundedSender<Message<P>>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (response, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::OpenEventStream {
+                service_id,
+                instance_id,
+                event_group_id,
+                events,
+                sender,
+                response,
+            },
+        )
+    }
+
+    #[cfg(feature = "client-tokio")]
+    #[must_use]
+    pub fn watch_service(
+        service_id: u16,
+        instance_id: u16,
+        sender: tokio::sync::watch::Sender<Option<ServiceEndpointKey>>,
+    ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
+        let (response, receiver) = C::oneshot();
+        (
+            receiver,
+            Self::WatchService {
+                service_id,
+                instance_id,
+                sender,
+                response,
+            },
+        )
+    }
+
+    #[cfg(all(test, feature = "client-tokio"))]
+    #[must_use]
+    pub fn force_sd_session_wrapped_for_test(
+        wrapped: bool,
     ) -> (C::OneshotReceiver<Result<(), Error>>, Self) {
         let (sender, receiver) = C::oneshot();
         (
@@ -257,14 +1220,40 @@ where
             | Self::UnbindDiscovery(response)
             | Self::SendSD(_, _, response)
             | Self::AddEndpoint(_, _, _, response)
+            | Self::AddStaticEndpoint(_, _, response)
             | Self::RemoveEndpoint(_, response)
-            | Self::Subscribe { response, .. } => {
+            | Self::SetVersionPolicy(_, _, response)
+            | Self::SetProviderSelection(_, _, response)
+            | Self::SetReceivePort { response, .. }
+            | Self::SetOfferExpiryMargin(_, response)
+            | Self::SetDuplicateFilter(_, response)
+            | Self::SetEventValidator { response, .. }
+            | Self::SetSessionHandling(_, _, response)
+            | Self::SetReachabilityProbe(_, response)
+            | Self::SetJanitor(_, response)
+            | Self::Subscribe { response, .. }
+            | Self::SubscribeMany { response, .. }
+            | Self::StopSubscription { response, .. }
+            | Self::SubscribeEventgroup { response, .. }
+            | Self::UnsubscribeEventgroup { response, .. }
+            | Self::OpenEventStream { response, .. }
+            | Self::CancelFind { response, .. }
+            | Self::Unsubscribe { response, .. } => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            #[cfg(feature = "client-tokio")]
+            Self::WatchService { response, .. } => {
                 let _ = response.send(Err(Error::Capacity(structure_name)));
             }
             Self::SendToService {
                 send_complete,
                 response,
                 ..
+            }
+            | Self::SendToInstance {
+                send_complete,
+                response,
+                ..
             } => {
                 let _ = send_complete.send(Err(Error::Capacity(structure_name)));
                 let _ = response.send(Err(Error::Capacity(structure_name)));
@@ -272,6 +1261,27 @@ where
             Self::QueryRebootFlag(response) => {
                 let _ = response.send(Err(Error::Capacity(structure_name)));
             }
+            Self::QueryOfferTtl { response, .. } => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            Self::QueryPeerStats(response) => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            Self::QueryJanitorStats(response) => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            Self::QueryDropStats(response) => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            Self::QueryStateSnapshot(response) => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            Self::QuerySubscription { response, .. } => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
+            Self::WaitForService { response, .. } | Self::SelectProvider { response, .. } => {
+                let _ = response.send(Err(Error::Capacity(structure_name)));
+            }
             #[cfg(all(test, feature = "client-tokio"))]
             Self::ForceSdSessionWrappedForTest(_, response) => {
                 let _ = response.send(Err(Error::Capacity(structure_name)));
@@ -290,14 +1300,19 @@ pub(super) struct Inner<
     /// MPSC Receiver used to receive control messages from outer client
     control_receiver: C::BoundedReceiver<ControlMessage<PayloadDefinitions, C>, 4>,
     /// Queue of pending control messages to process
-    request_queue: Deque<ControlMessage<PayloadDefinitions, C>, REQUEST_QUEUE_CAP>,
-    /// Pending request-responses keyed by `request_id` (`client_id` << 16 | `session_counter`).
-    /// Set by `SendToService`, cleared when a matching unicast arrives.
+    request_queue: FairQueue<ControlMessage<PayloadDefinitions, C>, REQUEST_QUEUE_CAP>,
+    /// Pending request-responses keyed by peer, message ID and
+    /// `request_id` (`client_id` << 16 | the peer's session ID), so any
+    /// number of requests can be outstanding at once. Set by `SendToService`, cleared when a
+    /// unicast echoing both arrives.
     pending_responses: FnvIndexMap<
-        u32,
+        RequestKey,
         C::OneshotSender<Result<PayloadDefinitions, Error>>,
         PENDING_RESPONSES_CAP,
     >,
+    /// Requests sent with a `RequestPolicy`, timed against their reply.
+    timed_requests:
+        TimedRequests<PayloadDefinitions, C::OneshotSender<Result<PayloadDefinitions, Error>>>,
     /// Unbounded sender used to send updates to outer client
     update_sender: C::UnboundedSender<ClientUpdate<PayloadDefinitions>>,
     /// Target interface for sockets
@@ -312,16 +1327,70 @@ pub(super) struct Inner<
     discovery_unicast_socket: Option<SocketManager<PayloadDefinitions, C>>,
     /// Socket managers for unicast messages, keyed by local port
     unicast_sockets: FnvIndexMap<u16, SocketManager<PayloadDefinitions, C>, UNICAST_SOCKETS_CAP>,
+    /// Connections to services offered over TCP, keyed by provider
+    /// endpoint. Opened on first use; one whose peer closed it is dropped
+    /// and reopened by the next message.
+    tcp_connections:
+        FnvIndexMap<SocketAddrV4, SocketManager<PayloadDefinitions, C>, TCP_CONNECTIONS_CAP>,
+    /// Requests awaiting a reply on each TCP connection; failed with
+    /// `SocketClosedUnexpectedly` when their connection closes.
+    tcp_in_flight: TcpInFlight<TCP_CONNECTIONS_CAP>,
+    /// [`Timer::now`] of the last message sent or received over each TCP
+    /// connection, for the janitor's idle check
+    tcp_last_active: FnvIndexMap<SocketAddrV4, core::time::Duration, TCP_CONNECTIONS_CAP>,
     /// Per-sender SD session state for reboot detection
     session_tracker: SessionTracker,
     /// Registry of known service endpoints (auto-populated from SD + manual)
     service_registry: ServiceRegistry,
+    /// Per-service version acceptance rules applied to SD offers before
+    /// they reach `service_registry`
+    version_policies: VersionPolicies,
+    /// Per-service choice among redundant providers of one instance
+    provider_selections: ProviderSelections,
+    /// Unicast ports set aside for one service's or eventgroup's traffic
+    receive_ports: ReceivePorts,
+    /// How long before an offer lapses `ClientUpdate::OfferExpiring` is
+    /// emitted; `None` disables the update
+    offer_expiry_margin: Option<core::time::Duration>,
+    /// Per-peer traffic statistics, read through `Client::peer_stats`
+    peers: PeerTable,
+    /// Recently delivered notifications; `None` while duplicate filtering
+    /// is off (the default)
+    duplicate_filter: Option<DuplicateFilter>,
+    /// Payload checks run on notifications before they are delivered
+    event_validators: EventValidators<PayloadDefinitions>,
+    /// Services whose peers do not use session IDs.
+    sessionless_services: SessionlessServices,
+    /// Eventgroup subscriptions awaiting their ack or their refresh.
+    subscriptions: Subscriptions<C::OneshotSender<Result<(), Error>>>,
+    /// Endpoint reachability prober; `None` while probing is off (the
+    /// default)
+    prober: Option<Prober>,
+    /// Sweep schedule; `None` while the janitor is off (the default)
+    janitor: Option<Janitor>,
+    /// What the janitor has collected, read through
+    /// `Client::janitor_stats`. Kept across `Client::set_janitor` calls.
+    janitor_stats: JanitorStats,
+    /// Pending `Client::wait_for_service` calls, answered as soon as a
+    /// matching offer is in `service_registry`
+    service_waiters: ServiceWaiters<C::OneshotSender<Result<ServiceEndpointKey, Error>>>,
+    /// Pending `Client::subscribe_and_wait_first` calls, answered by the
+    /// first notification from the subscribed provider
+    event_waiters: EventWaiters<C::OneshotSender<Result<(), Error>>>,
+    /// Open `Client::event_stream`s, fed the notifications of their
+    /// eventgroup instead of the update stream
+    event_streams: EventRoutes<C::UnboundedSender<Message<PayloadDefinitions>>>,
+    /// Open `Client::watch_service` watches, sent each change of where
+    /// their instance is offered. Allocated by the first watch; most
+    /// clients never open one.
+    #[cfg(feature = "client-tokio")]
+    service_watches: Option<std::boxed::Box<ServiceWatches>>,
     /// Internal flag to continue run loop
     run: bool,
     /// Client ID for SOME/IP request headers (upper 16 bits of request ID)
     client_id: u16,
-    /// Incrementing session counter for SOME/IP request headers (lower 16 bits of request ID)
-    session_counter: u16,
+    /// Per-peer session counters for SOME/IP request headers (lower 16 bits of request ID)
+    request_sessions: RequestSessions,
     /// SD session state persisted across discovery socket rebinds so that
     /// `unbind_discovery` + `bind_discovery` does not emit a false reboot signal.
     sd_session_id: u16,
@@ -330,6 +1399,14 @@ pub(super) struct Inner<
     e2e_registry: R,
     /// Enable multicast loopback on SD sockets for same-host testing
     multicast_loopback: bool,
+    /// Channel capacities, overflow policy, receive buffer size and SD
+    /// endpoint of each bound socket
+    socket_settings: SocketSettings,
+    /// Ports tried for a unicast socket bound without one; `None` binds
+    /// an ephemeral port
+    unicast_ports: Option<(u16, u16)>,
+    /// Longest wait for a TCP connection to a provider to open
+    tcp_connect_timeout: core::time::Duration,
     /// Bind dispatch — abstracts the bind-and-spawn step over either a
     /// [`Spawner`](crate::transport::Spawner) (Send-required) or a
     /// [`LocalSpawner`](crate::transport::LocalSpawner) (single-task)
@@ -354,7 +1431,7 @@ impl<P: PayloadWireFormat, Tm: Timer, R: E2ERegistryHandle, C: ChannelFactory, D
             .field("session_tracker", &self.session_tracker)
             .field("run", &self.run)
             .field("client_id", &self.client_id)
-            .field("session_counter", &self.session_counter)
+            .field("request_sessions", &self.request_sessions)
             .finish_non_exhaustive()
     }
 }
@@ -370,6 +1447,13 @@ where
     Result<(), Error>: crate::transport::OneshotPooled<C>,
     Result<PayloadDefinitions, Error>: crate::transport::OneshotPooled<C>,
     Result<crate::protocol::sd::RebootFlag, Error>: crate::transport::OneshotPooled<C>,
+    Result<Option<core::time::Duration>, Error>: crate::transport::OneshotPooled<C>,
+    Result<PeerTable, Error>: crate::transport::OneshotPooled<C>,
+    Result<ServiceEndpointKey, Error>: crate::transport::OneshotPooled<C>,
+    Result<JanitorStats, Error>: crate::transport::OneshotPooled<C>,
+    Result<DropStats, Error>: crate::transport::OneshotPooled<C>,
+    Result<ClientSnapshot, Error>: crate::transport::OneshotPooled<C>,
+    Result<SubscriptionState, Error>: crate::transport::OneshotPooled<C>,
     ControlMessage<PayloadDefinitions, C>: crate::transport::BoundedPooled<C, 4>,
     super::socket_manager::SendMessage<PayloadDefinitions, C>:
         crate::transport::BoundedPooled<C, 16>,
@@ -393,7 +1477,7 @@ where
     pub fn build(
         interface: Ipv4Addr,
         e2e_registry: R,
-        multicast_loopback: bool,
+        config: ClientConfig,
         dispatch: D,
         timer: Tm,
     ) -> (
@@ -402,153 +1486,1051 @@ where
         impl core::future::Future<Output = ()> + 'static,
     ) {
         info!("Initializing SOME/IP Client");
-        let (control_sender, control_receiver) = C::bounded::<_, 4>();
+        let (control_sender, control_receiver) =
+            C::bounded_with_capacity::<_, 4>(config.control_capacity);
         let (update_sender, update_receiver) = C::unbounded();
         let inner = Self {
             control_receiver,
-            request_queue: Deque::new(),
+            request_queue: FairQueue::new(),
             pending_responses: FnvIndexMap::new(),
+            timed_requests: heapless::Vec::new(),
             update_sender,
             interface,
             discovery_socket: None,
             discovery_unicast_socket: None,
             unicast_sockets: FnvIndexMap::new(),
+            tcp_connections: FnvIndexMap::new(),
+            tcp_in_flight: TcpInFlight::new(),
+            tcp_last_active: FnvIndexMap::new(),
             session_tracker: SessionTracker::default(),
             service_registry: ServiceRegistry::default(),
+            version_policies: VersionPolicies::default(),
+            provider_selections: ProviderSelections::default(),
+            receive_ports: ReceivePorts::default(),
+            offer_expiry_margin: None,
+            peers: PeerTable::new(),
+            duplicate_filter: None,
+            event_validators: EventValidators::new(),
+            sessionless_services: SessionlessServices::default(),
+            subscriptions: heapless::Vec::new(),
+            prober: None,
+            janitor: None,
+            janitor_stats: JanitorStats::default(),
+            service_waiters: heapless::Vec::new(),
+            event_waiters: heapless::Vec::new(),
+            event_streams: heapless::Vec::new(),
+            #[cfg(feature = "client-tokio")]
+            service_watches: None,
             run: true,
             client_id: 0x1234,
-            session_counter: 1,
+            request_sessions: RequestSessions::default(),
             sd_session_id: 1,
             sd_session_has_wrapped: false,
             e2e_registry,
-            multicast_loopback,
+            multicast_loopback: config.multicast_loopback,
+            socket_settings: config.socket_settings(),
+            unicast_ports: config.unicast_ports,
+            tcp_connect_timeout: config.tcp_connect_timeout,
             dispatch,
             timer,
             phantom: core::marker::PhantomData,
         };
-        (control_sender, update_receiver, inner.run_future())
+        (
+            control_sender,
+            update_receiver,
+            inner.run_future(config.auto_bind_discovery),
+        )
+    }
+
+    async fn bind_discovery(&mut self) -> Result<(), Error> {
+        if self.discovery_socket.is_some() {
+            Ok(())
+        } else {
+            let socket = self
+                .dispatch
+                .bind_discovery(
+                    self.interface,
+                    self.e2e_registry.clone(),
+                    self.sd_session_id,
+                    self.sd_session_has_wrapped,
+                    self.multicast_loopback,
+                    self.socket_settings,
+                )
+                .await?;
+            self.discovery_socket = Some(socket);
+            // Receive-only unicast SD socket bound to the interface IP — see
+            // `discovery_unicast_socket`. Best-effort: if the unicast bind
+            // fails, multicast discovery still works (we just lose the
+            // unicast-domain split), so don't fail the whole bind.
+            match self
+                .dispatch
+                .bind_discovery_unicast(
+                    self.interface,
+                    self.e2e_registry.clone(),
+                    self.socket_settings,
+                )
+                .await
+            {
+                Ok(unicast) => self.discovery_unicast_socket = Some(unicast),
+                Err(e) => error!("Failed to bind unicast discovery socket: {:?}", e),
+            }
+            Ok(())
+        }
+    }
+
+    // Dropping the receiver kills the loop
+    async fn unbind_discovery(&mut self) {
+        debug!("Unbinding Discovery socket.");
+        if let Some(socket) = self.discovery_socket.take() {
+            self.sd_session_id = socket.session_id();
+            self.sd_session_has_wrapped =
+                socket.reboot_flag() == crate::protocol::sd::RebootFlag::Continuous;
+            socket.shut_down().await;
+        }
+        if let Some(socket) = self.discovery_unicast_socket.take() {
+            socket.shut_down().await;
+        }
+    }
+
+    fn set_interface(&mut self, interface: Ipv4Addr) {
+        self.interface = interface;
+    }
+
+    async fn bind_unicast(&mut self, port: u16) -> Result<u16, Error> {
+        if port != 0
+            && let Some(socket) = self.unicast_sockets.get(&port)
+        {
+            return Ok(socket.port());
+        }
+        // Check capacity before asking the OS for a port so we don't
+        // bind-then-drop a socket we can't track.
+        if self.unicast_sockets.len() >= UNICAST_SOCKETS_CAP {
+            warn!(
+                "unicast_sockets at capacity ({}); refusing new bind of port {}",
+                UNICAST_SOCKETS_CAP, port
+            );
+            return Err(Error::Capacity("unicast_sockets"));
+        }
+        let unicast_socket = match (port, self.unicast_ports) {
+            (0, Some((first, last))) => self.bind_unicast_in(first, last).await?,
+            _ => {
+                self.dispatch
+                    .bind_unicast(port, self.e2e_registry.clone(), self.socket_settings)
+                    .await?
+            }
+        };
+        let bound_port = unicast_socket.port();
+        // Capacity was checked above, so insert cannot report "full" here.
+        // A defensive check guards against a future refactor that changes
+        // the ordering.
+        if self
+            .unicast_sockets
+            .insert(bound_port, unicast_socket)
+            .is_err()
+        {
+            error!(
+                "unicast_sockets insert failed after capacity check passed — invariant violation"
+            );
+            return Err(Error::Capacity("unicast_sockets"));
+        }
+        debug!("Bound unicast socket on port {}", bound_port);
+        Ok(bound_port)
+    }
+
+    /// Bind a unicast socket on the first port of `first..=last` that
+    /// is neither bound by this client nor in use on the host.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"unicast_ports"`) if every
+    /// port of the range is taken, and the bind error of any other
+    /// failure.
+    async fn bind_unicast_in(
+        &mut self,
+        first: u16,
+        last: u16,
+    ) -> Result<SocketManager<PayloadDefinitions, C>, Error> {
+        for port in first.max(1)..=last {
+            if self.unicast_sockets.contains_key(&port) {
+                continue;
+            }
+            match self
+                .dispatch
+                .bind_unicast(port, self.e2e_registry.clone(), self.socket_settings)
+                .await
+            {
+                Err(Error::Transport(crate::transport::TransportError::AddressInUse)) => {}
+                result => return result,
+            }
+        }
+        Err(Error::Capacity("unicast_ports"))
+    }
+
+    /// Send `message` over the TCP connection to `peer`, connecting first
+    /// if there is none. A connection whose loop has already ended (the
+    /// peer closed it) is reopened once. A connect that takes longer than
+    /// `tcp_connect_timeout` fails with [`Error::Timeout`] so one
+    /// unreachable peer cannot hold up the run loop.
+    async fn send_over_tcp(
+        &mut self,
+        peer: SocketAddrV4,
+        message: Message<PayloadDefinitions>,
+    ) -> Result<(), Error> {
+        if let Some(connection) = self.tcp_connections.get_mut(&peer) {
+            match connection.send(peer, message.clone()).await {
+                Err(Error::SocketClosedUnexpectedly) => {
+                    info!("TCP connection to {} closed; reconnecting", peer);
+                    self.tcp_connections.remove(&peer);
+                    self.fail_tcp_in_flight(peer);
+                }
+                result => {
+                    self.touch_tcp_connection(peer);
+                    return result;
+                }
+            }
+        }
+        if self.tcp_connections.len() >= TCP_CONNECTIONS_CAP {
+            warn!(
+                "tcp_connections at capacity ({}); refusing connection to {}",
+                TCP_CONNECTIONS_CAP, peer
+            );
+            return Err(Error::Capacity("tcp_connections"));
+        }
+        let mut connection = {
+            let connect_fut = self
+                .dispatch
+                .connect(peer, self.e2e_registry.clone(), self.socket_settings)
+                .fuse();
+            let timeout_fut = self.timer.sleep(self.tcp_connect_timeout).fuse();
+            pin_mut!(connect_fut, timeout_fut);
+            select_biased! {
+                connection = connect_fut => connection?,
+                () = timeout_fut => {
+                    warn!("TCP connection to {} timed out", peer);
+                    return Err(Error::Timeout);
+                }
+            }
+        };
+        debug!("Connected to {} over TCP", peer);
+        let sent = connection.send(peer, message).await;
+        // Capacity was checked above.
+        let _ = self.tcp_connections.insert(peer, connection);
+        self.touch_tcp_connection(peer);
+        sent
+    }
+
+    /// Note traffic on the TCP connection to `peer` for the janitor's
+    /// idle check, forgetting connections that have since closed.
+    fn touch_tcp_connection(&mut self, peer: SocketAddrV4) {
+        let Some(now) = self.timer.now() else {
+            return;
+        };
+        let connections = &self.tcp_connections;
+        self.tcp_last_active
+            .retain(|peer, _| connections.contains_key(peer));
+        // Cannot fail: every entry left is an open connection.
+        let _ = self.tcp_last_active.insert(peer, now);
+    }
+
+    /// Fail the requests still awaiting a reply over the closed TCP
+    /// connection to `peer`.
+    fn fail_tcp_in_flight(&mut self, peer: SocketAddrV4) {
+        for request in self.tcp_in_flight.take(peer) {
+            if let Some(response) = self.pending_responses.remove(&request) {
+                let _ = response.send(Err(Error::SocketClosedUnexpectedly));
+            }
+        }
+    }
+
+    /// [`Self::fail_tcp_in_flight`] for every connection the receive
+    /// path has evicted since the last pass.
+    fn fail_closed_tcp_requests(&mut self) {
+        let closed: heapless::Vec<SocketAddrV4, TCP_CONNECTIONS_CAP> = self
+            .tcp_in_flight
+            .peers()
+            .filter(|peer| !self.tcp_connections.contains_key(peer))
+            .collect();
+        for peer in closed {
+            debug!("TCP connection to {} closed with requests in flight", peer);
+            self.fail_tcp_in_flight(peer);
+        }
+    }
+
+    /// Stamp `message` with the next request ID and send it to `key`'s
+    /// endpoint, binding a unicast socket or opening a TCP connection as
+    /// needed. Returns the request it went out as.
+    async fn send_request(
+        &mut self,
+        key: ServiceEndpointKey,
+        mut message: Message<PayloadDefinitions>,
+    ) -> Result<RequestKey, Error> {
+        let Some(endpoint_info) = self.service_registry.get(key) else {
+            return Err(Error::ServiceNotFound);
+        };
+        let desired_port = endpoint_info.local_port;
+        // The send target is the key's endpoint; today's
+        // transports are IPv4 only.
+        let SocketAddr::V4(target) = key.endpoint.addr else {
+            return Err(Error::UnsupportedEndpoint(key.endpoint));
+        };
+
+        // Stamp request ID with the peer's CURRENT session counter,
+        // but only advance it on successful send. A failed
+        // send should not chew through the 16-bit session
+        // space — under transient transport failure that
+        // could wrap toward in-flight pending_responses
+        // far faster than expected. Fire-and-forget messages to
+        // session-less services get session ID 0 and leave the
+        // counter alone; requests keep counting, since their
+        // replies are told apart by request ID.
+        let peer = *target.ip();
+        let expects_response =
+            message.header().message_type().message_type() == protocol::MessageType::Request;
+        let sessionless = !expects_response && self.sessionless_services.contains(key.service_id);
+        let session_id = if sessionless {
+            0
+        } else {
+            self.request_sessions.current(IpAddr::V4(peer))
+        };
+        let request_id = (u32::from(self.client_id) << 16) | u32::from(session_id);
+        message.set_request_id(request_id);
+        let request = RequestKey::new(peer, message.header().message_id(), request_id);
+
+        let over_tcp = key.endpoint.protocol == crate::TransportProtocol::Tcp;
+        let send_result = if over_tcp {
+            if expects_response && self.tcp_in_flight.is_full(target) {
+                warn!(
+                    "tcp_in_flight at capacity ({}) for {}; refusing request",
+                    TCP_IN_FLIGHT_CAP, target
+                );
+                Err(Error::Capacity("tcp_in_flight"))
+            } else {
+                self.send_over_tcp(target, message).await
+            }
+        } else {
+            let source_port = self
+                .unicast_port_for(desired_port, key.service_id, None)
+                .await?;
+            let socket = self.unicast_sockets.get_mut(&source_port).unwrap();
+            socket.send(target, message).await
+        };
+        send_result?;
+        self.peers.record_sent(SocketAddr::V4(target));
+        if !sessionless {
+            self.request_sessions.advance(IpAddr::V4(peer));
+        }
+        if over_tcp && expects_response {
+            self.tcp_in_flight.record(target, request);
+        }
+        Ok(request)
+    }
+
+    /// The provider of `service_id`/`instance_id` to send to: the one
+    /// remembered from an earlier send while it is still offered, else a
+    /// fresh pick by the service's [`ProviderSelection`]. Round-robin
+    /// picks are not remembered, so they keep rotating.
+    fn resolve_endpoint(
+        &mut self,
+        service_id: u16,
+        instance_id: u16,
+    ) -> Option<ServiceEndpointKey> {
+        if let Some(key) = self.service_registry.resolved(service_id, instance_id) {
+            return Some(key);
+        }
+        let key = self.provider_selections.select(
+            &self.service_registry,
+            service_id,
+            instance_id,
+            service_wait::draw(self.timer.now(), service_id, instance_id),
+        )?;
+        if self.provider_selections.selection(service_id) != ProviderSelection::RoundRobin {
+            self.service_registry
+                .remember_resolved(service_id, instance_id, key);
+        }
+        Some(key)
+    }
+
+    /// Unicast port to talk to an endpoint of `service_id` registered
+    /// with `desired_port` from, for `event_group_id` if subscribing:
+    /// that port, bound if needed, or for `0` the service's receive port
+    /// if one is set, else the first shared socket, binding an ephemeral
+    /// one if there is none.
+    async fn unicast_port_for(
+        &mut self,
+        desired_port: u16,
+        service_id: u16,
+        event_group_id: Option<u16>,
+    ) -> Result<u16, Error> {
+        if desired_port != 0 {
+            return self.bind_unicast(desired_port).await;
+        }
+        if let Some(port) = self.receive_port(service_id, event_group_id).await? {
+            return Ok(port);
+        }
+        let receive_ports = &self.receive_ports;
+        if let Some(port) = self
+            .unicast_sockets
+            .keys()
+            .find(|port| !receive_ports.reserves(**port))
+        {
+            return Ok(*port);
+        }
+        let port = self.bind_unicast(0).await?;
+        debug!("Auto-bound unicast on port {}", port);
+        Ok(port)
+    }
+
+    /// The receive port set for `event_group_id` of `service_id` (`None`
+    /// for requests), bound if needed, or `None` if there is none.
+    async fn receive_port(
+        &mut self,
+        service_id: u16,
+        event_group_id: Option<u16>,
+    ) -> Result<Option<u16>, Error> {
+        let Some(index) = self.receive_ports.find(service_id, event_group_id) else {
+            return Ok(None);
+        };
+        let port = self.bind_unicast(self.receive_ports.port(index)).await?;
+        self.receive_ports.bound(index, port);
+        Ok(Some(port))
+    }
+
+    /// Port a `SubscribeEventgroup` asked to go out from `client_port`
+    /// announces: that port, bound if needed, or for `0` the eventgroup's
+    /// receive port if one is set, else a fresh ephemeral one.
+    async fn subscriber_port(
+        &mut self,
+        client_port: u16,
+        service_id: u16,
+        event_group_id: u16,
+    ) -> Result<u16, Error> {
+        if client_port == 0
+            && let Some(port) = self.receive_port(service_id, Some(event_group_id)).await?
+        {
+            return Ok(port);
+        }
+        self.bind_unicast(client_port).await
+    }
+
+    /// Send a `SubscribeEventgroup` entry (a stop with `ttl` 0) for
+    /// `event_group_id` of the service at `key` to `provider`, announcing
+    /// `unicast_port` as the subscriber endpoint. Needs the discovery
+    /// socket bound.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_subscribe_entry(
+        &mut self,
+        key: ServiceEndpointKey,
+        provider: SocketAddrV4,
+        instance_id: u16,
+        major_version: u8,
+        ttl: u32,
+        event_group_id: u16,
+        unicast_port: u16,
+    ) -> Result<(), Error> {
+        self.send_subscribe_entries(
+            key,
+            provider,
+            instance_id,
+            major_version,
+            ttl,
+            &[event_group_id],
+            unicast_port,
+        )
+        .await
+    }
+
+    /// [`Self::send_subscribe_entry`] for several eventgroups, packing as
+    /// many entries into one message as
+    /// [`PayloadWireFormat::push_subscription_entry`] takes.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_subscribe_entries(
+        &mut self,
+        key: ServiceEndpointKey,
+        provider: SocketAddrV4,
+        instance_id: u16,
+        major_version: u8,
+        ttl: u32,
+        event_group_ids: &[u16],
+        unicast_port: u16,
+    ) -> Result<(), Error> {
+        let target = SocketAddrV4::new(*provider.ip(), self.socket_settings.sd_port);
+        let Some(discovery_socket) = self.discovery_socket.as_mut() else {
+            return Err(Error::SocketClosedUnexpectedly);
+        };
+        let mut event_group_ids = event_group_ids.iter().copied().peekable();
+        while let Some(event_group_id) = event_group_ids.next() {
+            let mut sd_header = PayloadDefinitions::new_subscription_sd_header(
+                key.service_id,
+                instance_id,
+                major_version,
+                ttl,
+                event_group_id,
+                self.interface,
+                crate::protocol::sd::TransportProtocol::Udp,
+                unicast_port,
+                discovery_socket.reboot_flag(),
+            );
+            while let Some(&next) = event_group_ids.peek() {
+                if !PayloadDefinitions::push_subscription_entry(
+                    &mut sd_header,
+                    key.service_id,
+                    instance_id,
+                    major_version,
+                    ttl,
+                    next,
+                ) {
+                    break;
+                }
+                event_group_ids.next();
+            }
+            let session_id = u32::from(discovery_socket.session_id());
+            let message = Message::<PayloadDefinitions>::new_sd(session_id, &sd_header);
+            debug!("Sending Subscribe {:?} to {}", &message, target);
+            discovery_socket.send(target, message).await?;
+            self.peers.record_sent(SocketAddr::V4(target));
+        }
+        Ok(())
+    }
+
+    /// [`Self::send_subscribe_entry`] for a tracked subscription: the
+    /// instance and major version come from the registry, and the
+    /// discovery socket is bound first if needed.
+    async fn send_tracked_subscribe(
+        &mut self,
+        key: ServiceEndpointKey,
+        ttl: u32,
+        event_group_id: u16,
+        client_port: u16,
+    ) -> Result<(), Error> {
+        let Some(info) = self.service_registry.get(key) else {
+            return Err(Error::ServiceNotFound);
+        };
+        let (instance_id, major_version) = (info.instance_id, info.major_version);
+        let SocketAddr::V4(provider) = key.endpoint.addr else {
+            return Err(Error::UnsupportedEndpoint(key.endpoint));
+        };
+        if self.discovery_socket.is_none() {
+            self.bind_discovery().await?;
+        }
+        self.send_subscribe_entry(
+            key,
+            provider,
+            instance_id,
+            major_version,
+            ttl,
+            event_group_id,
+            client_port,
+        )
+        .await
     }
 
-    async fn bind_discovery(&mut self) -> Result<(), Error> {
-        if self.discovery_socket.is_some() {
-            Ok(())
+    /// Subscribe to `event_group_id` of `service_id`/`instance_id` and
+    /// track the subscription; `response` is answered by its ack.
+    async fn subscribe_eventgroup(
+        &mut self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        ttl: u32,
+        response: C::OneshotSender<Result<(), Error>>,
+    ) {
+        let Some(key) = self.service_registry.find(|key, info| {
+            key.service_id == service_id
+                && info.instance_id == instance_id
+                && key.endpoint.protocol == crate::TransportProtocol::Udp
+        }) else {
+            let _ = response.send(Err(Error::ServiceNotFound));
+            return;
+        };
+        let existing = self.tracked_subscription(key, event_group_id);
+        if existing.is_none() && self.subscriptions.is_full() {
+            warn!(
+                "subscriptions at capacity ({}); refusing subscription to 0x{:04X}",
+                SUBSCRIPTIONS_CAP, service_id
+            );
+            let _ = response.send(Err(Error::Capacity("subscriptions")));
+            return;
+        }
+        let client_port = if let Some(index) = existing {
+            self.subscriptions[index].client_port
         } else {
-            let socket = self
-                .dispatch
-                .bind_discovery(
-                    self.interface,
-                    self.e2e_registry.clone(),
-                    self.sd_session_id,
-                    self.sd_session_has_wrapped,
-                    self.multicast_loopback,
-                )
-                .await?;
-            self.discovery_socket = Some(socket);
-            // Receive-only unicast SD socket bound to the interface IP — see
-            // `discovery_unicast_socket`. Best-effort: if the unicast bind
-            // fails, multicast discovery still works (we just lose the
-            // unicast-domain split), so don't fail the whole bind.
+            let desired_port = self
+                .service_registry
+                .get(key)
+                .map_or(0, |info| info.local_port);
             match self
-                .dispatch
-                .bind_discovery_unicast(self.interface, self.e2e_registry.clone())
+                .unicast_port_for(desired_port, service_id, Some(event_group_id))
                 .await
             {
-                Ok(unicast) => self.discovery_unicast_socket = Some(unicast),
-                Err(e) => error!("Failed to bind unicast discovery socket: {:?}", e),
+                Ok(port) => port,
+                Err(e) => {
+                    let _ = response.send(Err(e));
+                    return;
+                }
             }
-            Ok(())
+        };
+        if let Err(e) = self
+            .send_tracked_subscribe(key, ttl, event_group_id, client_port)
+            .await
+        {
+            let _ = response.send(Err(e));
+            return;
         }
+        self.track_subscription(
+            key,
+            instance_id,
+            event_group_id,
+            ttl,
+            client_port,
+            0,
+            response,
+        );
     }
 
-    // Dropping the receiver kills the loop
-    async fn unbind_discovery(&mut self) {
-        debug!("Unbinding Discovery socket.");
-        if let Some(socket) = self.discovery_socket.take() {
-            self.sd_session_id = socket.session_id();
-            self.sd_session_has_wrapped =
-                socket.reboot_flag() == crate::protocol::sd::RebootFlag::Continuous;
-            socket.shut_down().await;
+    /// Send tracked subscriptions to `event_group_ids` of the service at
+    /// `key`, packed into as few messages as the payload allows; each is
+    /// answered on its entry of `acks`. Nothing is sent unless all of
+    /// them can be tracked.
+    #[allow(clippy::too_many_arguments)]
+    async fn subscribe_many(
+        &mut self,
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_ids: &[u16],
+        client_port: u16,
+        acks: heapless::Vec<C::OneshotSender<Result<(), Error>>, SUBSCRIBE_MANY_CAP>,
+        id: u32,
+    ) -> Result<(), Error> {
+        let untracked = event_group_ids
+            .iter()
+            .filter(|&&event_group_id| self.tracked_subscription(key, event_group_id).is_none())
+            .count();
+        if untracked > SUBSCRIPTIONS_CAP - self.subscriptions.len() {
+            return Err(Error::Capacity("subscriptions"));
         }
-        if let Some(socket) = self.discovery_unicast_socket.take() {
-            socket.shut_down().await;
+        let Some(info) = self.service_registry.get(key) else {
+            return Err(Error::ServiceNotFound);
+        };
+        let instance_id = info.instance_id;
+        // Resolved like a single `Subscribe`.
+        let major_version = if major_version == crate::protocol::sd::ANY_MAJOR_VERSION {
+            info.major_version
+        } else {
+            major_version
+        };
+        let (SocketAddr::V4(provider), crate::TransportProtocol::Udp) =
+            (key.endpoint.addr, key.endpoint.protocol)
+        else {
+            return Err(Error::UnsupportedEndpoint(key.endpoint));
+        };
+        // Eventgroups with a receive port of their own announce it; the
+        // rest share `client_port`, bound once.
+        let mut ports = heapless::Vec::<u16, SUBSCRIBE_MANY_CAP>::new();
+        let mut shared_port = None;
+        for &event_group_id in event_group_ids {
+            let dedicated = if client_port == 0 {
+                self.receive_port(key.service_id, Some(event_group_id))
+                    .await?
+            } else {
+                None
+            };
+            let port = match (dedicated, shared_port) {
+                (Some(port), _) | (None, Some(port)) => port,
+                (None, None) => {
+                    let port = self.bind_unicast(client_port).await?;
+                    shared_port = Some(port);
+                    port
+                }
+            };
+            // Cannot fail: one per eventgroup id.
+            let _ = ports.push(port);
+        }
+        if self.discovery_socket.is_none() {
+            self.bind_discovery().await?;
+        }
+        // One run of messages per announced port, in first-use order.
+        for (index, &port) in ports.iter().enumerate() {
+            if ports[..index].contains(&port) {
+                continue;
+            }
+            let group: heapless::Vec<u16, SUBSCRIBE_MANY_CAP> = event_group_ids
+                .iter()
+                .zip(&ports)
+                .filter(|&(_, &other)| other == port)
+                .map(|(&event_group_id, _)| event_group_id)
+                .collect();
+            self.send_subscribe_entries(
+                key,
+                provider,
+                instance_id,
+                major_version,
+                ttl,
+                &group,
+                port,
+            )
+            .await?;
+        }
+        for ((&event_group_id, &port), ack) in event_group_ids.iter().zip(&ports).zip(acks) {
+            self.track_subscription(key, instance_id, event_group_id, ttl, port, id, ack);
         }
+        Ok(())
     }
 
-    fn set_interface(&mut self, interface: Ipv4Addr) {
-        self.interface = interface;
+    /// Index of the tracked subscription to `event_group_id` of the
+    /// service at `key`.
+    fn tracked_subscription(&self, key: ServiceEndpointKey, event_group_id: u16) -> Option<usize> {
+        self.subscriptions.iter().position(|subscription| {
+            subscription.key == key && subscription.event_group_id == event_group_id
+        })
     }
 
-    async fn bind_unicast(&mut self, port: u16) -> Result<u16, Error> {
-        if port != 0
-            && let Some(socket) = self.unicast_sockets.get(&port)
-        {
-            return Ok(socket.port());
+    /// Track a subscription just sent under `id`, awaiting its ack on
+    /// `waiter`. It replaces one to the same eventgroup, whose waiter
+    /// gets [`Error::SubscriptionReplaced`]; otherwise the caller checked
+    /// for room.
+    #[allow(clippy::too_many_arguments)]
+    fn track_subscription(
+        &mut self,
+        key: ServiceEndpointKey,
+        instance_id: u16,
+        event_group_id: u16,
+        ttl: u32,
+        client_port: u16,
+        id: u32,
+        waiter: C::OneshotSender<Result<(), Error>>,
+    ) {
+        let major_version = self
+            .service_registry
+            .get(key)
+            .map_or(protocol::sd::ANY_MAJOR_VERSION, |info| info.major_version);
+        let subscription = Subscription {
+            id,
+            key,
+            instance_id,
+            major_version,
+            event_group_id,
+            ttl,
+            client_port,
+            waiter: Some(waiter),
+            due: self
+                .timer
+                .now()
+                .map(|now| now.saturating_add(SUBSCRIBE_ACK_TIMEOUT)),
+            offer_lost: false,
+        };
+        if let Some(index) = self.tracked_subscription(key, event_group_id) {
+            let replaced = core::mem::replace(&mut self.subscriptions[index], subscription);
+            if let Some(waiter) = replaced.waiter {
+                let _ = waiter.send(Err(Error::SubscriptionReplaced));
+            }
+        } else {
+            // Cannot fail: the caller checked for room.
+            let _ = self.subscriptions.push(subscription);
         }
-        // Check capacity before asking the OS for a port so we don't
-        // bind-then-drop a socket we can't track.
-        if self.unicast_sockets.len() >= UNICAST_SOCKETS_CAP {
-            warn!(
-                "unicast_sockets at capacity ({}); refusing new bind of port {}",
-                UNICAST_SOCKETS_CAP, port
-            );
-            return Err(Error::Capacity("unicast_sockets"));
+    }
+
+    /// Stop tracking the subscriptions matching `matches` and send the
+    /// `StopSubscribeEventgroup` for them.
+    async fn stop_tracked_subscription(
+        &mut self,
+        matches: impl FnMut(&Subscription<C::OneshotSender<Result<(), Error>>>) -> bool,
+    ) -> Result<(), Error> {
+        let Some(subscription) = self.forget_subscriptions(matches) else {
+            return Err(Error::NotSubscribed);
+        };
+        self.send_tracked_subscribe(
+            subscription.key,
+            0,
+            subscription.event_group_id,
+            subscription.client_port,
+        )
+        .await
+    }
+
+    /// Stop refreshing the subscriptions whose provider's offer went
+    /// away, and subscribe again once it is back, possibly at another
+    /// endpoint, reporting each as `ClientUpdate::Resubscribed`. The next `refresh_subscriptions`
+    /// sends them.
+    fn resume_subscriptions(&mut self) {
+        if self.subscriptions.is_empty() {
+            return;
         }
-        let unicast_socket = self
-            .dispatch
-            .bind_unicast(port, self.e2e_registry.clone())
-            .await?;
-        let bound_port = unicast_socket.port();
-        // Capacity was checked above, so insert cannot report "full" here.
-        // A defensive check guards against a future refactor that changes
-        // the ordering.
-        if self
-            .unicast_sockets
-            .insert(bound_port, unicast_socket)
-            .is_err()
-        {
-            error!(
-                "unicast_sockets insert failed after capacity check passed — invariant violation"
+        let update_sender = &self.update_sender;
+        follow_offers(
+            &mut self.subscriptions,
+            &self.service_registry,
+            self.timer.now(),
+            |subscription| {
+                debug!(
+                    "Offer of 0x{:04X} back at {:?}; subscribing to eventgroup 0x{:04X} again",
+                    subscription.key.service_id,
+                    subscription.key.endpoint,
+                    subscription.event_group_id
+                );
+                let _ = update_sender.send_now(ClientUpdate::Resubscribed {
+                    key: subscription.key,
+                    event_group_id: subscription.event_group_id,
+                });
+            },
+        );
+    }
+
+    /// Fail tracked subscriptions whose ack is overdue, and refresh the
+    /// acknowledged ones halfway through their lifetime.
+    async fn refresh_subscriptions(&mut self) {
+        if self.subscriptions.is_empty() {
+            return;
+        }
+        let Some(now) = self.timer.now() else {
+            return;
+        };
+        let mut index = 0;
+        while index < self.subscriptions.len() {
+            let subscription = &mut self.subscriptions[index];
+            if subscription.due.is_none_or(|due| now < due) {
+                index += 1;
+                continue;
+            }
+            if let Some(waiter) = subscription.waiter.take() {
+                debug!(
+                    "No ack for eventgroup 0x{:04X} of service 0x{:04X}",
+                    subscription.event_group_id, subscription.key.service_id
+                );
+                let _ = waiter.send(Err(Error::Timeout));
+                self.subscriptions.swap_remove(index);
+                continue;
+            }
+            subscription.due = subscription.next_refresh(Some(now));
+            let (key, ttl, event_group_id, client_port) = (
+                subscription.key,
+                subscription.ttl,
+                subscription.event_group_id,
+                subscription.client_port,
             );
-            return Err(Error::Capacity("unicast_sockets"));
+            index += 1;
+            if let Err(e) = self
+                .send_tracked_subscribe(key, ttl, event_group_id, client_port)
+                .await
+            {
+                debug!(
+                    "Refreshing eventgroup 0x{:04X} of service 0x{:04X} failed: {:?}",
+                    event_group_id, key.service_id, e
+                );
+            }
+        }
+    }
+
+    /// Stop tracking the subscriptions matching `matches`. A caller still
+    /// waiting for an ack gets [`Error::NotSubscribed`]. Returns the last
+    /// one removed.
+    fn forget_subscriptions(
+        &mut self,
+        mut matches: impl FnMut(&Subscription<C::OneshotSender<Result<(), Error>>>) -> bool,
+    ) -> Option<Subscription<C::OneshotSender<Result<(), Error>>>> {
+        let mut forgotten = None;
+        while let Some(index) = self.subscriptions.iter().position(&mut matches) {
+            let mut subscription = self.subscriptions.swap_remove(index);
+            if let Some(waiter) = subscription.waiter.take() {
+                let _ = waiter.send(Err(Error::NotSubscribed));
+            }
+            forgotten = Some(subscription);
+        }
+        forgotten
+    }
+
+    /// The offers, subscriptions and pending requests for
+    /// `Client::state_snapshot`.
+    fn state_snapshot(&self) -> ClientSnapshot {
+        let now = self.timer.now();
+        let mut offers = heapless::Vec::new();
+        for (key, info) in self.service_registry.iter() {
+            // Never fails: the snapshot holds as many as the registry.
+            let _ = offers.push(DiscoveredOffer {
+                key: *key,
+                instance_id: info.instance_id,
+                major_version: info.major_version,
+                minor_version: info.minor_version,
+                ttl_remaining: now.and_then(|now| info.remaining(now)),
+                is_static: info.is_static,
+                degraded: info.probe.is_degraded(),
+            });
+        }
+        let subscriptions = self
+            .subscriptions
+            .iter()
+            .map(|subscription| TrackedSubscription {
+                key: subscription.key,
+                instance_id: subscription.instance_id,
+                event_group_id: subscription.event_group_id,
+                ttl: subscription.ttl,
+                client_port: subscription.client_port,
+                state: subscription.state(),
+            })
+            .collect();
+        let pending_requests = self
+            .pending_responses
+            .keys()
+            .map(|request| {
+                let message_id = protocol::MessageId::new(request.message_id);
+                PendingRequest {
+                    peer: request.peer,
+                    service_id: message_id.service_id(),
+                    method_id: message_id.method_id(),
+                    // The low half of the request ID; the high half is
+                    // the client ID.
+                    session_id: (request.request_id & 0xFFFF) as u16,
+                }
+            })
+            .collect();
+        ClientSnapshot {
+            interface: self.interface,
+            offers,
+            subscriptions,
+            pending_requests,
+        }
+    }
+
+    /// Start the clock of a request sent under `policy`. Without a clock
+    /// the request never times out.
+    fn track_timed_request(
+        &mut self,
+        key: ServiceEndpointKey,
+        request: RequestKey,
+        policy: RequestPolicy,
+        message: Option<Resend<PayloadDefinitions>>,
+    ) {
+        let Some(now) = self.timer.now() else {
+            return;
+        };
+        // Cannot fail: the caller checked for room before sending.
+        let _ = self.timed_requests.push(TimedRequest {
+            key,
+            request,
+            policy,
+            message,
+            due: now.saturating_add(policy.timeout),
+            response: None,
+        });
+    }
+
+    /// Resolve requests whose reply is overdue to [`Error::Timeout`], or
+    /// resend them while their policy has retries left.
+    fn expire_timed_requests(&mut self) {
+        if self.timed_requests.is_empty() {
+            return;
+        }
+        let Some(now) = self.timer.now() else {
+            return;
+        };
+        let mut index = 0;
+        while index < self.timed_requests.len() {
+            let timed = &mut self.timed_requests[index];
+            if timed.response.is_none() && !self.pending_responses.contains_key(&timed.request) {
+                // Answered, or its tracking was rejected.
+                self.timed_requests.swap_remove(index);
+                continue;
+            }
+            let verdict = timed.verdict(now);
+            if verdict == Verdict::Wait {
+                index += 1;
+                continue;
+            }
+            let response = if let Some(response) = timed.response.take() {
+                response
+            } else {
+                if let SocketAddr::V4(peer) = timed.key.endpoint.addr {
+                    self.tcp_in_flight.answered(peer, timed.request);
+                }
+                let Some(response) = self.pending_responses.remove(&timed.request) else {
+                    self.timed_requests.swap_remove(index);
+                    continue;
+                };
+                response
+            };
+            match verdict {
+                Verdict::BackOff => {
+                    timed.response = Some(response);
+                    timed.due = now.saturating_add(timed.policy.backoff);
+                    index += 1;
+                }
+                Verdict::Resend => {
+                    let timed = self.timed_requests.swap_remove(index);
+                    let Some(message) = timed.message.map(into_resend) else {
+                        let _ = response.send(Err(Error::Timeout));
+                        continue;
+                    };
+                    debug!(
+                        "Resending request 0x{:08X} to service 0x{:04X}",
+                        timed.request.request_id, timed.key.service_id
+                    );
+                    // Queued like a fresh request, so the send path lives in
+                    // one place. Nobody awaits the send completion: failures
+                    // reach the caller through `response`.
+                    let (send_complete, _) = C::oneshot();
+                    let resend = ControlMessage::SendToService {
+                        key: timed.key,
+                        message,
+                        send_complete,
+                        response,
+                        deadline: None,
+                        policy: Some(timed.policy.next_attempt()),
+                    };
+                    if let Err(rejected) = self.request_queue.push_back(resend) {
+                        warn!(
+                            "request_queue at capacity ({}); dropping resend",
+                            REQUEST_QUEUE_CAP
+                        );
+                        rejected.reject_with_capacity("request_queue");
+                    }
+                }
+                Verdict::GiveUp | Verdict::Wait => {
+                    debug!(
+                        "Request 0x{:08X} to service 0x{:04X} timed out",
+                        timed.request.request_id, timed.key.service_id
+                    );
+                    let _ = response.send(Err(Error::Timeout));
+                    self.timed_requests.swap_remove(index);
+                }
+            }
         }
-        debug!("Bound unicast socket on port {}", bound_port);
-        Ok(bound_port)
     }
 
-    /// Tracks the caller's response channel against `request_id` so a
+    /// Tracks the caller's response channel against `request` so a
     /// future unicast reply can be routed back. If the
     /// `pending_responses` map is already at `PENDING_RESPONSES_CAP`, the
     /// `response` sender is recovered from the failed `insert` and used
     /// to deliver `Err(Error::Capacity("pending_responses"))` — the
     /// caller's `PendingResponse::response().await` resolves cleanly
     /// instead of panicking on the `RecvError` that dropping the Sender
-    /// would have produced. If `request_id` is reused while an older
-    /// pending entry still exists (e.g. after a `session_counter`
+    /// would have produced. If a request ID is reused for the same
+    /// message while an older
+    /// pending entry still exists (e.g. after a session counter
     /// wrap-around), the displaced sender is likewise completed with
     /// `Err(Error::Capacity("pending_responses"))` rather than being
     /// silently dropped — the caller awaiting the previous request
     /// sees a clean error instead of a `RecvError` panic. Any reply
-    /// that later arrives for a dropped `request_id` is surfaced on
+    /// that later arrives for a dropped request is surfaced on
     /// the update stream via `ClientUpdate::Unicast` instead of
     /// matching a pending entry.
     fn track_or_reject_pending_response(
         &mut self,
-        request_id: u32,
+        request: RequestKey,
         response: C::OneshotSender<Result<PayloadDefinitions, Error>>,
     ) {
-        match self.pending_responses.insert(request_id, response) {
+        match self.pending_responses.insert(request, response) {
             Ok(None) => {}
             Ok(Some(displaced_response)) => {
-                // `request_id` reuse is expected once `session_counter`
-                // wraps every ~65k requests on a long-lived client, and
+                // `request_id` reuse is expected once a peer's session
+                // counter wraps every ~65k requests on a long-lived client, and
                 // legitimate when the previous request is still pending.
                 // The displaced sender carries `Error::Capacity` to its
                 // awaiter; logging at `warn!` per wrap floods ops dashboards
                 // for a routine event, so demote to `debug!`.
                 debug!(
                     "pending_responses already contained request_id \
-                     0x{:08X}; replacing existing pending response",
-                    request_id
+                     0x{:08X} for message 0x{:08X}; replacing existing pending response",
+                    request.request_id, request.message_id
                 );
                 let _ = displaced_response.send(Err(Error::Capacity("pending_responses")));
             }
@@ -556,7 +2538,7 @@ where
                 warn!(
                     "pending_responses at capacity ({}); response tracking \
                      dropped for request_id 0x{:08X}",
-                    PENDING_RESPONSES_CAP, request_id
+                    PENDING_RESPONSES_CAP, request.request_id
                 );
                 let _ = response.send(Err(Error::Capacity("pending_responses")));
             }
@@ -597,24 +2579,28 @@ where
 
     /// Process one received SD datagram: feed every service-instance entry to
     /// the reboot [`SessionTracker`] under `transport`, refresh the service
-    /// registry, and emit `SenderRebooted` / `DiscoveryUpdated`. Shared by the
+    /// registry, and emit `SenderRebooted` / `DiscoveryUpdated`. Returns
+    /// `true` if the sender rebooted. Shared by the
     /// multicast and unicast discovery receive arms so each transport's SD
     /// session counter is tracked on its own key — without this split the
     /// sensor's interleaved multicast/unicast session counters look like
     /// perpetual reboots.
     ///
     /// [`SessionTracker`]: super::session::SessionTracker
-    #[allow(clippy::too_many_arguments)]
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
     fn handle_discovery_datagram(
         source: SocketAddr,
         transport: TransportKind,
         someip_header: protocol::Header,
         sd_header: <PayloadDefinitions as PayloadWireFormat>::SdHeader,
+        now: Option<core::time::Duration>,
         session_tracker: &mut SessionTracker,
         service_registry: &mut ServiceRegistry,
+        version_policies: &VersionPolicies,
+        subscriptions: &mut Subscriptions<C::OneshotSender<Result<(), Error>>>,
         e2e_registry: &R,
         update_sender: &C::UnboundedSender<ClientUpdate<PayloadDefinitions>>,
-    ) {
+    ) -> bool {
         // Extract session ID from SOME/IP request_id (lower 16 bits)
         let session_id = (someip_header.request_id() & 0xFFFF) as u16;
         let sd_payload = PayloadDefinitions::new_sd_payload(&sd_header);
@@ -655,31 +2641,75 @@ where
                 service_id: ep.service_id,
                 endpoint,
             };
-            if ep.is_offer {
-                if service_registry
-                    .insert(
-                        key,
-                        ServiceEndpointInfo {
+            if service_registry.get(key).is_some_and(|info| info.is_static) {
+                trace!(
+                    "Registry: 0x{:04X} at {:?} is static, SD entry ignored",
+                    ep.service_id, endpoint,
+                );
+                return;
+            }
+            if ep.is_offer
+                && !version_policies.accepts(ep.service_id, ep.major_version, ep.minor_version)
+            {
+                // A provider that moved to a version we no longer accept
+                // must not keep serving us from a stale entry either.
+                Self::withdraw_endpoint(
+                    service_registry,
+                    update_sender,
+                    key,
+                    UnavailableReason::VersionRejected,
+                );
+                debug!(
+                    "Ignoring offer for 0x{:04X} v{}.{} at {:?}: rejected by version policy",
+                    ep.service_id, ep.major_version, ep.minor_version, endpoint,
+                );
+            } else if ep.is_offer {
+                let probe = service_registry
+                    .get(key)
+                    .map(|info| info.probe)
+                    .unwrap_or_default();
+                match discovery_cache::offer(
+                    service_registry,
+                    key,
+                    ServiceEndpointInfo {
                             instance_id: ep.instance_id,
                             local_port: 0,
                             major_version: ep.major_version,
                             minor_version: ep.minor_version,
+                            expires_at: now.zip(crate::protocol::sd::ttl_lifetime(ep.ttl))
+                                .map(|(now, lifetime)| now + lifetime),
+                            expiry_notified: false,
+                            probe,
+                            is_static: false,
+                            load_balancing: ep.load_balancing,
                         },
-                    )
-                    .is_ok()
-                {
-                    trace!(
-                        "Registry: added 0x{:04X} -> {:?} (instance 0x{:04X})",
-                        ep.service_id, endpoint, ep.instance_id,
-                    );
-                } else {
-                    warn!(
-                        "Registry full; dropped offer for 0x{:04X} at {:?}",
-                        ep.service_id, endpoint,
-                    );
+                ) {
+                    Ok(is_new) => {
+                        trace!(
+                            "Registry: added 0x{:04X} -> {:?} (instance 0x{:04X})",
+                            ep.service_id, endpoint, ep.instance_id,
+                        );
+                        if is_new {
+                            let _ = update_sender.send_now(ClientUpdate::ServiceAvailable {
+                                key,
+                                instance_id: ep.instance_id,
+                            });
+                        }
+                    }
+                    Err(_) => {
+                        warn!(
+                            "Registry full; dropped offer for 0x{:04X} at {:?}",
+                            ep.service_id, endpoint,
+                        );
+                    }
                 }
             } else {
-                service_registry.remove(key);
+                Self::withdraw_endpoint(
+                    service_registry,
+                    update_sender,
+                    key,
+                    UnavailableReason::StopOffer,
+                );
                 trace!(
                     "Registry: removed 0x{:04X} at {:?}",
                     ep.service_id, endpoint,
@@ -687,11 +2717,52 @@ where
             }
         });
 
+        // Answer the tracked subscriptions this message acknowledges or
+        // rejects.
+        sd_payload.for_each_subscription_ack(|ack| {
+            let Some(index) = subscriptions
+                .iter()
+                .position(|subscription| subscription.matches(source.ip(), &ack))
+            else {
+                return;
+            };
+            if ack.ttl == 0 {
+                let subscription = subscriptions.swap_remove(index);
+                let error = Error::SubscriptionRejected {
+                    service_id: ack.service_id,
+                    instance_id: ack.instance_id,
+                    event_group_id: ack.event_group_id,
+                };
+                debug!("{}", error);
+                if let Some(waiter) = subscription.waiter {
+                    let _ = waiter.send(Err(error));
+                } else {
+                    let _ = update_sender.send_now(ClientUpdate::Error(error));
+                }
+            } else {
+                let subscription = &mut subscriptions[index];
+                if let Some(waiter) = subscription.waiter.take() {
+                    let _ = waiter.send(Ok(()));
+                }
+                subscription.due = subscription.next_refresh(now);
+            }
+        });
+
         if rebooted {
             // A rebooted sender restarts its E2E counter at zero, so drop our
             // stored per-source receive state for it; otherwise its first
             // post-reboot frame would read as out-of-sequence.
             e2e_registry.reset_source(source.ip());
+            // Its services may have come back elsewhere: resolve afresh.
+            service_registry.forget_resolved_at(source.ip());
+            // It forgot its subscribers too: subscribe again right away.
+            let resubscribed = resubscribe_rebooted(subscriptions, source.ip(), now);
+            if resubscribed > 0 {
+                debug!(
+                    "{} rebooted; renewing {} subscriptions",
+                    source, resubscribed
+                );
+            }
             let _ = update_sender.send_now(ClientUpdate::SenderRebooted(source));
         }
 
@@ -701,13 +2772,61 @@ where
             sd_header,
         };
         let _ = update_sender.send_now(ClientUpdate::DiscoveryUpdated(discovery_msg));
+        rebooted
+    }
+
+    /// Register the endpoint at `key`, reporting it as
+    /// `ClientUpdate::ServiceAvailable` if it is new.
+    fn add_endpoint(
+        &mut self,
+        key: ServiceEndpointKey,
+        info: ServiceEndpointInfo,
+    ) -> Result<(), Error> {
+        let instance_id = info.instance_id;
+        let Ok(is_new) = discovery_cache::offer(&mut self.service_registry, key, info) else {
+            warn!(
+                "service_registry at capacity ({}); cannot add 0x{:04X} at {:?}",
+                crate::client::service_registry::SERVICE_REGISTRY_CAP,
+                key.service_id,
+                key.endpoint,
+            );
+            return Err(Error::Capacity("service_registry"));
+        };
+        debug!(
+            "Added endpoint for service 0x{:04X} -> {:?}",
+            key.service_id, key.endpoint,
+        );
+        if is_new {
+            let _ = self
+                .update_sender
+                .send_now(ClientUpdate::ServiceAvailable { key, instance_id });
+        }
+        Ok(())
     }
 
-    /// Receive from any bound unicast socket. Returns the first message ready
-    /// from any socket. If no sockets are bound, returns a future that never resolves.
+    /// Drop the endpoint cached for `key`, reporting it as
+    /// `ClientUpdate::ServiceUnavailable` if there was one.
+    fn withdraw_endpoint(
+        service_registry: &mut ServiceRegistry,
+        update_sender: &C::UnboundedSender<ClientUpdate<PayloadDefinitions>>,
+        key: ServiceEndpointKey,
+        reason: UnavailableReason,
+    ) {
+        if let Some(info) = service_registry.remove(key) {
+            let _ = update_sender.send_now(ClientUpdate::ServiceUnavailable {
+                key,
+                instance_id: info.instance_id,
+                reason,
+            });
+        }
+    }
+
+    /// Receive from any bound unicast socket or open TCP connection.
+    /// Returns the first message ready from any of them. If there are
+    /// none, returns a future that never resolves.
     ///
-    /// A unicast socket whose loop has exited (`poll_receive` returns
-    /// `Poll::Ready(None)`) is evicted from the map immediately rather
+    /// A socket whose loop has exited (`poll_receive` returns
+    /// `Poll::Ready(None)`) is evicted from its map immediately rather
     /// than having `Err(SocketClosedUnexpectedly)` returned once per
     /// poll forever, which would CPU-pin the run-loop and flood the
     /// update stream.
@@ -717,56 +2836,76 @@ where
             SocketManager<PayloadDefinitions, C>,
             UNICAST_SOCKETS_CAP,
         >,
+        tcp_connections: &mut FnvIndexMap<
+            SocketAddrV4,
+            SocketManager<PayloadDefinitions, C>,
+            TCP_CONNECTIONS_CAP,
+        >,
     ) -> Result<ReceivedMessage<PayloadDefinitions>, Error> {
-        if unicast_sockets.is_empty() {
+        if unicast_sockets.is_empty() && tcp_connections.is_empty() {
             return future::pending().await;
         }
 
-        core::future::poll_fn(|cx| {
-            // Collect ports of any sockets that report `Ready(None)`
-            // (loop has exited). Evict them after the iteration so we
-            // do not mutate the map while iterating it.
-            let mut dead_ports: heapless::Vec<u16, UNICAST_SOCKETS_CAP> = heapless::Vec::new();
-            let mut delivered: Option<Result<ReceivedMessage<PayloadDefinitions>, Error>> = None;
-            for (port, socket) in unicast_sockets.iter_mut() {
-                if let Poll::Ready(result) = socket.poll_receive(cx) {
-                    match result {
-                        Some(msg) => {
-                            delivered = Some(msg);
-                            break;
-                        }
-                        None => {
-                            // Mark for eviction; keep scanning others.
-                            let _ = dead_ports.push(*port);
-                        }
+        core::future::poll_fn(|cx| {
+            if let Poll::Ready(msg) =
+                Self::poll_sockets(unicast_sockets, "Unicast socket on port", cx)
+            {
+                return Poll::Ready(msg);
+            }
+            Self::poll_sockets(tcp_connections, "TCP connection to", cx)
+        })
+        .await
+    }
+
+    /// Poll every socket in `sockets` once, evicting those whose loop
+    /// has exited. `kind` names them in the eviction log.
+    fn poll_sockets<K, const N: usize>(
+        sockets: &mut FnvIndexMap<K, SocketManager<PayloadDefinitions, C>, N>,
+        kind: &str,
+        cx: &mut core::task::Context<'_>,
+    ) -> Poll<Result<ReceivedMessage<PayloadDefinitions>, Error>>
+    where
+        K: Copy + Eq + core::hash::Hash + core::fmt::Display,
+    {
+        // Collect keys of any sockets that report `Ready(None)` (loop
+        // has exited). Evict them after the iteration so we do not
+        // mutate the map while iterating it.
+        let mut dead: heapless::Vec<K, N> = heapless::Vec::new();
+        let mut delivered: Option<Result<ReceivedMessage<PayloadDefinitions>, Error>> = None;
+        for (key, socket) in sockets.iter_mut() {
+            if let Poll::Ready(result) = socket.poll_receive(cx) {
+                match result {
+                    Some(msg) => {
+                        delivered = Some(msg);
+                        break;
+                    }
+                    None => {
+                        // Mark for eviction; keep scanning others.
+                        let _ = dead.push(*key);
                     }
                 }
             }
-            for port in &dead_ports {
-                // Removing the `SocketManager` drops its channel ends, so the
-                // spawned socket-loop future returns and is dropped. That drop
-                // releases its `BufferLease` (#125), freeing the pool slot for
-                // the next bind — no explicit buffer release is needed here.
-                unicast_sockets.remove(port);
-                crate::log::warn!("Unicast socket on port {port} closed; evicted from registry");
-            }
-            if let Some(msg) = delivered {
-                Poll::Ready(msg)
-            } else if unicast_sockets.is_empty() {
-                // The last socket just got evicted; fall through to a
-                // pending state so the next bind triggers a fresh poll.
-                Poll::Pending
-            } else if !dead_ports.is_empty() {
-                // At least one socket got evicted but others remain;
-                // re-poll so the caller observes the next ready event
-                // promptly instead of waiting on a stale waker.
-                cx.waker().wake_by_ref();
-                Poll::Pending
-            } else {
-                Poll::Pending
-            }
-        })
-        .await
+        }
+        for key in &dead {
+            // Removing the `SocketManager` drops its channel ends, so the
+            // spawned socket-loop future returns and is dropped. That drop
+            // releases its `BufferLease` (#125), freeing the pool slot for
+            // the next bind — no explicit buffer release is needed here.
+            sockets.remove(key);
+            crate::log::warn!("{kind} {key} closed; evicted from registry");
+        }
+        if let Some(msg) = delivered {
+            Poll::Ready(msg)
+        } else if !dead.is_empty() && !sockets.is_empty() {
+            // At least one socket got evicted but others remain;
+            // re-poll so the caller observes the next ready event
+            // promptly instead of waiting on a stale waker. Once the
+            // last one is gone, the next bind triggers a fresh poll.
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else {
+            Poll::Pending
+        }
     }
 
     #[allow(clippy::too_many_lines)]
@@ -872,6 +3011,9 @@ where
                                 .unwrap()
                                 .send(target, message)
                                 .await;
+                            if send_result.is_ok() && !target.ip().is_multicast() {
+                                self.peers.record_sent(SocketAddr::V4(target));
+                            }
                             if response.send(send_result).is_err() {
                                 debug!("SendSD: caller dropped the response receiver");
                             }
@@ -879,142 +3021,682 @@ where
                     }
                 }
                 ControlMessage::AddEndpoint(key, instance_id, local_port, response) => {
-                    let insert_result = self.service_registry.insert(
+                    let outcome = self.add_endpoint(
                         key,
                         ServiceEndpointInfo {
                             instance_id,
                             local_port,
                             major_version: 0xFF,
                             minor_version: 0xFFFF_FFFF,
+                            expires_at: None,
+                            expiry_notified: false,
+                            probe: ProbeState::default(),
+                            is_static: false,
+                            load_balancing: None,
                         },
                     );
-                    let outcome = if insert_result.is_ok() {
+                    if response.send(outcome).is_err() {
+                        debug!("AddEndpoint: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::AddStaticEndpoint(key, instance_id, response) => {
+                    let outcome = self.add_endpoint(
+                        key,
+                        ServiceEndpointInfo {
+                            instance_id,
+                            local_port: 0,
+                            major_version: 0xFF,
+                            minor_version: 0xFFFF_FFFF,
+                            expires_at: None,
+                            expiry_notified: false,
+                            probe: ProbeState::default(),
+                            is_static: true,
+                            load_balancing: None,
+                        },
+                    );
+                    if response.send(outcome).is_err() {
+                        debug!("AddStaticEndpoint: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::RemoveEndpoint(key, response) => {
+                    Self::withdraw_endpoint(
+                        &mut self.service_registry,
+                        &self.update_sender,
+                        key,
+                        UnavailableReason::Removed,
+                    );
+                    debug!(
+                        "Removed endpoint for service 0x{:04X} at {:?}",
+                        key.service_id, key.endpoint,
+                    );
+                    if response.send(Ok(())).is_err() {
+                        debug!("RemoveEndpoint: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetVersionPolicy(service_id, policy, response) => {
+                    let outcome = if self.version_policies.set(service_id, policy).is_ok() {
+                        // Drop already-discovered offers the new policy
+                        // rejects; they come back on the next matching
+                        // offer once the policy is relaxed. Manual
+                        // endpoints (`AddEndpoint`, major 0xFF) carry no
+                        // offered version to judge and are kept.
+                        let policies = &self.version_policies;
+                        let update_sender = &self.update_sender;
+                        self.service_registry.retain(|key, info| {
+                            let keep = info.major_version == 0xFF
+                                || policies.accepts(
+                                    key.service_id,
+                                    info.major_version,
+                                    info.minor_version,
+                                );
+                            if !keep {
+                                let _ = update_sender.send_now(ClientUpdate::ServiceUnavailable {
+                                    key: *key,
+                                    instance_id: info.instance_id,
+                                    reason: UnavailableReason::VersionRejected,
+                                });
+                            }
+                            keep
+                        });
+                        debug!(
+                            "Version policy for service 0x{:04X} set to {:?}",
+                            service_id, policy,
+                        );
+                        Ok(())
+                    } else {
+                        warn!(
+                            "version_policies at capacity ({}); cannot set policy for 0x{:04X}",
+                            crate::client::version_policy::VERSION_POLICIES_CAP,
+                            service_id,
+                        );
+                        Err(Error::Capacity("version_policies"))
+                    };
+                    if response.send(outcome).is_err() {
+                        debug!("SetVersionPolicy: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetProviderSelection(service_id, selection, response) => {
+                    let outcome = if self.provider_selections.set(service_id, selection).is_ok() {
+                        self.service_registry.forget_resolved(service_id);
+                        debug!(
+                            "Provider selection for service 0x{:04X} set to {:?}",
+                            service_id, selection,
+                        );
+                        Ok(())
+                    } else {
+                        warn!(
+                            "provider_selections at capacity ({}); cannot set selection for 0x{:04X}",
+                            crate::client::provider_selection::PROVIDER_SELECTIONS_CAP,
+                            service_id,
+                        );
+                        Err(Error::Capacity("provider_selections"))
+                    };
+                    if response.send(outcome).is_err() {
+                        debug!("SetProviderSelection: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetReceivePort {
+                    service_id,
+                    event_group_id,
+                    port,
+                    response,
+                } => {
+                    let outcome = if self
+                        .receive_ports
+                        .set(service_id, event_group_id, port)
+                        .is_ok()
+                    {
+                        debug!(
+                            "Receive port for service 0x{:04X} (eventgroup {:?}) set to {:?}",
+                            service_id, event_group_id, port,
+                        );
+                        Ok(())
+                    } else {
+                        warn!(
+                            "receive_ports at capacity ({}); cannot set a port for 0x{:04X}",
+                            RECEIVE_PORTS_CAP, service_id,
+                        );
+                        Err(Error::Capacity("receive_ports"))
+                    };
+                    if response.send(outcome).is_err() {
+                        debug!("SetReceivePort: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SendToService {
+                    key,
+                    message,
+                    send_complete,
+                    response,
+                    deadline,
+                    policy,
+                } => {
+                    if deadline.is_some_and(|d| d.has_passed(self.timer.now())) {
+                        warn!(
+                            "Deadline passed before sending to service 0x{:04X}; dropping request",
+                            key.service_id
+                        );
+                        let _ = send_complete.send(Err(Error::DeadlineExceeded));
+                        return;
+                    }
+                    let expects_response = message.header().message_type().message_type()
+                        == protocol::MessageType::Request;
+                    let policy = policy.filter(|_| expects_response);
+                    if policy.is_some() && self.timed_requests.is_full() {
+                        warn!(
+                            "timed_requests at capacity ({}); refusing request",
+                            TIMED_REQUESTS_CAP
+                        );
+                        if let Err(Err(e)) =
+                            send_complete.send(Err(Error::Capacity("timed_requests")))
+                        {
+                            let _ = response.send(Err(e));
+                        }
+                        return;
+                    }
+                    let resend = policy
+                        .filter(|policy| policy.retries > 0)
+                        .and_then(|_| keep_for_resend(&message));
+                    match self.send_request(key, message).await {
+                        Ok(request) => {
+                            let _ = send_complete.send(Ok(()));
+                            self.track_or_reject_pending_response(request, response);
+                            if let Some(policy) = policy {
+                                self.track_timed_request(key, request, policy, resend);
+                            }
+                        }
+                        Err(e) => {
+                            // A resend has no one awaiting its completion.
+                            if let Err(Err(e)) = send_complete.send(Err(e)) {
+                                let _ = response.send(Err(e));
+                            }
+                        }
+                    }
+                }
+                ControlMessage::SendToInstance {
+                    service_id,
+                    instance_id,
+                    message,
+                    send_complete,
+                    response,
+                } => {
+                    let Some(key) = self.resolve_endpoint(service_id, instance_id) else {
+                        let _ = send_complete.send(Err(Error::ServiceNotFound));
+                        return;
+                    };
+                    // Sent from the front of the queue as the resolved
+                    // request, so the send path lives in one place.
+                    if let Err(rejected) =
+                        self.request_queue
+                            .push_front(ControlMessage::SendToService {
+                                key,
+                                message,
+                                send_complete,
+                                response,
+                                deadline: None,
+                                policy: None,
+                            })
+                    {
+                        error!("request_queue push_front failed after pop — invariant broken");
+                        rejected.reject_with_capacity("request_queue");
+                    }
+                }
+                #[cfg(all(test, feature = "client-tokio"))]
+                ControlMessage::ForceSdSessionWrappedForTest(wrapped, response) => {
+                    self.sd_session_has_wrapped = wrapped;
+                    let _ = response.send(Ok(()));
+                }
+                ControlMessage::QueryRebootFlag(response) => {
+                    // Prefer the live socket's tracked flag when bound. When
+                    // unbound, fall back to `sd_session_has_wrapped`, which
+                    // persists wrap state across unbind/rebind (updated in
+                    // `unbind_discovery` from the socket manager before it's
+                    // dropped). Without this fallback, a long-running client
+                    // that wraps past 0xFFFF and then unbinds discovery
+                    // would erroneously revert to `RecentlyRebooted` on the
+                    // next `reboot_flag()` call.
+                    let flag = if let Some(socket) = self.discovery_socket.as_ref() {
+                        socket.reboot_flag()
+                    } else if self.sd_session_has_wrapped {
+                        crate::protocol::sd::RebootFlag::Continuous
+                    } else {
+                        crate::protocol::sd::RebootFlag::RecentlyRebooted
+                    };
+                    if response.send(Ok(flag)).is_err() {
+                        debug!("QueryRebootFlag: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetOfferExpiryMargin(margin, response) => {
+                    self.offer_expiry_margin = margin;
+                    debug!("Offer expiry margin set to {:?}", margin);
+                    if response.send(Ok(())).is_err() {
+                        debug!("SetOfferExpiryMargin: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetDuplicateFilter(enabled, response) => {
+                    // Re-enabling starts from an empty history.
+                    self.duplicate_filter = enabled.then(DuplicateFilter::new);
+                    debug!("Duplicate notification filter enabled: {}", enabled);
+                    if response.send(Ok(())).is_err() {
+                        debug!("SetDuplicateFilter: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetEventValidator {
+                    service_id,
+                    event_id,
+                    validator,
+                    response,
+                } => {
+                    let outcome = if self.event_validators.set(service_id, event_id, validator) {
+                        debug!(
+                            "Validator for event 0x{:04X}.0x{:04X} set: {}",
+                            service_id,
+                            event_id,
+                            validator.is_some(),
+                        );
+                        Ok(())
+                    } else {
+                        warn!(
+                            "event_validators at capacity ({}); cannot validate event 0x{:04X}.0x{:04X}",
+                            EVENT_VALIDATORS_CAP, service_id, event_id,
+                        );
+                        Err(Error::Capacity("event_validators"))
+                    };
+                    if response.send(outcome).is_err() {
+                        debug!("SetEventValidator: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetSessionHandling(service_id, enabled, response) => {
+                    let outcome = if self.sessionless_services.set(service_id, enabled).is_ok() {
+                        debug!(
+                            "Session handling for service 0x{:04X} enabled: {}",
+                            service_id, enabled
+                        );
+                        Ok(())
+                    } else {
+                        warn!(
+                            "sessionless_services at capacity ({}); cannot disable session handling for 0x{:04X}",
+                            SESSIONLESS_SERVICES_CAP, service_id,
+                        );
+                        Err(Error::Capacity("sessionless_services"))
+                    };
+                    if response.send(outcome).is_err() {
+                        debug!("SetSessionHandling: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetReachabilityProbe(config, response) => {
+                    // Disabling forgets every endpoint's probe history,
+                    // degraded marks included.
+                    if config.is_none() {
+                        for (_, info) in self.service_registry.iter_mut() {
+                            info.probe = ProbeState::default();
+                        }
+                    }
+                    self.prober = config.map(Prober::new);
+                    debug!("Reachability probe set to {:?}", config);
+                    if response.send(Ok(())).is_err() {
+                        debug!("SetReachabilityProbe: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::QueryOfferTtl {
+                    service_id,
+                    instance_id,
+                    response,
+                } => {
+                    let remaining = self.timer.now().and_then(|now| {
+                        self.service_registry
+                            .offer_ttl_remaining(service_id, instance_id, now)
+                    });
+                    if response.send(Ok(remaining)).is_err() {
+                        debug!("QueryOfferTtl: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::QueryPeerStats(response) => {
+                    if response.send(Ok(self.peers.clone())).is_err() {
+                        debug!("QueryPeerStats: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SetJanitor(config, response) => {
+                    self.janitor = config.map(Janitor::new);
+                    debug!("Janitor set to {:?}", config);
+                    if response.send(Ok(())).is_err() {
+                        debug!("SetJanitor: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::QueryJanitorStats(response) => {
+                    if response.send(Ok(self.janitor_stats)).is_err() {
+                        debug!("QueryJanitorStats: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::QueryDropStats(response) => {
+                    let mut stats = DropStats::default();
+                    for socket in [&self.discovery_socket, &self.discovery_unicast_socket]
+                        .into_iter()
+                        .flatten()
+                    {
+                        stats.add_discovery(socket.dropped_datagrams());
+                    }
+                    for socket in self.unicast_sockets.values() {
+                        stats.add_unicast(socket.dropped_datagrams());
+                    }
+                    for socket in [&self.discovery_socket, &self.discovery_unicast_socket]
+                        .into_iter()
+                        .flatten()
+                        .chain(self.unicast_sockets.values())
+                        .chain(self.tcp_connections.values())
+                    {
+                        stats.overflowed += socket.overflowed();
+                    }
+                    if response.send(Ok(stats)).is_err() {
+                        debug!("QueryDropStats: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::QueryStateSnapshot(response) => {
+                    if response.send(Ok(self.state_snapshot())).is_err() {
+                        debug!("QueryStateSnapshot: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::WaitForService {
+                    service_id,
+                    instance_id,
+                    policy,
+                    response,
+                } => {
+                    let draw = service_wait::draw(self.timer.now(), service_id, instance_id);
+                    if let Some(key) = self.provider_selections.select(
+                        &self.service_registry,
+                        service_id,
+                        instance_id,
+                        draw,
+                    ) {
+                        if response.send(Ok(key)).is_err() {
+                            debug!("WaitForService: caller dropped the response receiver");
+                        }
+                        return;
+                    }
+                    // Offers only arrive with discovery bound; bind it and
+                    // re-queue like SendSD does.
+                    if self.discovery_socket.is_none() {
+                        let message = match self.bind_discovery().await {
+                            Ok(()) => ControlMessage::WaitForService {
+                                service_id,
+                                instance_id,
+                                policy,
+                                response,
+                            },
+                            Err(e) => {
+                                let _ = response.send(Err(e));
+                                return;
+                            }
+                        };
+                        if let Err(rejected) = self.request_queue.push_front(message) {
+                            error!("request_queue push_front failed after pop — invariant broken");
+                            rejected.reject_with_capacity("request_queue");
+                        }
+                        return;
+                    }
+                    if self.service_waiters.is_full() {
+                        let _ = response.send(Err(Error::Capacity("service_waiters")));
+                        return;
+                    }
+                    let now = self.timer.now();
+                    let mut waiter =
+                        ServiceWaiter::new(service_id, instance_id, policy, now, draw, response);
+                    if waiter.take_find_due(now) {
+                        self.send_find_service(service_id, instance_id).await;
+                    }
+                    // Cannot fail: checked for room above.
+                    let _ = self.service_waiters.push(waiter);
+                }
+                ControlMessage::SelectProvider {
+                    service_id,
+                    instance_id,
+                    failover,
+                    response,
+                } => {
+                    let selected = match failover {
+                        None => self.provider_selections.select(
+                            &self.service_registry,
+                            service_id,
+                            instance_id,
+                            service_wait::draw(self.timer.now(), service_id, instance_id),
+                        ),
+                        Some((primary, attempt)) => self.provider_selections.fallback(
+                            &self.service_registry,
+                            service_id,
+                            instance_id,
+                            primary,
+                            attempt,
+                        ),
+                    };
+                    if response
+                        .send(selected.ok_or(Error::ServiceNotFound))
+                        .is_err()
+                    {
+                        debug!("SelectProvider: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::CancelFind {
+                    service_id,
+                    instance_id,
+                    response,
+                } => {
+                    // SD has no entry withdrawing a `FindService`: dropping
+                    // the waiters ends their repetitions.
+                    let mut cancelled = 0usize;
+                    let mut index = 0;
+                    while index < self.service_waiters.len() {
+                        let waiter = &self.service_waiters[index];
+                        if waiter.service_id != service_id
+                            || (instance_id != 0xFFFF && waiter.instance_id != instance_id)
+                        {
+                            index += 1;
+                            continue;
+                        }
+                        let waiter = self.service_waiters.swap_remove(index);
+                        if waiter.response.send(Err(Error::FindCancelled)).is_err() {
+                            debug!("WaitForService: caller dropped the response receiver");
+                        }
+                        cancelled += 1;
+                    }
+                    debug!(
+                        "Cancelled {} search(es) for 0x{:04X}.0x{:04X}",
+                        cancelled, service_id, instance_id
+                    );
+                    if response.send(Ok(())).is_err() {
+                        debug!("CancelFind: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::OpenEventStream {
+                    service_id,
+                    instance_id,
+                    event_group_id,
+                    events,
+                    sender,
+                    response,
+                } => {
+                    // Streams dropped since the last notification still
+                    // hold their slot; free them first.
+                    self.event_streams.retain(|route| !route.sender.is_closed());
+                    let route =
+                        EventRoute::new(service_id, instance_id, event_group_id, events, sender);
+                    let outcome = if self.event_streams.push(route).is_ok() {
+                        debug!(
+                            "Event stream opened for eventgroup 0x{:04X} of 0x{:04X}.0x{:04X}",
+                            event_group_id, service_id, instance_id
+                        );
+                        Ok(())
+                    } else {
+                        warn!(
+                            "event_streams at capacity ({}); refusing stream for 0x{:04X}",
+                            EVENT_STREAMS_CAP, service_id
+                        );
+                        Err(Error::Capacity("event_streams"))
+                    };
+                    if response.send(outcome).is_err() {
+                        debug!("OpenEventStream: caller dropped the response receiver");
+                    }
+                }
+                #[cfg(feature = "client-tokio")]
+                ControlMessage::WatchService {
+                    service_id,
+                    instance_id,
+                    sender,
+                    response,
+                } => {
+                    let watches = self.service_watches.get_or_insert_default();
+                    watches.retain(|watch| !watch.is_closed());
+                    // The watch gets the current endpoint, if any, at the
+                    // end of this loop iteration.
+                    let watch = ServiceWatch::new(service_id, instance_id, sender);
+                    let outcome = if watches.push(watch).is_ok() {
                         debug!(
-                            "Added endpoint for service 0x{:04X} -> {:?}",
-                            key.service_id, key.endpoint,
+                            "Service watch opened for 0x{:04X}.0x{:04X}",
+                            service_id, instance_id
                         );
                         Ok(())
                     } else {
                         warn!(
-                            "service_registry at capacity ({}); cannot add 0x{:04X} at {:?}",
-                            crate::client::service_registry::SERVICE_REGISTRY_CAP,
-                            key.service_id,
-                            key.endpoint,
+                            "service_watches at capacity ({}); refusing watch of 0x{:04X}",
+                            SERVICE_WATCHES_CAP, service_id
                         );
-                        Err(Error::Capacity("service_registry"))
+                        Err(Error::Capacity("service_watches"))
                     };
                     if response.send(outcome).is_err() {
-                        debug!("AddEndpoint: caller dropped the response receiver");
+                        debug!("WatchService: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SubscribeEventgroup {
+                    service_id,
+                    instance_id,
+                    event_group_id,
+                    ttl,
+                    response,
+                } => {
+                    self.subscribe_eventgroup(
+                        service_id,
+                        instance_id,
+                        event_group_id,
+                        ttl,
+                        response,
+                    )
+                    .await;
+                }
+                ControlMessage::UnsubscribeEventgroup {
+                    service_id,
+                    instance_id,
+                    event_group_id,
+                    response,
+                } => {
+                    let outcome = self
+                        .stop_tracked_subscription(|subscription| {
+                            subscription.key.service_id == service_id
+                                && subscription.instance_id == instance_id
+                                && subscription.event_group_id == event_group_id
+                        })
+                        .await;
+                    if response.send(outcome).is_err() {
+                        debug!("UnsubscribeEventgroup: caller dropped the response receiver");
+                    }
+                }
+                ControlMessage::SubscribeMany {
+                    key,
+                    major_version,
+                    ttl,
+                    event_group_ids,
+                    client_port,
+                    response,
+                    acks,
+                    id,
+                } => {
+                    let outcome = self
+                        .subscribe_many(
+                            key,
+                            major_version,
+                            ttl,
+                            &event_group_ids,
+                            client_port,
+                            acks,
+                            id,
+                        )
+                        .await;
+                    if response.send(outcome).is_err() {
+                        debug!("SubscribeMany: caller dropped the response receiver");
                     }
                 }
-                ControlMessage::RemoveEndpoint(key, response) => {
-                    self.service_registry.remove(key);
-                    debug!(
-                        "Removed endpoint for service 0x{:04X} at {:?}",
-                        key.service_id, key.endpoint,
-                    );
-                    if response.send(Ok(())).is_err() {
-                        debug!("RemoveEndpoint: caller dropped the response receiver");
+                ControlMessage::StopSubscription {
+                    key,
+                    event_group_id,
+                    id,
+                    response,
+                } => {
+                    let outcome = self
+                        .stop_tracked_subscription(|subscription| {
+                            subscription.key == key
+                                && subscription.event_group_id == event_group_id
+                                && id.is_none_or(|id| subscription.id == id)
+                        })
+                        .await;
+                    // Usually sent by a dropped `SubscriptionHandle`,
+                    // which does not wait for the outcome.
+                    if response.send(outcome).is_err() {
+                        debug!("StopSubscription: caller dropped the response receiver");
                     }
                 }
-                ControlMessage::SendToService {
+                ControlMessage::QuerySubscription {
                     key,
-                    mut message,
-                    send_complete,
+                    event_group_id,
+                    id,
                     response,
                 } => {
-                    let Some(endpoint_info) = self.service_registry.get(key) else {
-                        let _ = send_complete.send(Err(Error::ServiceNotFound));
-                        return;
-                    };
-                    let desired_port = endpoint_info.local_port;
-                    // The send target is the key's endpoint; today's
-                    // transports are IPv4 + UDP only.
-                    let (SocketAddr::V4(target), crate::TransportProtocol::Udp) =
-                        (key.endpoint.addr, key.endpoint.protocol)
-                    else {
-                        let _ = send_complete.send(Err(Error::UnsupportedEndpoint(key.endpoint)));
-                        return;
-                    };
-
-                    let source_port = if desired_port == 0 {
-                        // Ephemeral: auto-bind only if no sockets exist, then use first
-                        if self.unicast_sockets.is_empty() {
-                            match self.bind_unicast(0).await {
-                                Ok(port) => {
-                                    debug!("Auto-bound unicast on port {} for SendToService", port);
-                                    port
-                                }
-                                Err(e) => {
-                                    let _ = send_complete.send(Err(e));
-                                    return;
-                                }
-                            }
-                        } else {
-                            *self.unicast_sockets.keys().next().unwrap()
-                        }
-                    } else {
-                        // Specific port: bind if not already bound
-                        match self.bind_unicast(desired_port).await {
-                            Ok(port) => port,
-                            Err(e) => {
-                                let _ = send_complete.send(Err(e));
-                                return;
-                            }
-                        }
-                    };
-                    let socket = self.unicast_sockets.get_mut(&source_port).unwrap();
-
-                    // Stamp request ID with the CURRENT session counter,
-                    // but only advance it on successful send. A failed
-                    // send should not chew through the 16-bit session
-                    // space — under transient transport failure that
-                    // could wrap toward in-flight pending_responses
-                    // far faster than expected.
-                    let request_id =
-                        (u32::from(self.client_id) << 16) | u32::from(self.session_counter);
-                    message.set_request_id(request_id);
-
-                    let send_result = socket.send(target, message).await;
-                    match send_result {
-                        Ok(()) => {
-                            // Advance the counter only after a real
-                            // wire transmission. Skip 0 on wrap.
-                            self.session_counter = self.session_counter.wrapping_add(1);
-                            if self.session_counter == 0 {
-                                self.session_counter = 1;
-                            }
-                            let _ = send_complete.send(Ok(()));
-                            self.track_or_reject_pending_response(request_id, response);
-                        }
-                        Err(e) => {
-                            let _ = send_complete.send(Err(e));
-                        }
+                    let state = self
+                        .tracked_subscription(key, event_group_id)
+                        .map(|index| &self.subscriptions[index])
+                        .filter(|subscription| id.is_none_or(|id| subscription.id == id))
+                        .map_or(SubscriptionState::Inactive, Subscription::state);
+                    if response.send(Ok(state)).is_err() {
+                        debug!("QuerySubscription: caller dropped the response receiver");
                     }
                 }
-                #[cfg(all(test, feature = "client-tokio"))]
-                ControlMessage::ForceSdSessionWrappedForTest(wrapped, response) => {
-                    self.sd_session_has_wrapped = wrapped;
-                    let _ = response.send(Ok(()));
-                }
-                ControlMessage::QueryRebootFlag(response) => {
-                    // Prefer the live socket's tracked flag when bound. When
-                    // unbound, fall back to `sd_session_has_wrapped`, which
-                    // persists wrap state across unbind/rebind (updated in
-                    // `unbind_discovery` from the socket manager before it's
-                    // dropped). Without this fallback, a long-running client
-                    // that wraps past 0xFFFF and then unbinds discovery
-                    // would erroneously revert to `RecentlyRebooted` on the
-                    // next `reboot_flag()` call.
-                    let flag = if let Some(socket) = self.discovery_socket.as_ref() {
-                        socket.reboot_flag()
-                    } else if self.sd_session_has_wrapped {
-                        crate::protocol::sd::RebootFlag::Continuous
-                    } else {
-                        crate::protocol::sd::RebootFlag::RecentlyRebooted
-                    };
-                    if response.send(Ok(flag)).is_err() {
-                        debug!("QueryRebootFlag: caller dropped the response receiver");
+                ControlMessage::Unsubscribe {
+                    key,
+                    major_version,
+                    event_group_id,
+                    client_port,
+                    response,
+                } => {
+                    // A tracked subscription from that port must not be
+                    // refreshed back to life.
+                    self.forget_subscriptions(|subscription| {
+                        subscription.key == key
+                            && subscription.event_group_id == event_group_id
+                            && subscription.client_port == client_port
+                    });
+                    // The provider keys the subscription by the endpoint it
+                    // was made from; a stop from any other port would
+                    // remove nothing, so refuse rather than bind one.
+                    if !self.unicast_sockets.contains_key(&client_port) {
+                        if response.send(Err(Error::UnicastSocketNotBound)).is_err() {
+                            debug!("Unsubscribe: caller dropped the response receiver");
+                        }
+                        return;
+                    }
+                    // A stop-subscribe is a Subscribe with TTL 0. The port
+                    // is already bound, so the Subscribe path's bind step
+                    // is a lookup. See the `SetInterface` re-enqueue note.
+                    if let Err(rejected) =
+                        self.request_queue.push_front(ControlMessage::Subscribe {
+                            key,
+                            major_version,
+                            ttl: 0,
+                            event_group_id,
+                            client_port,
+                            response,
+                            first_event: None,
+                            ack: None,
+                        })
+                    {
+                        error!("request_queue push_front failed after pop — invariant broken");
+                        rejected.reject_with_capacity("request_queue");
                     }
                 }
                 ControlMessage::Subscribe {
@@ -1024,7 +3706,20 @@ where
                     event_group_id,
                     client_port,
                     response,
+                    first_event,
+                    ack,
                 } => {
+                    if first_event.is_some() && self.event_waiters.is_full() {
+                        let _ = response.send(Err(Error::Capacity("event_waiters")));
+                        return;
+                    }
+                    if ack.is_some()
+                        && self.tracked_subscription(key, event_group_id).is_none()
+                        && self.subscriptions.is_full()
+                    {
+                        let _ = response.send(Err(Error::Capacity("subscriptions")));
+                        return;
+                    }
                     // Look up endpoint from service registry; the
                     // instance id travels in the value ([PRS_SOMEIP_00162])
                     // but SubscribeEventgroup entries carry it on the wire.
@@ -1037,6 +3732,15 @@ where
                         return;
                     };
                     let instance_id = reg.instance_id;
+                    // A wildcard major version resolves to the concrete
+                    // one recorded from the provider's offer. Manual
+                    // endpoints record the wildcard itself, which goes
+                    // out as-is and is resolved by the server instead.
+                    let major_version = if major_version == crate::protocol::sd::ANY_MAJOR_VERSION {
+                        reg.major_version
+                    } else {
+                        major_version
+                    };
                     // Subscribes go to the provider's socket; today's
                     // transports are IPv4 + UDP only.
                     let (SocketAddr::V4(provider), crate::TransportProtocol::Udp) =
@@ -1047,7 +3751,10 @@ where
                     };
 
                     // Bind unicast on the requested port (0 = ephemeral)
-                    let unicast_port = match self.bind_unicast(client_port).await {
+                    let unicast_port = match self
+                        .subscriber_port(client_port, key.service_id, event_group_id)
+                        .await
+                    {
                         Ok(port) => {
                             debug!("Bound unicast on port {} for Subscribe", port);
                             port
@@ -1063,8 +3770,8 @@ where
                     };
 
                     // Auto-bind discovery if not bound (re-queue like SendSD does)
-                    match &mut self.discovery_socket {
-                        None => match self.bind_discovery().await {
+                    if self.discovery_socket.is_none() {
+                        match self.bind_discovery().await {
                             Ok(()) => {
                                 // Re-enqueue the Subscribe carrying the
                                 // ALREADY-bound `unicast_port` so pass-2
@@ -1084,6 +3791,8 @@ where
                                         event_group_id,
                                         client_port: unicast_port,
                                         response,
+                                        first_event,
+                                        ack,
                                     })
                                 {
                                     error!(
@@ -1099,36 +3808,48 @@ where
                                     );
                                 }
                             }
-                        },
-                        Some(discovery_socket) => {
-                            let sd_header = PayloadDefinitions::new_subscription_sd_header(
-                                key.service_id,
+                        }
+                    } else {
+                        let send_result = self
+                            .send_subscribe_entry(
+                                key,
+                                provider,
                                 instance_id,
                                 major_version,
                                 ttl,
                                 event_group_id,
-                                self.interface,
-                                crate::protocol::sd::TransportProtocol::Udp,
                                 unicast_port,
-                                discovery_socket.reboot_flag(),
+                            )
+                            .await;
+                        if send_result.is_ok()
+                            && let Some((timeout, first_event)) = first_event
+                        {
+                            // Cannot fail: checked for room above.
+                            let _ = self.event_waiters.push(EventWaiter::new(
+                                key.service_id,
+                                (*provider.ip()).into(),
+                                timeout,
+                                self.timer.now(),
+                                first_event,
+                            ));
+                        }
+                        if send_result.is_ok()
+                            && let Some((id, ack)) = ack
+                        {
+                            self.track_subscription(
+                                key,
+                                instance_id,
+                                event_group_id,
+                                ttl,
+                                unicast_port,
+                                id,
+                                ack,
+                            );
+                        }
+                        if response.send(send_result).is_err() {
+                            debug!(
+                                "Subscribe: caller dropped the response receiver (expected for subscribe_no_wait)"
                             );
-                            let session_id = u32::from(discovery_socket.session_id());
-                            let message =
-                                Message::<PayloadDefinitions>::new_sd(session_id, &sd_header);
-                            let target =
-                                SocketAddrV4::new(*provider.ip(), protocol::sd::MULTICAST_PORT);
-                            debug!("Sending Subscribe {:?} to {}", &message, target);
-                            let send_result = self
-                                .discovery_socket
-                                .as_mut()
-                                .unwrap()
-                                .send(target, message)
-                                .await;
-                            if response.send(send_result).is_err() {
-                                debug!(
-                                    "Subscribe: caller dropped the response receiver (expected for subscribe_no_wait)"
-                                );
-                            }
                         }
                     }
                 }
@@ -1137,8 +3858,12 @@ where
     }
 
     #[allow(clippy::too_many_lines)]
-    async fn run_future(mut self) {
+    async fn run_future(mut self, bind_discovery: bool) {
         info!("SOME/IP Client processing loop started");
+        if bind_discovery && let Err(e) = self.bind_discovery().await {
+            error!("Failed to bind discovery on start: {:?}", e);
+            let _ = self.update_sender.send_now(ClientUpdate::Error(e));
+        }
         loop {
             // Scope the `&mut self` destructure + pinned per-iteration
             // futures so all borrows of `self` drop before we call
@@ -1153,10 +3878,22 @@ where
                     discovery_socket,
                     discovery_unicast_socket,
                     unicast_sockets,
+                    tcp_connections,
+                    tcp_in_flight,
+                    tcp_last_active,
                     update_sender,
                     request_queue,
                     session_tracker,
                     service_registry,
+                    version_policies,
+                    peers,
+                    duplicate_filter,
+                    event_validators,
+                    sessionless_services,
+                    subscriptions,
+                    prober,
+                    event_waiters,
+                    event_streams,
                     e2e_registry,
                     run,
                     timer,
@@ -1178,7 +3915,8 @@ where
                 let discovery_fut = Self::receive_discovery(discovery_socket).fuse();
                 let discovery_unicast_fut =
                     Self::receive_discovery(discovery_unicast_socket).fuse();
-                let unicast_fut = Self::receive_any_unicast(unicast_sockets).fuse();
+                let unicast_fut =
+                    Self::receive_any_unicast(unicast_sockets, tcp_connections).fuse();
                 pin_mut!(
                     control_fut,
                     sleep_fut,
@@ -1231,16 +3969,23 @@ where
                     trace!("Received discovery message: {:?}", discovery);
                     match discovery {
                         Ok((source, someip_header, sd_header)) => {
-                            Self::handle_discovery_datagram(
+                            let now = timer.now();
+                            peers.record_received(source, now);
+                            if Self::handle_discovery_datagram(
                                 source,
                                 TransportKind::Multicast,
                                 someip_header,
                                 sd_header,
+                                now,
                                 session_tracker,
                                 service_registry,
+                                version_policies,
+                                subscriptions,
                                 e2e_registry,
                                 update_sender,
-                            );
+                            ) {
+                                peers.record_reboot(source);
+                            }
                         }
                         Err(err) => {
                             error!("Error receiving discovery message: {:?}", err);
@@ -1254,16 +3999,23 @@ where
                     trace!("Received unicast discovery message: {:?}", unicast_discovery);
                     match unicast_discovery {
                         Ok((source, someip_header, sd_header)) => {
-                            Self::handle_discovery_datagram(
+                            let now = timer.now();
+                            peers.record_received(source, now);
+                            if Self::handle_discovery_datagram(
                                 source,
                                 TransportKind::Unicast,
                                 someip_header,
                                 sd_header,
+                                now,
                                 session_tracker,
                                 service_registry,
+                                version_policies,
+                                subscriptions,
                                 e2e_registry,
                                 update_sender,
-                            );
+                            ) {
+                                peers.record_reboot(source);
+                            }
                         }
                         Err(err) => {
                             error!("Error receiving unicast discovery message: {:?}", err);
@@ -1275,15 +4027,83 @@ where
                      trace!("Received unicast message: {:?}", unicast);
                      match unicast {
                          Ok(received) => {
-                             let ReceivedMessage { message: received_message, e2e_status, source } = received;
+                             let ReceivedMessage { message: mut received_message, e2e_status, source, local_port, .. } = received;
+                             peers.record_received(source, timer.now());
+                             if e2e_status.is_some_and(E2ECheckStatus::is_failure) {
+                                 peers.record_error(source);
+                             }
                              // Check if this matches a pending request-response by request_id
                              let request_id = received_message.header().request_id();
-                             if let Some(sender) = pending_responses.remove(&request_id) {
-                                 let _ = sender.send(Ok(received_message.payload().clone()));
+                             // A reply to a reachability probe, even an
+                             // error reply, proves the provider alive and
+                             // is not forwarded.
+                             if prober.is_some()
+                                 && matches!(
+                                     received_message.header().message_type().message_type(),
+                                     protocol::MessageType::Response | protocol::MessageType::Error
+                                 )
+                                 && let Some((key, recovered)) = service_registry
+                                     .iter_mut()
+                                     .filter(|(key, _)| key.endpoint.addr.ip() == source.ip())
+                                     .find_map(|(key, info)| info.probe.answer(request_id).map(|recovered| (*key, recovered)))
+                             {
+                                 if recovered {
+                                     info!("Endpoint 0x{:04X} at {:?} answered its probe again", key.service_id, key.endpoint);
+                                     let _ = update_sender.send_now(ClientUpdate::Reachability { key, reachable: true });
+                                 }
                                  continue;
                              }
+                             // Requests only go to IPv4 peers.
+                             if let SocketAddr::V4(peer) = source {
+                                 let request = RequestKey::new(*peer.ip(), received_message.header().message_id(), request_id);
+                                 tcp_in_flight.answered(peer, request);
+                                 if let (Some(last_active), Some(now)) = (tcp_last_active.get_mut(&peer), timer.now()) {
+                                     *last_active = now;
+                                 }
+                                 if let Some(sender) = pending_responses.remove(&request) {
+                                     let _ = sender.send(match e2e_status {
+                                         Some(status) if status.is_failure() => Err(Error::E2ECheckFailed(status)),
+                                         _ => reply_result(&received_message),
+                                     });
+                                     continue;
+                                 }
+                             }
+                             let header = received_message.header();
+                             if header.message_type().message_type() == protocol::MessageType::Notification
+                                 && !sessionless_services.contains(header.message_id().service_id())
+                                 && let Some(filter) = duplicate_filter.as_mut()
+                                 && filter.is_duplicate(source, header.message_id().message_id(), (request_id & 0xFFFF) as u16)
+                             {
+                                 debug!("Dropping duplicate notification {:?} from {}", header.message_id(), source);
+                                 continue;
+                             }
+                             if header.message_type().message_type() == protocol::MessageType::Notification
+                                 && event_validators
+                                     .validate(header.message_id().service_id(), header.message_id().method_id(), received_message.payload())
+                                     .is_err()
+                             {
+                                 peers.record_rejected_payload(source);
+                                 continue;
+                             }
+                             if header.message_type().message_type() == protocol::MessageType::Notification {
+                                 Self::answer_event_waiters(event_waiters, header.message_id().service_id(), source);
+                                 // A notification failing its E2E check
+                                 // stays on the update stream, with its
+                                 // status.
+                                 if !e2e_status.is_some_and(E2ECheckStatus::is_failure) {
+                                     match event_streams::route(
+                                         event_streams,
+                                         service_registry,
+                                         received_message,
+                                         source.ip(),
+                                     ) {
+                                         Some(unrouted) => received_message = unrouted,
+                                         None => continue,
+                                     }
+                                 }
+                             }
                              // Not a response — forward as ClientUpdate::Unicast
-                             let _ = update_sender.send_now(ClientUpdate::Unicast { message: received_message, e2e_status, source });
+                             let _ = update_sender.send_now(ClientUpdate::Unicast { message: received_message, e2e_status, source, local_port });
                          }
                          Err(err) => {
                              let _ = update_sender.send_now(ClientUpdate::Error(err));
@@ -1297,9 +4117,327 @@ where
                 info!("SOME/IP Client processing loop exiting");
                 break;
             }
+            self.emit_offer_expiring();
+            self.expire_offers();
+            self.serve_service_waiters().await;
+            #[cfg(feature = "client-tokio")]
+            if let Some(watches) = self.service_watches.as_deref_mut() {
+                service_watch::update(watches, &self.service_registry);
+            }
+            self.resume_subscriptions();
+            self.expire_event_waiters();
+            self.fail_closed_tcp_requests();
+            self.expire_timed_requests();
+            self.refresh_subscriptions().await;
+            self.run_probes().await;
+            self.run_janitor();
             self.handle_control_message().await;
         }
     }
+
+    /// Multicast a `FindService` for `service_id`/`instance_id`, if
+    /// discovery is bound and the payload type can build one. Failures
+    /// are only logged: the waiter still sees cyclic offers.
+    async fn send_find_service(&mut self, service_id: u16, instance_id: u16) {
+        let target = SocketAddrV4::new(
+            self.socket_settings.sd_multicast_group,
+            self.socket_settings.sd_port,
+        );
+        let Some(discovery_socket) = self.discovery_socket.as_mut() else {
+            return;
+        };
+        let Some(header) = PayloadDefinitions::new_find_service_sd_header(
+            service_id,
+            instance_id,
+            discovery_socket.reboot_flag(),
+        ) else {
+            return;
+        };
+        let message = Message::<PayloadDefinitions>::new_sd(
+            u32::from(discovery_socket.session_id()),
+            &header,
+        );
+        debug!(
+            "Sending FindService for 0x{:04X}.0x{:04X}",
+            service_id, instance_id
+        );
+        if let Err(e) = discovery_socket.send(target, message).await {
+            warn!(
+                "FindService for 0x{:04X}.0x{:04X} failed: {:?}",
+                service_id, instance_id, e
+            );
+        }
+    }
+
+    /// Answer every `wait_for_service` whose service is now in the
+    /// registry, time out overdue ones, and repeat due `FindService`s.
+    /// Runs once per loop iteration, so an offer resolves its waiters
+    /// right after it is processed.
+    async fn serve_service_waiters(&mut self) {
+        if self.service_waiters.is_empty() {
+            return;
+        }
+        let now = self.timer.now();
+        let mut finds: heapless::Vec<(u16, u16), { service_wait::SERVICE_WAITERS_CAP }> =
+            heapless::Vec::new();
+        let mut index = 0;
+        while index < self.service_waiters.len() {
+            let waiter = &mut self.service_waiters[index];
+            let result = match self.provider_selections.select(
+                &self.service_registry,
+                waiter.service_id,
+                waiter.instance_id,
+                service_wait::draw(now, waiter.service_id, waiter.instance_id),
+            ) {
+                Some(key) => Ok(key),
+                None if waiter.has_timed_out(now) => Err(Error::Timeout),
+                None => {
+                    if waiter.take_find_due(now) {
+                        let _ = finds.push((waiter.service_id, waiter.instance_id));
+                    }
+                    index += 1;
+                    continue;
+                }
+            };
+            let waiter = self.service_waiters.swap_remove(index);
+            if waiter.response.send(result).is_err() {
+                debug!("WaitForService: caller dropped the response receiver");
+            }
+        }
+        for (service_id, instance_id) in finds {
+            self.send_find_service(service_id, instance_id).await;
+        }
+    }
+
+    /// Answer every `subscribe_and_wait_first` waiting on a notification
+    /// of `service_id` from `source`.
+    fn answer_event_waiters(
+        event_waiters: &mut EventWaiters<C::OneshotSender<Result<(), Error>>>,
+        service_id: u16,
+        source: SocketAddr,
+    ) {
+        let mut index = 0;
+        while index < event_waiters.len() {
+            if event_waiters[index].matches(service_id, source.ip()) {
+                let waiter = event_waiters.swap_remove(index);
+                if waiter.response.send(Ok(())).is_err() {
+                    debug!("SubscribeAndWaitFirst: caller dropped the response receiver");
+                }
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Fail every `subscribe_and_wait_first` whose notification is overdue
+    /// with `Error::Timeout`.
+    fn expire_event_waiters(&mut self) {
+        if self.event_waiters.is_empty() {
+            return;
+        }
+        let now = self.timer.now();
+        let mut index = 0;
+        while index < self.event_waiters.len() {
+            if self.event_waiters[index].has_timed_out(now) {
+                let waiter = self.event_waiters.swap_remove(index);
+                if waiter.response.send(Err(Error::Timeout)).is_err() {
+                    debug!("SubscribeAndWaitFirst: caller dropped the response receiver");
+                }
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Once per janitor interval, close TCP connections that have been
+    /// idle too long.
+    ///
+    /// A connection with requests still awaiting a reply is never idle.
+    /// Closing drops its `SocketManager`, which ends the socket loop; the
+    /// next message to that provider opens a fresh connection. SOME/IP-TP
+    /// buffers of the socket loops are not swept here: they expire as
+    /// later segments arrive. Like `emit_offer_expiring`, this needs
+    /// [`Timer::now`].
+    fn run_janitor(&mut self) {
+        let (Some(janitor), Some(now)) = (self.janitor.as_mut(), self.timer.now()) else {
+            return;
+        };
+        if !janitor.start_sweep(now) {
+            return;
+        }
+        let stats = &mut self.janitor_stats;
+        stats.sweeps += 1;
+
+        let idle: heapless::Vec<SocketAddrV4, TCP_CONNECTIONS_CAP> = self
+            .tcp_last_active
+            .iter()
+            .filter(|&(peer, &last_active)| {
+                self.tcp_connections.contains_key(peer)
+                    && !self.tcp_in_flight.peers().any(|busy| busy == *peer)
+                    && janitor.is_idle(last_active, now)
+            })
+            .map(|(peer, _)| *peer)
+            .collect();
+        for peer in idle {
+            debug!("Janitor: closing idle TCP connection to {}", peer);
+            self.tcp_connections.remove(&peer);
+            self.tcp_last_active.remove(&peer);
+            stats.tcp_connections_closed += 1;
+        }
+    }
+
+    /// Expire overdue reachability probes and, once per interval, probe
+    /// every endpoint.
+    ///
+    /// Probes go out from an already bound unicast socket (the endpoint's
+    /// `local_port`, or any for ephemeral endpoints) and never bind one
+    /// themselves; an endpoint with no usable socket, or whose probe fails
+    /// to send, is skipped for the round. Like `emit_offer_expiring`, this
+    /// needs [`Timer::now`].
+    async fn run_probes(&mut self) {
+        let (Some(prober), Some(now)) = (self.prober.as_mut(), self.timer.now()) else {
+            return;
+        };
+        let config = prober.config;
+        if prober.replies_overdue(now) {
+            for (key, info) in self.service_registry.iter_mut() {
+                if info.probe.expire(&config) {
+                    warn!(
+                        "Endpoint 0x{:04X} at {:?} missed {} probes; marking degraded",
+                        key.service_id,
+                        key.endpoint,
+                        config.failures_before_degraded.max(1),
+                    );
+                    let _ = self.update_sender.send_now(ClientUpdate::Reachability {
+                        key: *key,
+                        reachable: false,
+                    });
+                }
+            }
+        }
+        let Some(round) = prober.start_round(now) else {
+            return;
+        };
+
+        // Every pass visits one endpoint, so this ends.
+        loop {
+            let Some((&key, info)) = self
+                .service_registry
+                .iter_mut()
+                .find(|(_, info)| info.probe.is_pending(round))
+            else {
+                break;
+            };
+            let socket_port = if info.local_port == 0 {
+                self.unicast_sockets.keys().next().copied()
+            } else {
+                Some(info.local_port).filter(|port| self.unicast_sockets.contains_key(port))
+            };
+            let message_id =
+                protocol::MessageId::new_from_service_and_method(key.service_id, config.method_id);
+            let (
+                (SocketAddr::V4(target), crate::TransportProtocol::Udp),
+                Some(socket),
+                Ok(payload),
+            ) = (
+                (key.endpoint.addr, key.endpoint.protocol),
+                socket_port.and_then(|port| self.unicast_sockets.get_mut(&port)),
+                PayloadDefinitions::from_payload_bytes(message_id, &[]),
+            )
+            else {
+                trace!("No way to probe {:?} yet", key.endpoint);
+                info.probe.visit(round, None);
+                continue;
+            };
+            let session_id = self.request_sessions.current(IpAddr::V4(*target.ip()));
+            let request_id = (u32::from(self.client_id) << 16) | u32::from(session_id);
+            let header = protocol::Header::new(
+                message_id,
+                request_id,
+                0x01,
+                info.major_version,
+                protocol::MessageTypeField::new(protocol::MessageType::Request, false),
+                protocol::ReturnCode::Ok,
+                0,
+            );
+            info.probe.visit(round, Some(request_id));
+            if let Err(e) = socket.send(target, Message::new(header, payload)).await {
+                debug!("Failed to send probe to {:?}: {:?}", key.endpoint, e);
+                if let Some(info) = self.service_registry.get_mut(key) {
+                    info.probe.visit(round, None);
+                }
+                continue;
+            }
+            trace!("Probe 0x{:08X} sent to {:?}", request_id, key.endpoint);
+            self.peers.record_sent(SocketAddr::V4(target));
+            self.request_sessions.advance(IpAddr::V4(*target.ip()));
+        }
+    }
+
+    /// Drop discovered offers whose TTL has lapsed, reporting each as
+    /// `ClientUpdate::ServiceUnavailable` and counting it in the janitor
+    /// stats. Like `emit_offer_expiring`, this needs [`Timer::now`].
+    fn expire_offers(&mut self) {
+        let Some(now) = self.timer.now() else {
+            return;
+        };
+        let update_sender = &self.update_sender;
+        let stats = &mut self.janitor_stats;
+        discovery_cache::expire(&mut self.service_registry, now, |key, info| {
+            debug!(
+                "Offer for 0x{:04X} at {:?} expired",
+                key.service_id, key.endpoint
+            );
+            stats.discovery_entries_pruned += 1;
+            let _ = update_sender.send_now(ClientUpdate::ServiceUnavailable {
+                key,
+                instance_id: info.instance_id,
+                reason: UnavailableReason::Expired,
+            });
+        });
+    }
+
+    /// Emit `ClientUpdate::OfferExpiring` for every offer that entered
+    /// the configured margin since the last loop iteration. The run-loop
+    /// wakes at least every 125 ms, which bounds how late the update can
+    /// be.
+    fn emit_offer_expiring(&mut self) {
+        let (Some(margin), Some(now)) = (self.offer_expiry_margin, self.timer.now()) else {
+            return;
+        };
+        let update_sender = &self.update_sender;
+        self.service_registry
+            .take_expiring(now, margin, |key, info, remaining| {
+                debug!(
+                    "Offer for 0x{:04X} at {:?} expires in {:?}",
+                    key.service_id, key.endpoint, remaining,
+                );
+                let _ = update_sender.send_now(ClientUpdate::OfferExpiring {
+                    key,
+                    instance_id: info.instance_id,
+                    remaining,
+                });
+            });
+    }
+}
+
+/// What a pending request resolves to: the payload of a `RESPONSE`, or
+/// [`Error::ErrorResponse`] for an `ERROR`, with its payload decoded if it
+/// is an [`ApplicationError`](protocol::ApplicationError).
+fn reply_result<P: PayloadWireFormat + Clone>(message: &Message<P>) -> Result<P, Error> {
+    let header = message.header();
+    if header.message_type().message_type() != protocol::MessageType::Error {
+        return Ok(message.payload().clone());
+    }
+    let mut bytes = [0u8; protocol::ApplicationError::ENCODED_LEN];
+    let application_error = (message.payload().required_size() == bytes.len()
+        && message.payload().encode(&mut &mut bytes[..]).is_ok())
+    .then(|| protocol::ApplicationError::parse(&bytes).ok())
+    .flatten();
+    Err(Error::ErrorResponse {
+        return_code: header.return_code(),
+        application_error,
+    })
 }
 
 #[cfg(all(test, feature = "client-tokio"))]
@@ -1335,6 +4473,7 @@ mod tests {
             crate::tokio_transport::TokioTransport,
             TokioSpawner,
             crate::tokio_transport::TokioBufferProvider,
+            crate::tokio_transport::TokioTransport,
         >,
     >;
 
@@ -1372,6 +4511,9 @@ mod tests {
 
         let (_rx, msg) = TestControl::subscribe(lh_key(0x1234, 5000), 1, 3, 0x01, 0);
         assert!(matches!(msg, ControlMessage::Subscribe { .. }));
+
+        let (_rx, msg) = TestControl::unsubscribe(lh_key(0x1234, 5000), 1, 0x01, 40000);
+        assert!(matches!(msg, ControlMessage::Unsubscribe { .. }));
     }
 
     /// `reject_with_capacity` must notify every oneshot sender inside a
@@ -1381,6 +4523,7 @@ mod tests {
     /// (or `.expect(...)` inside `PendingResponse::response()`) panic on
     /// the resulting `RecvError`, which is exactly what Copilot flagged.
     #[test]
+    #[allow(clippy::too_many_lines)]
     fn reject_with_capacity_notifies_every_sender() {
         use crate::transport::OneshotCancelled;
         use futures_util::FutureExt;
@@ -1404,31 +4547,152 @@ mod tests {
         msg.reject_with_capacity("request_queue");
         expect_capacity(rx.recv(), "BindDiscovery");
 
-        let (rx, msg) = TestControl::unbind_discovery();
+        let (rx, msg) = TestControl::unbind_discovery();
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "UnbindDiscovery");
+
+        let target = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1234);
+        let (rx, msg) = TestControl::send_sd(target, empty_sd_header());
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "SendSD");
+
+        let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 5000);
+        let (rx, msg) = TestControl::add_endpoint(
+            ServiceEndpointKey::udp(0x1234, SocketAddr::V4(addr)),
+            0x0001,
+            0,
+        );
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "AddEndpoint");
+
+        let (rx, msg) = TestControl::remove_endpoint(lh_key(0x1234, 5000));
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "RemoveEndpoint");
+
+        let (rx, msg) = TestControl::subscribe(lh_key(0x1234, 5000), 1, 3, 0x01, 0);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "Subscribe");
+
+        let (rx, msg) = TestControl::unsubscribe(lh_key(0x1234, 5000), 1, 0x01, 40000);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "Unsubscribe");
+
+        let (rx, msg) = TestControl::set_offer_expiry_margin(None);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "SetOfferExpiryMargin");
+
+        let (rx, msg) = TestControl::set_duplicate_filter(true);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "SetDuplicateFilter");
+
+        let (rx, msg) = TestControl::set_event_validator(0x1234, 0x8001, None);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "SetEventValidator");
+
+        let (rx, msg) = TestControl::set_reachability_probe(None);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "SetReachabilityProbe");
+
+        let (rx, msg) = TestControl::query_offer_ttl(0x1234, 1);
+        msg.reject_with_capacity("request_queue");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "QueryOfferTtl"
+        );
+
+        let (rx, msg) = TestControl::query_peer_stats();
+        msg.reject_with_capacity("request_queue");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "QueryPeerStats"
+        );
+
+        let (rx, msg) = TestControl::set_janitor(None);
+        msg.reject_with_capacity("request_queue");
+        expect_capacity(rx.recv(), "SetJanitor");
+
+        let (rx, msg) = TestControl::query_janitor_stats();
+        msg.reject_with_capacity("request_queue");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "QueryJanitorStats"
+        );
+
+        let (rx, msg) = TestControl::query_drop_stats();
+        msg.reject_with_capacity("request_queue");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "QueryDropStats"
+        );
+
+        let (rx, msg) = TestControl::query_state_snapshot();
+        msg.reject_with_capacity("request_queue");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "QueryStateSnapshot"
+        );
+
+        let (rx, msg) = TestControl::wait_for_service(0x1234, 1, FindPolicy::new());
         msg.reject_with_capacity("request_queue");
-        expect_capacity(rx.recv(), "UnbindDiscovery");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "WaitForService"
+        );
 
-        let target = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1234);
-        let (rx, msg) = TestControl::send_sd(target, empty_sd_header());
+        let (rx, msg) = TestControl::cancel_find(0x1234, 1);
         msg.reject_with_capacity("request_queue");
-        expect_capacity(rx.recv(), "SendSD");
+        expect_capacity(rx.recv(), "CancelFind");
 
-        let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 5000);
-        let (rx, msg) = TestControl::add_endpoint(
-            ServiceEndpointKey::udp(0x1234, SocketAddr::V4(addr)),
-            0x0001,
-            0,
-        );
+        let (rx, msg) = TestControl::set_provider_selection(0x1234, None);
         msg.reject_with_capacity("request_queue");
-        expect_capacity(rx.recv(), "AddEndpoint");
+        expect_capacity(rx.recv(), "SetProviderSelection");
 
-        let (rx, msg) = TestControl::remove_endpoint(lh_key(0x1234, 5000));
+        let (rx, msg) = TestControl::select_provider(0x1234, 1, None);
         msg.reject_with_capacity("request_queue");
-        expect_capacity(rx.recv(), "RemoveEndpoint");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "SelectProvider"
+        );
 
-        let (rx, msg) = TestControl::subscribe(lh_key(0x1234, 5000), 1, 3, 0x01, 0);
+        // The caller awaits the subscribe result before the first event,
+        // so only that sender needs the error.
+        let (rx, _first_rx, msg) = TestControl::subscribe_and_wait_first(
+            lh_key(0x1234, 5000),
+            1,
+            3,
+            0x01,
+            0,
+            core::time::Duration::ZERO,
+        );
         msg.reject_with_capacity("request_queue");
-        expect_capacity(rx.recv(), "Subscribe");
+        assert!(
+            matches!(
+                rx.recv().now_or_never(),
+                Some(Ok(Err(Error::Capacity("request_queue"))))
+            ),
+            "Subscribe (wait first)"
+        );
 
         // SendToService carries two senders — both must be notified so that
         // neither `send_rx.recv().await.unwrap()?` nor `PendingResponse::response()`
@@ -1490,6 +4754,63 @@ mod tests {
         assert!(s.contains("Subscribe"));
         assert!(s.contains("service_id"));
         assert!(s.contains("event_group_id"));
+
+        let (_rx, msg) =
+            TestControl::set_offer_expiry_margin(Some(core::time::Duration::from_secs(1)));
+        assert!(format!("{msg:?}").contains("SetOfferExpiryMargin"));
+
+        let (_rx, msg) = TestControl::set_duplicate_filter(true);
+        assert_eq!(format!("{msg:?}"), "SetDuplicateFilter(true)");
+
+        let (_rx, msg) = TestControl::set_event_validator(0x1234, 0x8001, None);
+        let s = format!("{msg:?}");
+        assert!(s.contains("SetEventValidator"));
+        assert!(s.contains("validator: false"));
+
+        let (_rx, msg) = TestControl::set_reachability_probe(None);
+        assert_eq!(format!("{msg:?}"), "SetReachabilityProbe(None)");
+
+        let (_rx, msg) = TestControl::query_offer_ttl(0x1234, 1);
+        let s = format!("{msg:?}");
+        assert!(s.contains("QueryOfferTtl"));
+        assert!(s.contains("instance_id"));
+
+        let (_rx, msg) = TestControl::query_peer_stats();
+        assert_eq!(format!("{msg:?}"), "QueryPeerStats");
+
+        let (_rx, msg) = TestControl::set_janitor(None);
+        assert_eq!(format!("{msg:?}"), "SetJanitor(None)");
+
+        let (_rx, msg) = TestControl::query_janitor_stats();
+        assert_eq!(format!("{msg:?}"), "QueryJanitorStats");
+
+        let (_rx, msg) = TestControl::query_drop_stats();
+        assert_eq!(format!("{msg:?}"), "QueryDropStats");
+
+        let (_rx, msg) = TestControl::query_state_snapshot();
+        assert_eq!(format!("{msg:?}"), "QueryStateSnapshot");
+
+        let (_rx, msg) = TestControl::wait_for_service(0x1234, 1, FindPolicy::new());
+        let s = format!("{msg:?}");
+        assert!(s.contains("WaitForService"));
+        assert!(s.contains("timeout"));
+
+        let (_rx, msg) = TestControl::cancel_find(0x1234, 1);
+        let s = format!("{msg:?}");
+        assert!(s.contains("CancelFind"));
+        assert!(s.contains("instance_id"));
+
+        let (_rx, msg) =
+            TestControl::set_provider_selection(0x1234, Some(ProviderSelection::RoundRobin));
+        assert_eq!(
+            format!("{msg:?}"),
+            "SetProviderSelection(4660, Some(RoundRobin))"
+        );
+
+        let (_rx, msg) = TestControl::select_provider(0x1234, 1, None);
+        let s = format!("{msg:?}");
+        assert!(s.contains("SelectProvider"));
+        assert!(s.contains("failover"));
     }
 
     /// Build an [`Inner`] without spawning the run loop, for direct
@@ -1501,26 +4822,51 @@ mod tests {
             TokioChannels::unbounded::<ClientUpdate<TestPayload>>();
         Inner {
             control_receiver,
-            request_queue: Deque::new(),
+            request_queue: FairQueue::new(),
             pending_responses: FnvIndexMap::new(),
+            timed_requests: heapless::Vec::new(),
             update_sender,
             interface: Ipv4Addr::LOCALHOST,
             discovery_socket: None,
             discovery_unicast_socket: None,
             unicast_sockets: FnvIndexMap::new(),
+            tcp_connections: FnvIndexMap::new(),
+            tcp_in_flight: TcpInFlight::new(),
+            tcp_last_active: FnvIndexMap::new(),
             session_tracker: SessionTracker::default(),
             service_registry: ServiceRegistry::default(),
+            version_policies: VersionPolicies::default(),
+            provider_selections: ProviderSelections::default(),
+            receive_ports: ReceivePorts::default(),
+            offer_expiry_margin: None,
+            peers: PeerTable::new(),
+            duplicate_filter: None,
+            event_validators: EventValidators::new(),
+            sessionless_services: SessionlessServices::default(),
+            subscriptions: heapless::Vec::new(),
+            prober: None,
+            janitor: None,
+            janitor_stats: JanitorStats::default(),
+            service_waiters: heapless::Vec::new(),
+            event_waiters: heapless::Vec::new(),
+            event_streams: heapless::Vec::new(),
+            #[cfg(feature = "client-tokio")]
+            service_watches: None,
             run: true,
             client_id: 0x1234,
-            session_counter: 1,
+            request_sessions: RequestSessions::default(),
             sd_session_id: 1,
             sd_session_has_wrapped: false,
             e2e_registry: Arc::new(Mutex::new(E2ERegistry::new())),
             multicast_loopback: false,
+            unicast_ports: None,
+            tcp_connect_timeout: ClientConfig::new().tcp_connect_timeout,
+            socket_settings: SocketSettings::default(),
             dispatch: crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             timer: TokioTimer,
             phantom: core::marker::PhantomData,
@@ -1560,6 +4906,25 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn bind_unicast_without_port_uses_the_configured_range() {
+        let mut inner = make_inner_for_test();
+        let held = std::net::UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).unwrap();
+        let port = held.local_addr().unwrap().port();
+        inner.unicast_ports = Some((port, port));
+
+        let err = inner.bind_unicast(0).await.expect_err("range is taken");
+        assert!(matches!(err, Error::Capacity("unicast_ports")), "{err:?}");
+
+        drop(held);
+        assert_eq!(inner.bind_unicast(0).await.unwrap(), port);
+        let err = inner
+            .bind_unicast(0)
+            .await
+            .expect_err("the client's own socket holds the range");
+        assert!(matches!(err, Error::Capacity("unicast_ports")), "{err:?}");
+    }
+
     /// Happy path: with room in `pending_responses`, the helper tracks
     /// the entry and does NOT signal the caller — the sender stays
     /// alive so a future unicast reply can resolve it.
@@ -1569,12 +4934,25 @@ mod tests {
         let mut inner = make_inner_for_test();
         let (tx, rx) = oneshot::channel::<Result<TestPayload, Error>>();
 
-        inner.track_or_reject_pending_response(0xDEAD_BEEF, tx);
+        let request = RequestKey::new(
+            Ipv4Addr::LOCALHOST,
+            protocol::MessageId::new(0x1234_0001),
+            0xDEAD_BEEF,
+        );
+        inner.track_or_reject_pending_response(request, tx);
 
         assert_eq!(inner.pending_responses.len(), 1);
         assert!(
-            inner.pending_responses.contains_key(&0xDEAD_BEEF),
-            "entry should be keyed by the provided request_id",
+            inner.pending_responses.contains_key(&request),
+            "entry should be keyed by the provided request",
+        );
+        assert!(
+            !inner.pending_responses.contains_key(&RequestKey::new(
+                Ipv4Addr::LOCALHOST,
+                protocol::MessageId::new(0x1234_0002),
+                0xDEAD_BEEF
+            )),
+            "a reply to another method with the same request_id does not match",
         );
         // Receiver is still waiting — helper did NOT pre-emptively
         // resolve it with a capacity error on the happy path.
@@ -1610,7 +4988,11 @@ mod tests {
             inner
                 .pending_responses
                 .insert(
-                    u32::try_from(i).expect("PENDING_RESPONSES_CAP fits in u32"),
+                    RequestKey::new(
+                        Ipv4Addr::LOCALHOST,
+                        protocol::MessageId::new(0x1234_0001),
+                        u32::try_from(i).expect("PENDING_RESPONSES_CAP fits in u32"),
+                    ),
                     tx,
                 )
                 .expect("filling under cap must succeed");
@@ -1622,7 +5004,11 @@ mod tests {
         // sender from the failed insert and deliver an explicit
         // capacity error on it.
         let (overflow_tx, overflow_rx) = oneshot::channel::<Result<TestPayload, Error>>();
-        let overflow_key: u32 = 0xFFFF_FFFE;
+        let overflow_key = RequestKey::new(
+            Ipv4Addr::LOCALHOST,
+            protocol::MessageId::new(0x1234_0001),
+            0xFFFF_FFFE,
+        );
         inner.track_or_reject_pending_response(overflow_key, overflow_tx);
 
         // Map size unchanged — the overflow attempt was rejected, not
@@ -1649,7 +5035,7 @@ mod tests {
     }
 
     /// If a `request_id` is reused while an older pending entry is still
-    /// live (e.g. `session_counter` wrap-around), `insert` returns
+    /// live (e.g. session counter wrap-around), `insert` returns
     /// `Ok(Some(old_sender))`. Without handling that case, the displaced
     /// sender is dropped and the caller awaiting the original request
     /// hits `RecvError` (which `PendingResponse::response()` treats as a
@@ -1662,7 +5048,11 @@ mod tests {
         use futures_util::FutureExt;
 
         let mut inner = make_inner_for_test();
-        let key: u32 = 0xCAFE_F00D;
+        let key = RequestKey::new(
+            Ipv4Addr::LOCALHOST,
+            protocol::MessageId::new(0x1234_0001),
+            0xCAFE_F00D,
+        );
 
         // First tracking: the sender lives in the map.
         let (first_tx, first_rx) = oneshot::channel::<Result<TestPayload, Error>>();
@@ -1744,29 +5134,55 @@ mod tests {
                 TokioTransport,
                 CountingSpawner,
                 TokioBufferProvider,
+                TokioTransport,
             >,
         > = Inner {
             control_receiver,
-            request_queue: Deque::new(),
+            request_queue: FairQueue::new(),
             pending_responses: FnvIndexMap::new(),
+            timed_requests: heapless::Vec::new(),
             update_sender,
             interface: Ipv4Addr::LOCALHOST,
             discovery_socket: None,
             discovery_unicast_socket: None,
             unicast_sockets: FnvIndexMap::new(),
+            tcp_connections: FnvIndexMap::new(),
+            tcp_in_flight: TcpInFlight::new(),
+            tcp_last_active: FnvIndexMap::new(),
             session_tracker: SessionTracker::default(),
             service_registry: ServiceRegistry::default(),
+            version_policies: VersionPolicies::default(),
+            provider_selections: ProviderSelections::default(),
+            receive_ports: ReceivePorts::default(),
+            offer_expiry_margin: None,
+            peers: PeerTable::new(),
+            duplicate_filter: None,
+            event_validators: EventValidators::new(),
+            sessionless_services: SessionlessServices::default(),
+            subscriptions: heapless::Vec::new(),
+            prober: None,
+            janitor: None,
+            janitor_stats: JanitorStats::default(),
+            service_waiters: heapless::Vec::new(),
+            event_waiters: heapless::Vec::new(),
+            event_streams: heapless::Vec::new(),
+            #[cfg(feature = "client-tokio")]
+            service_watches: None,
             run: true,
             client_id: 0x1234,
-            session_counter: 1,
+            request_sessions: RequestSessions::default(),
             sd_session_id: 1,
             sd_session_has_wrapped: false,
             e2e_registry: Arc::new(Mutex::new(E2ERegistry::new())),
             multicast_loopback: false,
+            unicast_ports: None,
+            tcp_connect_timeout: ClientConfig::new().tcp_connect_timeout,
+            socket_settings: SocketSettings::default(),
             dispatch: crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             timer: TokioTimer,
             phantom: core::marker::PhantomData,
@@ -1794,11 +5210,12 @@ mod tests {
         let (control_sender, mut update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -1844,11 +5261,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -1867,11 +5285,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -1890,11 +5309,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -1915,11 +5335,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -1951,11 +5372,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2032,11 +5454,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2060,11 +5483,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2078,16 +5502,48 @@ mod tests {
         assert_inner_alive(&control_sender).await;
     }
 
+    #[tokio::test]
+    async fn test_send_to_service_past_deadline_is_dropped() {
+        let (control_sender, _update_receiver, run_fut) = TestInner::build(
+            Ipv4Addr::LOCALHOST,
+            Arc::new(Mutex::new(E2ERegistry::new())),
+            ClientConfig::default(),
+            crate::client::bind_dispatch::SpawnerDispatch {
+                factory: TokioTransport,
+                spawner: TokioSpawner,
+                buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
+            },
+            TokioTimer,
+        );
+        let _run_handle = tokio::spawn(run_fut);
+        let (rx, msg) = TestControl::add_endpoint(lh_key(0x1234, 5000), 0x0001, 0);
+        control_sender.send(msg).await.unwrap();
+        rx.recv().await.unwrap().unwrap();
+
+        let deadline = Deadline::after(&TokioTimer, core::time::Duration::ZERO).unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let message = Message::<TestPayload>::new_sd(1, &empty_sd_header());
+        let (send_rx, _resp_rx, msg) =
+            TestControl::send_to_service_before(lh_key(0x1234, 5000), message, Some(deadline));
+        control_sender.send(msg).await.unwrap();
+        assert!(matches!(
+            send_rx.recv().await.unwrap(),
+            Err(Error::DeadlineExceeded)
+        ));
+    }
+
     #[tokio::test]
     async fn test_dropped_receiver_send_to_service_send_complete_continues() {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2120,11 +5576,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            true,
+            ClientConfig::default().with_multicast_loopback(true),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2141,11 +5598,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2167,11 +5625,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2194,11 +5653,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2229,11 +5689,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2270,11 +5731,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2300,16 +5762,47 @@ mod tests {
         assert!(result.is_ok(), "subscribe should auto-bind: {result:?}");
     }
 
+    #[tokio::test]
+    async fn test_unsubscribe_requires_bound_client_port() {
+        let (control_sender, _update_receiver, run_fut) = TestInner::build(
+            Ipv4Addr::LOCALHOST,
+            Arc::new(Mutex::new(E2ERegistry::new())),
+            ClientConfig::default(),
+            crate::client::bind_dispatch::SpawnerDispatch {
+                factory: TokioTransport,
+                spawner: TokioSpawner,
+                buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
+            },
+            TokioTimer,
+        );
+        let _run_handle = tokio::spawn(run_fut);
+
+        // No unicast socket on this port: refusing beats sending a stop
+        // from an endpoint the provider never saw.
+        let (rx, msg) = TestControl::unsubscribe(lh_key(0x1234, 5000), 1, 0x01, 40000);
+        control_sender.send(msg).await.unwrap();
+        let result = tokio::time::timeout(std::time::Duration::from_secs(2), rx.recv())
+            .await
+            .expect("Timed out waiting for Unsubscribe")
+            .expect("Unsubscribe oneshot closed");
+        assert!(
+            matches!(result, Err(Error::UnicastSocketNotBound)),
+            "expected UnicastSocketNotBound, got {result:?}"
+        );
+    }
+
     #[tokio::test]
     async fn test_subscribe_unknown_service_returns_error() {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2330,11 +5823,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2375,11 +5869,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2399,11 +5894,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2430,11 +5926,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2467,11 +5964,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2524,11 +6022,12 @@ mod tests {
         let (control_sender, _update_receiver, run_fut) = TestInner::build(
             Ipv4Addr::LOCALHOST,
             Arc::new(Mutex::new(E2ERegistry::new())),
-            false,
+            ClientConfig::default(),
             crate::client::bind_dispatch::SpawnerDispatch {
                 factory: TokioTransport,
                 spawner: TokioSpawner,
                 buffer_provider: TokioBufferProvider::new(),
+                stream_factory: TokioTransport,
             },
             TokioTimer,
         );
@@ -2617,6 +6116,7 @@ mod tests {
                 TokioTransport,
                 TokioSpawner,
                 TokioBufferProvider,
+                TokioTransport,
             >,
         >;
 
@@ -2631,7 +6131,7 @@ mod tests {
             let service_entry = ServiceEntry {
                 index_first_options_run: 0,
                 index_second_options_run: 0,
-                options_count: OptionsCount::new(1, 0),
+                options_count: OptionsCount::ONE,
                 service_id: SERVICE_ID,
                 instance_id: INSTANCE_ID,
                 major_version: 1,
@@ -2677,8 +6177,11 @@ mod tests {
                 TransportKind::Multicast,
                 someip_header,
                 sd_header,
+                None,
                 &mut session_tracker,
                 &mut service_registry,
+                &VersionPolicies::default(),
+                &mut Subscriptions::new(),
                 &e2e_registry,
                 &update_sender,
             );
@@ -2708,8 +6211,11 @@ mod tests {
             TransportKind::Multicast,
             someip_header,
             stop_header,
+            None,
             &mut session_tracker,
             &mut service_registry,
+            &VersionPolicies::default(),
+            &mut Subscriptions::new(),
             &e2e_registry,
             &update_sender,
         );
@@ -2724,4 +6230,391 @@ mod tests {
             "device B's entry must survive device A's StopOffer"
         );
     }
+
+    /// A `MinimumMinor` policy admits a provider's minor bump but not a
+    /// downgrade, and a rejected re-offer evicts the stale entry.
+    #[test]
+    fn handle_discovery_datagram_applies_version_policy() {
+        use crate::RawPayload;
+        use crate::protocol::sd::{self, Entry, Options, OptionsCount, ServiceEntry};
+        use crate::traits::WireFormat;
+        use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+        type RawInner = Inner<
+            RawPayload,
+            TokioTimer,
+            Arc<Mutex<E2ERegistry>>,
+            TokioChannels,
+            crate::client::bind_dispatch::SpawnerDispatch<
+                TokioTransport,
+                TokioSpawner,
+                TokioBufferProvider,
+                TokioTransport,
+            >,
+        >;
+
+        const SERVICE_ID: u16 = 0x1234;
+        let addr = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 10), 30_509);
+        let key = ServiceEndpointKey::udp(SERVICE_ID, SocketAddr::V4(addr));
+
+        let mut session_tracker = SessionTracker::default();
+        let mut service_registry = ServiceRegistry::default();
+        let mut policies = VersionPolicies::default();
+        policies
+            .set(
+                SERVICE_ID,
+                Some(VersionPolicy::MinimumMinor { major: 1, minor: 2 }),
+            )
+            .unwrap();
+        let e2e_registry: Arc<Mutex<E2ERegistry>> = Arc::new(Mutex::new(E2ERegistry::new()));
+        let (update_sender, _update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<RawPayload>>();
+
+        let mut offer = |request_id: u32, minor_version: u32| {
+            let sd_header = crate::VecSdHeader {
+                flags: sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+                entries: std::vec![Entry::OfferService(ServiceEntry {
+                    index_first_options_run: 0,
+                    index_second_options_run: 0,
+                    options_count: OptionsCount::ONE,
+                    service_id: SERVICE_ID,
+                    instance_id: 1,
+                    major_version: 1,
+                    ttl: 3,
+                    minor_version,
+                })],
+                options: std::vec![Options::IpV4Endpoint {
+                    ip: *addr.ip(),
+                    protocol: sd::TransportProtocol::Udp,
+                    port: addr.port(),
+                }],
+            };
+            let someip_header = protocol::Header::new_sd(request_id, sd_header.required_size());
+            RawInner::handle_discovery_datagram(
+                SocketAddr::new((*addr.ip()).into(), sd::MULTICAST_PORT),
+                TransportKind::Multicast,
+                someip_header,
+                sd_header,
+                None,
+                &mut session_tracker,
+                &mut service_registry,
+                &policies,
+                &mut Subscriptions::new(),
+                &e2e_registry,
+                &update_sender,
+            );
+            service_registry.get(key).map(|info| info.minor_version)
+        };
+
+        assert_eq!(offer(1, 1), None, "minor below the minimum is ignored");
+        assert_eq!(offer(2, 2), Some(2));
+        assert_eq!(offer(3, 5), Some(5), "minor bump stays compatible");
+        assert_eq!(offer(4, 1), None, "downgrade evicts the stale entry");
+    }
+
+    #[test]
+    fn handle_discovery_datagram_records_offer_deadline() {
+        use crate::RawPayload;
+        use crate::protocol::sd::{self, Entry, Options, OptionsCount, ServiceEntry};
+        use crate::traits::WireFormat;
+        use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+        use core::time::Duration;
+
+        type RawInner = Inner<
+            RawPayload,
+            TokioTimer,
+            Arc<Mutex<E2ERegistry>>,
+            TokioChannels,
+            crate::client::bind_dispatch::SpawnerDispatch<
+                TokioTransport,
+                TokioSpawner,
+                TokioBufferProvider,
+                TokioTransport,
+            >,
+        >;
+
+        const SERVICE_ID: u16 = 0x1234;
+        let addr = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 10), 30_509);
+        let key = ServiceEndpointKey::udp(SERVICE_ID, SocketAddr::V4(addr));
+
+        let mut session_tracker = SessionTracker::default();
+        let mut service_registry = ServiceRegistry::default();
+        let e2e_registry: Arc<Mutex<E2ERegistry>> = Arc::new(Mutex::new(E2ERegistry::new()));
+        let (update_sender, _update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<RawPayload>>();
+
+        let mut offer = |request_id: u32, ttl: u32, now: Option<Duration>| {
+            let sd_header = crate::VecSdHeader {
+                flags: sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+                entries: std::vec![Entry::OfferService(ServiceEntry {
+                    index_first_options_run: 0,
+                    index_second_options_run: 0,
+                    options_count: OptionsCount::ONE,
+                    service_id: SERVICE_ID,
+                    instance_id: 1,
+                    major_version: 1,
+                    ttl,
+                    minor_version: 0,
+                })],
+                options: std::vec![Options::IpV4Endpoint {
+                    ip: *addr.ip(),
+                    protocol: sd::TransportProtocol::Udp,
+                    port: addr.port(),
+                }],
+            };
+            let someip_header = protocol::Header::new_sd(request_id, sd_header.required_size());
+            RawInner::handle_discovery_datagram(
+                SocketAddr::new((*addr.ip()).into(), sd::MULTICAST_PORT),
+                TransportKind::Multicast,
+                someip_header,
+                sd_header,
+                now,
+                &mut session_tracker,
+                &mut service_registry,
+                &VersionPolicies::default(),
+                &mut Subscriptions::new(),
+                &e2e_registry,
+                &update_sender,
+            );
+            service_registry.get(key).unwrap().expires_at
+        };
+
+        let now = Some(Duration::from_secs(10));
+        assert_eq!(offer(1, 3, now), Some(Duration::from_secs(13)));
+        assert_eq!(offer(2, sd::TTL_INFINITE, now), None, "infinite TTL");
+        assert_eq!(offer(3, 3, None), None, "timer without a clock");
+    }
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn handle_discovery_datagram_reports_availability_transitions() {
+        use crate::RawPayload;
+        use crate::protocol::sd::{self, Entry, Options, OptionsCount, ServiceEntry};
+        use crate::traits::WireFormat;
+        use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+        type RawInner = Inner<
+            RawPayload,
+            TokioTimer,
+            Arc<Mutex<E2ERegistry>>,
+            TokioChannels,
+            crate::client::bind_dispatch::SpawnerDispatch<
+                TokioTransport,
+                TokioSpawner,
+                TokioBufferProvider,
+                TokioTransport,
+            >,
+        >;
+
+        const SERVICE_ID: u16 = 0x1234;
+        let addr = SocketAddrV4::new(Ipv4Addr::new(127, 0, 0, 10), 30_509);
+        let key = ServiceEndpointKey::udp(SERVICE_ID, SocketAddr::V4(addr));
+
+        let mut session_tracker = SessionTracker::default();
+        let mut service_registry = ServiceRegistry::default();
+        let e2e_registry: Arc<Mutex<E2ERegistry>> = Arc::new(Mutex::new(E2ERegistry::new()));
+        let (update_sender, mut update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<RawPayload>>();
+
+        let mut receive =
+            |service_registry: &mut ServiceRegistry, request_id: u32, is_offer: bool| {
+                let service_entry = ServiceEntry {
+                    index_first_options_run: 0,
+                    index_second_options_run: 0,
+                    options_count: OptionsCount::ONE,
+                    service_id: SERVICE_ID,
+                    instance_id: 1,
+                    major_version: 1,
+                    ttl: 3,
+                    minor_version: 0,
+                };
+                let sd_header = crate::VecSdHeader {
+                    flags: sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+                    entries: std::vec![if is_offer {
+                        Entry::OfferService(service_entry)
+                    } else {
+                        Entry::StopOfferService(service_entry)
+                    }],
+                    options: std::vec![Options::IpV4Endpoint {
+                        ip: *addr.ip(),
+                        protocol: sd::TransportProtocol::Udp,
+                        port: addr.port(),
+                    }],
+                };
+                let someip_header = protocol::Header::new_sd(request_id, sd_header.required_size());
+                RawInner::handle_discovery_datagram(
+                    SocketAddr::new((*addr.ip()).into(), sd::MULTICAST_PORT),
+                    TransportKind::Multicast,
+                    someip_header,
+                    sd_header,
+                    None,
+                    &mut session_tracker,
+                    service_registry,
+                    &VersionPolicies::default(),
+                    &mut Subscriptions::new(),
+                    &e2e_registry,
+                    &update_sender,
+                );
+            };
+        let mut transitions = || {
+            let mut transitions = std::vec::Vec::new();
+            while let Ok(update) = update_receiver.0.try_recv() {
+                match update {
+                    ClientUpdate::ServiceAvailable { key: got, .. } => {
+                        assert_eq!(got, key);
+                        transitions.push(None);
+                    }
+                    ClientUpdate::ServiceUnavailable {
+                        key: got, reason, ..
+                    } => {
+                        assert_eq!(got, key);
+                        transitions.push(Some(reason));
+                    }
+                    _ => {}
+                }
+            }
+            transitions
+        };
+
+        receive(&mut service_registry, 1, true);
+        assert_eq!(transitions(), [None], "first offer");
+        receive(&mut service_registry, 2, true);
+        assert!(transitions().is_empty(), "renewal");
+        receive(&mut service_registry, 3, false);
+        assert_eq!(transitions(), [Some(UnavailableReason::StopOffer)]);
+        receive(&mut service_registry, 4, false);
+        assert!(transitions().is_empty(), "nothing left to stop");
+
+        // SD leaves a static endpoint alone.
+        let static_info = ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version: 0xFF,
+            minor_version: 0xFFFF_FFFF,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: true,
+            load_balancing: None,
+        };
+        service_registry.insert(key, static_info).unwrap();
+        receive(&mut service_registry, 5, true);
+        receive(&mut service_registry, 6, false);
+        assert!(transitions().is_empty());
+        let info = service_registry.get(key).expect("kept through the stop");
+        assert!(info.is_static && info.expires_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_offer_expiring_emitted_once_within_margin() {
+        use core::time::Duration;
+
+        let mut inner = make_inner_for_test();
+        let (update_sender, mut update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<TestPayload>>();
+        inner.update_sender = update_sender;
+        let now = inner.timer.now().unwrap();
+        let key = lh_key(0x1234, 30_509);
+        inner
+            .service_registry
+            .insert(
+                key,
+                ServiceEndpointInfo {
+                    instance_id: 1,
+                    local_port: 0,
+                    major_version: 1,
+                    minor_version: 0,
+                    expires_at: Some(now + Duration::from_secs(1)),
+                    expiry_notified: false,
+                    probe: ProbeState::default(),
+                    is_static: false,
+                    load_balancing: None,
+                },
+            )
+            .unwrap();
+
+        inner.emit_offer_expiring();
+        assert!(
+            update_receiver.0.try_recv().is_err(),
+            "no margin configured, no update"
+        );
+
+        inner.offer_expiry_margin = Some(Duration::from_secs(2));
+        inner.emit_offer_expiring();
+        inner.emit_offer_expiring();
+        match update_receiver.0.try_recv() {
+            Ok(ClientUpdate::OfferExpiring {
+                key: got,
+                instance_id: 1,
+                remaining,
+            }) => {
+                assert_eq!(got, key);
+                assert!(remaining <= Duration::from_secs(1));
+            }
+            other => panic!("expected OfferExpiring, got {other:?}"),
+        }
+        assert!(update_receiver.0.try_recv().is_err(), "emitted only once");
+    }
+
+    #[tokio::test]
+    async fn test_expired_offers_become_unavailable() {
+        use core::time::Duration;
+
+        let mut inner = make_inner_for_test();
+        let (update_sender, mut update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<TestPayload>>();
+        inner.update_sender = update_sender;
+        let now = inner.timer.now().unwrap();
+        let offer = |expires_at| ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
+        };
+        let expired = lh_key(0x1234, 30_510);
+        let live = lh_key(0x1235, 30_511);
+        let manual = lh_key(0x1236, 30_512);
+        inner
+            .service_registry
+            .insert(expired, offer(Some(now)))
+            .unwrap();
+        inner
+            .service_registry
+            .insert(live, offer(Some(now + Duration::from_mins(1))))
+            .unwrap();
+        inner.service_registry.insert(manual, offer(None)).unwrap();
+
+        // Offers lapse whether or not the janitor runs.
+        inner.expire_offers();
+        assert!(inner.service_registry.get(expired).is_none());
+        assert!(inner.service_registry.get(live).is_some());
+        assert!(inner.service_registry.get(manual).is_some());
+        match update_receiver.0.try_recv() {
+            Ok(ClientUpdate::ServiceUnavailable {
+                key,
+                instance_id: 1,
+                reason: UnavailableReason::Expired,
+            }) => assert_eq!(key, expired),
+            other => panic!("expected ServiceUnavailable, got {other:?}"),
+        }
+        assert!(update_receiver.0.try_recv().is_err());
+
+        inner.janitor = Some(Janitor::new(
+            JanitorConfig::new().with_interval(Duration::from_mins(1)),
+        ));
+        inner.run_janitor();
+        assert_eq!(
+            inner.janitor_stats,
+            JanitorStats {
+                sweeps: 1,
+                discovery_entries_pruned: 1,
+                ..JanitorStats::default()
+            }
+        );
+    }
 }
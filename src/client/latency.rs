@@ -0,0 +1,114 @@
+//! Round-trip statistics of [`Client::measure_latency`](super::Client::measure_latency).
+
+use core::time::Duration;
+
+/// The echo requests [`Client::measure_latency`](super::Client::measure_latency)
+/// sends: method, interface version, payload and how long to wait for
+/// each reply.
+///
+/// Pair it with a provider that answers the method with its payload,
+/// such as a `MethodRouter::echo` route of this crate's server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatencyProbe<'a> {
+    pub method_id: u16,
+    /// Defaults to `1`.
+    pub interface_version: u8,
+    /// Sent with every request, empty by default. Its size is part of
+    /// what is measured.
+    pub payload: &'a [u8],
+    /// A request unanswered for this long counts as lost. Defaults to one
+    /// second.
+    pub timeout: Duration,
+}
+
+impl<'a> LatencyProbe<'a> {
+    #[must_use]
+    pub const fn new(method_id: u16) -> Self {
+        Self {
+            method_id,
+            interface_version: 1,
+            payload: &[],
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_interface_version(mut self, interface_version: u8) -> Self {
+        self.interface_version = interface_version;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_payload(mut self, payload: &'a [u8]) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Round-trip times of a series of requests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatencyStats {
+    /// Requests answered in time, which the times below cover.
+    pub answered: usize,
+    /// Requests that timed out.
+    pub lost: usize,
+    pub min: Duration,
+    pub median: Duration,
+    /// 99th percentile, by nearest rank: with fewer than 100 answers this
+    /// is the slowest one.
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Statistics of the round-trip times in `samples`, which are sorted
+    /// in place. `None` if `samples` is empty.
+    #[must_use]
+    pub fn from_samples(samples: &mut [Duration], lost: usize) -> Option<Self> {
+        samples.sort_unstable();
+        let (&min, &max) = (samples.first()?, samples.last()?);
+        let rank = |percent: usize| samples[(samples.len() * percent).div_ceil(100) - 1];
+        Some(Self {
+            answered: samples.len(),
+            lost,
+            min,
+            median: rank(50),
+            p99: rank(99),
+            max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_are_taken_by_nearest_rank() {
+        let mut samples: std::vec::Vec<Duration> =
+            (1..=200).rev().map(Duration::from_micros).collect();
+        let stats = LatencyStats::from_samples(&mut samples, 3).unwrap();
+        assert_eq!(
+            stats,
+            LatencyStats {
+                answered: 200,
+                lost: 3,
+                min: Duration::from_micros(1),
+                median: Duration::from_micros(100),
+                p99: Duration::from_micros(198),
+                max: Duration::from_micros(200),
+            }
+        );
+
+        let mut one = [Duration::from_millis(2)];
+        let stats = LatencyStats::from_samples(&mut one, 0).unwrap();
+        assert_eq!((stats.median, stats.p99), (one[0], one[0]));
+        assert_eq!(LatencyStats::from_samples(&mut [], 5), None);
+    }
+}
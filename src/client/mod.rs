@@ -37,13 +37,41 @@
 //!
 //! See `docs/simple_someip/plans/2026-06-09-phase22-125-memory-reduction-design.md`.
 mod bind_dispatch;
+mod config;
+mod dedup;
+mod dependencies;
+mod discovery_cache;
 mod error;
+mod event_streams;
+mod eventgroups;
 mod inner;
+mod latency;
+mod provider_selection;
+mod reachability;
+mod receive_ports;
+mod request_queue;
+mod retry;
 mod service_registry;
+mod service_wait;
+#[cfg(feature = "client-tokio")]
+mod service_watch;
 mod session;
 mod socket_manager;
-
+mod state_snapshot;
+mod subscriptions;
+mod tcp_in_flight;
+mod update_lanes;
+mod version_policy;
+
+pub use config::{ClientConfig, OverflowPolicy};
+pub use dedup::DUPLICATE_FILTER_CAP;
+pub use dependencies::{DEPENDENCIES_CAP, Dependencies, Dependency, DependencyState, Readiness};
+pub use discovery_cache::UnavailableReason;
 pub use error::Error;
+pub use event_streams::EVENT_STREAMS_CAP;
+pub use eventgroups::{
+    EVENTGROUP_EVENTS_CAP, EVENTGROUP_LAYOUT_CAP, EventgroupLayout, EventgroupSelection,
+};
 /// Internal control message exchanged between [`Client`] handles and
 /// the run-loop. Exposed (rather than `pub(super)`) so callers can
 /// declare static channel pools for it via
@@ -51,16 +79,36 @@ pub use error::Error;
 /// reference this type directly — the `define_static_channels!` macro
 /// (under `feature = "bare_metal"`) names it for them.
 pub use inner::ControlMessage;
-pub use service_registry::ServiceEndpointKey;
+pub use latency::{LatencyProbe, LatencyStats};
+pub use provider_selection::{PROVIDER_SELECTIONS_CAP, ProviderSelection};
+pub use reachability::ProbeConfig;
+pub use receive_ports::RECEIVE_PORTS_CAP;
+pub use retry::{RequestPolicy, TIMED_REQUESTS_CAP};
+pub use service_registry::{RESOLVED_ENDPOINTS_CAP, ServiceEndpointKey};
+pub use service_wait::{EVENT_WAITERS_CAP, FindPolicy, SERVICE_WAITERS_CAP};
+#[cfg(feature = "client-tokio")]
+pub use service_watch::SERVICE_WATCHES_CAP;
+pub use session::SESSIONLESS_SERVICES_CAP;
 /// Per-socket message types exposed for the same reason as
 /// [`ControlMessage`] — see its docstring.
 pub use socket_manager::{ReceivedMessage, SendMessage};
+pub use state_snapshot::{
+    ClientSnapshot, DiscoveredOffer, PENDING_REQUESTS_CAP, PendingRequest, TrackedSubscription,
+};
+pub use subscriptions::{
+    SUBSCRIBE_ACK_TIMEOUT, SUBSCRIBE_MANY_CAP, SUBSCRIPTIONS_CAP, SubscriptionState,
+};
+pub use tcp_in_flight::TCP_IN_FLIGHT_CAP;
+pub use update_lanes::{
+    DropPolicy, LaneConfig, UPDATE_LANE_CAP, UpdateCategory, UpdateLanes, UpdateLanesConfig,
+};
+pub use version_policy::{VERSION_POLICIES_CAP, VersionPolicy};
 
 use crate::Timer;
 #[cfg(feature = "client-tokio")]
 use crate::e2e::E2ERegistry;
 use crate::e2e::{E2ECheckStatus, E2EKey, E2EProfile};
-use crate::log::info;
+use crate::log::{info, warn};
 #[cfg(feature = "client-tokio")]
 use crate::tokio_transport::{TokioChannels, TokioSpawner, TokioTimer};
 use crate::transport::{
@@ -76,7 +124,7 @@ use std::sync::{Arc, Mutex, RwLock};
 /// Marker trait declaring the channel-pool entries a [`ChannelFactory`]
 /// must declare for [`Client`] to compile against it. End users do not
 /// implement this trait directly: it has a blanket impl over any
-/// [`ChannelFactory`] for which all seven required `OneshotPooled` /
+/// [`ChannelFactory`] for which all the required `OneshotPooled` /
 /// `BoundedPooled` / `UnboundedPooled` entries exist.
 ///
 /// # Required entries
@@ -89,11 +137,21 @@ use std::sync::{Arc, Mutex, RwLock};
 /// | `oneshot` | `Result<(), client::Error>` | per-pool default |
 /// | `oneshot` | `Result<P, client::Error>` | per-pool default |
 /// | `oneshot` | `Result<protocol::sd::RebootFlag, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<Option<core::time::Duration>, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<PeerTable, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<ServiceEndpointKey, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<JanitorStats, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<DropStats, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<ClientSnapshot, client::Error>` | per-pool default |
+/// | `oneshot` | `Result<SubscriptionState, client::Error>` | per-pool default |
 /// | `bounded` | `(ControlMessage<P, C>, 4)` | per-pool default |
 /// | `bounded` | `(SendMessage<P, C>, 16)` | per-pool default |
 /// | `bounded` | `(Result<ReceivedMessage<P>, client::Error>, 16)` | per-pool default |
 /// | `unbounded` | `ClientUpdate<P>` | per-pool default |
 ///
+/// [`Client::event_stream`] additionally needs an `unbounded` pool of
+/// `Message<P>`, one slot per stream open at a time.
+///
 /// where `C` is the channel-factory type generated by
 /// `define_static_channels!`. `bare_metal` consumers will typically
 /// look at the `examples/bare_metal_client/` example for a copy-pasteable
@@ -104,7 +162,7 @@ use std::sync::{Arc, Mutex, RwLock};
 /// Today this trait is **discoverability-only**: stable Rust does not
 /// elaborate where-clause bounds on a trait, so a generic function
 /// taking `C: ClientChannelTypes<P>` cannot use that bound to satisfy
-/// the seven underlying `OneshotPooled` / `BoundedPooled` /
+/// the underlying `OneshotPooled` / `BoundedPooled` /
 /// `UnboundedPooled` constraints. Each `impl<…> Client<…>` block
 /// repeats the bounds inline, and downstream witness functions would
 /// have to do the same.
@@ -125,6 +183,13 @@ where
     Result<(), Error>: OneshotPooled<Self>,
     Result<P, Error>: OneshotPooled<Self>,
     Result<protocol::sd::RebootFlag, Error>: OneshotPooled<Self>,
+    Result<Option<core::time::Duration>, Error>: OneshotPooled<Self>,
+    Result<crate::PeerTable, Error>: OneshotPooled<Self>,
+    Result<ServiceEndpointKey, Error>: OneshotPooled<Self>,
+    Result<crate::JanitorStats, Error>: OneshotPooled<Self>,
+    Result<crate::DropStats, Error>: OneshotPooled<Self>,
+    Result<ClientSnapshot, Error>: OneshotPooled<Self>,
+    Result<SubscriptionState, Error>: OneshotPooled<Self>,
     ControlMessage<P, Self>: BoundedPooled<Self, 4>,
     SendMessage<P, Self>: BoundedPooled<Self, 16>,
     Result<ReceivedMessage<P>, Error>: BoundedPooled<Self, 16>,
@@ -139,6 +204,13 @@ where
     Result<(), Error>: OneshotPooled<C>,
     Result<P, Error>: OneshotPooled<C>,
     Result<protocol::sd::RebootFlag, Error>: OneshotPooled<C>,
+    Result<Option<core::time::Duration>, Error>: OneshotPooled<C>,
+    Result<crate::PeerTable, Error>: OneshotPooled<C>,
+    Result<ServiceEndpointKey, Error>: OneshotPooled<C>,
+    Result<crate::JanitorStats, Error>: OneshotPooled<C>,
+    Result<crate::DropStats, Error>: OneshotPooled<C>,
+    Result<ClientSnapshot, Error>: OneshotPooled<C>,
+    Result<SubscriptionState, Error>: OneshotPooled<C>,
     ControlMessage<P, C>: BoundedPooled<C, 4>,
     SendMessage<P, C>: BoundedPooled<C, 16>,
     Result<ReceivedMessage<P>, Error>: BoundedPooled<C, 16>,
@@ -165,7 +237,8 @@ impl<P: Send + 'static, C: ChannelFactory> PendingResponse<P, C> {
     /// # Errors
     ///
     /// Returns the same errors as the request itself (e.g. deserialization
-    /// failure). Returns [`Error::Capacity`] with tag `"pending_responses"`
+    /// failure). Returns [`Error::ErrorResponse`] if the provider answered
+    /// with an `ERROR` message. Returns [`Error::Capacity`] with tag `"pending_responses"`
     /// if the inner loop's response-tracking map was full when the request
     /// was sent — the UDP send still went out, but the reply (if any)
     /// arrives on [`ClientUpdates`] rather than this oneshot.
@@ -178,6 +251,186 @@ impl<P: Send + 'static, C: ChannelFactory> PendingResponse<P, C> {
     }
 }
 
+/// A subscription made with [`Client::subscribe`], stopped when the
+/// handle is dropped.
+///
+/// The client tracks the subscription: it waits for the provider's
+/// answer, which [`acknowledged`](Self::acknowledged) reports, and once
+/// acknowledged re-sends it halfway through its TTL. Dropping the handle
+/// sends the `StopSubscribeEventgroup` without waiting for it to go out;
+/// if the control channel is full at that moment the stop is skipped and
+/// the subscription lapses with its TTL instead. Use
+/// [`unsubscribe`](Self::unsubscribe) to wait for the stop, or
+/// [`detach`](Self::detach) to keep the subscription alive without a
+/// handle.
+///
+/// A later subscription to the same eventgroup replaces this one; the
+/// handle then no longer stops it, neither on drop nor through
+/// [`unsubscribe`](Self::unsubscribe).
+#[must_use = "dropping the handle stops the subscription; call `detach` to keep it"]
+pub struct SubscriptionHandle<P, C>
+where
+    P: PayloadWireFormat + Send + 'static,
+    C: ChannelFactory,
+    Result<(), Error>: OneshotPooled<C>,
+{
+    control_sender: C::BoundedSender<inner::ControlMessage<P, C>, 4>,
+    key: ServiceEndpointKey,
+    event_group_id: u16,
+    /// Id the subscription is tracked under.
+    id: u32,
+    /// The provider's answer, until [`Self::acknowledged`] took it.
+    ack: Option<C::OneshotReceiver<Result<(), Error>>>,
+    /// Whether the answer taken was an Ack.
+    acknowledged: bool,
+    /// Set by [`Self::detach`] and [`Self::unsubscribe`]: nothing to
+    /// stop on drop.
+    detached: bool,
+}
+
+impl<P, C> core::fmt::Debug for SubscriptionHandle<P, C>
+where
+    P: PayloadWireFormat + Send + 'static,
+    C: ChannelFactory,
+    Result<(), Error>: OneshotPooled<C>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SubscriptionHandle")
+            .field("key", &self.key)
+            .field("event_group_id", &self.event_group_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, C> SubscriptionHandle<P, C>
+where
+    P: PayloadWireFormat + Send + 'static,
+    C: ChannelFactory,
+    Result<(), Error>: OneshotPooled<C>,
+    Result<SubscriptionState, Error>: OneshotPooled<C>,
+{
+    /// Endpoint of the service subscribed to.
+    #[must_use]
+    pub fn key(&self) -> ServiceEndpointKey {
+        self.key
+    }
+
+    /// Eventgroup subscribed to.
+    #[must_use]
+    pub fn event_group_id(&self) -> u16 {
+        self.event_group_id
+    }
+
+    /// Where the subscription stands right now.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited.
+    pub async fn state(&self) -> Result<SubscriptionState, Error> {
+        // Built here rather than through `ControlMessage`'s constructors,
+        // which require every channel pool the client uses.
+        let (sender, response) = C::oneshot();
+        let message = inner::ControlMessage::QuerySubscription {
+            key: self.key,
+            event_group_id: self.event_group_id,
+            id: Some(self.id),
+            response: sender,
+        };
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Waits for the provider to answer the subscription. Resolves at
+    /// once if an earlier call already saw the answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SubscriptionRejected`] if the provider answered
+    /// with a `SubscribeEventgroupNack`, and [`Error::Timeout`] if it did
+    /// not answer within [`SUBSCRIBE_ACK_TIMEOUT`]; the subscription is
+    /// then no longer tracked, and later calls return
+    /// [`Error::NotSubscribed`].
+    /// Returns [`Error::NotSubscribed`] if the subscription was stopped
+    /// before the answer, and [`Error::SubscriptionReplaced`] if a later
+    /// subscription to the same eventgroup replaced it.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited.
+    pub async fn acknowledged(&mut self) -> Result<(), Error> {
+        let Some(ack) = self.ack.take() else {
+            return if self.acknowledged {
+                Ok(())
+            } else {
+                Err(Error::NotSubscribed)
+            };
+        };
+        let outcome = ack.recv().await.map_err(|_| Error::Shutdown)?;
+        self.acknowledged = outcome.is_ok();
+        outcome
+    }
+
+    /// Stops the subscription and waits for the
+    /// `StopSubscribeEventgroup` to go out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSubscribed`] if the subscription is no longer
+    /// tracked, e.g. because the provider rejected it or a later
+    /// subscription to the same eventgroup replaced it.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited.
+    pub async fn unsubscribe(mut self) -> Result<(), Error> {
+        self.detached = true;
+        let (sender, response) = C::oneshot();
+        let message = inner::ControlMessage::StopSubscription {
+            key: self.key,
+            event_group_id: self.event_group_id,
+            id: Some(self.id),
+            response: sender,
+        };
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Drops the handle but keeps the subscription: the client goes on
+    /// refreshing it until [`Client::unsubscribe`] stops it.
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+}
+
+impl<P, C> Drop for SubscriptionHandle<P, C>
+where
+    P: PayloadWireFormat + Send + 'static,
+    C: ChannelFactory,
+    Result<(), Error>: OneshotPooled<C>,
+{
+    fn drop(&mut self) {
+        if self.detached {
+            return;
+        }
+        let (response, _) = C::oneshot();
+        let message = inner::ControlMessage::StopSubscription {
+            key: self.key,
+            event_group_id: self.event_group_id,
+            id: Some(self.id),
+            response,
+        };
+        if self.control_sender.try_send(message).is_err() {
+            warn!(
+                "Could not stop the subscription to eventgroup 0x{:04X} of service 0x{:04X}; it lapses with its TTL",
+                self.event_group_id, self.key.service_id
+            );
+        }
+    }
+}
+
 /// A discovery message together with its source address and SOME/IP header.
 pub struct DiscoveryMessage<P: PayloadWireFormat> {
     /// The network address this discovery message was received from.
@@ -202,7 +455,10 @@ impl<P: PayloadWireFormat> core::fmt::Debug for DiscoveryMessage<P> {
 pub enum ClientUpdate<P: PayloadWireFormat> {
     /// Discovery message received.
     DiscoveryUpdated(DiscoveryMessage<P>),
-    /// A remote sender has rebooted (detected via SD session tracking).
+    /// A remote sender has rebooted, detected from the reboot flag and
+    /// session IDs of its SD messages. The client has already subscribed
+    /// again to the eventgroups it had subscribed at that sender; state the
+    /// application cached about it, such as field values, may be stale.
     SenderRebooted(SocketAddr),
     /// Unicast message received.
     ///
@@ -218,6 +474,69 @@ pub enum ClientUpdate<P: PayloadWireFormat> {
         /// way to attribute a unicast event to a specific device, since the
         /// SOME/IP header carries no instance id.
         source: SocketAddr,
+        /// Local port of the socket it arrived on. A client subscribed to
+        /// one eventgroup from two ports, at a provider that sends to both
+        /// (see `EventPublisher::set_standby_subscriber`), tells the two
+        /// paths of each notification apart by it.
+        local_port: u16,
+    },
+    /// A discovered offer is about to lapse without having been renewed.
+    ///
+    /// Emitted once per offer when its remaining TTL drops to the margin
+    /// set with [`Client::set_offer_expiry_margin`]; a renewed offer
+    /// re-arms it. Requires a [`Timer`] that implements
+    /// [`Timer::now`].
+    OfferExpiring {
+        /// The provider endpoint whose offer is expiring.
+        key: ServiceEndpointKey,
+        /// Instance id carried by that offer.
+        instance_id: u16,
+        /// Time left before the offer lapses.
+        remaining: core::time::Duration,
+    },
+    /// A service instance was discovered: an offer arrived for an
+    /// endpoint the client had no offer cached for. Renewals of a cached
+    /// offer are not reported.
+    ServiceAvailable {
+        /// The provider endpoint now offering the service.
+        key: ServiceEndpointKey,
+        /// Instance id carried by the offer.
+        instance_id: u16,
+    },
+    /// A cached offer went away, for the given reason. Offers lapse once
+    /// their TTL passes without a renewal, which needs a [`Timer`] that
+    /// implements [`Timer::now`].
+    ServiceUnavailable {
+        /// The provider endpoint no longer offering the service.
+        key: ServiceEndpointKey,
+        /// Instance id of the cached offer.
+        instance_id: u16,
+        /// Why the offer went away.
+        reason: UnavailableReason,
+    },
+    /// A probed endpoint changed reachability.
+    ///
+    /// Only emitted while probing is enabled with
+    /// [`Client::set_reachability_probe`]: `reachable: false` once the
+    /// endpoint missed enough consecutive probes to count as degraded,
+    /// `reachable: true` when a degraded endpoint answers again.
+    Reachability {
+        /// The probed provider endpoint.
+        key: ServiceEndpointKey,
+        /// Whether the endpoint answers its probes again.
+        reachable: bool,
+    },
+    /// The offer of a subscribed service came back after going away, by
+    /// a `StopOffer` or its TTL lapsing: the provider may have restarted
+    /// and forgotten its subscribers, so the client subscribed to the
+    /// eventgroup again. The offer may be back at another endpoint, for
+    /// the same instance and major version; the subscription moves there.
+    /// Requires a [`Timer`] that implements [`Timer::now`].
+    Resubscribed {
+        /// The provider endpoint offering the service again.
+        key: ServiceEndpointKey,
+        /// The eventgroup subscribed to again.
+        event_group_id: u16,
     },
     /// The client encountered an error.
     Error(Error),
@@ -232,11 +551,51 @@ impl<P: PayloadWireFormat> core::fmt::Debug for ClientUpdate<P> {
                 message,
                 e2e_status,
                 source,
+                local_port,
             } => f
                 .debug_struct("Unicast")
                 .field("message", message)
                 .field("e2e_status", e2e_status)
                 .field("source", source)
+                .field("local_port", local_port)
+                .finish(),
+            Self::OfferExpiring {
+                key,
+                instance_id,
+                remaining,
+            } => f
+                .debug_struct("OfferExpiring")
+                .field("key", key)
+                .field("instance_id", instance_id)
+                .field("remaining", remaining)
+                .finish(),
+            Self::ServiceAvailable { key, instance_id } => f
+                .debug_struct("ServiceAvailable")
+                .field("key", key)
+                .field("instance_id", instance_id)
+                .finish(),
+            Self::ServiceUnavailable {
+                key,
+                instance_id,
+                reason,
+            } => f
+                .debug_struct("ServiceUnavailable")
+                .field("key", key)
+                .field("instance_id", instance_id)
+                .field("reason", reason)
+                .finish(),
+            Self::Reachability { key, reachable } => f
+                .debug_struct("Reachability")
+                .field("key", key)
+                .field("reachable", reachable)
+                .finish(),
+            Self::Resubscribed {
+                key,
+                event_group_id,
+            } => f
+                .debug_struct("Resubscribed")
+                .field("key", key)
+                .field("event_group_id", event_group_id)
                 .finish(),
             Self::Error(err) => f.debug_tuple("Error").field(err).finish(),
         }
@@ -248,7 +607,12 @@ impl<P: PayloadWireFormat> core::fmt::Debug for ClientUpdate<P> {
 /// Returned by `Client::new` (under `client-tokio`) or
 /// `Client::new_with_deps` / `Client::new_with_deps_local` (under
 /// `client`). Call [`recv`](Self::recv) to receive
-/// discovery, unicast, and error updates.
+/// discovery, unicast, and error updates, or use it as a
+/// [`Stream`](futures_util::Stream) with stream combinators.
+///
+/// Both are cancel-safe: an update is only taken off the channel by the
+/// poll that returns it, so a `recv()` dropped by a losing `select!` arm
+/// never loses one.
 pub struct ClientUpdates<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> {
     update_receiver: C::UnboundedReceiver<ClientUpdate<MessageDefinitions>>,
 }
@@ -269,7 +633,66 @@ impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory>
     /// Returns `None` when the inner loop has exited (all `Client` handles
     /// dropped and the event loop finished draining).
     pub async fn recv(&mut self) -> Option<ClientUpdate<MessageDefinitions>> {
-        UnboundedRecv::recv(&mut self.update_receiver).await
+        core::future::poll_fn(|cx| self.update_receiver.poll_recv(cx)).await
+    }
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> futures_util::Stream
+    for ClientUpdates<MessageDefinitions, C>
+where
+    C::UnboundedReceiver<ClientUpdate<MessageDefinitions>>: Unpin,
+{
+    type Item = ClientUpdate<MessageDefinitions>;
+
+    fn poll_next(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Option<Self::Item>> {
+        self.get_mut().update_receiver.poll_recv(cx)
+    }
+}
+
+/// Notifications of one eventgroup, opened with [`Client::event_stream`].
+///
+/// Call [`recv`](Self::recv) or use it as a
+/// [`Stream`](futures_util::Stream); both are cancel-safe, as for
+/// [`ClientUpdates`]. Dropping it closes the stream: later notifications
+/// go to the update stream again.
+pub struct EventStream<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> {
+    receiver: C::UnboundedReceiver<Message<MessageDefinitions>>,
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> core::fmt::Debug
+    for EventStream<MessageDefinitions, C>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EventStream").finish_non_exhaustive()
+    }
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory>
+    EventStream<MessageDefinitions, C>
+{
+    /// Waits for the next notification of the eventgroup.
+    ///
+    /// Returns `None` when the client's run-loop has exited.
+    pub async fn recv(&mut self) -> Option<Message<MessageDefinitions>> {
+        core::future::poll_fn(|cx| self.receiver.poll_recv(cx)).await
+    }
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> futures_util::Stream
+    for EventStream<MessageDefinitions, C>
+where
+    C::UnboundedReceiver<Message<MessageDefinitions>>: Unpin,
+{
+    type Item = Message<MessageDefinitions>;
+
+    fn poll_next(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Option<Self::Item>> {
+        self.get_mut().receiver.poll_recv(cx)
     }
 }
 
@@ -288,7 +711,7 @@ impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory>
 ///
 /// All five fields are public so callers can construct the struct
 /// inline; there's no builder ceremony beyond the field assignments.
-pub struct ClientDeps<F, Tm, R, I, Sp, BP>
+pub struct ClientDeps<F, Tm, R, I, Sp, BP, St = crate::transport::NoStreams>
 where
     F: TransportFactory,
     Tm: Timer,
@@ -312,6 +735,10 @@ where
     /// heap-provisioned on the tokio path. One provider per client,
     /// reused for every `bind_*`.
     pub buffer_provider: BP,
+    /// Opens the TCP connections used to reach services offered over
+    /// TCP. [`NoStreams`](crate::transport::NoStreams) for a UDP-only
+    /// client.
+    pub stream_factory: St,
 }
 
 /// Tokio-defaulted constructor.
@@ -343,6 +770,7 @@ impl
         Arc<RwLock<Ipv4Addr>>,
         TokioSpawner,
         crate::tokio_transport::TokioBufferProvider,
+        crate::tokio_transport::TokioTransport,
     >
 {
     /// Build a `ClientDeps` with the tokio defaults.
@@ -362,6 +790,7 @@ impl
             interface: Arc::new(RwLock::new(interface)),
             spawner: TokioSpawner,
             buffer_provider: crate::tokio_transport::TokioBufferProvider::new(),
+            stream_factory: crate::tokio_transport::TokioTransport,
         }
     }
 }
@@ -386,7 +815,7 @@ impl
 /// # let _ = deps;
 /// # }
 /// ```
-impl<F, Tm, R, I, Sp, BP> ClientDeps<F, Tm, R, I, Sp, BP>
+impl<F, Tm, R, I, Sp, BP, St> ClientDeps<F, Tm, R, I, Sp, BP, St>
 where
     F: TransportFactory,
     Tm: Timer,
@@ -399,7 +828,7 @@ where
     pub fn with_factory<F2: TransportFactory>(
         self,
         factory: F2,
-    ) -> ClientDeps<F2, Tm, R, I, Sp, BP> {
+    ) -> ClientDeps<F2, Tm, R, I, Sp, BP, St> {
         ClientDeps {
             factory,
             timer: self.timer,
@@ -407,12 +836,13 @@ where
             interface: self.interface,
             spawner: self.spawner,
             buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
         }
     }
 
     /// Replace the `timer` field, returning a `ClientDeps` over the new
     /// timer type.
-    pub fn with_timer<Tm2: Timer>(self, timer: Tm2) -> ClientDeps<F, Tm2, R, I, Sp, BP> {
+    pub fn with_timer<Tm2: Timer>(self, timer: Tm2) -> ClientDeps<F, Tm2, R, I, Sp, BP, St> {
         ClientDeps {
             factory: self.factory,
             timer,
@@ -420,6 +850,7 @@ where
             interface: self.interface,
             spawner: self.spawner,
             buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
         }
     }
 
@@ -428,7 +859,7 @@ where
     pub fn with_e2e_registry<R2: E2ERegistryHandle>(
         self,
         e2e_registry: R2,
-    ) -> ClientDeps<F, Tm, R2, I, Sp, BP> {
+    ) -> ClientDeps<F, Tm, R2, I, Sp, BP, St> {
         ClientDeps {
             factory: self.factory,
             timer: self.timer,
@@ -436,6 +867,7 @@ where
             interface: self.interface,
             spawner: self.spawner,
             buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
         }
     }
 
@@ -444,7 +876,7 @@ where
     pub fn with_interface<I2: InterfaceHandle>(
         self,
         interface: I2,
-    ) -> ClientDeps<F, Tm, R, I2, Sp, BP> {
+    ) -> ClientDeps<F, Tm, R, I2, Sp, BP, St> {
         ClientDeps {
             factory: self.factory,
             timer: self.timer,
@@ -452,6 +884,7 @@ where
             interface,
             spawner: self.spawner,
             buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
         }
     }
 
@@ -463,7 +896,7 @@ where
     /// [`Client::new_with_deps_local`] expects a `LocalSpawner` and
     /// the bound is enforced here at the builder call site rather
     /// than deferred to construction.
-    pub fn with_spawner<Sp2: Spawner>(self, spawner: Sp2) -> ClientDeps<F, Tm, R, I, Sp2, BP> {
+    pub fn with_spawner<Sp2: Spawner>(self, spawner: Sp2) -> ClientDeps<F, Tm, R, I, Sp2, BP, St> {
         ClientDeps {
             factory: self.factory,
             timer: self.timer,
@@ -471,6 +904,7 @@ where
             interface: self.interface,
             spawner,
             buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
         }
     }
 
@@ -483,7 +917,7 @@ where
     pub fn with_local_spawner<Sp2: crate::transport::LocalSpawner>(
         self,
         spawner: Sp2,
-    ) -> ClientDeps<F, Tm, R, I, Sp2, BP> {
+    ) -> ClientDeps<F, Tm, R, I, Sp2, BP, St> {
         ClientDeps {
             factory: self.factory,
             timer: self.timer,
@@ -491,6 +925,7 @@ where
             interface: self.interface,
             spawner,
             buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
         }
     }
 
@@ -501,7 +936,7 @@ where
     pub fn with_buffer_provider<BP2: crate::transport::BufferProvider>(
         self,
         buffer_provider: BP2,
-    ) -> ClientDeps<F, Tm, R, I, Sp, BP2> {
+    ) -> ClientDeps<F, Tm, R, I, Sp, BP2, St> {
         ClientDeps {
             factory: self.factory,
             timer: self.timer,
@@ -509,6 +944,45 @@ where
             interface: self.interface,
             spawner: self.spawner,
             buffer_provider,
+            stream_factory: self.stream_factory,
+        }
+    }
+
+    /// Wrap the `factory` so every datagram the client's sockets send or
+    /// receive is reported to `tap`, timestamped by the configured
+    /// `timer`. See [`TappedFactory`](crate::tap::TappedFactory).
+    pub fn with_tap<T: crate::tap::DatagramTap>(
+        self,
+        tap: T,
+    ) -> ClientDeps<crate::tap::TappedFactory<F, T, Tm>, Tm, R, I, Sp, BP, St>
+    where
+        Tm: Clone,
+    {
+        ClientDeps {
+            factory: crate::tap::TappedFactory::new(self.factory, tap, self.timer.clone()),
+            timer: self.timer,
+            e2e_registry: self.e2e_registry,
+            interface: self.interface,
+            spawner: self.spawner,
+            buffer_provider: self.buffer_provider,
+            stream_factory: self.stream_factory,
+        }
+    }
+
+    /// Replace the `stream_factory` field, returning a `ClientDeps` over
+    /// the new factory type. Needed to reach services offered over TCP.
+    pub fn with_stream_factory<St2: crate::transport::StreamFactory>(
+        self,
+        stream_factory: St2,
+    ) -> ClientDeps<F, Tm, R, I, Sp, BP, St2> {
+        ClientDeps {
+            factory: self.factory,
+            timer: self.timer,
+            e2e_registry: self.e2e_registry,
+            interface: self.interface,
+            spawner: self.spawner,
+            buffer_provider: self.buffer_provider,
+            stream_factory,
         }
     }
 }
@@ -548,6 +1022,11 @@ pub struct Client<
     interface: I,
     control_sender: C::BoundedSender<inner::ControlMessage<MessageDefinitions, C>, 4>,
     e2e_registry: R,
+    /// SD multicast group and port, where `sd_announcements_loop` sends.
+    #[cfg(feature = "client-tokio")]
+    sd_target: SocketAddrV4,
+    /// Policy of `find_service` and `find_service_timeout`.
+    find_policy: FindPolicy,
 }
 
 impl<MessageDefinitions, R, I, C> core::fmt::Debug for Client<MessageDefinitions, R, I, C>
@@ -576,7 +1055,10 @@ impl<MessageDefinitions>
 where
     MessageDefinitions: PayloadWireFormat + Clone + core::fmt::Debug + 'static,
 {
-    /// Creates a new client bound to the given network interface and returns its run-loop future to be driven by the caller.
+    /// Creates a new client with the settings of `config` and returns its run-loop future to be driven by the caller.
+    ///
+    /// `config` is a [`ClientConfig`], or just the network interface
+    /// address for the default settings on it.
     ///
     /// Returns a `(Client, ClientUpdates, run_future)` triple. The `Client`
     /// handle is [`Clone`]-able and can be shared across tasks.
@@ -600,15 +1082,34 @@ where
     /// # let _ = (client, updates);
     /// # }
     /// ```
+    ///
+    /// With settings other than the defaults:
+    ///
+    /// ```no_run
+    /// # use simple_someip::{Client, ClientConfig, RawPayload};
+    /// # use simple_someip::client::OverflowPolicy;
+    /// # use std::net::Ipv4Addr;
+    /// # async fn demo() {
+    /// let config = ClientConfig::from(Ipv4Addr::LOCALHOST)
+    ///     .with_unicast_port_range(40000, 40099)
+    ///     .with_recv_buffer_size(1 << 20)
+    ///     .with_receive_capacity(256)
+    ///     .with_receive_overflow(OverflowPolicy::KeepLatest)
+    ///     .with_auto_bind_discovery(true);
+    /// let (client, mut updates, run) = Client::<RawPayload, _, _, _>::new(config);
+    /// let _run_task = tokio::spawn(run);
+    /// # let _ = (client, updates);
+    /// # }
+    /// ```
     #[must_use = "the returned run-loop future must be spawned (e.g. tokio::spawn) for the client to make progress"]
     pub fn new(
-        interface: Ipv4Addr,
+        config: impl Into<ClientConfig>,
     ) -> (
         Self,
         ClientUpdates<MessageDefinitions, TokioChannels>,
         impl core::future::Future<Output = ()> + Send + 'static,
     ) {
-        Self::new_with_loopback(interface, false)
+        Self::new_with_spawner_and_config(config, TokioSpawner)
     }
 
     /// Like [`Self::new`], but with explicit control over multicast loopback.
@@ -643,7 +1144,10 @@ where
         ClientUpdates<MessageDefinitions, TokioChannels>,
         impl core::future::Future<Output = ()> + Send + 'static,
     ) {
-        Self::new_with_spawner_and_loopback(interface, multicast_loopback, TokioSpawner)
+        Self::new_with_spawner_and_config(
+            ClientConfig::from(interface).with_multicast_loopback(multicast_loopback),
+            TokioSpawner,
+        )
     }
 
     /// Like [`Self::new_with_loopback`], but with a caller-provided
@@ -691,12 +1195,33 @@ where
     where
         Sp: Spawner + Send + Sync + 'static,
     {
-        Self::new_with_deps(
+        Self::new_with_spawner_and_config(
+            ClientConfig::from(interface).with_multicast_loopback(multicast_loopback),
+            spawner,
+        )
+    }
+
+    /// Like [`Self::new`], but with a caller-provided [`Spawner`]; see
+    /// [`Self::new_with_spawner_and_loopback`].
+    #[must_use = "the returned run-loop future must be spawned (e.g. via the Spawner) for the client to make progress"]
+    pub fn new_with_spawner_and_config<Sp>(
+        config: impl Into<ClientConfig>,
+        spawner: Sp,
+    ) -> (
+        Self,
+        ClientUpdates<MessageDefinitions, TokioChannels>,
+        impl core::future::Future<Output = ()> + Send + 'static,
+    )
+    where
+        Sp: Spawner + Send + Sync + 'static,
+    {
+        let config = config.into();
+        Self::new_with_deps_and_config(
             ClientDeps {
                 factory: crate::tokio_transport::TokioTransport,
                 timer: TokioTimer,
                 e2e_registry: Arc::new(Mutex::new(E2ERegistry::new())),
-                interface: Arc::new(RwLock::new(interface)),
+                interface: Arc::new(RwLock::new(config.interface)),
                 spawner,
                 // One `TokioBufferProvider::new()` per client construction.
                 // It is `Arc`-backed (freed when the last provider/lease
@@ -704,8 +1229,9 @@ where
                 // avoids a per-bind heap allocation. This single call
                 // covers every `bind_*`.
                 buffer_provider: crate::tokio_transport::TokioBufferProvider::new(),
+                stream_factory: crate::tokio_transport::TokioTransport,
             },
-            multicast_loopback,
+            config,
         )
     }
 }
@@ -720,6 +1246,13 @@ where
     Result<(), Error>: OneshotPooled<C>,
     Result<MessageDefinitions, Error>: OneshotPooled<C>,
     Result<protocol::sd::RebootFlag, Error>: OneshotPooled<C>,
+    Result<Option<core::time::Duration>, Error>: OneshotPooled<C>,
+    Result<crate::PeerTable, Error>: OneshotPooled<C>,
+    Result<ServiceEndpointKey, Error>: OneshotPooled<C>,
+    Result<crate::JanitorStats, Error>: OneshotPooled<C>,
+    Result<crate::DropStats, Error>: OneshotPooled<C>,
+    Result<ClientSnapshot, Error>: OneshotPooled<C>,
+    Result<SubscriptionState, Error>: OneshotPooled<C>,
     ControlMessage<MessageDefinitions, C>: BoundedPooled<C, 4>,
     SendMessage<MessageDefinitions, C>: BoundedPooled<C, 16>,
     Result<ReceivedMessage<MessageDefinitions>, Error>: BoundedPooled<C, 16>,
@@ -753,8 +1286,8 @@ where
     /// `LocalSet`-style spawner shim.
     #[allow(clippy::type_complexity)]
     #[must_use = "the returned run-loop future must be spawned (e.g. via the Spawner) for the client to make progress"]
-    pub fn new_with_deps<F, Tm, Sp, BP>(
-        deps: ClientDeps<F, Tm, R, I, Sp, BP>,
+    pub fn new_with_deps<F, Tm, Sp, BP, St>(
+        deps: ClientDeps<F, Tm, R, I, Sp, BP, St>,
         multicast_loopback: bool,
     ) -> (
         Self,
@@ -771,6 +1304,45 @@ where
         Tm: Timer + Send + Sync + 'static,
         for<'a> Tm::SleepFuture<'a>: Send,
         BP: crate::transport::BufferProvider,
+        St: crate::transport::StreamFactory + Send + Sync + 'static,
+        St::Stream: Send + Sync + 'static,
+        for<'a> St::ConnectFuture<'a>: Send,
+        for<'a> <St::Stream as TransportSocket>::SendFuture<'a>: Send,
+        for<'a> <St::Stream as TransportSocket>::RecvFuture<'a>: Send,
+    {
+        Self::new_with_deps_and_config(
+            deps,
+            ClientConfig::new().with_multicast_loopback(multicast_loopback),
+        )
+    }
+
+    /// Like [`Self::new_with_deps`], with the settings of `config`. Its
+    /// `interface` is ignored: `deps.interface` supplies it.
+    #[allow(clippy::type_complexity)]
+    #[must_use = "the returned run-loop future must be spawned (e.g. via the Spawner) for the client to make progress"]
+    pub fn new_with_deps_and_config<F, Tm, Sp, BP, St>(
+        deps: ClientDeps<F, Tm, R, I, Sp, BP, St>,
+        config: ClientConfig,
+    ) -> (
+        Self,
+        ClientUpdates<MessageDefinitions, C>,
+        impl core::future::Future<Output = ()> + Send + 'static,
+    )
+    where
+        F: TransportFactory + Send + Sync + 'static,
+        F::Socket: Send + Sync + 'static,
+        for<'a> F::BindFuture<'a>: Send,
+        for<'a> <F::Socket as TransportSocket>::SendFuture<'a>: Send,
+        for<'a> <F::Socket as TransportSocket>::RecvFuture<'a>: Send,
+        Sp: Spawner + Send + Sync + 'static,
+        Tm: Timer + Send + Sync + 'static,
+        for<'a> Tm::SleepFuture<'a>: Send,
+        BP: crate::transport::BufferProvider,
+        St: crate::transport::StreamFactory + Send + Sync + 'static,
+        St::Stream: Send + Sync + 'static,
+        for<'a> St::ConnectFuture<'a>: Send,
+        for<'a> <St::Stream as TransportSocket>::SendFuture<'a>: Send,
+        for<'a> <St::Stream as TransportSocket>::RecvFuture<'a>: Send,
     {
         let ClientDeps {
             factory,
@@ -779,23 +1351,25 @@ where
             interface,
             spawner,
             buffer_provider,
+            stream_factory,
         } = deps;
         let initial_addr = interface.get();
         let dispatch = bind_dispatch::SpawnerDispatch {
             factory,
             spawner,
             buffer_provider,
+            stream_factory,
         };
         let (control_sender, update_receiver, run_future) = Inner::<
             MessageDefinitions,
             Tm,
             R,
             C,
-            bind_dispatch::SpawnerDispatch<F, Sp, BP>,
+            bind_dispatch::SpawnerDispatch<F, Sp, BP, St>,
         >::build(
             initial_addr,
             e2e_registry.clone(),
-            multicast_loopback,
+            config,
             dispatch,
             timer,
         );
@@ -803,6 +1377,9 @@ where
             interface,
             control_sender,
             e2e_registry,
+            #[cfg(feature = "client-tokio")]
+            sd_target: SocketAddrV4::new(config.sd_multicast_group, config.sd_port),
+            find_policy: config.find_policy,
         };
         let updates = ClientUpdates { update_receiver };
         (client, updates, run_future)
@@ -825,8 +1402,8 @@ where
     /// [`Spawner`]: crate::transport::Spawner
     #[allow(clippy::type_complexity)]
     #[must_use = "the returned run-loop future must be spawned (e.g. via the LocalSpawner) for the client to make progress"]
-    pub fn new_with_deps_local<F, Tm, Sp, BP>(
-        deps: ClientDeps<F, Tm, R, I, Sp, BP>,
+    pub fn new_with_deps_local<F, Tm, Sp, BP, St>(
+        deps: ClientDeps<F, Tm, R, I, Sp, BP, St>,
         multicast_loopback: bool,
     ) -> (
         Self,
@@ -839,6 +1416,35 @@ where
         Sp: crate::transport::LocalSpawner + 'static,
         Tm: Timer + 'static,
         BP: crate::transport::BufferProvider,
+        St: crate::transport::StreamFactory + 'static,
+        St::Stream: 'static,
+    {
+        Self::new_with_deps_local_and_config(
+            deps,
+            ClientConfig::new().with_multicast_loopback(multicast_loopback),
+        )
+    }
+
+    /// Like [`Self::new_with_deps_local`], with the settings of `config`. Its
+    /// `interface` is ignored: `deps.interface` supplies it.
+    #[allow(clippy::type_complexity)]
+    #[must_use = "the returned run-loop future must be spawned (e.g. via the LocalSpawner) for the client to make progress"]
+    pub fn new_with_deps_local_and_config<F, Tm, Sp, BP, St>(
+        deps: ClientDeps<F, Tm, R, I, Sp, BP, St>,
+        config: ClientConfig,
+    ) -> (
+        Self,
+        ClientUpdates<MessageDefinitions, C>,
+        impl core::future::Future<Output = ()> + 'static,
+    )
+    where
+        F: TransportFactory + 'static,
+        F::Socket: 'static,
+        Sp: crate::transport::LocalSpawner + 'static,
+        Tm: Timer + 'static,
+        BP: crate::transport::BufferProvider,
+        St: crate::transport::StreamFactory + 'static,
+        St::Stream: 'static,
     {
         let ClientDeps {
             factory,
@@ -847,23 +1453,25 @@ where
             interface,
             spawner,
             buffer_provider,
+            stream_factory,
         } = deps;
         let initial_addr = interface.get();
         let dispatch = bind_dispatch::LocalSpawnerDispatch {
             factory,
             spawner,
             buffer_provider,
+            stream_factory,
         };
         let (control_sender, update_receiver, run_future) = Inner::<
             MessageDefinitions,
             Tm,
             R,
             C,
-            bind_dispatch::LocalSpawnerDispatch<F, Sp, BP>,
+            bind_dispatch::LocalSpawnerDispatch<F, Sp, BP, St>,
         >::build(
             initial_addr,
             e2e_registry.clone(),
-            multicast_loopback,
+            config,
             dispatch,
             timer,
         );
@@ -871,6 +1479,9 @@ where
             interface,
             control_sender,
             e2e_registry,
+            #[cfg(feature = "client-tokio")]
+            sd_target: SocketAddrV4::new(config.sd_multicast_group, config.sd_port),
+            find_policy: config.find_policy,
         };
         let updates = ClientUpdates { update_receiver };
         (client, updates, run_future)
@@ -944,11 +1555,28 @@ where
     /// entry is read from the registry value stored by
     /// [`add_endpoint`](Self::add_endpoint) or SD auto-registration.
     ///
+    /// `major_version` may be
+    /// [`ANY_MAJOR_VERSION`](protocol::sd::ANY_MAJOR_VERSION) (`0xFF`): the
+    /// entry then carries the concrete major version recorded from the
+    /// provider's offer, or the wildcard itself for endpoints added via
+    /// `add_endpoint`, which the provider resolves in its ack.
+    ///
+    /// Resolves once the `SubscribeEventgroup` is sent. The returned
+    /// [`SubscriptionHandle`] reports the provider's answer and keeps the
+    /// subscription alive, refreshing it halfway through `ttl` (seconds);
+    /// dropping it stops the subscription. Refreshes need a [`Timer`]
+    /// that implements [`Timer::now`]. At most [`SUBSCRIPTIONS_CAP`]
+    /// subscriptions are tracked, together with those of
+    /// [`subscribe_eventgroup`](Self::subscribe_eventgroup).
+    ///
     /// # Errors
     ///
     /// Returns an error if the service is not found or subscription fails.
     /// Returns [`Error::UnsupportedEndpoint`] if the key's endpoint is
-    /// not an IPv4/UDP socket.
+    /// not an IPv4 socket.
+    /// Returns [`Error::Capacity`] (with tag `"subscriptions"`) if
+    /// [`SUBSCRIPTIONS_CAP`] subscriptions are already tracked, before
+    /// anything is sent.
     /// Returns [`Error::Shutdown`] if the client's run-loop future has
     /// exited before this call (dropped, cancelled, or otherwise gone)
     /// — the `Client` handle has outlived its driver and further
@@ -960,44 +1588,258 @@ where
         ttl: u32,
         event_group_id: u16,
         client_port: u16,
-    ) -> Result<(), Error> {
-        let (response, message) =
-            ControlMessage::subscribe(key, major_version, ttl, event_group_id, client_port);
+    ) -> Result<SubscriptionHandle<MessageDefinitions, C>, Error> {
+        let id = subscriptions::next_subscription_id();
+        let (response, ack, message) = ControlMessage::subscribe_tracked(
+            key,
+            major_version,
+            ttl,
+            event_group_id,
+            client_port,
+            id,
+        );
         self.control_sender
             .send(message)
             .await
             .map_err(|()| Error::Shutdown)?;
-        response.recv().await.map_err(|_| Error::Shutdown)?
+        response.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(SubscriptionHandle {
+            control_sender: self.control_sender.clone(),
+            key,
+            event_group_id,
+            id,
+            ack: Some(ack),
+            acknowledged: false,
+            detached: false,
+        })
     }
 
-    /// Like [`subscribe`](Self::subscribe) but does not wait for the
-    /// subscription result.
-    ///
-    /// Returns `()`: if the run-loop has exited the request is silently
-    /// lost — there is no error surface and no panic. Use
-    /// [`subscribe`](Self::subscribe) when you need to detect dispatch
-    /// failures.
-    ///
-    /// This still awaits enqueueing the control message on the internal
-    /// channel, so it may block if that bounded channel is full. Useful
-    /// for periodic renewals where waiting for subscription processing is
-    /// unnecessary.
+    /// [`subscribe`](Self::subscribe) to several eventgroups of the
+    /// service at `key` at once. Their `SubscribeEventgroup` entries share
+    /// one SD message where the payload type allows (see
+    /// [`PayloadWireFormat::push_subscription_entry`]), instead of one
+    /// message per eventgroup. Eventgroups with a receive port of their
+    /// own (see [`set_receive_port`](Self::set_receive_port)) go out in a
+    /// message announcing it.
     ///
-    /// The response oneshot is simply dropped at the end of this call.
-    /// The inner loop's send-to-dropped-receiver path is not logged at
-    /// `warn!`; at most it is logged at `debug!`, so fire-and-forget
-    /// usage remains low-noise.
+    /// Returns one [`SubscriptionHandle`] per eventgroup, in
+    /// `event_group_ids` order; each reports its own ack.
     ///
-    /// # Silent drop on a closed channel
+    /// # Errors
     ///
-    /// Unlike the other `Client` methods (which return
-    /// `Err(Error::Shutdown)` if the run-loop has exited and closed the
-    /// receiver), `subscribe_no_wait` deliberately discards the `send`
-    /// result. If the run-loop has exited, the request is silently
-    /// dropped — no error surface, no panic. This matches the
-    /// fire-and-forget contract: callers that need to know whether the
-    /// subscription was actually dispatched should use
-    /// [`subscribe`](Self::subscribe) instead.
+    /// Returns the errors of [`subscribe`](Self::subscribe); nothing is
+    /// sent unless all of the subscriptions can be tracked.
+    /// Returns [`Error::Capacity`] (with tag `"subscribe_many"`) if
+    /// `event_group_ids` holds more than [`SUBSCRIBE_MANY_CAP`] ids.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn subscribe_many(
+        &self,
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_ids: &[u16],
+        client_port: u16,
+    ) -> Result<heapless::Vec<SubscriptionHandle<MessageDefinitions, C>, SUBSCRIBE_MANY_CAP>, Error>
+    {
+        let ids = heapless::Vec::from_slice(event_group_ids)
+            .map_err(|_| Error::Capacity("subscribe_many"))?;
+        let id = subscriptions::next_subscription_id();
+        let (response, acks, message) =
+            ControlMessage::subscribe_many(key, major_version, ttl, ids, client_port, id);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)??;
+        let mut handles = heapless::Vec::new();
+        for (&event_group_id, ack) in event_group_ids.iter().zip(acks) {
+            // Cannot fail: at most `SUBSCRIBE_MANY_CAP` acks.
+            let _ = handles.push(SubscriptionHandle {
+                control_sender: self.control_sender.clone(),
+                key,
+                event_group_id,
+                id,
+                ack: Some(ack),
+                acknowledged: false,
+                detached: false,
+            });
+        }
+        Ok(handles)
+    }
+
+    /// Like [`subscribe`](Self::subscribe), but then also waits for data
+    /// to flow: resolves once the first notification of the service
+    /// arrives from the provider, be it an event or an initial field
+    /// value. A startup gate for "subscribed and receiving".
+    ///
+    /// Any notification of `key.service_id` from the provider's address
+    /// counts, whatever its eventgroup. It is still delivered as a
+    /// [`ClientUpdate::Unicast`] on the update stream. Without a
+    /// [`Timer`] clock the wait has no timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`subscribe`](Self::subscribe), in which case
+    /// nothing is waited for.
+    /// Returns [`Error::Timeout`] if no notification arrived within
+    /// `timeout` of the subscription; the subscription stays in place.
+    /// Returns [`Error::Capacity`] (with tag `"event_waiters"`) if
+    /// [`EVENT_WAITERS_CAP`] waits are already pending, before anything
+    /// is sent.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn subscribe_and_wait_first(
+        &self,
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        event_group_id: u16,
+        client_port: u16,
+        timeout: core::time::Duration,
+    ) -> Result<(), Error> {
+        let (response, first_event, message) = ControlMessage::subscribe_and_wait_first(
+            key,
+            major_version,
+            ttl,
+            event_group_id,
+            client_port,
+            timeout,
+        );
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)??;
+        first_event.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Subscribes to the events `event_ids` of a service instance: picks
+    /// the fewest eventgroups of `layout` that carry all of them (see
+    /// [`EventgroupLayout::select`]) and subscribes to each, as
+    /// [`subscribe`](Self::subscribe) does but without tracking the
+    /// subscriptions. Returns the eventgroups subscribed.
+    ///
+    /// Eventgroups are subscribed one after the other; on error the ones
+    /// before the failing one stay subscribed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventNotInLayout`] if `layout` has no eventgroup
+    /// for one of `event_ids`, before anything is sent; otherwise the
+    /// first error from [`subscribe`](Self::subscribe).
+    pub async fn subscribe_events(
+        &self,
+        key: ServiceEndpointKey,
+        major_version: u8,
+        ttl: u32,
+        layout: &EventgroupLayout,
+        event_ids: &[u16],
+        client_port: u16,
+    ) -> Result<EventgroupSelection, Error> {
+        let selection = layout.select(event_ids)?;
+        for &event_group_id in &selection {
+            let (response, message) =
+                ControlMessage::subscribe(key, major_version, ttl, event_group_id, client_port);
+            self.control_sender
+                .send(message)
+                .await
+                .map_err(|()| Error::Shutdown)?;
+            response.recv().await.map_err(|_| Error::Shutdown)??;
+        }
+        Ok(selection)
+    }
+
+    /// Opens a stream of the notifications of `event_group_id` from the
+    /// service instance `service_id`/`instance_id` (`0xFFFF` for any
+    /// instance).
+    ///
+    /// A notification belongs to the eventgroup if `layout` lists its
+    /// event ID there and it comes from an address the instance is known
+    /// at, offered or added with [`add_endpoint`](Self::add_endpoint).
+    /// Such notifications go to the stream instead of the update stream
+    /// as [`ClientUpdate::Unicast`]. One that fails its E2E check stays
+    /// on the update stream with its [`E2ECheckStatus`], so a stream only
+    /// carries notifications that passed or are not protected. When
+    /// streams overlap, each of them gets the notification; a full stream
+    /// misses it, and one no stream took goes to the update stream. The
+    /// stream does not subscribe: pair it with
+    /// [`subscribe`](Self::subscribe).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EventgroupNotInLayout`] if `layout` does not
+    /// declare `event_group_id`, before anything is sent.
+    /// Returns [`Error::Capacity`] (with tag `"event_streams"`) if
+    /// [`EVENT_STREAMS_CAP`] streams are already open.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn event_stream(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        layout: &EventgroupLayout,
+    ) -> Result<EventStream<MessageDefinitions, C>, Error>
+    where
+        Message<MessageDefinitions>: UnboundedPooled<C>,
+    {
+        let events = layout
+            .events(event_group_id)
+            .ok_or(Error::EventgroupNotInLayout(event_group_id))?;
+        // Cannot fail: a layout holds at most as many events per group.
+        let events =
+            heapless::Vec::from_slice(events).map_err(|_| Error::Capacity("eventgroup_layout"))?;
+        let (sender, receiver) = C::unbounded();
+        let (response, message) = ControlMessage::open_event_stream(
+            service_id,
+            instance_id,
+            event_group_id,
+            events,
+            sender,
+        );
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(EventStream { receiver })
+    }
+
+    /// Like [`subscribe`](Self::subscribe) but does not wait for the
+    /// subscription result.
+    ///
+    /// Returns `()`: if the run-loop has exited the request is silently
+    /// lost — there is no error surface and no panic. Use
+    /// [`subscribe`](Self::subscribe) when you need to detect dispatch
+    /// failures.
+    ///
+    /// This still awaits enqueueing the control message on the internal
+    /// channel, so it may block if that bounded channel is full. Useful
+    /// for periodic renewals where waiting for subscription processing is
+    /// unnecessary.
+    ///
+    /// The response oneshot is simply dropped at the end of this call.
+    /// The inner loop's send-to-dropped-receiver path is not logged at
+    /// `warn!`; at most it is logged at `debug!`, so fire-and-forget
+    /// usage remains low-noise.
+    ///
+    /// # Silent drop on a closed channel
+    ///
+    /// Unlike the other `Client` methods (which return
+    /// `Err(Error::Shutdown)` if the run-loop has exited and closed the
+    /// receiver), `subscribe_no_wait` deliberately discards the `send`
+    /// result. If the run-loop has exited, the request is silently
+    /// dropped — no error surface, no panic. This matches the
+    /// fire-and-forget contract: callers that need to know whether the
+    /// subscription was actually dispatched should use
+    /// [`subscribe`](Self::subscribe) instead.
     pub async fn subscribe_no_wait(
         &self,
         key: ServiceEndpointKey,
@@ -1011,6 +1853,118 @@ where
         let _ = self.control_sender.send(message).await;
     }
 
+    /// Subscribes to `event_group_id` of the service instance
+    /// `service_id`/`instance_id` and keeps the subscription alive.
+    ///
+    /// The provider's UDP endpoint is looked up in the endpoint registry,
+    /// filled by SD offers or [`add_endpoint`](Self::add_endpoint). The
+    /// subscription is made from the endpoint's local port, as requests
+    /// are, and resolves once the provider answers it. Once acknowledged,
+    /// it is sent again halfway through `ttl` (seconds) until
+    /// [`unsubscribe_eventgroup`](Self::unsubscribe_eventgroup); a
+    /// [`TTL_INFINITE`](protocol::sd::TTL_INFINITE) subscription is sent
+    /// once. A refresh the provider rejects ends the subscription and is
+    /// reported as [`ClientUpdate::Error`].
+    ///
+    /// The ack timeout and the refreshes need a [`Timer`] that implements
+    /// [`Timer::now`]; without a clock the call waits for the answer
+    /// however long it takes, and the subscription is never refreshed.
+    /// Subscribing again to a tracked eventgroup re-sends it with the new
+    /// `ttl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ServiceNotFound`] if no UDP endpoint of the
+    /// instance is known.
+    /// Returns [`Error::SubscriptionRejected`] if the provider answers
+    /// with a `SubscribeEventgroupNack`, and [`Error::Timeout`] if it does
+    /// not answer within [`SUBSCRIBE_ACK_TIMEOUT`]; the subscription is
+    /// then dropped.
+    /// Returns [`Error::Capacity`] (with tag `"subscriptions"`) if
+    /// [`SUBSCRIPTIONS_CAP`] subscriptions are already tracked, or if a
+    /// later call for the same eventgroup replaced this one before its
+    /// ack.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn subscribe_eventgroup(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        ttl: u32,
+    ) -> Result<(), Error> {
+        let (response, message) =
+            ControlMessage::subscribe_eventgroup(service_id, instance_id, event_group_id, ttl);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Stops a subscription made with
+    /// [`subscribe_eventgroup`](Self::subscribe_eventgroup): sends the
+    /// `StopSubscribeEventgroup` and stops refreshing it. A call still
+    /// waiting for its ack fails with [`Error::NotSubscribed`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSubscribed`] if no such subscription is
+    /// tracked. The subscription is forgotten even if sending the stop
+    /// fails, e.g. with [`Error::ServiceNotFound`] once the provider
+    /// stopped offering.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn unsubscribe_eventgroup(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> Result<(), Error> {
+        let (response, message) =
+            ControlMessage::unsubscribe_eventgroup(service_id, instance_id, event_group_id);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Sends a `StopSubscribeEventgroup` ending a subscription made with
+    /// [`subscribe`](Self::subscribe).
+    ///
+    /// `client_port` must be the unicast port the subscription was made
+    /// from: the provider keys subscribers by that endpoint. Subscribing
+    /// with `client_port = 0` binds an ephemeral port that cannot be
+    /// named here, so pass an explicit port to `subscribe` when the
+    /// subscription is to be stopped explicitly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnicastSocketNotBound`] if no unicast socket is
+    /// bound on `client_port`.
+    /// Returns [`Error::ServiceNotFound`] if `key` is not in the endpoint
+    /// registry, e.g. because the provider stopped offering.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn unsubscribe(
+        &self,
+        key: ServiceEndpointKey,
+        major_version: u8,
+        event_group_id: u16,
+        client_port: u16,
+    ) -> Result<(), Error> {
+        let (response, message) =
+            ControlMessage::unsubscribe(key, major_version, event_group_id, client_port);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
     /// Returns the current SD reboot flag tracked by the client.
     ///
     /// Per AUTOSAR SOME/IP-SD, the reboot flag is
@@ -1029,23 +1983,643 @@ where
     /// [`RebootFlag::RecentlyRebooted`](protocol::sd::RebootFlag::RecentlyRebooted)
     /// is returned.
     ///
-    /// Call this before manually building an SD header (e.g. one passed to
-    /// [`send_sd_message`](Self::send_sd_message)) so the reboot flag reflects
-    /// the current tracked state instead of a stale value baked at call time.
-    /// Headers passed to `sd_announcements_loop` (under `client-tokio`)
-    /// are refreshed automatically per-tick and do not need this call.
+    /// Call this before manually building an SD header (e.g. one passed to
+    /// [`send_sd_message`](Self::send_sd_message)) so the reboot flag reflects
+    /// the current tracked state instead of a stale value baked at call time.
+    /// Headers passed to `sd_announcements_loop` (under `client-tokio`)
+    /// are refreshed automatically per-tick and do not need this call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"request_queue"`) if the
+    /// run loop's bounded control queue is saturated under load.
+    pub async fn reboot_flag(&self) -> Result<protocol::sd::RebootFlag, Error> {
+        let (response, message) = ControlMessage::query_reboot_flag();
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Test-only: force the inner loop's `sd_session_has_wrapped` so tests
+    /// can observe post-wrap behavior without sending 65k SD messages.
+    /// Mirrors the public `Client` API: returns `Err(Error::Shutdown)` on
+    /// closed channels rather than panicking.
+    #[cfg(all(test, feature = "client-tokio"))]
+    pub(crate) async fn force_sd_session_wrapped_for_test(
+        &self,
+        wrapped: bool,
+    ) -> Result<(), Error> {
+        let (response, message) = ControlMessage::force_sd_session_wrapped_for_test(wrapped);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Sends an SD message to a specific target address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if sending the SD message fails.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn send_sd_message(
+        &self,
+        target: SocketAddrV4,
+        sd_header: <MessageDefinitions as PayloadWireFormat>::SdHeader,
+    ) -> Result<(), Error> {
+        let (response, message) = ControlMessage::send_sd(target, sd_header);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Registers a service endpoint in the client's endpoint registry.
+    ///
+    /// `key` carries the wire identity (service id + provider socket +
+    /// transport protocol); `instance_id` is stored as data because
+    /// `SubscribeEventgroup` SD entries need it on the wire
+    /// (`[PRS_SOMEIP_00162]`: it is not part of a service instance's
+    /// wire identity).
+    ///
+    /// `local_port` controls which source port is used when sending to this
+    /// endpoint via [`send_to_service`](Self::send_to_service). Pass `0` to
+    /// use an ephemeral (OS-assigned) port.
+    ///
+    /// Service-discovery (SD) automatically populates endpoints with
+    /// `local_port = 0`. If your configuration requires a specific source
+    /// port, you must call `add_endpoint` explicitly — even if SD has already
+    /// registered the service — so that the correct `local_port` is stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if registering the endpoint fails.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn add_endpoint(
+        &self,
+        key: ServiceEndpointKey,
+        instance_id: u16,
+        local_port: u16,
+    ) -> Result<(), Error> {
+        let (response, message) = ControlMessage::add_endpoint(key, instance_id, local_port);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Registers a provider of `service_id`/`instance_id` known ahead of
+    /// time at `addr`, so requests and subscriptions can target it without
+    /// waiting for discovery. Returns the key to address it by.
+    ///
+    /// Static endpoints take part in availability like discovered ones:
+    /// a new one is reported as [`ClientUpdate::ServiceAvailable`], and
+    /// removing it with [`remove_endpoint`](Self::remove_endpoint) as
+    /// [`ClientUpdate::ServiceUnavailable`]. Unlike them, they never
+    /// expire, and SD offers and stops for the endpoint leave them
+    /// alone. They carry the wildcard major version, like endpoints added
+    /// with [`add_endpoint`](Self::add_endpoint), and send from the shared
+    /// unicast socket or the service's
+    /// [receive port](Self::set_receive_port).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"service_registry"`) if the
+    /// registry is full.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn add_static_endpoint(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        addr: SocketAddrV4,
+        protocol: crate::TransportProtocol,
+    ) -> Result<ServiceEndpointKey, Error> {
+        let key = ServiceEndpointKey::new(
+            service_id,
+            crate::NetEndpoint::new(SocketAddr::V4(addr), protocol),
+        );
+        let (response, message) = ControlMessage::add_static_endpoint(key, instance_id);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(key)
+    }
+
+    /// Removes a service endpoint from the client's endpoint registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if removing the endpoint fails.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn remove_endpoint(&self, key: ServiceEndpointKey) -> Result<(), Error> {
+        let (response, message) = ControlMessage::remove_endpoint(key);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Apply the part of a configuration reload that concerns a running
+    /// client, e.g. the changes from
+    /// [`ConfigNotifier::next_changes`](crate::config::ConfigNotifier::next_changes):
+    /// [`ConfigChange::StaticEndpointAdded`](crate::config::ConfigChange::StaticEndpointAdded)
+    /// registers the UDP endpoint with [`Self::add_static_endpoint`] and
+    /// [`ConfigChange::StaticEndpointRemoved`](crate::config::ConfigChange::StaticEndpointRemoved)
+    /// drops it with [`Self::remove_endpoint`].
+    ///
+    /// An interface change needs the client rebuilt and is reported as
+    /// rejected. TTL, port, `announce`, offer and offer timing changes
+    /// are the server's and are left out of the report.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::add_static_endpoint`]. Changes listed before the
+    /// failing one stay applied.
+    #[cfg(feature = "std")]
+    pub async fn apply_config_changes(
+        &self,
+        changes: &[crate::config::ConfigChange],
+    ) -> Result<crate::config::ApplyReport, Error> {
+        use crate::config::ConfigChange;
+
+        let mut report = crate::config::ApplyReport::default();
+        for change in changes {
+            match *change {
+                ConfigChange::StaticEndpointAdded(endpoint) => {
+                    self.add_static_endpoint(
+                        endpoint.service_id,
+                        endpoint.instance_id,
+                        endpoint.addr,
+                        crate::TransportProtocol::Udp,
+                    )
+                    .await?;
+                    report.applied.push(*change);
+                }
+                ConfigChange::StaticEndpointRemoved(endpoint) => {
+                    let key = ServiceEndpointKey::new(
+                        endpoint.service_id,
+                        crate::NetEndpoint::udp(SocketAddr::V4(endpoint.addr)),
+                    );
+                    self.remove_endpoint(key).await?;
+                    report.applied.push(*change);
+                }
+                ConfigChange::InterfaceChanged { .. } => {
+                    warn!(
+                        "Configuration change needs the client rebuilt: {:?}",
+                        change
+                    );
+                    report.rejected.push(*change);
+                }
+                ConfigChange::LocalPortChanged { .. }
+                | ConfigChange::TtlChanged { .. }
+                | ConfigChange::AnnounceChanged { .. }
+                | ConfigChange::OfferAdded(_)
+                | ConfigChange::OfferRemoved(_)
+                | ConfigChange::CyclicOfferDelayChanged { .. }
+                | ConfigChange::RepetitionsMaxChanged { .. }
+                | ConfigChange::RepetitionsBaseDelayChanged { .. }
+                | ConfigChange::InitialDelayMinChanged { .. }
+                | ConfigChange::InitialDelayMaxChanged { .. } => {}
+            }
+        }
+        Ok(report)
+    }
+
+    /// Sets (`Some`) or clears (`None`) the [`VersionPolicy`] applied to
+    /// SD offers of `service_id`.
+    ///
+    /// Offers the policy rejects are not registered, and already-known
+    /// endpoints of that service that no longer satisfy it are removed
+    /// immediately. Without a policy every offered version is accepted.
+    /// Manually added endpoints ([`Self::add_endpoint`]) carry no
+    /// offered version and are left alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] if policies are already pinned for
+    /// [`VERSION_POLICIES_CAP`] other services.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn set_version_policy(
+        &self,
+        service_id: u16,
+        policy: Option<VersionPolicy>,
+    ) -> Result<(), Error> {
+        let (response, message) = ControlMessage::set_version_policy(service_id, policy);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Sets (`Some`) or clears (`None`) the [`ProviderSelection`] used
+    /// when several endpoints offer the same instance of `service_id`.
+    /// Without one, the offers' load-balancing options decide.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"provider_selections"`) if
+    /// selections are already set for [`PROVIDER_SELECTIONS_CAP`] other
+    /// services.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn set_provider_selection(
+        &self,
+        service_id: u16,
+        selection: Option<ProviderSelection>,
+    ) -> Result<(), Error> {
+        let (response, message) = ControlMessage::set_provider_selection(service_id, selection);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Sets aside (`Some`) or releases (`None`) a unicast socket for the
+    /// traffic of `service_id`, instead of the socket shared by all
+    /// services. `Some(0)` binds an ephemeral port on first use.
+    ///
+    /// With `event_group_id` `None` the port carries the service's
+    /// requests and subscriptions; with `Some` it carries only that
+    /// eventgroup's subscription and overrides the service's port for
+    /// it, isolating a high-rate stream. Only calls that leave the port
+    /// to the client use it: endpoints added with a `local_port` and
+    /// [`subscribe`](Self::subscribe) calls with a `client_port` keep
+    /// theirs. Subscriptions already made keep their port.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"receive_ports"`) if
+    /// [`RECEIVE_PORTS_CAP`] ports are already set aside.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn set_receive_port(
+        &self,
+        service_id: u16,
+        event_group_id: Option<u16>,
+        port: Option<u16>,
+    ) -> Result<(), Error> {
+        let (response, message) =
+            ControlMessage::set_receive_port(service_id, event_group_id, port);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Waits until `service_id`/`instance_id` is offered and returns the
+    /// provider's endpoint, ready for [`subscribe`](Self::subscribe) or
+    /// [`request`](Self::request). `instance_id` `0xFFFF` accepts any
+    /// instance.
+    ///
+    /// Searches as [`find_service_with`](Self::find_service_with) does
+    /// under the client's [`ClientConfig::find_policy`], with `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`find_service_with`](Self::find_service_with).
+    pub async fn wait_for_service(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        timeout: core::time::Duration,
+    ) -> Result<ServiceEndpointKey, Error> {
+        self.find_service_with(
+            service_id,
+            instance_id,
+            self.find_policy.with_timeout(timeout),
+        )
+        .await
+    }
+
+    /// Finds any instance of `service_id` and returns its provider's
+    /// endpoint: [`find_service_with`](Self::find_service_with) under the
+    /// client's [`ClientConfig::find_policy`]. The default [`FindPolicy`]
+    /// searches until an offer arrives.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`find_service_with`](Self::find_service_with).
+    pub async fn find_service(&self, service_id: u16) -> Result<ServiceEndpointKey, Error> {
+        self.find_service_with(service_id, 0xFFFF, self.find_policy)
+            .await
+    }
+
+    /// Finds `service_id`/`instance_id` (`0xFFFF` for any instance) and
+    /// returns its provider's endpoint, ready for
+    /// [`subscribe`](Self::subscribe) or [`request`](Self::request).
+    ///
+    /// Returns at once if an offer is already known. Otherwise binds
+    /// discovery if needed and multicasts `FindService`s on the schedule
+    /// of `policy`: an initial wait, then repetitions at doubling delays.
+    /// The search stops as soon as a matching offer arrives, whether
+    /// answering a `FindService` or cyclic.
+    ///
+    /// When several providers match, the service's [`ProviderSelection`]
+    /// picks one. By default the SD load-balancing options of their
+    /// offers decide: the lowest priority value wins, and among equal
+    /// priorities each provider is chosen at random with a chance
+    /// proportional to its weight.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if no offer arrived within the policy's
+    /// timeout.
+    /// Returns [`Error::Capacity`] (with tag `"service_waiters"`) if
+    /// [`SERVICE_WAITERS_CAP`] searches are already pending; a search whose
+    /// future was dropped keeps its slot until it would have resolved or
+    /// timed out, unless [`cancel_find`](Self::cancel_find) frees it.
+    /// Returns [`Error::FindCancelled`] if `cancel_find` stopped the
+    /// search.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn find_service_with(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        policy: FindPolicy,
+    ) -> Result<ServiceEndpointKey, Error> {
+        let (response, message) = ControlMessage::wait_for_service(service_id, instance_id, policy);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Like [`find_service_with`](Self::find_service_with) but does not
+    /// wait for the result: the search goes on, and a new offer it finds
+    /// is reported as [`ClientUpdate::ServiceAvailable`]. As with
+    /// [`subscribe_no_wait`](Self::subscribe_no_wait), a request the
+    /// run-loop cannot take, or a search it rejects, is silently lost.
+    pub async fn find_service_no_wait(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        policy: FindPolicy,
+    ) {
+        let (_response, message) =
+            ControlMessage::wait_for_service(service_id, instance_id, policy);
+        let _ = self.control_sender.send(message).await;
+    }
+
+    /// Starts tracking `dependencies`: subscribes to the eventgroups of
+    /// those already offered and searches for the rest with
+    /// [`find_service_no_wait`](Self::find_service_no_wait). Returns the
+    /// [`Readiness`] if it changed from [`Readiness::Lost`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn start_dependencies(
+        &self,
+        dependencies: &mut Dependencies,
+    ) -> Result<Option<Readiness>, Error> {
+        self.settle_dependencies(dependencies).await?;
+        Ok(dependencies.transition())
+    }
+
+    /// Updates `dependencies` with `update`, as received from
+    /// [`ClientUpdates`]: subscribes to the eventgroups of a dependency
+    /// that is newly offered, and searches again for one whose offer went
+    /// away, falling back to another provider already known. Returns the
+    /// new [`Readiness`] if it changed.
+    ///
+    /// Subscribing waits for the provider's answer; a rejected or
+    /// unanswered subscription leaves the dependency
+    /// [`SubscribeFailed`](DependencyState::SubscribeFailed).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn track_dependencies(
+        &self,
+        dependencies: &mut Dependencies,
+        update: &ClientUpdate<MessageDefinitions>,
+    ) -> Result<Option<Readiness>, Error> {
+        if let ClientUpdate::ServiceAvailable { key, instance_id } = update {
+            self.dependency_offered(dependencies, *key, *instance_id)
+                .await?;
+        } else if let ClientUpdate::ServiceUnavailable {
+            key,
+            instance_id,
+            reason,
+        } = update
+            && dependencies.withdraw(*key, *instance_id, *reason)
+        {
+            self.settle_dependencies(dependencies).await?;
+        }
+        Ok(dependencies.transition())
+    }
+
+    /// Serve the dependencies without an offer from the registry, and
+    /// search for those it has none for.
+    async fn settle_dependencies(&self, dependencies: &mut Dependencies) -> Result<(), Error> {
+        let snapshot = self.state_snapshot().await?;
+        for offer in &snapshot.offers {
+            self.dependency_offered(dependencies, offer.key, offer.instance_id)
+                .await?;
+        }
+        let policy = dependencies.find_policy();
+        let searching: heapless::Vec<(u16, u16), DEPENDENCIES_CAP> =
+            dependencies.searching().collect();
+        for (service_id, instance_id) in searching {
+            self.find_service_no_wait(service_id, instance_id, policy)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Subscribe the dependency waiting for `key`'s offer of
+    /// `instance_id`, if any, to its eventgroups.
+    async fn dependency_offered(
+        &self,
+        dependencies: &mut Dependencies,
+        key: ServiceEndpointKey,
+        instance_id: u16,
+    ) -> Result<(), Error> {
+        let Some(index) = dependencies.waiting_for(key.service_id, instance_id) else {
+            return Ok(());
+        };
+        let dependency = dependencies.dependency(index).clone();
+        let mut state = DependencyState::Ready { key, instance_id };
+        for &event_group_id in &dependency.event_group_ids {
+            match self
+                .subscribe_eventgroup(key.service_id, instance_id, event_group_id, dependency.ttl)
+                .await
+            {
+                Ok(()) => {}
+                Err(Error::Shutdown) => return Err(Error::Shutdown),
+                Err(_) => {
+                    state = DependencyState::SubscribeFailed {
+                        key,
+                        instance_id,
+                        event_group_id,
+                    };
+                    break;
+                }
+            }
+        }
+        dependencies.set_state(index, state);
+        Ok(())
+    }
+
+    /// Stops the pending searches for `service_id`/`instance_id`:
+    /// their `FindService` repetitions end and the
+    /// [`find_service_with`](Self::find_service_with) calls waiting on them
+    /// fail with [`Error::FindCancelled`]. `instance_id` `0xFFFF` stops
+    /// every search for the service, whatever instance it asked for. A
+    /// no-op if none is pending.
+    ///
+    /// SOME/IP-SD has no entry withdrawing a `FindService`, so nothing is
+    /// sent; applications that give up on an optional service just stop
+    /// generating SD traffic for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn cancel_find(&self, service_id: u16, instance_id: u16) -> Result<(), Error> {
+        let (response, message) = ControlMessage::cancel_find(service_id, instance_id);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Time left before the current offer of `service_id`/`instance_id`
+    /// lapses, counted from its last received `OfferService` entry.
+    ///
+    /// When several providers offer the same instance the longest
+    /// remaining lifetime is reported. Returns `Ok(None)` when no offer is
+    /// known, the offer's TTL is `0xFFFFFF` ("until further notice"), the
+    /// endpoint was added manually, or the [`Timer`] provides no clock.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call (dropped, cancelled, or otherwise gone)
+    /// — the `Client` handle has outlived its driver and further
+    /// control-channel sends cannot make progress.
+    pub async fn offer_ttl_remaining(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+    ) -> Result<Option<core::time::Duration>, Error> {
+        let (response, message) = ControlMessage::query_offer_ttl(service_id, instance_id);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Snapshot of the per-peer traffic counters: datagrams received from
+    /// and sent to each remote address, decode and E2E failures, detected
+    /// reboots, and when each peer was last heard from.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn peer_stats(&self) -> Result<crate::PeerTable, Error> {
+        let (response, message) = ControlMessage::query_peer_stats();
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Enables (`Some`) or disables (`None`, the default) the janitor,
+    /// which periodically closes idle TCP connections. See
+    /// [`crate::JanitorConfig`]. Discovered offers expire without it,
+    /// with a [`ClientUpdate::ServiceUnavailable`].
+    ///
+    /// Needs a clock from [`crate::Timer::now`]; without one the janitor
+    /// never sweeps.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn set_janitor(&self, config: Option<crate::JanitorConfig>) -> Result<(), Error> {
+        let (response, message) = ControlMessage::set_janitor(config);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// What the janitor has collected since the client started, across
+    /// every [`Self::set_janitor`] call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn janitor_stats(&self) -> Result<crate::JanitorStats, Error> {
+        let (response, message) = ControlMessage::query_janitor_stats();
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Datagrams the OS dropped because one of the client's UDP
+    /// sockets had a full receive queue: the client fell behind, rather
+    /// than the network losing them.
+    ///
+    /// Each socket's count is sampled by its receive loop every 64
+    /// datagrams, so it can trail the OS count until that many more arrive.
     ///
     /// # Errors
     ///
     /// Returns [`Error::Shutdown`] if the client's run-loop future has
-    /// exited before this call (dropped, cancelled, or otherwise gone)
-    /// — the `Client` handle has outlived its driver and further
-    /// control-channel sends cannot make progress.
-    ///
-    /// Returns [`Error::Capacity`] (with tag `"request_queue"`) if the
-    /// run loop's bounded control queue is saturated under load.
-    pub async fn reboot_flag(&self) -> Result<protocol::sd::RebootFlag, Error> {
-        let (response, message) = ControlMessage::query_reboot_flag();
+    /// exited before this call.
+    pub async fn drop_stats(&self) -> Result<crate::DropStats, Error> {
+        let (response, message) = ControlMessage::query_drop_stats();
         self.control_sender
             .send(message)
             .await
@@ -1053,16 +2627,16 @@ where
         response.recv().await.map_err(|_| Error::Shutdown)?
     }
 
-    /// Test-only: force the inner loop's `sd_session_has_wrapped` so tests
-    /// can observe post-wrap behavior without sending 65k SD messages.
-    /// Mirrors the public `Client` API: returns `Err(Error::Shutdown)` on
-    /// closed channels rather than panicking.
-    #[cfg(all(test, feature = "client-tokio"))]
-    pub(crate) async fn force_sd_session_wrapped_for_test(
-        &self,
-        wrapped: bool,
-    ) -> Result<(), Error> {
-        let (response, message) = ControlMessage::force_sd_session_wrapped_for_test(wrapped);
+    /// The offers in the service registry, the subscriptions kept alive
+    /// and the requests awaiting a reply, for crash reports and support
+    /// bundles; serializable with the `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn state_snapshot(&self) -> Result<ClientSnapshot, Error> {
+        let (response, message) = ControlMessage::query_state_snapshot();
         self.control_sender
             .send(message)
             .await
@@ -1070,21 +2644,24 @@ where
         response.recv().await.map_err(|_| Error::Shutdown)?
     }
 
-    /// Sends an SD message to a specific target address.
+    /// Enables (`Some`) or disables (`None`, the default)
+    /// [`ClientUpdate::OfferExpiring`] updates, emitted `margin` before a
+    /// discovered offer would lapse.
+    ///
+    /// Lets a monitor react to a silent provider before its data stops
+    /// flowing rather than after the registry entry is gone.
     ///
     /// # Errors
     ///
-    /// Returns an error if sending the SD message fails.
     /// Returns [`Error::Shutdown`] if the client's run-loop future has
     /// exited before this call (dropped, cancelled, or otherwise gone)
     /// — the `Client` handle has outlived its driver and further
     /// control-channel sends cannot make progress.
-    pub async fn send_sd_message(
+    pub async fn set_offer_expiry_margin(
         &self,
-        target: SocketAddrV4,
-        sd_header: <MessageDefinitions as PayloadWireFormat>::SdHeader,
+        margin: Option<core::time::Duration>,
     ) -> Result<(), Error> {
-        let (response, message) = ControlMessage::send_sd(target, sd_header);
+        let (response, message) = ControlMessage::set_offer_expiry_margin(margin);
         self.control_sender
             .send(message)
             .await
@@ -1092,37 +2669,55 @@ where
         response.recv().await.map_err(|_| Error::Shutdown)?
     }
 
-    /// Registers a service endpoint in the client's endpoint registry.
+    /// Enables or disables (the default) dropping duplicate
+    /// notifications: a `Notification` whose sender, message ID and
+    /// session ID match one delivered recently is not forwarded as a
+    /// [`ClientUpdate::Unicast`].
     ///
-    /// `key` carries the wire identity (service id + provider socket +
-    /// transport protocol); `instance_id` is stored as data because
-    /// `SubscribeEventgroup` SD entries need it on the wire
-    /// (`[PRS_SOMEIP_00162]`: it is not part of a service instance's
-    /// wire identity).
+    /// Protects applications from the duplicates multicast + unicast
+    /// overlap or the network itself can introduce. The last
+    /// [`DUPLICATE_FILTER_CAP`] notifications are remembered; senders
+    /// that do not use session IDs (session `0`) are never filtered.
+    /// Enabling again starts from an empty history.
     ///
-    /// `local_port` controls which source port is used when sending to this
-    /// endpoint via [`send_to_service`](Self::send_to_service). Pass `0` to
-    /// use an ephemeral (OS-assigned) port.
+    /// # Errors
     ///
-    /// Service-discovery (SD) automatically populates endpoints with
-    /// `local_port = 0`. If your configuration requires a specific source
-    /// port, you must call `add_endpoint` explicitly — even if SD has already
-    /// registered the service — so that the correct `local_port` is stored.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited before this call.
+    pub async fn set_duplicate_filter(&self, enabled: bool) -> Result<(), Error> {
+        let (response, message) = ControlMessage::set_duplicate_filter(enabled);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Sets (`Some`) or clears (`None`) the validator of the
+    /// notifications of `event_id` from `service_id`, to catch a
+    /// provider's bug (a value out of range, an unknown enum value)
+    /// before it reaches the application.
+    ///
+    /// The validator runs on each notification once its payload has
+    /// decoded. A rejected notification is not delivered: it is counted
+    /// in the sender's [`PeerStats::rejected_payloads`](crate::PeerStats::rejected_payloads)
+    /// (see [`Self::peer_stats`]) and logged, less often as rejections of
+    /// the event pile up.
     ///
     /// # Errors
     ///
-    /// Returns an error if registering the endpoint fails.
-    /// Returns [`Error::Shutdown`] if the client's run-loop future has
-    /// exited before this call (dropped, cancelled, or otherwise gone)
-    /// — the `Client` handle has outlived its driver and further
-    /// control-channel sends cannot make progress.
-    pub async fn add_endpoint(
+    /// Returns [`Error::Capacity`] (with tag `"event_validators"`) if
+    /// [`EVENT_VALIDATORS_CAP`](crate::event_validation::EVENT_VALIDATORS_CAP)
+    /// events already have a validator, or [`Error::Shutdown`] if the
+    /// client's run-loop future has exited before this call.
+    pub async fn set_event_validator(
         &self,
-        key: ServiceEndpointKey,
-        instance_id: u16,
-        local_port: u16,
+        service_id: u16,
+        event_id: u16,
+        validator: Option<crate::EventValidator<MessageDefinitions>>,
     ) -> Result<(), Error> {
-        let (response, message) = ControlMessage::add_endpoint(key, instance_id, local_port);
+        let (response, message) =
+            ControlMessage::set_event_validator(service_id, event_id, validator);
         self.control_sender
             .send(message)
             .await
@@ -1130,17 +2725,57 @@ where
         response.recv().await.map_err(|_| Error::Shutdown)?
     }
 
-    /// Removes a service endpoint from the client's endpoint registry.
+    /// Enables (the default) or disables session handling toward
+    /// `service_id`, for providers whose SOME/IP stack runs without it
+    /// and expects session ID `0`.
+    ///
+    /// With session handling disabled, fire-and-forget messages
+    /// (`REQUEST_NO_RETURN`) to the service carry session ID `0` and do
+    /// not advance the client's session counter, and its notifications
+    /// bypass the duplicate filter of
+    /// [`set_duplicate_filter`](Self::set_duplicate_filter), which cannot
+    /// tell repeats apart without session IDs. Requests that expect a
+    /// response keep their session IDs: replies are matched by request
+    /// ID, so requests to the same method can be pending at once. Up to
+    /// [`SESSIONLESS_SERVICES_CAP`] services can be session-less.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] if `SESSIONLESS_SERVICES_CAP` services
+    /// are already session-less, or [`Error::Shutdown`] if the client's
+    /// run-loop future has exited before this call.
+    pub async fn set_session_handling(&self, service_id: u16, enabled: bool) -> Result<(), Error> {
+        let (response, message) = ControlMessage::set_session_handling(service_id, enabled);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)?
+    }
+
+    /// Enables (`Some`) or disables (`None`, the default) probing every
+    /// known endpoint for reachability, as configured by `config`.
+    ///
+    /// An SD offer only shows that a provider was alive when it sent it;
+    /// the process can die or hang while the offer is still valid. Probes
+    /// catch that: an endpoint that misses
+    /// [`ProbeConfig::failures_before_degraded`] probes in a row is marked
+    /// degraded and reported with [`ClientUpdate::Reachability`], and
+    /// reported again once it answers. Offer renewals do not clear the
+    /// mark. Probe replies are consumed, not forwarded as
+    /// [`ClientUpdate::Unicast`].
+    ///
+    /// Probes are sent from already bound unicast sockets only — the same
+    /// one [`send_to_service`](Self::send_to_service) would use — and
+    /// require a [`Timer`] that implements [`Timer::now`]. Disabling
+    /// clears every endpoint's probe state, degraded marks included.
     ///
     /// # Errors
     ///
-    /// Returns an error if removing the endpoint fails.
     /// Returns [`Error::Shutdown`] if the client's run-loop future has
-    /// exited before this call (dropped, cancelled, or otherwise gone)
-    /// — the `Client` handle has outlived its driver and further
-    /// control-channel sends cannot make progress.
-    pub async fn remove_endpoint(&self, key: ServiceEndpointKey) -> Result<(), Error> {
-        let (response, message) = ControlMessage::remove_endpoint(key);
+    /// exited before this call.
+    pub async fn set_reachability_probe(&self, config: Option<ProbeConfig>) -> Result<(), Error> {
+        let (response, message) = ControlMessage::set_reachability_probe(config);
         self.control_sender
             .send(message)
             .await
@@ -1166,10 +2801,10 @@ where
     ///
     /// # Errors
     ///
-    /// Returns an error if the service is not found, unicast binding fails,
-    /// or the UDP send fails.
+    /// Returns an error if the service is not found, unicast binding
+    /// or connecting fails, or the send fails.
     /// Returns [`Error::UnsupportedEndpoint`] if the key's endpoint is
-    /// not an IPv4/UDP socket.
+    /// not an IPv4 socket.
     /// Returns [`Error::Shutdown`] if the client's run-loop future has
     /// exited before this call (dropped, cancelled, or otherwise gone)
     /// — the `Client` handle has outlived its driver and further
@@ -1190,6 +2825,41 @@ where
         })
     }
 
+    /// [`send_to_service`](Self::send_to_service) to a known provider of
+    /// `service_id`/`instance_id` (`0xFFFF` for any instance), picked by
+    /// the service's [`ProviderSelection`].
+    ///
+    /// The pick is remembered, so later sends to the same instance skip
+    /// the lookup. It is resolved afresh once the provider withdraws or
+    /// lets its offer lapse, reboots, is removed, or another provider of
+    /// the service appears, and when the service's selection changes.
+    /// [`ProviderSelection::RoundRobin`] picks are never remembered.
+    /// Up to [`RESOLVED_ENDPOINTS_CAP`] instances are remembered.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ServiceNotFound`] if no provider is known; use
+    /// [`find_service_with`](Self::find_service_with) first to wait for
+    /// one. Otherwise the same as
+    /// [`send_to_service`](Self::send_to_service).
+    pub async fn send_to_instance(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        message: crate::protocol::Message<MessageDefinitions>,
+    ) -> Result<PendingResponse<MessageDefinitions, C>, Error> {
+        let (send_rx, response_rx, ctrl_msg) =
+            ControlMessage::send_to_instance(service_id, instance_id, message);
+        self.control_sender
+            .send(ctrl_msg)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        send_rx.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(PendingResponse {
+            receiver: response_rx,
+        })
+    }
+
     /// Sends a request to a service and awaits the response in one call.
     ///
     /// Unlike [`send_to_service`](Self::send_to_service), this method does not
@@ -1198,10 +2868,10 @@ where
     ///
     /// # Errors
     ///
-    /// Returns an error if the service is not found, unicast binding fails,
-    /// the UDP send fails, or the response payload fails to deserialize.
+    /// Returns an error if the service is not found, unicast binding
+    /// or connecting fails, the send fails, or the response payload fails to deserialize.
     /// Returns [`Error::UnsupportedEndpoint`] if the key's endpoint is
-    /// not an IPv4/UDP socket.
+    /// not an IPv4 socket.
     /// Returns [`Error::Capacity`] with tag `"pending_responses"` if the
     /// inner loop's response-tracking map was full when this request was
     /// sent — the UDP send still went out, but the reply cannot be
@@ -1225,6 +2895,190 @@ where
         response_rx.recv().await.map_err(|_| Error::Shutdown)?
     }
 
+    /// Sends a request to a known provider of `service_id`/`instance_id`
+    /// (`0xFFFF` for any instance) and awaits the response, failing over
+    /// to the next redundant provider whenever one times out.
+    ///
+    /// The first provider is picked by the service's
+    /// [`ProviderSelection`]; each following one in the failover order it
+    /// documents. Every attempt is sent under `policy`, retries included,
+    /// so an attempt only fails over once its own retries are spent.
+    /// Only offers already known are tried: use
+    /// [`find_service_with`](Self::find_service_with) first to wait for
+    /// one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ServiceNotFound`] if no provider is known.
+    /// Returns [`Error::Timeout`] if every provider timed out.
+    /// Any other error of an attempt, as for
+    /// [`send_to_service_with_policy`](Self::send_to_service_with_policy),
+    /// is returned without trying further providers.
+    pub async fn request_with_failover(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        message: crate::protocol::Message<MessageDefinitions>,
+        policy: RequestPolicy,
+    ) -> Result<MessageDefinitions, Error> {
+        let mut primary = None;
+        for attempt in 0..=u8::MAX {
+            let failover = primary.map(|primary| (primary, attempt));
+            let (response, ctrl_msg) =
+                ControlMessage::select_provider(service_id, instance_id, failover);
+            self.control_sender
+                .send(ctrl_msg)
+                .await
+                .map_err(|()| Error::Shutdown)?;
+            let key = match response.recv().await.map_err(|_| Error::Shutdown)? {
+                Ok(key) => key,
+                Err(Error::ServiceNotFound) if primary.is_some() => break,
+                Err(e) => return Err(e),
+            };
+            primary.get_or_insert(key);
+            let pending = self
+                .send_to_service_with_policy(key, message.clone(), policy)
+                .await?;
+            match pending.response().await {
+                Err(Error::Timeout) => {
+                    warn!(
+                        "Request to service 0x{:04X} at {:?} timed out; failing over",
+                        service_id, key.endpoint,
+                    );
+                }
+                result => return result,
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    /// [`send_to_service`](Self::send_to_service) with a send deadline on
+    /// the client [`Timer`]'s clock: if the request is still queued in the
+    /// client when `deadline` passes, it is dropped instead of sent late.
+    ///
+    /// Only the send is bounded; use a timeout on the returned
+    /// [`PendingResponse`] to bound the reply as well. Without a clock
+    /// ([`Timer::now`] returning `None`) the deadline never passes.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`send_to_service`](Self::send_to_service), plus
+    /// [`Error::DeadlineExceeded`] if the request was dropped.
+    pub async fn send_to_service_before(
+        &self,
+        key: ServiceEndpointKey,
+        message: crate::protocol::Message<MessageDefinitions>,
+        deadline: crate::Deadline,
+    ) -> Result<PendingResponse<MessageDefinitions, C>, Error> {
+        let (send_rx, response_rx, ctrl_msg) =
+            ControlMessage::send_to_service_before(key, message, Some(deadline));
+        self.control_sender
+            .send(ctrl_msg)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        send_rx.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(PendingResponse {
+            receiver: response_rx,
+        })
+    }
+
+    /// [`send_to_service`](Self::send_to_service) with a timeout on the
+    /// reply: if none arrives in time the request is resent as `policy`
+    /// allows, and the returned [`PendingResponse`] resolves to
+    /// [`Error::Timeout`] once the retries are spent. See
+    /// [`RequestPolicy`] for the schedule.
+    ///
+    /// The policy only applies to `REQUEST`s; other messages are sent as
+    /// by `send_to_service`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`send_to_service`](Self::send_to_service), plus
+    /// [`Error::Capacity`] with tag `"timed_requests"` if
+    /// [`TIMED_REQUESTS_CAP`] requests with a policy are already awaiting
+    /// their reply; the request is then not sent.
+    pub async fn send_to_service_with_policy(
+        &self,
+        key: ServiceEndpointKey,
+        message: crate::protocol::Message<MessageDefinitions>,
+        policy: RequestPolicy,
+    ) -> Result<PendingResponse<MessageDefinitions, C>, Error> {
+        let (send_rx, response_rx, ctrl_msg) =
+            ControlMessage::send_to_service_with(key, message, None, Some(policy));
+        self.control_sender
+            .send(ctrl_msg)
+            .await
+            .map_err(|()| Error::Shutdown)?;
+        send_rx.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(PendingResponse {
+            receiver: response_rx,
+        })
+    }
+
+    /// Measure request/response round-trip times to the provider at
+    /// `key`: send one request per slot of `samples`, one at a time, each
+    /// shaped by `probe` and timed on `timer`'s clock, then summarize the
+    /// answered ones. A request unanswered within [`LatencyProbe::timeout`]
+    /// counts as lost rather than failing the measurement.
+    ///
+    /// Use it against an echo method, such as a `MethodRouter::echo`
+    /// route of this crate's server, to
+    /// quantify network and middleware latency on the target. The times
+    /// include the hops through the client's run-loop.
+    ///
+    /// Resolves to `Ok(None)` if no request was answered, and always does
+    /// without a clock ([`Timer::now`] returning `None`). `samples` is
+    /// left holding the answered round-trip times, sorted, in front.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`send_to_service_with_policy`](Self::send_to_service_with_policy),
+    /// plus [`Error::ErrorResponse`] if the provider answers with an
+    /// `ERROR`, and any error decoding `probe`'s payload into a message.
+    pub async fn measure_latency<Tm: Timer>(
+        &self,
+        timer: &Tm,
+        key: ServiceEndpointKey,
+        probe: LatencyProbe<'_>,
+        samples: &mut [core::time::Duration],
+    ) -> Result<Option<LatencyStats>, Error> {
+        use crate::protocol::{Header, MessageId, MessageType, MessageTypeField, ReturnCode};
+
+        let message_id = MessageId::new_from_service_and_method(key.service_id, probe.method_id);
+        let mut answered = 0;
+        let mut lost = 0;
+        for _ in 0..samples.len() {
+            let header = Header::new(
+                message_id,
+                0,
+                0x01,
+                probe.interface_version,
+                MessageTypeField::new(MessageType::Request, false),
+                ReturnCode::Ok,
+                probe.payload.len(),
+            );
+            let payload = MessageDefinitions::from_payload_bytes(message_id, probe.payload)?;
+            let message = crate::protocol::Message::new(header, payload);
+            let Some(sent) = timer.now() else {
+                return Ok(None);
+            };
+            let pending = self
+                .send_to_service_with_policy(key, message, RequestPolicy::new(probe.timeout))
+                .await?;
+            match pending.response().await {
+                Ok(_) => {
+                    if let Some(now) = timer.now() {
+                        samples[answered] = now.saturating_sub(sent);
+                        answered += 1;
+                    }
+                }
+                Err(Error::Timeout) => lost += 1,
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(LatencyStats::from_samples(&mut samples[..answered], lost))
+    }
+
     /// Register an E2E profile for the given key.
     ///
     /// Once registered, incoming messages matching `key` will have their E2E
@@ -1355,14 +3209,13 @@ where
     where
         <MessageDefinitions as PayloadWireFormat>::SdHeader: Send + 'static,
     {
-        use crate::protocol::sd;
         use crate::transport::OneshotRecv;
 
         // Use a WeakSender so this future does NOT keep the control channel
         // alive. When all strong Client handles are dropped (shut_down),
         // the weak sender will fail to upgrade and the loop exits cleanly.
         let weak_sender = self.control_sender.downgrade();
-        let target = SocketAddrV4::new(sd::MULTICAST_IP, sd::MULTICAST_PORT);
+        let target = self.sd_target;
         let interval = interval.max(std::time::Duration::from_millis(100));
 
         async move {
@@ -1435,6 +3288,42 @@ where
             }
         }
     }
+
+    /// Watch where instance `instance_id` (`0xFFFF` for any) of
+    /// `service_id` is offered, without reading the update stream.
+    ///
+    /// The receiver holds the provider endpoint, or `None` while no
+    /// offer of the instance is cached; `changed()` resolves when it
+    /// appears, moves or goes away. While the endpoint stays offered, a
+    /// second provider of the instance does not replace it. The watch
+    /// does not search: pair it with
+    /// [`find_service_no_wait`](Self::find_service_no_wait) or
+    /// [`add_endpoint`](Self::add_endpoint). It ends once every receiver
+    /// is dropped, or with the run loop.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (with tag `"service_watches"`) if
+    /// [`SERVICE_WATCHES_CAP`] watches are already open.
+    /// Returns [`Error::Shutdown`] if the client's run-loop future has
+    /// exited.
+    pub async fn watch_service(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+    ) -> Result<tokio::sync::watch::Receiver<Option<ServiceEndpointKey>>, Error> {
+        use crate::transport::OneshotRecv;
+
+        // The run loop holds the sending side and updates it in place.
+        let (sender, receiver) = tokio::sync::watch::channel(None);
+        let (response, message) = ControlMessage::watch_service(service_id, instance_id, sender);
+        self.control_sender
+            .send(message)
+            .await
+            .map_err(|_| Error::Shutdown)?;
+        response.recv().await.map_err(|_| Error::Shutdown)??;
+        Ok(receiver)
+    }
 }
 
 #[cfg(all(test, feature = "client-tokio"))]
@@ -1493,6 +3382,7 @@ mod tests {
             message: msg,
             e2e_status: None,
             source: SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 30640),
+            local_port: 30490,
         };
         let debug_str = format!("{update:?}");
         assert!(debug_str.contains("Unicast"));
@@ -1511,6 +3401,7 @@ mod tests {
             message: msg,
             e2e_status: None,
             source: src,
+            local_port: 30490,
         };
         match update {
             ClientUpdate::Unicast { source, .. } => assert_eq!(source, src),
@@ -1653,6 +3544,51 @@ mod tests {
         client.shut_down();
     }
 
+    #[tokio::test]
+    async fn test_set_version_policy_keeps_manual_endpoints() {
+        let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+        let _run_handle = tokio::spawn(run_fut);
+        let key = ServiceEndpointKey::udp(
+            0x1234,
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1)),
+        );
+        client.add_endpoint(key, 0x0001, 0).await.unwrap();
+        client
+            .set_version_policy(0x1234, Some(VersionPolicy::Exact { major: 1, minor: 0 }))
+            .await
+            .unwrap();
+        let msg = crate::protocol::Message::new_sd(1, &empty_sd_header());
+        let result = client.send_to_service(key, msg).await;
+        assert!(
+            !matches!(result, Err(Error::ServiceNotFound)),
+            "manual endpoint must survive a version policy, got {result:?}"
+        );
+        client.set_version_policy(0x1234, None).await.unwrap();
+        client.shut_down();
+    }
+
+    #[tokio::test]
+    async fn test_watch_service_follows_added_and_removed_endpoints() {
+        let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+        let _run_handle = tokio::spawn(run_fut);
+        let key = ServiceEndpointKey::udp(
+            0x1234,
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1)),
+        );
+        let mut watch = client.watch_service(0x1234, 0x0001).await.unwrap();
+        assert_eq!(*watch.borrow(), None);
+
+        client.add_endpoint(key, 0x0002, 0).await.unwrap();
+        client.add_endpoint(key, 0x0001, 0).await.unwrap();
+        watch.changed().await.unwrap();
+        assert_eq!(*watch.borrow_and_update(), Some(key));
+
+        client.remove_endpoint(key).await.unwrap();
+        watch.changed().await.unwrap();
+        assert_eq!(*watch.borrow(), None);
+        client.shut_down();
+    }
+
     #[tokio::test]
     async fn test_send_to_service_unknown_returns_error() {
         let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
@@ -2327,6 +4263,149 @@ mod tests {
         client.shut_down();
     }
 
+    /// A `recv()` dropped while pending (the losing arm of a `select!`)
+    /// must not consume an update; the `Stream` impl sees the same
+    /// sequence and ends when the loop's sender goes away.
+    #[tokio::test]
+    async fn client_updates_recv_is_cancel_safe_and_streams() {
+        use crate::transport::UnboundedSend;
+        use futures_util::{FutureExt, StreamExt};
+
+        let (update_sender, update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<TestPayload>>();
+        let mut updates = ClientUpdates::<TestPayload, TokioChannels> { update_receiver };
+        assert!(
+            updates.recv().now_or_never().is_none(),
+            "nothing queued yet"
+        );
+
+        let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30_490));
+        update_sender
+            .send_now(ClientUpdate::SenderRebooted(addr))
+            .unwrap();
+        update_sender
+            .send_now(ClientUpdate::Error(Error::Shutdown))
+            .unwrap();
+        drop(update_sender);
+
+        assert!(matches!(
+            updates.recv().await,
+            Some(ClientUpdate::SenderRebooted(a)) if a == addr
+        ));
+        assert!(matches!(
+            updates.next().await,
+            Some(ClientUpdate::Error(Error::Shutdown))
+        ));
+        assert!(updates.next().await.is_none());
+    }
+
+    /// A discovery lane nobody reads fills up and drops its oldest
+    /// updates, while events and responses queued behind them still come
+    /// through in order.
+    #[tokio::test]
+    async fn update_lanes_keep_events_flowing_past_stalled_discovery() {
+        use crate::protocol::{
+            Header, Message, MessageId, MessageType, MessageTypeField, ReturnCode,
+        };
+        use crate::traits::PayloadWireFormat;
+        use crate::transport::UnboundedSend;
+
+        let (update_sender, update_receiver) =
+            TokioChannels::unbounded::<ClientUpdate<TestPayload>>();
+        let mut lanes = ClientUpdates::<TestPayload, TokioChannels> { update_receiver }.into_lanes(
+            UpdateLanesConfig::default().with_discovery(LaneConfig::new(2, DropPolicy::DropOldest)),
+        );
+        let source = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30_490));
+        let unicast = |message_type, request_id| ClientUpdate::Unicast {
+            message: Message::new(
+                Header::new(
+                    MessageId::new_from_service_and_method(0x1234, 0x8001),
+                    request_id,
+                    1,
+                    1,
+                    MessageTypeField::new(message_type, false),
+                    ReturnCode::Ok,
+                    0,
+                ),
+                TestPayload::new_sd_payload(&empty_sd_header()),
+            ),
+            e2e_status: None,
+            source,
+            local_port: 30_491,
+        };
+
+        for port in 1..=5 {
+            let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port));
+            update_sender
+                .send_now(ClientUpdate::SenderRebooted(addr))
+                .unwrap();
+        }
+        update_sender
+            .send_now(unicast(MessageType::Notification, 1))
+            .unwrap();
+        update_sender
+            .send_now(unicast(MessageType::Response, 2))
+            .unwrap();
+        update_sender
+            .send_now(unicast(MessageType::Notification, 3))
+            .unwrap();
+        update_sender
+            .send_now(ClientUpdate::Error(Error::Shutdown))
+            .unwrap();
+
+        for expected in [1, 3] {
+            match lanes.recv(UpdateCategory::Events).await {
+                Some(ClientUpdate::Unicast { message, .. }) => {
+                    assert_eq!(message.header().request_id(), expected);
+                }
+                other => panic!("expected an event, got {other:?}"),
+            }
+        }
+        assert!(lanes.try_recv(UpdateCategory::Events).is_none());
+        assert_eq!(lanes.len(UpdateCategory::Discovery), 2);
+        assert_eq!(lanes.dropped(UpdateCategory::Discovery), 3);
+
+        drop(update_sender);
+        assert!(matches!(
+            lanes.recv_next().await,
+            Some(ClientUpdate::Error(Error::Shutdown))
+        ));
+        assert_eq!(
+            lanes.recv_next().await.map(|u| u.category()),
+            Some(UpdateCategory::Responses)
+        );
+        for port in [4, 5] {
+            assert!(matches!(
+                lanes.recv_next().await,
+                Some(ClientUpdate::SenderRebooted(SocketAddr::V4(a))) if a.port() == port
+            ));
+        }
+        assert!(lanes.recv_next().await.is_none());
+        assert!(lanes.is_empty());
+    }
+
+    /// With a `JoinSetSpawner` the socket loops started by
+    /// `bind_discovery` are owned by the caller's set rather than
+    /// detached, and `shutdown` stops them.
+    #[tokio::test]
+    async fn client_with_join_set_spawner_owns_socket_tasks() {
+        let tasks = crate::JoinSetSpawner::new();
+        let (client, _updates, run_fut) =
+            TestClient::new_with_spawner_and_loopback(Ipv4Addr::LOCALHOST, false, tasks.clone());
+        let run_handle = tokio::spawn(run_fut);
+
+        client
+            .bind_discovery()
+            .await
+            .expect("bind_discovery must succeed");
+        assert_eq!(tasks.len(), 2, "multicast + unicast SD socket loops");
+
+        client.shut_down();
+        run_handle.await.unwrap();
+        tasks.shutdown().await;
+        assert!(tasks.is_empty());
+    }
+
     /// Host-arch PROXY budgets for the client's two dominant futures.
     /// thumbv7em layouts differ (pointer width/alignment) — the
     /// authoritative numbers come from `tools/capture_type_sizes.sh`.
@@ -2335,7 +4414,34 @@ mod tests {
     /// If this trips: run the capture script and compare against the
     /// baseline before raising the budget — a layout regression in a PR
     /// is exactly what this witness exists to catch.
-    const TOKIO_CLIENT_RUN_FUTURE_BUDGET: usize = 132736; // = ceil64(106152 × 1.25)
+    const TOKIO_CLIENT_RUN_FUTURE_BUDGET: usize = 132_736
+        + 448
+        + 768
+        + 704
+        + 384
+        + 1536
+        + 7328
+        + 48
+        + 2832
+        + 16
+        + 1472
+        + 128
+        + 448
+        + 1024
+        + 320
+        + 512
+        + 1984
+        + 896
+        + 2432
+        + 64
+        + 1664
+        + 320
+        + 48
+        + 16
+        + 8
+        + 512
+        + 200
+        + 1024; // = ceil64(106152 × 1.25) + TCP connection map + first-event waiters + TCP in-flight table + janitor state + message IDs in pending-request keys + request policies and the timed-request table + session-less services + tracked subscriptions + tracked `subscribe`s + event streams + receive ports + sampled drop counts + load-balancing options of registry entries + find policies of service waiters + payload ranges in decode limits + first-seen order of registry entries and provider selections + event validators + per-peer request sessions and peers in pending-request keys + startup spacing of find policies + remembered endpoint resolutions + channel settings and overflow counts of sockets + discovery tuning options + service watches + subscription handles tied to their subscription + TCP connect timeouts + decode limits of sockets + request sessions keyed by `IpAddr`
     /// See [`TOKIO_CLIENT_RUN_FUTURE_BUDGET`] — same proxy-budget rules.
     const TOKIO_CLIENT_SOCKET_LOOP_BUDGET: usize = 8768; // = ceil64(6968 × 1.25)
 
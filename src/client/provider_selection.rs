@@ -0,0 +1,264 @@
+use heapless::Vec;
+use heapless::index_map::FnvIndexMap;
+
+use super::service_registry::{SERVICE_REGISTRY_CAP, ServiceEndpointKey, ServiceRegistry};
+use super::service_wait;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_PROVIDER_SELECTIONS_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_PROVIDER_SELECTIONS_CAP: usize = 16;
+
+/// Maximum number of services a client can pin a [`ProviderSelection`]
+/// to. Must be a power of two ([`FnvIndexMap`] requirement). Sized via
+/// `SIMPLE_SOMEIP_PROVIDER_SELECTIONS_CAP`; defaults to 4 on bare-metal,
+/// 16 otherwise.
+pub const PROVIDER_SELECTIONS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_PROVIDER_SELECTIONS_CAP"),
+    _DEFAULT_PROVIDER_SELECTIONS_CAP,
+);
+
+/// How the client picks one provider when several endpoints offer the
+/// same service instance, e.g. redundant providers on two ECUs.
+///
+/// Set per service with
+/// [`Client::set_provider_selection`](crate::Client::set_provider_selection).
+/// It decides what [`Client::find_service_with`](crate::Client::find_service_with)
+/// returns and where
+/// [`Client::request_with_failover`](crate::Client::request_with_failover)
+/// sends first; the failover then tries the other providers in the
+/// order listed for each variant. Services without a selection use
+/// [`ProviderSelection::LoadBalancing`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ProviderSelection {
+    /// Follow the offers' load-balancing options: the lowest priority
+    /// value wins, equal priorities split by weight at random. Failover
+    /// goes by priority, then by first-seen order.
+    #[default]
+    LoadBalancing,
+    /// The provider whose offer was registered first. Failover follows
+    /// first-seen order.
+    FirstSeen,
+    /// Each selection takes the provider after the previous one, in
+    /// first-seen order. Failover continues along that order.
+    RoundRobin,
+}
+
+/// Returned by [`ProviderSelections::set`] when the table is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProviderSelectionsFull;
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    selection: ProviderSelection,
+    /// Selections made so far under [`ProviderSelection::RoundRobin`].
+    turn: usize,
+}
+
+/// Per-service [`ProviderSelection`] table, with the round-robin
+/// position of each service.
+#[derive(Debug, Default)]
+pub struct ProviderSelections {
+    slots: FnvIndexMap<u16, Slot, PROVIDER_SELECTIONS_CAP>,
+}
+
+/// Offers of one service instance as `(first-seen order, key,
+/// load-balancing priority)`, in first-seen order.
+type Candidates = Vec<(u64, ServiceEndpointKey, u16), SERVICE_REGISTRY_CAP>;
+
+fn candidates(registry: &ServiceRegistry, service_id: u16, instance_id: u16) -> Candidates {
+    let mut candidates: Candidates = registry
+        .iter_seen()
+        .filter(|(key, info, _)| {
+            key.service_id == service_id
+                && (instance_id == 0xFFFF || info.instance_id == instance_id)
+        })
+        .map(|(key, info, seen)| {
+            let priority = info
+                .load_balancing
+                .map_or(u16::MAX, |(priority, _)| priority);
+            (seen, *key, priority)
+        })
+        .collect();
+    candidates.sort_unstable_by_key(|(seen, ..)| *seen);
+    candidates
+}
+
+impl ProviderSelections {
+    /// Install (`Some`) or clear (`None`) the selection for `service_id`.
+    /// Returns `Err(ProviderSelectionsFull)` if the table is at
+    /// [`PROVIDER_SELECTIONS_CAP`] and `service_id` has no selection yet.
+    pub fn set(
+        &mut self,
+        service_id: u16,
+        selection: Option<ProviderSelection>,
+    ) -> Result<(), ProviderSelectionsFull> {
+        if let Some(selection) = selection {
+            self.slots
+                .insert(service_id, Slot { selection, turn: 0 })
+                .map(|_| ())
+                .map_err(|_| ProviderSelectionsFull)
+        } else {
+            self.slots.swap_remove(&service_id);
+            Ok(())
+        }
+    }
+
+    pub fn selection(&self, service_id: u16) -> ProviderSelection {
+        self.slots
+            .get(&service_id)
+            .map_or_else(ProviderSelection::default, |slot| slot.selection)
+    }
+
+    /// Pick the provider of `service_id`/`instance_id` (`0xFFFF` for any
+    /// instance), `draw` rolling the load-balancing split. Advances the
+    /// round-robin position.
+    pub fn select(
+        &mut self,
+        registry: &ServiceRegistry,
+        service_id: u16,
+        instance_id: u16,
+        draw: u64,
+    ) -> Option<ServiceEndpointKey> {
+        match self.selection(service_id) {
+            ProviderSelection::LoadBalancing => {
+                service_wait::resolve(registry, service_id, instance_id, draw)
+            }
+            ProviderSelection::FirstSeen => candidates(registry, service_id, instance_id)
+                .first()
+                .map(|(_, key, _)| *key),
+            ProviderSelection::RoundRobin => {
+                let candidates = candidates(registry, service_id, instance_id);
+                if candidates.is_empty() {
+                    return None;
+                }
+                let slot = self.slots.get_mut(&service_id)?;
+                let (_, key, _) = candidates[slot.turn % candidates.len()];
+                slot.turn = slot.turn.wrapping_add(1);
+                Some(key)
+            }
+        }
+    }
+
+    /// The `attempt`th alternative (from 1) to `primary` for failover,
+    /// in the order the service's selection documents. `None` once every
+    /// other provider has been offered.
+    pub fn fallback(
+        &self,
+        registry: &ServiceRegistry,
+        service_id: u16,
+        instance_id: u16,
+        primary: ServiceEndpointKey,
+        attempt: u8,
+    ) -> Option<ServiceEndpointKey> {
+        let mut candidates = candidates(registry, service_id, instance_id);
+        let primary_at = candidates.iter().position(|(_, key, _)| *key == primary);
+        match self.selection(service_id) {
+            ProviderSelection::LoadBalancing => {
+                candidates.sort_unstable_by_key(|(seen, _, priority)| (*priority, *seen));
+            }
+            ProviderSelection::FirstSeen => {}
+            // Continue along the rotation from the primary.
+            ProviderSelection::RoundRobin => {
+                if let Some(at) = primary_at {
+                    candidates.rotate_left(at);
+                }
+            }
+        }
+        candidates
+            .iter()
+            .map(|(_, key, _)| *key)
+            .filter(|key| *key != primary)
+            .nth(usize::from(attempt.checked_sub(1)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::reachability::ProbeState;
+    use crate::client::service_registry::ServiceEndpointInfo;
+    use core::net::SocketAddr;
+
+    fn key(port: u16) -> ServiceEndpointKey {
+        ServiceEndpointKey::udp(0x5B, SocketAddr::from(([127, 0, 0, 1], port)))
+    }
+
+    fn offer(load_balancing: Option<(u16, u16)>) -> ServiceEndpointInfo {
+        ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing,
+        }
+    }
+
+    fn registry() -> ServiceRegistry {
+        let mut registry = ServiceRegistry::default();
+        registry.insert(key(3), offer(Some((2, 1)))).unwrap();
+        registry.insert(key(1), offer(Some((1, 1)))).unwrap();
+        registry.insert(key(2), offer(None)).unwrap();
+        registry
+    }
+
+    #[test]
+    fn first_seen_picks_the_oldest_offer_and_fails_over_in_order() {
+        let registry = registry();
+        let mut table = ProviderSelections::default();
+        table.set(0x5B, Some(ProviderSelection::FirstSeen)).unwrap();
+        assert_eq!(table.select(&registry, 0x5B, 1, 0), Some(key(3)));
+        assert_eq!(table.select(&registry, 0x5B, 1, 0), Some(key(3)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(3), 1), Some(key(1)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(3), 2), Some(key(2)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(3), 3), None);
+    }
+
+    #[test]
+    fn round_robin_rotates_and_fails_over_along_the_rotation() {
+        let registry = registry();
+        let mut table = ProviderSelections::default();
+        table
+            .set(0x5B, Some(ProviderSelection::RoundRobin))
+            .unwrap();
+        let picks: std::vec::Vec<_> = (0..4)
+            .map(|_| table.select(&registry, 0x5B, 0xFFFF, 0).unwrap())
+            .collect();
+        assert_eq!(picks, [key(3), key(1), key(2), key(3)]);
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(1), 1), Some(key(2)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(1), 2), Some(key(3)));
+    }
+
+    #[test]
+    fn load_balancing_is_the_default_and_fails_over_by_priority() {
+        let mut registry = registry();
+        let mut table = ProviderSelections::default();
+        assert_eq!(table.select(&registry, 0x5B, 1, 42), Some(key(1)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(1), 1), Some(key(3)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(1), 2), Some(key(2)));
+        assert_eq!(table.fallback(&registry, 0x5B, 1, key(1), 0), None);
+
+        registry.remove(key(1));
+        registry.remove(key(2));
+        registry.remove(key(3));
+        assert_eq!(table.select(&registry, 0x5B, 1, 42), None);
+    }
+
+    #[test]
+    fn table_reports_full_and_clears() {
+        let mut table = ProviderSelections::default();
+        for id in 0..u16::try_from(PROVIDER_SELECTIONS_CAP).unwrap() {
+            table.set(id, Some(ProviderSelection::FirstSeen)).unwrap();
+        }
+        assert_eq!(
+            table.set(0xFFFF, Some(ProviderSelection::FirstSeen)),
+            Err(ProviderSelectionsFull)
+        );
+        table.set(0, None).unwrap();
+        assert_eq!(table.selection(0), ProviderSelection::LoadBalancing);
+    }
+}
@@ -0,0 +1,222 @@
+use core::num::NonZeroU32;
+use core::time::Duration;
+
+/// Settings for the endpoint reachability prober enabled with
+/// [`Client::set_reachability_probe`](crate::Client::set_reachability_probe).
+///
+/// Each discovered or manually added endpoint is sent an empty `Request`
+/// for `method_id` every `interval`. Any reply within `timeout` —
+/// including an `Error` message such as `E_UNKNOWN_METHOD` — proves the
+/// provider process is alive. After `failures_before_degraded`
+/// consecutive unanswered probes the endpoint is marked degraded, even if
+/// its SD offer is still being renewed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProbeConfig {
+    /// Method ID of the probe request, within the probed service.
+    pub method_id: u16,
+    /// Time between probes of one endpoint.
+    pub interval: Duration,
+    /// How long to wait for a probe reply before counting a failure.
+    /// Capped at `interval`.
+    pub timeout: Duration,
+    /// Consecutive failures that mark an endpoint degraded. `0` is treated
+    /// as `1`.
+    pub failures_before_degraded: u8,
+}
+
+impl ProbeConfig {
+    /// Probe `method_id` every second, waiting 500 ms for a reply and
+    /// marking the endpoint degraded after 3 misses.
+    #[must_use]
+    pub const fn new(method_id: u16) -> Self {
+        Self {
+            method_id,
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_millis(500),
+            failures_before_degraded: 3,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_failures_before_degraded(mut self, failures: u8) -> Self {
+        self.failures_before_degraded = failures;
+        self
+    }
+}
+
+/// Probing schedule shared by every endpoint: all endpoints are probed
+/// together once per round, so per-endpoint state stays small.
+#[derive(Debug)]
+pub(super) struct Prober {
+    pub(super) config: ProbeConfig,
+    /// Number of the current round, never 0 so that a fresh
+    /// [`ProbeState`] counts as not yet visited.
+    round: u8,
+    /// When the current round started; `None` before the first.
+    round_at: Option<Duration>,
+}
+
+impl Prober {
+    pub(super) const fn new(config: ProbeConfig) -> Self {
+        Self {
+            config,
+            round: 0,
+            round_at: None,
+        }
+    }
+
+    /// `true` once the current round's replies are overdue at `now`. The
+    /// timeout is capped at the interval so a round's probes always
+    /// expire before the next round starts.
+    pub(super) fn replies_overdue(&self, now: Duration) -> bool {
+        let timeout = self.config.timeout.min(self.config.interval);
+        self.round_at
+            .is_some_and(|at| now.saturating_sub(at) >= timeout)
+    }
+
+    /// Start a new round if the interval has elapsed at `now`, returning
+    /// its number.
+    pub(super) fn start_round(&mut self, now: Duration) -> Option<u8> {
+        if self
+            .round_at
+            .is_some_and(|at| now.saturating_sub(at) < self.config.interval)
+        {
+            return None;
+        }
+        self.round = self.round.wrapping_add(1).max(1);
+        self.round_at = Some(now);
+        Some(self.round)
+    }
+}
+
+/// Reachability state of one registry endpoint. Carried over when a fresh
+/// offer replaces the entry, so a renewed offer does not hide a dead
+/// provider. Kept small: every registry slot holds one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProbeState {
+    /// Set once enough consecutive probes went unanswered; cleared by the
+    /// next reply.
+    degraded: bool,
+    failures: u8,
+    /// Last round that visited this endpoint.
+    round: u8,
+    /// Request ID of the unanswered probe, if any. Never zero: the
+    /// session counter skips 0.
+    outstanding: Option<NonZeroU32>,
+}
+
+impl ProbeState {
+    /// `true` while enough consecutive probes went unanswered.
+    pub(super) fn is_degraded(self) -> bool {
+        self.degraded
+    }
+
+    /// Count the outstanding probe, if any, as failed. Returns `true` if
+    /// this made the endpoint degraded.
+    pub(super) fn expire(&mut self, config: &ProbeConfig) -> bool {
+        if self.outstanding.take().is_none() {
+            return false;
+        }
+        self.failures = self.failures.saturating_add(1);
+        if self.degraded || self.failures < config.failures_before_degraded.max(1) {
+            return false;
+        }
+        self.degraded = true;
+        true
+    }
+
+    /// `true` if `round` has not visited this endpoint yet.
+    pub(super) fn is_pending(self, round: u8) -> bool {
+        self.round != round
+    }
+
+    /// Record that `round` sent this endpoint the probe `request_id`, or
+    /// skipped it (`None`).
+    pub(super) fn visit(&mut self, round: u8, request_id: Option<u32>) {
+        self.round = round;
+        self.outstanding = request_id.and_then(NonZeroU32::new);
+    }
+
+    /// Match a reply against the outstanding probe. Returns `None` if
+    /// `request_id` is not this endpoint's probe, otherwise whether the
+    /// reply brought a degraded endpoint back.
+    pub(super) fn answer(&mut self, request_id: u32) -> Option<bool> {
+        if self.outstanding.is_none_or(|id| id.get() != request_id) {
+            return None;
+        }
+        self.outstanding = None;
+        self.failures = 0;
+        let recovered = self.degraded;
+        self.degraded = false;
+        Some(recovered)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn rounds_follow_the_interval_and_cap_the_timeout() {
+        let mut prober = Prober::new(
+            ProbeConfig::new(0x7F00)
+                .with_interval(ms(100))
+                .with_timeout(ms(500)),
+        );
+        assert!(!prober.replies_overdue(ms(0)));
+        assert_eq!(prober.start_round(ms(0)), Some(1));
+        assert_eq!(prober.start_round(ms(99)), None);
+        assert!(!prober.replies_overdue(ms(99)));
+        assert!(
+            prober.replies_overdue(ms(100)),
+            "timeout capped at interval"
+        );
+        assert_eq!(prober.start_round(ms(100)), Some(2));
+
+        prober.round = u8::MAX;
+        assert_eq!(prober.start_round(ms(200)), Some(1), "round 0 is skipped");
+    }
+
+    #[test]
+    fn degrades_after_consecutive_misses_and_recovers_on_reply() {
+        let config = ProbeConfig::new(0x7F00).with_failures_before_degraded(2);
+        let mut state = ProbeState::default();
+        assert!(state.is_pending(1));
+
+        state.visit(1, Some(1));
+        assert!(!state.is_pending(1));
+        assert!(!state.expire(&config), "one miss is tolerated");
+        assert!(!state.expire(&config), "nothing outstanding");
+
+        state.visit(2, Some(2));
+        assert!(state.expire(&config));
+
+        // A skipped round neither fails nor recovers the endpoint.
+        state.visit(3, None);
+        assert!(!state.expire(&config));
+
+        state.visit(4, Some(4));
+        assert_eq!(state.answer(2), None, "stale request ID");
+        assert_eq!(state.answer(4), Some(true), "degraded endpoint recovered");
+
+        state.visit(5, Some(5));
+        assert_eq!(state.answer(5), Some(false));
+        assert_eq!(state.answer(5), None, "already answered");
+    }
+}
@@ -0,0 +1,111 @@
+use heapless::Vec;
+
+/// Number of dedicated receive ports a client can set with
+/// [`Client::set_receive_port`](crate::Client::set_receive_port). Sized
+/// via `SIMPLE_SOMEIP_RECEIVE_PORTS_CAP`.
+pub const RECEIVE_PORTS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_RECEIVE_PORTS_CAP"), 8);
+
+/// Returned by [`ReceivePorts::set`] when the table is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReceivePortsFull;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ReceivePort {
+    service_id: u16,
+    /// `None` for every eventgroup of the service, and its requests.
+    event_group_id: Option<u16>,
+    /// `0` until an ephemeral port is bound for it.
+    port: u16,
+}
+
+/// Unicast sockets set aside for the traffic of one service, or of one
+/// of its eventgroups, instead of the shared one.
+#[derive(Debug, Default)]
+pub struct ReceivePorts {
+    ports: Vec<ReceivePort, RECEIVE_PORTS_CAP>,
+}
+
+impl ReceivePorts {
+    /// Set (`Some`, `0` for an ephemeral port) or clear (`None`) the
+    /// receive port of `service_id`, or of its `event_group_id`.
+    pub fn set(
+        &mut self,
+        service_id: u16,
+        event_group_id: Option<u16>,
+        port: Option<u16>,
+    ) -> Result<(), ReceivePortsFull> {
+        let existing = self.ports.iter().position(|entry| {
+            entry.service_id == service_id && entry.event_group_id == event_group_id
+        });
+        match (existing, port) {
+            (Some(index), Some(port)) => self.ports[index].port = port,
+            (Some(index), None) => {
+                self.ports.swap_remove(index);
+            }
+            (None, Some(port)) => self
+                .ports
+                .push(ReceivePort {
+                    service_id,
+                    event_group_id,
+                    port,
+                })
+                .map_err(|_| ReceivePortsFull)?,
+            (None, None) => {}
+        }
+        Ok(())
+    }
+
+    /// Index of the receive port for `event_group_id` of `service_id`
+    /// (`None` for requests): the eventgroup's own, else the service's.
+    pub fn find(&self, service_id: u16, event_group_id: Option<u16>) -> Option<usize> {
+        let matching = |group: Option<u16>| {
+            self.ports
+                .iter()
+                .position(|entry| entry.service_id == service_id && entry.event_group_id == group)
+        };
+        event_group_id
+            .and_then(|group| matching(Some(group)))
+            .or_else(|| matching(None))
+    }
+
+    /// Port at `index`; `0` if it is yet to be bound.
+    pub fn port(&self, index: usize) -> u16 {
+        self.ports[index].port
+    }
+
+    /// Record the port bound for the entry at `index`.
+    pub fn bound(&mut self, index: usize, port: u16) {
+        self.ports[index].port = port;
+    }
+
+    /// `true` if `port` is set aside, so must not carry shared traffic.
+    pub fn reserves(&self, port: u16) -> bool {
+        self.ports.iter().any(|entry| entry.port == port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eventgroup_port_overrides_the_service_port() {
+        let mut ports = ReceivePorts::default();
+        ports.set(0x5B, None, Some(40_000)).unwrap();
+        ports.set(0x5B, Some(0x02), Some(0)).unwrap();
+
+        let service = ports.find(0x5B, None).unwrap();
+        assert_eq!(ports.port(service), 40_000);
+        assert_eq!(ports.find(0x5B, Some(0x01)), Some(service));
+        let group = ports.find(0x5B, Some(0x02)).unwrap();
+        assert_eq!(ports.port(group), 0, "ephemeral, not bound yet");
+        ports.bound(group, 40_001);
+        assert!(ports.reserves(40_001));
+        assert_eq!(ports.find(0x5C, None), None);
+
+        ports.set(0x5B, None, None).unwrap();
+        assert_eq!(ports.find(0x5B, Some(0x01)), None);
+        assert!(!ports.reserves(40_000));
+    }
+}
@@ -0,0 +1,119 @@
+use heapless::Vec;
+
+/// Queue lane of a control message: the service it targets, or `None`
+/// for client-wide operations (interface, discovery socket, settings).
+pub(super) trait Lane {
+    fn lane(&self) -> Option<u16>;
+}
+
+/// Bounded control-message queue drained round-robin across lanes.
+///
+/// Messages of one lane leave in FIFO order, but each pop serves the
+/// next lane in turn, so a burst of requests toward one service cannot
+/// hold up calls to unrelated services that arrived behind it. All lanes
+/// share the `N` slots; a lane is in the rotation only while it has
+/// queued messages.
+pub(super) struct FairQueue<T, const N: usize> {
+    /// Queued messages, in arrival order.
+    items: Vec<T, N>,
+    /// Lanes with queued messages, next to be served first.
+    rotation: Vec<Option<u16>, N>,
+}
+
+impl<T: Lane, const N: usize> FairQueue<T, N> {
+    pub(super) const fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            rotation: Vec::new(),
+        }
+    }
+
+    /// Enqueue `item` behind the other messages of its lane. Returns it
+    /// back if the queue is full.
+    pub(super) fn push_back(&mut self, item: T) -> Result<(), T> {
+        let lane = item.lane();
+        self.items.push(item)?;
+        if !self.rotation.contains(&lane) {
+            // Cannot fail: every lane in the rotation holds a message.
+            let _ = self.rotation.push(lane);
+        }
+        Ok(())
+    }
+
+    /// Put `item` back so it is the very next message served, ahead of
+    /// every lane. Used to retry a message after a side step such as
+    /// binding a socket. Returns it back if the queue is full.
+    pub(super) fn push_front(&mut self, item: T) -> Result<(), T> {
+        let lane = item.lane();
+        self.items.insert(0, item)?;
+        if let Some(pos) = self.rotation.iter().position(|l| *l == lane) {
+            self.rotation.remove(pos);
+        }
+        // Cannot fail: see `push_back`.
+        let _ = self.rotation.insert(0, lane);
+        Ok(())
+    }
+
+    /// Take the oldest message of the lane whose turn it is, and move that
+    /// lane to the back of the rotation.
+    pub(super) fn pop_front(&mut self) -> Option<T> {
+        let lane = *self.rotation.first()?;
+        self.rotation.remove(0);
+        let pos = self.items.iter().position(|item| item.lane() == lane)?;
+        let item = self.items.remove(pos);
+        if self.items.iter().any(|item| item.lane() == lane) {
+            let _ = self.rotation.push(lane);
+        }
+        Some(item)
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Msg(Option<u16>, u8);
+
+    impl Lane for Msg {
+        fn lane(&self) -> Option<u16> {
+            self.0
+        }
+    }
+
+    fn drain<const N: usize>(queue: &mut FairQueue<Msg, N>) -> std::vec::Vec<u8> {
+        core::iter::from_fn(|| queue.pop_front())
+            .map(|m| m.1)
+            .collect()
+    }
+
+    #[test]
+    fn burst_on_one_lane_does_not_block_others() {
+        let mut queue = FairQueue::<Msg, 8>::new();
+        for n in 0..4 {
+            queue.push_back(Msg(Some(0xA), n)).unwrap();
+        }
+        queue.push_back(Msg(Some(0xB), 10)).unwrap();
+        queue.push_back(Msg(None, 20)).unwrap();
+        queue.push_back(Msg(Some(0xB), 11)).unwrap();
+        assert_eq!(drain(&mut queue), [0, 10, 20, 1, 11, 2, 3]);
+        assert_eq!(queue.len(), 0);
+    }
+
+    #[test]
+    fn push_front_is_served_next_and_capacity_is_shared() {
+        let mut queue = FairQueue::<Msg, 3>::new();
+        queue.push_back(Msg(Some(0xA), 0)).unwrap();
+        queue.push_back(Msg(Some(0xB), 1)).unwrap();
+        let retried = queue.pop_front().unwrap();
+        queue.push_back(Msg(Some(0xB), 2)).unwrap();
+        queue.push_front(retried).unwrap();
+        assert_eq!(queue.push_back(Msg(None, 3)), Err(Msg(None, 3)));
+        assert_eq!(drain(&mut queue), [0, 1, 2]);
+    }
+}
@@ -0,0 +1,193 @@
+use core::time::Duration;
+
+use heapless::Vec;
+
+use crate::protocol::Message;
+
+use super::service_registry::ServiceEndpointKey;
+use super::tcp_in_flight::RequestKey;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_TIMED_REQUESTS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_TIMED_REQUESTS_CAP: usize = 8;
+
+/// Number of requests sent with a [`RequestPolicy`] that can await their
+/// reply at the same time. Sized via `SIMPLE_SOMEIP_TIMED_REQUESTS_CAP`;
+/// defaults to 2 on bare-metal, 8 otherwise.
+pub const TIMED_REQUESTS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_TIMED_REQUESTS_CAP"),
+    _DEFAULT_TIMED_REQUESTS_CAP,
+);
+
+/// Timeout and retry policy of a request sent with
+/// [`Client::send_to_service_with_policy`](crate::Client::send_to_service_with_policy).
+///
+/// A request still unanswered `timeout` after it was sent is resent with
+/// a fresh request ID, up to `retries` times: the first resend waits
+/// `backoff`, each further one twice as long as the one before. Once the
+/// retries are spent the request resolves to
+/// [`Error::Timeout`](super::Error::Timeout), and a late reply is
+/// forwarded as [`ClientUpdate::Unicast`](super::ClientUpdate::Unicast).
+///
+/// Timeouts are checked on the client's 125 ms tick and need a clock
+/// from [`Timer::now`](crate::Timer::now); without one requests never
+/// time out.
+///
+/// Resending keeps a heap copy of the request, so builds without an
+/// allocator (neither `std` nor `embassy_channels`) ignore `retries` and
+/// only time requests out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// How long to wait for the reply to each attempt.
+    pub timeout: Duration,
+    /// Resends after the first attempt.
+    pub retries: u8,
+    /// Wait before the first resend; doubles for each further one.
+    pub backoff: Duration,
+}
+
+impl RequestPolicy {
+    /// Time out after `timeout`, without retries.
+    #[must_use]
+    pub const fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            retries: 0,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_retries(mut self, retries: u8, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// Policy of the resend after an attempt under `self` timed out.
+    pub(super) fn next_attempt(self) -> Self {
+        Self {
+            timeout: self.timeout,
+            retries: self.retries.saturating_sub(1),
+            backoff: self.backoff.saturating_mul(2),
+        }
+    }
+}
+
+/// Copy of a request kept to resend it, boxed so the table of timed
+/// requests stays small whatever the payload type. Without an allocator
+/// no copy is kept and requests are not resent.
+#[cfg(feature = "_alloc")]
+pub(super) type Resend<P> = alloc::boxed::Box<Message<P>>;
+#[cfg(not(feature = "_alloc"))]
+pub(super) type Resend<P> = (core::convert::Infallible, core::marker::PhantomData<P>);
+
+/// Keep a copy of `message` to resend it, where an allocator allows.
+// Always `Some` with an allocator, always `None` without.
+#[allow(clippy::unnecessary_wraps)]
+pub(super) fn keep_for_resend<P: Clone>(message: &Message<P>) -> Option<Resend<P>> {
+    #[cfg(feature = "_alloc")]
+    {
+        Some(alloc::boxed::Box::new(message.clone()))
+    }
+    #[cfg(not(feature = "_alloc"))]
+    {
+        let _ = message;
+        None
+    }
+}
+
+/// The message kept by [`keep_for_resend`].
+#[allow(clippy::boxed_local)]
+pub(super) fn into_resend<P>(resend: Resend<P>) -> Message<P> {
+    #[cfg(feature = "_alloc")]
+    {
+        *resend
+    }
+    #[cfg(not(feature = "_alloc"))]
+    {
+        match resend.0 {}
+    }
+}
+
+/// A request sent with a [`RequestPolicy`], awaiting its reply or its
+/// resend.
+#[derive(Debug)]
+pub(super) struct TimedRequest<P, S> {
+    pub(super) key: ServiceEndpointKey,
+    pub(super) request: RequestKey,
+    pub(super) policy: RequestPolicy,
+    /// Copy to resend, while retries remain.
+    pub(super) message: Option<Resend<P>>,
+    /// When the reply is due or, while backing off, when to resend.
+    pub(super) due: Duration,
+    /// The caller's response sender while backing off. Otherwise it is
+    /// in the pending-response map, under `request`.
+    pub(super) response: Option<S>,
+}
+
+/// What to do with a [`TimedRequest`] at a given time.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Verdict {
+    Wait,
+    /// No retries left: resolve to `Error::Timeout`.
+    GiveUp,
+    /// Hold the response until `due` passes.
+    BackOff,
+    Resend,
+}
+
+impl<P, S> TimedRequest<P, S> {
+    /// What is due at `now`.
+    pub(super) fn verdict(&self, now: Duration) -> Verdict {
+        if now < self.due {
+            Verdict::Wait
+        } else if self.response.is_some() {
+            Verdict::Resend
+        } else if self.message.is_none() {
+            Verdict::GiveUp
+        } else if self.policy.backoff.is_zero() {
+            Verdict::Resend
+        } else {
+            Verdict::BackOff
+        }
+    }
+}
+
+/// Requests of one client awaiting their reply or their resend.
+pub(super) type TimedRequests<P, S> = Vec<TimedRequest<P, S>, TIMED_REQUESTS_CAP>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::MessageId;
+    use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+    #[test]
+    fn next_attempt_doubles_backoff_and_verdict_follows_deadline() {
+        let policy =
+            RequestPolicy::new(Duration::from_secs(1)).with_retries(2, Duration::from_millis(100));
+        assert_eq!(
+            policy.next_attempt(),
+            RequestPolicy::new(Duration::from_secs(1)).with_retries(1, Duration::from_millis(200))
+        );
+        assert_eq!(policy.next_attempt().next_attempt().retries, 0);
+
+        let mut timed = TimedRequest::<(), ()> {
+            key: ServiceEndpointKey::udp(
+                0x5B,
+                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30509)),
+            ),
+            request: RequestKey::new(Ipv4Addr::LOCALHOST, MessageId::new(0x005B_0001), 1),
+            policy,
+            message: None,
+            due: Duration::from_secs(10),
+            response: None,
+        };
+        assert_eq!(timed.verdict(Duration::from_millis(9999)), Verdict::Wait);
+        assert_eq!(timed.verdict(Duration::from_secs(10)), Verdict::GiveUp);
+        timed.response = Some(());
+        assert_eq!(timed.verdict(Duration::from_secs(10)), Verdict::Resend);
+    }
+}
@@ -1,3 +1,4 @@
+use super::reachability::ProbeState;
 use crate::NetEndpoint;
 use heapless::index_map::FnvIndexMap;
 
@@ -10,6 +11,14 @@ use heapless::index_map::FnvIndexMap;
 pub const SERVICE_REGISTRY_CAP: usize =
     crate::from_env_or(option_env!("SIMPLE_SOMEIP_SERVICE_REGISTRY_CAP"), 64);
 
+/// Maximum number of `(service, instance)` to endpoint resolutions the
+/// registry remembers for
+/// [`Client::send_to_instance`](crate::Client::send_to_instance). Must
+/// be a power of two ([`FnvIndexMap`] requirement). Past the cap one of
+/// the remembered resolutions is forgotten and re-done on its next use.
+pub const RESOLVED_ENDPOINTS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_RESOLVED_ENDPOINTS_CAP"), 16);
+
 /// Identifies one service instance by its wire identity: the service id
 /// plus the provider's transport endpoint. Per AUTOSAR `PRS_SOMEIP`
 /// §4.2.1.3, a service instance is identified "through the combination
@@ -30,6 +39,7 @@ pub const SERVICE_REGISTRY_CAP: usize =
 /// `Copy`. For today's UDP transports, [`ServiceEndpointKey::udp`] is
 /// the convenient constructor.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ServiceEndpointKey {
     pub service_id: u16,
     pub endpoint: NetEndpoint,
@@ -59,15 +69,52 @@ pub struct ServiceEndpointInfo {
     /// carry it on the wire, so it is stored as data.
     pub instance_id: u16,
     pub local_port: u16,
-    #[allow(dead_code)]
     pub major_version: u8,
-    #[allow(dead_code)]
     pub minor_version: u32,
+    /// [`Timer::now`](crate::Timer::now) instant at which the offer
+    /// lapses. `None` for manual endpoints, infinite TTLs, and timers
+    /// without a clock.
+    pub expires_at: Option<core::time::Duration>,
+    /// Set once `ClientUpdate::OfferExpiring` has been emitted for this
+    /// offer; a fresh offer replaces the entry and clears it.
+    pub expiry_notified: bool,
+    /// Reachability probing state, including whether the endpoint is
+    /// degraded. Unlike the fields above it survives offer renewal.
+    pub probe: ProbeState,
+    /// Added with `Client::add_static_endpoint`: SD offers and stops for
+    /// the endpoint leave the entry alone.
+    pub is_static: bool,
+    /// `(priority, weight)` from the offer's load-balancing option. `None`
+    /// for offers without one and for manual endpoints.
+    pub load_balancing: Option<(u16, u16)>,
+}
+
+impl ServiceEndpointInfo {
+    /// Time left before the offer lapses, saturating at zero. `None` if
+    /// the entry has no deadline.
+    #[must_use]
+    pub fn remaining(&self, now: core::time::Duration) -> Option<core::time::Duration> {
+        self.expires_at.map(|at| at.saturating_sub(now))
+    }
+}
+
+/// A registry slot: the endpoint plus the order in which its key was
+/// first registered. Replacing the info (offer renewal) keeps the order.
+#[derive(Debug)]
+struct Entry {
+    seen: u64,
+    info: ServiceEndpointInfo,
 }
 
 #[derive(Debug, Default)]
 pub struct ServiceRegistry {
-    endpoints: FnvIndexMap<ServiceEndpointKey, ServiceEndpointInfo, SERVICE_REGISTRY_CAP>,
+    endpoints: FnvIndexMap<ServiceEndpointKey, Entry, SERVICE_REGISTRY_CAP>,
+    /// Order stamp of the next key registered.
+    next_seen: u64,
+    /// Endpoint picked for each `(service_id, instance_id)`, `0xFFFF`
+    /// instance for any. Dropped with the entry it points at and when a
+    /// new endpoint of the service registers, which may be a better pick.
+    resolved: FnvIndexMap<(u16, u16), ServiceEndpointKey, RESOLVED_ENDPOINTS_CAP>,
 }
 
 /// Returned by [`ServiceRegistry::insert`] when the registry is full.
@@ -84,25 +131,160 @@ impl ServiceRegistry {
         key: ServiceEndpointKey,
         info: ServiceEndpointInfo,
     ) -> Result<(), ServiceRegistryFull> {
+        if let Some(entry) = self.endpoints.get_mut(&key) {
+            if entry.info.instance_id != info.instance_id {
+                self.resolved.retain(|_, resolved| *resolved != key);
+            }
+            entry.info = info;
+            return Ok(());
+        }
+        let seen = self.next_seen;
         self.endpoints
-            .insert(key, info)
-            .map(|_| ())
-            .map_err(|_| ServiceRegistryFull)
+            .insert(key, Entry { seen, info })
+            .map_err(|_| ServiceRegistryFull)?;
+        self.next_seen += 1;
+        self.forget_resolved(key.service_id);
+        Ok(())
     }
 
     pub fn remove(&mut self, key: ServiceEndpointKey) -> Option<ServiceEndpointInfo> {
-        self.endpoints.swap_remove(&key)
+        self.resolved.retain(|_, resolved| *resolved != key);
+        self.endpoints.swap_remove(&key).map(|entry| entry.info)
+    }
+
+    /// The endpoint remembered for `service_id`/`instance_id` by
+    /// [`Self::remember_resolved`], if it is still registered.
+    pub fn resolved(&self, service_id: u16, instance_id: u16) -> Option<ServiceEndpointKey> {
+        self.resolved.get(&(service_id, instance_id)).copied()
+    }
+
+    /// Remember `key` as the endpoint of `service_id`/`instance_id` until
+    /// it is removed or the service's providers change.
+    pub fn remember_resolved(
+        &mut self,
+        service_id: u16,
+        instance_id: u16,
+        key: ServiceEndpointKey,
+    ) {
+        if self.resolved.is_full()
+            && !self.resolved.contains_key(&(service_id, instance_id))
+            && let Some(evicted) = self.resolved.keys().next().copied()
+        {
+            self.resolved.swap_remove(&evicted);
+        }
+        let _ = self.resolved.insert((service_id, instance_id), key);
+    }
+
+    /// Forget the endpoints remembered for `service_id`.
+    pub fn forget_resolved(&mut self, service_id: u16) {
+        self.resolved.retain(|(id, _), _| *id != service_id);
+    }
+
+    /// Forget the endpoints remembered at `ip`, e.g. once that provider
+    /// rebooted.
+    pub fn forget_resolved_at(&mut self, ip: core::net::IpAddr) {
+        self.resolved
+            .retain(|_, resolved| resolved.endpoint.addr.ip() != ip);
     }
 
     pub fn get(&self, key: ServiceEndpointKey) -> Option<&ServiceEndpointInfo> {
-        self.endpoints.get(&key)
+        self.endpoints.get(&key).map(|entry| &entry.info)
+    }
+
+    pub fn get_mut(&mut self, key: ServiceEndpointKey) -> Option<&mut ServiceEndpointInfo> {
+        self.endpoints.get_mut(&key).map(|entry| &mut entry.info)
+    }
+
+    /// Every entry, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&ServiceEndpointKey, &ServiceEndpointInfo)> {
+        self.endpoints.iter().map(|(key, entry)| (key, &entry.info))
+    }
+
+    /// Every entry with its first-seen order: lower values were
+    /// registered earlier, and renewing an entry keeps its value.
+    pub fn iter_seen(
+        &self,
+    ) -> impl Iterator<Item = (&ServiceEndpointKey, &ServiceEndpointInfo, u64)> {
+        self.endpoints
+            .iter()
+            .map(|(key, entry)| (key, &entry.info, entry.seen))
+    }
+
+    /// Every entry, mutably, in no particular order.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&ServiceEndpointKey, &mut ServiceEndpointInfo)> {
+        self.endpoints
+            .iter_mut()
+            .map(|(key, entry)| (key, &mut entry.info))
+    }
+
+    /// Key of the first entry for which `matches` returns `true`.
+    pub fn find(
+        &self,
+        mut matches: impl FnMut(&ServiceEndpointKey, &ServiceEndpointInfo) -> bool,
+    ) -> Option<ServiceEndpointKey> {
+        self.iter()
+            .find(|(key, info)| matches(key, info))
+            .map(|(key, _)| *key)
+    }
+
+    /// Keep only the entries for which `keep` returns `true`.
+    pub fn retain(
+        &mut self,
+        mut keep: impl FnMut(&ServiceEndpointKey, &ServiceEndpointInfo) -> bool,
+    ) {
+        let resolved = &mut self.resolved;
+        self.endpoints.retain(|key, entry| {
+            let kept = keep(key, &entry.info);
+            if !kept {
+                resolved.retain(|_, resolved| resolved != key);
+            }
+            kept
+        });
+    }
+
+    /// Longest remaining lifetime among the offers of
+    /// `service_id`/`instance_id`, or `None` if no such entry carries a
+    /// deadline.
+    pub fn offer_ttl_remaining(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        now: core::time::Duration,
+    ) -> Option<core::time::Duration> {
+        self.iter()
+            .filter(|(key, info)| key.service_id == service_id && info.instance_id == instance_id)
+            .filter_map(|(_, info)| info.remaining(now))
+            .max()
+    }
+
+    /// Call `notify` once for every offer whose remaining lifetime has
+    /// dropped to `margin` or below, marking it so later calls skip it.
+    pub fn take_expiring(
+        &mut self,
+        now: core::time::Duration,
+        margin: core::time::Duration,
+        mut notify: impl FnMut(ServiceEndpointKey, &ServiceEndpointInfo, core::time::Duration),
+    ) {
+        for (key, info) in self.iter_mut() {
+            if info.expiry_notified {
+                continue;
+            }
+            if let Some(remaining) = info.remaining(now)
+                && remaining <= margin
+            {
+                info.expiry_notified = true;
+                notify(*key, info, remaining);
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+    use core::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
 
     fn key(service: u16, ip: Ipv4Addr, port: u16) -> ServiceEndpointKey {
         ServiceEndpointKey::udp(service, SocketAddr::V4(SocketAddrV4::new(ip, port)))
@@ -113,6 +295,11 @@ mod tests {
             local_port: 0,
             major_version: 1,
             minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
         }
     }
     const A: Ipv4Addr = Ipv4Addr::LOCALHOST;
@@ -194,6 +381,29 @@ mod tests {
         assert!(reg.get(key(0x47, B, 30002)).is_some());
     }
 
+    #[test]
+    fn first_seen_order_survives_renewal_and_removal() {
+        let mut reg = ServiceRegistry::default();
+        reg.insert(key(0x47, A, 30001), info(1)).unwrap();
+        reg.insert(key(0x47, B, 30002), info(1)).unwrap();
+        reg.insert(key(0x47, A, 30003), info(1)).unwrap();
+        reg.insert(key(0x47, A, 30001), info(2)).unwrap();
+        reg.remove(key(0x47, B, 30002));
+        reg.insert(key(0x47, B, 30002), info(1)).unwrap();
+        let mut seen: std::vec::Vec<_> = reg.iter_seen().map(|(k, _, seen)| (seen, *k)).collect();
+        seen.sort_unstable_by_key(|(seen, _)| *seen);
+        let order: std::vec::Vec<_> = seen.into_iter().map(|(_, k)| k.endpoint).collect();
+        assert_eq!(
+            order,
+            [
+                key(0x47, A, 30001).endpoint,
+                key(0x47, A, 30003).endpoint,
+                key(0x47, B, 30002).endpoint,
+            ],
+            "renewal keeps its place, re-registration goes last"
+        );
+    }
+
     #[test]
     fn get_missing_returns_none() {
         let reg = ServiceRegistry::default();
@@ -231,4 +441,62 @@ mod tests {
         assert!(reg.insert(key(0, A, 30000), info(9999)).is_ok());
         assert_eq!(reg.get(key(0, A, 30000)).unwrap().instance_id, 9999);
     }
+
+    #[test]
+    fn expiring_offers_notify_once() {
+        use core::time::Duration;
+        let mut reg = ServiceRegistry::default();
+        let mut soon = info(1);
+        soon.expires_at = Some(Duration::from_secs(3));
+        let mut later = info(1);
+        later.expires_at = Some(Duration::from_secs(10));
+        reg.insert(key(0x47, A, 30001), soon).unwrap();
+        reg.insert(key(0x47, B, 30002), later).unwrap();
+        reg.insert(key(0x48, A, 30003), info(1)).unwrap();
+
+        let now = Duration::from_secs(2);
+        assert_eq!(
+            reg.offer_ttl_remaining(0x47, 1, now),
+            Some(Duration::from_secs(8))
+        );
+        assert_eq!(reg.offer_ttl_remaining(0x48, 1, now), None);
+
+        let mut fired = 0;
+        reg.take_expiring(now, Duration::from_secs(1), |k, _, remaining| {
+            assert_eq!(k, key(0x47, A, 30001));
+            assert_eq!(remaining, Duration::from_secs(1));
+            fired += 1;
+        });
+        reg.take_expiring(now, Duration::from_secs(1), |_, _, _| fired += 1);
+        assert_eq!(fired, 1);
+    }
+
+    #[test]
+    fn resolved_endpoints_are_forgotten_with_their_provider() {
+        let mut reg = ServiceRegistry::default();
+        let a = key(0x47, A, 30001);
+        let b = key(0x47, B, 30002);
+        reg.insert(a, info(1)).unwrap();
+        reg.remember_resolved(0x47, 1, a);
+        reg.remember_resolved(0x47, 0xFFFF, a);
+        reg.insert(a, info(1)).unwrap();
+        assert_eq!(reg.resolved(0x47, 1), Some(a), "renewal keeps it");
+
+        reg.remove(a);
+        assert_eq!(reg.resolved(0x47, 1), None, "withdrawn");
+        assert_eq!(reg.resolved(0x47, 0xFFFF), None);
+
+        reg.insert(a, info(1)).unwrap();
+        reg.remember_resolved(0x47, 1, a);
+        reg.insert(b, info(1)).unwrap();
+        assert_eq!(reg.resolved(0x47, 1), None, "another provider appeared");
+
+        reg.remember_resolved(0x47, 1, b);
+        reg.forget_resolved_at(IpAddr::V4(B));
+        assert_eq!(reg.resolved(0x47, 1), None, "provider rebooted");
+
+        reg.remember_resolved(0x47, 1, b);
+        reg.retain(|k, _| *k != b);
+        assert_eq!(reg.resolved(0x47, 1), None, "expired");
+    }
 }
@@ -0,0 +1,408 @@
+use core::net::IpAddr;
+use core::time::Duration;
+
+use heapless::Vec;
+
+use super::service_registry::{ServiceEndpointKey, ServiceRegistry};
+
+/// Number of [`Client::wait_for_service`](crate::Client::wait_for_service)
+/// calls that can wait at the same time. Sized via
+/// `SIMPLE_SOMEIP_SERVICE_WAITERS_CAP`.
+pub const SERVICE_WAITERS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_SERVICE_WAITERS_CAP"), 8);
+
+/// How [`Client::find_service_with`](crate::Client::find_service_with)
+/// looks for a service, following the SOME/IP-SD find phases.
+///
+/// The first `FindService` goes out after a random initial wait between
+/// `initial_delay_min` and `initial_delay_max`, then is repeated
+/// `repetitions_max` times, the first repetition `repetitions_base_delay`
+/// later and each further one after twice the previous delay. Past them
+/// the search relies on the provider's cyclic offers. It ends as soon as a
+/// matching offer is known, or once `timeout` has passed. A non-zero
+/// `startup_spacing` further holds the first `FindService` until that
+/// long after the first SD message of every server or search of the
+/// process that started before, so many searches and offers starting
+/// together go out one after another.
+///
+/// Timing needs a clock from [`Timer::now`](crate::Timer::now): without
+/// one, the first `FindService` is sent right away and never repeated,
+/// and the search has no timeout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FindPolicy {
+    pub initial_delay_min: Duration,
+    pub initial_delay_max: Duration,
+    pub startup_spacing: Duration,
+    pub repetitions_max: u8,
+    pub repetitions_base_delay: Duration,
+    /// `None` searches until an offer arrives.
+    pub timeout: Option<Duration>,
+}
+
+impl FindPolicy {
+    /// Find right away, repeat three times from 200 ms, with no timeout.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            initial_delay_min: Duration::ZERO,
+            initial_delay_max: Duration::ZERO,
+            startup_spacing: Duration::ZERO,
+            repetitions_max: 3,
+            repetitions_base_delay: Duration::from_millis(200),
+            timeout: None,
+        }
+    }
+
+    /// Wait a random delay between `min` and `max` before the first
+    /// `FindService`; a `max` below `min` waits exactly `min`.
+    #[must_use]
+    pub const fn with_initial_delay(mut self, min: Duration, max: Duration) -> Self {
+        self.initial_delay_min = min;
+        self.initial_delay_max = max;
+        self
+    }
+
+    /// Space the first `FindService` at least `spacing` after the first
+    /// SD message of every server or search of the process started
+    /// before it.
+    #[must_use]
+    pub const fn with_startup_spacing(mut self, spacing: Duration) -> Self {
+        self.startup_spacing = spacing;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_repetitions(mut self, max: u8, base_delay: Duration) -> Self {
+        self.repetitions_max = max;
+        self.repetitions_base_delay = base_delay;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The initial wait, drawn with `draw`.
+    fn initial_delay(&self, draw: u64) -> Duration {
+        let min = self.initial_delay_min;
+        let Some(span) = self.initial_delay_max.checked_sub(min) else {
+            return min;
+        };
+        let span_micros = u64::try_from(span.as_micros()).unwrap_or(u64::MAX);
+        if span_micros == 0 {
+            return min;
+        }
+        min + Duration::from_micros(draw % span_micros.saturating_add(1))
+    }
+}
+
+impl Default for FindPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One pending `find_service`, answered through `response`.
+#[derive(Debug)]
+pub(super) struct ServiceWaiter<S> {
+    pub(super) service_id: u16,
+    /// `0xFFFF` for any instance.
+    pub(super) instance_id: u16,
+    /// `None` without a clock or timeout: the wait then has no timeout.
+    deadline: Option<Duration>,
+    /// When the next `FindService` is due; `None` once repetitions are
+    /// exhausted or without a clock.
+    next_find: Option<Duration>,
+    /// `FindService`s sent so far, the initial one included.
+    finds: u8,
+    repetitions_max: u8,
+    repetitions_base_delay: Duration,
+    pub(super) response: S,
+}
+
+impl<S> ServiceWaiter<S> {
+    /// A waiter starting at `now`, its initial wait drawn with `draw`.
+    pub(super) fn new(
+        service_id: u16,
+        instance_id: u16,
+        policy: FindPolicy,
+        now: Option<Duration>,
+        draw: u64,
+        response: S,
+    ) -> Self {
+        Self {
+            service_id,
+            instance_id,
+            deadline: now
+                .zip(policy.timeout)
+                .map(|(now, timeout)| now.saturating_add(timeout)),
+            next_find: now.map(|now| {
+                let delay = policy.initial_delay(draw);
+                now.saturating_add(crate::sd_startup::spread(
+                    policy.startup_spacing,
+                    Some(now),
+                    delay,
+                ))
+            }),
+            finds: 0,
+            repetitions_max: policy.repetitions_max,
+            repetitions_base_delay: policy.repetitions_base_delay,
+            response,
+        }
+    }
+
+    /// `true` once `now` is past the deadline.
+    pub(super) fn has_timed_out(&self, now: Option<Duration>) -> bool {
+        now.zip(self.deadline)
+            .is_some_and(|(now, deadline)| now >= deadline)
+    }
+
+    /// `true` if a `FindService` is due at `now`; schedules the next one.
+    /// Without a clock only the initial one is ever due.
+    pub(super) fn take_find_due(&mut self, now: Option<Duration>) -> bool {
+        let Some(now) = now else {
+            let initial = self.finds == 0;
+            self.finds = 1;
+            return initial;
+        };
+        match self.next_find {
+            Some(due) if now >= due => {}
+            _ => return false,
+        }
+        self.finds = self.finds.saturating_add(1);
+        self.next_find = (self.finds <= self.repetitions_max).then(|| {
+            let factor = 1u32
+                .checked_shl(u32::from(self.finds) - 1)
+                .unwrap_or(u32::MAX);
+            now.saturating_add(self.repetitions_base_delay.saturating_mul(factor))
+        });
+        true
+    }
+}
+
+/// Registry entry resolving a wait for `service_id`/`instance_id`
+/// (`0xFFFF` matching any instance), if one is known.
+///
+/// When several offers match, their load-balancing options decide: only
+/// the offers with the lowest priority value compete, each chosen with a
+/// chance proportional to its weight, as rolled by `draw`. An offer
+/// without the option ranks last with weight 1; if every competing
+/// weight is 0, they are equally likely.
+pub(super) fn resolve(
+    registry: &ServiceRegistry,
+    service_id: u16,
+    instance_id: u16,
+    draw: u64,
+) -> Option<ServiceEndpointKey> {
+    let candidates = || {
+        registry
+            .iter()
+            .filter(move |(key, info)| {
+                key.service_id == service_id
+                    && (instance_id == 0xFFFF || info.instance_id == instance_id)
+            })
+            .map(|(key, info)| (*key, info.load_balancing.unwrap_or((u16::MAX, 1))))
+    };
+    let priority = candidates().map(|(_, (priority, _))| priority).min()?;
+    let competing = || candidates().filter(move |(_, (p, _))| *p == priority);
+    let total: u64 = competing().map(|(_, (_, weight))| u64::from(weight)).sum();
+    let share = |weight: u16| if total == 0 { 1 } else { u64::from(weight) };
+    let mut pick = draw
+        % competing()
+            .map(|(_, (_, weight))| share(weight))
+            .sum::<u64>();
+    for (key, (_, weight)) in competing() {
+        if pick < share(weight) {
+            return Some(key);
+        }
+        pick -= share(weight);
+    }
+    None
+}
+
+/// The `draw` for [`resolve`]: a pseudo-random value from the clock and
+/// the wanted service. Without a clock the same service always draws the
+/// same value.
+pub(super) fn draw(now: Option<Duration>, service_id: u16, instance_id: u16) -> u64 {
+    crate::jitter::draw(now, u64::from(service_id) << 16 | u64::from(instance_id))
+}
+
+/// Pending waits of one client.
+pub(super) type ServiceWaiters<S> = Vec<ServiceWaiter<S>, SERVICE_WAITERS_CAP>;
+
+/// Number of
+/// [`Client::subscribe_and_wait_first`](crate::Client::subscribe_and_wait_first)
+/// calls that can wait for their first notification at the same time.
+/// Sized via `SIMPLE_SOMEIP_EVENT_WAITERS_CAP`.
+pub const EVENT_WAITERS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_EVENT_WAITERS_CAP"), 8);
+
+/// One subscription waiting for its first notification, answered through
+/// `response`.
+#[derive(Debug)]
+pub(super) struct EventWaiter<S> {
+    service_id: u16,
+    /// Address of the provider the subscription went to. Only the IP is
+    /// matched: events may come from another port than the offered one.
+    provider: IpAddr,
+    /// `None` without a clock: the wait then has no timeout.
+    deadline: Option<Duration>,
+    pub(super) response: S,
+}
+
+impl<S> EventWaiter<S> {
+    /// A waiter for a subscription sent at `now`.
+    pub(super) fn new(
+        service_id: u16,
+        provider: IpAddr,
+        timeout: Duration,
+        now: Option<Duration>,
+        response: S,
+    ) -> Self {
+        Self {
+            service_id,
+            provider,
+            deadline: now.map(|now| now.saturating_add(timeout)),
+            response,
+        }
+    }
+
+    /// `true` if a notification of `service_id` from `source` answers
+    /// this wait.
+    pub(super) fn matches(&self, service_id: u16, source: IpAddr) -> bool {
+        self.service_id == service_id && self.provider == source
+    }
+
+    /// `true` once `now` is past the deadline.
+    pub(super) fn has_timed_out(&self, now: Option<Duration>) -> bool {
+        now.zip(self.deadline)
+            .is_some_and(|(now, deadline)| now >= deadline)
+    }
+}
+
+/// Subscriptions of one client waiting for their first notification.
+pub(super) type EventWaiters<S> = Vec<EventWaiter<S>, EVENT_WAITERS_CAP>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_repeat_with_doubling_delay_until_exhausted() {
+        let start = Duration::from_secs(10);
+        let policy = FindPolicy::new().with_timeout(Duration::from_secs(5));
+        let mut waiter = ServiceWaiter::new(0x5B, 1, policy, Some(start), 0, ());
+        let at = |ms| Some(start + Duration::from_millis(ms));
+        assert!(waiter.take_find_due(at(0)), "initial find");
+        assert!(!waiter.take_find_due(at(199)));
+        assert!(waiter.take_find_due(at(200)));
+        assert!(!waiter.take_find_due(at(599)));
+        assert!(waiter.take_find_due(at(600)));
+        assert!(waiter.take_find_due(at(1400)));
+        assert!(!waiter.take_find_due(at(4000)), "repetitions exhausted");
+        assert!(!waiter.has_timed_out(at(4999)));
+        assert!(waiter.has_timed_out(at(5000)));
+
+        let mut clockless = ServiceWaiter::new(0x5B, 1, policy, None, 0, ());
+        assert!(clockless.take_find_due(None), "initial find");
+        assert!(!clockless.take_find_due(None));
+        assert!(!clockless.has_timed_out(None), "no timeout without a clock");
+    }
+
+    #[test]
+    fn startup_spacing_staggers_searches_starting_together() {
+        // Far from the clock readings of other tests sharing the
+        // process-wide schedule.
+        let start = Duration::from_hours(24);
+        let ms = Duration::from_millis;
+        let policy = FindPolicy::new().with_startup_spacing(ms(20));
+        let mut first = ServiceWaiter::new(0x5B, 1, policy, Some(start), 0, ());
+        let mut second = ServiceWaiter::new(0x5C, 1, policy, Some(start), 0, ());
+        let at = |millis| Some(start + ms(millis));
+        assert!(first.take_find_due(at(0)));
+        assert!(!second.take_find_due(at(19)));
+        assert!(second.take_find_due(at(20)));
+    }
+
+    #[test]
+    fn initial_wait_delays_the_first_find() {
+        let start = Duration::from_secs(10);
+        let ms = Duration::from_millis;
+        let policy = FindPolicy::new()
+            .with_initial_delay(ms(50), ms(150))
+            .with_repetitions(1, ms(20));
+        let delays: std::vec::Vec<_> = (0..64)
+            .map(|i| policy.initial_delay(draw(Some(ms(i)), 0x5B, 1)))
+            .collect();
+        assert!(delays.iter().all(|d| (ms(50)..=ms(150)).contains(d)));
+        assert!(delays.iter().any(|d| *d != delays[0]), "draws vary");
+
+        let mut waiter = ServiceWaiter::new(0x5B, 1, policy, Some(start), 30_000, ());
+        let at = |millis| Some(start + ms(millis));
+        assert!(!waiter.take_find_due(at(79)));
+        assert!(waiter.take_find_due(at(80)), "50 ms + 30 ms drawn");
+        assert!(waiter.take_find_due(at(100)));
+        assert!(!waiter.take_find_due(at(10_000)), "one repetition only");
+        assert!(!waiter.has_timed_out(at(1_000_000)), "no timeout");
+    }
+
+    #[test]
+    fn resolve_prefers_priority_then_splits_by_weight() {
+        use crate::client::reachability::ProbeState;
+        use crate::client::service_registry::ServiceEndpointInfo;
+        use core::net::SocketAddr;
+
+        let key = |port| ServiceEndpointKey::udp(0x5B, SocketAddr::from(([127, 0, 0, 1], port)));
+        let offer = |load_balancing| ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing,
+        };
+        let mut registry = ServiceRegistry::default();
+        registry.insert(key(1), offer(None)).unwrap();
+        assert_eq!(resolve(&registry, 0x5B, 1, 7), Some(key(1)));
+        registry.insert(key(2), offer(Some((1, 3)))).unwrap();
+        registry.insert(key(3), offer(Some((1, 1)))).unwrap();
+        registry.insert(key(4), offer(Some((2, 100)))).unwrap();
+
+        let picks: std::vec::Vec<_> = (0..400u64)
+            .map(|i| {
+                resolve(
+                    &registry,
+                    0x5B,
+                    0xFFFF,
+                    draw(Some(Duration::from_nanos(i)), 0x5B, 1),
+                )
+                .unwrap()
+            })
+            .collect();
+        let count = |port| picks.iter().filter(|k| **k == key(port)).count();
+        assert_eq!(count(1) + count(4), 0, "only the best priority competes");
+        assert!(count(2) > count(3) * 2, "weight 3 beats weight 1");
+        assert!(count(3) > 0);
+        assert_eq!(resolve(&registry, 0x5C, 0xFFFF, 0), None);
+    }
+
+    #[test]
+    fn event_waiter_matches_service_from_provider_until_deadline() {
+        let provider = IpAddr::from([192, 168, 1, 10]);
+        let start = Duration::from_secs(10);
+        let waiter = EventWaiter::new(0x5B, provider, Duration::from_secs(1), Some(start), ());
+        assert!(waiter.matches(0x5B, provider));
+        assert!(!waiter.matches(0x5C, provider), "other service");
+        assert!(
+            !waiter.matches(0x5B, IpAddr::from([192, 168, 1, 11])),
+            "other provider"
+        );
+        assert!(!waiter.has_timed_out(Some(start + Duration::from_millis(999))));
+        assert!(waiter.has_timed_out(Some(start + Duration::from_secs(1))));
+    }
+}
@@ -0,0 +1,152 @@
+use heapless::Vec;
+use tokio::sync::watch;
+
+use super::service_registry::{ServiceEndpointInfo, ServiceEndpointKey, ServiceRegistry};
+use crate::log::debug;
+
+/// Number of `Client::watch_service` watches that can be open at the
+/// same time. Sized via `SIMPLE_SOMEIP_SERVICE_WATCHES_CAP`.
+pub const SERVICE_WATCHES_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_SERVICE_WATCHES_CAP"), 8);
+
+/// A watch of where one service instance is offered. The run loop holds
+/// the sending side, whose value is the endpoint last sent, `None` while
+/// the instance is unavailable.
+#[derive(Debug)]
+pub(super) struct ServiceWatch {
+    service_id: u16,
+    /// `0xFFFF` for any instance.
+    instance_id: u16,
+    sender: watch::Sender<Option<ServiceEndpointKey>>,
+}
+
+impl ServiceWatch {
+    pub(super) const fn new(
+        service_id: u16,
+        instance_id: u16,
+        sender: watch::Sender<Option<ServiceEndpointKey>>,
+    ) -> Self {
+        Self {
+            service_id,
+            instance_id,
+            sender,
+        }
+    }
+
+    /// `true` once every receiver is dropped.
+    pub(super) fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    fn offers(&self, key: &ServiceEndpointKey, info: &ServiceEndpointInfo) -> bool {
+        key.service_id == self.service_id
+            && (self.instance_id == 0xFFFF || info.instance_id == self.instance_id)
+    }
+
+    /// Where the instance is offered in `registry`: the endpoint sent
+    /// last while it is still there, so a second provider does not flip
+    /// the watch, else the first one found.
+    fn locate(&self, registry: &ServiceRegistry) -> Option<ServiceEndpointKey> {
+        if let Some(current) = *self.sender.borrow()
+            && registry
+                .get(current)
+                .is_some_and(|info| self.offers(&current, info))
+        {
+            return Some(current);
+        }
+        registry.find(|key, info| self.offers(key, info))
+    }
+}
+
+/// Open service watches of one client.
+pub(super) type ServiceWatches = Vec<ServiceWatch, SERVICE_WATCHES_CAP>;
+
+/// Send every watch whose instance moved, appeared or went away in
+/// `registry` its new endpoint. Watches whose receivers are gone are
+/// closed on the way.
+pub(super) fn update(watches: &mut ServiceWatches, registry: &ServiceRegistry) {
+    let mut index = 0;
+    while index < watches.len() {
+        let watch = &watches[index];
+        if watch.is_closed() {
+            let closed = watches.swap_remove(index);
+            debug!(
+                "Service watch for 0x{:04X}.0x{:04X} closed",
+                closed.service_id, closed.instance_id
+            );
+            continue;
+        }
+        let located = watch.locate(registry);
+        watch.sender.send_if_modified(|current| {
+            let moved = *current != located;
+            *current = located;
+            moved
+        });
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::reachability::ProbeState;
+    use core::net::SocketAddr;
+
+    fn info(instance_id: u16) -> ServiceEndpointInfo {
+        ServiceEndpointInfo {
+            instance_id,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
+        }
+    }
+
+    #[test]
+    fn watches_report_changes_of_their_instance_only() {
+        let first = ServiceEndpointKey::udp(0x5B, SocketAddr::from(([192, 168, 1, 10], 30509)));
+        let second = ServiceEndpointKey::udp(0x5B, SocketAddr::from(([192, 168, 1, 11], 30509)));
+        let (sender, mut receiver) = watch::channel(None);
+        let mut watches = ServiceWatches::new();
+        watches.push(ServiceWatch::new(0x5B, 1, sender)).unwrap();
+        let mut seen = std::vec::Vec::new();
+        let mut step = |registry: &ServiceRegistry| {
+            update(&mut watches, registry);
+            if receiver.has_changed().unwrap() {
+                seen.push(*receiver.borrow_and_update());
+            }
+        };
+
+        let mut registry = ServiceRegistry::default();
+        step(&registry);
+        registry.insert(first, info(2)).unwrap();
+        step(&registry);
+        registry.insert(second, info(1)).unwrap();
+        step(&registry);
+        registry.insert(first, info(1)).unwrap();
+        step(&registry);
+        registry.remove(second);
+        step(&registry);
+        registry.remove(first);
+        step(&registry);
+        assert_eq!(
+            seen,
+            [Some(second), Some(first), None],
+            "other instances are ignored and a second provider does not flip the watch"
+        );
+    }
+
+    #[test]
+    fn watches_without_receivers_are_closed() {
+        let (sender, receiver) = watch::channel(None);
+        let mut watches = ServiceWatches::new();
+        watches.push(ServiceWatch::new(0x5B, 1, sender)).unwrap();
+        drop(receiver);
+        update(&mut watches, &ServiceRegistry::default());
+        assert!(watches.is_empty());
+    }
+}
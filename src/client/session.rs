@@ -1,5 +1,6 @@
 use crate::protocol::sd::RebootFlag;
-use core::net::SocketAddr;
+use core::net::{IpAddr, SocketAddr};
+use heapless::Vec;
 use heapless::index_map::FnvIndexMap;
 
 /// Max number of distinct `(sender, transport, service, instance)` tuples tracked
@@ -169,10 +170,105 @@ impl SessionTracker {
     }
 }
 
+/// Number of services a client can mark as session-less with
+/// [`Client::set_session_handling`](crate::Client::set_session_handling).
+/// Sized via `SIMPLE_SOMEIP_SESSIONLESS_SERVICES_CAP`.
+pub const SESSIONLESS_SERVICES_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_SESSIONLESS_SERVICES_CAP"), 8);
+
+/// Services whose peers run with session handling disabled:
+/// fire-and-forget messages to them carry session ID `0` and their
+/// notifications bypass the duplicate filter.
+#[derive(Debug, Default)]
+pub(super) struct SessionlessServices {
+    services: Vec<u16, SESSIONLESS_SERVICES_CAP>,
+}
+
+impl SessionlessServices {
+    pub(super) fn contains(&self, service_id: u16) -> bool {
+        self.services.contains(&service_id)
+    }
+
+    /// Turn session handling for `service_id` on or off. Fails only when
+    /// turning it off for one service too many.
+    pub(super) fn set(&mut self, service_id: u16, session_handling: bool) -> Result<(), ()> {
+        let position = self.services.iter().position(|id| *id == service_id);
+        match (session_handling, position) {
+            (true, Some(pos)) => {
+                self.services.swap_remove(pos);
+                Ok(())
+            }
+            (false, None) => self.services.push(service_id).map_err(|_| ()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_REQUEST_SESSIONS_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_REQUEST_SESSIONS_CAP: usize = 32;
+
+/// Number of peers a client keeps a request session counter for. Must be
+/// a power of two ([`FnvIndexMap`] requirement). Sized via
+/// `SIMPLE_SOMEIP_REQUEST_SESSIONS_CAP`; defaults to 4 on bare-metal, 32
+/// otherwise.
+pub const REQUEST_SESSIONS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_REQUEST_SESSIONS_CAP"),
+    _DEFAULT_REQUEST_SESSIONS_CAP,
+);
+
+/// Session ID of the next request to each peer, by IP address, so every
+/// provider sees its own gap-free sequence however the client's traffic
+/// is spread. Counters start at `1` and wrap from `0xFFFF` to `0x0001`,
+/// never `0`.
+///
+/// When full, a new peer takes the slot of the one sent to least
+/// recently, whose sequence restarts at `1` if it is sent to again.
+#[derive(Debug, Default)]
+pub(super) struct RequestSessions {
+    /// Next session ID and the `sends` count at the last send, per peer.
+    next: FnvIndexMap<IpAddr, (u16, u64), REQUEST_SESSIONS_CAP>,
+    /// Sends so far, ordering the peers by recency.
+    sends: u64,
+}
+
+impl RequestSessions {
+    /// The session ID the next request to `peer` carries.
+    pub(super) fn current(&self, peer: IpAddr) -> u16 {
+        self.next
+            .get(&peer)
+            .map_or(1, |(session_id, _)| *session_id)
+    }
+
+    /// Move past the session ID of a request sent to `peer`. Only called
+    /// once the request is on the wire, so failed sends do not use up
+    /// the session space.
+    pub(super) fn advance(&mut self, peer: IpAddr) {
+        let session_id = match self.current(peer) {
+            u16::MAX => 1,
+            session_id => session_id + 1,
+        };
+        self.sends += 1;
+        if !self.next.contains_key(&peer) && self.next.len() == REQUEST_SESSIONS_CAP {
+            let stalest = self
+                .next
+                .iter()
+                .min_by_key(|(_, (_, sent_at))| *sent_at)
+                .map(|(addr, _)| *addr);
+            if let Some(stalest) = stalest {
+                self.next.swap_remove(&stalest);
+            }
+        }
+        // Cannot fail: either `peer` is present or a slot was just freed.
+        let _ = self.next.insert(peer, (session_id, self.sends));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::net::{Ipv4Addr, SocketAddr};
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
     fn addr(port: u16) -> SocketAddr {
         SocketAddr::new(Ipv4Addr::new(192, 168, 1, 10).into(), port)
@@ -476,4 +572,51 @@ mod tests {
             SessionVerdict::Reboot
         );
     }
+
+    fn ip(last: u8) -> IpAddr {
+        Ipv4Addr::new(192, 168, 1, last).into()
+    }
+
+    #[test]
+    fn request_sessions_count_per_peer_and_skip_zero() {
+        let mut sessions = RequestSessions::default();
+        assert_eq!(sessions.current(ip(1)), 1);
+        sessions.advance(ip(1));
+        sessions.advance(ip(1));
+        assert_eq!(sessions.current(ip(1)), 3);
+        assert_eq!(sessions.current(ip(2)), 1, "other peers keep their own");
+
+        for _ in 3..=u16::MAX {
+            sessions.advance(ip(1));
+        }
+        assert_eq!(sessions.current(ip(1)), 1, "0xFFFF wraps to 0x0001");
+    }
+
+    #[test]
+    fn request_sessions_forget_the_stalest_peer_when_full() {
+        let mut sessions = RequestSessions::default();
+        for last in 0..REQUEST_SESSIONS_CAP {
+            let peer = ip(u8::try_from(last).unwrap());
+            sessions.advance(peer);
+            sessions.advance(peer);
+        }
+        sessions.advance(ip(0));
+        sessions.advance(ip(255));
+        assert_eq!(sessions.current(ip(255)), 2);
+        assert_eq!(sessions.current(ip(0)), 4, "sent to most recently");
+        assert_eq!(sessions.current(ip(1)), 1, "stalest forgotten");
+    }
+
+    #[test]
+    fn request_sessions_keep_ipv6_peers_apart_from_ipv4_ones() {
+        let mut sessions = RequestSessions::default();
+        let v6 = IpAddr::V6(Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1));
+        let mapped = IpAddr::V6(Ipv4Addr::new(192, 168, 1, 1).to_ipv6_mapped());
+        sessions.advance(v6);
+        sessions.advance(v6);
+        sessions.advance(ip(1));
+        assert_eq!(sessions.current(v6), 3);
+        assert_eq!(sessions.current(ip(1)), 2);
+        assert_eq!(sessions.current(mapped), 1, "a distinct peer address");
+    }
 }
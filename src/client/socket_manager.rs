@@ -1,4 +1,4 @@
-//! Client-side UDP socket management.
+//! Client-side socket management: UDP sockets and TCP connections.
 //!
 //! Each bound socket is backed by a transport socket (concrete
 //! `TokioSocket` on `std + tokio`, pluggable via [`TransportFactory`] on
@@ -44,21 +44,47 @@ use crate::{
     UDP_BUFFER_SIZE,
     buffer_pool::BufferLease,
     e2e::{E2ECheckStatus, E2EKey},
-    protocol::{Message, MessageView, sd},
+    protocol::{DecodeLimits, Message, MessageView},
     traits::{PayloadWireFormat, WireFormat},
     transport::{
         ChannelFactory, E2ERegistryHandle, LocalSpawner, MpscRecv, MpscSend, OneshotRecv,
-        OneshotSend, ReceivedDatagram, SocketOptions, Spawner, TransportFactory, TransportSocket,
+        OneshotSend, ReceivedDatagram, SocketOptions, Spawner, StreamFactory, TransportFactory,
+        TransportSocket,
     },
 };
 
+use super::config::{OverflowPolicy, SocketSettings};
 use super::error::Error;
 use crate::log::{debug, error, info, trace, warn};
+#[cfg(feature = "std")]
+use crate::protocol::tp::{Reassembler, TP_REASSEMBLY_TIMEOUT};
+#[cfg(feature = "_alloc")]
+use crate::{
+    e2e::PROFILE4_HEADER_SIZE,
+    protocol::tp::{self, Segmenter, TP_MAX_MESSAGE_LEN},
+};
 use core::{
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     task::{Context, Poll},
 };
-use futures_util::{FutureExt, pin_mut, select_biased};
+use futures_util::{
+    FutureExt,
+    future::{Fuse, FusedFuture},
+    pin_mut, select_biased,
+};
+
+/// Largest message [`SocketManager::send`] accepts. With an allocator,
+/// messages too large for one datagram go out as SOME/IP-TP segments, up
+/// to the size a default [`Reassembler`](crate::protocol::tp::Reassembler)
+/// puts back together.
+#[cfg(feature = "_alloc")]
+const SEND_LIMIT: usize = if TP_MAX_MESSAGE_LEN > UDP_BUFFER_SIZE {
+    TP_MAX_MESSAGE_LEN
+} else {
+    UDP_BUFFER_SIZE
+};
+#[cfg(not(feature = "_alloc"))]
+const SEND_LIMIT: usize = UDP_BUFFER_SIZE;
 
 /// A received message together with the source address it came from.
 ///
@@ -71,7 +97,16 @@ use futures_util::{FutureExt, pin_mut, select_biased};
 pub struct ReceivedMessage<P> {
     pub message: Message<P>,
     pub source: SocketAddr,
+    /// Local port of the socket that received the message.
+    pub local_port: u16,
     pub e2e_status: Option<E2ECheckStatus>,
+    /// The receiving socket's
+    /// [`dropped_datagrams`](crate::transport::TransportSocket::dropped_datagrams),
+    /// on the messages the socket loop sampled it for.
+    pub dropped_datagrams: Option<u64>,
+    /// Messages the socket loop dropped since the previous one it
+    /// delivered, because the channel to the run loop was full.
+    pub overflowed: u64,
 }
 
 /// Structure representing a request to send a message
@@ -99,6 +134,9 @@ impl<P: PayloadWireFormat + Send + 'static, C: ChannelFactory> core::fmt::Debug
 enum Outcome<P: PayloadWireFormat + Send + 'static, C: ChannelFactory> {
     Send(Option<SendMessage<P, C>>),
     Recv(Result<ReceivedDatagram, crate::transport::TransportError>),
+    /// A message waiting for room under [`OverflowPolicy::KeepLatest`]
+    /// was delivered, or the run loop is gone.
+    Delivered(Result<(), ()>),
 }
 
 impl<PayloadDefinitions, C> SendMessage<PayloadDefinitions, C>
@@ -132,6 +170,10 @@ pub struct SocketManager<PayloadDefinitions: Send + 'static, C: ChannelFactory>
     /// Per AUTOSAR SOME/IP-SD, the reboot flag must be cleared after the
     /// first counter wrap and stay cleared.
     session_has_wrapped: bool,
+    /// Latest drop count sampled by the socket loop.
+    dropped_datagrams: Option<u64>,
+    /// Messages the socket loop dropped on a full channel, in all.
+    overflowed: u64,
 }
 
 impl<P: PayloadWireFormat + Send + 'static, C: ChannelFactory> core::fmt::Debug
@@ -197,6 +239,7 @@ where
             session_has_wrapped,
             multicast_loopback,
             buf,
+            SocketSettings::default(),
         )
         .await
     }
@@ -244,6 +287,7 @@ where
         session_has_wrapped: bool,
         multicast_loopback: bool,
         buf: BufferLease,
+        settings: SocketSettings,
     ) -> Result<Self, Error>
     where
         F: TransportFactory,
@@ -253,8 +297,13 @@ where
         S: Spawner,
         R: E2ERegistryHandle,
     {
-        let (rx_tx, rx_rx) = C::bounded::<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>();
-        let (tx_tx, tx_rx) = C::bounded::<SendMessage<MessageDefinitions, C>, 16>();
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
 
         // Control whether multicast packets sent by this socket are looped
         // back to sockets on the same host — INCLUDING this socket itself.
@@ -266,25 +315,38 @@ where
         // as inbound discovery traffic.
         let options = {
             let mut o = SocketOptions::new();
+            o.recv_buffer_size = settings.recv_buffer_size;
             o.reuse_address = true;
             o.reuse_port = true;
             o.multicast_if_v4 = Some(interface);
             o.multicast_loop_v4 = Some(multicast_loopback);
             o
         };
-        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, sd::MULTICAST_PORT);
+        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, settings.sd_port);
 
         let socket = factory.bind(bind_addr, &options).await?;
-        socket.join_multicast_v4(sd::MULTICAST_IP, interface)?;
+        socket.join_multicast_v4(settings.sd_multicast_group, interface)?;
 
-        let fut = Self::socket_loop_future(socket, rx_tx, tx_rx, e2e_registry, buf);
+        let fut = Self::socket_loop_future(
+            socket,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            true,
+            settings.sd_port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
         spawner.spawn(fut);
         Ok(Self {
             receiver: rx_rx,
             sender: tx_tx,
-            local_port: sd::MULTICAST_PORT,
+            local_port: settings.sd_port,
             session_id: session_id.max(1),
             session_has_wrapped,
+            dropped_datagrams: None,
+            overflowed: 0,
         })
     }
 
@@ -302,6 +364,7 @@ where
         session_has_wrapped: bool,
         multicast_loopback: bool,
         buf: BufferLease,
+        settings: SocketSettings,
     ) -> Result<Self, Error>
     where
         F: TransportFactory,
@@ -309,27 +372,45 @@ where
         S: LocalSpawner,
         R: E2ERegistryHandle,
     {
-        let (rx_tx, rx_rx) = C::bounded::<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>();
-        let (tx_tx, tx_rx) = C::bounded::<SendMessage<MessageDefinitions, C>, 16>();
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
         let options = {
             let mut o = SocketOptions::new();
+            o.recv_buffer_size = settings.recv_buffer_size;
             o.reuse_address = true;
             o.reuse_port = true;
             o.multicast_if_v4 = Some(interface);
             o.multicast_loop_v4 = Some(multicast_loopback);
             o
         };
-        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, sd::MULTICAST_PORT);
+        let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, settings.sd_port);
         let socket = factory.bind(bind_addr, &options).await?;
-        socket.join_multicast_v4(sd::MULTICAST_IP, interface)?;
-        let fut = Self::socket_loop_future(socket, rx_tx, tx_rx, e2e_registry, buf);
+        socket.join_multicast_v4(settings.sd_multicast_group, interface)?;
+        let fut = Self::socket_loop_future(
+            socket,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            true,
+            settings.sd_port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
         spawner.spawn_local(fut);
         Ok(Self {
             receiver: rx_rx,
             sender: tx_tx,
-            local_port: sd::MULTICAST_PORT,
+            local_port: settings.sd_port,
             session_id: session_id.max(1),
             session_has_wrapped,
+            dropped_datagrams: None,
+            overflowed: 0,
         })
     }
 
@@ -354,6 +435,7 @@ where
         interface: Ipv4Addr,
         e2e_registry: R,
         buf: BufferLease,
+        settings: SocketSettings,
     ) -> Result<Self, Error>
     where
         F: TransportFactory,
@@ -363,28 +445,46 @@ where
         S: Spawner,
         R: E2ERegistryHandle,
     {
-        let (rx_tx, rx_rx) = C::bounded::<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>();
-        let (tx_tx, tx_rx) = C::bounded::<SendMessage<MessageDefinitions, C>, 16>();
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
         // Receive-only: reuse addr/port so it can share the SD port with the
         // multicast discovery socket, but no `multicast_if`/`loop`/group join.
         let options = {
             let mut o = SocketOptions::new();
+            o.recv_buffer_size = settings.recv_buffer_size;
             o.reuse_address = true;
             o.reuse_port = true;
             o
         };
         // Specific-IP bind (vs the multicast socket's `INADDR_ANY`) is what
         // makes the kernel divert unicast SD here.
-        let bind_addr = SocketAddrV4::new(interface, sd::MULTICAST_PORT);
+        let bind_addr = SocketAddrV4::new(interface, settings.sd_port);
         let socket = factory.bind(bind_addr, &options).await?;
-        let fut = Self::socket_loop_future(socket, rx_tx, tx_rx, e2e_registry, buf);
+        let fut = Self::socket_loop_future(
+            socket,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            true,
+            settings.sd_port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
         spawner.spawn(fut);
         Ok(Self {
             receiver: rx_rx,
             sender: tx_tx,
-            local_port: sd::MULTICAST_PORT,
+            local_port: settings.sd_port,
             session_id: 1,
             session_has_wrapped: false,
+            dropped_datagrams: None,
+            overflowed: 0,
         })
     }
 
@@ -396,6 +496,7 @@ where
         interface: Ipv4Addr,
         e2e_registry: R,
         buf: BufferLease,
+        settings: SocketSettings,
     ) -> Result<Self, Error>
     where
         F: TransportFactory,
@@ -403,24 +504,42 @@ where
         S: LocalSpawner,
         R: E2ERegistryHandle,
     {
-        let (rx_tx, rx_rx) = C::bounded::<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>();
-        let (tx_tx, tx_rx) = C::bounded::<SendMessage<MessageDefinitions, C>, 16>();
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
         let options = {
             let mut o = SocketOptions::new();
+            o.recv_buffer_size = settings.recv_buffer_size;
             o.reuse_address = true;
             o.reuse_port = true;
             o
         };
-        let bind_addr = SocketAddrV4::new(interface, sd::MULTICAST_PORT);
+        let bind_addr = SocketAddrV4::new(interface, settings.sd_port);
         let socket = factory.bind(bind_addr, &options).await?;
-        let fut = Self::socket_loop_future(socket, rx_tx, tx_rx, e2e_registry, buf);
+        let fut = Self::socket_loop_future(
+            socket,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            true,
+            settings.sd_port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
         spawner.spawn_local(fut);
         Ok(Self {
             receiver: rx_rx,
             sender: tx_tx,
-            local_port: sd::MULTICAST_PORT,
+            local_port: settings.sd_port,
             session_id: 1,
             session_has_wrapped: false,
+            dropped_datagrams: None,
+            overflowed: 0,
         })
     }
 
@@ -443,7 +562,15 @@ where
         let buf = TokioBufferProvider::new()
             .claim()
             .ok_or(Error::Capacity("udp_buffer"))?;
-        Self::bind_with_transport(&TokioTransport, &TokioSpawner, port, e2e_registry, buf).await
+        Self::bind_with_transport(
+            &TokioTransport,
+            &TokioSpawner,
+            port,
+            e2e_registry,
+            buf,
+            SocketSettings::default(),
+        )
+        .await
     }
 
     /// Variant of [`Self::bind`] that constructs the underlying socket
@@ -463,6 +590,7 @@ where
         port: u16,
         e2e_registry: R,
         buf: BufferLease,
+        settings: SocketSettings,
     ) -> Result<Self, Error>
     where
         F: TransportFactory,
@@ -480,11 +608,17 @@ where
         // already used. Bumping the unicast capacity from 4 to 16 has
         // no semantic effect — it just lets the channels absorb a
         // brief burst before backpressure kicks in.
-        let (rx_tx, rx_rx) = C::bounded::<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>();
-        let (tx_tx, tx_rx) = C::bounded::<SendMessage<MessageDefinitions, C>, 16>();
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
 
         let options = {
             let mut o = SocketOptions::new();
+            o.recv_buffer_size = settings.recv_buffer_size;
             o.reuse_address = true;
             o
         };
@@ -492,7 +626,17 @@ where
 
         let socket = factory.bind(bind_addr, &options).await?;
         let port = socket.local_addr()?.port();
-        let fut = Self::socket_loop_future(socket, rx_tx, tx_rx, e2e_registry, buf);
+        let fut = Self::socket_loop_future(
+            socket,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            true,
+            port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
         spawner.spawn(fut);
         Ok(Self {
             receiver: rx_rx,
@@ -500,6 +644,8 @@ where
             local_port: port,
             session_id: 1,
             session_has_wrapped: false,
+            dropped_datagrams: None,
+            overflowed: 0,
         })
     }
 
@@ -517,6 +663,7 @@ where
         port: u16,
         e2e_registry: R,
         buf: BufferLease,
+        settings: SocketSettings,
     ) -> Result<Self, Error>
     where
         F: TransportFactory,
@@ -524,17 +671,134 @@ where
         S: LocalSpawner,
         R: E2ERegistryHandle,
     {
-        let (rx_tx, rx_rx) = C::bounded::<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>();
-        let (tx_tx, tx_rx) = C::bounded::<SendMessage<MessageDefinitions, C>, 16>();
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
         let options = {
             let mut o = SocketOptions::new();
+            o.recv_buffer_size = settings.recv_buffer_size;
             o.reuse_address = true;
             o
         };
         let bind_addr = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, port);
         let socket = factory.bind(bind_addr, &options).await?;
         let port = socket.local_addr()?.port();
-        let fut = Self::socket_loop_future(socket, rx_tx, tx_rx, e2e_registry, buf);
+        let fut = Self::socket_loop_future(
+            socket,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            true,
+            port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
+        spawner.spawn_local(fut);
+        Ok(Self {
+            receiver: rx_rx,
+            sender: tx_tx,
+            local_port: port,
+            session_id: 1,
+            session_has_wrapped: false,
+            dropped_datagrams: None,
+            overflowed: 0,
+        })
+    }
+
+    /// Connect a stream socket to `peer` through a [`StreamFactory`] and
+    /// submit its I/O loop, the same loop as a bound UDP socket. Messages
+    /// sent through the returned manager go to `peer` whatever their
+    /// target; the loop ends when the peer closes the connection.
+    ///
+    /// Bounds as for [`Self::bind_with_transport`].
+    pub async fn connect_with_transport<F, S, R>(
+        factory: &F,
+        spawner: &S,
+        peer: SocketAddrV4,
+        e2e_registry: R,
+        buf: BufferLease,
+        settings: SocketSettings,
+    ) -> Result<Self, Error>
+    where
+        F: StreamFactory,
+        F::Stream: Send + Sync + 'static,
+        for<'a> <F::Stream as TransportSocket>::SendFuture<'a>: Send,
+        for<'a> <F::Stream as TransportSocket>::RecvFuture<'a>: Send,
+        S: Spawner,
+        R: E2ERegistryHandle,
+    {
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
+        let stream = factory.connect(peer).await?;
+        let port = stream.local_addr()?.port();
+        let fut = Self::socket_loop_future(
+            stream,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            false,
+            port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
+        spawner.spawn(fut);
+        Ok(Self {
+            receiver: rx_rx,
+            sender: tx_tx,
+            local_port: port,
+            session_id: 1,
+            session_has_wrapped: false,
+            dropped_datagrams: None,
+            overflowed: 0,
+        })
+    }
+
+    /// `!Send` counterpart to [`Self::connect_with_transport`].
+    pub async fn connect_with_transport_local<F, S, R>(
+        factory: &F,
+        spawner: &S,
+        peer: SocketAddrV4,
+        e2e_registry: R,
+        buf: BufferLease,
+        settings: SocketSettings,
+    ) -> Result<Self, Error>
+    where
+        F: StreamFactory,
+        F::Stream: 'static,
+        S: LocalSpawner,
+        R: E2ERegistryHandle,
+    {
+        let (rx_tx, rx_rx) = C::bounded_with_capacity::<
+            Result<ReceivedMessage<MessageDefinitions>, Error>,
+            16,
+        >(settings.receive_capacity);
+        let (tx_tx, tx_rx) = C::bounded_with_capacity::<SendMessage<MessageDefinitions, C>, 16>(
+            settings.send_capacity,
+        );
+        let stream = factory.connect(peer).await?;
+        let port = stream.local_addr()?.port();
+        let fut = Self::socket_loop_future(
+            stream,
+            rx_tx,
+            tx_rx,
+            e2e_registry,
+            buf,
+            false,
+            port,
+            settings.receive_overflow,
+            settings.decode_limits,
+        );
         spawner.spawn_local(fut);
         Ok(Self {
             receiver: rx_rx,
@@ -542,6 +806,8 @@ where
             local_port: port,
             session_id: 1,
             session_has_wrapped: false,
+            dropped_datagrams: None,
+            overflowed: 0,
         })
     }
 
@@ -558,13 +824,13 @@ where
         // protocol-level I/O error from inside the socket loop.
         let required = message.required_size();
         // Coarse fail-fast: `send()` has no leased buffer in scope, so
-        // UDP_BUFFER_SIZE is the only bound available here.  The socket
+        // SEND_LIMIT is the only bound available here.  The socket
         // loop's `buf.len()` check is the authoritative guard; E2E
         // protection can still expand a frame that passes this pre-filter
         // beyond the leased buffer, and that case is caught there.
-        if required > UDP_BUFFER_SIZE {
+        if required > SEND_LIMIT {
             warn!(
-                "outgoing message size {required} exceeds UDP_BUFFER_SIZE ({UDP_BUFFER_SIZE}); rejecting with Capacity(\"udp_buffer\")"
+                "outgoing message size {required} exceeds the send limit ({SEND_LIMIT}); rejecting with Capacity(\"udp_buffer\")"
             );
             return Err(Error::Capacity("udp_buffer"));
         }
@@ -601,7 +867,15 @@ where
     }
 
     pub async fn receive(&mut self) -> Option<Result<ReceivedMessage<MessageDefinitions>, Error>> {
-        MpscRecv::recv(&mut self.receiver).await
+        let received = MpscRecv::recv(&mut self.receiver).await;
+        self.note_drops(received.as_ref());
+        received
+    }
+
+    /// Messages its loop dropped because the channel to the run loop was
+    /// full; see [`OverflowPolicy`].
+    pub fn overflowed(&self) -> u64 {
+        self.overflowed
     }
 
     /// Poll the receiver for a message without blocking.
@@ -610,7 +884,55 @@ where
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<ReceivedMessage<MessageDefinitions>, Error>>> {
-        self.receiver.poll_recv(cx)
+        let received = self.receiver.poll_recv(cx);
+        if let Poll::Ready(received) = &received {
+            self.note_drops(received.as_ref());
+        }
+        received
+    }
+
+    fn note_drops(
+        &mut self,
+        received: Option<&Result<ReceivedMessage<MessageDefinitions>, Error>>,
+    ) {
+        if let Some(Ok(received)) = received {
+            if let Some(dropped) = received.dropped_datagrams {
+                self.dropped_datagrams = Some(dropped);
+            }
+            self.overflowed += received.overflowed;
+        }
+    }
+
+    /// Move the socket loop's drop counts onto `message` for the run
+    /// loop, returning them in case it is not delivered after all.
+    fn carry_drops(
+        message: &mut Result<ReceivedMessage<MessageDefinitions>, Error>,
+        dropped_datagrams: &mut Option<u64>,
+        overflowed: &mut u64,
+    ) -> (Option<u64>, u64) {
+        let Ok(received) = message else {
+            return (None, 0);
+        };
+        received.dropped_datagrams = dropped_datagrams.take();
+        received.overflowed = core::mem::take(overflowed);
+        (received.dropped_datagrams, received.overflowed)
+    }
+
+    /// Put back counts [`Self::carry_drops`] moved onto a message that
+    /// was dropped; a newer drop sample wins over the carried one.
+    fn restore_drops(
+        carried: (Option<u64>, u64),
+        dropped_datagrams: &mut Option<u64>,
+        overflowed: &mut u64,
+    ) {
+        *dropped_datagrams = dropped_datagrams.or(carried.0);
+        *overflowed += carried.1;
+    }
+
+    /// Datagrams the OS dropped for this socket, as last sampled by its
+    /// loop; `None` before the first sample or if the socket cannot tell.
+    pub fn dropped_datagrams(&self) -> Option<u64> {
+        self.dropped_datagrams
     }
 
     pub fn session_id(&self) -> u16 {
@@ -638,6 +960,109 @@ where
         while MpscRecv::recv(&mut receiver).await.is_some() {}
     }
 
+    /// Encode `message` whole into `encoded`, E2E-protecting its payload
+    /// into `protected` if a profile is registered, and send it to
+    /// `target` as SOME/IP-TP segments built in `buf`, each at most
+    /// `buf.len()` bytes. `encoded` and `protected` are the socket loop's
+    /// scratch, kept across sends so their capacity is reused.
+    #[cfg(feature = "_alloc")]
+    async fn send_segmented<T, R>(
+        socket: &T,
+        e2e_registry: &R,
+        message: &Message<MessageDefinitions>,
+        target: SocketAddrV4,
+        buf: &mut [u8],
+        encoded: &mut alloc::vec::Vec<u8>,
+        protected: &mut alloc::vec::Vec<u8>,
+    ) -> Result<(), Error>
+    where
+        T: TransportSocket,
+        R: E2ERegistryHandle,
+    {
+        encoded.clear();
+        encoded.resize(message.required_size(), 0);
+        let length = message.encode(&mut &mut encoded[..])?;
+        encoded.truncate(length);
+        let header = message.header();
+        let key = E2EKey::from_message_id(header.message_id());
+        protected.clear();
+        if e2e_registry.contains_key(&key) {
+            let upper_header: [u8; 8] = encoded[8..16].try_into().expect("upper header slice");
+            protected.resize(length - 16 + PROFILE4_HEADER_SIZE, 0);
+            match e2e_registry.protect(key, &encoded[16..], upper_header, protected) {
+                Some(Ok(protected_len)) => protected.truncate(protected_len),
+                Some(Err(e)) => {
+                    error!(
+                        "E2E protect failed for configured key {:?}: {:?}; \
+                         refusing to send unprotected message",
+                        key, e
+                    );
+                    return Err(Error::E2e(e));
+                }
+                None => unreachable!("contains_key was true"),
+            }
+        }
+        let payload = if protected.is_empty() {
+            &encoded[16..]
+        } else {
+            &protected[..]
+        };
+        let mut segmenter = match Segmenter::new(header, payload, buf.len()) {
+            Ok(segmenter) => segmenter,
+            Err(tp::Error::SegmentTooSmall(_)) => {
+                warn!(
+                    "claimed buffer ({}) too small for a SOME/IP-TP segment; rejecting with Capacity(\"udp_buffer\")",
+                    buf.len()
+                );
+                return Err(Error::Capacity("udp_buffer"));
+            }
+            Err(e) => return Err(crate::protocol::Error::from(e).into()),
+        };
+        debug!(
+            "Sending {}-byte message to {} as {} SOME/IP-TP segments",
+            length,
+            target,
+            segmenter.segment_count()
+        );
+        while let Some(len) = segmenter
+            .write_next(buf)
+            .map_err(crate::protocol::Error::from)?
+        {
+            socket.send_to(&buf[..len], target).await?;
+        }
+        Ok(())
+    }
+
+    /// Pass `datagram` through [`Reassembler`] if it is a SOME/IP-TP
+    /// segment. Returns the bytes to decode: `datagram` itself, or the
+    /// whole message once its last segment is in; `None` while segments
+    /// are missing or when the segment was dropped.
+    #[cfg(feature = "std")]
+    fn reassemble<'a>(
+        reassembler: &'a mut Option<alloc::boxed::Box<Reassembler>>,
+        epoch: std::time::Instant,
+        datagram: &'a [u8],
+        source: SocketAddrV4,
+        decode_limits: &DecodeLimits,
+    ) -> Option<&'a [u8]> {
+        // A datagram that does not parse is reported by the caller.
+        let Ok(view) = MessageView::parse_with_limits(datagram, decode_limits) else {
+            return Some(datagram);
+        };
+        if !view.header().message_type().is_tp() {
+            return Some(datagram);
+        }
+        let reassembler = reassembler
+            .get_or_insert_with(|| alloc::boxed::Box::new(Reassembler::new(TP_REASSEMBLY_TIMEOUT)));
+        match reassembler.feed(source.into(), &view, epoch.elapsed()) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!("Dropped SOME/IP-TP segment from {}: {}", source, e);
+                None
+            }
+        }
+    }
+
     /// Build the I/O loop over any [`TransportSocket`] as a future.
     /// Callers are expected to spawn this future alongside [`Self`];
     /// the socket loop runs concurrently with its owner so
@@ -659,13 +1084,32 @@ where
     /// Bare-metal `TransportSocket` impls must ensure their `SendFuture`
     /// and `RecvFuture` associated types are `Send` (e.g. by avoiding
     /// `Rc` / `RefCell` in the future state) for this to compile.
-    #[allow(clippy::too_many_lines)]
+    ///
+    /// # SOME/IP-TP
+    ///
+    /// On a `udp` socket, a message too large for `buf` is sent as
+    /// SOME/IP-TP segments when an allocator is available to encode it
+    /// whole, and received segments are put together before they are
+    /// decoded on `std` builds, which have the clock reassembly times out
+    /// on. TCP connections carry every message whole.
+    ///
+    /// # Overflow
+    ///
+    /// `receive_overflow` decides what happens to a received message
+    /// when `rx_tx` is full. Messages dropped are counted, and the count
+    /// rides on the next message delivered, as the drop samples do.
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+    #[cfg_attr(not(feature = "_alloc"), allow(unused_variables))]
     async fn socket_loop_future<T, R>(
         socket: T,
         rx_tx: C::BoundedSender<Result<ReceivedMessage<MessageDefinitions>, Error>, 16>,
         mut tx_rx: C::BoundedReceiver<SendMessage<MessageDefinitions, C>, 16>,
         e2e_registry: R,
         mut buf: BufferLease,
+        udp: bool,
+        local_port: u16,
+        receive_overflow: OverflowPolicy,
+        decode_limits: DecodeLimits,
     ) where
         T: TransportSocket + 'static,
         R: E2ERegistryHandle,
@@ -678,7 +1122,14 @@ where
         // platform-level network-stack collapse) used to pin a CPU on a
         // tight `error!` log loop with no exit; this counter caps that.
         const MAX_CONSECUTIVE_RECV_ERRORS: u32 = 16;
+        // The kernel drop count is read on the first datagram and every
+        // `DROP_SAMPLE_INTERVAL`th after it, then handed up with the next
+        // message. Drops happen while the receive queue is full, so the
+        // datagrams still queued after the last of them take a sample.
+        const DROP_SAMPLE_INTERVAL: u32 = 64;
         let mut consecutive_recv_errors: u32 = 0;
+        let mut datagrams_until_drop_sample: u32 = 0;
+        let mut dropped_datagrams: Option<u64> = None;
         // The receive/scratch buffer is now leased from a caller-provided
         // `BufferProvider` (see `#125`): on bare-metal it is a slot of a
         // consumer-declared `static BufferPool`; on tokio it is heap-backed.
@@ -692,6 +1143,28 @@ where
         // Flipping the priority each iteration approximates the
         // fairness `select!` would give without pulling std.
         let mut prefer_recv_first = false;
+        // Messages dropped under `receive_overflow` since the last one
+        // handed up, which carries the count.
+        let mut overflowed: u64 = 0;
+        // Under `OverflowPolicy::KeepLatest`, the newest message waiting
+        // for room in `rx_tx`. Kept across iterations, so a send on the
+        // socket does not cancel it; only a newer message replaces it.
+        let deliver = Fuse::terminated();
+        pin_mut!(deliver);
+        // Drop counts the waiting message carries, put back if it is
+        // replaced.
+        let mut deliver_carries: (Option<u64>, u64) = (None, 0);
+        // Allocated on the first segment received; most sockets never
+        // see one.
+        #[cfg(feature = "std")]
+        let mut reassembler: Option<alloc::boxed::Box<Reassembler>> = None;
+        #[cfg(feature = "std")]
+        let epoch = std::time::Instant::now();
+        // Encoding and E2E scratch of `send_segmented`, grown by the first
+        // message too large for `buf` and reused by the ones after it.
+        #[cfg(feature = "_alloc")]
+        let (mut segmented, mut segmented_protected) =
+            (alloc::vec::Vec::new(), alloc::vec::Vec::new());
 
         loop {
             // The fresh `.fuse()`'d per-iteration futures are pinned
@@ -704,13 +1177,17 @@ where
                 let send_fut = MpscRecv::recv(&mut tx_rx).fuse();
                 let recv_fut = socket.recv_from(&mut buf[..]).fuse();
                 pin_mut!(send_fut, recv_fut);
+                // A waiting message goes first, ahead of the newer ones
+                // the socket holds.
                 if prefer_recv_first {
                     select_biased! {
+                        delivered = deliver.as_mut() => Outcome::Delivered(delivered),
                         result = recv_fut => Outcome::Recv(result),
                         message = send_fut => Outcome::Send(message),
                     }
                 } else {
                     select_biased! {
+                        delivered = deliver.as_mut() => Outcome::Delivered(delivered),
                         message = send_fut => Outcome::Send(message),
                         result = recv_fut => Outcome::Recv(result),
                     }
@@ -727,6 +1204,24 @@ where
                     // smaller than `UDP_BUFFER_SIZE`, and the message must fit
                     // the buffer we actually encode into.
                     let required = send_message.message.required_size();
+                    #[cfg(feature = "_alloc")]
+                    if required > buf.len() && udp {
+                        let result = Self::send_segmented(
+                            &socket,
+                            &e2e_registry,
+                            &send_message.message,
+                            send_message.target_addr,
+                            &mut buf,
+                            &mut segmented,
+                            &mut segmented_protected,
+                        )
+                        .await;
+                        if send_message.response.send(result).is_err() {
+                            info!("Socket owner closed channel, closing socket.");
+                            break;
+                        }
+                        continue;
+                    }
                     if required > buf.len() {
                         warn!(
                             "outgoing message size {required} exceeds claimed buffer ({}); rejecting with Capacity(\"udp_buffer\")",
@@ -842,6 +1337,11 @@ where
                     truncated,
                 })) => {
                     consecutive_recv_errors = 0;
+                    if datagrams_until_drop_sample == 0 {
+                        datagrams_until_drop_sample = DROP_SAMPLE_INTERVAL;
+                        dropped_datagrams = socket.dropped_datagrams().or(dropped_datagrams);
+                    }
+                    datagrams_until_drop_sample -= 1;
                     if bytes_received > buf.len() {
                         // A backend reported a received length larger than
                         // the buffer it was given. Parsing
@@ -880,7 +1380,25 @@ where
                         continue;
                     }
                     let source_address = SocketAddr::V4(source);
-                    let parse_result = MessageView::parse(&buf[..bytes_received])
+                    let datagram = &buf[..bytes_received];
+                    #[cfg(feature = "std")]
+                    let datagram = if udp {
+                        let Some(message) = Self::reassemble(
+                            &mut reassembler,
+                            epoch,
+                            datagram,
+                            source,
+                            &decode_limits,
+                        ) else {
+                            continue;
+                        };
+                        message
+                    } else {
+                        datagram
+                    };
+                    // Bounded before `from_payload_bytes` copies the
+                    // payload (and every SD entry/option) into owned form.
+                    let mut parse_result = MessageView::parse_with_limits(datagram, &decode_limits)
                         .and_then(|view| {
                             let header = view.header().to_owned();
                             let upper_header = header.upper_header_bytes();
@@ -907,16 +1425,68 @@ where
                             Ok(ReceivedMessage {
                                 message: Message::new(header, payload),
                                 source: source_address,
+                                local_port,
                                 e2e_status,
+                                dropped_datagrams: None,
+                                overflowed: 0,
                             })
                         })
                         .map_err(Error::from);
-                    if rx_tx.send(parse_result).await.is_ok() {
-                    } else {
-                        info!("Socket Dropping");
-                        // The receiver has been dropped, so we should exit
-                        break;
+                    if receive_overflow == OverflowPolicy::KeepLatest && !deliver.is_terminated() {
+                        // Superseded by the message just received.
+                        deliver.set(Fuse::terminated());
+                        Self::restore_drops(
+                            deliver_carries,
+                            &mut dropped_datagrams,
+                            &mut overflowed,
+                        );
+                        overflowed += 1;
                     }
+                    let carried = Self::carry_drops(
+                        &mut parse_result,
+                        &mut dropped_datagrams,
+                        &mut overflowed,
+                    );
+                    match receive_overflow {
+                        // Through `deliver`, which the future is laid out
+                        // in once for all three policies.
+                        OverflowPolicy::Block => {
+                            deliver.set(rx_tx.send(parse_result).fuse());
+                            if deliver.as_mut().await.is_err() {
+                                info!("Socket Dropping");
+                                // The receiver has been dropped, so we should exit
+                                break;
+                            }
+                        }
+                        // A dropped receiver also fails `try_send`; the
+                        // loop then ends with the send channel, which the
+                        // same `SocketManager` holds.
+                        OverflowPolicy::DropNewest => {
+                            if rx_tx.try_send(parse_result).is_err() {
+                                Self::restore_drops(
+                                    carried,
+                                    &mut dropped_datagrams,
+                                    &mut overflowed,
+                                );
+                                overflowed += 1;
+                            }
+                        }
+                        OverflowPolicy::KeepLatest => {
+                            if let Err(waiting) = rx_tx.try_send(parse_result) {
+                                deliver_carries = carried;
+                                deliver.set(rx_tx.send(waiting).fuse());
+                            }
+                        }
+                    }
+                }
+                Outcome::Delivered(Ok(())) => deliver_carries = (None, 0),
+                Outcome::Delivered(Err(())) => {
+                    info!("Socket Dropping");
+                    break;
+                }
+                Outcome::Recv(Err(crate::transport::TransportError::ConnectionClosed)) => {
+                    info!("Connection closed, closing socket.");
+                    break;
                 }
                 Outcome::Recv(Err(recv_err)) => {
                     // Classify by transport kind: transient kinds
@@ -1181,6 +1751,111 @@ mod tests {
         assert!(received.message.is_sd());
     }
 
+    /// Bind a socket whose receive channel holds 2 messages, send it
+    /// `count` SD messages numbered from 1 and give its loop time to read
+    /// them all.
+    async fn flood(policy: OverflowPolicy, count: u32) -> (TestSocketManager, UdpSocket) {
+        let sm = TestSocketManager::bind_with_transport(
+            &crate::tokio_transport::TokioTransport,
+            &TokioSpawner,
+            0,
+            test_registry(),
+            test_buf(),
+            SocketSettings {
+                receive_capacity: 2,
+                receive_overflow: policy,
+                ..SocketSettings::default()
+            },
+        )
+        .await
+        .unwrap();
+        let raw_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        for request_id in 1..=count {
+            send_numbered(&raw_socket, sm.port(), request_id).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        (sm, raw_socket)
+    }
+
+    async fn send_numbered(raw_socket: &UdpSocket, port: u16, request_id: u32) {
+        let msg = Message::<TestPayload>::new_sd(request_id, &empty_sd_header());
+        let mut buf = vec![0u8; 128];
+        let n = msg.encode(&mut buf.as_mut_slice()).unwrap();
+        raw_socket
+            .send_to(&buf[..n], SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+            .await
+            .unwrap();
+    }
+
+    async fn receive_numbered(sm: &mut TestSocketManager) -> (u32, u64) {
+        let received = tokio::time::timeout(std::time::Duration::from_secs(2), sm.receive())
+            .await
+            .expect("Timed out waiting for message")
+            .unwrap()
+            .unwrap();
+        (received.message.header().request_id(), received.overflowed)
+    }
+
+    #[tokio::test]
+    async fn payload_out_of_the_configured_range_is_rejected() {
+        static RANGES: [crate::protocol::PayloadRange; 1] = [crate::protocol::PayloadRange::new(
+            crate::protocol::MessageId::SD,
+            100,
+            200,
+        )];
+        let mut sm = TestSocketManager::bind_with_transport(
+            &crate::tokio_transport::TokioTransport,
+            &TokioSpawner,
+            0,
+            test_registry(),
+            test_buf(),
+            SocketSettings {
+                decode_limits: DecodeLimits::DEFAULT.with_payload_ranges(&RANGES),
+                ..SocketSettings::default()
+            },
+        )
+        .await
+        .unwrap();
+        let raw_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        send_numbered(&raw_socket, sm.port(), 1).await;
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(2), sm.receive())
+            .await
+            .expect("Timed out waiting for message")
+            .unwrap();
+        assert!(matches!(
+            result,
+            Err(Error::Protocol(crate::protocol::Error::PayloadOutOfRange {
+                min: 100,
+                max: 200,
+                ..
+            }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn drop_newest_keeps_the_queued_messages() {
+        let (mut sm, raw_socket) = flood(OverflowPolicy::DropNewest, 5).await;
+        assert_eq!(receive_numbered(&mut sm).await, (1, 0));
+        assert_eq!(receive_numbered(&mut sm).await, (2, 0));
+        send_numbered(&raw_socket, sm.port(), 6).await;
+        assert_eq!(
+            receive_numbered(&mut sm).await,
+            (6, 3),
+            "the next message delivered carries the count"
+        );
+        assert_eq!(sm.overflowed(), 3);
+    }
+
+    #[tokio::test]
+    async fn keep_latest_delivers_the_latest_message() {
+        let (mut sm, _raw_socket) = flood(OverflowPolicy::KeepLatest, 5).await;
+        assert_eq!(receive_numbered(&mut sm).await, (1, 0));
+        assert_eq!(receive_numbered(&mut sm).await, (2, 0));
+        assert_eq!(receive_numbered(&mut sm).await, (5, 2));
+        assert_eq!(sm.overflowed(), 2);
+    }
+
     #[tokio::test]
     async fn test_poll_receive() {
         let mut sm = bind_ephemeral_spawned().await;
@@ -1236,7 +1911,10 @@ mod tests {
         let received = ReceivedMessage {
             message: msg,
             source: SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 5000),
+            local_port: 30490,
             e2e_status: None,
+            dropped_datagrams: None,
+            overflowed: 0,
         };
         let s = format!("{received:?}");
         assert!(s.contains("ReceivedMessage"));
@@ -1409,6 +2087,7 @@ mod tests {
             0,
             test_registry(),
             test_buf(),
+            SocketSettings::default(),
         )
         .await
         .expect("bind via custom factory");
@@ -1461,6 +2140,7 @@ mod tests {
             0,
             test_registry(),
             test_buf(),
+            SocketSettings::default(),
         )
         .await
         .expect("bind via custom factory");
@@ -1575,6 +2255,7 @@ mod tests {
             0,
             test_registry(),
             test_buf(),
+            SocketSettings::default(),
         )
         .await
         .expect("bind via wrapping factory");
@@ -1634,6 +2315,7 @@ mod tests {
             0,
             test_registry(),
             test_buf(),
+            SocketSettings::default(),
         )
         .await
         .expect_err("factory returned Err, bind must surface it");
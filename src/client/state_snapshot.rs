@@ -0,0 +1,85 @@
+//! What a client discovered and is waiting for, as returned by
+//! [`Client::state_snapshot`](crate::Client::state_snapshot).
+
+use core::net::Ipv4Addr;
+use core::time::Duration;
+
+use heapless::Vec;
+
+use super::service_registry::{SERVICE_REGISTRY_CAP, ServiceEndpointKey};
+use super::subscriptions::{SUBSCRIPTIONS_CAP, SubscriptionState};
+
+/// Number of requests awaiting a reply a [`ClientSnapshot`] lists: all
+/// of them, as the client tracks no more.
+pub const PENDING_REQUESTS_CAP: usize = super::inner::PENDING_RESPONSES_CAP;
+
+/// An offer in the client's service registry: discovered through SD or
+/// added with `Client::add_endpoint` / `Client::add_static_endpoint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DiscoveredOffer {
+    /// Service ID and provider endpoint.
+    pub key: ServiceEndpointKey,
+    /// Instance ID
+    pub instance_id: u16,
+    /// Major version
+    pub major_version: u8,
+    /// Minor version
+    pub minor_version: u32,
+    /// Time left before the offer lapses; `None` for one that does not,
+    /// or without a clock.
+    pub ttl_remaining: Option<Duration>,
+    /// Added with `Client::add_static_endpoint`.
+    pub is_static: bool,
+    /// Unanswered by the reachability probe.
+    pub degraded: bool,
+}
+
+/// An eventgroup subscription the client keeps alive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TrackedSubscription {
+    /// Service ID and provider endpoint.
+    pub key: ServiceEndpointKey,
+    /// Instance ID
+    pub instance_id: u16,
+    /// Event group ID
+    pub event_group_id: u16,
+    /// TTL requested, in seconds.
+    pub ttl: u32,
+    /// Unicast port the subscription was made from.
+    pub client_port: u16,
+    /// [`SubscriptionState::Pending`] or
+    /// [`SubscriptionState::Acknowledged`].
+    pub state: SubscriptionState,
+}
+
+/// A request awaiting its reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PendingRequest {
+    /// Provider the request went to.
+    pub peer: Ipv4Addr,
+    /// Service ID
+    pub service_id: u16,
+    /// Method ID
+    pub method_id: u16,
+    /// Session ID, counted per peer.
+    pub session_id: u16,
+}
+
+/// Discovery, subscription and request state of a client at one instant,
+/// for crash reports and support bundles. Serializable with the `serde`
+/// feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ClientSnapshot {
+    /// Interface the client's sockets are bound to.
+    pub interface: Ipv4Addr,
+    /// Offers in the service registry, in no particular order.
+    pub offers: Vec<DiscoveredOffer, SERVICE_REGISTRY_CAP>,
+    /// Subscriptions awaiting their ack or their next refresh.
+    pub subscriptions: Vec<TrackedSubscription, SUBSCRIPTIONS_CAP>,
+    /// Requests awaiting their reply.
+    pub pending_requests: Vec<PendingRequest, PENDING_REQUESTS_CAP>,
+}
@@ -0,0 +1,402 @@
+use core::net::IpAddr;
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+use heapless::Vec;
+
+use crate::protocol::sd::TTL_INFINITE;
+
+use super::service_registry::{ServiceEndpointKey, ServiceRegistry};
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_SUBSCRIPTIONS_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_SUBSCRIPTIONS_CAP: usize = 16;
+
+/// Number of eventgroup subscriptions made with
+/// [`Client::subscribe_eventgroup`](crate::Client::subscribe_eventgroup)
+/// a client tracks and refreshes. Sized via
+/// `SIMPLE_SOMEIP_SUBSCRIPTIONS_CAP`; defaults to 4 on bare-metal, 16
+/// otherwise.
+pub const SUBSCRIPTIONS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_SUBSCRIPTIONS_CAP"),
+    _DEFAULT_SUBSCRIPTIONS_CAP,
+);
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_SUBSCRIBE_MANY_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_SUBSCRIBE_MANY_CAP: usize = 16;
+
+/// Number of eventgroups one
+/// [`Client::subscribe_many`](crate::Client::subscribe_many) call can
+/// subscribe to. Sized via `SIMPLE_SOMEIP_SUBSCRIBE_MANY_CAP`; defaults to
+/// 4 on bare-metal, 16 otherwise.
+pub const SUBSCRIBE_MANY_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_SUBSCRIBE_MANY_CAP"),
+    _DEFAULT_SUBSCRIBE_MANY_CAP,
+);
+
+/// How long [`Client::subscribe_eventgroup`](crate::Client::subscribe_eventgroup)
+/// waits for the provider's `SubscribeEventgroupAck` or `Nack`.
+pub const SUBSCRIBE_ACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where a tracked subscription stands, as reported by
+/// [`SubscriptionHandle::state`](super::SubscriptionHandle::state).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum SubscriptionState {
+    /// Sent; the provider has not answered yet.
+    Pending,
+    /// Acknowledged by the provider, and refreshed while it lasts.
+    Acknowledged,
+    /// No longer tracked: rejected, unanswered, or stopped.
+    Inactive,
+}
+
+/// Source of [`Subscription::id`]s, shared by every client in the
+/// process.
+static NEXT_SUBSCRIPTION_ID: AtomicU32 = AtomicU32::new(1);
+
+/// A fresh id for a subscription made through a
+/// [`SubscriptionHandle`](super::SubscriptionHandle).
+pub(super) fn next_subscription_id() -> u32 {
+    NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// An eventgroup subscription the client keeps alive.
+#[derive(Debug)]
+pub(super) struct Subscription<S> {
+    /// Tells the subscription apart from an earlier one to the same
+    /// eventgroup it replaced, so the earlier handle cannot stop it. `0`
+    /// when made without a handle.
+    pub(super) id: u32,
+    pub(super) key: ServiceEndpointKey,
+    pub(super) instance_id: u16,
+    /// Major version of the offer subscribed to; an offer of the same
+    /// service instance and version at another endpoint takes over.
+    pub(super) major_version: u8,
+    pub(super) event_group_id: u16,
+    pub(super) ttl: u32,
+    /// Unicast port the subscription was made from; the provider keys
+    /// its subscribers by it, so refreshes and the stop use it too.
+    pub(super) client_port: u16,
+    /// Caller awaiting the first Ack or Nack.
+    pub(super) waiter: Option<S>,
+    /// While a caller waits, when to give up on the ack; once
+    /// acknowledged, when to refresh. `None` without a clock, or for a
+    /// subscription that never lapses.
+    pub(super) due: Option<Duration>,
+    /// The provider's offer went away since the ack. It is not
+    /// refreshed meanwhile, and sent again once the offer is back.
+    pub(super) offer_lost: bool,
+}
+
+impl<S> Subscription<S> {
+    /// `true` if `ack`, received from `source`, answers this
+    /// subscription.
+    pub(super) fn matches(&self, source: IpAddr, ack: &crate::SubscriptionAck) -> bool {
+        self.key.endpoint.addr.ip() == source
+            && self.key.service_id == ack.service_id
+            && self.instance_id == ack.instance_id
+            && self.event_group_id == ack.event_group_id
+    }
+
+    /// When to send the next refresh after one was acknowledged at
+    /// `now`: halfway through the subscription's lifetime.
+    pub(super) fn next_refresh(&self, now: Option<Duration>) -> Option<Duration> {
+        if self.ttl >= TTL_INFINITE {
+            return None;
+        }
+        now.map(|now| now.saturating_add(Duration::from_secs(u64::from(self.ttl)) / 2))
+    }
+
+    pub(super) fn state(&self) -> SubscriptionState {
+        if self.waiter.is_some() {
+            SubscriptionState::Pending
+        } else {
+            SubscriptionState::Acknowledged
+        }
+    }
+}
+
+/// Subscriptions of one client, awaiting their ack or their refresh.
+pub(super) type Subscriptions<S> = Vec<Subscription<S>, SUBSCRIPTIONS_CAP>;
+
+/// Make the acknowledged subscriptions to `provider` due at `now`: it
+/// rebooted and forgot its subscribers, so they are sent again on the
+/// next refresh instead of halfway through their lifetime, or never for
+/// an infinite TTL. Returns how many. Without a clock (`now` is `None`)
+/// nothing is refreshed, and neither are these.
+pub(super) fn resubscribe_rebooted<S>(
+    subscriptions: &mut Subscriptions<S>,
+    provider: IpAddr,
+    now: Option<Duration>,
+) -> usize {
+    let Some(now) = now else {
+        return 0;
+    };
+    let mut count = 0;
+    for subscription in subscriptions
+        .iter_mut()
+        .filter(|subscription| subscription.waiter.is_none())
+        .filter(|subscription| subscription.key.endpoint.addr.ip() == provider)
+    {
+        subscription.due = Some(now);
+        count += 1;
+    }
+    count
+}
+
+/// Follow the offers of the providers subscribed to in `registry`. An
+/// acknowledged subscription whose offer went away, by a `StopOffer` or
+/// its TTL lapsing, is no longer refreshed. Once the service instance is
+/// offered again at the same major version, at the same endpoint or at
+/// another one of the same transport, it is moved to that offer and made
+/// due at `now`, as the provider may have restarted and forgotten its
+/// subscribers, and passed to `resumed`. Without a clock (`now` is
+/// `None`) nothing is refreshed, and these are not resumed.
+pub(super) fn follow_offers<S>(
+    subscriptions: &mut Subscriptions<S>,
+    registry: &ServiceRegistry,
+    now: Option<Duration>,
+    mut resumed: impl FnMut(&Subscription<S>),
+) {
+    for subscription in subscriptions
+        .iter_mut()
+        .filter(|subscription| subscription.waiter.is_none())
+    {
+        let offered = registry.get(subscription.key).is_some();
+        if offered {
+            if subscription.offer_lost
+                && let Some(now) = now
+            {
+                subscription.offer_lost = false;
+                subscription.due = Some(now);
+                resumed(subscription);
+            }
+            continue;
+        }
+        let moved = registry.find(|key, info| {
+            key.service_id == subscription.key.service_id
+                && key.endpoint.protocol == subscription.key.endpoint.protocol
+                && info.instance_id == subscription.instance_id
+                && info.major_version == subscription.major_version
+        });
+        if let (Some(key), Some(now)) = (moved, now) {
+            subscription.key = key;
+            subscription.offer_lost = false;
+            subscription.due = Some(now);
+            resumed(subscription);
+        } else if !subscription.offer_lost {
+            subscription.offer_lost = true;
+            subscription.due = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+    #[test]
+    fn matches_ack_from_provider_and_refreshes_at_half_ttl() {
+        let provider = Ipv4Addr::new(192, 168, 1, 20);
+        let subscription = Subscription::<()> {
+            id: 0,
+            key: ServiceEndpointKey::udp(0x5C, SocketAddr::V4(SocketAddrV4::new(provider, 30509))),
+            instance_id: 1,
+            major_version: 1,
+            event_group_id: 0x10,
+            ttl: 4,
+            client_port: 40000,
+            waiter: None,
+            due: None,
+            offer_lost: false,
+        };
+        let ack = crate::SubscriptionAck {
+            service_id: 0x5C,
+            instance_id: 1,
+            major_version: 1,
+            event_group_id: 0x10,
+            ttl: 4,
+        };
+        assert!(subscription.matches(provider.into(), &ack));
+        assert!(!subscription.matches(Ipv4Addr::new(192, 168, 1, 21).into(), &ack));
+        let other_group = crate::SubscriptionAck {
+            event_group_id: 0x11,
+            ..ack
+        };
+        assert!(!subscription.matches(provider.into(), &other_group));
+
+        assert_eq!(
+            subscription.next_refresh(Some(Duration::from_secs(10))),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(subscription.next_refresh(None), None);
+        let forever = Subscription::<()> {
+            ttl: TTL_INFINITE,
+            ..subscription
+        };
+        assert_eq!(forever.next_refresh(Some(Duration::from_secs(10))), None);
+    }
+
+    #[test]
+    fn reboot_makes_acknowledged_subscriptions_to_the_provider_due() {
+        let provider = Ipv4Addr::new(192, 168, 1, 20);
+        let subscription = |ip: Ipv4Addr, waiter: Option<()>| Subscription {
+            id: 0,
+            key: ServiceEndpointKey::udp(0x5C, SocketAddr::V4(SocketAddrV4::new(ip, 30509))),
+            instance_id: 1,
+            major_version: 1,
+            event_group_id: 0x10,
+            ttl: TTL_INFINITE,
+            client_port: 40000,
+            waiter,
+            due: None,
+            offer_lost: false,
+        };
+        let mut subscriptions: Subscriptions<()> = Vec::new();
+        subscriptions.push(subscription(provider, None)).unwrap();
+        subscriptions
+            .push(subscription(provider, Some(())))
+            .unwrap();
+        subscriptions
+            .push(subscription(Ipv4Addr::new(192, 168, 1, 21), None))
+            .unwrap();
+
+        assert_eq!(
+            resubscribe_rebooted(&mut subscriptions, provider.into(), None),
+            0
+        );
+        let now = Duration::from_secs(5);
+        assert_eq!(
+            resubscribe_rebooted(&mut subscriptions, provider.into(), Some(now)),
+            1
+        );
+        let due: std::vec::Vec<_> = subscriptions.iter().map(|s| s.due).collect();
+        assert_eq!(
+            due,
+            [Some(now), None, None],
+            "pending and other providers untouched"
+        );
+    }
+
+    #[test]
+    fn subscriptions_resume_once_their_offer_is_back() {
+        use crate::client::reachability::ProbeState;
+        use crate::client::service_registry::ServiceEndpointInfo;
+
+        let key = ServiceEndpointKey::udp(
+            0x5C,
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 20), 30509)),
+        );
+        let info = ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version: 1,
+            minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
+        };
+        let mut subscriptions: Subscriptions<()> = Vec::new();
+        subscriptions
+            .push(Subscription {
+                id: 0,
+                key,
+                instance_id: 1,
+                major_version: 1,
+                event_group_id: 0x10,
+                ttl: 4,
+                client_port: 40000,
+                waiter: None,
+                due: Some(Duration::from_secs(2)),
+                offer_lost: false,
+            })
+            .unwrap();
+        let mut registry = ServiceRegistry::default();
+        let mut resumed = 0;
+
+        follow_offers(&mut subscriptions, &registry, Some(Duration::ZERO), |_| {
+            resumed += 1;
+        });
+        assert!(subscriptions[0].offer_lost);
+        assert_eq!(subscriptions[0].due, None, "not refreshed while gone");
+
+        registry.insert(key, info).unwrap();
+        follow_offers(&mut subscriptions, &registry, None, |_| resumed += 1);
+        assert_eq!(resumed, 0, "not without a clock");
+        let now = Duration::from_secs(5);
+        follow_offers(&mut subscriptions, &registry, Some(now), |_| resumed += 1);
+        follow_offers(&mut subscriptions, &registry, Some(now), |_| resumed += 1);
+        assert_eq!(resumed, 1);
+        assert!(!subscriptions[0].offer_lost);
+        assert_eq!(subscriptions[0].due, Some(now));
+    }
+
+    #[test]
+    fn subscriptions_follow_the_provider_to_a_new_port() {
+        use crate::client::reachability::ProbeState;
+        use crate::client::service_registry::ServiceEndpointInfo;
+
+        let provider = Ipv4Addr::new(192, 168, 1, 20);
+        let at =
+            |port| ServiceEndpointKey::udp(0x5C, SocketAddr::V4(SocketAddrV4::new(provider, port)));
+        let info = |major_version| ServiceEndpointInfo {
+            instance_id: 1,
+            local_port: 0,
+            major_version,
+            minor_version: 0,
+            expires_at: None,
+            expiry_notified: false,
+            probe: ProbeState::default(),
+            is_static: false,
+            load_balancing: None,
+        };
+        let mut subscriptions: Subscriptions<()> = Vec::new();
+        subscriptions
+            .push(Subscription {
+                id: 0,
+                key: at(30509),
+                instance_id: 1,
+                major_version: 1,
+                event_group_id: 0x10,
+                ttl: 4,
+                client_port: 40000,
+                waiter: None,
+                due: Some(Duration::from_secs(2)),
+                offer_lost: false,
+            })
+            .unwrap();
+        let mut registry = ServiceRegistry::default();
+        let mut resumed = std::vec::Vec::new();
+
+        follow_offers(&mut subscriptions, &registry, Some(Duration::ZERO), |s| {
+            resumed.push(s.key);
+        });
+        assert!(subscriptions[0].offer_lost);
+
+        registry.insert(at(30600), info(2)).unwrap();
+        follow_offers(&mut subscriptions, &registry, Some(Duration::ZERO), |s| {
+            resumed.push(s.key);
+        });
+        assert!(
+            resumed.is_empty(),
+            "another major version does not take over"
+        );
+
+        registry.insert(at(30511), info(1)).unwrap();
+        let now = Duration::from_secs(5);
+        follow_offers(&mut subscriptions, &registry, Some(now), |s| {
+            resumed.push(s.key);
+        });
+        assert_eq!(resumed, [at(30511)]);
+        assert_eq!(subscriptions[0].key, at(30511));
+        assert!(!subscriptions[0].offer_lost);
+        assert_eq!(subscriptions[0].due, Some(now));
+    }
+}
@@ -0,0 +1,130 @@
+use core::net::{Ipv4Addr, SocketAddrV4};
+
+use heapless::Vec;
+use heapless::index_map::FnvIndexMap;
+
+/// Number of requests the client keeps outstanding on one TCP connection.
+/// Requests are pipelined: each is written as soon as it is sent, and
+/// replies are matched by request ID in whatever order they come back.
+/// Sized via `SIMPLE_SOMEIP_TCP_IN_FLIGHT_CAP`.
+pub const TCP_IN_FLIGHT_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_TCP_IN_FLIGHT_CAP"), 16);
+
+/// Identifies a request awaiting its reply, which comes from the peer it
+/// was sent to and echoes both its message ID and its request ID. The
+/// peer is part of the key because each one has its own session counter:
+/// requests to two peers can carry the same request ID.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) struct RequestKey {
+    pub(super) peer: Ipv4Addr,
+    pub(super) message_id: u32,
+    pub(super) request_id: u32,
+}
+
+impl RequestKey {
+    pub(super) const fn new(
+        peer: Ipv4Addr,
+        message_id: crate::protocol::MessageId,
+        request_id: u32,
+    ) -> Self {
+        Self {
+            peer,
+            message_id: message_id.message_id(),
+            request_id,
+        }
+    }
+}
+
+/// Requests awaiting a reply on each of up to `N` TCP connections, keyed
+/// by provider endpoint. `N` must be a power of two.
+#[derive(Debug)]
+pub(super) struct TcpInFlight<const N: usize> {
+    requests: FnvIndexMap<SocketAddrV4, Vec<RequestKey, TCP_IN_FLIGHT_CAP>, N>,
+}
+
+impl<const N: usize> TcpInFlight<N> {
+    pub(super) const fn new() -> Self {
+        Self {
+            requests: FnvIndexMap::new(),
+        }
+    }
+
+    /// `true` if another request to `peer` would exceed
+    /// [`TCP_IN_FLIGHT_CAP`].
+    pub(super) fn is_full(&self, peer: SocketAddrV4) -> bool {
+        self.requests
+            .get(&peer)
+            .is_some_and(|ids| ids.len() >= TCP_IN_FLIGHT_CAP)
+    }
+
+    /// Record a request sent to `peer`. Callers check [`Self::is_full`]
+    /// first; a request that does not fit is not tracked.
+    pub(super) fn record(&mut self, peer: SocketAddrV4, request: RequestKey) {
+        if let Some(ids) = self.requests.get_mut(&peer) {
+            let _ = ids.push(request);
+        } else {
+            let mut ids = Vec::new();
+            let _ = ids.push(request);
+            let _ = self.requests.insert(peer, ids);
+        }
+    }
+
+    /// Forget `request` once `peer` has replied to it.
+    pub(super) fn answered(&mut self, peer: SocketAddrV4, request: RequestKey) {
+        if let Some(ids) = self.requests.get_mut(&peer) {
+            ids.retain(|&id| id != request);
+            if ids.is_empty() {
+                self.requests.remove(&peer);
+            }
+        }
+    }
+
+    /// Remove and return the requests still waiting on `peer`, whose
+    /// connection has closed.
+    pub(super) fn take(&mut self, peer: SocketAddrV4) -> Vec<RequestKey, TCP_IN_FLIGHT_CAP> {
+        self.requests.remove(&peer).unwrap_or_default()
+    }
+
+    /// Endpoints with requests in flight.
+    pub(super) fn peers(&self) -> impl Iterator<Item = SocketAddrV4> + '_ {
+        self.requests.keys().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::MessageId;
+
+    fn key(request_id: u32) -> RequestKey {
+        RequestKey::new(
+            Ipv4Addr::new(10, 0, 0, 1),
+            MessageId::new(0x1234_0001),
+            request_id,
+        )
+    }
+
+    #[test]
+    fn tracks_requests_per_connection_up_to_the_cap() {
+        let a = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 30501);
+        let b = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 30501);
+        let mut in_flight = TcpInFlight::<4>::new();
+        for id in 0..u32::try_from(TCP_IN_FLIGHT_CAP).unwrap() {
+            assert!(!in_flight.is_full(a));
+            in_flight.record(a, key(id));
+        }
+        assert!(in_flight.is_full(a));
+        assert!(!in_flight.is_full(b), "limit is per connection");
+
+        in_flight.answered(a, key(3));
+        assert!(!in_flight.is_full(a));
+        in_flight.record(b, key(99));
+        assert_eq!(in_flight.peers().count(), 2);
+
+        let orphaned = in_flight.take(a);
+        assert_eq!(orphaned.len(), TCP_IN_FLIGHT_CAP - 1);
+        assert!(!orphaned.contains(&key(3)));
+        in_flight.answered(b, key(99));
+        assert_eq!(in_flight.peers().count(), 0);
+    }
+}
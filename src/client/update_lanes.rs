@@ -0,0 +1,352 @@
+//! Categorized update lanes, created with
+//! [`ClientUpdates::into_lanes`](super::ClientUpdates::into_lanes).
+
+use core::task::{Context, Poll, Waker};
+
+use heapless::Deque;
+
+use crate::protocol::MessageType;
+use crate::traits::PayloadWireFormat;
+use crate::transport::{ChannelFactory, UnboundedRecv};
+
+use super::{ClientUpdate, ClientUpdates};
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_UPDATE_LANE_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_UPDATE_LANE_CAP: usize = 32;
+
+/// Most updates one lane of [`UpdateLanes`] can hold; a
+/// [`LaneConfig::capacity`] above it is clamped. Sized via
+/// `SIMPLE_SOMEIP_UPDATE_LANE_CAP`; defaults to 4 on bare-metal, 32
+/// otherwise.
+pub const UPDATE_LANE_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_UPDATE_LANE_CAP"),
+    _DEFAULT_UPDATE_LANE_CAP,
+);
+
+/// Lane a [`ClientUpdate`] is sorted into by [`UpdateLanes`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UpdateCategory {
+    /// `DiscoveryUpdated`, `SenderRebooted`, `OfferExpiring`,
+    /// `ServiceAvailable`, `ServiceUnavailable`, `Reachability` and
+    /// `Resubscribed`.
+    Discovery,
+    /// `Unicast` messages of type `Notification`.
+    Events,
+    /// Every other `Unicast` message: responses and error replies.
+    Responses,
+    /// `Error`.
+    Errors,
+}
+
+impl UpdateCategory {
+    /// All categories, in the order [`UpdateLanes::recv_next`] serves
+    /// them.
+    pub const ALL: [Self; 4] = [Self::Errors, Self::Responses, Self::Events, Self::Discovery];
+
+    const fn index(self) -> usize {
+        match self {
+            Self::Errors => 0,
+            Self::Responses => 1,
+            Self::Events => 2,
+            Self::Discovery => 3,
+        }
+    }
+}
+
+impl<P: PayloadWireFormat> ClientUpdate<P> {
+    /// The lane [`UpdateLanes`] sorts this update into.
+    #[must_use]
+    pub fn category(&self) -> UpdateCategory {
+        match self {
+            Self::DiscoveryUpdated(_)
+            | Self::SenderRebooted(_)
+            | Self::OfferExpiring { .. }
+            | Self::ServiceAvailable { .. }
+            | Self::ServiceUnavailable { .. }
+            | Self::Reachability { .. }
+            | Self::Resubscribed { .. } => UpdateCategory::Discovery,
+            Self::Unicast { message, .. } => {
+                if message.header().message_type().message_type() == MessageType::Notification {
+                    UpdateCategory::Events
+                } else {
+                    UpdateCategory::Responses
+                }
+            }
+            Self::Error(_) => UpdateCategory::Errors,
+        }
+    }
+}
+
+/// What a full lane does with an incoming update.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Drop the oldest queued update to make room: the lane keeps the
+    /// most recent state.
+    #[default]
+    DropOldest,
+    /// Drop the incoming update: the lane keeps what it already holds.
+    DropNewest,
+}
+
+/// Capacity and drop policy of one lane.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LaneConfig {
+    /// Updates the lane holds before dropping. Clamped to
+    /// `1..=UPDATE_LANE_CAP`.
+    pub capacity: usize,
+    pub drop_policy: DropPolicy,
+}
+
+impl LaneConfig {
+    #[must_use]
+    pub const fn new(capacity: usize, drop_policy: DropPolicy) -> Self {
+        Self {
+            capacity,
+            drop_policy,
+        }
+    }
+}
+
+impl Default for LaneConfig {
+    /// [`UPDATE_LANE_CAP`] updates, dropping the oldest.
+    fn default() -> Self {
+        Self::new(UPDATE_LANE_CAP, DropPolicy::DropOldest)
+    }
+}
+
+/// Per-category settings for [`UpdateLanes`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UpdateLanesConfig {
+    pub discovery: LaneConfig,
+    pub events: LaneConfig,
+    pub responses: LaneConfig,
+    pub errors: LaneConfig,
+}
+
+impl UpdateLanesConfig {
+    #[must_use]
+    pub const fn with_discovery(mut self, lane: LaneConfig) -> Self {
+        self.discovery = lane;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_events(mut self, lane: LaneConfig) -> Self {
+        self.events = lane;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_responses(mut self, lane: LaneConfig) -> Self {
+        self.responses = lane;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_errors(mut self, lane: LaneConfig) -> Self {
+        self.errors = lane;
+        self
+    }
+
+    const fn lane(&self, category: UpdateCategory) -> LaneConfig {
+        match category {
+            UpdateCategory::Discovery => self.discovery,
+            UpdateCategory::Events => self.events,
+            UpdateCategory::Responses => self.responses,
+            UpdateCategory::Errors => self.errors,
+        }
+    }
+}
+
+/// One bounded lane.
+#[derive(Debug)]
+struct Lane<T> {
+    queue: Deque<T, UPDATE_LANE_CAP>,
+    capacity: usize,
+    drop_policy: DropPolicy,
+    dropped: u64,
+}
+
+impl<T> Lane<T> {
+    fn new(config: LaneConfig) -> Self {
+        Self {
+            queue: Deque::new(),
+            capacity: config.capacity.clamp(1, UPDATE_LANE_CAP),
+            drop_policy: config.drop_policy,
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, item: T) {
+        if self.queue.len() >= self.capacity {
+            self.dropped += 1;
+            match self.drop_policy {
+                DropPolicy::DropOldest => {
+                    let _ = self.queue.pop_front();
+                }
+                DropPolicy::DropNewest => return,
+            }
+        }
+        // Cannot fail: `capacity` is at most the deque's size and a slot
+        // was freed above when full.
+        let _ = self.queue.push_back(item);
+    }
+}
+
+/// Client updates sorted into separately bounded lanes by
+/// [`UpdateCategory`].
+///
+/// With a single [`ClientUpdates`] channel, a consumer that falls behind
+/// on discovery chatter also delays the events queued behind it. Each
+/// poll of `UpdateLanes` first drains the channel into the lanes, so
+/// every lane only ever holds its own backlog: a lane nobody reads fills
+/// up and drops per its [`DropPolicy`] (counted by
+/// [`dropped`](Self::dropped)) while the other lanes keep flowing.
+///
+/// Like [`ClientUpdates`], `UpdateLanes` has a single owner; read one
+/// lane with [`recv`](Self::recv), or all of them by priority with
+/// [`recv_next`](Self::recv_next). Both are cancel-safe.
+pub struct UpdateLanes<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> {
+    updates: ClientUpdates<MessageDefinitions, C>,
+    /// Indexed by [`UpdateCategory::index`].
+    lanes: [Lane<ClientUpdate<MessageDefinitions>>; 4],
+    /// Set once the channel reported the client loop gone.
+    closed: bool,
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory> core::fmt::Debug
+    for UpdateLanes<MessageDefinitions, C>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UpdateLanes")
+            .field("closed", &self.closed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory>
+    ClientUpdates<MessageDefinitions, C>
+{
+    /// Split this stream into per-category lanes with their own capacity
+    /// and drop policy. See [`UpdateLanes`].
+    #[must_use]
+    pub fn into_lanes(self, config: UpdateLanesConfig) -> UpdateLanes<MessageDefinitions, C> {
+        UpdateLanes {
+            updates: self,
+            lanes: UpdateCategory::ALL.map(|category| Lane::new(config.lane(category))),
+            closed: false,
+        }
+    }
+}
+
+impl<MessageDefinitions: PayloadWireFormat + 'static, C: ChannelFactory>
+    UpdateLanes<MessageDefinitions, C>
+{
+    /// Wait for the next update of `category`.
+    ///
+    /// Returns `None` once the client loop has exited and the lane is
+    /// empty.
+    pub async fn recv(
+        &mut self,
+        category: UpdateCategory,
+    ) -> Option<ClientUpdate<MessageDefinitions>> {
+        core::future::poll_fn(|cx| self.poll_lane(cx, &[category])).await
+    }
+
+    /// Wait for the next update of any category, serving errors first,
+    /// then responses, events and discovery last.
+    ///
+    /// Returns `None` once the client loop has exited and every lane is
+    /// empty.
+    pub async fn recv_next(&mut self) -> Option<ClientUpdate<MessageDefinitions>> {
+        core::future::poll_fn(|cx| self.poll_lane(cx, &UpdateCategory::ALL)).await
+    }
+
+    /// Take the next queued update of `category` without waiting.
+    pub fn try_recv(
+        &mut self,
+        category: UpdateCategory,
+    ) -> Option<ClientUpdate<MessageDefinitions>> {
+        match self.poll_lane(&mut Context::from_waker(Waker::noop()), &[category]) {
+            Poll::Ready(update) => update,
+            Poll::Pending => None,
+        }
+    }
+
+    /// Updates currently queued in the lane of `category`.
+    #[must_use]
+    pub fn len(&self, category: UpdateCategory) -> usize {
+        self.lanes[category.index()].queue.len()
+    }
+
+    /// `true` if no lane holds an update.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lanes.iter().all(|lane| lane.queue.is_empty())
+    }
+
+    /// Updates of `category` dropped so far because the lane was full.
+    #[must_use]
+    pub fn dropped(&self, category: UpdateCategory) -> u64 {
+        self.lanes[category.index()].dropped
+    }
+
+    fn poll_lane(
+        &mut self,
+        cx: &mut Context<'_>,
+        categories: &[UpdateCategory],
+    ) -> Poll<Option<ClientUpdate<MessageDefinitions>>> {
+        // Drain first, so the channel never holds updates behind a slow
+        // lane. Stops on `Pending`, which registers `cx` for the next one.
+        while !self.closed {
+            match self.updates.update_receiver.poll_recv(cx) {
+                Poll::Ready(Some(update)) => {
+                    self.lanes[update.category().index()].push(update);
+                }
+                Poll::Ready(None) => self.closed = true,
+                Poll::Pending => break,
+            }
+        }
+        for category in categories {
+            if let Some(update) = self.lanes[category.index()].queue.pop_front() {
+                return Poll::Ready(Some(update));
+            }
+        }
+        if self.closed {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_lane_applies_its_drop_policy() {
+        let mut oldest = Lane::new(LaneConfig::new(2, DropPolicy::DropOldest));
+        let mut newest = Lane::new(LaneConfig::new(2, DropPolicy::DropNewest));
+        for n in 0..4u8 {
+            oldest.push(n);
+            newest.push(n);
+        }
+        assert_eq!(
+            oldest.queue.iter().copied().collect::<std::vec::Vec<_>>(),
+            [2, 3]
+        );
+        assert_eq!(
+            newest.queue.iter().copied().collect::<std::vec::Vec<_>>(),
+            [0, 1]
+        );
+        assert_eq!((oldest.dropped, newest.dropped), (2, 2));
+
+        let clamped = Lane::<u8>::new(LaneConfig::new(0, DropPolicy::DropOldest));
+        assert_eq!(clamped.capacity, 1);
+        let clamped = Lane::<u8>::new(LaneConfig::new(usize::MAX, DropPolicy::DropOldest));
+        assert_eq!(clamped.capacity, UPDATE_LANE_CAP);
+    }
+}
@@ -0,0 +1,157 @@
+use heapless::index_map::FnvIndexMap;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_VERSION_POLICIES_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_VERSION_POLICIES_CAP: usize = 16;
+
+/// Maximum number of services a client can pin a [`VersionPolicy`] to.
+/// Must be a power of two ([`FnvIndexMap`] requirement). Sized via
+/// `SIMPLE_SOMEIP_VERSION_POLICIES_CAP`; defaults to 4 on bare-metal, 16
+/// otherwise.
+pub const VERSION_POLICIES_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_VERSION_POLICIES_CAP"),
+    _DEFAULT_VERSION_POLICIES_CAP,
+);
+
+/// Which offered interface versions a client accepts for one service.
+///
+/// SOME/IP versions an interface as `major.minor`: a major bump breaks
+/// compatibility, a minor bump only adds. Filtering offers on an exact
+/// minor therefore makes a consumer disappear the moment its provider
+/// ships a backwards-compatible update; most consumers want [`VersionPolicy::MinimumMinor`] instead.
+///
+/// Set with [`Client::set_version_policy`](crate::Client::set_version_policy).
+/// Offers the policy rejects are not added to the endpoint registry, so
+/// `subscribe` / `request` against them fail with `ServiceNotFound`.
+/// Services without a policy accept every offer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VersionPolicy {
+    /// Accept any offered version.
+    Any,
+    /// Accept offers with this major version and any minor version.
+    AnyMinor {
+        /// Required major version.
+        major: u8,
+    },
+    /// Accept offers with this major version and a minor version of at
+    /// least `minor`.
+    MinimumMinor {
+        /// Required major version.
+        major: u8,
+        /// Lowest acceptable minor version.
+        minor: u32,
+    },
+    /// Accept only offers of exactly this version.
+    Exact {
+        /// Required major version.
+        major: u8,
+        /// Required minor version.
+        minor: u32,
+    },
+}
+
+impl VersionPolicy {
+    /// `true` if an offer of `major.minor` satisfies this policy.
+    #[must_use]
+    pub const fn accepts(&self, major: u8, minor: u32) -> bool {
+        match *self {
+            Self::Any => true,
+            Self::AnyMinor { major: want } => major == want,
+            Self::MinimumMinor {
+                major: want,
+                minor: least,
+            } => major == want && minor >= least,
+            Self::Exact {
+                major: want,
+                minor: exact,
+            } => major == want && minor == exact,
+        }
+    }
+}
+
+/// Returned by [`VersionPolicies::set`] when the table is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionPoliciesFull;
+
+/// Per-service [`VersionPolicy`] table consulted on every SD offer.
+#[derive(Debug, Default)]
+pub struct VersionPolicies {
+    policies: FnvIndexMap<u16, VersionPolicy, VERSION_POLICIES_CAP>,
+}
+
+impl VersionPolicies {
+    /// Install (`Some`) or clear (`None`) the policy for `service_id`.
+    /// Returns `Err(VersionPoliciesFull)` if the table is at
+    /// [`VERSION_POLICIES_CAP`] and `service_id` has no policy yet.
+    pub fn set(
+        &mut self,
+        service_id: u16,
+        policy: Option<VersionPolicy>,
+    ) -> Result<(), VersionPoliciesFull> {
+        if let Some(policy) = policy {
+            self.policies
+                .insert(service_id, policy)
+                .map(|_| ())
+                .map_err(|_| VersionPoliciesFull)
+        } else {
+            self.policies.swap_remove(&service_id);
+            Ok(())
+        }
+    }
+
+    /// `true` if an offer of `service_id` at `major.minor` is acceptable.
+    pub fn accepts(&self, service_id: u16, major: u8, minor: u32) -> bool {
+        self.policies
+            .get(&service_id)
+            .is_none_or(|policy| policy.accepts(major, minor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_match_documented_semantics() {
+        assert!(VersionPolicy::Any.accepts(7, 99));
+        let any_minor = VersionPolicy::AnyMinor { major: 1 };
+        assert!(any_minor.accepts(1, 0) && any_minor.accepts(1, 42));
+        assert!(!any_minor.accepts(2, 0));
+        let minimum = VersionPolicy::MinimumMinor { major: 1, minor: 3 };
+        assert!(minimum.accepts(1, 3) && minimum.accepts(1, 4));
+        assert!(!minimum.accepts(1, 2) && !minimum.accepts(2, 3));
+        let exact = VersionPolicy::Exact { major: 1, minor: 3 };
+        assert!(exact.accepts(1, 3));
+        assert!(!exact.accepts(1, 4));
+    }
+
+    #[test]
+    fn table_defaults_to_accept_and_clears() {
+        let mut table = VersionPolicies::default();
+        assert!(table.accepts(0x1234, 9, 9));
+        table
+            .set(0x1234, Some(VersionPolicy::Exact { major: 1, minor: 0 }))
+            .unwrap();
+        assert!(!table.accepts(0x1234, 1, 1));
+        assert!(table.accepts(0x5678, 1, 1), "other services unaffected");
+        table.set(0x1234, None).unwrap();
+        assert!(table.accepts(0x1234, 1, 1));
+    }
+
+    #[test]
+    fn table_reports_full() {
+        let mut table = VersionPolicies::default();
+        for id in 0..u16::try_from(VERSION_POLICIES_CAP).unwrap() {
+            table.set(id, Some(VersionPolicy::Any)).unwrap();
+        }
+        assert_eq!(
+            table.set(0xFFFF, Some(VersionPolicy::Any)),
+            Err(VersionPoliciesFull)
+        );
+        // Replacing an existing entry still works at capacity.
+        table
+            .set(0, Some(VersionPolicy::AnyMinor { major: 1 }))
+            .unwrap();
+    }
+}
@@ -0,0 +1,326 @@
+//! Line-oriented deployment configuration file.
+//!
+//! The format is deliberately minimal so it can be templated by any
+//! deployment tool without a parser dependency: one `key = value` pair
+//! per line, `#` starts a comment, blank lines are ignored. Scalar keys
+//! may appear at most once; list keys repeat.
+//!
+//! ```text
+//! # addressing (scalar)
+//! interface = 192.168.1.10
+//! local_port = 30500
+//! ttl_secs = 3
+//! announce = on
+//!
+//! # SD offer timing in milliseconds (scalar)
+//! cyclic_offer_delay_ms = 1000
+//! repetitions_max = 3
+//! repetitions_base_delay_ms = 30
+//! initial_delay_min_ms = 10
+//! initial_delay_max_ms = 100
+//!
+//! # offered services: service/instance (repeatable)
+//! offer = 0x1234/1
+//!
+//! # static endpoints bypassing SD: service/instance@ip:port (repeatable)
+//! static_endpoint = 0x5678/1@10.0.0.2:30501
+//! ```
+
+use core::net::{Ipv4Addr, SocketAddrV4};
+use std::vec::Vec;
+
+use super::{Error, parse_bool, parse_u16};
+
+/// A `(service, instance)` pair the deployment offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OfferedService {
+    /// Offered service ID.
+    pub service_id: u16,
+    /// Offered instance ID.
+    pub instance_id: u16,
+}
+
+/// A provider endpoint known ahead of time, used without waiting for an
+/// SD offer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StaticEndpoint {
+    /// Service ID served at `addr`.
+    pub service_id: u16,
+    /// Instance ID served at `addr`.
+    pub instance_id: u16,
+    /// Provider unicast address.
+    pub addr: SocketAddrV4,
+}
+
+/// Parsed deployment configuration file. Every scalar is optional so
+/// the file only needs to spell out what differs from the values the
+/// application sets in code; see the [module docs](super) for where it
+/// sits in the precedence order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileConfig {
+    /// `interface`
+    pub interface: Option<Ipv4Addr>,
+    /// `local_port`
+    pub local_port: Option<u16>,
+    /// `ttl_secs`
+    pub ttl_secs: Option<u32>,
+    /// `announce`
+    pub announce: Option<bool>,
+    /// `cyclic_offer_delay_ms`
+    pub cyclic_offer_delay_ms: Option<u32>,
+    /// `repetitions_max`
+    pub repetitions_max: Option<u32>,
+    /// `repetitions_base_delay_ms`
+    pub repetitions_base_delay_ms: Option<u32>,
+    /// `initial_delay_min_ms`
+    pub initial_delay_min_ms: Option<u32>,
+    /// `initial_delay_max_ms`
+    pub initial_delay_max_ms: Option<u32>,
+    /// `offer` lines, in file order.
+    pub offers: Vec<OfferedService>,
+    /// `static_endpoint` lines, in file order.
+    pub static_endpoints: Vec<StaticEndpoint>,
+}
+
+impl FileConfig {
+    /// Parse the contents of a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Parse`] for the first line that is not a
+    /// well-formed `key = value` pair, names an unknown key, repeats a
+    /// scalar key, or carries a malformed value.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut cfg = Self::default();
+        for (idx, raw) in text.lines().enumerate() {
+            let line = idx + 1;
+            let content = raw.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+            let err = |reason| Error::Parse { line, reason };
+            let (key, value) = content
+                .split_once('=')
+                .ok_or_else(|| err("expected `key = value`"))?;
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "interface" => set_once(
+                    &mut cfg.interface,
+                    value.parse().ok(),
+                    line,
+                    "interface must be a dotted IPv4 address",
+                )?,
+                "local_port" => set_once(
+                    &mut cfg.local_port,
+                    value.parse().ok(),
+                    line,
+                    "local_port must be a decimal port number",
+                )?,
+                "ttl_secs" => set_once(
+                    &mut cfg.ttl_secs,
+                    value.parse().ok(),
+                    line,
+                    "ttl_secs must be a decimal number of seconds",
+                )?,
+                "announce" => set_once(
+                    &mut cfg.announce,
+                    parse_bool(value),
+                    line,
+                    "announce must be a boolean",
+                )?,
+                "cyclic_offer_delay_ms" => set_once(
+                    &mut cfg.cyclic_offer_delay_ms,
+                    value.parse().ok(),
+                    line,
+                    "cyclic_offer_delay_ms must be a decimal number of milliseconds",
+                )?,
+                "repetitions_max" => set_once(
+                    &mut cfg.repetitions_max,
+                    value.parse().ok(),
+                    line,
+                    "repetitions_max must be a decimal count",
+                )?,
+                "repetitions_base_delay_ms" => set_once(
+                    &mut cfg.repetitions_base_delay_ms,
+                    value.parse().ok(),
+                    line,
+                    "repetitions_base_delay_ms must be a decimal number of milliseconds",
+                )?,
+                "initial_delay_min_ms" => set_once(
+                    &mut cfg.initial_delay_min_ms,
+                    value.parse().ok(),
+                    line,
+                    "initial_delay_min_ms must be a decimal number of milliseconds",
+                )?,
+                "initial_delay_max_ms" => set_once(
+                    &mut cfg.initial_delay_max_ms,
+                    value.parse().ok(),
+                    line,
+                    "initial_delay_max_ms must be a decimal number of milliseconds",
+                )?,
+                "offer" => {
+                    let (service_id, instance_id) = parse_service_instance(value)
+                        .ok_or_else(|| err("offer must be `service/instance`"))?;
+                    cfg.offers.push(OfferedService {
+                        service_id,
+                        instance_id,
+                    });
+                }
+                "static_endpoint" => {
+                    let (ids, addr) = value
+                        .split_once('@')
+                        .ok_or_else(|| err("static_endpoint must be `service/instance@ip:port`"))?;
+                    let (service_id, instance_id) = parse_service_instance(ids)
+                        .ok_or_else(|| err("static_endpoint must be `service/instance@ip:port`"))?;
+                    let addr = addr
+                        .trim()
+                        .parse()
+                        .map_err(|_| err("static_endpoint address must be `ip:port`"))?;
+                    cfg.static_endpoints.push(StaticEndpoint {
+                        service_id,
+                        instance_id,
+                        addr,
+                    });
+                }
+                _ => return Err(err("unknown key")),
+            }
+        }
+        Ok(cfg)
+    }
+
+    /// Read and parse the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] if the file cannot be read, otherwise as
+    /// [`Self::parse`].
+    pub fn load(path: &std::path::Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path).map_err(|e| Error::Io(e.kind()))?;
+        Self::parse(&text)
+    }
+
+    /// Layer the scalar fields set in this file over `config`. Offers and
+    /// static endpoints are not part of a `ServerConfig` and are left to
+    /// the application, which typically builds one `Server` per
+    /// [`OfferedService`].
+    #[cfg(feature = "server")]
+    #[must_use]
+    pub fn apply_server(
+        &self,
+        mut config: crate::server::ServerConfig,
+    ) -> crate::server::ServerConfig {
+        if let Some(interface) = self.interface {
+            config.interface = interface;
+        }
+        if let Some(local_port) = self.local_port {
+            config.local_port = local_port;
+        }
+        if let Some(ttl) = self.ttl_secs {
+            config.ttl = ttl;
+        }
+        if let Some(announce) = self.announce {
+            config.announce = announce;
+        }
+        let millis = |ms: u32| core::time::Duration::from_millis(u64::from(ms));
+        if let Some(delay) = self.cyclic_offer_delay_ms {
+            config.cyclic_offer_delay = millis(delay);
+        }
+        if let Some(repetitions_max) = self.repetitions_max {
+            config.repetitions_max = repetitions_max;
+        }
+        if let Some(delay) = self.repetitions_base_delay_ms {
+            config.repetitions_base_delay = millis(delay);
+        }
+        if let Some(delay) = self.initial_delay_min_ms {
+            config.initial_delay_min = millis(delay);
+        }
+        if let Some(delay) = self.initial_delay_max_ms {
+            config.initial_delay_max = millis(delay);
+        }
+        config
+    }
+}
+
+fn set_once<T>(
+    slot: &mut Option<T>,
+    parsed: Option<T>,
+    line: usize,
+    malformed: &'static str,
+) -> Result<(), Error> {
+    if slot.is_some() {
+        return Err(Error::Parse {
+            line,
+            reason: "scalar key repeated",
+        });
+    }
+    *slot = Some(parsed.ok_or(Error::Parse {
+        line,
+        reason: malformed,
+    })?);
+    Ok(())
+}
+
+fn parse_service_instance(value: &str) -> Option<(u16, u16)> {
+    let (service, instance) = value.split_once('/')?;
+    Some((parse_u16(service.trim())?, parse_u16(instance.trim())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_documented_example() {
+        let cfg = FileConfig::parse(
+            "# comment\n\
+             interface = 192.168.1.10\n\
+             local_port = 30500 # trailing comment\n\
+             ttl_secs = 3\n\
+             announce = on\n\
+             cyclic_offer_delay_ms = 2000\n\
+             initial_delay_max_ms = 100\n\
+             \n\
+             offer = 0x1234/1\n\
+             offer = 0x1235/2\n\
+             static_endpoint = 0x5678/1@10.0.0.2:30501\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.interface, Some(Ipv4Addr::new(192, 168, 1, 10)));
+        assert_eq!(cfg.local_port, Some(30500));
+        assert_eq!(cfg.ttl_secs, Some(3));
+        assert_eq!(cfg.announce, Some(true));
+        assert_eq!(cfg.cyclic_offer_delay_ms, Some(2000));
+        assert_eq!(cfg.initial_delay_max_ms, Some(100));
+        assert_eq!(cfg.repetitions_max, None);
+        assert_eq!(cfg.offers.len(), 2);
+        assert_eq!(
+            cfg.static_endpoints[0],
+            StaticEndpoint {
+                service_id: 0x5678,
+                instance_id: 1,
+                addr: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 30501),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_line_of_first_error() {
+        let err = FileConfig::parse("ttl_secs = 3\nbogus = 1\n").unwrap_err();
+        assert_eq!(
+            err,
+            Error::Parse {
+                line: 2,
+                reason: "unknown key"
+            }
+        );
+        let err = FileConfig::parse("ttl_secs = 3\nttl_secs = 4\n").unwrap_err();
+        assert!(matches!(err, Error::Parse { line: 2, .. }));
+    }
+
+    #[test]
+    fn load_missing_file_is_io_error() {
+        let err =
+            FileConfig::load(std::path::Path::new("/nonexistent/simple-someip.conf")).unwrap_err();
+        assert_eq!(err, Error::Io(std::io::ErrorKind::NotFound));
+    }
+}
@@ -0,0 +1,443 @@
+//! Runtime configuration overlay sourced from environment variables.
+//!
+//! Container deployments typically bake the service catalog (IDs,
+//! versions, event groups) into the image while the addresses the
+//! container runs on vary per host. This module lets those
+//! per-deployment values be injected through the environment without
+//! rebuilding or re-templating the baked configuration.
+//!
+//! # Precedence
+//!
+//! Values are resolved lowest-to-highest; a later layer overrides an
+//! earlier one field by field:
+//!
+//! 1. Crate defaults ([`ServerConfig::new`](crate::server::ServerConfig::new),
+//!    the interface the `Client` is constructed with).
+//! 2. Values the application set in code or loaded from its own
+//!    configuration file — the `config` argument handed to
+//!    [`EnvOverlay::apply_server`] / [`EnvOverlay::apply_interface`].
+//! 3. Environment variables read by [`EnvOverlay`].
+//!
+//! Unset variables leave the lower layer untouched. Set-but-empty
+//! variables are treated as unset so a `KEY=` line in an env file does
+//! not clobber the baked value. Set-but-malformed variables are a hard
+//! [`Error::InvalidValue`] rather than a silent fallback — a typo in a
+//! deployment manifest should fail startup, not bind the wrong address.
+//!
+//! # Variables
+//!
+//! | Variable | Overrides | Format |
+//! |---|---|---|
+//! | [`ENV_INTERFACE`] | `ServerConfig::interface`, client interface | dotted IPv4 |
+//! | [`ENV_LOCAL_PORT`] | `ServerConfig::local_port` | decimal `u16` |
+//! | [`ENV_TTL_SECS`] | `ServerConfig::ttl` | decimal seconds |
+//! | [`ENV_ANNOUNCE`] | `ServerConfig::announce` | boolean |
+//! | [`ENV_DISABLED_SERVICES`] | [`EnvOverlay::service_enabled`] | comma-separated IDs |
+//!
+//! Booleans accept `1`/`0`, `true`/`false`, `yes`/`no` and `on`/`off`
+//! (case-insensitive). Service IDs accept decimal or `0x`-prefixed hex.
+//!
+//! These are *runtime* variables, read when the overlay is applied.
+//! They are distinct from the `SIMPLE_SOMEIP_MAX_*` *build-time*
+//! variables, which size internal capacities via `option_env!` and have
+//! no effect once the crate is compiled.
+//!
+//! # Configuration files and change notification
+//!
+//! [`FileConfig`] is the deployment file format (layer 2 above) and
+//! [`ConfigNotifier`] re-reads it at runtime, reporting the difference as
+//! a list of [`ConfigChange`]s. Running servers and clients apply the
+//! safe ones (TTL, offered-service set, static endpoints) with their
+//! `apply_config_changes` and reject the ones that need them rebuilt,
+//! such as those that rebind sockets
+//! ([`ConfigChange::requires_reconnect`]).
+//!
+//! [`EnvOverlay`]: crate::config::EnvOverlay
+//! [`EnvOverlay::apply_server`]: crate::config::EnvOverlay::apply_server
+//! [`EnvOverlay::apply_interface`]: crate::config::EnvOverlay::apply_interface
+//! [`EnvOverlay::service_enabled`]: crate::config::EnvOverlay::service_enabled
+//! [`Error::InvalidValue`]: crate::config::Error::InvalidValue
+//! [`ENV_INTERFACE`]: crate::config::ENV_INTERFACE
+//! [`ENV_LOCAL_PORT`]: crate::config::ENV_LOCAL_PORT
+//! [`ENV_TTL_SECS`]: crate::config::ENV_TTL_SECS
+//! [`ENV_ANNOUNCE`]: crate::config::ENV_ANNOUNCE
+//! [`ENV_DISABLED_SERVICES`]: crate::config::ENV_DISABLED_SERVICES
+//! [`FileConfig`]: crate::config::FileConfig
+//! [`ConfigNotifier`]: crate::config::ConfigNotifier
+//! [`ConfigChange`]: crate::config::ConfigChange
+//! [`ConfigChange::requires_reconnect`]: crate::config::ConfigChange::requires_reconnect
+
+mod file;
+mod reload;
+
+pub use file::{FileConfig, OfferedService, StaticEndpoint};
+pub use reload::{ApplyReport, ConfigChange, ConfigNotifier, diff};
+
+use core::net::Ipv4Addr;
+use std::string::String;
+
+/// Local interface address used for the unicast and SD sockets.
+pub const ENV_INTERFACE: &str = "SIMPLE_SOMEIP_INTERFACE";
+/// Unicast port the server binds and advertises in its offers.
+pub const ENV_LOCAL_PORT: &str = "SIMPLE_SOMEIP_LOCAL_PORT";
+/// SD offer TTL, in whole seconds.
+pub const ENV_TTL_SECS: &str = "SIMPLE_SOMEIP_TTL_SECS";
+/// Whether the server drives its own `OfferService` announcements.
+pub const ENV_ANNOUNCE: &str = "SIMPLE_SOMEIP_ANNOUNCE";
+/// Comma-separated service IDs the deployment wants switched off.
+pub const ENV_DISABLED_SERVICES: &str = "SIMPLE_SOMEIP_DISABLED_SERVICES";
+
+/// Errors produced while loading configuration or applying an
+/// environment overlay.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The configuration file could not be read.
+    #[error("failed to read configuration file: {0}")]
+    Io(std::io::ErrorKind),
+    /// A configuration-file line could not be parsed. `line` is
+    /// 1-based.
+    #[error("configuration file line {line}: {reason}")]
+    Parse {
+        /// 1-based line number of the offending line.
+        line: usize,
+        /// What was wrong with it.
+        reason: &'static str,
+    },
+    /// A variable was set to a value that does not parse as the
+    /// expected type.
+    #[error("invalid value {value:?} for {var}: expected {expected}")]
+    InvalidValue {
+        /// Name of the offending variable.
+        var: &'static str,
+        /// The raw value as read from the environment.
+        value: String,
+        /// Human-readable description of the accepted format.
+        expected: &'static str,
+    },
+}
+
+/// Lookup function used by [`EnvOverlay`]: maps a variable name to its
+/// value, or `None` when unset.
+pub type EnvLookup = fn(&str) -> Option<String>;
+
+fn process_env(var: &str) -> Option<String> {
+    std::env::var(var).ok()
+}
+
+/// Reads the `SIMPLE_SOMEIP_*` runtime variables and layers them over
+/// an existing configuration. See the [module docs](self) for the
+/// precedence order and accepted formats.
+///
+/// The lookup is pluggable so callers (and tests) can source values
+/// from somewhere other than the process environment — an env file
+/// parsed by the application, a map captured at startup, etc.
+#[derive(Debug, Clone, Copy)]
+pub struct EnvOverlay<L = EnvLookup> {
+    lookup: L,
+}
+
+impl EnvOverlay {
+    /// Overlay backed by the process environment (`std::env::var`).
+    #[must_use]
+    pub fn from_process_env() -> Self {
+        Self {
+            lookup: process_env,
+        }
+    }
+}
+
+impl Default for EnvOverlay {
+    fn default() -> Self {
+        Self::from_process_env()
+    }
+}
+
+impl<L> EnvOverlay<L>
+where
+    L: Fn(&str) -> Option<String>,
+{
+    /// Overlay backed by a caller-supplied lookup.
+    pub fn with_lookup(lookup: L) -> Self {
+        Self { lookup }
+    }
+
+    /// Fetch `var`, treating set-but-empty as unset.
+    fn raw(&self, var: &str) -> Option<String> {
+        (self.lookup)(var).filter(|v| !v.trim().is_empty())
+    }
+
+    /// [`ENV_INTERFACE`], if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidValue`] if the variable is not a dotted
+    /// IPv4 address.
+    pub fn interface(&self) -> Result<Option<Ipv4Addr>, Error> {
+        self.parse(ENV_INTERFACE, "a dotted IPv4 address", |v| v.parse().ok())
+    }
+
+    /// [`ENV_LOCAL_PORT`], if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidValue`] if the variable is not a decimal
+    /// `u16`.
+    pub fn local_port(&self) -> Result<Option<u16>, Error> {
+        self.parse(ENV_LOCAL_PORT, "a decimal port number", |v| v.parse().ok())
+    }
+
+    /// [`ENV_TTL_SECS`], if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidValue`] if the variable is not a decimal
+    /// `u32`.
+    pub fn ttl_secs(&self) -> Result<Option<u32>, Error> {
+        self.parse(ENV_TTL_SECS, "a decimal number of seconds", |v| {
+            v.parse().ok()
+        })
+    }
+
+    /// [`ENV_ANNOUNCE`], if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidValue`] if the variable is not a
+    /// recognised boolean spelling.
+    pub fn announce(&self) -> Result<Option<bool>, Error> {
+        self.parse(
+            ENV_ANNOUNCE,
+            "a boolean (1/0, true/false, yes/no, on/off)",
+            parse_bool,
+        )
+    }
+
+    /// Whether `service_id` is still enabled after applying
+    /// [`ENV_DISABLED_SERVICES`]. Services are enabled unless listed.
+    ///
+    /// Applications that offer or consume several services consult this
+    /// before constructing the corresponding `Server` / subscription, so
+    /// a deployment can switch individual services off without editing
+    /// the baked catalog.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidValue`] if any list element is not a
+    /// decimal or `0x`-prefixed hex `u16`.
+    pub fn service_enabled(&self, service_id: u16) -> Result<bool, Error> {
+        let Some(list) = self.raw(ENV_DISABLED_SERVICES) else {
+            return Ok(true);
+        };
+        for item in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let id = parse_u16(item).ok_or_else(|| Error::InvalidValue {
+                var: ENV_DISABLED_SERVICES,
+                value: list.clone(),
+                expected: "comma-separated decimal or 0x-prefixed service IDs",
+            })?;
+            if id == service_id {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Layer the interface override over `interface`. Intended for the
+    /// `Client` constructors, which take the interface directly.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::interface`].
+    pub fn apply_interface(&self, interface: Ipv4Addr) -> Result<Ipv4Addr, Error> {
+        Ok(self.interface()?.unwrap_or(interface))
+    }
+
+    /// Layer the overrides over a loaded [`FileConfig`]: scalar fields
+    /// are replaced when set, and offers for services switched off via
+    /// [`ENV_DISABLED_SERVICES`] are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Error::InvalidValue`] encountered; the file
+    /// configuration is consumed either way.
+    pub fn apply_file(&self, mut file: FileConfig) -> Result<FileConfig, Error> {
+        let interface = self.interface()?;
+        let local_port = self.local_port()?;
+        let ttl = self.ttl_secs()?;
+        let announce = self.announce()?;
+        file.interface = interface.or(file.interface);
+        file.local_port = local_port.or(file.local_port);
+        file.ttl_secs = ttl.or(file.ttl_secs);
+        file.announce = announce.or(file.announce);
+        let mut offers = std::vec::Vec::with_capacity(file.offers.len());
+        for offer in file.offers {
+            if self.service_enabled(offer.service_id)? {
+                offers.push(offer);
+            }
+        }
+        file.offers = offers;
+        Ok(file)
+    }
+
+    /// Layer every server-relevant override over `config`.
+    ///
+    /// All variables are parsed before any field is written, so an
+    /// `Err` never leaves a half-applied configuration behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`Error::InvalidValue`] encountered.
+    #[cfg(feature = "server")]
+    pub fn apply_server(
+        &self,
+        mut config: crate::server::ServerConfig,
+    ) -> Result<crate::server::ServerConfig, Error> {
+        let interface = self.interface()?;
+        let local_port = self.local_port()?;
+        let ttl = self.ttl_secs()?;
+        let announce = self.announce()?;
+        if let Some(interface) = interface {
+            config.interface = interface;
+        }
+        if let Some(local_port) = local_port {
+            config.local_port = local_port;
+        }
+        if let Some(ttl) = ttl {
+            config.ttl = ttl;
+        }
+        if let Some(announce) = announce {
+            config.announce = announce;
+        }
+        Ok(config)
+    }
+
+    fn parse<T>(
+        &self,
+        var: &'static str,
+        expected: &'static str,
+        f: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<Option<T>, Error> {
+        let Some(value) = self.raw(var) else {
+            return Ok(None);
+        };
+        match f(value.trim()) {
+            Some(v) => Ok(Some(v)),
+            None => Err(Error::InvalidValue {
+                var,
+                value,
+                expected,
+            }),
+        }
+    }
+}
+
+fn parse_bool(v: &str) -> Option<bool> {
+    match v.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_u16(v: &str) -> Option<u16> {
+    match v.strip_prefix("0x").or_else(|| v.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None => v.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    fn overlay(
+        pairs: &'static [(&'static str, &'static str)],
+    ) -> EnvOverlay<impl Fn(&str) -> Option<String>> {
+        EnvOverlay::with_lookup(move |var| {
+            pairs
+                .iter()
+                .find(|(k, _)| *k == var)
+                .map(|(_, v)| (*v).to_string())
+        })
+    }
+
+    #[test]
+    fn unset_and_empty_variables_leave_lower_layer_untouched() {
+        let ov = overlay(&[(ENV_INTERFACE, "  ")]);
+        assert_eq!(ov.interface().unwrap(), None);
+        assert_eq!(
+            ov.apply_interface(Ipv4Addr::LOCALHOST).unwrap(),
+            Ipv4Addr::LOCALHOST
+        );
+        assert_eq!(ov.local_port().unwrap(), None);
+        assert!(ov.service_enabled(0x1234).unwrap());
+    }
+
+    #[test]
+    fn interface_override_wins_over_code_value() {
+        let ov = overlay(&[(ENV_INTERFACE, "10.0.0.7")]);
+        assert_eq!(
+            ov.apply_interface(Ipv4Addr::LOCALHOST).unwrap(),
+            Ipv4Addr::new(10, 0, 0, 7)
+        );
+    }
+
+    #[test]
+    fn malformed_value_is_a_hard_error() {
+        let ov = overlay(&[(ENV_LOCAL_PORT, "70000")]);
+        let err = ov.local_port().unwrap_err();
+        assert!(matches!(
+            err,
+            Error::InvalidValue {
+                var: ENV_LOCAL_PORT,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn boolean_spellings() {
+        for (raw, want) in [("1", true), ("Off", false), ("YES", true), ("false", false)] {
+            assert_eq!(parse_bool(raw), Some(want), "{raw}");
+        }
+        assert_eq!(parse_bool("maybe"), None);
+    }
+
+    #[test]
+    fn disabled_services_accept_hex_and_decimal() {
+        let ov = overlay(&[(ENV_DISABLED_SERVICES, "0x5B, 4660")]);
+        assert!(!ov.service_enabled(0x5B).unwrap());
+        assert!(!ov.service_enabled(0x1234).unwrap());
+        assert!(ov.service_enabled(0x5C).unwrap());
+
+        let bad = overlay(&[(ENV_DISABLED_SERVICES, "0x5B,nope")]);
+        assert!(bad.service_enabled(0x5C).is_err());
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn apply_server_overrides_only_set_fields() {
+        use crate::server::ServerConfig;
+        let ov = overlay(&[
+            (ENV_INTERFACE, "192.168.7.2"),
+            (ENV_LOCAL_PORT, "30501"),
+            (ENV_ANNOUNCE, "off"),
+        ]);
+        let cfg = ov
+            .apply_server(ServerConfig::new(0x5B, 1).with_major_version(3))
+            .unwrap();
+        assert_eq!(cfg.interface, Ipv4Addr::new(192, 168, 7, 2));
+        assert_eq!(cfg.local_port, 30501);
+        assert!(!cfg.announce);
+        assert_eq!(cfg.ttl, 3, "unset TTL keeps the code value");
+        assert_eq!(cfg.major_version, 3);
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn apply_server_is_all_or_nothing() {
+        use crate::server::ServerConfig;
+        let ov = overlay(&[(ENV_INTERFACE, "192.168.7.2"), (ENV_TTL_SECS, "-1")]);
+        assert!(ov.apply_server(ServerConfig::new(0x5B, 1)).is_err());
+    }
+}
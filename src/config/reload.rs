@@ -0,0 +1,436 @@
+//! Configuration hot reload: re-read a [`FileConfig`], report what
+//! changed, and apply the safe part to running components.
+//!
+//! [`ConfigNotifier::next_changes`] waits for the file to change and
+//! returns the [`ConfigChange`]s. Running `Server`s and `Client`s take
+//! them with `apply_config_changes`, which applies what can change in
+//! place and reports the rest in an [`ApplyReport`]:
+//!
+//! | Change | Applied by |
+//! |---|---|
+//! | [`ConfigChange::TtlChanged`] | `Server::apply_config_changes` (`Server::set_ttl` / `Server::reset_ttl`) |
+//! | [`ConfigChange::CyclicOfferDelayChanged`] | `Server::apply_config_changes` (`Server::set_cyclic_offer_delay` / `Server::reset_cyclic_offer_delay`) |
+//! | [`ConfigChange::OfferAdded`] / [`ConfigChange::OfferRemoved`] | `Server::apply_config_changes` (`Server::add_service` / `Server::remove_service`) |
+//! | [`ConfigChange::StaticEndpointAdded`] / [`ConfigChange::StaticEndpointRemoved`] | `Client::apply_config_changes` (`Client::add_static_endpoint` / `Client::remove_endpoint`) |
+//!
+//! Interface, port and `announce` changes, changes to the repetition
+//! phase and initial delay of the offers, which only apply at startup,
+//! and removing the service a `Server` was built for, are rejected: the application has to rebuild
+//! the affected client/server for them to take effect. Interface and
+//! port changes are flagged with [`ConfigChange::requires_reconnect`].
+
+use core::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::string::String;
+use std::vec::Vec;
+
+use super::{Error, FileConfig, OfferedService, StaticEndpoint};
+use crate::Timer;
+
+/// One difference between two [`FileConfig`] generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// `interface` changed. Requires reconnection.
+    InterfaceChanged {
+        /// Previous value.
+        from: Option<Ipv4Addr>,
+        /// New value.
+        to: Option<Ipv4Addr>,
+    },
+    /// `local_port` changed. Requires reconnection.
+    LocalPortChanged {
+        /// Previous value.
+        from: Option<u16>,
+        /// New value.
+        to: Option<u16>,
+    },
+    /// `ttl_secs` changed. `None` means "back to the code default".
+    TtlChanged {
+        /// Previous value.
+        from: Option<u32>,
+        /// New value.
+        to: Option<u32>,
+    },
+    /// `announce` changed.
+    AnnounceChanged {
+        /// Previous value.
+        from: Option<bool>,
+        /// New value.
+        to: Option<bool>,
+    },
+    /// `cyclic_offer_delay_ms` changed. `None` means "back to the code
+    /// default".
+    CyclicOfferDelayChanged {
+        /// Previous value, in milliseconds.
+        from: Option<u32>,
+        /// New value, in milliseconds.
+        to: Option<u32>,
+    },
+    /// `repetitions_max` changed.
+    RepetitionsMaxChanged {
+        /// Previous value.
+        from: Option<u32>,
+        /// New value.
+        to: Option<u32>,
+    },
+    /// `repetitions_base_delay_ms` changed.
+    RepetitionsBaseDelayChanged {
+        /// Previous value, in milliseconds.
+        from: Option<u32>,
+        /// New value, in milliseconds.
+        to: Option<u32>,
+    },
+    /// `initial_delay_min_ms` changed.
+    InitialDelayMinChanged {
+        /// Previous value, in milliseconds.
+        from: Option<u32>,
+        /// New value, in milliseconds.
+        to: Option<u32>,
+    },
+    /// `initial_delay_max_ms` changed.
+    InitialDelayMaxChanged {
+        /// Previous value, in milliseconds.
+        from: Option<u32>,
+        /// New value, in milliseconds.
+        to: Option<u32>,
+    },
+    /// A new `offer` line appeared.
+    OfferAdded(OfferedService),
+    /// An `offer` line disappeared.
+    OfferRemoved(OfferedService),
+    /// A new `static_endpoint` line appeared.
+    StaticEndpointAdded(StaticEndpoint),
+    /// A `static_endpoint` line disappeared.
+    StaticEndpointRemoved(StaticEndpoint),
+}
+
+impl ConfigChange {
+    /// `true` if applying this change means rebinding sockets, i.e.
+    /// rebuilding the affected `Client` / `Server`. Everything else can
+    /// be applied to running components.
+    #[must_use]
+    pub fn requires_reconnect(&self) -> bool {
+        matches!(
+            self,
+            Self::InterfaceChanged { .. } | Self::LocalPortChanged { .. }
+        )
+    }
+}
+
+/// What a running `Server` or `Client` did with the [`ConfigChange`]s
+/// handed to its `apply_config_changes`. Changes that concern another
+/// kind of component (a static endpoint for a server, a TTL for a
+/// client) are in neither list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApplyReport {
+    /// Changes now in effect.
+    pub applied: Vec<ConfigChange>,
+    /// Changes left unapplied because the component has to be rebuilt
+    /// for them.
+    pub rejected: Vec<ConfigChange>,
+}
+
+/// Compute the changes that turn `old` into `new`. Removals are listed
+/// before additions so a caller applying them in order never holds both
+/// generations of an entry at once.
+#[must_use]
+pub fn diff(old: &FileConfig, new: &FileConfig) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    if old.interface != new.interface {
+        changes.push(ConfigChange::InterfaceChanged {
+            from: old.interface,
+            to: new.interface,
+        });
+    }
+    if old.local_port != new.local_port {
+        changes.push(ConfigChange::LocalPortChanged {
+            from: old.local_port,
+            to: new.local_port,
+        });
+    }
+    if old.ttl_secs != new.ttl_secs {
+        changes.push(ConfigChange::TtlChanged {
+            from: old.ttl_secs,
+            to: new.ttl_secs,
+        });
+    }
+    if old.announce != new.announce {
+        changes.push(ConfigChange::AnnounceChanged {
+            from: old.announce,
+            to: new.announce,
+        });
+    }
+    if old.cyclic_offer_delay_ms != new.cyclic_offer_delay_ms {
+        changes.push(ConfigChange::CyclicOfferDelayChanged {
+            from: old.cyclic_offer_delay_ms,
+            to: new.cyclic_offer_delay_ms,
+        });
+    }
+    if old.repetitions_max != new.repetitions_max {
+        changes.push(ConfigChange::RepetitionsMaxChanged {
+            from: old.repetitions_max,
+            to: new.repetitions_max,
+        });
+    }
+    if old.repetitions_base_delay_ms != new.repetitions_base_delay_ms {
+        changes.push(ConfigChange::RepetitionsBaseDelayChanged {
+            from: old.repetitions_base_delay_ms,
+            to: new.repetitions_base_delay_ms,
+        });
+    }
+    if old.initial_delay_min_ms != new.initial_delay_min_ms {
+        changes.push(ConfigChange::InitialDelayMinChanged {
+            from: old.initial_delay_min_ms,
+            to: new.initial_delay_min_ms,
+        });
+    }
+    if old.initial_delay_max_ms != new.initial_delay_max_ms {
+        changes.push(ConfigChange::InitialDelayMaxChanged {
+            from: old.initial_delay_max_ms,
+            to: new.initial_delay_max_ms,
+        });
+    }
+    for offer in old.offers.iter().filter(|o| !new.offers.contains(o)) {
+        changes.push(ConfigChange::OfferRemoved(*offer));
+    }
+    for ep in old
+        .static_endpoints
+        .iter()
+        .filter(|e| !new.static_endpoints.contains(e))
+    {
+        changes.push(ConfigChange::StaticEndpointRemoved(*ep));
+    }
+    for offer in new.offers.iter().filter(|o| !old.offers.contains(o)) {
+        changes.push(ConfigChange::OfferAdded(*offer));
+    }
+    for ep in new
+        .static_endpoints
+        .iter()
+        .filter(|e| !old.static_endpoints.contains(e))
+    {
+        changes.push(ConfigChange::StaticEndpointAdded(*ep));
+    }
+    changes
+}
+
+/// Re-reads a configuration file and reports changes against the last
+/// successfully parsed generation, for the running components'
+/// `apply_config_changes` (see the [module docs](crate::config)).
+///
+/// A file that fails to read or parse never replaces the current
+/// generation: [`Self::poll`] returns the error and the next poll
+/// diffs against the last good configuration, so a half-written file
+/// observed mid-save is harmless.
+#[derive(Debug)]
+pub struct ConfigNotifier {
+    path: PathBuf,
+    last_text: String,
+    current: FileConfig,
+}
+
+impl ConfigNotifier {
+    /// Load the initial generation from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`FileConfig::load`]; a notifier needs a valid starting
+    /// point.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let last_text = std::fs::read_to_string(&path).map_err(|e| Error::Io(e.kind()))?;
+        let current = FileConfig::parse(&last_text)?;
+        Ok(Self {
+            path,
+            last_text,
+            current,
+        })
+    }
+
+    /// The last successfully parsed generation.
+    #[must_use]
+    pub fn current(&self) -> &FileConfig {
+        &self.current
+    }
+
+    /// Re-read the file. Returns the changes since the previous
+    /// generation (empty if the file is unchanged) and adopts the new
+    /// generation.
+    ///
+    /// The whole file is compared rather than its modification time:
+    /// configuration files are small, and mtime granularity on some
+    /// filesystems is coarse enough to hide two saves in quick
+    /// succession.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Io`] / [`Error::Parse`] without changing the
+    /// current generation.
+    pub fn poll(&mut self) -> Result<Vec<ConfigChange>, Error> {
+        let text = std::fs::read_to_string(&self.path).map_err(|e| Error::Io(e.kind()))?;
+        if text == self.last_text {
+            return Ok(Vec::new());
+        }
+        let next = FileConfig::parse(&text)?;
+        let changes = diff(&self.current, &next);
+        self.current = next;
+        self.last_text = text;
+        Ok(changes)
+    }
+
+    /// Poll every `interval` until the file changes, returning the
+    /// changes against the previous generation, which it adopts. Read and
+    /// parse failures are logged and retried on the next tick.
+    ///
+    /// Executor-agnostic: drive it with any [`Timer`] (e.g.
+    /// `TokioTimer`) alongside the client/server run-futures, handing
+    /// each result to the components' `apply_config_changes`.
+    pub async fn next_changes<Tm: Timer>(
+        &mut self,
+        timer: &Tm,
+        interval: core::time::Duration,
+    ) -> Vec<ConfigChange> {
+        loop {
+            timer.sleep(interval).await;
+            match self.poll() {
+                Ok(changes) if changes.is_empty() => {}
+                Ok(changes) => {
+                    crate::log::info!(
+                        "Configuration {} reloaded: {} change(s)",
+                        self.path.display(),
+                        changes.len()
+                    );
+                    return changes;
+                }
+                Err(e) => {
+                    crate::log::warn!(
+                        "Configuration {} not reloaded, keeping previous: {}",
+                        self.path.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Poll every `interval` forever, invoking `on_change` with the new
+    /// generation and its changes whenever the file changes, as
+    /// [`Self::next_changes`] does.
+    pub async fn watch<Tm, F>(
+        mut self,
+        timer: &Tm,
+        interval: core::time::Duration,
+        mut on_change: F,
+    ) where
+        Tm: Timer,
+        F: FnMut(&FileConfig, &[ConfigChange]),
+    {
+        loop {
+            let changes = self.next_changes(timer, interval).await;
+            on_change(&self.current, &changes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::SocketAddrV4;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(std::format!(
+            "simple-someip-{}-{}.conf",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn diff_classifies_reconnect_changes() {
+        let old = FileConfig::parse("interface = 10.0.0.1\nttl_secs = 3\noffer = 1/1\n").unwrap();
+        let new = FileConfig::parse(
+            "interface = 10.0.0.2\nttl_secs = 5\noffer = 2/1\n\
+             static_endpoint = 3/1@10.0.0.9:30500\n",
+        )
+        .unwrap();
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes,
+            [
+                ConfigChange::InterfaceChanged {
+                    from: Some(Ipv4Addr::new(10, 0, 0, 1)),
+                    to: Some(Ipv4Addr::new(10, 0, 0, 2)),
+                },
+                ConfigChange::TtlChanged {
+                    from: Some(3),
+                    to: Some(5)
+                },
+                ConfigChange::OfferRemoved(OfferedService {
+                    service_id: 1,
+                    instance_id: 1
+                }),
+                ConfigChange::OfferAdded(OfferedService {
+                    service_id: 2,
+                    instance_id: 1
+                }),
+                ConfigChange::StaticEndpointAdded(StaticEndpoint {
+                    service_id: 3,
+                    instance_id: 1,
+                    addr: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 9), 30500),
+                }),
+            ]
+        );
+        let reconnect: Vec<_> = changes
+            .iter()
+            .map(ConfigChange::requires_reconnect)
+            .collect();
+        assert_eq!(reconnect, [true, false, false, false, false]);
+    }
+
+    #[test]
+    fn diff_reports_sd_timing_changes() {
+        let old = FileConfig::parse("cyclic_offer_delay_ms = 1000\nrepetitions_max = 3\n").unwrap();
+        let new = FileConfig::parse(
+            "cyclic_offer_delay_ms = 250\nrepetitions_max = 3\n\
+             initial_delay_max_ms = 20\n",
+        )
+        .unwrap();
+        let changes = diff(&old, &new);
+        assert_eq!(
+            changes,
+            [
+                ConfigChange::CyclicOfferDelayChanged {
+                    from: Some(1000),
+                    to: Some(250)
+                },
+                ConfigChange::InitialDelayMaxChanged {
+                    from: None,
+                    to: Some(20)
+                },
+            ]
+        );
+        assert!(!changes.iter().any(ConfigChange::requires_reconnect));
+    }
+
+    #[test]
+    fn notifier_keeps_last_good_generation_on_parse_error() {
+        let path = temp_path("notifier");
+        std::fs::write(&path, "ttl_secs = 3\n").unwrap();
+        let mut notifier = ConfigNotifier::new(&path).unwrap();
+        assert!(notifier.poll().unwrap().is_empty(), "unchanged file");
+
+        std::fs::write(&path, "ttl_secs = oops\n").unwrap();
+        assert!(notifier.poll().is_err());
+        assert_eq!(notifier.current().ttl_secs, Some(3));
+
+        std::fs::write(&path, "ttl_secs = 7\n").unwrap();
+        assert_eq!(
+            notifier.poll().unwrap(),
+            [ConfigChange::TtlChanged {
+                from: Some(3),
+                to: Some(7)
+            }]
+        );
+        assert_eq!(notifier.current().ttl_secs, Some(7));
+        let _ = std::fs::remove_file(&path);
+    }
+}
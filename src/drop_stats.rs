@@ -0,0 +1,72 @@
+/// Datagrams the OS dropped because a socket's receive queue was full,
+/// summed over the sockets of each kind. Counts start when a socket is
+/// bound, so they restart after a rebind.
+///
+/// A kind is `None` when none of its sockets can report a count; see
+/// [`TransportSocket::dropped_datagrams`](crate::transport::TransportSocket::dropped_datagrams).
+/// Drops here mean the receiver fell behind; datagrams missing without
+/// them were lost on the way.
+///
+/// A client also counts the messages its sockets dropped themselves
+/// under a `client::OverflowPolicy` other than `Block`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DropStats {
+    /// Service discovery sockets.
+    pub discovery: Option<u64>,
+    /// Unicast sockets, carrying requests, responses and events.
+    pub unicast: Option<u64>,
+    /// Received messages a client's sockets dropped because the channel
+    /// to its run loop was full. Always 0 for a server.
+    pub overflowed: u64,
+}
+
+impl DropStats {
+    /// Datagrams dropped on all sockets that report a count, and
+    /// messages dropped on overflow.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.discovery.unwrap_or(0) + self.unicast.unwrap_or(0) + self.overflowed
+    }
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl DropStats {
+    /// Add a discovery socket's count.
+    pub(crate) fn add_discovery(&mut self, dropped: Option<u64>) {
+        Self::add(&mut self.discovery, dropped);
+    }
+
+    /// Add a unicast socket's count.
+    pub(crate) fn add_unicast(&mut self, dropped: Option<u64>) {
+        Self::add(&mut self.unicast, dropped);
+    }
+
+    fn add(sum: &mut Option<u64>, dropped: Option<u64>) {
+        if let Some(dropped) = dropped {
+            *sum = Some(sum.unwrap_or(0) + dropped);
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "client", feature = "server")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sockets_without_a_count_leave_their_kind_unknown() {
+        let mut stats = DropStats::default();
+        stats.add_discovery(None);
+        stats.add_unicast(Some(3));
+        stats.add_unicast(None);
+        stats.add_unicast(Some(4));
+        assert_eq!(
+            stats,
+            DropStats {
+                discovery: None,
+                unicast: Some(7),
+                overflowed: 0,
+            }
+        );
+        assert_eq!(stats.total(), 7);
+    }
+}
@@ -8,6 +8,11 @@ pub struct Profile4Config {
     /// Maximum allowed counter delta before reporting `WrongSequence`.
     /// A delta of 1 means consecutive messages, delta > 1 means some lost.
     pub max_delta_counter: u16,
+    /// Position of the E2E header in the protected data, in bytes (the
+    /// AUTOSAR `Offset` parameter counts bits). The `offset` data bytes
+    /// before the header stay in front of it and are covered by the CRC.
+    /// Defaults to `0` (header first); set via [`Self::with_offset`].
+    pub offset: u16,
 }
 
 impl Profile4Config {
@@ -21,8 +26,17 @@ impl Profile4Config {
         Self {
             data_id,
             max_delta_counter,
+            offset: 0,
         }
     }
+
+    /// Place the E2E header `offset` bytes into the protected data. See
+    /// [`Self::offset`].
+    #[must_use]
+    pub fn with_offset(mut self, offset: u16) -> Self {
+        self.offset = offset;
+        self
+    }
 }
 
 /// Configuration for E2E Profile 5.
@@ -35,6 +49,9 @@ pub struct Profile5Config {
     pub data_length: u16,
     /// Maximum allowed counter delta before reporting `WrongSequence`.
     pub max_delta_counter: u8,
+    /// Position of the E2E header in the protected data, in bytes. See
+    /// [`Profile4Config::offset`].
+    pub offset: u16,
 }
 
 impl Profile5Config {
@@ -50,6 +67,15 @@ impl Profile5Config {
             data_id,
             data_length,
             max_delta_counter,
+            offset: 0,
         }
     }
+
+    /// Place the E2E header `offset` bytes into the protected data. See
+    /// [`Self::offset`].
+    #[must_use]
+    pub fn with_offset(mut self, offset: u16) -> Self {
+        self.offset = offset;
+        self
+    }
 }
@@ -12,11 +12,22 @@ const CRC16_CCITT: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_3740);
 
 /// Compute CRC-32P4 for Profile 4.
 ///
-/// The CRC is computed over: Length (2) + Counter (2) + `DataID` (4) + Payload
+/// The CRC is computed over: Prefix + Length (2) + Counter (2) +
+/// `DataID` (4) + Payload, where `prefix` is the data in front of a header
+/// placed at an offset (empty otherwise).
 /// Note: CRC field itself is not included in the calculation.
-pub fn compute_crc32_p4(length: u16, counter: u16, data_id: u32, payload: &[u8]) -> u32 {
+pub fn compute_crc32_p4(
+    prefix: &[u8],
+    length: u16,
+    counter: u16,
+    data_id: u32,
+    payload: &[u8],
+) -> u32 {
     let mut digest = CRC32_P4.digest();
 
+    // Data in front of the header
+    digest.update(prefix);
+
     // Length (big-endian)
     digest.update(&length.to_be_bytes());
 
@@ -36,11 +47,13 @@ pub fn compute_crc32_p4(length: u16, counter: u16, data_id: u32, payload: &[u8])
 ///
 /// Per E2E Profile 5, the CRC is computed over all data bytes except the
 /// CRC field itself, plus the `DataID`. Specifically:
-/// - Counter (1 byte) + Payload (N bytes) + `DataID` (2 bytes, little-endian)
+/// - Prefix + Counter (1 byte) + Payload (N bytes) + `DataID` (2 bytes,
+///   little-endian), where `prefix` is the data in front of a header placed
+///   at an offset (empty otherwise)
 ///
 /// Note: CRC field itself is not included in the calculation.
 /// Note: `DataLength` is NOT included in the CRC calculation.
-pub fn compute_crc16_p5(data_id: u16, counter: u8, payload: &[u8]) -> u16 {
+pub fn compute_crc16_p5(data_id: u16, counter: u8, prefix: &[u8], payload: &[u8]) -> u16 {
     crate::log::trace!(
         "CRC-16 Profile5: data_id=0x{:04X}, counter={}, payload_len={}, payload={:02X?}",
         data_id,
@@ -51,6 +64,9 @@ pub fn compute_crc16_p5(data_id: u16, counter: u8, payload: &[u8]) -> u16 {
 
     let mut digest = CRC16_CCITT.digest();
 
+    // Data in front of the header
+    digest.update(prefix);
+
     // Counter (single byte)
     digest.update(&[counter]);
 
@@ -74,12 +90,14 @@ pub fn compute_crc16_p5(data_id: u16, counter: u8, payload: &[u8]) -> u16 {
 /// Compute CRC-16-CCITT for Profile 5 with SOME/IP upper-header prefix.
 ///
 /// The 8-byte upper header (UPPER-HEADER-BITS-TO-SHIFT = 64 bits) is prepended
-/// to the CRC input before Counter + Payload + `DataID`.
+/// to the CRC input before Prefix + Counter + Payload + `DataID`.
 ///
-/// CRC input order: `upper_header(8)` + Counter(1) + Payload(N) + DataID(2 LE)
+/// CRC input order: `upper_header(8)` + Prefix + Counter(1) + Payload(N) +
+/// DataID(2 LE)
 pub fn compute_crc16_p5_with_header(
     data_id: u16,
     counter: u8,
+    prefix: &[u8],
     payload: &[u8],
     upper_header: [u8; 8],
 ) -> u16 {
@@ -94,6 +112,7 @@ pub fn compute_crc16_p5_with_header(
 
     let mut digest = CRC16_CCITT.digest();
     digest.update(&upper_header);
+    digest.update(prefix);
     digest.update(&[counter]);
     digest.update(payload);
     digest.update(&data_id.to_le_bytes());
@@ -115,10 +134,10 @@ mod tests {
     #[test]
     fn test_crc32_p4_basic() {
         // Basic smoke test - verify CRC changes with different inputs
-        let crc1 = compute_crc32_p4(10, 0, 0x1234_5678, b"test");
-        let crc2 = compute_crc32_p4(10, 1, 0x1234_5678, b"test");
-        let crc3 = compute_crc32_p4(10, 0, 0x1234_5679, b"test");
-        let crc4 = compute_crc32_p4(10, 0, 0x1234_5678, b"Test");
+        let crc1 = compute_crc32_p4(&[], 10, 0, 0x1234_5678, b"test");
+        let crc2 = compute_crc32_p4(&[], 10, 1, 0x1234_5678, b"test");
+        let crc3 = compute_crc32_p4(&[], 10, 0, 0x1234_5679, b"test");
+        let crc4 = compute_crc32_p4(&[], 10, 0, 0x1234_5678, b"Test");
 
         assert_ne!(crc1, crc2, "Different counter should produce different CRC");
         assert_ne!(crc1, crc3, "Different data_id should produce different CRC");
@@ -128,10 +147,10 @@ mod tests {
     #[test]
     fn test_crc16_p5_basic() {
         // Basic smoke test - verify CRC changes with different inputs
-        let crc1 = compute_crc16_p5(0x1234, 0, b"test");
-        let crc2 = compute_crc16_p5(0x1234, 1, b"test");
-        let crc3 = compute_crc16_p5(0x1235, 0, b"test");
-        let crc4 = compute_crc16_p5(0x1234, 0, b"Test");
+        let crc1 = compute_crc16_p5(0x1234, 0, &[], b"test");
+        let crc2 = compute_crc16_p5(0x1234, 1, &[], b"test");
+        let crc3 = compute_crc16_p5(0x1235, 0, &[], b"test");
+        let crc4 = compute_crc16_p5(0x1234, 0, &[], b"Test");
 
         assert_ne!(crc1, crc2, "Different counter should produce different CRC");
         assert_ne!(crc1, crc3, "Different data_id should produce different CRC");
@@ -141,30 +160,30 @@ mod tests {
     #[test]
     fn test_crc32_p4_deterministic() {
         // Same inputs should always produce same output
-        let crc1 = compute_crc32_p4(20, 5, 0xABCD_EF01, b"payload data");
-        let crc2 = compute_crc32_p4(20, 5, 0xABCD_EF01, b"payload data");
+        let crc1 = compute_crc32_p4(&[], 20, 5, 0xABCD_EF01, b"payload data");
+        let crc2 = compute_crc32_p4(&[], 20, 5, 0xABCD_EF01, b"payload data");
         assert_eq!(crc1, crc2);
     }
 
     #[test]
     fn test_crc16_p5_deterministic() {
         // Same inputs should always produce same output
-        let crc1 = compute_crc16_p5(0xABCD, 5, b"payload data");
-        let crc2 = compute_crc16_p5(0xABCD, 5, b"payload data");
+        let crc1 = compute_crc16_p5(0xABCD, 5, &[], b"payload data");
+        let crc2 = compute_crc16_p5(0xABCD, 5, &[], b"payload data");
         assert_eq!(crc1, crc2);
     }
 
     #[test]
     fn test_crc32_p4_empty_payload() {
         // Should work with empty payload
-        let crc = compute_crc32_p4(8, 0, 0x1234_5678, b"");
+        let crc = compute_crc32_p4(&[], 8, 0, 0x1234_5678, b"");
         assert_ne!(crc, 0); // CRC should be non-trivial even for empty payload
     }
 
     #[test]
     fn test_crc16_p5_empty_payload() {
         // Should work with empty payload
-        let crc = compute_crc16_p5(0x1234, 0, b"");
+        let crc = compute_crc16_p5(0x1234, 0, &[], b"");
         assert_ne!(crc, 0); // CRC should be non-trivial even for empty payload
     }
 
@@ -172,8 +191,8 @@ mod tests {
     fn test_crc16_p5_with_header_nonzero_header_changes_crc() {
         // A non-zero upper header must produce a different CRC than the headerless variant
         let header = [0x00, 0x01, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00];
-        let crc_no_header = compute_crc16_p5(0x1234, 0, b"test");
-        let crc_with_header = compute_crc16_p5_with_header(0x1234, 0, b"test", header);
+        let crc_no_header = compute_crc16_p5(0x1234, 0, &[], b"test");
+        let crc_with_header = compute_crc16_p5_with_header(0x1234, 0, &[], b"test", header);
         assert_ne!(
             crc_no_header, crc_with_header,
             "Non-zero upper_header should change CRC vs headerless path"
@@ -184,8 +203,8 @@ mod tests {
     fn test_crc16_p5_with_header_different_headers_differ() {
         let header_a = [0x00, 0x01, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00];
         let header_b = [0x00, 0x02, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00]; // single byte changed
-        let crc_a = compute_crc16_p5_with_header(0x1234, 0, b"test", header_a);
-        let crc_b = compute_crc16_p5_with_header(0x1234, 0, b"test", header_b);
+        let crc_a = compute_crc16_p5_with_header(0x1234, 0, &[], b"test", header_a);
+        let crc_b = compute_crc16_p5_with_header(0x1234, 0, &[], b"test", header_b);
         assert_ne!(
             crc_a, crc_b,
             "Different upper_header should produce different CRC"
@@ -195,19 +214,19 @@ mod tests {
     #[test]
     fn test_crc16_p5_with_header_deterministic() {
         let header = [0x00, 0x01, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00];
-        let crc1 = compute_crc16_p5_with_header(0x1234, 0, b"test", header);
-        let crc2 = compute_crc16_p5_with_header(0x1234, 0, b"test", header);
+        let crc1 = compute_crc16_p5_with_header(0x1234, 0, &[], b"test", header);
+        let crc2 = compute_crc16_p5_with_header(0x1234, 0, &[], b"test", header);
         assert_eq!(crc1, crc2, "Same inputs should always produce same CRC");
     }
 
     #[test]
     fn test_crc16_p5_with_header_each_field_matters() {
         let header = [0x00, 0x01, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00];
-        let baseline = compute_crc16_p5_with_header(0x1234, 0, b"test", header);
+        let baseline = compute_crc16_p5_with_header(0x1234, 0, &[], b"test", header);
 
-        let crc_diff_data_id = compute_crc16_p5_with_header(0x1235, 0, b"test", header);
-        let crc_diff_counter = compute_crc16_p5_with_header(0x1234, 1, b"test", header);
-        let crc_diff_payload = compute_crc16_p5_with_header(0x1234, 0, b"Test", header);
+        let crc_diff_data_id = compute_crc16_p5_with_header(0x1235, 0, &[], b"test", header);
+        let crc_diff_counter = compute_crc16_p5_with_header(0x1234, 1, &[], b"test", header);
+        let crc_diff_payload = compute_crc16_p5_with_header(0x1234, 0, &[], b"Test", header);
 
         assert_ne!(
             baseline, crc_diff_data_id,
@@ -8,11 +8,12 @@ use super::{E2ECheckResult, E2ECheckStatus};
 
 /// Check E2E Profile 4 protected data.
 ///
-/// Validates the 12-byte header:
+/// Validates the 12-byte header at [`Profile4Config::offset`]:
 /// - Length (2 bytes): Verifies against actual message length
 /// - Counter (2 bytes): Checks sequence continuity
 /// - `DataID` (4 bytes): Must match configuration
-/// - CRC (4 bytes): Verified against computed CRC-32P4
+/// - CRC (4 bytes): Verified against CRC-32P4 computed over every other
+///   byte of the message
 ///
 /// # Arguments
 /// * `config` - Profile 4 configuration
@@ -21,22 +22,25 @@ use super::{E2ECheckResult, E2ECheckStatus};
 ///
 /// # Returns
 /// An `E2ECheckResult` containing the status, counter, and extracted payload.
+/// With a nonzero offset the header cannot be cut out of the borrowed
+/// message, so the payload is the whole message, header included.
 pub fn check_profile4<'a>(
     config: &Profile4Config,
     state: &mut Profile4State,
     protected: &'a [u8],
 ) -> E2ECheckResult<'a> {
     // Check minimum length
-    if protected.len() < PROFILE4_HEADER_SIZE {
+    let offset = usize::from(config.offset);
+    if protected.len() < offset + PROFILE4_HEADER_SIZE {
         return E2ECheckResult::error(E2ECheckStatus::BadArgument);
     }
 
     // Parse header
-    let length = u16::from_be_bytes([protected[0], protected[1]]);
-    let counter = u16::from_be_bytes([protected[2], protected[3]]);
-    let data_id = u32::from_be_bytes([protected[4], protected[5], protected[6], protected[7]]);
-    let received_crc =
-        u32::from_be_bytes([protected[8], protected[9], protected[10], protected[11]]);
+    let (prefix, header, rest) = split_header(protected, offset, PROFILE4_HEADER_SIZE);
+    let length = u16::from_be_bytes([header[0], header[1]]);
+    let counter = u16::from_be_bytes([header[2], header[3]]);
+    let data_id = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let received_crc = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
 
     // Verify length field matches actual message length
     if length as usize != protected.len() {
@@ -48,11 +52,8 @@ pub fn check_profile4<'a>(
         return E2ECheckResult::error(E2ECheckStatus::BadArgument);
     }
 
-    // Extract payload
-    let payload = &protected[PROFILE4_HEADER_SIZE..];
-
     // Compute and verify CRC
-    let computed_crc = compute_crc32_p4(length, counter, data_id, payload);
+    let computed_crc = compute_crc32_p4(prefix, length, counter, data_id, rest);
     if computed_crc != received_crc {
         return E2ECheckResult::error(E2ECheckStatus::CrcError);
     }
@@ -63,12 +64,13 @@ pub fn check_profile4<'a>(
     // Update state
     state.last_counter = Some(counter);
 
+    let payload = if offset == 0 { rest } else { protected };
     E2ECheckResult::success(status, u32::from(counter), payload)
 }
 
 /// Check E2E Profile 5 protected data.
 ///
-/// Validates the 3-byte header:
+/// Validates the 3-byte header at [`Profile5Config::offset`]:
 /// - CRC (2 bytes, little-endian): Verified against computed CRC-16-CCITT
 /// - Counter (1 byte): Checks sequence continuity
 ///
@@ -79,13 +81,16 @@ pub fn check_profile4<'a>(
 ///
 /// # Returns
 /// An `E2ECheckResult` containing the status, counter, and extracted payload.
+/// With a nonzero offset the payload is the whole message, header included,
+/// as for [`check_profile4`].
 pub fn check_profile5<'a>(
     config: &Profile5Config,
     state: &mut Profile5State,
     protected: &'a [u8],
 ) -> E2ECheckResult<'a> {
     // Check minimum length
-    if protected.len() < PROFILE5_HEADER_SIZE {
+    let offset = usize::from(config.offset);
+    if protected.len() < offset + PROFILE5_HEADER_SIZE {
         return E2ECheckResult::error(E2ECheckStatus::BadArgument);
     }
 
@@ -102,14 +107,12 @@ pub fn check_profile5<'a>(
     }
 
     // Parse header: CRC (2, little-endian) + Counter (1)
-    let received_crc = u16::from_le_bytes([protected[0], protected[1]]);
-    let counter = protected[2];
-
-    // Extract payload
-    let payload = &protected[PROFILE5_HEADER_SIZE..];
+    let (prefix, header, rest) = split_header(protected, offset, PROFILE5_HEADER_SIZE);
+    let received_crc = u16::from_le_bytes([header[0], header[1]]);
+    let counter = header[2];
 
     // Compute and verify CRC
-    let computed_crc = compute_crc16_p5(config.data_id, counter, payload);
+    let computed_crc = compute_crc16_p5(config.data_id, counter, prefix, rest);
     if computed_crc != received_crc {
         return E2ECheckResult::error(E2ECheckStatus::CrcError);
     }
@@ -120,14 +123,16 @@ pub fn check_profile5<'a>(
     // Update state
     state.last_counter = Some(counter);
 
+    let payload = if offset == 0 { rest } else { protected };
     E2ECheckResult::success(status, u32::from(counter), payload)
 }
 
 /// Check E2E Profile 5 protected data with SOME/IP upper-header in the CRC.
 ///
-/// Validates the 3-byte header:
+/// Validates the 3-byte header at [`Profile5Config::offset`]:
 /// - CRC (2 bytes, little-endian): Verified against CRC-16-CCITT computed over
-///   `upper_header(8) + Counter(1) + Payload(N) + DataID(2 LE)`
+///   `upper_header(8) + Prefix + Counter(1) + Payload(N) + DataID(2 LE)`,
+///   `Prefix` being the bytes before the header
 /// - Counter (1 byte): Checks sequence continuity
 ///
 /// The 8-byte `upper_header` (UPPER-HEADER-BITS-TO-SHIFT = 64 bits) is the
@@ -142,14 +147,16 @@ pub fn check_profile5<'a>(
 /// * `upper_header` - 8-byte SOME/IP upper header included in the CRC
 ///
 /// # Returns
-/// An [`E2ECheckResult`] containing the status, counter, and extracted payload.
+/// An [`E2ECheckResult`] containing the status, counter, and extracted payload
+/// (the whole message with a nonzero offset, as for [`check_profile4`]).
 pub fn check_profile5_with_header<'a>(
     config: &Profile5Config,
     state: &mut Profile5State,
     protected: &'a [u8],
     upper_header: [u8; 8],
 ) -> E2ECheckResult<'a> {
-    if protected.len() < PROFILE5_HEADER_SIZE {
+    let offset = usize::from(config.offset);
+    if protected.len() < offset + PROFILE5_HEADER_SIZE {
         return E2ECheckResult::error(E2ECheckStatus::BadArgument);
     }
 
@@ -164,11 +171,12 @@ pub fn check_profile5_with_header<'a>(
         return E2ECheckResult::error(E2ECheckStatus::BadArgument);
     }
 
-    let received_crc = u16::from_le_bytes([protected[0], protected[1]]);
-    let counter = protected[2];
-    let payload = &protected[PROFILE5_HEADER_SIZE..];
+    let (prefix, header, rest) = split_header(protected, offset, PROFILE5_HEADER_SIZE);
+    let received_crc = u16::from_le_bytes([header[0], header[1]]);
+    let counter = header[2];
 
-    let computed_crc = compute_crc16_p5_with_header(config.data_id, counter, payload, upper_header);
+    let computed_crc =
+        compute_crc16_p5_with_header(config.data_id, counter, prefix, rest, upper_header);
     if computed_crc != received_crc {
         return E2ECheckResult::error(E2ECheckStatus::CrcError);
     }
@@ -176,9 +184,19 @@ pub fn check_profile5_with_header<'a>(
     let status = check_sequence_profile5(state, counter, config.max_delta_counter);
     state.last_counter = Some(counter);
 
+    let payload = if offset == 0 { rest } else { protected };
     E2ECheckResult::success(status, u32::from(counter), payload)
 }
 
+/// Split `protected` into the bytes before the `header_size`-byte header at
+/// `offset`, the header and the bytes after it. The caller has checked that
+/// the header fits.
+fn split_header(protected: &[u8], offset: usize, header_size: usize) -> (&[u8], &[u8], &[u8]) {
+    let (prefix, tail) = protected.split_at(offset);
+    let (header, rest) = tail.split_at(header_size);
+    (prefix, header, rest)
+}
+
 /// Check sequence continuity for Profile 4 (16-bit counter).
 fn check_sequence_profile4(
     state: &Profile4State,
@@ -667,4 +685,70 @@ mod tests {
 
         assert_eq!(result.status, E2ECheckStatus::CrcError);
     }
+
+    #[test]
+    fn test_check_profile4_header_at_offset() {
+        let config = Profile4Config::new(0x1234_5678, 15).with_offset(4);
+        let mut protect_state = Profile4State::new();
+        let mut check_state = Profile4State::new();
+
+        let mut buf = [0u8; 256];
+        let len = protect_profile4(&config, &mut protect_state, b"prefix", &mut buf).unwrap();
+
+        let result = check_profile4(&config, &mut check_state, &buf[..len]);
+        assert_eq!(result.status, E2ECheckStatus::Ok);
+        assert_eq!(result.payload, Some(&buf[..len]));
+
+        // Without the offset the header is read from the wrong bytes.
+        let mut check_state = Profile4State::new();
+        let unshifted = Profile4Config::new(0x1234_5678, 15);
+        let result = check_profile4(&unshifted, &mut check_state, &buf[..len]);
+        assert_eq!(result.status, E2ECheckStatus::BadArgument);
+
+        // A corrupted byte before the header fails the CRC.
+        buf[0] ^= 0xFF;
+        let mut check_state = Profile4State::new();
+        let result = check_profile4(&config, &mut check_state, &buf[..len]);
+        assert_eq!(result.status, E2ECheckStatus::CrcError);
+    }
+
+    #[test]
+    fn test_check_profile5_with_header_at_offset() {
+        let config = Profile5Config::new(0x1234, 20, 15).with_offset(8);
+        let mut protect_state = Profile5State::new();
+        let mut check_state = Profile5State::new();
+        let upper_header: [u8; 8] = [0x00, 0x01, 0x00, 0x05, 0x01, 0x03, 0x02, 0x00];
+
+        let mut payload = [0u8; 20];
+        payload[..5].copy_from_slice(b"Hello");
+        let mut buf = [0u8; 256];
+        let len = protect_profile5_with_header(
+            &config,
+            &mut protect_state,
+            &payload,
+            upper_header,
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(&buf[..5], b"Hello");
+
+        let result =
+            check_profile5_with_header(&config, &mut check_state, &buf[..len], upper_header);
+        assert_eq!(result.status, E2ECheckStatus::Ok);
+        assert_eq!(result.counter, Some(0));
+
+        buf[len - 1] ^= 0x01;
+        let result =
+            check_profile5_with_header(&config, &mut check_state, &buf[..len], upper_header);
+        assert_eq!(result.status, E2ECheckStatus::CrcError);
+    }
+
+    #[test]
+    fn test_check_profile5_shorter_than_offset_is_bad_argument() {
+        let config = Profile5Config::new(0x1234, 0, 15).with_offset(4);
+        let mut check_state = Profile5State::new();
+
+        let result = check_profile5(&config, &mut check_state, &[0u8; 3]);
+        assert_eq!(result.status, E2ECheckStatus::BadArgument);
+    }
 }
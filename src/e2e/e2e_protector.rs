@@ -13,11 +13,14 @@ pub const PROFILE5_HEADER_SIZE: usize = 3;
 
 /// Add E2E Profile 4 protection to a payload.
 ///
-/// Writes a protected message into `output` with a 12-byte header prepended:
+/// Writes a protected message into `output` with a 12-byte header inserted
+/// [`Profile4Config::offset`] bytes into the payload (prepended by default):
 /// - Length (2 bytes): Total length including header
 /// - Counter (2 bytes): Sequence counter from state
 /// - `DataID` (4 bytes): From configuration
-/// - CRC (4 bytes): CRC-32P4 over Length + Counter + `DataID` + Payload
+/// - CRC (4 bytes): CRC-32P4 over every other byte of the message, i.e.
+///   the payload bytes before the header + Length + Counter + `DataID` +
+///   the rest of the payload
 ///
 /// The state counter is incremented after each call.
 ///
@@ -35,13 +38,15 @@ pub const PROFILE5_HEADER_SIZE: usize = 3;
 /// # Errors
 /// Returns [`Error::BufferTooSmall`] if `output` is too small to hold the
 /// protected message, or if the total message length (header + payload)
-/// exceeds 65 535 bytes (the Profile 4 length field is `u16`).
+/// exceeds 65 535 bytes (the Profile 4 length field is `u16`), and
+/// [`Error::PayloadShorterThanOffset`] if `payload` ends before the offset.
 pub fn protect_profile4(
     config: &Profile4Config,
     state: &mut Profile4State,
     payload: &[u8],
     output: &mut [u8],
 ) -> Result<usize, Error> {
+    let (prefix, rest) = split_at_offset(payload, config.offset)?;
     let total_length = PROFILE4_HEADER_SIZE + payload.len();
 
     if output.len() < total_length {
@@ -59,30 +64,70 @@ pub fn protect_profile4(
         actual: output.len(),
     })?;
 
+    let header = profile4_header(config, state, prefix, length, rest);
+    write_protected(output, prefix, &header, rest);
+    Ok(total_length)
+}
+
+/// Add E2E Profile 4 protection to a message in place.
+///
+/// Like [`protect_profile4`], for a caller that encodes the payload
+/// straight into its send buffer and leaves [`PROFILE4_HEADER_SIZE`] bytes
+/// free for the header [`Profile4Config::offset`] bytes in: `message` is
+/// the whole protected message, and the header is written into the gap
+/// without copying the payload.
+///
+/// # Errors
+/// Returns [`Error::BufferTooSmall`] if `message` ends before the header
+/// gap does, or is longer than the 65 535 bytes the Profile 4 length
+/// field can express.
+pub fn protect_profile4_in_place(
+    config: &Profile4Config,
+    state: &mut Profile4State,
+    message: &mut [u8],
+) -> Result<(), Error> {
+    let (prefix, header, rest) = split_header_gap(message, config.offset, PROFILE4_HEADER_SIZE)?;
+    let total_length = prefix.len() + PROFILE4_HEADER_SIZE + rest.len();
+    let length = u16::try_from(total_length).map_err(|_| Error::BufferTooSmall {
+        needed: total_length,
+        actual: total_length,
+    })?;
+    header.copy_from_slice(&profile4_header(config, state, prefix, length, rest));
+    Ok(())
+}
+
+/// Build the Profile 4 header of a message of `length` bytes and advance
+/// the counter.
+fn profile4_header(
+    config: &Profile4Config,
+    state: &mut Profile4State,
+    prefix: &[u8],
+    length: u16,
+    rest: &[u8],
+) -> [u8; PROFILE4_HEADER_SIZE] {
     let counter = state.protect_counter;
 
-    // Compute CRC over: Length + Counter + DataID + Payload
-    let crc = compute_crc32_p4(length, counter, config.data_id, payload);
+    // Compute CRC over: Prefix + Length + Counter + DataID + Payload
+    let crc = compute_crc32_p4(prefix, length, counter, config.data_id, rest);
 
     // Header: Length (2) + Counter (2) + DataID (4) + CRC (4)
-    output[0..2].copy_from_slice(&length.to_be_bytes());
-    output[2..4].copy_from_slice(&counter.to_be_bytes());
-    output[4..8].copy_from_slice(&config.data_id.to_be_bytes());
-    output[8..12].copy_from_slice(&crc.to_be_bytes());
-
-    // Payload
-    output[PROFILE4_HEADER_SIZE..total_length].copy_from_slice(payload);
+    let mut header = [0u8; PROFILE4_HEADER_SIZE];
+    header[0..2].copy_from_slice(&length.to_be_bytes());
+    header[2..4].copy_from_slice(&counter.to_be_bytes());
+    header[4..8].copy_from_slice(&config.data_id.to_be_bytes());
+    header[8..12].copy_from_slice(&crc.to_be_bytes());
 
     // Increment counter (wraps at u16::MAX)
     state.protect_counter = state.protect_counter.wrapping_add(1);
-
-    Ok(total_length)
+    header
 }
 
 /// Add E2E Profile 5 protection to a payload.
 ///
-/// Writes a protected message into `output` with a 3-byte header prepended:
-/// - CRC (2 bytes, little-endian): CRC-16-CCITT over Counter + Payload + DataID(LE)
+/// Writes a protected message into `output` with a 3-byte header inserted
+/// [`Profile5Config::offset`] bytes into the payload (prepended by default):
+/// - CRC (2 bytes, little-endian): CRC-16-CCITT over the payload bytes
+///   before the header + Counter + the rest of the payload + DataID(LE)
 /// - Counter (1 byte): Sequence counter from state
 ///
 /// The state counter is incremented after each call.
@@ -100,13 +145,15 @@ pub fn protect_profile4(
 ///
 /// # Errors
 /// Returns [`Error::BufferTooSmall`] if `output` is too small to hold the
-/// protected message.
+/// protected message, and [`Error::PayloadShorterThanOffset`] if `payload`
+/// ends before the offset.
 pub fn protect_profile5(
     config: &Profile5Config,
     state: &mut Profile5State,
     payload: &[u8],
     output: &mut [u8],
 ) -> Result<usize, Error> {
+    let (prefix, rest) = split_at_offset(payload, config.offset)?;
     let total_length = PROFILE5_HEADER_SIZE + payload.len();
 
     if output.len() < total_length {
@@ -116,29 +163,38 @@ pub fn protect_profile5(
         });
     }
 
-    let counter = state.protect_counter;
-
-    // Compute CRC over: Counter + Payload + DataID (LE)
-    let crc = compute_crc16_p5(config.data_id, counter, payload);
-
-    // Header: CRC (2, little-endian) + Counter (1)
-    output[0..2].copy_from_slice(&crc.to_le_bytes());
-    output[2] = counter;
-
-    // Payload
-    output[PROFILE5_HEADER_SIZE..total_length].copy_from_slice(payload);
-
-    // Increment counter (wraps at u8::MAX)
-    state.protect_counter = state.protect_counter.wrapping_add(1);
-
+    let header = profile5_header(config, state, prefix, rest, None);
+    write_protected(output, prefix, &header, rest);
     Ok(total_length)
 }
 
+/// Add E2E Profile 5 protection to a message in place.
+///
+/// Like [`protect_profile5`], for a caller that leaves
+/// [`PROFILE5_HEADER_SIZE`] bytes free for the header
+/// [`Profile5Config::offset`] bytes into `message`, the whole protected
+/// message; see [`protect_profile4_in_place`].
+///
+/// # Errors
+/// Returns [`Error::BufferTooSmall`] if `message` ends before the header
+/// gap does.
+pub fn protect_profile5_in_place(
+    config: &Profile5Config,
+    state: &mut Profile5State,
+    message: &mut [u8],
+) -> Result<(), Error> {
+    let (prefix, header, rest) = split_header_gap(message, config.offset, PROFILE5_HEADER_SIZE)?;
+    header.copy_from_slice(&profile5_header(config, state, prefix, rest, None));
+    Ok(())
+}
+
 /// Add E2E Profile 5 protection with SOME/IP upper-header in the CRC.
 ///
-/// Creates a protected message with a 3-byte header prepended:
+/// Creates a protected message with a 3-byte header inserted
+/// [`Profile5Config::offset`] bytes into the payload (prepended by default):
 /// - CRC (2 bytes, little-endian): CRC-16-CCITT over
-///   `upper_header(8) + Counter(1) + Payload(N) + DataID(2 LE)`
+///   `upper_header(8) + Prefix + Counter(1) + Payload(N) + DataID(2 LE)`,
+///   `Prefix` being the payload bytes before the header
 /// - Counter (1 byte): Sequence counter from state
 ///
 /// The 8-byte `upper_header` (UPPER-HEADER-BITS-TO-SHIFT = 64 bits) is the
@@ -158,7 +214,8 @@ pub fn protect_profile5(
 ///
 /// # Errors
 /// Returns [`Error::BufferTooSmall`] if `output` is too small to hold the
-/// protected message.
+/// protected message, and [`Error::PayloadShorterThanOffset`] if `payload`
+/// ends before the offset.
 pub fn protect_profile5_with_header(
     config: &Profile5Config,
     state: &mut Profile5State,
@@ -166,6 +223,7 @@ pub fn protect_profile5_with_header(
     upper_header: [u8; 8],
     output: &mut [u8],
 ) -> Result<usize, Error> {
+    let (prefix, rest) = split_at_offset(payload, config.offset)?;
     let total_length = PROFILE5_HEADER_SIZE + payload.len();
 
     if output.len() < total_length {
@@ -175,19 +233,105 @@ pub fn protect_profile5_with_header(
         });
     }
 
+    let header = profile5_header(config, state, prefix, rest, Some(upper_header));
+    write_protected(output, prefix, &header, rest);
+    Ok(total_length)
+}
+
+/// Add E2E Profile 5 protection, with the SOME/IP upper header in the CRC,
+/// to a message in place; see [`protect_profile5_with_header`] and
+/// [`protect_profile5_in_place`].
+///
+/// # Errors
+/// Returns [`Error::BufferTooSmall`] if `message` ends before the header
+/// gap does.
+pub fn protect_profile5_with_header_in_place(
+    config: &Profile5Config,
+    state: &mut Profile5State,
+    message: &mut [u8],
+    upper_header: [u8; 8],
+) -> Result<(), Error> {
+    let (prefix, header, rest) = split_header_gap(message, config.offset, PROFILE5_HEADER_SIZE)?;
+    header.copy_from_slice(&profile5_header(
+        config,
+        state,
+        prefix,
+        rest,
+        Some(upper_header),
+    ));
+    Ok(())
+}
+
+/// Build the Profile 5 header, with `upper_header` in the CRC if given,
+/// and advance the counter.
+fn profile5_header(
+    config: &Profile5Config,
+    state: &mut Profile5State,
+    prefix: &[u8],
+    rest: &[u8],
+    upper_header: Option<[u8; 8]>,
+) -> [u8; PROFILE5_HEADER_SIZE] {
     let counter = state.protect_counter;
-    let crc = compute_crc16_p5_with_header(config.data_id, counter, payload, upper_header);
+
+    // Compute CRC over: [UpperHeader] + Prefix + Counter + Payload + DataID (LE)
+    let crc = match upper_header {
+        Some(upper_header) => {
+            compute_crc16_p5_with_header(config.data_id, counter, prefix, rest, upper_header)
+        }
+        None => compute_crc16_p5(config.data_id, counter, prefix, rest),
+    };
+
+    // Increment counter (wraps at u8::MAX)
+    state.protect_counter = state.protect_counter.wrapping_add(1);
 
     // Header: CRC (2, little-endian) + Counter (1)
-    output[0..2].copy_from_slice(&crc.to_le_bytes());
-    output[2] = counter;
+    let [crc_lo, crc_hi] = crc.to_le_bytes();
+    [crc_lo, crc_hi, counter]
+}
 
-    // Payload
-    output[PROFILE5_HEADER_SIZE..total_length].copy_from_slice(payload);
+/// Split `payload` into the bytes before and after a header placed at
+/// `offset`.
+fn split_at_offset(payload: &[u8], offset: u16) -> Result<(&[u8], &[u8]), Error> {
+    let offset = usize::from(offset);
+    if payload.len() < offset {
+        return Err(Error::PayloadShorterThanOffset {
+            offset,
+            len: payload.len(),
+        });
+    }
+    Ok(payload.split_at(offset))
+}
 
-    state.protect_counter = state.protect_counter.wrapping_add(1);
+/// The bytes of a message before its E2E header gap, the gap, and the
+/// bytes after it.
+type HeaderGap<'a> = (&'a [u8], &'a mut [u8], &'a [u8]);
+
+/// Split a message around a `header_size`-byte gap at `offset`.
+fn split_header_gap(
+    message: &mut [u8],
+    offset: u16,
+    header_size: usize,
+) -> Result<HeaderGap<'_>, Error> {
+    let offset = usize::from(offset);
+    if message.len() < offset + header_size {
+        return Err(Error::BufferTooSmall {
+            needed: offset + header_size,
+            actual: message.len(),
+        });
+    }
+    let (prefix, tail) = message.split_at_mut(offset);
+    let (header, rest) = tail.split_at_mut(header_size);
+    Ok((prefix, header, rest))
+}
 
-    Ok(total_length)
+/// Write `prefix`, `header` and `rest` back to back into `output`, which
+/// the caller has checked is long enough.
+fn write_protected(output: &mut [u8], prefix: &[u8], header: &[u8], rest: &[u8]) {
+    let header_start = prefix.len();
+    let rest_start = header_start + header.len();
+    output[..header_start].copy_from_slice(prefix);
+    output[header_start..rest_start].copy_from_slice(header);
+    output[rest_start..rest_start + rest.len()].copy_from_slice(rest);
 }
 
 #[cfg(test)]
@@ -487,4 +631,95 @@ mod tests {
         let len = protect_profile5(&config, &mut state, b"", &mut buf).unwrap();
         assert_eq!(len, 3); // Just header
     }
+
+    #[test]
+    fn test_protect_profile4_header_at_offset() {
+        let config = Profile4Config::new(0x1234_5678, 15).with_offset(2);
+        let mut state = Profile4State::new();
+
+        let mut buf = [0u8; 256];
+        let len = protect_profile4(&config, &mut state, b"pre-test", &mut buf).unwrap();
+        assert_eq!(len, 12 + 8);
+        assert_eq!(&buf[..2], b"pr");
+        assert_eq!(u16::from_be_bytes([buf[2], buf[3]]), 20); // length
+        assert_eq!(&buf[6..10], &0x1234_5678u32.to_be_bytes()); // data_id
+        assert_eq!(&buf[14..len], b"e-test");
+
+        // The bytes before the header are covered by the CRC.
+        let mut state = Profile4State::new();
+        let mut other = [0u8; 256];
+        protect_profile4(&config, &mut state, b"PRe-test", &mut other).unwrap();
+        assert_ne!(buf[10..14], other[10..14]);
+    }
+
+    #[test]
+    fn test_protect_profile5_rejects_payload_shorter_than_offset() {
+        let config = Profile5Config::new(0x1234, 3, 15).with_offset(4);
+        let mut state = Profile5State::new();
+
+        let mut buf = [0u8; 256];
+        let err = protect_profile5(&config, &mut state, b"abc", &mut buf).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::e2e::Error::PayloadShorterThanOffset { offset: 4, len: 3 }
+        ));
+        assert_eq!(state.protect_counter, 0);
+    }
+
+    #[test]
+    fn in_place_protection_matches_the_copying_one() {
+        let payload = b"\x01\x02\x03\x04\x05\x06";
+        let mut copied = [0u8; 32];
+        let mut message = [0u8; 32];
+
+        let config = Profile4Config::new(0x1234_5678, 15).with_offset(2);
+        let len =
+            protect_profile4(&config, &mut Profile4State::new(), payload, &mut copied).unwrap();
+        message[..2].copy_from_slice(&payload[..2]);
+        message[2 + PROFILE4_HEADER_SIZE..len].copy_from_slice(&payload[2..]);
+        let mut state = Profile4State::new();
+        protect_profile4_in_place(&config, &mut state, &mut message[..len]).unwrap();
+        assert_eq!(message[..len], copied[..len]);
+        assert_eq!(state.protect_counter, 1);
+
+        let config = Profile5Config::new(0x1234, 9, 15);
+        let len =
+            protect_profile5(&config, &mut Profile5State::new(), payload, &mut copied).unwrap();
+        message[PROFILE5_HEADER_SIZE..len].copy_from_slice(payload);
+        protect_profile5_in_place(&config, &mut Profile5State::new(), &mut message[..len]).unwrap();
+        assert_eq!(message[..len], copied[..len]);
+
+        let upper_header = [0, 0, 0, 1, 1, 1, 2, 0];
+        let len = protect_profile5_with_header(
+            &config,
+            &mut Profile5State::new(),
+            payload,
+            upper_header,
+            &mut copied,
+        )
+        .unwrap();
+        protect_profile5_with_header_in_place(
+            &config,
+            &mut Profile5State::new(),
+            &mut message[..len],
+            upper_header,
+        )
+        .unwrap();
+        assert_eq!(message[..len], copied[..len]);
+    }
+
+    #[test]
+    fn in_place_protection_needs_room_for_the_header() {
+        let config = Profile4Config::new(0x1234_5678, 15).with_offset(4);
+        let mut state = Profile4State::new();
+        let err = protect_profile4_in_place(&config, &mut state, &mut [0u8; 15]).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::BufferTooSmall {
+                needed: 16,
+                actual: 15
+            }
+        ));
+        assert_eq!(state.protect_counter, 0);
+    }
 }
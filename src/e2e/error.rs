@@ -11,4 +11,17 @@ pub enum Error {
         /// The number of bytes available.
         actual: usize,
     },
+    /// The payload to protect ends before the configured header offset
+    /// (`Profile4Config::offset` / `Profile5Config::offset`).
+    #[error("payload of {len} bytes is shorter than the E2E header offset {offset}")]
+    PayloadShorterThanOffset {
+        /// The configured header offset, in bytes.
+        offset: usize,
+        /// The length of the payload.
+        len: usize,
+    },
+    /// The key provider of a `SecOC` profile has no key with this ID.
+    #[cfg(feature = "secoc")]
+    #[error("SecOC key {0} unavailable")]
+    KeyUnavailable(u16),
 }
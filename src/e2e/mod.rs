@@ -2,6 +2,8 @@
 //!
 //! This module implements E2E Profile 4 and Profile 5 protection as specified
 //! in the [Open SOME/IP Specification](https://github.com/some-ip-com/open-someip-spec).
+//! With the `secoc` feature, `E2EProfile::SecOc` authenticates payloads
+//! `SecOC`-style instead, with a freshness value and a truncated AES-128 CMAC.
 //!
 //! # Example
 //!
@@ -23,6 +25,29 @@
 //! let result = check_profile4(&config, &mut check_state, &buf[..len]);
 //! assert!(matches!(result.status, E2ECheckStatus::Ok));
 //! ```
+//!
+//! # Automatic protection and checking
+//!
+//! Most applications never call the profile functions directly. Register an
+//! [`E2EProfile`] for a message ID ([`E2EKey`]) with `Server::register_e2e`
+//! or `Client::register_e2e`, and the [`E2ERegistry`] behind it is consulted
+//! on every path that has the unprotected payload in hand:
+//!
+//! - `EventPublisher::publish_event` protects the encoded payload before it
+//!   is sent to the subscribers. The raw publish paths (`publish_raw_event`,
+//!   `EventSender`) send their payload as given.
+//! - The client protects requests it sends and checks the messages it
+//!   receives, with receive counters kept per sender. A checked message is
+//!   delivered with its E2E header stripped and the
+//!   [`E2ECheckStatus`] in `ClientUpdate::Unicast::e2e_status`. Event
+//!   streams only get notifications that passed, and a reply that failed
+//!   resolves its request to `client::Error::E2ECheckFailed`.
+//! - The server checks incoming requests the same way and hands the status
+//!   to the request handler in `Request::e2e_status`.
+//!
+//! Messages without a registered profile pass untouched, with no status.
+//!
+//! [`E2ERegistry`]: crate::e2e::E2ERegistry
 
 mod config;
 mod crc;
@@ -30,16 +55,21 @@ mod e2e_checker;
 mod e2e_protector;
 mod error;
 mod registry;
+#[cfg(feature = "secoc")]
+mod secoc;
 mod state;
 
 pub use config::{Profile4Config, Profile5Config};
 pub use e2e_checker::{check_profile4, check_profile5, check_profile5_with_header};
 pub use e2e_protector::{
-    PROFILE4_HEADER_SIZE, PROFILE5_HEADER_SIZE, protect_profile4, protect_profile5,
-    protect_profile5_with_header,
+    PROFILE4_HEADER_SIZE, PROFILE5_HEADER_SIZE, protect_profile4, protect_profile4_in_place,
+    protect_profile5, protect_profile5_in_place, protect_profile5_with_header,
+    protect_profile5_with_header_in_place,
 };
 pub use error::Error;
 pub use registry::{E2E_REGISTRY_CAP, E2E_RX_STATE_CAP, E2ERegistry, E2ERegistryFull};
+#[cfg(feature = "secoc")]
+pub use secoc::{SecOcConfig, SecOcState, check_secoc, protect_secoc};
 pub use state::{Profile4State, Profile5State};
 
 /// Status result from E2E check operations.
@@ -75,6 +105,19 @@ impl E2ECheckStatus {
             E2ECheckStatus::BadArgument => 6,
         }
     }
+
+    /// `true` if the check rejected the message: a CRC or sequence error,
+    /// a repeated counter, or a malformed E2E header.
+    #[must_use]
+    pub fn is_failure(self) -> bool {
+        matches!(
+            self,
+            E2ECheckStatus::CrcError
+                | E2ECheckStatus::Repeated
+                | E2ECheckStatus::WrongSequence
+                | E2ECheckStatus::BadArgument
+        )
+    }
 }
 
 /// Result from an E2E check operation.
@@ -84,7 +127,8 @@ pub struct E2ECheckResult<'a> {
     pub status: E2ECheckStatus,
     /// Counter value extracted from the header (if parsing succeeded).
     pub counter: Option<u32>,
-    /// Extracted payload without E2E header (if check succeeded).
+    /// Extracted payload without E2E header (if check succeeded). For a
+    /// header at a nonzero offset, the whole message including the header.
     ///
     /// This is a borrowed subslice of the input `protected` buffer and is only
     /// valid as long as that buffer is kept alive.
@@ -127,6 +171,9 @@ pub enum E2EProfile {
     Profile5(Profile5Config),
     /// E2E Profile 5 with SOME/IP upper-header included in the CRC.
     Profile5WithHeader(Profile5Config),
+    /// `SecOC`-style freshness value and truncated AES-128 CMAC.
+    #[cfg(feature = "secoc")]
+    SecOc(SecOcConfig),
 }
 
 /// Identifies a data element for E2E protection lookup.
@@ -165,6 +212,9 @@ pub(crate) enum E2EState {
     Profile4(Profile4State),
     /// State for Profile 5 (used by both `Profile5` and `Profile5WithHeader`).
     Profile5(Profile5State),
+    /// Freshness counters for `SecOc`.
+    #[cfg(feature = "secoc")]
+    SecOc(SecOcState),
 }
 
 impl E2EState {
@@ -174,6 +224,8 @@ impl E2EState {
             E2EProfile::Profile5(_) | E2EProfile::Profile5WithHeader(_) => {
                 Self::Profile5(Profile5State::new())
             }
+            #[cfg(feature = "secoc")]
+            E2EProfile::SecOc(_) => Self::SecOc(SecOcState::new()),
         }
     }
 }
@@ -196,6 +248,8 @@ pub(crate) fn e2e_check<'a>(
         (E2EProfile::Profile5WithHeader(config), E2EState::Profile5(st)) => {
             check_profile5_with_header(config, st, payload, upper_header)
         }
+        #[cfg(feature = "secoc")]
+        (E2EProfile::SecOc(config), E2EState::SecOc(st)) => check_secoc(config, st, payload),
         _ => return (E2ECheckStatus::BadArgument, payload),
     };
     let stripped = result.payload.unwrap_or(payload);
@@ -224,6 +278,10 @@ pub(crate) fn e2e_protect(
         (E2EProfile::Profile5WithHeader(config), E2EState::Profile5(st)) => {
             protect_profile5_with_header(config, st, payload, upper_header, output)
         }
+        #[cfg(feature = "secoc")]
+        (E2EProfile::SecOc(config), E2EState::SecOc(st)) => {
+            protect_secoc(config, st, payload, output)
+        }
         _ => unreachable!("E2EState is always created from E2EProfile"),
     }
 }
@@ -76,6 +76,7 @@ pub struct E2ERegistryFull(pub usize);
 /// Construction is `const`, so a `static` instance can be declared in
 /// firmware boot code. Profile/transmit slots are bounded by
 /// [`E2E_REGISTRY_CAP`]; receive slots by [`E2E_RX_STATE_CAP`].
+#[doc(alias = "E2EConfigRegistry")]
 #[derive(Debug)]
 pub struct E2ERegistry {
     /// Endpoint-agnostic profile configuration, keyed by data element.
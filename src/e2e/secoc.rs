@@ -0,0 +1,345 @@
+//! `SecOC`-style authentication: a freshness value and a truncated
+//! AES-128 CMAC appended to the payload.
+//!
+//! Protected layout: `payload || FV || MAC`, where `FV` is the low
+//! [`SecOcConfig::freshness_len`] bytes of a 64-bit freshness counter
+//! (big-endian) and `MAC` the first [`SecOcConfig::mac_len`] bytes of the
+//! CMAC over `data_id (u16 BE) || payload || full FV (u64 BE)`. The
+//! receiver rebuilds the full freshness value from the last one it
+//! accepted, so a truncated FV only has to be wide enough to span the
+//! messages that can be lost in a row.
+
+use cmac::{Cmac, Mac};
+
+use super::{E2ECheckResult, E2ECheckStatus, Error};
+use crate::keys::KeyProvider;
+
+/// Configuration of `SecOC`-style authentication for one data element.
+#[derive(Clone, Copy)]
+pub struct SecOcConfig {
+    /// Identifier of the data element, authenticated with the payload.
+    pub data_id: u16,
+    /// ID of the AES-128 key in [`Self::keys`]. It is looked up on every
+    /// protect and check, so a rotated key takes effect immediately.
+    pub key_id: u16,
+    /// Bytes of the freshness value sent with each message, `0..=8`.
+    pub freshness_len: u8,
+    /// Bytes of the CMAC sent with each message, `1..=16`.
+    pub mac_len: u8,
+    /// Where the key comes from.
+    pub keys: &'static dyn KeyProvider,
+}
+
+impl SecOcConfig {
+    /// Create a configuration sending an 8-bit freshness value and a
+    /// 24-bit MAC.
+    ///
+    /// # Arguments
+    /// * `data_id` - Unique identifier for this data element
+    /// * `key_id` - ID of the key in `keys`
+    /// * `keys` - Provider of the key
+    #[must_use]
+    pub fn new(data_id: u16, key_id: u16, keys: &'static dyn KeyProvider) -> Self {
+        Self {
+            data_id,
+            key_id,
+            freshness_len: 1,
+            mac_len: 3,
+            keys,
+        }
+    }
+
+    /// Bytes added to each payload: the freshness value and the MAC.
+    #[must_use]
+    pub fn overhead(&self) -> usize {
+        usize::from(self.freshness_len.min(8)) + usize::from(self.mac_len.clamp(1, 16))
+    }
+}
+
+impl core::fmt::Debug for SecOcConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SecOcConfig")
+            .field("data_id", &self.data_id)
+            .field("key_id", &self.key_id)
+            .field("freshness_len", &self.freshness_len)
+            .field("mac_len", &self.mac_len)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Freshness counters for `SecOC`-style authentication.
+#[derive(Debug, Clone, Default)]
+pub struct SecOcState {
+    /// Freshness value of the next protected message.
+    pub(crate) tx_freshness: u64,
+    /// Last freshness value accepted by a check.
+    pub(crate) rx_freshness: Option<u64>,
+}
+
+impl SecOcState {
+    /// Create a state starting both counters at 0.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the freshness value the next protected message carries.
+    #[must_use]
+    pub const fn tx_freshness(&self) -> u64 {
+        self.tx_freshness
+    }
+
+    /// Returns the last accepted freshness value, or `None` if no message
+    /// has been accepted yet.
+    #[must_use]
+    pub const fn rx_freshness(&self) -> Option<u64> {
+        self.rx_freshness
+    }
+
+    /// Reset the state to initial values.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// The full CMAC, or `None` if no 16-byte key is available.
+fn compute_mac(config: &SecOcConfig, payload: &[u8], freshness: u64) -> Option<Cmac<aes::Aes128>> {
+    let mut key = [0u8; 16];
+    let len = config.keys.key(config.key_id, &mut key)?;
+    let mut mac = <Cmac<aes::Aes128> as Mac>::new_from_slice(key.get(..len)?).ok()?;
+    mac.update(&config.data_id.to_be_bytes());
+    mac.update(payload);
+    mac.update(&freshness.to_be_bytes());
+    Some(mac)
+}
+
+/// Authenticate `payload` and write it, its freshness value and its MAC
+/// to `output`, returning the number of bytes written.
+///
+/// # Errors
+///
+/// Returns [`Error::BufferTooSmall`] if `output` cannot hold the protected
+/// payload, and [`Error::KeyUnavailable`] if the key provider has no
+/// 16-byte key [`SecOcConfig::key_id`]. The freshness counter only advances on success.
+pub fn protect_secoc(
+    config: &SecOcConfig,
+    state: &mut SecOcState,
+    payload: &[u8],
+    output: &mut [u8],
+) -> Result<usize, Error> {
+    let freshness_len = usize::from(config.freshness_len.min(8));
+    let mac_len = usize::from(config.mac_len.clamp(1, 16));
+    let needed = payload.len() + freshness_len + mac_len;
+    if output.len() < needed {
+        return Err(Error::BufferTooSmall {
+            needed,
+            actual: output.len(),
+        });
+    }
+    let freshness = state.tx_freshness;
+    let mac = compute_mac(config, payload, freshness)
+        .ok_or(Error::KeyUnavailable(config.key_id))?
+        .finalize()
+        .into_bytes();
+
+    let (data, rest) = output.split_at_mut(payload.len());
+    data.copy_from_slice(payload);
+    rest[..freshness_len].copy_from_slice(&freshness.to_be_bytes()[8 - freshness_len..]);
+    rest[freshness_len..freshness_len + mac_len].copy_from_slice(&mac[..mac_len]);
+    state.tx_freshness = freshness.wrapping_add(1);
+    Ok(needed)
+}
+
+/// Verify a message protected by [`protect_secoc`].
+///
+/// A MAC that does not match reports [`E2ECheckStatus::CrcError`], the
+/// MAC standing in for the E2E CRC; a freshness value that is not newer
+/// than the last accepted one [`E2ECheckStatus::Repeated`] or
+/// [`E2ECheckStatus::WrongSequence`]. A message shorter than the
+/// freshness value and MAC, or a missing key, reports
+/// [`E2ECheckStatus::BadArgument`]. The payload is returned on success
+/// only, and only then does the accepted freshness value move forward.
+pub fn check_secoc<'a>(
+    config: &SecOcConfig,
+    state: &mut SecOcState,
+    protected: &'a [u8],
+) -> E2ECheckResult<'a> {
+    let freshness_len = usize::from(config.freshness_len.min(8));
+    let mac_len = usize::from(config.mac_len.clamp(1, 16));
+    let Some(payload_len) = protected.len().checked_sub(freshness_len + mac_len) else {
+        return E2ECheckResult::error(E2ECheckStatus::BadArgument);
+    };
+    let (payload, rest) = protected.split_at(payload_len);
+    let (truncated, received_mac) = rest.split_at(freshness_len);
+
+    let mut be = [0u8; 8];
+    be[8 - freshness_len..].copy_from_slice(truncated);
+    let truncated = u64::from_be_bytes(be);
+    let freshness = match state.rx_freshness {
+        Some(last) if freshness_len == 8 => {
+            if truncated == last {
+                return E2ECheckResult::error(E2ECheckStatus::Repeated);
+            }
+            if truncated < last {
+                return E2ECheckResult::error(E2ECheckStatus::WrongSequence);
+            }
+            truncated
+        }
+        // Take the upper bits from the last accepted value, moving to the
+        // next epoch if that would not be newer.
+        Some(last) => {
+            let span = 1u64 << (freshness_len * 8);
+            let candidate = (last & !(span - 1)) | truncated;
+            if candidate <= last {
+                candidate.wrapping_add(span)
+            } else {
+                candidate
+            }
+        }
+        None => truncated,
+    };
+
+    let Some(mac) = compute_mac(config, payload, freshness) else {
+        return E2ECheckResult::error(E2ECheckStatus::BadArgument);
+    };
+    if mac.verify_truncated_left(received_mac).is_err() {
+        return E2ECheckResult::error(E2ECheckStatus::CrcError);
+    }
+
+    let status = match state.rx_freshness {
+        Some(last) if freshness != last.wrapping_add(1) => E2ECheckStatus::OkSomeLost,
+        _ => E2ECheckStatus::Ok,
+    };
+    state.rx_freshness = Some(freshness);
+    // The counter field reports the low half of the freshness value.
+    #[allow(clippy::cast_possible_truncation)]
+    E2ECheckResult::success(status, freshness as u32, payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static KEYS: [(u16, [u8; 16]); 1] = [(7, [0x2B; 16])];
+
+    #[test]
+    fn protect_and_check_round_trip() {
+        let config = SecOcConfig::new(0x0101, 7, &KEYS);
+        let mut tx = SecOcState::new();
+        let mut rx = SecOcState::new();
+        let mut buf = [0u8; 64];
+
+        for expected in 0..3u64 {
+            let len = protect_secoc(&config, &mut tx, b"speed=42", &mut buf).unwrap();
+            assert_eq!(len, 8 + config.overhead());
+            let result = check_secoc(&config, &mut rx, &buf[..len]);
+            assert_eq!(result.status, E2ECheckStatus::Ok);
+            assert_eq!(result.payload, Some(b"speed=42".as_slice()));
+            assert_eq!(rx.rx_freshness(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn tampered_replayed_and_unkeyed_messages_are_refused() {
+        let config = SecOcConfig::new(0x0101, 7, &KEYS);
+        let mut tx = SecOcState::new();
+        let mut rx = SecOcState::new();
+        let mut buf = [0u8; 64];
+        let len = protect_secoc(&config, &mut tx, b"data", &mut buf).unwrap();
+
+        let mut tampered = buf;
+        tampered[0] ^= 1;
+        let result = check_secoc(&config, &mut rx, &tampered[..len]);
+        assert_eq!(result.status, E2ECheckStatus::CrcError);
+        assert_eq!(rx.rx_freshness(), None, "a refused message moves nothing");
+
+        assert_eq!(
+            check_secoc(&config, &mut rx, &buf[..len]).status,
+            E2ECheckStatus::Ok
+        );
+        // The same truncated FV is read as the next epoch's, so the MAC
+        // no longer matches.
+        assert_eq!(
+            check_secoc(&config, &mut rx, &buf[..len]).status,
+            E2ECheckStatus::CrcError
+        );
+
+        let unkeyed = SecOcConfig::new(0x0101, 8, &KEYS);
+        assert!(matches!(
+            protect_secoc(&unkeyed, &mut tx, b"data", &mut buf),
+            Err(Error::KeyUnavailable(8))
+        ));
+        assert_eq!(
+            check_secoc(&unkeyed, &mut rx, &buf[..len]).status,
+            E2ECheckStatus::BadArgument
+        );
+    }
+
+    #[test]
+    fn truncated_freshness_is_rebuilt_across_epochs() {
+        let config = SecOcConfig::new(0x0101, 7, &KEYS);
+        let mut tx = SecOcState::new();
+        let mut rx = SecOcState::new();
+        let mut buf = [0u8; 64];
+
+        tx.tx_freshness = 0xFE;
+        let len = protect_secoc(&config, &mut tx, b"a", &mut buf).unwrap();
+        rx.rx_freshness = Some(0xFD);
+        assert_eq!(
+            check_secoc(&config, &mut rx, &buf[..len]).status,
+            E2ECheckStatus::Ok
+        );
+        // 0xFF is lost; 0x100 goes out with a truncated FV of 0x00.
+        tx.tx_freshness = 0x100;
+        let len = protect_secoc(&config, &mut tx, b"a", &mut buf).unwrap();
+        let result = check_secoc(&config, &mut rx, &buf[..len]);
+        assert_eq!(result.status, E2ECheckStatus::OkSomeLost);
+        assert_eq!(rx.rx_freshness(), Some(0x100));
+    }
+
+    #[test]
+    fn full_freshness_detects_repeats_before_the_mac() {
+        let config = SecOcConfig {
+            freshness_len: 8,
+            mac_len: 16,
+            ..SecOcConfig::new(0x0101, 7, &KEYS)
+        };
+        let mut tx = SecOcState::new();
+        let mut rx = SecOcState::new();
+        let mut buf = [0u8; 64];
+        let len = protect_secoc(&config, &mut tx, b"a", &mut buf).unwrap();
+        assert_eq!(len, 1 + 8 + 16);
+        assert_eq!(
+            check_secoc(&config, &mut rx, &buf[..len]).status,
+            E2ECheckStatus::Ok
+        );
+        assert_eq!(
+            check_secoc(&config, &mut rx, &buf[..len]).status,
+            E2ECheckStatus::Repeated
+        );
+        assert_eq!(
+            check_secoc(&config, &mut rx, &buf[..3]).status,
+            E2ECheckStatus::BadArgument
+        );
+    }
+
+    #[test]
+    fn registry_applies_secoc_per_message_id() {
+        use crate::e2e::{E2EKey, E2EProfile, E2ERegistry};
+
+        let key = E2EKey::new(0x1234, 0x8001);
+        let mut registry = E2ERegistry::new();
+        registry
+            .register(key, E2EProfile::SecOc(SecOcConfig::new(0x0101, 7, &KEYS)))
+            .unwrap();
+        let mut buf = [0u8; 64];
+        let len = registry
+            .protect(key, b"data", [0; 8], &mut buf)
+            .unwrap()
+            .unwrap();
+        let source = core::net::IpAddr::from([192, 168, 1, 10]);
+        let (status, payload) = registry.check(source, key, &buf[..len], [0; 8]).unwrap();
+        assert_eq!(status, E2ECheckStatus::Ok);
+        assert_eq!(payload, b"data");
+    }
+}
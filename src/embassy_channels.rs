@@ -277,6 +277,15 @@ impl<T: Send + 'static, const N: usize> MpscSend<T> for EmbassySyncBoundedSender
             .await
         }
     }
+
+    fn try_send(&self, value: T) -> Result<(), T> {
+        if self.inner.closed.load(Ordering::Acquire) {
+            return Err(value);
+        }
+        self.inner.chan.try_send(value).map_err(|e| match e {
+            embassy_sync::channel::TrySendError::Full(value) => value,
+        })
+    }
 }
 
 impl<T: Send + 'static, const N: usize> Drop for EmbassySyncBoundedReceiver<T, N> {
@@ -338,6 +347,10 @@ impl<T: Send + 'static> UnboundedSend<T> for EmbassySyncUnboundedSender<T> {
             embassy_sync::channel::TrySendError::Full(v) => v,
         })
     }
+
+    fn is_closed(&self) -> bool {
+        self.inner.closed.load(Ordering::Acquire)
+    }
 }
 
 impl<T: Send + 'static> Drop for EmbassySyncUnboundedReceiver<T> {
@@ -352,6 +365,10 @@ impl<T: Send + 'static> UnboundedRecv<T> for EmbassySyncUnboundedReceiver<T> {
         let inner = self.inner.clone();
         async move { mpsc_recv_inner(inner).await }
     }
+
+    fn poll_recv(&mut self, cx: &mut core::task::Context<'_>) -> core::task::Poll<Option<T>> {
+        mpsc_poll_recv(&self.inner, cx)
+    }
 }
 
 // ── Shared MPSC recv plumbing ─────────────────────────────────────────
@@ -0,0 +1,177 @@
+#[cfg(any(feature = "client", feature = "server"))]
+use heapless::Vec;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_EVENT_VALIDATORS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_EVENT_VALIDATORS_CAP: usize = 8;
+
+/// Maximum number of events one client or server `EventPublisher`
+/// validates. Sized via `SIMPLE_SOMEIP_EVENT_VALIDATORS_CAP`; defaults to
+/// 2 on bare-metal, 8 otherwise.
+pub const EVENT_VALIDATORS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_EVENT_VALIDATORS_CAP"),
+    _DEFAULT_EVENT_VALIDATORS_CAP,
+);
+
+/// Checks the invariants of one event's payload (value ranges, enum
+/// values), returning `Err` with a short reason for a payload that
+/// breaks them.
+///
+/// The server checks the encoded payload (`T = [u8]`) before publishing;
+/// the client checks the decoded payload (`T` its payload type) before
+/// delivering the notification.
+pub type EventValidator<T> = fn(&T) -> Result<(), &'static str>;
+
+#[cfg(any(feature = "client", feature = "server"))]
+#[derive(Debug)]
+struct Entry<T: ?Sized + 'static> {
+    service_id: u16,
+    event_id: u16,
+    validator: EventValidator<T>,
+    rejected: u64,
+}
+
+/// Per-event [`EventValidator`]s and the number of payloads each
+/// rejected.
+#[cfg(any(feature = "client", feature = "server"))]
+#[derive(Debug)]
+pub(crate) struct EventValidators<T: ?Sized + 'static> {
+    entries: Vec<Entry<T>, EVENT_VALIDATORS_CAP>,
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl<T: ?Sized + 'static> EventValidators<T> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set (`Some`) or clear (`None`) the validator of an event. A
+    /// replaced validator keeps its rejection count. Returns `false` if
+    /// the table is full.
+    pub(crate) fn set(
+        &mut self,
+        service_id: u16,
+        event_id: u16,
+        validator: Option<EventValidator<T>>,
+    ) -> bool {
+        let existing = self
+            .entries
+            .iter()
+            .position(|e| e.service_id == service_id && e.event_id == event_id);
+        match (existing, validator) {
+            (Some(index), Some(validator)) => self.entries[index].validator = validator,
+            (Some(index), None) => {
+                self.entries.swap_remove(index);
+            }
+            (None, Some(validator)) => {
+                return self
+                    .entries
+                    .push(Entry {
+                        service_id,
+                        event_id,
+                        validator,
+                        rejected: 0,
+                    })
+                    .is_ok();
+            }
+            (None, None) => {}
+        }
+        true
+    }
+
+    /// Run the validator of the event, if it has one, on `payload`.
+    /// A rejection is counted and logged, with the log thinned out to
+    /// the 1st, 2nd, 4th, 8th... rejection of each event so a producer
+    /// stuck on a bad value cannot flood it.
+    pub(crate) fn validate(
+        &mut self,
+        service_id: u16,
+        event_id: u16,
+        payload: &T,
+    ) -> Result<(), &'static str> {
+        let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.service_id == service_id && e.event_id == event_id)
+        else {
+            return Ok(());
+        };
+        let result = (entry.validator)(payload);
+        if let Err(reason) = result {
+            entry.rejected += 1;
+            if entry.rejected.is_power_of_two() {
+                crate::log::warn!(
+                    "Event 0x{:04X}.0x{:04X} payload rejected: {} ({} so far)",
+                    service_id,
+                    event_id,
+                    reason,
+                    entry.rejected
+                );
+            }
+        }
+        result
+    }
+
+    /// Payloads of the event rejected so far; `0` without a validator.
+    /// The client counts rejections per peer instead.
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    pub(crate) fn rejected(&self, service_id: u16, event_id: u16) -> u64 {
+        self.entries
+            .iter()
+            .find(|e| e.service_id == service_id && e.event_id == event_id)
+            .map_or(0, |e| e.rejected)
+    }
+}
+
+#[cfg(all(test, any(feature = "client", feature = "server")))]
+mod tests {
+    use super::*;
+
+    fn gear(payload: &[u8]) -> Result<(), &'static str> {
+        match payload {
+            [0..=3] => Ok(()),
+            [_] => Err("gear out of range"),
+            _ => Err("not one byte"),
+        }
+    }
+
+    #[test]
+    fn rejections_are_counted_per_event() {
+        let mut validators = EventValidators::<[u8]>::new();
+        assert!(validators.set(0x5B, 0x8001, Some(gear)));
+        assert_eq!(validators.validate(0x5B, 0x8001, &[2]), Ok(()));
+        assert_eq!(
+            validators.validate(0x5B, 0x8001, &[7]),
+            Err("gear out of range")
+        );
+        assert_eq!(validators.validate(0x5B, 0x8001, &[]), Err("not one byte"));
+        assert_eq!(
+            validators.validate(0x5B, 0x8002, &[7]),
+            Ok(()),
+            "no validator"
+        );
+        assert_eq!(validators.rejected(0x5B, 0x8001), 2);
+
+        assert!(validators.set(0x5B, 0x8001, None));
+        assert_eq!(validators.validate(0x5B, 0x8001, &[7]), Ok(()));
+        assert_eq!(validators.rejected(0x5B, 0x8001), 0);
+    }
+
+    #[test]
+    fn a_full_table_rejects_new_events() {
+        let mut validators = EventValidators::<[u8]>::new();
+        for event_id in 0..EVENT_VALIDATORS_CAP {
+            let event_id = u16::try_from(event_id).unwrap();
+            assert!(validators.set(0x5B, event_id, Some(gear)));
+        }
+        assert!(!validators.set(0x5B, u16::MAX, Some(gear)));
+        assert!(
+            validators.set(0x5B, 0, Some(gear)),
+            "replacing always succeeds"
+        );
+        assert!(validators.set(0x5B, 0, None), "clearing always succeeds");
+    }
+}
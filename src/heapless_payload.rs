@@ -218,6 +218,43 @@ impl PayloadWireFormat for HeaplessPayload {
         }
     }
 
+    fn push_subscription_entry(
+        header: &mut HeaplessSdHeader,
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        ttl: u32,
+        event_group_id: u16,
+    ) -> bool {
+        header
+            .entries
+            .push(sd::Entry::SubscribeEventGroup(sd::EventGroupEntry::new(
+                service_id,
+                instance_id,
+                major_version,
+                ttl,
+                event_group_id,
+            )))
+            .is_ok()
+    }
+
+    fn new_find_service_sd_header(
+        service_id: u16,
+        instance_id: u16,
+        reboot_flag: sd::RebootFlag,
+    ) -> Option<HeaplessSdHeader> {
+        let mut entries: HVec<sd::Entry, ENTRY_CAP> = HVec::new();
+        let _ = entries.push(sd::Entry::FindService(sd::ServiceEntry::find_instance(
+            service_id,
+            instance_id,
+        ))); // cap >= 1, never fails
+        Some(HeaplessSdHeader {
+            flags: sd::Flags::new_sd(reboot_flag),
+            entries,
+            options: HVec::new(),
+        })
+    }
+
     fn set_reboot_flag(header: &mut HeaplessSdHeader, reboot: sd::RebootFlag) {
         header.flags = sd::Flags::new(bool::from(reboot), header.flags.unicast());
     }
@@ -233,10 +270,8 @@ impl PayloadWireFormat for HeaplessPayload {
         for entry in &header.entries {
             if let sd::Entry::OfferService(svc) | sd::Entry::StopOfferService(svc) = entry {
                 let is_offer = matches!(entry, sd::Entry::OfferService(_));
-                let endpoint =
-                    sd::extract_ipv4_endpoint(&header.options).map(|(addr, protocol)| {
-                        crate::NetEndpoint::new(core::net::SocketAddr::V4(addr), protocol)
-                    });
+                let endpoint = sd::extract_endpoint(&header.options)
+                    .map(|(addr, protocol)| crate::NetEndpoint::new(addr, protocol));
                 f(crate::OfferedEndpoint {
                     service_id: svc.service_id,
                     instance_id: svc.instance_id,
@@ -244,6 +279,9 @@ impl PayloadWireFormat for HeaplessPayload {
                     minor_version: svc.minor_version,
                     endpoint,
                     is_offer,
+                    ttl: svc.ttl,
+                    configuration: sd::extract_configuration(&header.options),
+                    load_balancing: sd::extract_load_balancing(&header.options),
                 });
             }
         }
@@ -269,4 +307,25 @@ impl PayloadWireFormat for HeaplessPayload {
             f(svc, inst);
         }
     }
+
+    fn for_each_subscription_ack<F>(&self, mut f: F)
+    where
+        F: FnMut(crate::SubscriptionAck),
+    {
+        let header = match &self.kind {
+            HeaplessPayloadKind::Sd(header) => header,
+            HeaplessPayloadKind::Raw(_) => return,
+        };
+        for entry in &header.entries {
+            if let sd::Entry::SubscribeAckEventGroup(eg) = entry {
+                f(crate::SubscriptionAck {
+                    service_id: eg.service_id,
+                    instance_id: eg.instance_id,
+                    major_version: eg.major_version,
+                    event_group_id: eg.event_group_id,
+                    ttl: eg.ttl,
+                });
+            }
+        }
+    }
 }
@@ -0,0 +1,126 @@
+use core::time::Duration;
+
+/// Settings for the background janitor, enabled on the client with
+/// `Client::set_janitor` and on the server with
+/// `ServerConfig::with_janitor`.
+///
+/// Every `interval` the janitor closes TCP connections that carried no
+/// traffic for `tcp_idle_timeout` and drops SOME/IP-TP messages whose
+/// segments stopped coming.
+/// What it collected is counted in [`JanitorStats`]. Sweeps need a clock
+/// from [`Timer::now`](crate::Timer::now); without one the janitor does
+/// nothing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JanitorConfig {
+    /// Time between sweeps.
+    pub interval: Duration,
+    /// How long a TCP connection may stay silent before a sweep closes
+    /// it. Connections with requests awaiting a reply are kept. `None`
+    /// keeps idle connections open.
+    pub tcp_idle_timeout: Option<Duration>,
+}
+
+impl JanitorConfig {
+    /// Sweep every second, closing TCP connections silent for 30 s.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            tcp_idle_timeout: Some(Duration::from_secs(30)),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_tcp_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.tcp_idle_timeout = timeout;
+        self
+    }
+}
+
+impl Default for JanitorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the janitor has collected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JanitorStats {
+    /// Sweeps run.
+    pub sweeps: u64,
+    /// TCP connections closed for being idle.
+    pub tcp_connections_closed: u64,
+    /// Discovered offers removed after their TTL lapsed. The client
+    /// expires offers whether or not its janitor runs, and counts them
+    /// here either way.
+    pub discovery_entries_pruned: u64,
+    /// SOME/IP-TP messages dropped while still missing segments.
+    pub tp_messages_dropped: u64,
+}
+
+/// Sweep schedule of one janitor.
+#[cfg(any(feature = "client", feature = "server"))]
+#[derive(Debug)]
+pub(crate) struct Janitor {
+    pub(crate) config: JanitorConfig,
+    /// When the last sweep ran; `None` before the first.
+    last_sweep: Option<Duration>,
+}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl Janitor {
+    pub(crate) const fn new(config: JanitorConfig) -> Self {
+        Self {
+            config,
+            last_sweep: None,
+        }
+    }
+
+    /// `true` if a sweep is due at `now`; the caller then runs it.
+    pub(crate) fn start_sweep(&mut self, now: Duration) -> bool {
+        if self
+            .last_sweep
+            .is_some_and(|at| now.saturating_sub(at) < self.config.interval)
+        {
+            return false;
+        }
+        self.last_sweep = Some(now);
+        true
+    }
+
+    /// `true` if a TCP connection last active at `last_active` has been
+    /// idle too long at `now`.
+    pub(crate) fn is_idle(&self, last_active: Duration, now: Duration) -> bool {
+        self.config
+            .tcp_idle_timeout
+            .is_some_and(|timeout| now.saturating_sub(last_active) >= timeout)
+    }
+}
+
+#[cfg(all(test, any(feature = "client", feature = "server")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sweeps_once_per_interval_and_flags_idle_connections() {
+        let config = JanitorConfig::new()
+            .with_interval(Duration::from_secs(2))
+            .with_tcp_idle_timeout(Some(Duration::from_secs(5)));
+        let mut janitor = Janitor::new(config);
+        let at = Duration::from_secs;
+        assert!(janitor.start_sweep(at(10)), "first sweep runs at once");
+        assert!(!janitor.start_sweep(at(11)));
+        assert!(janitor.start_sweep(at(12)));
+
+        assert!(!janitor.is_idle(at(8), at(12)));
+        assert!(janitor.is_idle(at(7), at(12)));
+        let keep_open = Janitor::new(config.with_tcp_idle_timeout(None));
+        assert!(!keep_open.is_idle(at(0), at(1000)));
+    }
+}
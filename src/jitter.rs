@@ -0,0 +1,23 @@
+//! Pseudo-random draws for randomised SD delays, seeded from the clock.
+//!
+//! Not cryptographic: the draws only have to keep the clients and servers
+//! of an ECU, and ECUs that power up together, from picking the same
+//! delays.
+
+use core::time::Duration;
+
+/// A pseudo-random value from the clock and `seed`: the splitmix64
+/// finalizer over the time in nanoseconds mixed with the seed. Without a
+/// clock the same seed always draws the same value.
+pub(crate) fn draw(now: Option<Duration>, seed: u64) -> u64 {
+    let clock = now.map_or(0, |now| {
+        now.as_secs()
+            .wrapping_mul(1_000_000_000)
+            .wrapping_add(u64::from(now.subsec_nanos()))
+    });
+    let mut x = clock ^ seed;
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
@@ -0,0 +1,73 @@
+/// Source of the keys the crate's security layers use, looked up by ID.
+///
+/// The crate never stores keys itself: each use asks the provider, so an
+/// implementation backed by an HSM or SHE can keep them in protected
+/// storage and swap them at any time. Consumed by the `secoc` feature's
+/// `e2e::SecOcConfig`.
+///
+/// Fixed tables of `(key ID, key)` pairs implement it, for tests and for
+/// deployments with keys compiled in:
+///
+/// ```
+/// use simple_someip::keys::KeyProvider;
+///
+/// static KEYS: [(u16, [u8; 16]); 1] = [(7, [0x2B; 16])];
+///
+/// let mut key = [0u8; 16];
+/// assert_eq!(KEYS.key(7, &mut key), Some(16));
+/// assert_eq!(KEYS.key(8, &mut key), None);
+/// ```
+pub trait KeyProvider: Send + Sync {
+    /// Copy key `key_id` into `out` and return its length, or `None` if
+    /// the key is unavailable or longer than `out`.
+    fn key(&self, key_id: u16, out: &mut [u8]) -> Option<usize>;
+
+    /// Ask to be told whenever a key is rotated. Returns `false` if the
+    /// provider never rotates keys, which is what the default does.
+    fn on_rotate(&self, observer: &'static dyn KeyRotation) -> bool {
+        let _ = observer;
+        false
+    }
+}
+
+/// Told by a [`KeyProvider`] that a key was replaced, for layers that
+/// derive state from it, such as sessions to re-establish. Implemented for
+/// every `Fn(u16)` closure.
+pub trait KeyRotation: Send + Sync {
+    /// Key `key_id` has a new value.
+    fn rotated(&self, key_id: u16);
+}
+
+impl<F> KeyRotation for F
+where
+    F: Fn(u16) + Send + Sync,
+{
+    fn rotated(&self, key_id: u16) {
+        self(key_id);
+    }
+}
+
+impl<const LEN: usize, const KEYS: usize> KeyProvider for [(u16, [u8; LEN]); KEYS] {
+    fn key(&self, key_id: u16, out: &mut [u8]) -> Option<usize> {
+        let (_, key) = self.iter().find(|(id, _)| *id == key_id)?;
+        out.get_mut(..LEN)?.copy_from_slice(key);
+        Some(LEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_tables_refuse_short_buffers_and_do_not_rotate() {
+        static KEYS: [(u16, [u8; 4]); 2] = [(1, [1; 4]), (2, [2; 4])];
+        static ROTATION: fn(u16) = |_| {};
+
+        let mut out = [0u8; 8];
+        assert_eq!(KEYS.key(2, &mut out), Some(4));
+        assert_eq!(out[..4], [2; 4]);
+        assert_eq!(KEYS.key(1, &mut out[..3]), None);
+        assert!(!KEYS.on_rotate(&ROTATION));
+    }
+}
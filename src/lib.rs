@@ -75,6 +75,7 @@
 //! ```rust,no_run
 //! # #[cfg(feature = "client-tokio")]
 //! # fn wrapper() {
+//! use core::net::Ipv4Addr;
 //! use simple_someip::{Client, ClientUpdate, RawPayload};
 //!
 //! #[tokio::main]
@@ -83,15 +84,20 @@
 //!     // the run-loop future. Spawn the future on the tokio runtime;
 //!     // the returned future depends on `tokio::select!` / `tokio::time`
 //!     // / tokio sockets, so it is not executor-agnostic today.
-//!     let (client, mut updates, run) = Client::<RawPayload, _, _, _>::new([192, 168, 1, 100].into());
+//!     let (client, mut updates, run) = Client::<RawPayload, _, _, _>::new(Ipv4Addr::new(192, 168, 1, 100));
 //!     let _run_task = tokio::spawn(run);
 //!     client.bind_discovery().await.unwrap();
 //!
 //!     while let Some(update) = updates.recv().await {
 //!         match update {
 //!             ClientUpdate::DiscoveryUpdated(msg) => { /* SD message received */ }
-//!             ClientUpdate::Unicast { message, e2e_status, source } => { /* unicast reply */ }
+//!             ClientUpdate::Unicast { message, e2e_status, source, local_port } => { /* unicast reply */ }
 //!             ClientUpdate::SenderRebooted(addr) => { /* remote reboot */ }
+//!             ClientUpdate::OfferExpiring { key, remaining, .. } => { /* offer about to lapse */ }
+//!             ClientUpdate::ServiceAvailable { key, .. } => { /* instance discovered */ }
+//!             ClientUpdate::ServiceUnavailable { key, reason, .. } => { /* instance gone */ }
+//!             ClientUpdate::Reachability { key, reachable } => { /* probe result changed */ }
+//!             ClientUpdate::Resubscribed { key, event_group_id } => { /* offer came back */ }
 //!             ClientUpdate::Error(err) => { /* error */ }
 //!         }
 //!     }
@@ -149,6 +155,11 @@ extern crate std;
 #[cfg(feature = "_alloc")]
 extern crate alloc;
 
+/// Message corpus generators, timing helpers and the crate's decode hot
+/// paths, for benchmarking payload codecs against them.
+#[cfg(feature = "bench_support")]
+pub mod bench_support;
+
 /// Maximum size, in bytes, of UDP payloads for `client` / `server` send
 /// paths that serialize into a fixed-size buffer of this size.
 ///
@@ -186,20 +197,48 @@ pub mod buffer_pool;
 /// SOME/IP client for discovering services and exchanging messages.
 #[cfg(feature = "client")]
 pub mod client;
+/// Runtime configuration overlay: layers `SIMPLE_SOMEIP_*` environment
+/// variables over the configuration an application built in code.
+#[cfg(feature = "std")]
+pub mod config;
+/// Counts of datagrams the OS dropped for full socket receive queues,
+/// reported by both the client and the server.
+pub mod drop_stats;
 /// End-to-end (E2E) protection utilities for SOME/IP payloads.
 pub mod e2e;
+/// Per-event payload validators, run by the server before publishing and
+/// by the client before delivering a notification.
+pub mod event_validation;
 /// no_std / no-alloc [`PayloadWireFormat`] mirroring the std-only
 /// `RawPayload` with `heapless::Vec`-backed storage. Available whenever
 /// the `bare_metal` feature is enabled.
 #[cfg(feature = "bare_metal")]
 pub mod heapless_payload;
+/// Background sweeps that close idle TCP connections and drop expired
+/// discovery and SOME/IP-TP state, shared by the client and the server.
+pub mod janitor;
+/// Key lookup by ID for the security layers, so key storage stays with
+/// the integrator.
+pub mod keys;
 mod log;
 mod net_endpoint;
+/// Per-peer traffic counters and last-seen tracking, kept by both the
+/// client and the server.
+pub mod peer_stats;
 /// SOME/IP protocol primitives: headers, messages, return codes, and service discovery.
 pub mod protocol;
 /// A general-purpose, heap-allocated [`PayloadWireFormat`] implementation.
 #[cfg(feature = "std")]
 mod raw_payload;
+/// Scripting-friendly message exchange for hardware-in-the-loop rigs:
+/// build a message from a table of fields, send it, and await the reply
+/// matching a predicate, with plain types that bind into Python.
+#[cfg(all(feature = "std", feature = "client"))]
+pub mod script;
+/// Recording of a session's datagrams through the [`tap`] and rendering
+/// of it as a textual sequence diagram, for integration debugging.
+#[cfg(feature = "std")]
+pub mod sequence_diagram;
 /// SOME/IP server for offering services and handling incoming requests.
 ///
 /// The engine is generic over [`transport::TransportFactory`] +
@@ -213,6 +252,10 @@ mod raw_payload;
 /// `TokioTransport` / `TokioTimer`.
 #[cfg(feature = "server")]
 pub mod server;
+/// Raw datagram tap: a [`transport::TransportFactory`] wrapper that
+/// reports every datagram sent or received, for diagnostics and custom
+/// recorders.
+pub mod tap;
 /// Tokio + `socket2` implementation of the [`transport`] traits. Provided
 /// as the default `std` backend — available whenever `client-tokio` or
 /// `server-tokio` is enabled.
@@ -235,11 +278,15 @@ pub mod bare_metal_tasks;
 /// [`static_channels`] instead.
 #[cfg(feature = "embassy_channels")]
 pub mod embassy_channels;
+#[cfg(any(feature = "client", feature = "server"))]
+mod jitter;
 /// Pure, no-alloc SOME/IP + SD datagram codec: transport-agnostic
 /// builders/parsers used by the server receive loop, the firmware shim,
 /// and the spawnable futures in [`bare_metal_tasks`].
 #[cfg(any(feature = "bare_metal", feature = "server"))]
 pub mod sd_codec;
+#[cfg(any(feature = "client", feature = "server"))]
+mod sd_startup;
 /// Static-pool no-alloc primitives for [`transport::ChannelFactory`].
 /// Backs the consumer-declared static `OneshotPool` / `MpscPool`
 /// instances that the [`define_static_channels!`] macro
@@ -254,17 +301,22 @@ mod traits;
 /// because the target module is feature-gated and would break
 /// default-feature rustdoc builds.
 pub mod transport;
+pub use drop_stats::DropStats;
+pub use event_validation::EventValidator;
 #[cfg(feature = "bare_metal")]
 pub use heapless_payload::{HeaplessPayload, HeaplessSdHeader};
+pub use janitor::{JanitorConfig, JanitorStats};
 pub use net_endpoint::{NetEndpoint, TransportProtocol};
+pub use peer_stats::{PeerStats, PeerTable};
 #[cfg(feature = "std")]
 pub use raw_payload::{RawPayload, VecSdHeader};
-pub use traits::{OfferedEndpoint, PayloadWireFormat, WireFormat};
+pub use traits::{OfferedEndpoint, PayloadWireFormat, SubscriptionAck, WireFormat};
 
 #[cfg(feature = "client")]
 pub use client::{
-    Client, ClientDeps, ClientUpdate, ClientUpdates, DiscoveryMessage, PendingResponse,
-    ServiceEndpointKey,
+    Client, ClientConfig, ClientDeps, ClientUpdate, ClientUpdates, DiscoveryMessage, EventStream,
+    EventgroupLayout, FindPolicy, PendingResponse, ProbeConfig, ProviderSelection, RequestPolicy,
+    ServiceEndpointKey, UnavailableReason, VersionPolicy,
 };
 // `ClientChannelTypes`, `ControlMessage`, `SendMessage`, `ReceivedMessage`
 // are intentionally NOT re-exported at crate root — they are
@@ -280,13 +332,17 @@ pub use server::{
     NonSdRequestCallback, Server, ServerDeps, ServerHandles, ServerStorage, SubscriptionHandle,
 };
 #[cfg(any(feature = "client-tokio", feature = "server-tokio"))]
-pub use tokio_transport::{TokioChannels, TokioSocket, TokioSpawner, TokioTimer, TokioTransport};
+pub use tokio_transport::{
+    JoinSetSpawner, TokioChannels, TokioListener, TokioSocket, TokioSpawner, TokioTimer,
+    TokioTransport,
+};
 #[cfg(feature = "bare_metal")]
 pub use transport::AtomicInterfaceHandle;
 pub use transport::{
-    ChannelFactory, E2ERegistryHandle, InterfaceHandle, IoErrorKind, LocalSpawner, MpscRecv,
-    MpscSend, OneshotCancelled, OneshotRecv, OneshotSend, ReceivedDatagram, SocketOptions, Spawner,
-    Timer, TransportError, TransportFactory, TransportSocket, UnboundedRecv, UnboundedSend,
+    ChannelFactory, Deadline, E2ERegistryHandle, InterfaceHandle, IoErrorKind, LocalSpawner,
+    MpscRecv, MpscSend, NoStreams, OneshotCancelled, OneshotRecv, OneshotSend, ReceivedDatagram,
+    SocketOptions, Spawner, StreamFactory, StreamListener, Timer, TransportError, TransportFactory,
+    TransportSocket, UnboundedRecv, UnboundedSend,
 };
 #[cfg(feature = "bare_metal")]
 pub use transport::{StaticE2EHandle, StaticE2EStorage};
@@ -298,11 +354,7 @@ pub use transport::{StaticE2EHandle, StaticE2EStorage};
 /// Returns `default` when the variable is absent or empty.
 /// Panics at compile time if the string contains a non-digit character.
 ///
-/// Gated on `server`/`client`: every caller lives in the server module, the
-/// client module, or in the `bare-metal-runtime` runtime (which itself
-/// implies `server`), so a build with neither feature enabled would
-/// otherwise see it as dead code.
-#[cfg(any(feature = "server", feature = "client"))]
+/// Not feature-gated: the `protocol` decode-limit defaults use it too.
 pub(crate) const fn from_env_or(var: Option<&'static str>, default: usize) -> usize {
     match var {
         None => default,
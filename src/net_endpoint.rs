@@ -17,6 +17,7 @@ use core::net::SocketAddr;
 /// transport protocols the SOME/IP specification defines for endpoint
 /// options.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[non_exhaustive]
 pub enum TransportProtocol {
     /// UDP (IANA 0x11).
@@ -27,6 +28,7 @@ pub enum TransportProtocol {
 
 /// A full transport endpoint: socket address plus transport protocol.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NetEndpoint {
     /// IP address and port.
     pub addr: SocketAddr,
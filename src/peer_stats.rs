@@ -0,0 +1,299 @@
+use core::net::SocketAddr;
+use core::time::Duration;
+
+use heapless::index_map::FnvIndexMap;
+
+use crate::protocol::sd::RebootFlag;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_PEER_TABLE_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_PEER_TABLE_CAP: usize = 32;
+
+/// Maximum number of peers a [`PeerTable`] tracks. Must be a power of two
+/// ([`FnvIndexMap`] requirement). Sized via `SIMPLE_SOMEIP_PEER_TABLE_CAP`;
+/// defaults to 4 on bare-metal, 32 otherwise.
+pub const PEER_TABLE_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_PEER_TABLE_CAP"),
+    _DEFAULT_PEER_TABLE_CAP,
+);
+
+/// Traffic counters for one remote address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PeerStats {
+    /// Datagrams received from the peer, including ones that failed to
+    /// decode.
+    pub received: u64,
+    /// Datagrams sent to the peer.
+    pub sent: u64,
+    /// Datagrams from the peer that failed to decode, exceeded the decode
+    /// limits, or failed their E2E check.
+    pub errors: u64,
+    /// Of [`Self::errors`], messages whose payload length was outside the
+    /// [`PayloadRange`](crate::protocol::PayloadRange) declared for their
+    /// message ID.
+    pub out_of_range_payloads: u64,
+    /// Of [`Self::errors`], notifications whose payload the client's
+    /// [`EventValidator`](crate::EventValidator) for the event rejected.
+    pub rejected_payloads: u64,
+    /// Reboots detected from the peer's SD session counter and reboot flag.
+    pub reboots: u32,
+    /// [`Timer::now`](crate::Timer::now) when the peer was last heard
+    /// from. `None` if the timer has no clock.
+    pub last_seen: Option<Duration>,
+    /// Last SD `(session_id, reboot_flag)` per transport, multicast first.
+    sd_sessions: [Option<(u16, RebootFlag)>; 2],
+}
+
+impl PeerStats {
+    /// How long the peer has been silent at `now`. `None` without a
+    /// clock.
+    #[must_use]
+    pub fn silent_for(&self, now: Duration) -> Option<Duration> {
+        self.last_seen.map(|seen| now.saturating_sub(seen))
+    }
+}
+
+/// Per-peer statistics shared by the client and the server: which ECUs
+/// are talking, how much, and which went silent.
+///
+/// Bounded by [`PEER_TABLE_CAP`]. When full, a new peer replaces the one
+/// heard from least recently, so a burst of one-off senders cannot pin
+/// the table and hide the peers that matter.
+#[derive(Clone, Debug, Default)]
+pub struct PeerTable {
+    peers: FnvIndexMap<SocketAddr, PeerStats, PEER_TABLE_CAP>,
+}
+
+impl PeerTable {
+    /// An empty table.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            peers: FnvIndexMap::new(),
+        }
+    }
+
+    /// Statistics for `peer`, if it is tracked.
+    #[must_use]
+    pub fn get(&self, peer: &SocketAddr) -> Option<&PeerStats> {
+        self.peers.get(peer)
+    }
+
+    /// Every tracked peer, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&SocketAddr, &PeerStats)> {
+        self.peers.iter()
+    }
+
+    /// Peers not heard from for at least `threshold` at `now`. Peers with
+    /// no `last_seen` (no clock, or only ever sent to) are not reported.
+    pub fn silent(
+        &self,
+        now: Duration,
+        threshold: Duration,
+    ) -> impl Iterator<Item = (&SocketAddr, &PeerStats)> {
+        self.peers
+            .iter()
+            .filter(move |(_, stats)| stats.silent_for(now).is_some_and(|d| d >= threshold))
+    }
+
+    /// Number of tracked peers.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// `true` if no peer is tracked.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Forget every peer.
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+
+    /// Count a datagram received from `peer` at `now`.
+    pub fn record_received(&mut self, peer: SocketAddr, now: Option<Duration>) {
+        let stats = self.entry(peer);
+        stats.received += 1;
+        if now.is_some() {
+            stats.last_seen = now;
+        }
+    }
+
+    /// Count a datagram sent to `peer`.
+    pub fn record_sent(&mut self, peer: SocketAddr) {
+        self.entry(peer).sent += 1;
+    }
+
+    /// Count a datagram from `peer` that was malformed or failed its E2E
+    /// check.
+    pub fn record_error(&mut self, peer: SocketAddr) {
+        self.entry(peer).errors += 1;
+    }
+
+    /// Count a message from `peer` that `error` kept from decoding, as
+    /// [`Self::record_error`] does, also counting payloads out of their
+    /// declared range.
+    pub fn record_decode_error(&mut self, peer: SocketAddr, error: &crate::protocol::Error) {
+        let stats = self.entry(peer);
+        stats.errors += 1;
+        if matches!(error, crate::protocol::Error::PayloadOutOfRange { .. }) {
+            stats.out_of_range_payloads += 1;
+        }
+    }
+
+    /// Count a notification from `peer` whose payload failed validation,
+    /// as [`Self::record_error`] does.
+    pub fn record_rejected_payload(&mut self, peer: SocketAddr) {
+        let stats = self.entry(peer);
+        stats.errors += 1;
+        stats.rejected_payloads += 1;
+    }
+
+    /// Count a reboot of `peer` detected by the caller.
+    pub fn record_reboot(&mut self, peer: SocketAddr) {
+        self.entry(peer).reboots += 1;
+    }
+
+    /// Feed the SD header of a message from `peer` to reboot detection and
+    /// return `true` if it reveals a reboot (which is also counted).
+    ///
+    /// Uses the SOME/IP-SD rule: the reboot flag going from `Continuous`
+    /// to `RecentlyRebooted`, or the session ID going backwards while the
+    /// flag stays `RecentlyRebooted`. Multicast and unicast keep separate
+    /// session counters on the wire and are tracked separately here.
+    pub fn record_sd_session(
+        &mut self,
+        peer: SocketAddr,
+        unicast: bool,
+        session_id: u16,
+        reboot_flag: RebootFlag,
+    ) -> bool {
+        let stats = self.entry(peer);
+        let slot = &mut stats.sd_sessions[usize::from(unicast)];
+        let rebooted = match *slot {
+            Some((last_session, last_flag)) => {
+                reboot_flag == RebootFlag::RecentlyRebooted
+                    && (last_flag == RebootFlag::Continuous || session_id < last_session)
+            }
+            None => false,
+        };
+        *slot = Some((session_id, reboot_flag));
+        if rebooted {
+            stats.reboots += 1;
+        }
+        rebooted
+    }
+
+    fn entry(&mut self, peer: SocketAddr) -> &mut PeerStats {
+        if !self.peers.contains_key(&peer) && self.peers.len() == PEER_TABLE_CAP {
+            let stalest = self
+                .peers
+                .iter()
+                .min_by_key(|(_, stats)| stats.last_seen)
+                .map(|(addr, _)| *addr);
+            if let Some(stalest) = stalest {
+                self.peers.swap_remove(&stalest);
+            }
+        }
+        // Cannot fail: either `peer` is present or a slot was just freed.
+        match self.peers.entry(peer) {
+            heapless::index_map::Entry::Occupied(e) => e.into_mut(),
+            heapless::index_map::Entry::Vacant(e) => e
+                .insert(PeerStats::default())
+                .unwrap_or_else(|_| unreachable!("slot freed above")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::{Ipv4Addr, SocketAddrV4};
+
+    fn peer(n: u16) -> SocketAddr {
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), n))
+    }
+
+    #[test]
+    fn counts_traffic_and_reports_silent_peers() {
+        let mut table = PeerTable::new();
+        table.record_received(peer(1), Some(Duration::from_secs(1)));
+        table.record_received(peer(1), Some(Duration::from_secs(5)));
+        table.record_sent(peer(1));
+        table.record_error(peer(2));
+        table.record_received(peer(2), Some(Duration::from_secs(2)));
+
+        let one = table.get(&peer(1)).unwrap();
+        assert_eq!((one.received, one.sent, one.errors), (2, 1, 0));
+        assert_eq!(one.last_seen, Some(Duration::from_secs(5)));
+        assert_eq!(table.get(&peer(2)).unwrap().errors, 1);
+
+        let now = Duration::from_secs(10);
+        let silent: heapless::Vec<_, 2> = table
+            .silent(now, Duration::from_secs(6))
+            .map(|(addr, _)| *addr)
+            .collect();
+        assert_eq!(silent, [peer(2)]);
+        assert_eq!(one.silent_for(now), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn out_of_range_and_rejected_payloads_are_counted_as_errors() {
+        use crate::protocol::{Error, MessageId};
+
+        let mut table = PeerTable::new();
+        table.record_decode_error(peer(1), &Error::UnexpectedEof);
+        table.record_decode_error(
+            peer(1),
+            &Error::PayloadOutOfRange {
+                message_id: MessageId::new_from_service_and_method(0x1234, 0x0001),
+                len: 9,
+                min: 2,
+                max: 4,
+            },
+        );
+        table.record_rejected_payload(peer(1));
+        let stats = table.get(&peer(1)).unwrap();
+        assert_eq!(
+            (
+                stats.errors,
+                stats.out_of_range_payloads,
+                stats.rejected_payloads
+            ),
+            (3, 1, 1)
+        );
+    }
+
+    #[test]
+    fn sd_session_detects_reboots_per_transport() {
+        let mut table = PeerTable::new();
+        let p = peer(1);
+        assert!(!table.record_sd_session(p, false, 1, RebootFlag::RecentlyRebooted));
+        assert!(!table.record_sd_session(p, false, 2, RebootFlag::RecentlyRebooted));
+        // Unicast has its own counter: starting low there is not a reboot.
+        assert!(!table.record_sd_session(p, true, 1, RebootFlag::RecentlyRebooted));
+        // Counter went backwards with the flag still set.
+        assert!(table.record_sd_session(p, false, 1, RebootFlag::RecentlyRebooted));
+        assert!(!table.record_sd_session(p, false, 9, RebootFlag::Continuous));
+        // Continuous -> RecentlyRebooted.
+        assert!(table.record_sd_session(p, false, 10, RebootFlag::RecentlyRebooted));
+        assert_eq!(table.get(&p).unwrap().reboots, 2);
+    }
+
+    #[test]
+    fn full_table_evicts_least_recently_seen() {
+        let mut table = PeerTable::new();
+        let cap = u16::try_from(PEER_TABLE_CAP).unwrap();
+        for n in 0..cap {
+            table.record_received(peer(n), Some(Duration::from_secs(u64::from(n) + 1)));
+        }
+        table.record_received(peer(cap), Some(Duration::from_secs(100)));
+        assert_eq!(table.len(), PEER_TABLE_CAP);
+        assert!(table.get(&peer(0)).is_none(), "stalest peer evicted");
+        assert!(table.get(&peer(cap)).is_some());
+    }
+}
@@ -0,0 +1,107 @@
+use super::Error;
+
+/// Error payload of an `ERROR` message in the AUTOSAR `ApApplicationError`
+/// convention: a union whose one member (type 1) is a struct of the error
+/// domain value and the error code.
+///
+/// On the wire, big-endian: the union's length (12), its type (1), the
+/// 64-bit domain and the signed 32-bit code, 20 bytes in all.
+///
+/// ```
+/// use simple_someip::protocol::ApplicationError;
+///
+/// let error = ApplicationError::new(0x8000_0000_0000_0042, -3);
+/// let bytes = error.to_bytes();
+/// assert_eq!(bytes.len(), ApplicationError::ENCODED_LEN);
+/// assert_eq!(ApplicationError::parse(&bytes).unwrap(), error);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ApplicationError {
+    /// Identifies the error domain the code belongs to.
+    pub domain: u64,
+    /// Error code within `domain`.
+    pub code: i32,
+}
+
+impl ApplicationError {
+    /// Size of the encoded payload.
+    pub const ENCODED_LEN: usize = 20;
+
+    /// Union member carrying the domain and code.
+    const UNION_TYPE: u32 = 1;
+
+    /// Size of that member, the value of the union's length field.
+    const MEMBER_LEN: u32 = 12;
+
+    #[must_use]
+    pub const fn new(domain: u64, code: i32) -> Self {
+        Self { domain, code }
+    }
+
+    /// Encode as the payload of an `ERROR` message.
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0u8; Self::ENCODED_LEN];
+        bytes[0..4].copy_from_slice(&Self::MEMBER_LEN.to_be_bytes());
+        bytes[4..8].copy_from_slice(&Self::UNION_TYPE.to_be_bytes());
+        bytes[8..16].copy_from_slice(&self.domain.to_be_bytes());
+        bytes[16..20].copy_from_slice(&self.code.to_be_bytes());
+        bytes
+    }
+
+    /// Decode the payload of an `ERROR` message.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEof`] if `payload` is shorter than
+    /// [`Self::ENCODED_LEN`], and [`Error::InvalidErrorPayload`] if it is
+    /// a union of another layout.
+    pub fn parse(payload: &[u8]) -> Result<Self, Error> {
+        let Some(bytes) = payload.get(..Self::ENCODED_LEN) else {
+            return Err(Error::UnexpectedEof);
+        };
+        let length = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let union_type = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        if length != Self::MEMBER_LEN || union_type != Self::UNION_TYPE {
+            return Err(Error::InvalidErrorPayload { length, union_type });
+        }
+        let mut domain = [0u8; 8];
+        domain.copy_from_slice(&bytes[8..16]);
+        Ok(Self {
+            domain: u64::from_be_bytes(domain),
+            code: i32::from_be_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_other_layouts() {
+        let error = ApplicationError::new(0x0102_0304_0506_0708, -2);
+        let bytes = error.to_bytes();
+        assert_eq!(
+            bytes,
+            [
+                0, 0, 0, 12, 0, 0, 0, 1, 1, 2, 3, 4, 5, 6, 7, 8, 0xFF, 0xFF, 0xFF, 0xFE
+            ]
+        );
+        assert_eq!(ApplicationError::parse(&bytes).unwrap(), error);
+
+        assert!(matches!(
+            ApplicationError::parse(&bytes[..19]),
+            Err(Error::UnexpectedEof)
+        ));
+        let mut other = bytes;
+        other[7] = 2;
+        assert!(matches!(
+            ApplicationError::parse(&other),
+            Err(Error::InvalidErrorPayload {
+                length: 12,
+                union_type: 2
+            })
+        ));
+    }
+}
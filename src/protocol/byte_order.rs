@@ -3,6 +3,10 @@ use embedded_io::Error as _;
 
 /// Extension trait for reading big-endian values from a byte stream.
 ///
+/// SOME/IP is big-endian, but payloads gatewayed from other buses may
+/// carry little-endian fields; each `_be` method has an `_le` counterpart
+/// so such fields can be read where they occur.
+///
 /// The only required method is [`read_bytes`](ReadBytesExt::read_bytes).
 /// Backed by `embedded_io::Read` via a blanket impl.
 pub trait ReadBytesExt {
@@ -139,6 +143,116 @@ pub trait ReadBytesExt {
         self.read_bytes(&mut buf)?;
         Ok(f64::from_be_bytes(buf))
     }
+
+    /// Read a `u16` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_u16_le(&mut self) -> Result<u16, Error> {
+        let mut buf = [0u8; 2];
+        self.read_bytes(&mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    /// Read an `i16` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_i16_le(&mut self) -> Result<i16, Error> {
+        let mut buf = [0u8; 2];
+        self.read_bytes(&mut buf)?;
+        Ok(i16::from_le_bytes(buf))
+    }
+
+    /// Read the next 3 bytes as the lower 3 bytes of a `u32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_u24_le(&mut self) -> Result<u32, Error> {
+        let mut buf = [0u8; 3];
+        self.read_bytes(&mut buf)?;
+        Ok(u32::from_le_bytes([buf[0], buf[1], buf[2], 0]))
+    }
+
+    /// Read a `u32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_u32_le(&mut self) -> Result<u32, Error> {
+        let mut buf = [0u8; 4];
+        self.read_bytes(&mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    /// Read an `i32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_i32_le(&mut self) -> Result<i32, Error> {
+        let mut buf = [0u8; 4];
+        self.read_bytes(&mut buf)?;
+        Ok(i32::from_le_bytes(buf))
+    }
+
+    /// Read a `u64` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_u64_le(&mut self) -> Result<u64, Error> {
+        let mut buf = [0u8; 8];
+        self.read_bytes(&mut buf)?;
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    /// Read an `i64` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_i64_le(&mut self) -> Result<i64, Error> {
+        let mut buf = [0u8; 8];
+        self.read_bytes(&mut buf)?;
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    /// Read a `u128` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_u128_le(&mut self) -> Result<u128, Error> {
+        let mut buf = [0u8; 16];
+        self.read_bytes(&mut buf)?;
+        Ok(u128::from_le_bytes(buf))
+    }
+
+    /// Read an `i128` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_i128_le(&mut self) -> Result<i128, Error> {
+        let mut buf = [0u8; 16];
+        self.read_bytes(&mut buf)?;
+        Ok(i128::from_le_bytes(buf))
+    }
+
+    /// Read an `f32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_f32_le(&mut self) -> Result<f32, Error> {
+        let mut buf = [0u8; 4];
+        self.read_bytes(&mut buf)?;
+        Ok(f32::from_le_bytes(buf))
+    }
+
+    /// Read an `f64` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying reader fails.
+    fn read_f64_le(&mut self) -> Result<f64, Error> {
+        let mut buf = [0u8; 8];
+        self.read_bytes(&mut buf)?;
+        Ok(f64::from_le_bytes(buf))
+    }
 }
 
 impl<T: embedded_io::Read> ReadBytesExt for T {
@@ -150,7 +264,8 @@ impl<T: embedded_io::Read> ReadBytesExt for T {
     }
 }
 
-/// Extension trait for writing big-endian values to a byte stream.
+/// Extension trait for writing big-endian values to a byte stream, with
+/// `_le` counterparts for little-endian fields.
 ///
 /// The only required method is [`write_bytes`](WriteBytesExt::write_bytes).
 /// Backed by `embedded_io::Write` via a blanket impl.
@@ -264,6 +379,94 @@ pub trait WriteBytesExt {
     fn write_f64_be(&mut self, val: f64) -> Result<(), Error> {
         self.write_bytes(&val.to_be_bytes())
     }
+
+    /// Write a `u16` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_u16_le(&mut self, val: u16) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write an `i16` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_i16_le(&mut self, val: i16) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write the lower 3 bytes of a `u32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_u24_le(&mut self, val: u32) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes()[..3])
+    }
+
+    /// Write a `u32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_u32_le(&mut self, val: u32) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write an `i32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_i32_le(&mut self, val: i32) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write a `u64` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_u64_le(&mut self, val: u64) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write an `i64` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_i64_le(&mut self, val: i64) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write a `u128` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_u128_le(&mut self, val: u128) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write an `i128` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_i128_le(&mut self, val: i128) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write an `f32` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_f32_le(&mut self, val: f32) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
+
+    /// Write an `f64` in little-endian byte order.
+    ///
+    /// # Errors
+    /// Returns [`Error::Io`] if the underlying writer fails.
+    fn write_f64_le(&mut self, val: f64) -> Result<(), Error> {
+        self.write_bytes(&val.to_le_bytes())
+    }
 }
 
 impl<T: embedded_io::Write> WriteBytesExt for T {
@@ -514,6 +717,46 @@ mod tests {
 
     // --- Round-trip ---
 
+    // --- Little-endian ---
+
+    #[test]
+    fn little_endian_reads_reverse_big_endian_bytes() {
+        let buf: &[u8] = &[
+            0x02, 0x01, 0x03, 0x02, 0x01, 0x04, 0x03, 0x02, 0x01, 0xFE, 0xFF,
+        ];
+        let mut reader = buf;
+        assert_eq!(reader.read_u16_le().unwrap(), 0x0102);
+        assert_eq!(reader.read_u24_le().unwrap(), 0x01_0203);
+        assert_eq!(reader.read_u32_le().unwrap(), 0x0102_0304);
+        assert_eq!(reader.read_i16_le().unwrap(), -2);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn little_endian_writes_round_trip() {
+        let mut buf = [0u8; 49];
+        let mut writer: &mut [u8] = &mut buf;
+        writer.write_u16_le(0x0102).unwrap();
+        writer.write_u24_le(0x01_0203).unwrap();
+        writer.write_i32_le(-2).unwrap();
+        writer.write_u64_le(0x0102_0304_0506_0708).unwrap();
+        writer.write_i128_le(-3).unwrap();
+        writer.write_f32_le(1.5).unwrap();
+        writer.write_f64_le(-0.25).unwrap();
+        writer.write_u16_be(0x0102).unwrap();
+        assert_eq!(buf[..5], [0x02, 0x01, 0x03, 0x02, 0x01]);
+
+        let mut reader: &[u8] = &buf;
+        assert_eq!(reader.read_u16_le().unwrap(), 0x0102);
+        assert_eq!(reader.read_u24_le().unwrap(), 0x01_0203);
+        assert_eq!(reader.read_i32_le().unwrap(), -2);
+        assert_eq!(reader.read_u64_le().unwrap(), 0x0102_0304_0506_0708);
+        assert_eq!(reader.read_i128_le().unwrap(), -3);
+        assert_eq!(reader.read_f32_le().unwrap(), 1.5);
+        assert_eq!(reader.read_f64_le().unwrap(), -0.25);
+        assert_eq!(reader.read_u16_be().unwrap(), 0x0102, "mixed orders");
+    }
+
     #[test]
     fn round_trip_f32() {
         let val: f32 = core::f32::consts::PI;
@@ -0,0 +1,102 @@
+use core::net::IpAddr;
+
+/// How strictly the traffic of a peer or service is held to SOME/IP
+/// (AUTOSAR R23-11), for legacy stacks that deviate from it.
+///
+/// ```
+/// use simple_someip::protocol::{CompatibilityProfile, CompatibilityRule};
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// static RULES: [CompatibilityRule; 2] = [
+///     CompatibilityRule::for_peer(
+///         IpAddr::V4(Ipv4Addr::new(192, 168, 1, 30)),
+///         CompatibilityProfile::Legacy,
+///     ),
+///     CompatibilityRule::for_service(0x1234, CompatibilityProfile::Legacy),
+/// ];
+/// let peer = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 40));
+/// assert_eq!(
+///     CompatibilityRule::profile_for(&RULES, peer, 0x1234),
+///     CompatibilityProfile::Legacy
+/// );
+/// assert_eq!(
+///     CompatibilityRule::profile_for(&RULES, peer, 0x5B),
+///     CompatibilityProfile::Strict
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CompatibilityProfile {
+    /// R23-11 as written.
+    #[default]
+    Strict,
+    /// Stacks that predate R23-11:
+    /// - the length field of a non-SD message is not trusted: the payload
+    ///   is the rest of the datagram, whatever length it declares;
+    /// - a `SubscribeEventgroupAck` carries no options, so the multicast
+    ///   address of an event group is not announced in it.
+    Legacy,
+}
+
+impl CompatibilityProfile {
+    /// `true` if the payload of a non-SD message is the rest of the
+    /// datagram rather than what its length field declares.
+    #[must_use]
+    pub const fn lenient_length(self) -> bool {
+        matches!(self, Self::Legacy)
+    }
+
+    /// `true` if a `SubscribeEventgroupAck` may carry options.
+    #[must_use]
+    pub const fn ack_options(self) -> bool {
+        matches!(self, Self::Strict)
+    }
+}
+
+/// Selects a [`CompatibilityProfile`] for a peer, a service, or a service
+/// of one peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatibilityRule {
+    /// Peer the rule applies to; `None` for every peer.
+    pub peer: Option<IpAddr>,
+    /// Service the rule applies to; `None` for every service.
+    pub service_id: Option<u16>,
+    pub profile: CompatibilityProfile,
+}
+
+impl CompatibilityRule {
+    /// A rule for every service of `peer`.
+    #[must_use]
+    pub const fn for_peer(peer: IpAddr, profile: CompatibilityProfile) -> Self {
+        Self {
+            peer: Some(peer),
+            service_id: None,
+            profile,
+        }
+    }
+
+    /// A rule for `service_id`, whichever peer it comes from.
+    #[must_use]
+    pub const fn for_service(service_id: u16, profile: CompatibilityProfile) -> Self {
+        Self {
+            peer: None,
+            service_id: Some(service_id),
+            profile,
+        }
+    }
+
+    /// `true` if the rule covers `service_id` of `peer`.
+    #[must_use]
+    pub fn matches(&self, peer: IpAddr, service_id: u16) -> bool {
+        self.peer.is_none_or(|p| p == peer) && self.service_id.is_none_or(|s| s == service_id)
+    }
+
+    /// The profile of the first rule in `rules` covering `service_id` of
+    /// `peer`; [`CompatibilityProfile::Strict`] if none does.
+    #[must_use]
+    pub fn profile_for(rules: &[Self], peer: IpAddr, service_id: u16) -> CompatibilityProfile {
+        rules
+            .iter()
+            .find(|rule| rule.matches(peer, service_id))
+            .map_or(CompatibilityProfile::Strict, |rule| rule.profile)
+    }
+}
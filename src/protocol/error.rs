@@ -21,7 +21,42 @@ pub enum Error {
     /// The message ID is not supported by the payload implementation.
     #[error("Unsupported MessageID  {0:X?}")]
     UnsupportedMessageID(super::MessageId),
+    /// The declared payload length exceeds
+    /// [`DecodeLimits::max_payload_len`](super::DecodeLimits::max_payload_len).
+    #[error("Payload length {len} exceeds limit of {limit} bytes")]
+    PayloadTooLarge {
+        /// Payload length declared in the header.
+        len: usize,
+        /// Configured limit.
+        limit: usize,
+    },
+    /// The payload length is outside the
+    /// [`PayloadRange`](super::PayloadRange) declared for the message ID in
+    /// [`DecodeLimits::payload_ranges`](super::DecodeLimits::payload_ranges).
+    #[error("Payload length {len} of {message_id:X?} outside {min}..={max} bytes")]
+    PayloadOutOfRange {
+        message_id: super::MessageId,
+        /// Payload length declared in the header.
+        len: usize,
+        min: usize,
+        max: usize,
+    },
+    /// An `ERROR` message payload is not an
+    /// [`ApplicationError`](super::ApplicationError) union.
+    #[error("Invalid error payload: union of length {length} and type {union_type}")]
+    InvalidErrorPayload {
+        /// Union length field.
+        length: u32,
+        /// Union type field.
+        union_type: u32,
+    },
     /// A service discovery (SD) error occurred.
     #[error(transparent)]
     Sd(#[from] super::sd::Error),
+    /// A SOME/IP-TP segmentation or reassembly error occurred.
+    #[error(transparent)]
+    Tp(#[from] super::tp::Error),
+    /// A payload could not be serialized or deserialized.
+    #[error(transparent)]
+    Serialization(#[from] super::serialization::Error),
 }
@@ -260,10 +260,11 @@ impl<'a> HeaderView<'a> {
         u32::from_be_bytes([self.0[8], self.0[9], self.0[10], self.0[11]])
     }
 
-    /// Returns the payload size in bytes (`length - 8`).
+    /// Returns the payload size in bytes (`length - 8`, `0` for a length
+    /// field below 8).
     #[must_use]
     pub fn payload_size(&self) -> usize {
-        self.length() as usize - 8
+        (self.length() as usize).saturating_sub(8)
     }
 
     /// Returns header bytes 8..16: the request ID, protocol and interface
@@ -0,0 +1,182 @@
+use super::MessageId;
+
+/// Default for [`DecodeLimits::max_payload_len`]: the largest SOME/IP
+/// payload a single IPv4 UDP datagram can carry (65 507 bytes of UDP
+/// payload minus the 16-byte SOME/IP header). Override at build time with
+/// `SIMPLE_SOMEIP_MAX_PAYLOAD_LEN`.
+pub const DEFAULT_MAX_PAYLOAD_LEN: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_MAX_PAYLOAD_LEN"), 65_491);
+
+/// Default for [`DecodeLimits::max_sd_entries`]. A full-MTU SD datagram
+/// holds at most ~90 entries; override at build time with
+/// `SIMPLE_SOMEIP_MAX_SD_ENTRIES`.
+pub const DEFAULT_MAX_SD_ENTRIES: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_MAX_SD_ENTRIES"), 128);
+
+/// Default for [`DecodeLimits::max_sd_options`]. Override at build time
+/// with `SIMPLE_SOMEIP_MAX_SD_OPTIONS`.
+pub const DEFAULT_MAX_SD_OPTIONS: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_MAX_SD_OPTIONS"), 128);
+
+/// Upper bounds applied while decoding untrusted input.
+///
+/// The zero-copy views never allocate on their own, but everything
+/// downstream of them does work proportional to the declared sizes:
+/// `RawPayload` copies the payload and every SD entry/option into `Vec`s,
+/// and the SD handlers walk every entry. A crafted length or
+/// `entries_size` field therefore costs memory and CPU up to the size of
+/// the datagram. [`MessageView::parse_with_limits`](super::MessageView::parse_with_limits)
+/// and [`SdHeaderView::parse_with_limits`](super::sd::SdHeaderView::parse_with_limits)
+/// reject such input before anything else looks at it.
+///
+/// [`Default`] uses the `DEFAULT_*` constants, which are generous enough
+/// for any well-formed UDP traffic. [`Self::UNLIMITED`] only bounds input
+/// by the buffer it arrived in, which is what the plain `parse` functions
+/// do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Largest payload (bytes after the 16-byte header) accepted.
+    pub max_payload_len: usize,
+    /// Most SD entries accepted in one SD message.
+    pub max_sd_entries: usize,
+    /// Most SD options accepted in one SD message.
+    pub max_sd_options: usize,
+    /// Expected payload lengths of individual message IDs. A message whose
+    /// ID is listed is rejected unless its payload length is in range;
+    /// other messages are only bounded by [`Self::max_payload_len`].
+    /// Empty by default.
+    pub payload_ranges: &'static [PayloadRange],
+}
+
+/// Payload lengths accepted for one message ID, in
+/// [`DecodeLimits::payload_ranges`].
+///
+/// ```
+/// use simple_someip::protocol::{DecodeLimits, MessageId, PayloadRange};
+///
+/// static RANGES: [PayloadRange; 1] = [PayloadRange::new(
+///     MessageId::new_from_service_and_method(0x1234, 0x8001),
+///     4,
+///     8,
+/// )];
+/// let limits = DecodeLimits::default().with_payload_ranges(&RANGES);
+/// assert!(RANGES[0].contains(6));
+/// assert!(!RANGES[0].contains(9));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadRange {
+    /// Message ID whose payloads the range applies to.
+    pub message_id: MessageId,
+    /// Shortest payload accepted, in bytes.
+    pub min: usize,
+    /// Longest payload accepted, in bytes.
+    pub max: usize,
+}
+
+impl PayloadRange {
+    /// Accept payloads of `min` to `max` bytes, both inclusive, for
+    /// `message_id`.
+    ///
+    /// # Panics
+    ///
+    /// If `min > max`: such a range would accept no payload at all.
+    /// Evaluated in a `static` or `const`, that is a compile error.
+    #[must_use]
+    pub const fn new(message_id: MessageId, min: usize, max: usize) -> Self {
+        assert!(min <= max, "PayloadRange min exceeds max");
+        Self {
+            message_id,
+            min,
+            max,
+        }
+    }
+
+    /// `true` if a payload of `len` bytes is in range.
+    #[must_use]
+    pub const fn contains(&self, len: usize) -> bool {
+        self.min <= len && len <= self.max
+    }
+}
+
+impl DecodeLimits {
+    /// The `DEFAULT_*` limits and no payload ranges, as [`Default`]
+    /// gives, for `const` contexts.
+    pub const DEFAULT: Self = Self {
+        max_payload_len: DEFAULT_MAX_PAYLOAD_LEN,
+        max_sd_entries: DEFAULT_MAX_SD_ENTRIES,
+        max_sd_options: DEFAULT_MAX_SD_OPTIONS,
+        payload_ranges: &[],
+    };
+
+    /// No limits beyond the size of the input buffer.
+    pub const UNLIMITED: Self = Self {
+        max_payload_len: usize::MAX,
+        max_sd_entries: usize::MAX,
+        max_sd_options: usize::MAX,
+        payload_ranges: &[],
+    };
+
+    /// Set [`Self::max_payload_len`].
+    #[must_use]
+    pub const fn with_max_payload_len(mut self, max_payload_len: usize) -> Self {
+        self.max_payload_len = max_payload_len;
+        self
+    }
+
+    /// Set [`Self::max_sd_entries`].
+    #[must_use]
+    pub const fn with_max_sd_entries(mut self, max_sd_entries: usize) -> Self {
+        self.max_sd_entries = max_sd_entries;
+        self
+    }
+
+    /// Set [`Self::max_sd_options`].
+    #[must_use]
+    pub const fn with_max_sd_options(mut self, max_sd_options: usize) -> Self {
+        self.max_sd_options = max_sd_options;
+        self
+    }
+
+    /// Set [`Self::payload_ranges`].
+    #[must_use]
+    pub const fn with_payload_ranges(mut self, payload_ranges: &'static [PayloadRange]) -> Self {
+        self.payload_ranges = payload_ranges;
+        self
+    }
+
+    /// The range of [`Self::payload_ranges`] for `message_id`, if any.
+    #[must_use]
+    pub fn payload_range(&self, message_id: MessageId) -> Option<&PayloadRange> {
+        self.payload_ranges
+            .iter()
+            .find(|range| range.message_id == message_id)
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_range_bounds_are_inclusive() {
+        let range = PayloadRange::new(MessageId::new_from_service_and_method(0x1234, 1), 2, 4);
+        assert!(!range.contains(1));
+        assert!(range.contains(2));
+        assert!(range.contains(4));
+        assert!(!range.contains(5));
+        let exact = PayloadRange::new(MessageId::new_from_service_and_method(0x1234, 1), 3, 3);
+        assert!(exact.contains(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "PayloadRange min exceeds max")]
+    fn inverted_payload_range_is_rejected() {
+        let _ = PayloadRange::new(MessageId::new_from_service_and_method(0x1234, 1), 8, 4);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{
-    protocol::{Error, Header, MessageType, ReturnCode, header::HeaderView, sd::SdHeaderView},
+    protocol::{
+        CompatibilityProfile, DecodeLimits, Error, Header, MessageType, ReturnCode,
+        header::HeaderView, sd::SdHeaderView,
+    },
     traits::{PayloadWireFormat, WireFormat},
 };
 
@@ -81,9 +84,62 @@ impl<'a> MessageView<'a> {
     /// Returns an error if the header is invalid, the buffer is too short for the
     /// declared payload, or SD-specific validation fails.
     pub fn parse(buf: &'a [u8]) -> Result<Self, Error> {
+        Self::parse_with_limits(buf, &DecodeLimits::UNLIMITED)
+    }
+
+    /// [`Self::parse`], additionally rejecting input that exceeds `limits`.
+    ///
+    /// The payload length is checked before anything else reads the
+    /// payload, and SD messages have their entry and option counts
+    /// checked as in [`SdHeaderView::parse_with_limits`].
+    ///
+    /// # Errors
+    ///
+    /// Everything [`Self::parse`] returns, plus
+    /// [`Error::PayloadTooLarge`], [`Error::PayloadOutOfRange`],
+    /// [`sd::Error::TooManyEntries`](crate::protocol::sd::Error::TooManyEntries)
+    /// and [`sd::Error::TooManyOptions`](crate::protocol::sd::Error::TooManyOptions).
+    pub fn parse_with_limits(buf: &'a [u8], limits: &DecodeLimits) -> Result<Self, Error> {
+        Self::parse_with_profile(buf, limits, CompatibilityProfile::Strict)
+    }
+
+    /// [`Self::parse_with_limits`] for a message from a peer or service
+    /// under `profile`. With [`CompatibilityProfile::lenient_length`], the
+    /// payload of a non-SD message is the rest of `buf`, and the length
+    /// field is not checked; [`HeaderView::payload_size`] still reports
+    /// what it declares.
+    ///
+    /// # Errors
+    ///
+    /// Everything [`Self::parse_with_limits`] returns.
+    pub fn parse_with_profile(
+        buf: &'a [u8],
+        limits: &DecodeLimits,
+        profile: CompatibilityProfile,
+    ) -> Result<Self, Error> {
         let (header, remaining) = HeaderView::parse(buf)?;
-        let payload_size = header.payload_size();
+        let payload_size = if profile.lenient_length() && !header.is_sd() {
+            remaining.len()
+        } else {
+            header.payload_size()
+        };
 
+        if payload_size > limits.max_payload_len {
+            return Err(Error::PayloadTooLarge {
+                len: payload_size,
+                limit: limits.max_payload_len,
+            });
+        }
+        if let Some(range) = limits.payload_range(header.message_id())
+            && !range.contains(payload_size)
+        {
+            return Err(Error::PayloadOutOfRange {
+                message_id: range.message_id,
+                len: payload_size,
+                min: range.min,
+                max: range.max,
+            });
+        }
         if remaining.len() < payload_size {
             return Err(Error::UnexpectedEof);
         }
@@ -114,6 +170,9 @@ impl<'a> MessageView<'a> {
         }
 
         let payload = &remaining[..payload_size];
+        if header.is_sd() && *limits != DecodeLimits::UNLIMITED {
+            SdHeaderView::parse_with_limits(payload, limits)?;
+        }
         Ok(Self { header, payload })
     }
 
@@ -405,4 +464,124 @@ mod tests {
             )))
         ));
     }
+
+    // --- parse_with_limits ---
+
+    #[test]
+    fn parse_with_limits_rejects_oversized_payload_before_eof_check() {
+        let msg = make_sd_message();
+        let mut buf = [0u8; 64];
+        let n = msg.encode(&mut buf.as_mut_slice()).unwrap();
+        let limits = DecodeLimits::default().with_max_payload_len(12);
+        assert!(MessageView::parse_with_limits(&buf[..n], &limits).is_ok());
+
+        // Declare a ~4 GiB payload: a limit error, not a buffer walk.
+        buf[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            MessageView::parse_with_limits(&buf[..n], &limits),
+            Err(Error::PayloadTooLarge { limit: 12, .. })
+        ));
+        assert!(matches!(
+            MessageView::parse(&buf[..n]),
+            Err(Error::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn parse_with_limits_checks_declared_payload_ranges() {
+        static RANGES: [crate::protocol::PayloadRange; 1] = [crate::protocol::PayloadRange::new(
+            crate::protocol::MessageId::new_from_service_and_method(0x1234, 0x0001),
+            2,
+            4,
+        )];
+        let limits = DecodeLimits::default().with_payload_ranges(&RANGES);
+        let message = |method_id: u16, payload_len: usize| {
+            let mut buf = heapless::Vec::<u8, 32>::new();
+            buf.resize(16 + payload_len, 0).unwrap();
+            Header::new(
+                crate::protocol::MessageId::new_from_service_and_method(0x1234, method_id),
+                1,
+                1,
+                1,
+                crate::protocol::MessageTypeField::new(MessageType::Request, false),
+                ReturnCode::Ok,
+                payload_len,
+            )
+            .encode_to_slice(&mut buf)
+            .unwrap();
+            buf
+        };
+        for len in 2..=4 {
+            assert!(MessageView::parse_with_limits(&message(1, len), &limits).is_ok());
+        }
+        for len in [0, 1, 5] {
+            assert!(matches!(
+                MessageView::parse_with_limits(&message(1, len), &limits),
+                Err(Error::PayloadOutOfRange { min: 2, max: 4, .. })
+            ));
+        }
+        assert!(
+            MessageView::parse_with_limits(&message(2, 9), &limits).is_ok(),
+            "other methods are not ranged"
+        );
+    }
+
+    #[test]
+    fn parse_with_profile_takes_the_rest_of_a_legacy_datagram() {
+        let mut buf = [0u8; 16 + 6];
+        Header::new(
+            crate::protocol::MessageId::new_from_service_and_method(0x1234, 0x0001),
+            1,
+            1,
+            1,
+            crate::protocol::MessageTypeField::new(MessageType::Response, false),
+            ReturnCode::Ok,
+            6,
+        )
+        .encode_to_slice(&mut buf)
+        .unwrap();
+        let limits = DecodeLimits::default();
+
+        // Length field counting only the payload, as some stacks send it.
+        buf[4..8].copy_from_slice(&6u32.to_be_bytes());
+        let view = MessageView::parse_with_limits(&buf, &limits).unwrap();
+        assert_eq!(view.payload_bytes().len(), 0, "strict: trailing bytes left");
+        let view =
+            MessageView::parse_with_profile(&buf, &limits, CompatibilityProfile::Legacy).unwrap();
+        assert_eq!(view.payload_bytes().len(), 6);
+
+        // Length field past the end of the datagram.
+        buf[4..8].copy_from_slice(&20u32.to_be_bytes());
+        assert!(matches!(
+            MessageView::parse_with_limits(&buf, &limits),
+            Err(Error::UnexpectedEof)
+        ));
+        let view =
+            MessageView::parse_with_profile(&buf, &limits, CompatibilityProfile::Legacy).unwrap();
+        assert_eq!(view.payload_bytes().len(), 6);
+    }
+
+    #[test]
+    fn parse_with_limits_checks_sd_entry_count() {
+        let mut entries = heapless::Vec::<sd::Entry, 4>::new();
+        for service in 1..=2 {
+            entries
+                .push(sd::Entry::FindService(sd::ServiceEntry::find(service)))
+                .unwrap();
+        }
+        let sd_hdr = TestSdHeader {
+            flags: sd::Flags::new_sd(RebootFlag::RecentlyRebooted),
+            entries,
+            options: heapless::Vec::new(),
+        };
+        let msg = Msg::new_sd(0x42, &sd_hdr);
+        let mut buf = [0u8; 96];
+        let n = msg.encode(&mut buf.as_mut_slice()).unwrap();
+        let limits = DecodeLimits::default().with_max_sd_entries(1);
+        assert!(matches!(
+            MessageView::parse_with_limits(&buf[..n], &limits),
+            Err(Error::Sd(sd::Error::TooManyEntries { count: 2, limit: 1 }))
+        ));
+        assert!(MessageView::parse(&buf[..n]).is_ok());
+    }
 }
@@ -1,6 +1,9 @@
+mod application_error;
 pub mod byte_order;
+mod compat;
 mod error;
 mod header;
+mod limits;
 mod message;
 mod message_id;
 mod message_type;
@@ -9,11 +12,20 @@ mod return_code;
 /// Service Discovery
 pub mod sd;
 
+/// Payload serialization per the SOME/IP wire format rules
+pub mod serialization;
+
 /// SOME/IP-TP
 pub mod tp;
 
+pub use application_error::ApplicationError;
+pub use compat::{CompatibilityProfile, CompatibilityRule};
 pub use error::Error;
 pub use header::{Header, HeaderView};
+pub use limits::{
+    DEFAULT_MAX_PAYLOAD_LEN, DEFAULT_MAX_SD_ENTRIES, DEFAULT_MAX_SD_OPTIONS, DecodeLimits,
+    PayloadRange,
+};
 pub use message::{Message, MessageView};
 pub use message_id::MessageId;
 pub use message_type::{MessageType, MessageTypeField};
@@ -0,0 +1,322 @@
+use super::{
+    Entry, Error, Flags, Header, MAX_DATAGRAM_LEN, MAX_OPTIONS_PER_RUN, Options, OptionsCount,
+    TTL_INFINITE,
+};
+use crate::traits::WireFormat;
+
+/// Builds an SD message of up to `ENTRIES` entries that share up to
+/// `OPTIONS` options, and that fits one [`MAX_DATAGRAM_LEN`] datagram.
+///
+/// Each entry is pushed with the options of its two runs. A run already
+/// present in the message is referenced rather than repeated, and a run
+/// that starts with the options at the end of the message only appends
+/// the rest, so entries describing the same endpoint cost one option
+/// between them. The builder fills in each entry's
+/// `index_first_options_run`, `index_second_options_run` and
+/// `options_count`.
+///
+/// ```
+/// use simple_someip::protocol::sd::{
+///     Entry, Flags, MessageBuilder, Options, RebootFlag, ServiceEntry, TransportProtocol,
+/// };
+/// use std::net::Ipv4Addr;
+///
+/// let endpoint = Options::IpV4Endpoint {
+///     ip: Ipv4Addr::new(192, 168, 1, 10),
+///     protocol: TransportProtocol::Udp,
+///     port: 30500,
+/// };
+/// let offer = |service_id| {
+///     Entry::OfferService(ServiceEntry {
+///         service_id,
+///         instance_id: 1,
+///         major_version: 1,
+///         ttl: 3,
+///         minor_version: 0,
+///         ..ServiceEntry::find(service_id)
+///     })
+/// };
+/// let mut message = MessageBuilder::<4, 4>::new(Flags::new_sd(RebootFlag::Continuous));
+/// message.push(offer(0x1234), &[endpoint.clone()], &[]).unwrap();
+/// message.push(offer(0x5678), &[endpoint], &[]).unwrap();
+/// assert_eq!(message.entries().len(), 2);
+/// assert_eq!(message.options().len(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MessageBuilder<const ENTRIES: usize, const OPTIONS: usize> {
+    flags: Flags,
+    entries: heapless::Vec<Entry, ENTRIES>,
+    options: heapless::Vec<Options, OPTIONS>,
+}
+
+impl<const ENTRIES: usize, const OPTIONS: usize> MessageBuilder<ENTRIES, OPTIONS> {
+    /// An empty message with `flags`.
+    #[must_use]
+    pub const fn new(flags: Flags) -> Self {
+        Self {
+            flags,
+            entries: heapless::Vec::new(),
+            options: heapless::Vec::new(),
+        }
+    }
+
+    /// Append `entry` with `first_run` and `second_run` as its options
+    /// runs. The option indices and counts already in `entry` are
+    /// replaced.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::TooManyEntries`] or [`Error::TooManyOptions`] if the
+    /// message is full, [`Error::DatagramTooLarge`] if it would no longer
+    /// fit a [`MAX_DATAGRAM_LEN`] datagram, [`Error::OptionsRunTooLong`]
+    /// if a run is longer than [`MAX_OPTIONS_PER_RUN`] options and
+    /// [`Error::TtlOutOfRange`] if the entry's TTL is above
+    /// [`TTL_INFINITE`]. The message is left unchanged.
+    pub fn push(
+        &mut self,
+        mut entry: Entry,
+        first_run: &[Options],
+        second_run: &[Options],
+    ) -> Result<(), Error> {
+        let options_count = OptionsCount::new(run_len(first_run)?, run_len(second_run)?)?;
+        let ttl = match &entry {
+            Entry::FindService(service)
+            | Entry::OfferService(service)
+            | Entry::StopOfferService(service) => service.ttl,
+            Entry::SubscribeEventGroup(event_group)
+            | Entry::SubscribeAckEventGroup(event_group) => event_group.ttl,
+        };
+        if ttl > TTL_INFINITE {
+            return Err(Error::TtlOutOfRange(ttl));
+        }
+        if self.entries.is_full() {
+            return Err(Error::TooManyEntries {
+                count: ENTRIES + 1,
+                limit: ENTRIES,
+            });
+        }
+        let options_len = self.options.len();
+        let runs = self
+            .place_run(first_run)
+            .and_then(|first| Ok((first, self.place_run(second_run)?)));
+        let Ok((first_index, second_index)) = runs else {
+            self.options.truncate(options_len);
+            return Err(Error::TooManyOptions { limit: OPTIONS });
+        };
+        match &mut entry {
+            Entry::FindService(service)
+            | Entry::OfferService(service)
+            | Entry::StopOfferService(service) => {
+                service.index_first_options_run = first_index;
+                service.index_second_options_run = second_index;
+                service.options_count = options_count;
+            }
+            Entry::SubscribeEventGroup(event_group)
+            | Entry::SubscribeAckEventGroup(event_group) => {
+                event_group.index_first_options_run = first_index;
+                event_group.index_second_options_run = second_index;
+                event_group.options_count = options_count;
+            }
+        }
+        let _ = self.entries.push(entry);
+        let len = self.encoded_len();
+        if len > MAX_DATAGRAM_LEN {
+            self.entries.pop();
+            self.options.truncate(options_len);
+            return Err(Error::DatagramTooLarge {
+                len,
+                limit: MAX_DATAGRAM_LEN,
+            });
+        }
+        Ok(())
+    }
+
+    /// The length of the message encoded with its SOME/IP header, at most
+    /// [`MAX_DATAGRAM_LEN`].
+    #[must_use]
+    pub fn encoded_len(&self) -> usize {
+        16 + self.header().required_size()
+    }
+
+    /// Index of `run` in the options, appending what is missing. `Err`
+    /// if the options are full or the index does not fit in a byte.
+    fn place_run(&mut self, run: &[Options]) -> Result<u8, ()> {
+        if run.is_empty() {
+            return Ok(0);
+        }
+        let index = if let Some(index) = self
+            .options
+            .windows(run.len())
+            .position(|window| window == run)
+        {
+            index
+        } else {
+            // Longest prefix of `run` the options already end with.
+            let overlap = (1..run.len())
+                .rev()
+                .find(|&len| self.options.ends_with(&run[..len]))
+                .unwrap_or(0);
+            let index = self.options.len() - overlap;
+            for option in &run[overlap..] {
+                self.options.push(option.clone()).map_err(|_| ())?;
+            }
+            index
+        };
+        u8::try_from(index).map_err(|_| ())
+    }
+
+    /// The entries pushed so far.
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The options the entries reference.
+    #[must_use]
+    pub fn options(&self) -> &[Options] {
+        &self.options
+    }
+
+    /// `true` if no entry was pushed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The message as a [`Header`], ready to encode.
+    #[must_use]
+    pub fn header(&self) -> Header<'_> {
+        Header::new(self.flags, &self.entries, &self.options)
+    }
+}
+
+/// The length of `run` as an options count, if it fits one.
+fn run_len(run: &[Options]) -> Result<u8, Error> {
+    u8::try_from(run.len())
+        .ok()
+        .filter(|&len| len <= MAX_OPTIONS_PER_RUN)
+        .ok_or(Error::OptionsRunTooLong(run.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::sd::{
+        EventGroupEntry, RebootFlag, SdHeaderView, ServiceEntry, TransportProtocol,
+    };
+    use core::net::Ipv4Addr;
+
+    fn endpoint(port: u16) -> Options {
+        Options::IpV4Endpoint {
+            ip: Ipv4Addr::new(10, 0, 0, 1),
+            protocol: TransportProtocol::Udp,
+            port,
+        }
+    }
+
+    fn subscribe(event_group_id: u16) -> Entry {
+        Entry::SubscribeEventGroup(EventGroupEntry::new(0x1234, 1, 1, 3, event_group_id))
+    }
+
+    #[test]
+    fn shared_and_overlapping_runs_are_not_repeated() {
+        let mut message = MessageBuilder::<8, 8>::new(Flags::new_sd(RebootFlag::Continuous));
+        message.push(subscribe(1), &[endpoint(1)], &[]).unwrap();
+        message.push(subscribe(2), &[endpoint(1)], &[]).unwrap();
+        message
+            .push(subscribe(3), &[endpoint(1), endpoint(2)], &[])
+            .unwrap();
+        message
+            .push(
+                Entry::FindService(ServiceEntry::find_instance(0x1234, 1)),
+                &[],
+                &[],
+            )
+            .unwrap();
+        message
+            .push(subscribe(4), &[endpoint(3)], &[endpoint(2)])
+            .unwrap();
+        assert_eq!(message.options(), [endpoint(1), endpoint(2), endpoint(3)]);
+
+        let mut buf = [0u8; 256];
+        let len = message.header().encode_to_slice(&mut buf).unwrap();
+        let view = SdHeaderView::parse(&buf[..len]).unwrap();
+        let runs: heapless::Vec<_, 5> = view
+            .entries()
+            .map(|entry| {
+                let count = entry.options_count();
+                (
+                    entry.index_first_options_run(),
+                    count.first_options_count,
+                    entry.index_second_options_run(),
+                    count.second_options_count,
+                )
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                (0, 1, 0, 0),
+                (0, 1, 0, 0),
+                (0, 2, 0, 0),
+                (0, 0, 0, 0),
+                (2, 1, 1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn a_full_message_is_left_unchanged() {
+        let mut message = MessageBuilder::<2, 1>::new(Flags::new_sd(RebootFlag::Continuous));
+        message.push(subscribe(1), &[endpoint(1)], &[]).unwrap();
+        assert!(matches!(
+            message.push(subscribe(2), &[endpoint(1)], &[endpoint(2)]),
+            Err(Error::TooManyOptions { limit: 1 })
+        ));
+        assert_eq!(message.entries().len(), 1);
+        assert_eq!(message.options(), [endpoint(1)]);
+        message.push(subscribe(2), &[endpoint(1)], &[]).unwrap();
+        assert!(matches!(
+            message.push(subscribe(3), &[], &[]),
+            Err(Error::TooManyEntries { limit: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn wire_format_limits_are_errors() {
+        let mut message = MessageBuilder::<4, 32>::new(Flags::new_sd(RebootFlag::Continuous));
+        let run: heapless::Vec<_, 16> = (0..16).map(endpoint).collect();
+        assert!(matches!(
+            message.push(subscribe(1), &run, &[]),
+            Err(Error::OptionsRunTooLong(16))
+        ));
+        let mut entry = EventGroupEntry::new(0x1234, 1, 1, 3, 1);
+        entry.ttl = TTL_INFINITE + 1;
+        assert!(matches!(
+            message.push(Entry::SubscribeEventGroup(entry), &[], &[]),
+            Err(Error::TtlOutOfRange(ttl)) if ttl == TTL_INFINITE + 1
+        ));
+        message.push(subscribe(1), &run[..15], &[]).unwrap();
+        assert_eq!(message.options().len(), 15);
+    }
+
+    #[test]
+    fn a_message_is_bounded_by_the_datagram_size() {
+        use crate::protocol::sd::MAX_ENTRIES_PER_DATAGRAM;
+
+        let mut message = MessageBuilder::<128, 1>::new(Flags::new_sd(RebootFlag::Continuous));
+        for group in 0..MAX_ENTRIES_PER_DATAGRAM {
+            let group = u16::try_from(group).unwrap();
+            message.push(subscribe(group), &[], &[]).unwrap();
+        }
+        assert!(message.encoded_len() <= MAX_DATAGRAM_LEN);
+        assert!(matches!(
+            message.push(subscribe(0xFFFF), &[], &[]),
+            Err(Error::DatagramTooLarge {
+                limit: MAX_DATAGRAM_LEN,
+                ..
+            })
+        ));
+        assert_eq!(message.entries().len(), MAX_ENTRIES_PER_DATAGRAM);
+    }
+}
@@ -0,0 +1,223 @@
+//! DNS-SD style `key=value` strings carried by the SD configuration
+//! option.
+//!
+//! Each item is a length byte followed by that many bytes of `key` or
+//! `key=value`; a zero length byte ends the string. A key without `=` is
+//! present without a value, `key=` has an empty one.
+
+use super::Error;
+use super::options::MAX_CONFIGURATION_STRING_LENGTH;
+
+/// An SD configuration string, as sent in an
+/// [`Options::Configuration`](super::Options::Configuration) option.
+///
+/// ```
+/// use simple_someip::protocol::sd::ConfigurationString;
+///
+/// let mut configuration = ConfigurationString::new();
+/// configuration.push("mode", Some("fast")).unwrap();
+/// configuration.push("debug", None).unwrap();
+/// assert_eq!(configuration.get("mode"), Some(Some("fast")));
+/// assert_eq!(configuration.get("debug"), Some(None));
+/// assert_eq!(configuration.as_bytes(), b"\x09mode=fast\x05debug\x00");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigurationString {
+    /// Wire form: the items, then the terminating zero byte. Empty while
+    /// there are no items.
+    bytes: heapless::Vec<u8, MAX_CONFIGURATION_STRING_LENGTH>,
+}
+
+impl ConfigurationString {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bytes: heapless::Vec::new(),
+        }
+    }
+
+    /// Take a configuration string as received. Items are checked as they
+    /// are read by [`Self::entries`], not here.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ConfigurationStringTooLong`] if `bytes` is longer
+    /// than [`MAX_CONFIGURATION_STRING_LENGTH`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes = heapless::Vec::from_slice(bytes)
+            .map_err(|_| Error::ConfigurationStringTooLong(bytes.len()))?;
+        Ok(Self { bytes })
+    }
+
+    /// Append `key`, with `value` if `Some`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfigurationEntry`] if `key` is empty or
+    /// holds anything but printable ASCII other than `=`, or if the item
+    /// exceeds the 255 bytes its length byte can count, and
+    /// [`Error::ConfigurationStringTooLong`] if the string would outgrow
+    /// [`MAX_CONFIGURATION_STRING_LENGTH`]. The string is unchanged then.
+    pub fn push(&mut self, key: &str, value: Option<&str>) -> Result<(), Error> {
+        if key.is_empty() || !key.bytes().all(|b| (0x20..=0x7E).contains(&b) && b != b'=') {
+            return Err(Error::InvalidConfigurationEntry("invalid key"));
+        }
+        let item_len = key.len() + value.map_or(0, |value| 1 + value.len());
+        let Ok(len_byte) = u8::try_from(item_len) else {
+            return Err(Error::InvalidConfigurationEntry(
+                "item longer than 255 bytes",
+            ));
+        };
+        // The items so far, without the terminator.
+        let items = self.bytes.len().saturating_sub(1);
+        let total = items + 1 + item_len + 1;
+        if total > MAX_CONFIGURATION_STRING_LENGTH {
+            return Err(Error::ConfigurationStringTooLong(total));
+        }
+        self.bytes.truncate(items);
+        // Cannot fail: `total` was checked against the capacity above.
+        let _ = self.bytes.push(len_byte);
+        let _ = self.bytes.extend_from_slice(key.as_bytes());
+        if let Some(value) = value {
+            let _ = self.bytes.push(b'=');
+            let _ = self.bytes.extend_from_slice(value.as_bytes());
+        }
+        let _ = self.bytes.push(0);
+        Ok(())
+    }
+
+    /// The items in order, each as `(key, value)`.
+    #[must_use]
+    pub fn entries(&self) -> ConfigurationEntries<'_> {
+        ConfigurationEntries::new(&self.bytes)
+    }
+
+    /// The value of the first item named `key`: `Some(None)` if it has
+    /// none, `None` if there is no such item.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Option<&str>> {
+        self.entries()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// `true` if there are no items.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries().next().is_none()
+    }
+
+    /// The wire form: the items and the terminating zero byte, or nothing
+    /// if there are no items.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Iterator over the `(key, value)` items of a configuration string,
+/// returned by [`ConfigurationString::entries`] and
+/// [`Options::configuration_entries`](super::Options::configuration_entries).
+///
+/// Ends at the terminating zero byte, at the end of the string, or at the
+/// first item that is cut short or not UTF-8.
+#[derive(Clone, Debug)]
+pub struct ConfigurationEntries<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> ConfigurationEntries<'a> {
+    /// Read the items of the configuration string `bytes`.
+    #[must_use]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { remaining: bytes }
+    }
+}
+
+impl<'a> Iterator for ConfigurationEntries<'a> {
+    type Item = (&'a str, Option<&'a str>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&len, rest) = self.remaining.split_first()?;
+        let item = rest
+            .get(..usize::from(len))
+            .filter(|_| len != 0)
+            .and_then(|item| core::str::from_utf8(item).ok());
+        let Some(item) = item else {
+            self.remaining = &[];
+            return None;
+        };
+        self.remaining = &rest[usize::from(len)..];
+        Some(match item.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (item, None),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn items_round_trip_through_the_wire_form() {
+        let mut configuration = ConfigurationString::new();
+        assert!(configuration.is_empty());
+        assert_eq!(configuration.as_bytes(), b"");
+        configuration.push("a", Some("1")).unwrap();
+        configuration.push("flag", None).unwrap();
+        configuration.push("empty", Some("")).unwrap();
+        configuration.push("url", Some("x=y")).unwrap();
+        assert_eq!(
+            configuration.as_bytes(),
+            b"\x03a=1\x04flag\x06empty=\x07url=x=y\x00"
+        );
+
+        let received = ConfigurationString::from_bytes(configuration.as_bytes()).unwrap();
+        assert_eq!(
+            received.entries().collect::<heapless::Vec<_, 4>>(),
+            [
+                ("a", Some("1")),
+                ("flag", None),
+                ("empty", Some("")),
+                ("url", Some("x=y")),
+            ]
+        );
+        assert_eq!(received.get("url"), Some(Some("x=y")));
+        assert_eq!(received.get("missing"), None);
+    }
+
+    #[test]
+    fn invalid_items_are_refused_and_leave_the_string_unchanged() {
+        let mut configuration = ConfigurationString::new();
+        configuration.push("a", None).unwrap();
+        for key in ["", "a=b", "tab\t"] {
+            assert!(matches!(
+                configuration.push(key, None),
+                Err(Error::InvalidConfigurationEntry(_))
+            ));
+        }
+        let long = "v".repeat(255);
+        assert!(matches!(
+            configuration.push("k", Some(&long)),
+            Err(Error::InvalidConfigurationEntry(_))
+        ));
+        assert_eq!(configuration.as_bytes(), b"\x01a\x00");
+
+        let half = "v".repeat(MAX_CONFIGURATION_STRING_LENGTH / 2);
+        configuration.push("b", Some(&half)).unwrap();
+        let before = configuration.clone();
+        assert!(matches!(
+            configuration.push("c", Some(&half)),
+            Err(Error::ConfigurationStringTooLong(_))
+        ));
+        assert_eq!(configuration, before);
+    }
+
+    #[test]
+    fn reading_stops_at_a_truncated_item() {
+        let entries: heapless::Vec<_, 1> = ConfigurationEntries::new(b"\x03a=1\x09cut").collect();
+        assert_eq!(entries, [("a", Some("1"))]);
+        assert_eq!(ConfigurationEntries::new(b"\x00\x03a=1").count(), 0);
+    }
+}
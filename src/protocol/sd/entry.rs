@@ -1,6 +1,7 @@
 use super::Error;
 use crate::{protocol::byte_order::WriteBytesExt, traits::WireFormat};
 
+/// Size of an encoded SD entry in bytes.
 pub const ENTRY_SIZE: usize = 16;
 
 /// The type of an SD entry.
@@ -73,16 +74,37 @@ impl From<OptionsCount> for u8 {
 }
 
 impl OptionsCount {
-    /// # Panics
-    /// Panics if either count is >= 16 (each count must fit in a 4-bit nibble).
-    #[must_use]
-    pub const fn new(first_options_count: u8, second_options_count: u8) -> Self {
-        assert!(first_options_count < 16);
-        assert!(second_options_count < 16);
-        OptionsCount {
+    /// No options in either run.
+    pub const NONE: Self = Self {
+        first_options_count: 0,
+        second_options_count: 0,
+    };
+
+    /// One option in the first run, none in the second: the usual
+    /// endpoint option of an offer or subscription.
+    pub const ONE: Self = Self {
+        first_options_count: 1,
+        second_options_count: 0,
+    };
+
+    /// Counts of `first_options_count` and `second_options_count`
+    /// options.
+    ///
+    /// # Errors
+    /// [`Error::OptionsRunTooLong`] if either count is above
+    /// [`MAX_OPTIONS_PER_RUN`](super::MAX_OPTIONS_PER_RUN) (each count
+    /// must fit in a 4-bit nibble).
+    pub const fn new(first_options_count: u8, second_options_count: u8) -> Result<Self, Error> {
+        if first_options_count > super::MAX_OPTIONS_PER_RUN {
+            return Err(Error::OptionsRunTooLong(first_options_count as usize));
+        }
+        if second_options_count > super::MAX_OPTIONS_PER_RUN {
+            return Err(Error::OptionsRunTooLong(second_options_count as usize));
+        }
+        Ok(OptionsCount {
             first_options_count,
             second_options_count,
-        }
+        })
     }
 }
 
@@ -101,7 +123,8 @@ pub struct EventGroupEntry {
     pub instance_id: u16,
     /// The major version of the service interface.
     pub major_version: u8,
-    /// Time-to-live in seconds (24-bit value).
+    /// Time-to-live in seconds (24-bit value). [`TTL_INFINITE`](super::TTL_INFINITE)
+    /// and above mean "until explicitly stopped".
     pub ttl: u32,
     /// Event group counter.
     pub counter: u16,
@@ -122,7 +145,7 @@ impl EventGroupEntry {
         Self {
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: OptionsCount::new(1, 0),
+            options_count: OptionsCount::ONE,
             service_id,
             instance_id,
             major_version,
@@ -131,6 +154,19 @@ impl EventGroupEntry {
             event_group_id,
         }
     }
+
+    /// Creates a `StopSubscribeEventgroup` entry: a subscribe entry with
+    /// TTL 0. Send it with the endpoint option the subscription was made
+    /// with; see [`Header::new_stop_subscription`](super::Header::new_stop_subscription).
+    #[must_use]
+    pub const fn new_stop(
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        event_group_id: u16,
+    ) -> Self {
+        Self::new(service_id, instance_id, major_version, 0, event_group_id)
+    }
 }
 
 impl WireFormat for EventGroupEntry {
@@ -148,7 +184,7 @@ impl WireFormat for EventGroupEntry {
         writer.write_u16_be(self.service_id)?;
         writer.write_u16_be(self.instance_id)?;
         writer.write_u8(self.major_version)?;
-        writer.write_u24_be(self.ttl)?;
+        writer.write_u24_be(self.ttl.min(super::TTL_INFINITE))?;
         writer.write_u16_be(self.counter)?;
         writer.write_u16_be(self.event_group_id)?;
         Ok(16)
@@ -170,7 +206,8 @@ pub struct ServiceEntry {
     pub instance_id: u16,
     /// The major version of the service interface.
     pub major_version: u8,
-    /// Time-to-live in seconds (24-bit value).
+    /// Time-to-live in seconds (24-bit value). [`TTL_INFINITE`](super::TTL_INFINITE)
+    /// and above mean "until explicitly stopped".
     pub ttl: u32,
     /// The minor version of the service interface.
     pub minor_version: u32,
@@ -183,12 +220,23 @@ impl ServiceEntry {
         Self {
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: OptionsCount::new(1, 0),
+            options_count: OptionsCount::ONE,
             service_id,
             instance_id: 0xFFFF,
-            major_version: 0xFF,
+            major_version: super::ANY_MAJOR_VERSION,
             ttl: 0x00FF_FFFF,
-            minor_version: 0xFFFF_FFFF,
+            minor_version: super::ANY_MINOR_VERSION,
+        }
+    }
+
+    /// Creates a `FindService` entry for one instance (`0xFFFF` for any),
+    /// any version, referencing no options.
+    #[must_use]
+    pub const fn find_instance(service_id: u16, instance_id: u16) -> Self {
+        Self {
+            options_count: OptionsCount::NONE,
+            instance_id,
+            ..Self::find(service_id)
         }
     }
 }
@@ -208,7 +256,7 @@ impl WireFormat for ServiceEntry {
         writer.write_u16_be(self.service_id)?;
         writer.write_u16_be(self.instance_id)?;
         writer.write_u8(self.major_version)?;
-        writer.write_u24_be(self.ttl)?;
+        writer.write_u24_be(self.ttl.min(super::TTL_INFINITE))?;
         writer.write_u32_be(self.minor_version)?;
         Ok(16)
     }
@@ -491,7 +539,7 @@ mod tests {
         ServiceEntry {
             index_first_options_run: 1,
             index_second_options_run: 2,
-            options_count: OptionsCount::new(3, 4),
+            options_count: OptionsCount::new(3, 4).unwrap(),
             service_id: 0x1234,
             instance_id: 0x5678,
             major_version: 0x01,
@@ -504,7 +552,7 @@ mod tests {
         EventGroupEntry {
             index_first_options_run: 1,
             index_second_options_run: 2,
-            options_count: OptionsCount::new(3, 4),
+            options_count: OptionsCount::new(3, 4).unwrap(),
             service_id: 0xABCD,
             instance_id: 0x0001,
             major_version: 0x02,
@@ -549,13 +597,26 @@ mod tests {
 
     #[test]
     fn options_count_round_trip() {
-        let oc = OptionsCount::new(3, 7);
+        let oc = OptionsCount::new(3, 7).unwrap();
         let byte = u8::from(oc);
         let decoded = OptionsCount::from(byte);
         assert_eq!(decoded.first_options_count, 3);
         assert_eq!(decoded.second_options_count, 7);
     }
 
+    #[test]
+    fn options_count_rejects_counts_above_a_nibble() {
+        assert!(OptionsCount::new(15, 15).is_ok());
+        assert!(matches!(
+            OptionsCount::new(16, 0),
+            Err(Error::OptionsRunTooLong(16))
+        ));
+        assert!(matches!(
+            OptionsCount::new(0, 200),
+            Err(Error::OptionsRunTooLong(200))
+        ));
+    }
+
     // --- required_size ---
 
     #[test]
@@ -631,6 +692,26 @@ mod tests {
         assert_eq!(view.to_owned().unwrap(), entry);
     }
 
+    #[test]
+    fn ttl_above_24_bits_encodes_as_infinite() {
+        use crate::protocol::sd::{TTL_INFINITE, ttl_lifetime};
+
+        let mut service = make_service_entry();
+        service.ttl = 0x0100_0000;
+        let mut group = make_event_group_entry();
+        group.ttl = u32::MAX;
+        for entry in [
+            Entry::OfferService(service),
+            Entry::SubscribeEventGroup(group),
+        ] {
+            let buf = encode_entry(&entry);
+            let entry_bytes: &[u8; ENTRY_SIZE] = buf[..ENTRY_SIZE].try_into().unwrap();
+            assert_eq!(EntryView(entry_bytes).ttl(), TTL_INFINITE);
+        }
+        assert_eq!(ttl_lifetime(3), Some(core::time::Duration::from_secs(3)));
+        assert_eq!(ttl_lifetime(TTL_INFINITE), None);
+    }
+
     #[test]
     fn offer_service_entry_round_trips() {
         let entry = Entry::OfferService(make_service_entry());
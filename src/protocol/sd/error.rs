@@ -30,10 +30,47 @@ pub enum Error {
     /// A configuration string exceeds the maximum allowed length.
     #[error("Configuration string too long: {0} bytes")]
     ConfigurationStringTooLong(usize),
+    /// A configuration string item is malformed.
+    #[error("Invalid configuration string entry: {0}")]
+    InvalidConfigurationEntry(&'static str),
     /// An SD message failed structural validation.
     #[error("Invalid SD message: {0}")]
     InvalidMessage(&'static str),
     /// The entries array length is not a multiple of the entry size (16 bytes).
     #[error("Entries array length {0} is not a multiple of entry size (16)")]
     IncorrectEntriesSize(usize),
+    /// The SD message carries more entries than
+    /// [`DecodeLimits::max_sd_entries`](crate::protocol::DecodeLimits::max_sd_entries).
+    #[error("SD message has {count} entries, limit is {limit}")]
+    TooManyEntries {
+        /// Number of entries declared by `entries_size`.
+        count: usize,
+        /// Configured limit.
+        limit: usize,
+    },
+    /// The SD message carries more options than
+    /// [`DecodeLimits::max_sd_options`](crate::protocol::DecodeLimits::max_sd_options).
+    #[error("SD message has more than {limit} options")]
+    TooManyOptions {
+        /// Configured limit.
+        limit: usize,
+    },
+    /// An options run is longer than
+    /// [`MAX_OPTIONS_PER_RUN`](super::MAX_OPTIONS_PER_RUN), so its count
+    /// does not fit the 4-bit field of the entry.
+    #[error("options run of {0} options, at most 15 fit an entry")]
+    OptionsRunTooLong(usize),
+    /// An entry TTL above [`TTL_INFINITE`](super::TTL_INFINITE), the
+    /// largest value of the 24-bit field.
+    #[error("entry TTL {0} does not fit 24 bits")]
+    TtlOutOfRange(u32),
+    /// The SD message would encode to more than
+    /// [`MAX_DATAGRAM_LEN`](super::MAX_DATAGRAM_LEN) bytes.
+    #[error("SD datagram of {len} bytes, limit is {limit}")]
+    DatagramTooLarge {
+        /// Encoded length, SOME/IP header included.
+        len: usize,
+        /// The limit.
+        limit: usize,
+    },
 }
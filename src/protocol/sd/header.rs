@@ -1,9 +1,10 @@
+use crate::protocol::DecodeLimits;
 use crate::protocol::byte_order::WriteBytesExt;
 
 use crate::traits::WireFormat;
 
 use super::{
-    Entry, Flags, Options,
+    Entry, Flags, Options, RebootFlag,
     entry::{ENTRY_SIZE, EntryIter, EntryType},
     options::{OptionIter, validate_option},
 };
@@ -32,6 +33,29 @@ impl<'a> Header<'a> {
             options,
         }
     }
+
+    /// Creates a `StopSubscribeEventgroup` message header.
+    ///
+    /// Each of `entries` must be a `SubscribeEventGroup` built with
+    /// [`EventGroupEntry::new_stop`](super::EventGroupEntry::new_stop)
+    /// (TTL 0), and `options` must carry the endpoint the subscription was
+    /// made from: servers key subscribers by that endpoint, so a stop
+    /// naming a different one removes nothing. Servers do not answer a
+    /// stop-subscribe.
+    #[must_use]
+    pub fn new_stop_subscription(
+        reboot: RebootFlag,
+        entries: &'a [Entry],
+        options: &'a [Options],
+    ) -> Self {
+        debug_assert!(
+            entries
+                .iter()
+                .all(|e| matches!(e, Entry::SubscribeEventGroup(eg) if eg.ttl == 0)),
+            "stop-subscription entries must be SubscribeEventGroup with TTL 0"
+        );
+        Self::new(Flags::new_sd(reboot), entries, options)
+    }
 }
 
 /// Zero-copy view into an SD header payload.
@@ -62,6 +86,22 @@ impl<'a> SdHeaderView<'a> {
     /// any entry type byte is invalid, or any option has an invalid type, length, or
     /// transport protocol byte.
     pub fn parse(buf: &'a [u8]) -> Result<Self, crate::protocol::Error> {
+        Self::parse_with_limits(buf, &DecodeLimits::UNLIMITED)
+    }
+
+    /// [`Self::parse`], additionally rejecting SD messages with more
+    /// entries or options than `limits` allows. The entry count is checked
+    /// before any entry is inspected.
+    ///
+    /// # Errors
+    ///
+    /// Everything [`Self::parse`] returns, plus
+    /// [`Error::TooManyEntries`](super::Error::TooManyEntries) and
+    /// [`Error::TooManyOptions`](super::Error::TooManyOptions).
+    pub fn parse_with_limits(
+        buf: &'a [u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, crate::protocol::Error> {
         // Minimum: 4 (flags+reserved) + 4 (entries_size) + 4 (options_size) = 12
         if buf.len() < 12 {
             return Err(crate::protocol::Error::UnexpectedEof);
@@ -76,8 +116,19 @@ impl<'a> SdHeaderView<'a> {
             return Err(super::Error::IncorrectEntriesSize(entries_size).into());
         }
 
-        // Need entries data + 4 bytes for options_size field
-        if buf.len() < 8 + entries_size + 4 {
+        let entry_count = entries_size / ENTRY_SIZE;
+        if entry_count > limits.max_sd_entries {
+            return Err(super::Error::TooManyEntries {
+                count: entry_count,
+                limit: limits.max_sd_entries,
+            }
+            .into());
+        }
+
+        // Need entries data + 4 bytes for options_size field. The sizes
+        // come off the wire as `u32`, so the sums are checked: on 32-bit
+        // targets they can wrap past `buf.len()`.
+        if (buf.len() - 12) < entries_size {
             return Err(crate::protocol::Error::UnexpectedEof);
         }
 
@@ -99,7 +150,7 @@ impl<'a> SdHeaderView<'a> {
         ]) as usize;
 
         let options_start = options_size_offset + 4;
-        if buf.len() < options_start + options_size {
+        if buf.len() - options_start < options_size {
             return Err(crate::protocol::Error::UnexpectedEof);
         }
 
@@ -107,7 +158,15 @@ impl<'a> SdHeaderView<'a> {
 
         // Validate all options
         let mut opt_offset = 0;
+        let mut option_count = 0;
         while opt_offset < options_size {
+            if option_count == limits.max_sd_options {
+                return Err(super::Error::TooManyOptions {
+                    limit: limits.max_sd_options,
+                }
+                .into());
+            }
+            option_count += 1;
             let remaining = &options_buf[opt_offset..];
             let wire_size = validate_option(remaining)?;
             opt_offset += wire_size;
@@ -227,6 +286,27 @@ mod tests {
         assert!(h.options.is_empty());
     }
 
+    #[test]
+    fn stop_subscription_encodes_subscribe_with_zero_ttl() {
+        let entries = [Entry::SubscribeEventGroup(EventGroupEntry::new_stop(
+            0x1234, 1, 1, 0x0010,
+        ))];
+        let options = [Options::IpV4Endpoint {
+            ip: Ipv4Addr::LOCALHOST,
+            protocol: TransportProtocol::Udp,
+            port: 40000,
+        }];
+        let header = Header::new_stop_subscription(RebootFlag::Continuous, &entries, &options);
+        let mut buf = [0u8; 64];
+        let n = header.encode_to_slice(&mut buf).unwrap();
+        let view = SdHeaderView::parse(&buf[..n]).unwrap();
+        let entry = view.entries().next().unwrap();
+        assert_eq!(entry.entry_type().unwrap(), EntryType::Subscribe);
+        assert_eq!(entry.ttl(), 0);
+        assert_eq!(entry.event_group_id(), 0x0010);
+        assert_eq!(view.options().count(), 1);
+    }
+
     #[test]
     fn service_offer_round_trips() {
         let ip = Ipv4Addr::new(192, 168, 1, 10);
@@ -237,7 +317,7 @@ mod tests {
             ttl: 0xFF_FFFF,
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: OptionsCount::new(1, 0),
+            options_count: OptionsCount::ONE,
             minor_version: 0,
         });
         let endpoint = Options::IpV4Endpoint {
@@ -284,7 +364,7 @@ mod tests {
             ttl: 0xFF_FFFF,
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: OptionsCount::new(1, 0),
+            options_count: OptionsCount::ONE,
             minor_version: 0,
         });
         let endpoint = Options::IpV4Endpoint {
@@ -383,4 +463,57 @@ mod tests {
             ))
         ));
     }
+
+    #[test]
+    fn parse_with_limits_rejects_excess_entries_and_options() {
+        let entries = [
+            Entry::FindService(ServiceEntry::find(0x0001)),
+            Entry::FindService(ServiceEntry::find(0x0002)),
+        ];
+        let endpoint = Options::IpV4Endpoint {
+            ip: Ipv4Addr::LOCALHOST,
+            protocol: TransportProtocol::Udp,
+            port: 30509,
+        };
+        let options = [endpoint.clone(), endpoint];
+        let h = Header::new(Flags::new_sd(RebootFlag::Continuous), &entries, &options);
+        let mut buf = [0u8; 128];
+        let n = h.encode(&mut buf.as_mut_slice()).unwrap();
+
+        let exact = DecodeLimits::UNLIMITED
+            .with_max_sd_entries(2)
+            .with_max_sd_options(2);
+        assert!(SdHeaderView::parse_with_limits(&buf[..n], &exact).is_ok());
+        assert!(matches!(
+            SdHeaderView::parse_with_limits(&buf[..n], &exact.with_max_sd_entries(1)),
+            Err(crate::protocol::Error::Sd(SdError::TooManyEntries {
+                count: 2,
+                limit: 1
+            }))
+        ));
+        assert!(matches!(
+            SdHeaderView::parse_with_limits(&buf[..n], &exact.with_max_sd_options(1)),
+            Err(crate::protocol::Error::Sd(SdError::TooManyOptions {
+                limit: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn parse_huge_declared_sizes_is_eof_not_overflow() {
+        let max_entries = u32::MAX - u32::MAX % 16;
+        assert!(matches!(
+            SdHeaderView::parse(&raw_header(max_entries, 0)),
+            Err(crate::protocol::Error::UnexpectedEof)
+        ));
+        assert!(matches!(
+            SdHeaderView::parse(&raw_header(0, u32::MAX)),
+            Err(crate::protocol::Error::UnexpectedEof)
+        ));
+        // The entry limit fires before the length check.
+        assert!(matches!(
+            SdHeaderView::parse_with_limits(&raw_header(max_entries, 0), &DecodeLimits::default()),
+            Err(crate::protocol::Error::Sd(SdError::TooManyEntries { .. }))
+        ));
+    }
 }
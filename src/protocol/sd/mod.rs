@@ -1,3 +1,5 @@
+mod builder;
+mod configuration;
 mod entry;
 mod error;
 mod flags;
@@ -15,16 +17,59 @@ pub const MULTICAST_IP: Ipv4Addr = Ipv4Addr::new(239, 255, 0, 255);
 pub const MULTICAST_PORT: u16 = 30490;
 /// SOME/IP Message ID for service discovery messages (`0xFFFF_8100`).
 pub const MESSAGE_ID_VALUE: u32 = 0xffff_8100;
+/// Major-version wildcard (`0xFF`): in `FindService` and
+/// `SubscribeEventgroup` entries, matches any offered major version.
+pub const ANY_MAJOR_VERSION: u8 = 0xFF;
+/// Minor-version wildcard (`0xFFFF_FFFF`) for `FindService` entries.
+pub const ANY_MINOR_VERSION: u32 = 0xFFFF_FFFF;
+/// Entry TTL meaning "valid until explicitly stopped" — the largest
+/// value the 24-bit TTL field can carry. Entries encode any larger TTL as
+/// this value rather than truncating it.
+pub const TTL_INFINITE: u32 = 0xFF_FFFF;
+
+/// Longest options run an entry can reference: the count of each of its
+/// two runs is a 4-bit field.
+pub const MAX_OPTIONS_PER_RUN: u8 = 15;
+/// Largest SD datagram, SOME/IP header included, that fits an Ethernet
+/// MTU of 1500 bytes without IP fragmentation: 1500 less the 20-byte IPv4
+/// and 8-byte UDP headers.
+pub const MAX_DATAGRAM_LEN: usize = 1472;
+/// Most entries a [`MAX_DATAGRAM_LEN`] SD datagram can carry: the bytes
+/// left after the 16-byte SOME/IP header and the 12 bytes of SD flags and
+/// array lengths, in [`ENTRY_SIZE`]-byte entries, with no options.
+pub const MAX_ENTRIES_PER_DATAGRAM: usize = (MAX_DATAGRAM_LEN - 16 - 12) / ENTRY_SIZE;
+
+/// How long an entry with `ttl` stays valid, or `None` for
+/// [`TTL_INFINITE`] (and anything above it): such an offer never lapses
+/// and such a subscription needs no renewal.
+#[must_use]
+pub const fn ttl_lifetime(ttl: u32) -> Option<core::time::Duration> {
+    if ttl >= TTL_INFINITE {
+        None
+    } else {
+        Some(core::time::Duration::from_secs(ttl as u64))
+    }
+}
+
+/// `true` if a request for `requested` major version (possibly
+/// [`ANY_MAJOR_VERSION`]) is satisfied by a provider offering `offered`.
+#[must_use]
+pub const fn major_version_matches(offered: u8, requested: u8) -> bool {
+    requested == ANY_MAJOR_VERSION || requested == offered
+}
 
 // Export all definitions from the service discovery mod
 
+pub use builder::MessageBuilder;
+pub use configuration::{ConfigurationEntries, ConfigurationString};
 pub use entry::{
-    Entry, EntryIter, EntryType, EntryView, EventGroupEntry, OptionsCount, ServiceEntry,
+    ENTRY_SIZE, Entry, EntryIter, EntryType, EntryView, EventGroupEntry, OptionsCount, ServiceEntry,
 };
 pub use error::Error;
 pub use flags::{Flags, RebootFlag};
 pub use header::{Header, SdHeaderView};
 pub use options::{
     MAX_CONFIGURATION_STRING_LENGTH, OptionIter, OptionType, OptionView, Options,
-    TransportProtocol, extract_ipv4_endpoint,
+    TransportProtocol, extract_configuration, extract_endpoint, extract_ipv4_endpoint,
+    extract_load_balancing,
 };
@@ -1,6 +1,6 @@
 use core::net::{Ipv4Addr, Ipv6Addr};
 
-use super::Error;
+use super::{ConfigurationEntries, ConfigurationString, Error};
 use crate::protocol::byte_order::WriteBytesExt;
 
 /// Maximum length of an SD configuration option string in bytes.
@@ -227,6 +227,28 @@ impl Options {
         }
     }
 
+    /// A configuration option carrying `configuration`.
+    #[must_use]
+    pub fn configuration(configuration: &ConfigurationString) -> Self {
+        Options::Configuration {
+            // Cannot fail: both are bounded by `MAX_CONFIGURATION_STRING_LENGTH`.
+            configuration_string: heapless::Vec::from_slice(configuration.as_bytes())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The `(key, value)` items of a configuration option, or `None` for
+    /// any other option.
+    #[must_use]
+    pub fn configuration_entries(&self) -> Option<ConfigurationEntries<'_>> {
+        match self {
+            Options::Configuration {
+                configuration_string,
+            } => Some(ConfigurationEntries::new(configuration_string)),
+            _ => None,
+        }
+    }
+
     /// Serializes this option to a writer.
     ///
     /// # Errors
@@ -330,6 +352,54 @@ pub fn extract_ipv4_endpoint(
     })
 }
 
+/// Extract the first `IpV4Endpoint` from a slice of owned options, or
+/// failing that the first `IpV6Endpoint`.
+///
+/// Returns `None` if neither is present.
+#[must_use]
+pub fn extract_endpoint(options: &[Options]) -> Option<(core::net::SocketAddr, TransportProtocol)> {
+    extract_ipv4_endpoint(options)
+        .map(|(addr, protocol)| (core::net::SocketAddr::V4(addr), protocol))
+        .or_else(|| {
+            options.iter().find_map(|opt| match opt {
+                Options::IpV6Endpoint { ip, protocol, port } => Some((
+                    core::net::SocketAddr::V6(core::net::SocketAddrV6::new(*ip, *port, 0, 0)),
+                    *protocol,
+                )),
+                _ => None,
+            })
+        })
+}
+
+/// Extract the first `LoadBalancing` option from a slice of owned
+/// options, as `(priority, weight)`.
+///
+/// Returns `None` if no `LoadBalancing` option is present.
+#[must_use]
+pub fn extract_load_balancing(options: &[Options]) -> Option<(u16, u16)> {
+    options.iter().find_map(|opt| match opt {
+        Options::LoadBalancing { priority, weight } => Some((*priority, *weight)),
+        _ => None,
+    })
+}
+
+/// Extract the first `Configuration` option from a slice of owned
+/// options.
+///
+/// Returns an empty [`ConfigurationString`] if none is present.
+#[must_use]
+pub fn extract_configuration(options: &[Options]) -> ConfigurationString {
+    options
+        .iter()
+        .find_map(|opt| match opt {
+            Options::Configuration {
+                configuration_string,
+            } => ConfigurationString::from_bytes(configuration_string).ok(),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
 // --- Zero-copy view types ---
 
 /// Zero-copy view into a variable-length SD option in a buffer.
@@ -679,6 +749,26 @@ mod tests {
         round_trip(&option);
     }
 
+    #[test]
+    fn configuration_entries_round_trip() {
+        let mut configuration = ConfigurationString::new();
+        configuration.push("mode", Some("fast")).unwrap();
+        configuration.push("debug", None).unwrap();
+        let option = Options::configuration(&configuration);
+        round_trip(&option);
+        assert!(
+            option
+                .configuration_entries()
+                .unwrap()
+                .eq(configuration.entries())
+        );
+        let other = Options::LoadBalancing {
+            priority: 0,
+            weight: 0,
+        };
+        assert!(other.configuration_entries().is_none());
+    }
+
     #[test]
     fn load_balancing_round_trip() {
         let option = Options::LoadBalancing {
@@ -708,6 +798,41 @@ mod tests {
         round_trip(&option);
     }
 
+    #[test]
+    fn extract_endpoint_prefers_ipv4_then_ipv6() {
+        let v6 = Options::IpV6Endpoint {
+            ip: Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0x10),
+            protocol: TransportProtocol::Udp,
+            port: 30509,
+        };
+        let v4 = Options::IpV4Endpoint {
+            ip: Ipv4Addr::new(192, 168, 1, 10),
+            protocol: TransportProtocol::Tcp,
+            port: 30510,
+        };
+        assert_eq!(
+            extract_endpoint(&[v6.clone(), v4]),
+            Some((
+                core::net::SocketAddr::from(([192, 168, 1, 10], 30510)),
+                TransportProtocol::Tcp
+            ))
+        );
+        assert_eq!(
+            extract_endpoint(&[
+                Options::LoadBalancing {
+                    priority: 1,
+                    weight: 1
+                },
+                v6
+            ]),
+            Some((
+                core::net::SocketAddr::from((Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0x10), 30509)),
+                TransportProtocol::Udp
+            ))
+        );
+        assert_eq!(extract_endpoint(&[]), None);
+    }
+
     #[test]
     fn ipv4_multicast_round_trip() {
         let option = Options::IpV4Multicast {
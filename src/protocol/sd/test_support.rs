@@ -113,6 +113,24 @@ impl PayloadWireFormat for TestPayload {
             options,
         }
     }
+    fn new_find_service_sd_header(
+        service_id: u16,
+        instance_id: u16,
+        reboot_flag: sd::RebootFlag,
+    ) -> Option<TestSdHeader> {
+        let mut entries = heapless::Vec::new();
+        entries
+            .push(sd::Entry::FindService(sd::ServiceEntry::find_instance(
+                service_id,
+                instance_id,
+            )))
+            .unwrap();
+        Some(TestSdHeader {
+            flags: sd::Flags::new_sd(reboot_flag),
+            entries,
+            options: heapless::Vec::new(),
+        })
+    }
     fn set_reboot_flag(header: &mut TestSdHeader, reboot: sd::RebootFlag) {
         header.flags = sd::Flags::new(bool::from(reboot), header.flags.unicast());
     }
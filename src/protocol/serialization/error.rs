@@ -0,0 +1,67 @@
+use thiserror::Error;
+
+use super::LengthField;
+
+/// Errors that can occur when serializing or deserializing a SOME/IP
+/// payload with a [`Writer`](super::Writer) or [`Reader`](super::Reader).
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The writer's buffer cannot take `needed` more bytes.
+    #[error("Serialization buffer too small: {needed} more bytes needed, {available} left")]
+    BufferTooSmall {
+        /// Bytes the write needed.
+        needed: usize,
+        /// Bytes left in the buffer.
+        available: usize,
+    },
+    /// The payload ended before `needed` more bytes could be read.
+    #[error("Payload ended: {needed} more bytes needed, {available} left")]
+    UnexpectedEnd {
+        /// Bytes the read needed.
+        needed: usize,
+        /// Bytes left in the payload.
+        available: usize,
+    },
+    /// A value of `len` bytes does not fit the length field in front of
+    /// it.
+    #[error("Length {len} does not fit a {field:?} length field")]
+    LengthOverflow {
+        /// Length to be written.
+        len: usize,
+        /// The length field it was to be written to.
+        field: LengthField,
+    },
+    /// A string of `len` bytes, byte order mark and terminator included,
+    /// does not fit its fixed length of `max` bytes.
+    #[error("String of {len} bytes exceeds its fixed length of {max} bytes")]
+    TooLong {
+        /// Bytes the string takes.
+        len: usize,
+        /// Its fixed length.
+        max: usize,
+    },
+    /// A boolean other than `0` or `1`.
+    #[error("Invalid boolean value {0}")]
+    InvalidBool(u8),
+    /// A string does not start with the byte order mark of its encoding.
+    #[error("String without byte order mark")]
+    MissingBom,
+    /// A string does not end with a zero terminator.
+    #[error("String without terminator")]
+    MissingTerminator,
+    /// A UTF-8 string holds invalid UTF-8, or a UTF-16 one an unpaired
+    /// surrogate.
+    #[error("Invalid string encoding")]
+    InvalidEncoding,
+    /// A TLV data ID above
+    /// [`MAX_DATA_ID`](super::tlv::MAX_DATA_ID).
+    #[error("TLV data ID 0x{0:04X} exceeds 12 bits")]
+    InvalidDataId(u16),
+    /// A TLV member that needs a length field was given
+    /// [`LengthField::None`].
+    #[error("TLV member without length field")]
+    NoLengthField,
+    /// A UTF-16 string has an odd number of bytes.
+    #[error("UTF-16 string of odd length {0}")]
+    OddUtf16Length(usize),
+}
@@ -0,0 +1,358 @@
+//! Payload serialization following the SOME/IP wire format rules, for
+//! implementing [`PayloadWireFormat`](crate::PayloadWireFormat) on
+//! application types.
+//!
+//! A [`Writer`] fills a byte slice and a [`Reader`] walks one, without
+//! allocating:
+//!
+//! - basic types are big-endian, booleans one byte of `0` or `1`; the
+//!   `_le` methods read and write little-endian fields of gatewayed
+//!   payloads;
+//! - fixed-length arrays are their elements back to back; dynamic-length
+//!   arrays and structs are preceded by a [`LengthField`] counting their
+//!   bytes, written with [`Writer::write_with_length`] and read with
+//!   [`Reader::read_with_length`];
+//! - strings carry a byte order mark and a zero terminator, in UTF-8 or
+//!   UTF-16 of either byte order, with a length field or padded to a
+//!   fixed length;
+//! - a nested struct is its members in order, so it composes by writing
+//!   and reading them one after another, or through
+//!   [`SomeIpSerialize`] and [`SomeIpDeserialize`];
+//! - members of an extensible struct can be written as [`tlv`] members,
+//!   which readers that do not know them skip.
+//!
+//! ```
+//! use simple_someip::protocol::serialization::{LengthField, Reader, Writer};
+//!
+//! let mut buf = [0u8; 32];
+//! let mut writer = Writer::new(&mut buf);
+//! writer.write_u16(0x0102).unwrap();
+//! writer
+//!     .write_with_length(LengthField::U32, |w| {
+//!         (1u8..=3).try_for_each(|x| w.write_u8(x))
+//!     })
+//!     .unwrap();
+//! writer.write_utf8_string("hi", LengthField::U32).unwrap();
+//! let len = writer.position();
+//!
+//! let mut reader = Reader::new(&buf[..len]);
+//! assert_eq!(reader.read_u16().unwrap(), 0x0102);
+//! let mut array = reader.read_with_length(LengthField::U32).unwrap();
+//! assert_eq!(array.remaining(), 3);
+//! assert_eq!(array.read_u8().unwrap(), 1);
+//! assert_eq!(reader.read_utf8_string(LengthField::U32).unwrap(), "hi");
+//! assert!(reader.is_empty());
+//! ```
+//!
+//! [`Writer`]: crate::protocol::serialization::Writer
+//! [`Reader`]: crate::protocol::serialization::Reader
+//! [`LengthField`]: crate::protocol::serialization::LengthField
+//! [`Writer::write_with_length`]: crate::protocol::serialization::Writer::write_with_length
+//! [`Reader::read_with_length`]: crate::protocol::serialization::Reader::read_with_length
+//! [`SomeIpSerialize`]: crate::protocol::serialization::SomeIpSerialize
+//! [`SomeIpDeserialize`]: crate::protocol::serialization::SomeIpDeserialize
+//! [`tlv`]: crate::protocol::serialization::tlv
+
+mod error;
+mod reader;
+pub mod tlv;
+mod writer;
+
+pub use error::Error;
+pub use reader::{Reader, Utf16Str};
+pub use writer::Writer;
+
+/// UTF-8 byte order mark, in front of every UTF-8 string.
+pub const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// Size of the length field in front of a dynamic-length array, string
+/// or struct. The field counts the bytes that follow it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LengthField {
+    /// No length field: only for structs, whose length is known.
+    None,
+    U8,
+    U16,
+    /// The default for dynamic-length arrays and strings.
+    #[default]
+    U32,
+}
+
+impl LengthField {
+    /// Bytes the field itself takes.
+    #[must_use]
+    pub const fn size(self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::U32 => 4,
+        }
+    }
+
+    /// Largest length the field holds.
+    #[must_use]
+    pub const fn max_len(self) -> usize {
+        match self {
+            Self::None => usize::MAX,
+            Self::U8 => u8::MAX as usize,
+            Self::U16 => u16::MAX as usize,
+            Self::U32 => u32::MAX as usize,
+        }
+    }
+}
+
+/// Byte order of a UTF-16 string, told apart on the wire by its byte
+/// order mark.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ByteOrder {
+    #[default]
+    BigEndian,
+    LittleEndian,
+}
+
+/// A type that writes itself with a [`Writer`], e.g. a struct writing its
+/// members in order.
+pub trait SomeIpSerialize {
+    /// Write `self` at the writer's position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer's buffer is too small, or a length
+    /// overflows its field.
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error>;
+}
+
+/// A type that reads itself with a [`Reader`], borrowing from the
+/// payload for `'a`.
+pub trait SomeIpDeserialize<'a>: Sized {
+    /// Read a value at the reader's position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload ends early or holds an invalid
+    /// value.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error>;
+}
+
+macro_rules! impl_basic {
+    ($($ty:ty => $write:ident, $read:ident;)*) => {
+        $(
+            impl SomeIpSerialize for $ty {
+                fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+                    writer.$write(*self)
+                }
+            }
+
+            impl SomeIpDeserialize<'_> for $ty {
+                fn deserialize(reader: &mut Reader<'_>) -> Result<Self, Error> {
+                    reader.$read()
+                }
+            }
+        )*
+    };
+}
+
+impl_basic! {
+    bool => write_bool, read_bool;
+    u8 => write_u8, read_u8;
+    u16 => write_u16, read_u16;
+    u32 => write_u32, read_u32;
+    u64 => write_u64, read_u64;
+    i8 => write_i8, read_i8;
+    i16 => write_i16, read_i16;
+    i32 => write_i32, read_i32;
+    i64 => write_i64, read_i64;
+    f32 => write_f32, read_f32;
+    f64 => write_f64, read_f64;
+}
+
+/// A fixed-length array: its elements back to back.
+impl<T: SomeIpSerialize, const N: usize> SomeIpSerialize for [T; N] {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        self.iter()
+            .try_for_each(|element| element.serialize(writer))
+    }
+}
+
+impl<'a, T: SomeIpDeserialize<'a>, const N: usize> SomeIpDeserialize<'a> for [T; N] {
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        let mut elements = heapless::Vec::<T, N>::new();
+        for _ in 0..N {
+            // Cannot fail: exactly `N` elements are pushed.
+            let _ = elements.push(T::deserialize(reader)?);
+        }
+        Ok(elements
+            .into_array()
+            .unwrap_or_else(|_| unreachable!("exactly N elements were read")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position {
+        x: i16,
+        y: i16,
+    }
+
+    impl SomeIpSerialize for Position {
+        fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+            self.x.serialize(writer)?;
+            self.y.serialize(writer)
+        }
+    }
+
+    impl SomeIpDeserialize<'_> for Position {
+        fn deserialize(reader: &mut Reader<'_>) -> Result<Self, Error> {
+            Ok(Self {
+                x: i16::deserialize(reader)?,
+                y: i16::deserialize(reader)?,
+            })
+        }
+    }
+
+    #[test]
+    fn basic_types_are_big_endian() {
+        let mut buf = [0u8; 16];
+        let mut writer = Writer::new(&mut buf);
+        writer.write_u32(0x0102_0304).unwrap();
+        writer.write_i16(-2).unwrap();
+        writer.write_bool(true).unwrap();
+        writer.write_f32(1.5).unwrap();
+        assert_eq!(
+            writer.written(),
+            [1, 2, 3, 4, 0xFF, 0xFE, 1, 0x3F, 0xC0, 0, 0]
+        );
+
+        let mut reader = Reader::new(&buf[..11]);
+        assert_eq!(reader.read_u32().unwrap(), 0x0102_0304);
+        assert_eq!(reader.read_i16().unwrap(), -2);
+        assert!(reader.read_bool().unwrap());
+        assert!((reader.read_f32().unwrap() - 1.5).abs() < f32::EPSILON);
+        assert_eq!(
+            reader.read_u8(),
+            Err(Error::UnexpectedEnd {
+                needed: 1,
+                available: 0
+            })
+        );
+        assert_eq!(Reader::new(&[2]).read_bool(), Err(Error::InvalidBool(2)));
+    }
+
+    #[test]
+    fn little_endian_fields_sit_between_big_endian_ones() {
+        let mut buf = [0u8; 16];
+        let mut writer = Writer::new(&mut buf);
+        writer.write_u16(0x0102).unwrap();
+        writer.write_u32_le(0x0304_0506).unwrap();
+        writer.write_i16_le(-2).unwrap();
+        writer.write_f64_le(1.5).unwrap();
+        assert_eq!(
+            writer.write_u16_le(1),
+            Err(Error::BufferTooSmall {
+                needed: 2,
+                available: 0
+            })
+        );
+        assert_eq!(&buf[..8], [0x01, 0x02, 0x06, 0x05, 0x04, 0x03, 0xFE, 0xFF]);
+        assert_eq!(buf[8..], 1.5f64.to_le_bytes());
+
+        let mut reader = Reader::new(&buf);
+        assert_eq!(reader.read_u16().unwrap(), 0x0102);
+        assert_eq!(reader.read_u32_le().unwrap(), 0x0304_0506);
+        assert_eq!(reader.read_i16_le().unwrap(), -2);
+        assert!((reader.read_f64_le().unwrap() - 1.5).abs() < f64::EPSILON);
+        assert_eq!(
+            reader.read_u64_le(),
+            Err(Error::UnexpectedEnd {
+                needed: 8,
+                available: 0
+            })
+        );
+    }
+
+    #[test]
+    fn nested_structs_in_a_dynamic_array_round_trip() {
+        let positions = [Position { x: 1, y: -1 }, Position { x: 2, y: -2 }];
+        let mut buf = [0u8; 32];
+        let mut writer = Writer::new(&mut buf);
+        writer
+            .write_with_length(LengthField::U16, |w| {
+                positions.iter().try_for_each(|p| p.serialize(w))
+            })
+            .unwrap();
+        let len = writer.position();
+        assert_eq!(&buf[..2], [0, 8], "length field counts the elements' bytes");
+
+        let mut reader = Reader::new(&buf[..len]);
+        let mut array = reader.read_with_length(LengthField::U16).unwrap();
+        assert!(reader.is_empty());
+        let read: [Position; 2] = SomeIpDeserialize::deserialize(&mut array).unwrap();
+        assert_eq!(read, positions);
+        assert!(array.is_empty());
+    }
+
+    #[test]
+    fn strings_carry_bom_and_terminator() {
+        let mut buf = [0u8; 32];
+        let mut writer = Writer::new(&mut buf);
+        writer.write_utf8_string("ok", LengthField::U8).unwrap();
+        assert_eq!(writer.written(), [6, 0xEF, 0xBB, 0xBF, b'o', b'k', 0]);
+        writer
+            .write_utf16_string("é", LengthField::U8, ByteOrder::LittleEndian)
+            .unwrap();
+        writer.write_fixed_utf8_string("ab", 8).unwrap();
+        let len = writer.position();
+        assert_eq!(&buf[7..14], [6, 0xFF, 0xFE, 0xE9, 0, 0, 0]);
+        assert_eq!(&buf[14..len], [0xEF, 0xBB, 0xBF, b'a', b'b', 0, 0, 0]);
+
+        let mut reader = Reader::new(&buf[..len]);
+        assert_eq!(reader.read_utf8_string(LengthField::U8).unwrap(), "ok");
+        let utf16 = reader.read_utf16_string(LengthField::U8).unwrap();
+        assert_eq!(utf16.byte_order(), ByteOrder::LittleEndian);
+        assert!(utf16.chars().eq("é".chars().map(Ok)));
+        assert_eq!(reader.read_fixed_utf8_string(8).unwrap(), "ab");
+        assert!(reader.is_empty());
+
+        assert_eq!(
+            Reader::new(&[2, b'o', 0]).read_utf8_string(LengthField::U8),
+            Err(Error::MissingBom)
+        );
+        assert_eq!(
+            Reader::new(&[4, 0xEF, 0xBB, 0xBF, b'o']).read_utf8_string(LengthField::U8),
+            Err(Error::MissingTerminator)
+        );
+    }
+
+    #[test]
+    fn overflows_are_reported() {
+        let mut buf = [0u8; 300];
+        let mut writer = Writer::new(&mut buf);
+        assert_eq!(
+            writer.write_with_length(LengthField::U8, |w| w.write_bytes(&[0; 256])),
+            Err(Error::LengthOverflow {
+                len: 256,
+                field: LengthField::U8
+            })
+        );
+        assert_eq!(writer.position(), 0, "a failed write leaves nothing behind");
+        assert_eq!(
+            Writer::new(&mut [0u8; 1]).write_u16(1),
+            Err(Error::BufferTooSmall {
+                needed: 2,
+                available: 1
+            })
+        );
+        assert_eq!(
+            Writer::new(&mut [0u8; 4]).write_fixed_utf8_string("abc", 4),
+            Err(Error::TooLong { len: 7, max: 4 })
+        );
+        assert!(matches!(
+            Reader::new(&[9, 1]).read_with_length(LengthField::U8),
+            Err(Error::UnexpectedEnd { needed: 9, .. })
+        ));
+    }
+}
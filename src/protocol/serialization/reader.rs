@@ -0,0 +1,401 @@
+use super::{ByteOrder, Error, LengthField, UTF8_BOM};
+use crate::protocol::byte_order::ReadBytesExt;
+
+/// Reads a SOME/IP payload from a byte slice, borrowing strings and
+/// length-prefixed sections from it.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// A reader over the whole of `buf`.
+    #[must_use]
+    pub const fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    /// Bytes left to read.
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Whether every byte has been read.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// The next `len` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if fewer are left.
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.buf.len() {
+            return Err(Error::UnexpectedEnd {
+                needed: len,
+                available: self.buf.len(),
+            });
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    /// Read `1` as `true`, `0` as `false`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBool`] for any other byte, and
+    /// [`Error::UnexpectedEnd`] if the payload has ended.
+    pub fn read_bool(&mut self) -> Result<bool, Error> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(Error::InvalidBool(other)),
+        }
+    }
+
+    /// Read a `u8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload has ended.
+    pub fn read_u8(&mut self) -> Result<u8, Error> {
+        self.read_array().map(u8::from_be_bytes)
+    }
+
+    /// Read a big-endian `u16`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_u16(&mut self) -> Result<u16, Error> {
+        self.read_array().map(u16::from_be_bytes)
+    }
+
+    /// Read a big-endian `u32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_u32(&mut self) -> Result<u32, Error> {
+        self.read_array().map(u32::from_be_bytes)
+    }
+
+    /// Read a big-endian `u64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_u64(&mut self) -> Result<u64, Error> {
+        self.read_array().map(u64::from_be_bytes)
+    }
+
+    /// Read an `i8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload has ended.
+    pub fn read_i8(&mut self) -> Result<i8, Error> {
+        self.read_array().map(i8::from_be_bytes)
+    }
+
+    /// Read a big-endian `i16`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_i16(&mut self) -> Result<i16, Error> {
+        self.read_array().map(i16::from_be_bytes)
+    }
+
+    /// Read a big-endian `i32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_i32(&mut self) -> Result<i32, Error> {
+        self.read_array().map(i32::from_be_bytes)
+    }
+
+    /// Read a big-endian `i64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_i64(&mut self) -> Result<i64, Error> {
+        self.read_array().map(i64::from_be_bytes)
+    }
+
+    /// Read a big-endian IEEE 754 single-precision float.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_f32(&mut self) -> Result<f32, Error> {
+        self.read_array().map(f32::from_be_bytes)
+    }
+
+    /// Read a big-endian IEEE 754 double-precision float.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_f64(&mut self) -> Result<f64, Error> {
+        self.read_array().map(f64::from_be_bytes)
+    }
+
+    /// Read a little-endian `u16`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_u16_le(&mut self) -> Result<u16, Error> {
+        self.read_with::<2, _>(ReadBytesExt::read_u16_le)
+    }
+
+    /// Read a little-endian `u32`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_u32_le(&mut self) -> Result<u32, Error> {
+        self.read_with::<4, _>(ReadBytesExt::read_u32_le)
+    }
+
+    /// Read a little-endian `u64`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_u64_le(&mut self) -> Result<u64, Error> {
+        self.read_with::<8, _>(ReadBytesExt::read_u64_le)
+    }
+
+    /// Read an little-endian `i16`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_i16_le(&mut self) -> Result<i16, Error> {
+        self.read_with::<2, _>(ReadBytesExt::read_i16_le)
+    }
+
+    /// Read an little-endian `i32`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_i32_le(&mut self) -> Result<i32, Error> {
+        self.read_with::<4, _>(ReadBytesExt::read_i32_le)
+    }
+
+    /// Read an little-endian `i64`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_i64_le(&mut self) -> Result<i64, Error> {
+        self.read_with::<8, _>(ReadBytesExt::read_i64_le)
+    }
+
+    /// Read a little-endian IEEE 754 single-precision float, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_f32_le(&mut self) -> Result<f32, Error> {
+        self.read_with::<4, _>(ReadBytesExt::read_f32_le)
+    }
+
+    /// Read a little-endian IEEE 754 double-precision float, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_f64_le(&mut self) -> Result<f64, Error> {
+        self.read_with::<8, _>(ReadBytesExt::read_f64_le)
+    }
+
+    /// Decode the next `N` bytes with a [`ReadBytesExt`] method.
+    fn read_with<const N: usize, T>(
+        &mut self,
+        read: fn(&mut &'a [u8]) -> Result<T, crate::protocol::Error>,
+    ) -> Result<T, Error> {
+        let mut bytes = self.read_bytes(N)?;
+        // `read_bytes` checked that the `N` bytes `read` takes are there.
+        read(&mut bytes).map_err(|_| Error::UnexpectedEnd {
+            needed: N,
+            available: 0,
+        })
+    }
+
+    /// Read a `length` field and return a reader over the bytes it
+    /// counts, moving past them: a dynamic-length array, or a struct with
+    /// a length field. Bytes of the section its reader leaves unread, such
+    /// as members a newer struct version appended, are skipped.
+    ///
+    /// With [`LengthField::None`] the section is the rest of the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends before the
+    /// field or the bytes it counts.
+    pub fn read_with_length(&mut self, length: LengthField) -> Result<Self, Error> {
+        let len = match length {
+            LengthField::None => self.remaining(),
+            LengthField::U8 => usize::from(self.read_u8()?),
+            LengthField::U16 => usize::from(self.read_u16()?),
+            // Saturates on 16-bit targets, where no payload is that long.
+            LengthField::U32 => usize::try_from(self.read_u32()?).unwrap_or(usize::MAX),
+        };
+        self.read_bytes(len).map(Self::new)
+    }
+
+    /// Read a dynamic-length UTF-8 string written behind `length`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingBom`], [`Error::MissingTerminator`] or
+    /// [`Error::InvalidEncoding`] for a malformed string, and
+    /// [`Error::UnexpectedEnd`] if the payload ends first.
+    pub fn read_utf8_string(&mut self, length: LengthField) -> Result<&'a str, Error> {
+        let bytes = self.read_with_length(length)?.buf;
+        utf8_body(bytes)
+    }
+
+    /// Read a UTF-8 string of exactly `size` bytes, ignoring the padding
+    /// after its terminator.
+    ///
+    /// # Errors
+    ///
+    /// As [`read_utf8_string`](Self::read_utf8_string).
+    pub fn read_fixed_utf8_string(&mut self, size: usize) -> Result<&'a str, Error> {
+        let bytes = self.read_bytes(size)?;
+        let body = bytes.strip_prefix(&UTF8_BOM).ok_or(Error::MissingBom)?;
+        let end = body
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Error::MissingTerminator)?;
+        core::str::from_utf8(&body[..end]).map_err(|_| Error::InvalidEncoding)
+    }
+
+    /// Read a dynamic-length UTF-16 string written behind `length`, in
+    /// the byte order its byte order mark names.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingBom`], [`Error::MissingTerminator`] or
+    /// [`Error::OddUtf16Length`] for a malformed string, and
+    /// [`Error::UnexpectedEnd`] if the payload ends first. Unpaired
+    /// surrogates are reported by [`Utf16Str::chars`].
+    pub fn read_utf16_string(&mut self, length: LengthField) -> Result<Utf16Str<'a>, Error> {
+        let bytes = self.read_with_length(length)?.buf;
+        if !bytes.len().is_multiple_of(2) {
+            return Err(Error::OddUtf16Length(bytes.len()));
+        }
+        let string = Utf16Str::with_bom(bytes)?;
+        match string.units.strip_suffix(&[0, 0]) {
+            Some(units) => Ok(Utf16Str { units, ..string }),
+            None => Err(Error::MissingTerminator),
+        }
+    }
+
+    /// Read a UTF-16 string of exactly `size` bytes, ignoring the padding
+    /// after its terminator.
+    ///
+    /// # Errors
+    ///
+    /// As [`read_utf16_string`](Self::read_utf16_string).
+    pub fn read_fixed_utf16_string(&mut self, size: usize) -> Result<Utf16Str<'a>, Error> {
+        let bytes = self.read_bytes(size)?;
+        if !size.is_multiple_of(2) {
+            return Err(Error::OddUtf16Length(size));
+        }
+        let string = Utf16Str::with_bom(bytes)?;
+        let end = string
+            .units
+            .chunks_exact(2)
+            .position(|unit| unit == [0, 0])
+            .ok_or(Error::MissingTerminator)?;
+        Ok(Utf16Str {
+            units: &string.units[..2 * end],
+            ..string
+        })
+    }
+}
+
+/// The text of a UTF-8 string: its bytes between byte order mark and
+/// terminator.
+fn utf8_body(bytes: &[u8]) -> Result<&str, Error> {
+    let body = bytes.strip_prefix(&UTF8_BOM).ok_or(Error::MissingBom)?;
+    let body = body.strip_suffix(&[0]).ok_or(Error::MissingTerminator)?;
+    core::str::from_utf8(body).map_err(|_| Error::InvalidEncoding)
+}
+
+/// A UTF-16 string borrowed from a payload, without its byte order mark
+/// and terminator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf16Str<'a> {
+    units: &'a [u8],
+    order: ByteOrder,
+}
+
+impl<'a> Utf16Str<'a> {
+    /// Split the byte order mark off `bytes`.
+    fn with_bom(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (order, units) = match bytes {
+            [0xFE, 0xFF, units @ ..] => (ByteOrder::BigEndian, units),
+            [0xFF, 0xFE, units @ ..] => (ByteOrder::LittleEndian, units),
+            _ => return Err(Error::MissingBom),
+        };
+        Ok(Self { units, order })
+    }
+
+    /// The byte order its byte order mark named.
+    #[must_use]
+    pub const fn byte_order(&self) -> ByteOrder {
+        self.order
+    }
+
+    /// Its characters; an unpaired surrogate yields
+    /// [`Error::InvalidEncoding`].
+    pub fn chars(&self) -> impl Iterator<Item = Result<char, Error>> + 'a {
+        let order = self.order;
+        let units = self.units.chunks_exact(2).map(move |unit| {
+            let unit = [unit[0], unit[1]];
+            match order {
+                ByteOrder::BigEndian => u16::from_be_bytes(unit),
+                ByteOrder::LittleEndian => u16::from_le_bytes(unit),
+            }
+        });
+        char::decode_utf16(units).map(|c| c.map_err(|_| Error::InvalidEncoding))
+    }
+
+    /// Decode it into a `String`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidEncoding`] for an unpaired surrogate.
+    #[cfg(feature = "std")]
+    pub fn decode(&self) -> Result<std::string::String, Error> {
+        self.chars().collect()
+    }
+}
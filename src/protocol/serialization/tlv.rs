@@ -0,0 +1,357 @@
+//! Tag-length-value encoding of struct members, so a struct can gain
+//! members without breaking older readers.
+//!
+//! Each member is preceded by a 16-bit tag: a reserved bit, a 3-bit
+//! [`WireType`] and a 12-bit data ID. Basic types carry their size in the
+//! wire type; every other member is preceded by a length field, whose
+//! size the wire type names or, for [`WireType::ConfiguredLength`], the
+//! data definition fixes. A reader can therefore step over members whose
+//! data ID it does not know.
+
+use super::{Error, LengthField, Reader, SomeIpSerialize, Writer};
+
+/// Largest data ID a tag carries.
+pub const MAX_DATA_ID: u16 = 0x0FFF;
+
+/// How a TLV member's length is encoded, bits 14–12 of its tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireType {
+    /// A basic type of 8 bits, no length field.
+    Fixed8,
+    /// A basic type of 16 bits, no length field.
+    Fixed16,
+    /// A basic type of 32 bits, no length field.
+    Fixed32,
+    /// A basic type of 64 bits, no length field.
+    Fixed64,
+    /// A length field of the size the data definition configures.
+    ConfiguredLength,
+    /// An 8-bit length field.
+    Length8,
+    /// A 16-bit length field.
+    Length16,
+    /// A 32-bit length field.
+    Length32,
+}
+
+impl WireType {
+    /// The wire type encoded as `bits` (0–7); higher bits are ignored.
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x7 {
+            0 => Self::Fixed8,
+            1 => Self::Fixed16,
+            2 => Self::Fixed32,
+            3 => Self::Fixed64,
+            4 => Self::ConfiguredLength,
+            5 => Self::Length8,
+            6 => Self::Length16,
+            _ => Self::Length32,
+        }
+    }
+
+    /// The wire type as encoded in a tag, 0–7.
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        match self {
+            Self::Fixed8 => 0,
+            Self::Fixed16 => 1,
+            Self::Fixed32 => 2,
+            Self::Fixed64 => 3,
+            Self::ConfiguredLength => 4,
+            Self::Length8 => 5,
+            Self::Length16 => 6,
+            Self::Length32 => 7,
+        }
+    }
+
+    /// The wire type of a member behind a `length` field; `None` for
+    /// [`LengthField::None`], which TLV members cannot have.
+    #[must_use]
+    pub const fn for_length(length: LengthField) -> Option<Self> {
+        match length {
+            LengthField::None => None,
+            LengthField::U8 => Some(Self::Length8),
+            LengthField::U16 => Some(Self::Length16),
+            LengthField::U32 => Some(Self::Length32),
+        }
+    }
+}
+
+/// The tag in front of a TLV member.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TlvTag {
+    pub wire_type: WireType,
+    /// Up to [`MAX_DATA_ID`].
+    pub data_id: u16,
+}
+
+impl TlvTag {
+    /// Decode a tag, ignoring its reserved bit.
+    #[must_use]
+    pub const fn from_u16(tag: u16) -> Self {
+        Self {
+            wire_type: WireType::from_bits((tag >> 12) as u8),
+            data_id: tag & MAX_DATA_ID,
+        }
+    }
+
+    /// Encode the tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDataId`] if the data ID is above
+    /// [`MAX_DATA_ID`].
+    pub const fn to_u16(self) -> Result<u16, Error> {
+        if self.data_id > MAX_DATA_ID {
+            return Err(Error::InvalidDataId(self.data_id));
+        }
+        Ok(((self.wire_type.bits() as u16) << 12) | self.data_id)
+    }
+}
+
+/// A basic type written as a TLV member without a length field.
+pub trait TlvValue: SomeIpSerialize {
+    /// Its wire type, which gives its size.
+    const WIRE_TYPE: WireType;
+}
+
+macro_rules! impl_tlv_value {
+    ($($wire_type:ident: $($ty:ty),*;)*) => {
+        $($(
+            impl TlvValue for $ty {
+                const WIRE_TYPE: WireType = WireType::$wire_type;
+            }
+        )*)*
+    };
+}
+
+impl_tlv_value! {
+    Fixed8: bool, u8, i8;
+    Fixed16: u16, i16;
+    Fixed32: u32, i32, f32;
+    Fixed64: u64, i64, f64;
+}
+
+/// One TLV member as read: its tag and a reader over its value, without
+/// the length field.
+#[derive(Clone, Debug)]
+pub struct TlvMember<'a> {
+    pub tag: TlvTag,
+    pub value: Reader<'a>,
+}
+
+impl Writer<'_> {
+    /// Write `value` as the member `data_id`, with the wire type of its
+    /// size.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDataId`] if `data_id` is above
+    /// [`MAX_DATA_ID`], and [`Error::BufferTooSmall`] if the member does
+    /// not fit.
+    pub fn write_tlv<T: TlvValue>(&mut self, data_id: u16, value: &T) -> Result<(), Error> {
+        let tag = TlvTag {
+            wire_type: T::WIRE_TYPE,
+            data_id,
+        };
+        // Without a length field, for its undoing of a partial write.
+        self.write_with_length(LengthField::None, |w| {
+            w.write_u16(tag.to_u16()?)?;
+            value.serialize(w)
+        })
+    }
+
+    /// Write what `body` writes as the member `data_id` behind a `length`
+    /// field, with wire type 5, 6 or 7 naming its size: a struct, string
+    /// or array.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoLengthField`] for [`LengthField::None`],
+    /// [`Error::InvalidDataId`] if `data_id` is above [`MAX_DATA_ID`],
+    /// and whatever [`write_with_length`](Self::write_with_length)
+    /// returns.
+    pub fn write_tlv_with_length<F>(
+        &mut self,
+        data_id: u16,
+        length: LengthField,
+        body: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        let wire_type = WireType::for_length(length).ok_or(Error::NoLengthField)?;
+        self.write_tlv_member(TlvTag { wire_type, data_id }, length, body)
+    }
+
+    /// Like [`write_tlv_with_length`](Self::write_tlv_with_length), with
+    /// wire type 4: the `length` field size is the one the data
+    /// definition configures, which readers must know.
+    ///
+    /// # Errors
+    ///
+    /// As [`write_tlv_with_length`](Self::write_tlv_with_length).
+    pub fn write_tlv_with_configured_length<F>(
+        &mut self,
+        data_id: u16,
+        length: LengthField,
+        body: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        if length == LengthField::None {
+            return Err(Error::NoLengthField);
+        }
+        let tag = TlvTag {
+            wire_type: WireType::ConfiguredLength,
+            data_id,
+        };
+        self.write_tlv_member(tag, length, body)
+    }
+
+    fn write_tlv_member<F>(
+        &mut self,
+        tag: TlvTag,
+        length: LengthField,
+        body: F,
+    ) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        let tag = tag.to_u16()?;
+        self.write_with_length(LengthField::None, |w| {
+            w.write_u16(tag)?;
+            w.write_with_length(length, body)
+        })
+    }
+}
+
+impl<'a> Reader<'a> {
+    /// Read the next TLV member, or `None` once the reader is empty.
+    /// `configured` is the length field size of members with wire type
+    /// [`ConfiguredLength`](WireType::ConfiguredLength).
+    ///
+    /// A member whose data ID the caller does not know is skipped by
+    /// just reading the next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedEnd`] if the payload ends inside a
+    /// member, and [`Error::NoLengthField`] for a member with wire type
+    /// `ConfiguredLength` when `configured` is [`LengthField::None`].
+    pub fn read_tlv(&mut self, configured: LengthField) -> Result<Option<TlvMember<'a>>, Error> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let tag = TlvTag::from_u16(self.read_u16()?);
+        let value = match tag.wire_type {
+            WireType::Fixed8 => self.read_bytes(1).map(Reader::new)?,
+            WireType::Fixed16 => self.read_bytes(2).map(Reader::new)?,
+            WireType::Fixed32 => self.read_bytes(4).map(Reader::new)?,
+            WireType::Fixed64 => self.read_bytes(8).map(Reader::new)?,
+            WireType::ConfiguredLength => {
+                if configured == LengthField::None {
+                    return Err(Error::NoLengthField);
+                }
+                self.read_with_length(configured)?
+            }
+            WireType::Length8 => self.read_with_length(LengthField::U8)?,
+            WireType::Length16 => self.read_with_length(LengthField::U16)?,
+            WireType::Length32 => self.read_with_length(LengthField::U32)?,
+        };
+        Ok(Some(TlvMember { tag, value }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Version 2 of a struct adds `label` (data ID 3) to version 1's
+    /// `speed` (1) and `position` (2).
+    fn write_v2(buf: &mut [u8]) -> usize {
+        let mut writer = Writer::new(buf);
+        writer.write_tlv(1, &0x1234u16).unwrap();
+        writer
+            .write_tlv_with_length(3, LengthField::U8, |w| {
+                w.write_utf8_string("v2", LengthField::U8)
+            })
+            .unwrap();
+        writer
+            .write_tlv_with_configured_length(2, LengthField::U16, |w| {
+                w.write_i16(-1)?;
+                w.write_i16(1)
+            })
+            .unwrap();
+        writer.position()
+    }
+
+    #[test]
+    fn members_encode_their_wire_type() {
+        let mut buf = [0u8; 32];
+        let len = write_v2(&mut buf);
+        assert_eq!(
+            &buf[..4],
+            [0x10, 0x01, 0x12, 0x34],
+            "wire type 1, data ID 1"
+        );
+        assert_eq!(&buf[4..7], [0x50, 0x03, 7], "wire type 5, 8-bit length");
+        assert_eq!(
+            &buf[len - 8..len - 4],
+            [0x40, 0x02, 0, 4],
+            "wire type 4, 16-bit length"
+        );
+    }
+
+    #[test]
+    fn an_older_reader_skips_unknown_members() {
+        let mut buf = [0u8; 32];
+        let len = write_v2(&mut buf);
+
+        let mut reader = Reader::new(&buf[..len]);
+        let (mut speed, mut position) = (None, None);
+        while let Some(mut member) = reader.read_tlv(LengthField::U16).unwrap() {
+            match member.tag.data_id {
+                1 => speed = Some(member.value.read_u16().unwrap()),
+                2 => {
+                    position = Some((
+                        member.value.read_i16().unwrap(),
+                        member.value.read_i16().unwrap(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(speed, Some(0x1234));
+        assert_eq!(position, Some((-1, 1)));
+    }
+
+    #[test]
+    fn invalid_members_are_reported() {
+        let mut buf = [0u8; 8];
+        let mut writer = Writer::new(&mut buf);
+        assert_eq!(
+            writer.write_tlv(0x1000, &1u8),
+            Err(Error::InvalidDataId(0x1000))
+        );
+        assert_eq!(
+            writer.write_tlv_with_length(1, LengthField::None, |_| Ok(())),
+            Err(Error::NoLengthField)
+        );
+        assert_eq!(writer.position(), 0);
+
+        assert_eq!(
+            Reader::new(&[0x40, 0x01, 0])
+                .read_tlv(LengthField::None)
+                .err(),
+            Some(Error::NoLengthField)
+        );
+        assert!(matches!(
+            Reader::new(&[0x20, 0x01, 0]).read_tlv(LengthField::U32),
+            Err(Error::UnexpectedEnd { needed: 4, .. })
+        ));
+        assert_eq!(TlvTag::from_u16(0xF001).wire_type, WireType::Length32);
+    }
+}
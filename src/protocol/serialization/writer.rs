@@ -0,0 +1,392 @@
+use super::{ByteOrder, Error, LengthField, UTF8_BOM};
+use crate::protocol::byte_order::WriteBytesExt;
+
+/// Writes a SOME/IP payload into a byte slice.
+///
+/// A write that fails leaves the position where it was, so the caller can
+/// report the error without a half-written value in the buffer.
+#[derive(Debug)]
+pub struct Writer<'a> {
+    buf: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// A writer starting at the front of `buf`.
+    #[must_use]
+    pub const fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+
+    /// Bytes written so far.
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The bytes written so far.
+    #[must_use]
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.position]
+    }
+
+    /// Write `bytes` as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if they do not fit.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let available = self.buf.len() - self.position;
+        let out = self
+            .buf
+            .get_mut(self.position..self.position + bytes.len())
+            .ok_or(Error::BufferTooSmall {
+                needed: bytes.len(),
+                available,
+            })?;
+        out.copy_from_slice(bytes);
+        self.position += bytes.len();
+        Ok(())
+    }
+
+    /// Write `true` as `1`, `false` as `0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the buffer is full.
+    pub fn write_bool(&mut self, value: bool) -> Result<(), Error> {
+        self.write_u8(u8::from(value))
+    }
+
+    /// Write a `u8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the buffer is full.
+    pub fn write_u8(&mut self, value: u8) -> Result<(), Error> {
+        self.write_bytes(&[value])
+    }
+
+    /// Write a big-endian `u16`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_u16(&mut self, value: u16) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian `u32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_u32(&mut self, value: u32) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian `u64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_u64(&mut self, value: u64) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write an `i8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the buffer is full.
+    pub fn write_i8(&mut self, value: i8) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian `i16`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_i16(&mut self, value: i16) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian `i32`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_i32(&mut self, value: i32) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian `i64`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_i64(&mut self, value: i64) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian IEEE 754 single-precision float.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_f32(&mut self, value: f32) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a big-endian IEEE 754 double-precision float.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_f64(&mut self, value: f64) -> Result<(), Error> {
+        self.write_bytes(&value.to_be_bytes())
+    }
+
+    /// Write a little-endian `u16`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_u16_le(&mut self, value: u16) -> Result<(), Error> {
+        self.write_with::<2, _>(value, |mut out, value| out.write_u16_le(value))
+    }
+
+    /// Write a little-endian `u32`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_u32_le(&mut self, value: u32) -> Result<(), Error> {
+        self.write_with::<4, _>(value, |mut out, value| out.write_u32_le(value))
+    }
+
+    /// Write a little-endian `u64`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_u64_le(&mut self, value: u64) -> Result<(), Error> {
+        self.write_with::<8, _>(value, |mut out, value| out.write_u64_le(value))
+    }
+
+    /// Write an little-endian `i16`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_i16_le(&mut self, value: i16) -> Result<(), Error> {
+        self.write_with::<2, _>(value, |mut out, value| out.write_i16_le(value))
+    }
+
+    /// Write an little-endian `i32`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_i32_le(&mut self, value: i32) -> Result<(), Error> {
+        self.write_with::<4, _>(value, |mut out, value| out.write_i32_le(value))
+    }
+
+    /// Write an little-endian `i64`, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_i64_le(&mut self, value: i64) -> Result<(), Error> {
+        self.write_with::<8, _>(value, |mut out, value| out.write_i64_le(value))
+    }
+
+    /// Write a little-endian IEEE 754 single-precision float, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_f32_le(&mut self, value: f32) -> Result<(), Error> {
+        self.write_with::<4, _>(value, |mut out, value| out.write_f32_le(value))
+    }
+
+    /// Write a little-endian IEEE 754 double-precision float, for a field that departs from SOME/IP's
+    /// big-endian default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the value does not fit.
+    pub fn write_f64_le(&mut self, value: f64) -> Result<(), Error> {
+        self.write_with::<8, _>(value, |mut out, value| out.write_f64_le(value))
+    }
+
+    /// Encode `value` into the next `N` bytes with a [`WriteBytesExt`]
+    /// method.
+    fn write_with<const N: usize, T>(
+        &mut self,
+        value: T,
+        write: fn(&mut [u8], T) -> Result<(), crate::protocol::Error>,
+    ) -> Result<(), Error> {
+        let too_small = Error::BufferTooSmall {
+            needed: N,
+            available: self.buf.len() - self.position,
+        };
+        let out = self
+            .buf
+            .get_mut(self.position..self.position + N)
+            .ok_or(too_small)?;
+        write(out, value).map_err(|_| too_small)?;
+        self.position += N;
+        Ok(())
+    }
+
+    /// Write what `body` writes behind a `length` field counting its
+    /// bytes: a dynamic-length array, or a struct with a length field.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::LengthOverflow`] if `body` wrote more bytes than
+    /// the field holds, and whatever `body` or writing the field returns.
+    pub fn write_with_length<F>(&mut self, length: LengthField, body: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        let start = self.position;
+        let result = self.write_bytes(&[0; 4][..length.size()]).and_then(|()| {
+            body(self)?;
+            let len = self.position - start - length.size();
+            self.patch_length(start, length, len)
+        });
+        if result.is_err() {
+            self.position = start;
+        }
+        result
+    }
+
+    /// Write `len` into the `length` field at `at`.
+    fn patch_length(&mut self, at: usize, length: LengthField, len: usize) -> Result<(), Error> {
+        let overflow = Error::LengthOverflow { len, field: length };
+        let field = &mut self.buf[at..at + length.size()];
+        match length {
+            LengthField::None => {}
+            LengthField::U8 => field[0] = u8::try_from(len).map_err(|_| overflow)?,
+            LengthField::U16 => {
+                field.copy_from_slice(&u16::try_from(len).map_err(|_| overflow)?.to_be_bytes());
+            }
+            LengthField::U32 => {
+                field.copy_from_slice(&u32::try_from(len).map_err(|_| overflow)?.to_be_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `value` as a dynamic-length UTF-8 string: `length`, the byte
+    /// order mark, the bytes and a zero terminator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the string does not fit and
+    /// [`Error::LengthOverflow`] if it is too long for `length`.
+    pub fn write_utf8_string(&mut self, value: &str, length: LengthField) -> Result<(), Error> {
+        self.write_with_length(length, |w| w.write_utf8_body(value))
+    }
+
+    /// Write `value` as a UTF-8 string of exactly `size` bytes, padded
+    /// with zeros after its terminator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TooLong`] if the byte order mark, the bytes and the
+    /// terminator take more than `size` bytes, and
+    /// [`Error::BufferTooSmall`] if `size` bytes do not fit.
+    pub fn write_fixed_utf8_string(&mut self, value: &str, size: usize) -> Result<(), Error> {
+        let len = UTF8_BOM.len() + value.len() + 1;
+        if len > size {
+            return Err(Error::TooLong { len, max: size });
+        }
+        self.write_padded(size, |w| w.write_utf8_body(value))
+    }
+
+    fn write_utf8_body(&mut self, value: &str) -> Result<(), Error> {
+        self.write_bytes(&UTF8_BOM)?;
+        self.write_bytes(value.as_bytes())?;
+        self.write_u8(0)
+    }
+
+    /// Write `value` as a dynamic-length UTF-16 string in `order`:
+    /// `length`, the byte order mark, the code units and a zero
+    /// terminator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::BufferTooSmall`] if the string does not fit and
+    /// [`Error::LengthOverflow`] if it is too long for `length`.
+    pub fn write_utf16_string(
+        &mut self,
+        value: &str,
+        length: LengthField,
+        order: ByteOrder,
+    ) -> Result<(), Error> {
+        self.write_with_length(length, |w| w.write_utf16_body(value, order))
+    }
+
+    /// Write `value` as a UTF-16 string in `order` of exactly `size`
+    /// bytes, padded with zeros after its terminator.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TooLong`] if the byte order mark, the code units
+    /// and the terminator take more than `size` bytes, and
+    /// [`Error::BufferTooSmall`] if `size` bytes do not fit.
+    pub fn write_fixed_utf16_string(
+        &mut self,
+        value: &str,
+        size: usize,
+        order: ByteOrder,
+    ) -> Result<(), Error> {
+        let len = 2 * (value.encode_utf16().count() + 2);
+        if len > size {
+            return Err(Error::TooLong { len, max: size });
+        }
+        self.write_padded(size, |w| w.write_utf16_body(value, order))
+    }
+
+    fn write_utf16_body(&mut self, value: &str, order: ByteOrder) -> Result<(), Error> {
+        let unit = |unit: u16| match order {
+            ByteOrder::BigEndian => unit.to_be_bytes(),
+            ByteOrder::LittleEndian => unit.to_le_bytes(),
+        };
+        self.write_bytes(&unit(0xFEFF))?;
+        for code_unit in value.encode_utf16() {
+            self.write_bytes(&unit(code_unit))?;
+        }
+        self.write_bytes(&[0, 0])
+    }
+
+    /// Write what `body` writes, then zeros up to `size` bytes in all.
+    fn write_padded<F>(&mut self, size: usize, body: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error>,
+    {
+        let start = self.position;
+        let available = self.buf.len() - start;
+        if size > available {
+            return Err(Error::BufferTooSmall {
+                needed: size,
+                available,
+            });
+        }
+        body(self)?;
+        self.buf[self.position..start + size].fill(0);
+        self.position = start + size;
+        Ok(())
+    }
+}
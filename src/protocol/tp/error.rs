@@ -0,0 +1,48 @@
+use thiserror::Error;
+
+/// Errors that can occur when segmenting or reassembling SOME/IP-TP
+/// messages.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The message does not have the TP flag set in its message type.
+    #[error("Not a SOME/IP-TP segment")]
+    NotSegment,
+    /// The segment payload is shorter than the 4-byte TP header.
+    #[error("SOME/IP-TP segment shorter than its TP header")]
+    Truncated,
+    /// A segment other than the last carries a length that is not a
+    /// multiple of [`SEGMENT_ALIGNMENT`](super::SEGMENT_ALIGNMENT).
+    #[error("SOME/IP-TP segment at offset {offset} has misaligned length {len}")]
+    MisalignedSegment {
+        /// Offset of the segment in the original payload.
+        offset: u32,
+        /// Length of the segment data.
+        len: usize,
+    },
+    /// A segment ends past the end announced by the last segment, or a
+    /// second last segment announces a different end.
+    #[error("SOME/IP-TP segment at offset {offset} conflicts with the message end")]
+    Inconsistent {
+        /// Offset of the offending segment.
+        offset: u32,
+    },
+    /// The reassembled payload would exceed the reassembly buffer.
+    #[error("Reassembled SOME/IP-TP payload of {len} bytes exceeds limit of {limit} bytes")]
+    TooLarge {
+        /// Payload length the segment reaches.
+        len: usize,
+        /// Largest payload the buffer holds.
+        limit: usize,
+    },
+    /// Segments arrived so far out of order that the gaps between them
+    /// cannot be tracked.
+    #[error("Too many gaps in SOME/IP-TP reassembly")]
+    TooManyGaps,
+    /// Every reassembly slot is busy with another message.
+    #[error("No free SOME/IP-TP reassembly slot")]
+    NoFreeSlot,
+    /// A segment buffer cannot hold a SOME/IP header, a TP header and one
+    /// aligned block of data.
+    #[error("Segment length {0} too small for SOME/IP-TP")]
+    SegmentTooSmall(usize),
+}
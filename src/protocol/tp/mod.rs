@@ -1 +1,97 @@
+mod error;
+mod reassembler;
+mod segmenter;
 
+use super::MessageView;
+
+/// Length of the TP header that follows the SOME/IP header of every
+/// segment.
+pub const TP_HEADER_LEN: usize = 4;
+
+/// Segment offsets are multiples of this many bytes, and so is the data
+/// length of every segment but the last.
+pub const SEGMENT_ALIGNMENT: usize = 16;
+
+/// The TP header of one segment: where its data goes in the original
+/// payload and whether more segments follow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TpHeader {
+    offset: u32,
+    more_segments: bool,
+}
+
+impl TpHeader {
+    /// Creates a TP header. The low 4 bits of `offset` are dropped: the
+    /// wire format only carries multiples of [`SEGMENT_ALIGNMENT`].
+    #[must_use]
+    pub const fn new(offset: u32, more_segments: bool) -> Self {
+        Self {
+            offset: offset & !0xF,
+            more_segments,
+        }
+    }
+
+    /// Decodes the header from its 4 wire bytes, ignoring the reserved
+    /// bits.
+    #[must_use]
+    pub const fn from_bytes(bytes: [u8; TP_HEADER_LEN]) -> Self {
+        let word = u32::from_be_bytes(bytes);
+        Self::new(word, word & 1 != 0)
+    }
+
+    /// Encodes the header into its 4 wire bytes.
+    #[must_use]
+    pub const fn to_bytes(self) -> [u8; TP_HEADER_LEN] {
+        (self.offset | self.more_segments as u32).to_be_bytes()
+    }
+
+    /// Byte offset of the segment's data in the original payload.
+    #[must_use]
+    pub const fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// `true` on every segment but the last.
+    #[must_use]
+    pub const fn more_segments(&self) -> bool {
+        self.more_segments
+    }
+}
+
+/// Splits a TP segment into its TP header and its data.
+///
+/// # Errors
+///
+/// Returns [`Error::NotSegment`] if the TP flag is not set and
+/// [`Error::Truncated`] if the payload cannot hold the TP header.
+pub fn parse_segment<'a>(view: &MessageView<'a>) -> Result<(TpHeader, &'a [u8]), Error> {
+    if !view.header().message_type().is_tp() {
+        return Err(Error::NotSegment);
+    }
+    let payload = view.payload_bytes();
+    let Some((header, data)) = payload.split_first_chunk::<TP_HEADER_LEN>() else {
+        return Err(Error::Truncated);
+    };
+    Ok((TpHeader::from_bytes(*header), data))
+}
+
+pub use error::Error;
+pub use reassembler::{
+    Reassembler, TP_MAX_MESSAGE_LEN, TP_REASSEMBLY_SLOTS, TP_REASSEMBLY_TIMEOUT,
+};
+pub use segmenter::Segmenter;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tp_header_round_trips_and_ignores_reserved_bits() {
+        let header = TpHeader::new(0x0000_05A0, true);
+        assert_eq!(header.to_bytes(), [0x00, 0x00, 0x05, 0xA1]);
+        assert_eq!(TpHeader::from_bytes(header.to_bytes()), header);
+        let reserved = TpHeader::from_bytes([0x00, 0x00, 0x05, 0xAE]);
+        assert_eq!(reserved.offset(), 0x5A0);
+        assert!(!reserved.more_segments());
+    }
+}
@@ -0,0 +1,479 @@
+use core::net::SocketAddr;
+use core::time::Duration;
+
+use heapless::Vec;
+
+use super::{Error, SEGMENT_ALIGNMENT, TpHeader, parse_segment};
+use crate::protocol::{Header, MessageTypeField, MessageView};
+use crate::traits::WireFormat;
+
+const SOMEIP_HEADER_LEN: usize = 16;
+
+/// Disjoint runs of received data one reassembly keeps track of; segments
+/// arriving further out of order than that abort it.
+const RECEIVED_RANGES_CAP: usize = 8;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_TP_REASSEMBLY_SLOTS: usize = 1;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_TP_REASSEMBLY_SLOTS: usize = 4;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_TP_MAX_MESSAGE_LEN: usize = 4096;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_TP_MAX_MESSAGE_LEN: usize = 32_768;
+
+/// Default number of messages a [`Reassembler`] puts together at the same
+/// time. Sized via `SIMPLE_SOMEIP_TP_REASSEMBLY_SLOTS`; defaults to 1 on
+/// bare-metal, 4 otherwise.
+pub const TP_REASSEMBLY_SLOTS: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_TP_REASSEMBLY_SLOTS"),
+    _DEFAULT_TP_REASSEMBLY_SLOTS,
+);
+
+/// Default size of a [`Reassembler`] slot: the largest reassembled
+/// message, 16-byte header included. Sized via
+/// `SIMPLE_SOMEIP_TP_MAX_MESSAGE_LEN`; defaults to 4 KiB on bare-metal,
+/// 32 KiB otherwise.
+pub const TP_MAX_MESSAGE_LEN: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_TP_MAX_MESSAGE_LEN"),
+    _DEFAULT_TP_MAX_MESSAGE_LEN,
+);
+
+/// How long the client and server keep a partly received message after
+/// its latest segment.
+pub const TP_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// One message being put together.
+#[derive(Debug)]
+struct Slot<const LEN: usize> {
+    source: SocketAddr,
+    message_id: u32,
+    request_id: u32,
+    /// Header of the first segment received; its length and TP flag are
+    /// rewritten on completion.
+    header: Header,
+    /// Header slot followed by the payload received so far. Gaps read as
+    /// zeros until filled.
+    message: Vec<u8, LEN>,
+    /// Sorted, disjoint `[start, end)` payload ranges received.
+    received: Vec<(usize, usize), RECEIVED_RANGES_CAP>,
+    /// Payload length, known once the last segment has arrived.
+    total: Option<usize>,
+    last_activity: Duration,
+    complete: bool,
+}
+
+impl<const LEN: usize> Slot<LEN> {
+    /// Copies `data` in at the segment's offset; `true` once every byte
+    /// up to the end announced by the last segment has arrived.
+    fn add(&mut self, tp: TpHeader, data: &[u8], now: Duration) -> Result<bool, Error> {
+        let offset = tp.offset() as usize;
+        let end = offset + data.len();
+        if tp.more_segments() && !data.len().is_multiple_of(SEGMENT_ALIGNMENT) {
+            return Err(Error::MisalignedSegment {
+                offset: tp.offset(),
+                len: data.len(),
+            });
+        }
+        let limit = LEN.saturating_sub(SOMEIP_HEADER_LEN);
+        if end > limit {
+            return Err(Error::TooLarge { len: end, limit });
+        }
+        match self.total {
+            Some(total) if end > total || (!tp.more_segments() && end != total) => {
+                return Err(Error::Inconsistent {
+                    offset: tp.offset(),
+                });
+            }
+            None if !tp.more_segments() => {
+                if self.received.last().is_some_and(|&(_, seen)| seen > end) {
+                    return Err(Error::Inconsistent {
+                        offset: tp.offset(),
+                    });
+                }
+                self.total = Some(end);
+            }
+            _ => {}
+        }
+        if self.message.len() < SOMEIP_HEADER_LEN + end {
+            // Cannot fail: `end` was checked against `LEN` above.
+            let _ = self.message.resize(SOMEIP_HEADER_LEN + end, 0);
+        }
+        self.message[SOMEIP_HEADER_LEN + offset..SOMEIP_HEADER_LEN + end].copy_from_slice(data);
+        if !data.is_empty() {
+            self.insert_range(offset, end)?;
+        }
+        self.last_activity = now;
+        Ok(self.is_complete())
+    }
+
+    fn insert_range(&mut self, start: usize, end: usize) -> Result<(), Error> {
+        let mut merged = (start, end);
+        self.received.retain(|&(s, e)| {
+            let touches = s <= merged.1 && e >= merged.0;
+            if touches {
+                merged = (merged.0.min(s), merged.1.max(e));
+            }
+            !touches
+        });
+        let index = self
+            .received
+            .iter()
+            .position(|&(s, _)| s > merged.0)
+            .unwrap_or(self.received.len());
+        self.received
+            .insert(index, merged)
+            .map_err(|_| Error::TooManyGaps)
+    }
+
+    fn is_complete(&self) -> bool {
+        let Some(total) = self.total else {
+            return false;
+        };
+        match self.received.as_slice() {
+            [] => total == 0,
+            [(0, end)] => *end == total,
+            _ => false,
+        }
+    }
+
+    /// Writes the header of the reassembled message: the first segment's,
+    /// with the TP flag cleared and the full length.
+    #[allow(clippy::cast_possible_truncation)]
+    fn finish(&mut self) {
+        let payload_len = self.message.len() - SOMEIP_HEADER_LEN;
+        let header = Header::from_fields(
+            self.header.message_id(),
+            // At most `LEN`, which fits the 32-bit length field.
+            (8 + payload_len) as u32,
+            self.header.request_id(),
+            self.header.protocol_version(),
+            self.header.interface_version(),
+            MessageTypeField::new(self.header.message_type().message_type(), false),
+            self.header.return_code(),
+        );
+        // Cannot fail: the slot always starts with the header's 16 bytes.
+        let _ = header.encode_to_slice(&mut self.message[..SOMEIP_HEADER_LEN]);
+        self.complete = true;
+    }
+}
+
+/// Puts SOME/IP-TP segments back together into whole messages.
+///
+/// Segments are matched by sender, message ID and request ID and may
+/// arrive in any order or more than once; two senders whose request IDs
+/// collide never share a message. Up to `SLOTS` messages are reassembled at
+/// the same time, each up to `LEN` bytes (header included). A message
+/// whose last segment is older than the timeout is dropped by
+/// [`Self::expire`], which [`Self::feed`] also runs, and any segment that
+/// does not fit the message it belongs to aborts that message.
+///
+/// The buffers are inline, so a `Reassembler` with the default sizes
+/// takes `TP_REASSEMBLY_SLOTS * TP_MAX_MESSAGE_LEN` bytes; box it on
+/// hosts, or place it in a `static` on bare metal.
+///
+/// ```
+/// use core::time::Duration;
+/// use simple_someip::protocol::tp::{Reassembler, Segmenter};
+/// use simple_someip::protocol::{Header, MessageId, MessageType, MessageTypeField, MessageView, ReturnCode};
+///
+/// let payload = [0x5A; 100];
+/// let message_id = MessageId::new_from_service_and_method(0x1234, 0x8001);
+/// let notification = MessageTypeField::new(MessageType::Notification, false);
+/// let header = Header::new(message_id, 1, 1, 1, notification, ReturnCode::Ok, payload.len());
+/// let mut segmenter = Segmenter::new(&header, &payload, 64).unwrap();
+///
+/// let mut reassembler = Reassembler::<2, 256>::new(Duration::from_secs(5));
+/// let sender = "192.0.2.1:30509".parse().unwrap();
+/// let mut buf = [0u8; 64];
+/// let mut whole = None;
+/// while let Some(len) = segmenter.write_next(&mut buf).unwrap() {
+///     let segment = MessageView::parse(&buf[..len]).unwrap();
+///     if let Some(message) = reassembler.feed(sender, &segment, Duration::ZERO).unwrap() {
+///         whole = Some(message.to_vec());
+///     }
+/// }
+/// let whole = whole.unwrap();
+/// assert_eq!(MessageView::parse(&whole).unwrap().payload_bytes(), payload);
+/// ```
+#[derive(Debug)]
+pub struct Reassembler<
+    const SLOTS: usize = TP_REASSEMBLY_SLOTS,
+    const LEN: usize = TP_MAX_MESSAGE_LEN,
+> {
+    slots: Vec<Slot<LEN>, SLOTS>,
+    timeout: Duration,
+}
+
+impl<const SLOTS: usize, const LEN: usize> Reassembler<SLOTS, LEN> {
+    /// An empty reassembler dropping messages `timeout` after their
+    /// latest segment.
+    #[must_use]
+    pub const fn new(timeout: Duration) -> Self {
+        Self {
+            slots: Vec::new(),
+            timeout,
+        }
+    }
+
+    /// Adds one segment received from `source` at `now`. Returns the whole message,
+    /// header included, TP flag cleared and length set, once its last
+    /// missing segment arrives; it stays valid until the next call.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotSegment`] or [`Error::Truncated`] for a message
+    /// that is not a TP segment and [`Error::NoFreeSlot`] when every slot
+    /// is busy with other messages. Any other error also drops the
+    /// message the segment belongs to.
+    pub fn feed(
+        &mut self,
+        source: SocketAddr,
+        segment: &MessageView<'_>,
+        now: Duration,
+    ) -> Result<Option<&[u8]>, Error> {
+        self.slots.retain(|slot| !slot.complete);
+        self.expire(now);
+        let (tp, data) = parse_segment(segment)?;
+        let header = segment.header();
+        let key = (
+            source,
+            header.message_id().message_id(),
+            header.request_id(),
+        );
+        let index = if let Some(index) = self
+            .slots
+            .iter()
+            .position(|slot| (slot.source, slot.message_id, slot.request_id) == key)
+        {
+            index
+        } else {
+            let mut message = Vec::new();
+            if message.resize(SOMEIP_HEADER_LEN, 0).is_err() {
+                return Err(Error::TooLarge {
+                    len: data.len(),
+                    limit: 0,
+                });
+            }
+            self.slots
+                .push(Slot {
+                    source,
+                    message_id: key.1,
+                    request_id: key.2,
+                    header: header.to_owned(),
+                    message,
+                    received: Vec::new(),
+                    total: None,
+                    last_activity: now,
+                    complete: false,
+                })
+                .map_err(|_| Error::NoFreeSlot)?;
+            self.slots.len() - 1
+        };
+        match self.slots[index].add(tp, data, now) {
+            Ok(true) => {
+                let slot = &mut self.slots[index];
+                slot.finish();
+                Ok(Some(&slot.message))
+            }
+            Ok(false) => Ok(None),
+            Err(e) => {
+                self.slots.swap_remove(index);
+                Err(e)
+            }
+        }
+    }
+
+    /// Drops messages whose latest segment arrived `timeout` or more
+    /// before `now`, returning how many were dropped.
+    pub fn expire(&mut self, now: Duration) -> usize {
+        let before = self.slots.len();
+        let timeout = self.timeout;
+        self.slots.retain(|slot| {
+            slot.complete
+                || now
+                    .checked_sub(slot.last_activity)
+                    .is_none_or(|age| age < timeout)
+        });
+        before - self.slots.len()
+    }
+
+    /// Number of messages still missing segments.
+    #[must_use]
+    pub fn in_progress(&self) -> usize {
+        self.slots.iter().filter(|slot| !slot.complete).count()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::protocol::tp::Segmenter;
+    use crate::protocol::{MessageId, MessageType, ReturnCode};
+    use std::vec::Vec as StdVec;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn sender(port: u16) -> SocketAddr {
+        SocketAddr::from(([192, 0, 2, 1], port))
+    }
+
+    fn segments(request_id: u32, payload: &[u8], max_segment_len: usize) -> StdVec<StdVec<u8>> {
+        let header = Header::new(
+            MessageId::new_from_service_and_method(0x1234, 0x8001),
+            request_id,
+            1,
+            1,
+            MessageTypeField::new(MessageType::Notification, false),
+            ReturnCode::Ok,
+            payload.len(),
+        );
+        let mut segmenter = Segmenter::new(&header, payload, max_segment_len).unwrap();
+        let mut buf = std::vec![0u8; max_segment_len];
+        let mut out = StdVec::new();
+        while let Some(len) = segmenter.write_next(&mut buf).unwrap() {
+            out.push(buf[..len].to_vec());
+        }
+        out
+    }
+
+    fn feed<'r>(
+        reassembler: &'r mut Reassembler<2, 256>,
+        segment: &[u8],
+        now: Duration,
+    ) -> Result<Option<&'r [u8]>, Error> {
+        feed_from(reassembler, sender(30509), segment, now)
+    }
+
+    fn feed_from<'r>(
+        reassembler: &'r mut Reassembler<2, 256>,
+        source: SocketAddr,
+        segment: &[u8],
+        now: Duration,
+    ) -> Result<Option<&'r [u8]>, Error> {
+        reassembler.feed(source, &MessageView::parse(segment).unwrap(), now)
+    }
+
+    #[test]
+    fn reassembles_out_of_order_and_duplicate_segments() {
+        let payload: [u8; 150] = core::array::from_fn(|i| u8::try_from(i).unwrap());
+        let parts = segments(7, &payload, 52);
+        assert_eq!(parts.len(), 5);
+        let mut reassembler = Reassembler::<2, 256>::new(SECOND);
+        for index in [4, 1, 1, 3, 0] {
+            assert_eq!(
+                feed(&mut reassembler, &parts[index], Duration::ZERO),
+                Ok(None)
+            );
+        }
+        assert_eq!(reassembler.in_progress(), 1);
+        let whole = feed(&mut reassembler, &parts[2], Duration::ZERO)
+            .unwrap()
+            .expect("complete")
+            .to_vec();
+        let view = MessageView::parse(&whole).unwrap();
+        assert!(!view.header().message_type().is_tp());
+        assert_eq!(view.header().request_id(), 7);
+        assert_eq!(view.payload_bytes(), payload);
+        assert_eq!(reassembler.in_progress(), 0);
+    }
+
+    #[test]
+    fn keeps_messages_apart_by_request_id_and_bounds_slots() {
+        let payload = [0xAA; 40];
+        let a = segments(1, &payload, 52);
+        let b = segments(2, &payload, 52);
+        let c = segments(3, &payload, 52);
+        let mut reassembler = Reassembler::<2, 256>::new(SECOND);
+        assert_eq!(feed(&mut reassembler, &a[0], Duration::ZERO), Ok(None));
+        assert_eq!(feed(&mut reassembler, &b[0], Duration::ZERO), Ok(None));
+        assert_eq!(
+            feed(&mut reassembler, &c[0], Duration::ZERO),
+            Err(Error::NoFreeSlot)
+        );
+        assert!(
+            feed(&mut reassembler, &b[1], Duration::ZERO)
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            feed(&mut reassembler, &a[1], Duration::ZERO)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn keeps_messages_apart_by_sender() {
+        let a: [u8; 40] = core::array::from_fn(|i| u8::try_from(i).unwrap());
+        let b = [0xEE; 40];
+        // Same message ID and request ID from two senders.
+        let from_a = segments(1, &a, 52);
+        let from_b = segments(1, &b, 52);
+        let mut reassembler = Reassembler::<2, 256>::new(SECOND);
+        assert_eq!(
+            feed_from(&mut reassembler, sender(1), &from_a[0], Duration::ZERO),
+            Ok(None)
+        );
+        assert_eq!(
+            feed_from(&mut reassembler, sender(2), &from_b[1], Duration::ZERO),
+            Ok(None)
+        );
+        assert_eq!(reassembler.in_progress(), 2);
+        let whole = feed_from(&mut reassembler, sender(1), &from_a[1], Duration::ZERO)
+            .unwrap()
+            .expect("complete")
+            .to_vec();
+        assert_eq!(MessageView::parse(&whole).unwrap().payload_bytes(), a);
+        let whole = feed_from(&mut reassembler, sender(2), &from_b[0], Duration::ZERO)
+            .unwrap()
+            .expect("complete")
+            .to_vec();
+        assert_eq!(MessageView::parse(&whole).unwrap().payload_bytes(), b);
+    }
+
+    #[test]
+    fn drops_stale_reassemblies_after_timeout() {
+        let payload = [0x55; 40];
+        let parts = segments(1, &payload, 52);
+        let mut reassembler = Reassembler::<2, 256>::new(SECOND);
+        assert_eq!(feed(&mut reassembler, &parts[0], Duration::ZERO), Ok(None));
+        assert_eq!(reassembler.expire(Duration::from_millis(999)), 0);
+        assert_eq!(reassembler.expire(SECOND), 1);
+        // The rest of the message now starts a new, incomplete reassembly.
+        assert_eq!(feed(&mut reassembler, &parts[1], SECOND), Ok(None));
+        assert_eq!(reassembler.in_progress(), 1);
+    }
+
+    #[test]
+    fn rejects_misaligned_oversize_and_conflicting_segments() {
+        let mut reassembler = Reassembler::<2, 256>::new(SECOND);
+
+        let mut misaligned = segments(1, &[0u8; 40], 52)[0].clone();
+        misaligned.pop();
+        misaligned[7] -= 1;
+        assert_eq!(
+            feed(&mut reassembler, &misaligned, Duration::ZERO),
+            Err(Error::MisalignedSegment { offset: 0, len: 31 })
+        );
+
+        let oversize = segments(2, &[0u8; 300], 400);
+        assert_eq!(
+            feed(&mut reassembler, &oversize[0], Duration::ZERO),
+            Err(Error::TooLarge {
+                len: 300,
+                limit: 240
+            })
+        );
+
+        let long = segments(3, &[0u8; 100], 52);
+        let short = segments(3, &[0u8; 40], 52);
+        assert_eq!(feed(&mut reassembler, &long[3], Duration::ZERO), Ok(None));
+        assert_eq!(
+            feed(&mut reassembler, &short[1], Duration::ZERO),
+            Err(Error::Inconsistent { offset: 32 })
+        );
+        assert_eq!(reassembler.in_progress(), 0, "errors drop the message");
+    }
+}
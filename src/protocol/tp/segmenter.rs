@@ -0,0 +1,180 @@
+use super::{Error, SEGMENT_ALIGNMENT, TP_HEADER_LEN, TpHeader};
+use crate::protocol::{Header, MessageTypeField};
+use crate::traits::WireFormat;
+
+const SOMEIP_HEADER_LEN: usize = 16;
+
+/// Splits the payload of one SOME/IP message into SOME/IP-TP segments.
+///
+/// Every segment repeats the message's header with the TP flag set and
+/// its length adjusted, followed by a [`TpHeader`] and the data. All
+/// segments but the last carry the same amount of data: the largest
+/// multiple of [`SEGMENT_ALIGNMENT`] that fits `max_segment_len`.
+///
+/// ```
+/// use simple_someip::protocol::tp::Segmenter;
+/// use simple_someip::protocol::{Header, MessageId, MessageType, MessageTypeField, ReturnCode};
+///
+/// let payload = [0xAB; 3000];
+/// let message_id = MessageId::new_from_service_and_method(0x1234, 0x8001);
+/// let notification = MessageTypeField::new(MessageType::Notification, false);
+/// let header = Header::new(message_id, 1, 1, 1, notification, ReturnCode::Ok, payload.len());
+/// let mut segmenter = Segmenter::new(&header, &payload, 1400).unwrap();
+/// assert_eq!(segmenter.segment_count(), 3);
+/// let mut buf = [0u8; 1400];
+/// while let Some(len) = segmenter.write_next(&mut buf).unwrap() {
+///     // send &buf[..len]
+/// #   assert!(len <= 1400);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Segmenter<'a> {
+    header: Header,
+    payload: &'a [u8],
+    chunk_len: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> Segmenter<'a> {
+    /// Prepares to segment `payload`, sent with `header`, into datagrams
+    /// of at most `max_segment_len` bytes, SOME/IP and TP headers
+    /// included. The length field and TP flag of `header` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SegmentTooSmall`] if `max_segment_len` cannot hold
+    /// both headers and [`SEGMENT_ALIGNMENT`] bytes of data, and
+    /// [`Error::TooLarge`] if `payload` is beyond what TP offsets address.
+    pub fn new(header: &Header, payload: &'a [u8], max_segment_len: usize) -> Result<Self, Error> {
+        let chunk_len = max_segment_len.saturating_sub(SOMEIP_HEADER_LEN + TP_HEADER_LEN)
+            / SEGMENT_ALIGNMENT
+            * SEGMENT_ALIGNMENT;
+        if chunk_len == 0 {
+            return Err(Error::SegmentTooSmall(max_segment_len));
+        }
+        // Offsets are 32-bit and the length field covers 12 more bytes.
+        let limit = u32::MAX as usize - 8 - TP_HEADER_LEN;
+        if payload.len() > limit {
+            return Err(Error::TooLarge {
+                len: payload.len(),
+                limit,
+            });
+        }
+        Ok(Self {
+            header: header.clone(),
+            payload,
+            chunk_len,
+            offset: 0,
+            done: false,
+        })
+    }
+
+    /// Number of segments the payload is split into; an empty payload
+    /// still takes one.
+    #[must_use]
+    pub fn segment_count(&self) -> usize {
+        self.payload.len().div_ceil(self.chunk_len).max(1)
+    }
+
+    /// Writes the next segment into `buf`, returning its length, or
+    /// `None` once every segment has been written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SegmentTooSmall`] if `buf` cannot hold the
+    /// segment; the segment is not consumed.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write_next(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        let end = (self.offset + self.chunk_len).min(self.payload.len());
+        let data = &self.payload[self.offset..end];
+        let more_segments = end < self.payload.len();
+        let total = SOMEIP_HEADER_LEN + TP_HEADER_LEN + data.len();
+        let Some(buf) = buf.get_mut(..total) else {
+            return Err(Error::SegmentTooSmall(buf.len()));
+        };
+        // Lengths and offsets were bounded by `new`.
+        let header = Header::from_fields(
+            self.header.message_id(),
+            (8 + TP_HEADER_LEN + data.len()) as u32,
+            self.header.request_id(),
+            self.header.protocol_version(),
+            self.header.interface_version(),
+            MessageTypeField::new(self.header.message_type().message_type(), true),
+            self.header.return_code(),
+        );
+        header
+            .encode_to_slice(buf)
+            .map_err(|_| Error::SegmentTooSmall(total))?;
+        let tp_header = TpHeader::new(self.offset as u32, more_segments);
+        buf[SOMEIP_HEADER_LEN..SOMEIP_HEADER_LEN + TP_HEADER_LEN]
+            .copy_from_slice(&tp_header.to_bytes());
+        buf[SOMEIP_HEADER_LEN + TP_HEADER_LEN..].copy_from_slice(data);
+        self.offset = end;
+        self.done = !more_segments;
+        Ok(Some(total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::tp::parse_segment;
+    use crate::protocol::{MessageId, MessageType, MessageView, ReturnCode};
+
+    fn header(payload_len: usize) -> Header {
+        Header::new(
+            MessageId::new_from_service_and_method(0x1234, 0x0001),
+            0x0001_0002,
+            1,
+            3,
+            MessageTypeField::new(MessageType::Request, false),
+            ReturnCode::Ok,
+            payload_len,
+        )
+    }
+
+    #[test]
+    fn segments_carry_aligned_offsets_and_more_flag_until_last() {
+        let payload: [u8; 100] = core::array::from_fn(|i| u8::try_from(i).unwrap());
+        // 20 bytes of headers leave 44, rounded down to 32 per segment.
+        let mut segmenter = Segmenter::new(&header(payload.len()), &payload, 64).unwrap();
+        assert_eq!(segmenter.segment_count(), 4);
+        let mut buf = [0u8; 64];
+        let mut seen = [0u8; 100];
+        let mut segments = 0;
+        while let Some(len) = segmenter.write_next(&mut buf).unwrap() {
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            assert_eq!(view.header().request_id(), 0x0001_0002);
+            assert_eq!(
+                view.header().message_type().message_type(),
+                MessageType::Request
+            );
+            let (tp, data) = parse_segment(&view).unwrap();
+            assert_eq!(tp.offset() as usize, segments * 32);
+            assert_eq!(tp.more_segments(), segments < 3);
+            seen[tp.offset() as usize..][..data.len()].copy_from_slice(data);
+            segments += 1;
+        }
+        assert_eq!(segments, 4);
+        assert_eq!(seen, payload);
+    }
+
+    #[test]
+    fn rejects_buffers_too_small_for_one_block() {
+        assert_eq!(
+            Segmenter::new(&header(0), &[], 35).unwrap_err(),
+            Error::SegmentTooSmall(35)
+        );
+        let payload = [0u8; 64];
+        let mut segmenter = Segmenter::new(&header(64), &payload, 52).unwrap();
+        assert_eq!(
+            segmenter.write_next(&mut [0u8; 40]),
+            Err(Error::SegmentTooSmall(40))
+        );
+        assert_eq!(segmenter.write_next(&mut [0u8; 52]), Ok(Some(52)));
+    }
+}
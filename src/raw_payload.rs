@@ -57,6 +57,19 @@ pub struct RawPayload {
 }
 
 impl RawPayload {
+    /// An opaque payload of `bytes` for `message_id`, taking ownership of
+    /// them, for tooling that forwards messages without knowing their
+    /// schema. The bytes are kept as they are even for
+    /// [`MessageId::SD`]; build SD payloads with
+    /// [`new_sd_payload`](PayloadWireFormat::new_sd_payload).
+    #[must_use]
+    pub const fn new(message_id: MessageId, bytes: Vec<u8>) -> Self {
+        Self {
+            message_id,
+            kind: RawPayloadKind::Raw(bytes),
+        }
+    }
+
     /// Returns the raw payload bytes for non-SD messages, or `None` for SD messages.
     #[must_use]
     pub fn raw_bytes(&self) -> Option<&[u8]> {
@@ -65,6 +78,16 @@ impl RawPayload {
             RawPayloadKind::Sd(_) => None,
         }
     }
+
+    /// Takes the raw payload bytes of a non-SD message, or returns `None`
+    /// for an SD message.
+    #[must_use]
+    pub fn into_raw_bytes(self) -> Option<Vec<u8>> {
+        match self.kind {
+            RawPayloadKind::Raw(bytes) => Some(bytes),
+            RawPayloadKind::Sd(_) => None,
+        }
+    }
 }
 
 impl PayloadWireFormat for RawPayload {
@@ -171,6 +194,41 @@ impl PayloadWireFormat for RawPayload {
         }
     }
 
+    fn push_subscription_entry(
+        header: &mut VecSdHeader,
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        ttl: u32,
+        event_group_id: u16,
+    ) -> bool {
+        header
+            .entries
+            .push(sd::Entry::SubscribeEventGroup(sd::EventGroupEntry::new(
+                service_id,
+                instance_id,
+                major_version,
+                ttl,
+                event_group_id,
+            )));
+        true
+    }
+
+    fn new_find_service_sd_header(
+        service_id: u16,
+        instance_id: u16,
+        reboot_flag: sd::RebootFlag,
+    ) -> Option<VecSdHeader> {
+        Some(VecSdHeader {
+            flags: sd::Flags::new_sd(reboot_flag),
+            entries: std::vec![sd::Entry::FindService(sd::ServiceEntry::find_instance(
+                service_id,
+                instance_id,
+            ))],
+            options: std::vec::Vec::new(),
+        })
+    }
+
     fn set_reboot_flag(header: &mut VecSdHeader, reboot: sd::RebootFlag) {
         header.flags = sd::Flags::new(bool::from(reboot), header.flags.unicast());
     }
@@ -186,10 +244,8 @@ impl PayloadWireFormat for RawPayload {
         for entry in &header.entries {
             if let sd::Entry::OfferService(svc) | sd::Entry::StopOfferService(svc) = entry {
                 let is_offer = matches!(entry, sd::Entry::OfferService(_));
-                let endpoint =
-                    sd::extract_ipv4_endpoint(&header.options).map(|(addr, protocol)| {
-                        crate::NetEndpoint::new(core::net::SocketAddr::V4(addr), protocol)
-                    });
+                let endpoint = sd::extract_endpoint(&header.options)
+                    .map(|(addr, protocol)| crate::NetEndpoint::new(addr, protocol));
                 f(crate::OfferedEndpoint {
                     service_id: svc.service_id,
                     instance_id: svc.instance_id,
@@ -197,6 +253,9 @@ impl PayloadWireFormat for RawPayload {
                     minor_version: svc.minor_version,
                     endpoint,
                     is_offer,
+                    ttl: svc.ttl,
+                    configuration: sd::extract_configuration(&header.options),
+                    load_balancing: sd::extract_load_balancing(&header.options),
                 });
             }
         }
@@ -222,6 +281,27 @@ impl PayloadWireFormat for RawPayload {
             f(svc, inst);
         }
     }
+
+    fn for_each_subscription_ack<F>(&self, mut f: F)
+    where
+        F: FnMut(crate::SubscriptionAck),
+    {
+        let header = match &self.kind {
+            RawPayloadKind::Sd(header) => header,
+            RawPayloadKind::Raw(_) => return,
+        };
+        for entry in &header.entries {
+            if let sd::Entry::SubscribeAckEventGroup(eg) = entry {
+                f(crate::SubscriptionAck {
+                    service_id: eg.service_id,
+                    instance_id: eg.instance_id,
+                    major_version: eg.major_version,
+                    event_group_id: eg.event_group_id,
+                    ttl: eg.ttl,
+                });
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -287,6 +367,22 @@ mod tests {
         assert_eq!(p.raw_bytes(), Some(&[0xDE, 0xAD][..]));
     }
 
+    #[test]
+    fn new_keeps_the_bytes_opaque() {
+        let mid = MessageId::new_from_service_and_method(0x1234, 0x8001);
+        let p = RawPayload::new(mid, std::vec![0xDE, 0xAD]);
+        assert_eq!(
+            p,
+            RawPayload::from_payload_bytes(mid, &[0xDE, 0xAD]).unwrap()
+        );
+        assert!(p.as_sd_header().is_none());
+        assert_eq!(p.into_raw_bytes(), Some(std::vec![0xDE, 0xAD]));
+
+        let sd = RawPayload::new(MessageId::SD, std::vec![0; 12]);
+        assert!(sd.as_sd_header().is_none(), "not parsed as SD");
+        assert_eq!(make_sd_payload().into_raw_bytes(), None);
+    }
+
     #[test]
     fn raw_bytes_returns_none_for_sd_payload() {
         let p = make_sd_payload();
@@ -411,6 +507,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn push_subscription_entry_shares_the_endpoint_option() {
+        let mut header = RawPayload::new_subscription_sd_header(
+            0x5B,
+            1,
+            1,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            12345,
+            sd::RebootFlag::Continuous,
+        );
+        assert!(RawPayload::push_subscription_entry(
+            &mut header,
+            0x5B,
+            1,
+            1,
+            3,
+            0x02
+        ));
+        assert_eq!(header.entries.len(), 2);
+        assert_eq!(header.options.len(), 1);
+        let sd::Entry::SubscribeEventGroup(entry) = &header.entries[1] else {
+            panic!("expected a SubscribeEventgroup entry");
+        };
+        assert_eq!(entry.event_group_id, 0x02);
+        assert_eq!(entry.index_first_options_run, 0);
+        assert_eq!(entry.options_count.first_options_count, 1);
+    }
+
     #[test]
     fn offered_endpoints_from_raw_returns_empty() {
         let p = make_raw_payload();
@@ -421,7 +548,7 @@ mod tests {
         sd::ServiceEntry {
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: sd::OptionsCount::new(1, 0),
+            options_count: sd::OptionsCount::ONE,
             service_id,
             instance_id,
             major_version: 1,
@@ -456,6 +583,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn offered_endpoints_with_ipv6_endpoint() {
+        let offer = sd::Entry::OfferService(make_offer_entry(0x5B, 1));
+        let ip = core::net::Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 0x10);
+        let endpoint = sd::Options::IpV6Endpoint {
+            ip,
+            protocol: sd::TransportProtocol::Tcp,
+            port: 30000,
+        };
+        let header = VecSdHeader {
+            flags: sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            entries: std::vec![offer],
+            options: std::vec![endpoint],
+        };
+        let p = RawPayload::new_sd_payload(&header);
+        let ep = p.offered_endpoints()[0]
+            .endpoint
+            .expect("endpoint option present");
+        assert_eq!(ep.protocol, crate::TransportProtocol::Tcp);
+        assert_eq!(ep.addr, core::net::SocketAddr::from((ip, 30000)));
+    }
+
+    #[test]
+    fn offered_endpoints_carry_the_configuration_option() {
+        let offer = sd::Entry::OfferService(make_offer_entry(0x5B, 1));
+        let mut configuration = sd::ConfigurationString::new();
+        configuration.push("profile", Some("high")).unwrap();
+        let header = VecSdHeader {
+            flags: sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            entries: std::vec![offer],
+            options: std::vec![sd::Options::configuration(&configuration)],
+        };
+        let p = RawPayload::new_sd_payload(&header);
+        let endpoints = p.offered_endpoints();
+        assert_eq!(endpoints[0].configuration, configuration);
+        assert_eq!(
+            endpoints[0].configuration.get("profile"),
+            Some(Some("high"))
+        );
+    }
+
     #[test]
     fn offered_endpoints_with_stop_offer() {
         let mut entry = make_offer_entry(0x5B, 1);
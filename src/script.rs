@@ -0,0 +1,534 @@
+use core::net::SocketAddrV4;
+use core::pin::pin;
+use core::time::Duration;
+use std::string::String;
+use std::vec;
+use std::vec::Vec;
+
+use futures_util::future::{Either, select};
+
+use crate::protocol::{
+    self, Header, MessageId, MessageType, MessageTypeField, MessageView, ReturnCode,
+};
+use crate::traits::WireFormat;
+use crate::transport::{Timer, TransportError, TransportSocket};
+
+/// Protocol version written into every scripted header.
+const PROTOCOL_VERSION: u8 = 0x01;
+
+/// Errors produced while building, sending or awaiting scripted messages.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    /// The socket failed to send or receive.
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    /// The scripted message could not be encoded.
+    #[error(transparent)]
+    Protocol(#[from] protocol::Error),
+    /// No datagram matched the predicate before the timeout elapsed.
+    #[error("no matching reply within the timeout")]
+    Timeout,
+    /// A payload ended before the field table did.
+    #[error("payload too short for field {name:?} at offset {offset}")]
+    PayloadTooShort {
+        /// Name of the field that could not be read.
+        name: String,
+        /// Byte offset in the payload where the field starts.
+        offset: usize,
+    },
+}
+
+/// One value in a scripted payload, encoded big-endian with no padding
+/// or length prefix, as SOME/IP serializes fixed-size basic types.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldValue {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    /// One byte, `0x01` for `true`.
+    Bool(bool),
+    /// Bytes copied verbatim, for pre-serialized structures.
+    Bytes(Vec<u8>),
+}
+
+impl FieldValue {
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::U8(v) => out.push(*v),
+            Self::U16(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::U32(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::U64(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::I8(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::I16(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::I32(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::I64(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::F32(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::F64(v) => out.extend_from_slice(&v.to_be_bytes()),
+            Self::Bool(v) => out.push(u8::from(*v)),
+            Self::Bytes(v) => out.extend_from_slice(v),
+        }
+    }
+}
+
+/// The type of a field to read back out of a reply payload; the decoding
+/// counterpart of [`FieldValue`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+    Bool,
+    /// A fixed number of raw bytes.
+    Bytes(usize),
+}
+
+impl FieldKind {
+    const fn encoded_len(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 | Self::Bool => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+            Self::Bytes(len) => len,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> FieldValue {
+        fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+            let mut out = [0; N];
+            out.copy_from_slice(bytes);
+            out
+        }
+        match self {
+            Self::U8 => FieldValue::U8(bytes[0]),
+            Self::U16 => FieldValue::U16(u16::from_be_bytes(array(bytes))),
+            Self::U32 => FieldValue::U32(u32::from_be_bytes(array(bytes))),
+            Self::U64 => FieldValue::U64(u64::from_be_bytes(array(bytes))),
+            Self::I8 => FieldValue::I8(i8::from_be_bytes(array(bytes))),
+            Self::I16 => FieldValue::I16(i16::from_be_bytes(array(bytes))),
+            Self::I32 => FieldValue::I32(i32::from_be_bytes(array(bytes))),
+            Self::I64 => FieldValue::I64(i64::from_be_bytes(array(bytes))),
+            Self::F32 => FieldValue::F32(f32::from_be_bytes(array(bytes))),
+            Self::F64 => FieldValue::F64(f64::from_be_bytes(array(bytes))),
+            Self::Bool => FieldValue::Bool(bytes[0] != 0),
+            Self::Bytes(_) => FieldValue::Bytes(bytes.to_vec()),
+        }
+    }
+}
+
+/// Decode `payload` against a table of named fields, in order. Trailing
+/// bytes past the last field are ignored.
+///
+/// # Errors
+///
+/// Returns [`Error::PayloadTooShort`] naming the first field that does
+/// not fit in `payload`.
+pub fn decode_fields<N: AsRef<str>>(
+    payload: &[u8],
+    fields: &[(N, FieldKind)],
+) -> Result<Vec<(String, FieldValue)>, Error> {
+    let mut offset = 0;
+    let mut out = Vec::with_capacity(fields.len());
+    for (name, kind) in fields {
+        let end = offset + kind.encoded_len();
+        let Some(bytes) = payload.get(offset..end) else {
+            return Err(Error::PayloadTooShort {
+                name: name.as_ref().into(),
+                offset,
+            });
+        };
+        out.push((name.as_ref().into(), kind.decode(bytes)));
+        offset = end;
+    }
+    Ok(out)
+}
+
+/// A SOME/IP message described as a header plus a table of named fields.
+///
+/// Every setter takes plain integers and enums so the type binds
+/// directly into a scripting language; the field names only label the
+/// table and are not put on the wire.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptMessage {
+    pub service_id: u16,
+    pub method_id: u16,
+    pub interface_version: u8,
+    pub message_type: MessageType,
+    pub return_code: ReturnCode,
+    /// Request ID to send; `None` lets [`ScriptSession::send`] stamp the
+    /// session's next one.
+    pub request_id: Option<u32>,
+    pub fields: Vec<(String, FieldValue)>,
+}
+
+impl ScriptMessage {
+    /// A request to `method_id` of `service_id`, interface version 1, with
+    /// no fields.
+    #[must_use]
+    pub const fn request(service_id: u16, method_id: u16) -> Self {
+        Self {
+            service_id,
+            method_id,
+            interface_version: 1,
+            message_type: MessageType::Request,
+            return_code: ReturnCode::Ok,
+            request_id: None,
+            fields: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_interface_version(mut self, interface_version: u8) -> Self {
+        self.interface_version = interface_version;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_message_type(mut self, message_type: MessageType) -> Self {
+        self.message_type = message_type;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_return_code(mut self, return_code: ReturnCode) -> Self {
+        self.return_code = return_code;
+        self
+    }
+
+    /// Send with this exact request ID instead of the session's next one.
+    #[must_use]
+    pub const fn with_request_id(mut self, client_id: u16, session_id: u16) -> Self {
+        self.request_id = Some((client_id as u32) << 16 | session_id as u32);
+        self
+    }
+
+    /// Append a field to the payload table.
+    #[must_use]
+    pub fn with_field(mut self, name: impl Into<String>, value: FieldValue) -> Self {
+        self.fields.push((name.into(), value));
+        self
+    }
+
+    /// The payload: every field encoded in table order.
+    #[must_use]
+    pub fn payload(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (_, value) in &self.fields {
+            value.encode_into(&mut out);
+        }
+        out
+    }
+
+    /// The whole datagram, header included, sent with `request_id`
+    /// unless the message carries its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] if the payload is too large for a
+    /// SOME/IP length field.
+    pub fn to_bytes(&self, request_id: u32) -> Result<Vec<u8>, Error> {
+        let payload = self.payload();
+        let limit = u32::MAX as usize - 8;
+        if payload.len() > limit {
+            return Err(protocol::Error::PayloadTooLarge {
+                len: payload.len(),
+                limit,
+            }
+            .into());
+        }
+        let header = Header::new(
+            MessageId::new_from_service_and_method(self.service_id, self.method_id),
+            self.request_id.unwrap_or(request_id),
+            PROTOCOL_VERSION,
+            self.interface_version,
+            MessageTypeField::new(self.message_type, false),
+            self.return_code,
+            payload.len(),
+        );
+        let mut out = header.encode_to_vec()?;
+        out.extend_from_slice(&payload);
+        Ok(out)
+    }
+}
+
+/// A datagram received by a [`ScriptSession`], owned so it can be handed
+/// across a language boundary.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptReply {
+    pub source: SocketAddrV4,
+    pub header: Header,
+    pub payload: Vec<u8>,
+}
+
+impl ScriptReply {
+    #[must_use]
+    pub const fn service_id(&self) -> u16 {
+        self.header.message_id().service_id()
+    }
+
+    #[must_use]
+    pub const fn method_id(&self) -> u16 {
+        self.header.message_id().method_id()
+    }
+
+    #[must_use]
+    pub const fn message_type(&self) -> MessageType {
+        self.header.message_type().message_type()
+    }
+
+    #[must_use]
+    pub const fn return_code(&self) -> ReturnCode {
+        self.header.return_code()
+    }
+
+    /// `true` if this is the response or error to the request sent with
+    /// `request_id` to `service_id`/`method_id`.
+    #[must_use]
+    pub const fn answers(&self, service_id: u16, method_id: u16, request_id: u32) -> bool {
+        self.service_id() == service_id
+            && self.method_id() == method_id
+            && self.header.request_id() == request_id
+            && matches!(
+                self.message_type(),
+                MessageType::Response | MessageType::Error
+            )
+    }
+
+    /// Decode the payload against a field table; see [`decode_fields`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PayloadTooShort`] if the payload ends early.
+    pub fn decode_fields<N: AsRef<str>>(
+        &self,
+        fields: &[(N, FieldKind)],
+    ) -> Result<Vec<(String, FieldValue)>, Error> {
+        decode_fields(&self.payload, fields)
+    }
+}
+
+/// Sends [`ScriptMessage`]s to one target and waits for replies, over a
+/// bound [`TransportSocket`].
+///
+/// The session bypasses the [`Client`](crate::client::Client) entirely:
+/// it performs no service discovery and no request bookkeeping, so a
+/// test can send any header it likes, including malformed ones, and
+/// observe exactly what comes back.
+pub struct ScriptSession<S, Tm> {
+    socket: S,
+    timer: Tm,
+    target: SocketAddrV4,
+    client_id: u16,
+    next_session_id: u16,
+    recv_buffer: Vec<u8>,
+}
+
+impl<S: TransportSocket, Tm: Timer> ScriptSession<S, Tm> {
+    /// A session sending to `target` over `socket`, stamping request IDs
+    /// with client ID 0.
+    #[must_use]
+    pub fn new(socket: S, timer: Tm, target: SocketAddrV4) -> Self {
+        Self {
+            socket,
+            timer,
+            target,
+            client_id: 0,
+            next_session_id: 1,
+            recv_buffer: vec![0; crate::UDP_BUFFER_SIZE],
+        }
+    }
+
+    /// Use `client_id` in the request IDs this session stamps.
+    #[must_use]
+    pub const fn with_client_id(mut self, client_id: u16) -> Self {
+        self.client_id = client_id;
+        self
+    }
+
+    /// Where messages are sent.
+    #[must_use]
+    pub const fn target(&self) -> SocketAddrV4 {
+        self.target
+    }
+
+    /// Send `message` and return the request ID it went out with. The
+    /// session ID advances on every send, skipping 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Protocol`] if the message cannot be encoded and
+    /// [`Error::Transport`] if the socket fails.
+    pub async fn send(&mut self, message: &ScriptMessage) -> Result<u32, Error> {
+        let request_id = message.request_id.unwrap_or_else(|| {
+            let session_id = self.next_session_id;
+            self.next_session_id = self.next_session_id.checked_add(1).unwrap_or(1);
+            u32::from(self.client_id) << 16 | u32::from(session_id)
+        });
+        let bytes = message.to_bytes(request_id)?;
+        self.socket.send_to(&bytes, self.target).await?;
+        Ok(request_id)
+    }
+
+    /// Wait for the first datagram that parses as SOME/IP and satisfies
+    /// `predicate`. Everything else received meanwhile is discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if nothing matched within `timeout`
+    /// and [`Error::Transport`] if the socket fails.
+    pub async fn await_reply(
+        &mut self,
+        timeout: Duration,
+        mut predicate: impl FnMut(&ScriptReply) -> bool,
+    ) -> Result<ScriptReply, Error> {
+        let mut deadline = pin!(self.timer.sleep(timeout));
+        loop {
+            let received = {
+                let recv = pin!(self.socket.recv_from(&mut self.recv_buffer));
+                match select(recv, deadline.as_mut()).await {
+                    Either::Left((received, _)) => received?,
+                    Either::Right(((), _)) => return Err(Error::Timeout),
+                }
+            };
+            if received.truncated {
+                continue;
+            }
+            let Ok(view) = MessageView::parse(&self.recv_buffer[..received.bytes_received]) else {
+                continue;
+            };
+            let reply = ScriptReply {
+                source: received.source,
+                header: view.header().to_owned(),
+                payload: view.payload_bytes().to_vec(),
+            };
+            if predicate(&reply) {
+                return Ok(reply);
+            }
+        }
+    }
+
+    /// Send `message` and wait for its response or error, matched by
+    /// message ID and request ID.
+    ///
+    /// # Errors
+    ///
+    /// As [`Self::send`] and [`Self::await_reply`].
+    pub async fn call(
+        &mut self,
+        message: &ScriptMessage,
+        timeout: Duration,
+    ) -> Result<ScriptReply, Error> {
+        let request_id = self.send(message).await?;
+        self.await_reply(timeout, |reply| {
+            reply.answers(message.service_id, message.method_id, request_id)
+        })
+        .await
+    }
+}
+
+#[cfg(all(test, feature = "client-tokio"))]
+mod tests {
+    use core::net::Ipv4Addr;
+
+    use super::*;
+    use crate::tokio_transport::{TokioTimer, TokioTransport};
+    use crate::transport::{SocketOptions, TransportFactory};
+
+    #[test]
+    fn field_table_round_trips_through_the_payload() {
+        let message = ScriptMessage::request(0x1234, 0x0001)
+            .with_field("speed", FieldValue::U16(0x0102))
+            .with_field("offset", FieldValue::I32(-2))
+            .with_field("enabled", FieldValue::Bool(true))
+            .with_field("gain", FieldValue::F32(1.5))
+            .with_field("tag", FieldValue::Bytes(vec![0xAA, 0xBB]));
+        let bytes = message.to_bytes(0x0000_0007).unwrap();
+        let view = MessageView::parse(&bytes).unwrap();
+        assert_eq!(view.header().message_id().service_id(), 0x1234);
+        assert_eq!(view.header().request_id(), 7);
+
+        let decoded = decode_fields(
+            view.payload_bytes(),
+            &[
+                ("speed", FieldKind::U16),
+                ("offset", FieldKind::I32),
+                ("enabled", FieldKind::Bool),
+                ("gain", FieldKind::F32),
+                ("tag", FieldKind::Bytes(2)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(decoded, message.fields);
+    }
+
+    #[test]
+    fn short_payload_names_the_missing_field() {
+        let err = decode_fields(
+            &[0x01, 0x02],
+            &[("a", FieldKind::U8), ("b", FieldKind::U16)],
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::PayloadTooShort { ref name, offset: 1 } if name == "b"),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn call_skips_unrelated_datagrams_and_times_out_without_a_reply() {
+        let any = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+        let options = SocketOptions::default();
+        let peer = TokioTransport.bind(any, &options).await.unwrap();
+        let local = TokioTransport.bind(any, &options).await.unwrap();
+        let local_addr = local.local_addr().unwrap();
+        let mut session =
+            ScriptSession::new(local, TokioTimer, peer.local_addr().unwrap()).with_client_id(0x42);
+
+        let request = ScriptMessage::request(0x1234, 0x0001).with_field("x", FieldValue::U8(9));
+        let responder = async {
+            let mut buf = [0u8; 64];
+            let received = peer.recv_from(&mut buf).await.unwrap();
+            let view = MessageView::parse(&buf[..received.bytes_received]).unwrap();
+            let request_id = view.header().request_id();
+            let reply = |request_id| {
+                ScriptMessage::request(0x1234, 0x0001)
+                    .with_message_type(MessageType::Response)
+                    .with_field("y", FieldValue::U16(0xBEEF))
+                    .to_bytes(request_id)
+                    .unwrap()
+            };
+            // A stale response first, then the real one.
+            peer.send_to(&reply(request_id + 1), local_addr)
+                .await
+                .unwrap();
+            peer.send_to(&reply(request_id), local_addr).await.unwrap();
+        };
+        let (reply, ()) = tokio::join!(session.call(&request, Duration::from_secs(2)), responder);
+        let reply = reply.unwrap();
+        assert_eq!(reply.header.request_id(), 0x0042_0001);
+        assert_eq!(
+            reply.decode_fields(&[("y", FieldKind::U16)]).unwrap(),
+            vec![("y".into(), FieldValue::U16(0xBEEF))]
+        );
+
+        let err = session
+            .call(&request, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout), "{err:?}");
+    }
+}
@@ -13,8 +13,9 @@ use core::sync::atomic::{AtomicU16, Ordering};
 
 use crate::WireFormat;
 use crate::protocol::sd::{
-    Entry, EventGroupEntry, Flags, Header as SdHeader, Options as SdOptions, OptionsCount,
-    RebootFlag, SdHeaderView, ServiceEntry, TransportProtocol,
+    Entry, EventGroupEntry, Flags, Header as SdHeader, MessageBuilder as SdMessageBuilder,
+    Options as SdOptions, OptionsCount, RebootFlag, SdHeaderView, ServiceEntry, TTL_INFINITE,
+    TransportProtocol,
 };
 use crate::protocol::{Header, HeaderView, MessageId, MessageType, MessageTypeField, ReturnCode};
 use crate::transport::E2ERegistryHandle;
@@ -94,7 +95,7 @@ pub fn build_subscribe_eventgroup_datagram(
     let entry = Entry::SubscribeEventGroup(EventGroupEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(1, 0),
+        options_count: OptionsCount::ONE,
         service_id: request.service_id,
         instance_id: request.instance_id,
         major_version: request.major_version,
@@ -137,9 +138,9 @@ pub fn build_stop_offer_service_datagram(
 }
 
 /// Encode a single SD datagram carrying one `OfferService` entry per
-/// element of `requests` (up to `N`). Each entry references its own
-/// IPv4 endpoint option at the matching flat index — one coherent SD
-/// message instead of one packet per service.
+/// element of `requests` (up to `N`). Each entry references the IPv4
+/// endpoint option of its request, shared by requests with the same
+/// endpoint — one coherent SD message instead of one packet per service.
 ///
 /// # Errors
 /// See [`BuildError`].
@@ -170,20 +171,17 @@ fn build_multi_service_entry_datagram<const N: usize>(
     session: u16,
     stop: bool,
 ) -> Result<usize, BuildError> {
-    let mut entries: heapless::Vec<Entry, N> = heapless::Vec::new();
-    let mut options: heapless::Vec<SdOptions, N> = heapless::Vec::new();
-    for (i, req) in requests.iter().enumerate().take(N) {
-        #[allow(clippy::cast_possible_truncation)]
+    let mut message = SdMessageBuilder::<N, N>::new(Flags::new_sd(RebootFlag::Continuous));
+    for req in requests.iter().take(N) {
         let svc = ServiceEntry {
-            // Each entry owns exactly one option at position `i` in the
-            // flat options array.
-            index_first_options_run: i as u8,
+            index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: OptionsCount::new(1, 0),
+            options_count: OptionsCount::ONE,
             service_id: req.service_id,
             instance_id: req.instance_id,
             major_version: req.major_version,
-            ttl: if stop { 0 } else { req.ttl },
+            // Saturated like the single-entry builders' encoding.
+            ttl: if stop { 0 } else { req.ttl.min(TTL_INFINITE) },
             minor_version: req.minor_version,
         };
         let entry = if stop {
@@ -191,18 +189,23 @@ fn build_multi_service_entry_datagram<const N: usize>(
         } else {
             Entry::OfferService(svc)
         };
-        entries
-            .push(entry)
-            .map_err(|_| BuildError::BufferTooSmall)?;
-        options
-            .push(SdOptions::IpV4Endpoint {
-                ip: req.local_ip,
-                port: req.unicast_port,
-                protocol: TransportProtocol::Udp,
-            })
+        // Services behind the same endpoint share one option.
+        let endpoint = SdOptions::IpV4Endpoint {
+            ip: req.local_ip,
+            port: req.unicast_port,
+            protocol: TransportProtocol::Udp,
+        };
+        message
+            .push(entry, &[endpoint], &[])
             .map_err(|_| BuildError::BufferTooSmall)?;
     }
-    encode_sd_datagram(buf, &entries, &options, session, RebootFlag::Continuous)
+    encode_sd_datagram(
+        buf,
+        message.entries(),
+        message.options(),
+        session,
+        RebootFlag::Continuous,
+    )
 }
 
 /// Encode a `SubscribeAckEventgroup` datagram into `buf`. Caller
@@ -218,7 +221,7 @@ pub fn build_subscribe_ack_datagram(
     let entry = Entry::SubscribeAckEventGroup(EventGroupEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(0, 0),
+        options_count: OptionsCount::NONE,
         service_id: request.service_id,
         instance_id: request.instance_id,
         major_version: request.major_version,
@@ -238,7 +241,7 @@ fn encode_service_entry_datagram(
     let svc = ServiceEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(1, 0),
+        options_count: OptionsCount::ONE,
         service_id: request.service_id,
         instance_id: request.instance_id,
         major_version: request.major_version,
@@ -476,6 +479,18 @@ mod tests {
             })
             .collect();
         assert_eq!(services.as_slice(), &[0x0001, 0x0002, 0x0003]);
+        assert_eq!(view.options().count(), 3);
+
+        // Services behind one endpoint share its option.
+        let offers = [req(0x0001, 30501), req(0x0002, 30501), req(0x0003, 30503)];
+        let len = build_multi_offer_service_datagram::<8>(&mut buf, &offers, 8).unwrap();
+        let view = parse_someip_sd_datagram(&buf[..len]).expect("valid SD datagram");
+        assert_eq!(view.options().count(), 2);
+        let first_runs: heapless::Vec<u8, 8> = view
+            .entries()
+            .map(|e| e.index_first_options_run())
+            .collect();
+        assert_eq!(first_runs.as_slice(), &[0, 0, 1]);
     }
 
     #[test]
@@ -0,0 +1,90 @@
+//! Process-wide spacing of the first SD messages of servers and service
+//! searches, so dozens of them starting together on one ECU do not burst
+//! their SD frames in the same instant.
+//!
+//! Every participant reserves the instant of its first message on one
+//! shared schedule, kept on the [`Timer::now`](crate::Timer::now) clock
+//! in whole milliseconds. Clients and servers of one process share that
+//! clock (tokio's, or the embassy one), so their reservations line up.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+
+/// Milliseconds after which the next first message may go out, plus one
+/// so that `0` means nothing is reserved. Wraps after ~49 days, well past
+/// any start-up burst.
+static NEXT_FREE: AtomicU32 = AtomicU32::new(0);
+
+/// The delay to hold a first SD message for, at least `delay` from `now`
+/// and at least `spacing` after the first message of every other server
+/// or search of the process that reserved its slot before. A zero
+/// `spacing`, or no clock, leaves `delay` as it is.
+pub(crate) fn spread(spacing: Duration, now: Option<Duration>, delay: Duration) -> Duration {
+    reserve(&NEXT_FREE, spacing, now, delay)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn reserve(
+    next_free: &AtomicU32,
+    spacing: Duration,
+    now: Option<Duration>,
+    delay: Duration,
+) -> Duration {
+    let Some(now) = now else {
+        return delay;
+    };
+    // Truncation wraps the clock, which the comparisons below allow for.
+    let spacing_ms = spacing.as_millis() as u32;
+    if spacing_ms == 0 {
+        return delay;
+    }
+    let wanted = (now.saturating_add(delay).as_millis() as u32).wrapping_add(1);
+    let mut current = next_free.load(Ordering::Relaxed);
+    loop {
+        // A reservation ahead of `wanted` (by less than half the clock's
+        // range) pushes it back.
+        let slot = if current != 0 && current.wrapping_sub(wanted) < u32::MAX / 2 {
+            current
+        } else {
+            wanted
+        };
+        let next = slot.wrapping_add(spacing_ms).max(1);
+        match next_free.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => {
+                let pushed_back = Duration::from_millis(u64::from(slot.wrapping_sub(wanted)));
+                return delay.saturating_add(pushed_back);
+            }
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_messages_are_spaced_apart() {
+        let next_free = AtomicU32::new(0);
+        let ms = Duration::from_millis;
+        let spacing = ms(5);
+        let now = Some(ms(1000));
+
+        assert_eq!(reserve(&next_free, spacing, now, ms(0)), ms(0));
+        assert_eq!(reserve(&next_free, spacing, now, ms(0)), ms(5));
+        assert_eq!(reserve(&next_free, spacing, now, ms(2)), ms(10));
+        assert_eq!(
+            reserve(&next_free, spacing, now, ms(40)),
+            ms(40),
+            "past the burst"
+        );
+        assert_eq!(reserve(&next_free, spacing, Some(ms(1100)), ms(0)), ms(0));
+
+        assert_eq!(
+            reserve(&next_free, Duration::ZERO, now, ms(3)),
+            ms(3),
+            "no spacing"
+        );
+        assert_eq!(reserve(&next_free, spacing, None, ms(3)), ms(3), "no clock");
+    }
+}
@@ -0,0 +1,416 @@
+use core::fmt::Write as _;
+use core::net::SocketAddrV4;
+use core::time::Duration;
+use std::string::String;
+use std::sync::{Arc, Mutex};
+use std::vec::Vec;
+
+use crate::protocol::sd::EntryType;
+use crate::protocol::{MessageType, MessageView};
+use crate::tap::{DatagramTap, Direction, TappedDatagram};
+
+/// One datagram kept by a [`SessionRecorder`]: an owned
+/// [`TappedDatagram`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedDatagram {
+    pub direction: Direction,
+    /// Address of the socket that carried the datagram.
+    pub local: SocketAddrV4,
+    /// Sender of an inbound datagram, target of an outbound one.
+    pub peer: SocketAddrV4,
+    /// When the datagram was seen, `None` if the timer has no clock.
+    pub timestamp: Option<Duration>,
+    /// The raw datagram, starting at the SOME/IP header.
+    pub bytes: Vec<u8>,
+}
+
+impl RecordedDatagram {
+    /// The sending address.
+    #[must_use]
+    pub fn source(&self) -> SocketAddrV4 {
+        match self.direction {
+            Direction::Inbound => self.peer,
+            Direction::Outbound => self.local,
+        }
+    }
+
+    /// The receiving address.
+    #[must_use]
+    pub fn destination(&self) -> SocketAddrV4 {
+        match self.direction {
+            Direction::Inbound => self.local,
+            Direction::Outbound => self.peer,
+        }
+    }
+}
+
+impl From<&TappedDatagram<'_>> for RecordedDatagram {
+    fn from(datagram: &TappedDatagram<'_>) -> Self {
+        Self {
+            direction: datagram.direction,
+            local: datagram.local,
+            peer: datagram.peer,
+            timestamp: datagram.timestamp,
+            bytes: datagram.bytes.to_vec(),
+        }
+    }
+}
+
+/// A [`DatagramTap`] that keeps every datagram, for turning a session into
+/// a [`sequence_diagram`].
+///
+/// Clones share the recording, so one recorder can be installed on a
+/// client and a server at once; a datagram between them is then recorded
+/// twice, once per side.
+///
+/// ```no_run
+/// # #[cfg(feature = "client-tokio")]
+/// # async fn demo() {
+/// use simple_someip::sequence_diagram::SessionRecorder;
+/// use simple_someip::{Client, ClientDeps, RawPayload, TokioChannels};
+/// use std::net::Ipv4Addr;
+///
+/// let recorder = SessionRecorder::new();
+/// let deps = ClientDeps::tokio(Ipv4Addr::LOCALHOST).with_tap(recorder.clone());
+/// let (_client, _updates, run) =
+///     Client::<RawPayload, _, _, TokioChannels>::new_with_deps(deps, false);
+/// tokio::spawn(run);
+/// // ... exercise the system, then:
+/// println!("{}", recorder.sequence_diagram());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SessionRecorder {
+    datagrams: Arc<Mutex<Vec<RecordedDatagram>>>,
+}
+
+impl SessionRecorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The datagrams recorded so far, oldest first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the recording's mutex is poisoned.
+    #[must_use]
+    pub fn datagrams(&self) -> Vec<RecordedDatagram> {
+        self.datagrams
+            .lock()
+            .expect("recorder lock poisoned")
+            .clone()
+    }
+
+    /// Forget the datagrams recorded so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the recording's mutex is poisoned.
+    pub fn clear(&self) {
+        self.datagrams
+            .lock()
+            .expect("recorder lock poisoned")
+            .clear();
+    }
+
+    /// [`sequence_diagram`] of the datagrams recorded so far.
+    #[must_use]
+    pub fn sequence_diagram(&self) -> String {
+        sequence_diagram(&self.datagrams())
+    }
+}
+
+impl DatagramTap for SessionRecorder {
+    fn on_datagram(&self, datagram: &TappedDatagram<'_>) {
+        if let Ok(mut datagrams) = self.datagrams.lock() {
+            datagrams.push(datagram.into());
+        }
+    }
+}
+
+/// Identifies the response to a request: requester, responder, message
+/// ID and request ID.
+type Exchange = (SocketAddrV4, SocketAddrV4, u32, u32);
+
+/// Render `datagrams` as a textual sequence, one line per SD entry or
+/// message:
+///
+/// ```text
+///    0.000s  192.168.1.10:30490 -> 224.224.224.245:30490  SD OfferService 0x1234/0x0001 v1.0 ttl 3
+///    0.004s  192.168.1.20:30490 -> 192.168.1.10:30490  SD Subscribe 0x1234/0x0001 eventgroup 0x0001 ttl 3
+///    0.101s  192.168.1.20:40000 -> 192.168.1.10:30500  REQUEST 0x1234.0x0001 session 0x0005 (4 bytes)
+///    0.103s  192.168.1.10:30500 -> 192.168.1.20:40000  RESPONSE 0x1234.0x0001 session 0x0005 (2 bytes) after 2.000 ms
+/// ```
+///
+/// Times are relative to the first timestamped datagram (`-` where the
+/// timer had no clock). Responses and errors are paired with the request
+/// they answer by addresses, message ID and request ID, and report the
+/// time between them.
+#[must_use]
+pub fn sequence_diagram(datagrams: &[RecordedDatagram]) -> String {
+    let start = datagrams.iter().find_map(|datagram| datagram.timestamp);
+    let mut pending: Vec<(Exchange, Option<Duration>)> = Vec::new();
+    let mut out = String::new();
+    for datagram in datagrams {
+        let time = match (datagram.timestamp, start) {
+            (Some(at), Some(start)) => {
+                std::format!("{:.3}s", at.saturating_sub(start).as_secs_f64())
+            }
+            _ => String::from("-"),
+        };
+        let prefix = std::format!(
+            "{time:>9}  {} -> {}  ",
+            datagram.source(),
+            datagram.destination()
+        );
+        let Ok(view) = MessageView::parse(&datagram.bytes) else {
+            let _ = writeln!(
+                out,
+                "{prefix}undecodable datagram ({} bytes)",
+                datagram.bytes.len()
+            );
+            continue;
+        };
+        if view.is_sd() {
+            write_sd(&mut out, &prefix, &view);
+            continue;
+        }
+        let header = view.header();
+        let message_id = header.message_id();
+        let exchange = |requester, responder| -> Exchange {
+            (
+                requester,
+                responder,
+                message_id.message_id(),
+                header.request_id(),
+            )
+        };
+        let message_type = header.message_type().message_type();
+        let label = match message_type {
+            MessageType::Request => "REQUEST",
+            MessageType::RequestNoReturn => "REQUEST_NO_RETURN",
+            MessageType::Notification => "NOTIFICATION",
+            MessageType::Response => "RESPONSE",
+            MessageType::Error => "ERROR",
+        };
+        let _ = write!(
+            out,
+            "{prefix}{label} 0x{:04X}.0x{:04X}",
+            message_id.service_id(),
+            message_id.method_id()
+        );
+        if message_type == MessageType::Error {
+            let _ = write!(out, " {:?}", header.return_code());
+        }
+        if message_type != MessageType::Notification {
+            let _ = write!(out, " session 0x{:04X}", header.request_id() & 0xFFFF);
+        }
+        let _ = write!(out, " ({} bytes)", view.payload_bytes().len());
+        match message_type {
+            MessageType::Request => {
+                let key = exchange(datagram.source(), datagram.destination());
+                pending.retain(|(pending, _)| *pending != key);
+                pending.push((key, datagram.timestamp));
+            }
+            MessageType::Response | MessageType::Error => {
+                let key = exchange(datagram.destination(), datagram.source());
+                if let Some(index) = pending.iter().position(|(pending, _)| *pending == key) {
+                    let (_, sent) = pending.swap_remove(index);
+                    if let (Some(sent), Some(at)) = (sent, datagram.timestamp) {
+                        let latency = at.saturating_sub(sent).as_secs_f64() * 1000.0;
+                        let _ = write!(out, " after {latency:.3} ms");
+                    }
+                }
+            }
+            MessageType::RequestNoReturn | MessageType::Notification => {}
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// One line per entry of the SD message in `view`.
+fn write_sd(out: &mut String, prefix: &str, view: &MessageView<'_>) {
+    let Ok(sd) = view.sd_header() else {
+        let _ = writeln!(out, "{prefix}SD (malformed)");
+        return;
+    };
+    for entry in sd.entries() {
+        let ttl = entry.ttl();
+        let name = match entry.entry_type() {
+            Ok(EntryType::FindService) => "FindService",
+            Ok(EntryType::OfferService) if ttl == 0 => "StopOfferService",
+            Ok(EntryType::OfferService) => "OfferService",
+            Ok(EntryType::StopOfferService) => "StopOfferService",
+            Ok(EntryType::Subscribe) if ttl == 0 => "StopSubscribe",
+            Ok(EntryType::Subscribe) => "Subscribe",
+            Ok(EntryType::SubscribeAck) if ttl == 0 => "SubscribeNack",
+            Ok(EntryType::SubscribeAck) => "SubscribeAck",
+            Err(_) => "(unknown entry)",
+        };
+        let _ = write!(
+            out,
+            "{prefix}SD {name} 0x{:04X}/0x{:04X}",
+            entry.service_id(),
+            entry.instance_id()
+        );
+        match entry.entry_type() {
+            Ok(EntryType::FindService | EntryType::OfferService) if ttl != 0 => {
+                let _ = write!(
+                    out,
+                    " v{}.{} ttl {ttl}",
+                    entry.major_version(),
+                    entry.minor_version()
+                );
+            }
+            Ok(EntryType::Subscribe | EntryType::SubscribeAck) => {
+                let _ = write!(out, " eventgroup 0x{:04X}", entry.event_group_id());
+                if ttl != 0 {
+                    let _ = write!(out, " ttl {ttl}");
+                }
+            }
+            _ => {}
+        }
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Header, MessageId, MessageTypeField, ReturnCode};
+    use crate::traits::WireFormat;
+    use core::net::Ipv4Addr;
+
+    const CLIENT: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 20), 40000);
+    const SERVER: SocketAddrV4 = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 10), 30500);
+
+    fn datagram(
+        message_type: MessageType,
+        session: u16,
+        payload: &[u8],
+        millis: u64,
+    ) -> RecordedDatagram {
+        let header = Header::new(
+            MessageId::new_from_service_and_method(0x1234, 0x0001),
+            u32::from(session),
+            1,
+            1,
+            MessageTypeField::new(message_type, false),
+            ReturnCode::Ok,
+            payload.len(),
+        );
+        let mut bytes = std::vec![0u8; 16];
+        header.encode_to_slice(&mut bytes).unwrap();
+        bytes.extend_from_slice(payload);
+        let (direction, local, peer) = if message_type == MessageType::Request {
+            (Direction::Outbound, CLIENT, SERVER)
+        } else {
+            (Direction::Inbound, CLIENT, SERVER)
+        };
+        RecordedDatagram {
+            direction,
+            local,
+            peer,
+            timestamp: Some(Duration::from_millis(1000 + millis)),
+            bytes,
+        }
+    }
+
+    #[test]
+    fn requests_are_paired_with_their_responses() {
+        let diagram = sequence_diagram(&[
+            datagram(MessageType::Request, 5, b"ping", 0),
+            datagram(MessageType::Request, 6, b"ping", 1),
+            datagram(MessageType::Response, 6, b"ok", 3),
+            datagram(MessageType::Notification, 0, b"", 4),
+        ]);
+        let lines: Vec<&str> = diagram.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "   0.000s  192.168.1.20:40000 -> 192.168.1.10:30500  REQUEST 0x1234.0x0001 session 0x0005 (4 bytes)",
+                "   0.001s  192.168.1.20:40000 -> 192.168.1.10:30500  REQUEST 0x1234.0x0001 session 0x0006 (4 bytes)",
+                "   0.003s  192.168.1.10:30500 -> 192.168.1.20:40000  RESPONSE 0x1234.0x0001 session 0x0006 (2 bytes) after 2.000 ms",
+                "   0.004s  192.168.1.10:30500 -> 192.168.1.20:40000  NOTIFICATION 0x1234.0x0001 (0 bytes)",
+            ]
+        );
+    }
+
+    #[test]
+    fn sd_entries_get_a_line_each() {
+        use crate::protocol::sd::{self, Entry, EventGroupEntry, OptionsCount, ServiceEntry};
+
+        let offer = ServiceEntry {
+            index_first_options_run: 0,
+            index_second_options_run: 0,
+            options_count: OptionsCount::NONE,
+            service_id: 0x1234,
+            instance_id: 1,
+            major_version: 1,
+            ttl: 3,
+            minor_version: 0,
+        };
+        let nack = EventGroupEntry {
+            options_count: OptionsCount::NONE,
+            ..EventGroupEntry::new(0x1234, 1, 1, 0, 0x0010)
+        };
+        let entries = [
+            Entry::OfferService(offer),
+            Entry::SubscribeAckEventGroup(nack),
+        ];
+        let sd = sd::Header::new(
+            sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            &entries,
+            &[],
+        );
+        let mut bytes = std::vec![0u8; 16 + sd.required_size()];
+        Header::new_sd(1, sd.required_size())
+            .encode_to_slice(&mut bytes)
+            .unwrap();
+        sd.encode_to_slice(&mut bytes[16..]).unwrap();
+        let diagram = sequence_diagram(&[RecordedDatagram {
+            direction: Direction::Outbound,
+            local: SERVER,
+            peer: CLIENT,
+            timestamp: None,
+            bytes,
+        }]);
+        assert_eq!(
+            diagram,
+            "        -  192.168.1.10:30500 -> 192.168.1.20:40000  SD OfferService 0x1234/0x0001 v1.0 ttl 3\n        \
+             -  192.168.1.10:30500 -> 192.168.1.20:40000  SD SubscribeNack 0x1234/0x0001 eventgroup 0x0010\n"
+        );
+    }
+
+    #[test]
+    fn recorder_keeps_tapped_datagrams() {
+        let recorder = SessionRecorder::new();
+        let bytes = datagram(MessageType::Request, 1, b"", 0).bytes;
+        recorder.clone().on_datagram(&TappedDatagram {
+            direction: Direction::Outbound,
+            local: CLIENT,
+            peer: SERVER,
+            timestamp: None,
+            bytes: &bytes,
+        });
+        recorder.on_datagram(&TappedDatagram {
+            direction: Direction::Inbound,
+            local: CLIENT,
+            peer: SERVER,
+            timestamp: None,
+            bytes: &[0xFF],
+        });
+        assert_eq!(recorder.datagrams().len(), 2);
+        assert_eq!(recorder.datagrams()[0].source(), CLIENT);
+        assert_eq!(
+            recorder.sequence_diagram(),
+            "        -  192.168.1.20:40000 -> 192.168.1.10:30500  REQUEST 0x1234.0x0001 session 0x0001 (0 bytes)\n        \
+             -  192.168.1.10:30500 -> 192.168.1.20:40000  undecodable datagram (1 bytes)\n"
+        );
+        recorder.clear();
+        assert!(recorder.datagrams().is_empty());
+    }
+}
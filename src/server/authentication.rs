@@ -0,0 +1,222 @@
+//! Challenge/response authentication of peers, installed with
+//! [`Server::set_authenticator`](super::Server::set_authenticator).
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::net::{Ipv4Addr, SocketAddrV4};
+
+use crate::protocol::{MessageType, MessageView, ReturnCode};
+use crate::transport::TransportSocket;
+
+use super::request_handler::{MethodError, send_reply};
+use super::sd_state::SdStateManager;
+
+/// What an [`Authenticator`] makes of one handshake message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Handshake {
+    /// Send this payload back, typically a challenge, and wait for the
+    /// peer's next message. The peer is not authenticated yet.
+    Continue(Vec<u8>),
+    /// Authenticate the peer and send this payload back.
+    Accept(Vec<u8>),
+    /// Refuse the peer, answered with an `ERROR` carrying
+    /// [`ReturnCode::NotOk`]. An earlier authentication is revoked.
+    Reject,
+}
+
+/// Runs the server's side of an application-defined handshake.
+///
+/// Called with the payload of every request a peer sends to the
+/// handshake method of a [`PeerAuthenticator`], and with the peer's
+/// address, so an implementation keeping per-peer progress (such as the
+/// challenge it issued) keys it by that. Runs on the receive loop, so it
+/// should not block. Implemented for every
+/// `Fn(Ipv4Addr, &[u8]) -> Handshake` closure.
+pub trait Authenticator: Send + Sync + 'static {
+    fn handshake(&self, peer: Ipv4Addr, message: &[u8]) -> Handshake;
+}
+
+impl<F> Authenticator for F
+where
+    F: Fn(Ipv4Addr, &[u8]) -> Handshake + Send + Sync + 'static,
+{
+    fn handshake(&self, peer: Ipv4Addr, message: &[u8]) -> Handshake {
+        self(peer, message)
+    }
+}
+
+/// An [`Authenticator`] and the method ID its handshake messages are
+/// sent to.
+///
+/// While one is installed, the server only accepts subscriptions and
+/// requests from peers that completed the handshake: a `Subscribe` from
+/// any other peer is answered with a `SubscribeNack`, a request with an
+/// `ERROR` carrying [`ReturnCode::NotReachable`]. Requests to the
+/// handshake method of any service the receive loop answers go to the
+/// authenticator instead of the request router. Peers are told apart by
+/// IP address, as a client's SD and unicast traffic come from different
+/// ports; a peer that reboots must authenticate again.
+///
+/// ```
+/// use simple_someip::server::{Handshake, PeerAuthenticator};
+///
+/// let authenticator = PeerAuthenticator::new(0x7F00, |_, message: &[u8]| {
+///     if message == b"secret" {
+///         Handshake::Accept(Vec::new())
+///     } else {
+///         Handshake::Reject
+///     }
+/// });
+/// assert_eq!(authenticator.method_id(), 0x7F00);
+/// ```
+pub struct PeerAuthenticator {
+    method_id: u16,
+    authenticator: Box<dyn Authenticator>,
+}
+
+impl PeerAuthenticator {
+    #[must_use]
+    pub fn new(method_id: u16, authenticator: impl Authenticator) -> Self {
+        Self {
+            method_id,
+            authenticator: Box::new(authenticator),
+        }
+    }
+
+    /// Method ID the handshake messages are sent to.
+    #[must_use]
+    pub fn method_id(&self) -> u16 {
+        self.method_id
+    }
+}
+
+impl core::fmt::Debug for PeerAuthenticator {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PeerAuthenticator")
+            .field("method_id", &self.method_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The installed authenticator and the peers that completed its
+/// handshake.
+#[derive(Debug)]
+pub(super) struct Authentication {
+    authenticator: Option<Arc<PeerAuthenticator>>,
+    peers: BTreeSet<Ipv4Addr>,
+}
+
+impl Authentication {
+    pub(super) const fn new() -> Self {
+        Self {
+            authenticator: None,
+            peers: BTreeSet::new(),
+        }
+    }
+
+    /// Install (`Some`) or remove (`None`) the authenticator. Peers
+    /// authenticated by an earlier one must authenticate again.
+    pub(super) fn set_authenticator(&mut self, authenticator: Option<Arc<PeerAuthenticator>>) {
+        self.authenticator = authenticator;
+        self.peers.clear();
+    }
+
+    pub(super) fn authenticator(&self) -> Option<Arc<PeerAuthenticator>> {
+        self.authenticator.clone()
+    }
+
+    /// `true` if no authenticator is installed or `peer` completed its
+    /// handshake.
+    pub(super) fn admits(&self, peer: Ipv4Addr) -> bool {
+        self.authenticator.is_none() || self.peers.contains(&peer)
+    }
+
+    pub(super) fn set_authenticated(&mut self, peer: Ipv4Addr, authenticated: bool) {
+        if authenticated {
+            self.peers.insert(peer);
+        } else {
+            self.peers.remove(&peer);
+        }
+    }
+}
+
+/// Apply the installed authenticator, if any, to the request in `view`:
+/// run a handshake message through it, or refuse a request from a peer
+/// that has not authenticated. Returns `true` if the request was handled
+/// here and must not be dispatched further.
+pub(super) async fn screen_request<T: TransportSocket>(
+    socket: &T,
+    sd_state: &SdStateManager,
+    view: &MessageView<'_>,
+    source: SocketAddrV4,
+    send_buf: &mut [u8],
+) -> bool {
+    let Some(authenticator) = sd_state.authenticator() else {
+        return false;
+    };
+    let header = view.header();
+    let peer = *source.ip();
+    let reply = if header.message_id().method_id() == authenticator.method_id {
+        match authenticator
+            .authenticator
+            .handshake(peer, view.payload_bytes())
+        {
+            Handshake::Continue(payload) => Ok(payload),
+            Handshake::Accept(payload) => {
+                crate::log::info!("Peer {} authenticated", peer);
+                sd_state.set_authenticated(peer, true);
+                Ok(payload)
+            }
+            Handshake::Reject => {
+                crate::log::warn!("Peer {} failed authentication", peer);
+                sd_state.set_authenticated(peer, false);
+                Err(MethodError::from(ReturnCode::NotOk))
+            }
+        }
+    } else if sd_state.admits(core::net::IpAddr::V4(peer)) {
+        return false;
+    } else {
+        crate::log::debug!(
+            "Refusing {:?} from unauthenticated peer {}",
+            header.message_id(),
+            source
+        );
+        Err(MethodError::from(ReturnCode::NotReachable))
+    };
+    if header.message_type().message_type() == MessageType::Request {
+        send_reply(socket, sd_state, view, &reply, source, send_buf).await;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_authenticated_peers_are_admitted() {
+        let peer = Ipv4Addr::new(192, 168, 1, 10);
+        let mut authentication = Authentication::new();
+        assert!(authentication.admits(peer), "no authenticator, no gate");
+
+        let authenticator = |_: Ipv4Addr, _: &[u8]| Handshake::Reject;
+        authentication.set_authenticator(Some(Arc::new(PeerAuthenticator::new(1, authenticator))));
+        assert!(!authentication.admits(peer));
+        authentication.set_authenticated(peer, true);
+        assert!(authentication.admits(peer));
+        assert!(!authentication.admits(Ipv4Addr::new(192, 168, 1, 11)));
+        authentication.set_authenticated(peer, false);
+        assert!(!authentication.admits(peer));
+
+        authentication.set_authenticated(peer, true);
+        authentication.set_authenticator(Some(Arc::new(PeerAuthenticator::new(1, authenticator))));
+        assert!(
+            !authentication.admits(peer),
+            "a new authenticator starts over"
+        );
+        authentication.set_authenticator(None);
+        assert!(authentication.admits(peer));
+    }
+}
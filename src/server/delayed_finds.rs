@@ -0,0 +1,120 @@
+//! `FindService`s waiting out the request-response delay of
+//! [`ServerConfig::with_find_response_delay`](super::ServerConfig::with_find_response_delay)
+//! before their unicast `OfferService` is sent.
+
+use core::time::Duration;
+use heapless::Vec;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_DELAYED_FINDS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_DELAYED_FINDS_CAP: usize = 8;
+
+/// Maximum number of `FindService` answers one server holds back for the
+/// request-response delay at a time; further ones are answered right
+/// away. Sized via `SIMPLE_SOMEIP_DELAYED_FINDS_CAP`; defaults to 2 on
+/// bare-metal, 8 otherwise.
+pub const DELAYED_FINDS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_DELAYED_FINDS_CAP"),
+    _DEFAULT_DELAYED_FINDS_CAP,
+);
+
+/// A `FindService` to answer with a unicast offer once `due`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct DelayedFind {
+    /// Where the `FindService` came from, and the offer goes.
+    pub(super) target: core::net::SocketAddr,
+    /// Service ID searched for; `0xFFFF` for any.
+    pub(super) service_id: u16,
+    /// Major version searched for; `0xFF` for any.
+    pub(super) major_version: u8,
+    /// [`Timer::now`](crate::Timer::now) at which the offer is due.
+    pub(super) due: Duration,
+}
+
+impl DelayedFind {
+    /// `true` if the `FindService` looks for `service_id` at
+    /// `major_version`.
+    pub(super) fn finds(&self, service_id: u16, major_version: u8) -> bool {
+        (self.service_id == service_id || self.service_id == 0xFFFF)
+            && crate::protocol::sd::major_version_matches(major_version, self.major_version)
+    }
+}
+
+/// The held-back `FindService` answers, in arrival order.
+#[derive(Debug)]
+pub(super) struct DelayedFinds {
+    finds: Vec<DelayedFind, DELAYED_FINDS_CAP>,
+}
+
+impl DelayedFinds {
+    pub(super) const fn new() -> Self {
+        Self { finds: Vec::new() }
+    }
+
+    /// Hold back `find`. Returns `false` if the queue is full.
+    pub(super) fn push(&mut self, find: DelayedFind) -> bool {
+        self.finds.push(find).is_ok()
+    }
+
+    /// The earliest deadline held.
+    pub(super) fn next_due(&self) -> Option<Duration> {
+        self.finds.iter().map(|find| find.due).min()
+    }
+
+    /// Remove and return one `FindService` due at `now`.
+    pub(super) fn take_due(&mut self, now: Duration) -> Option<DelayedFind> {
+        let index = self.finds.iter().position(|find| find.due <= now)?;
+        Some(self.finds.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(service_id: u16, due_ms: u64) -> DelayedFind {
+        DelayedFind {
+            target: "127.0.0.1:40000".parse().unwrap(),
+            service_id,
+            major_version: 0xFF,
+            due: Duration::from_millis(due_ms),
+        }
+    }
+
+    #[test]
+    fn finds_come_due_in_deadline_order_and_are_capped() {
+        let mut finds = DelayedFinds::new();
+        assert_eq!(finds.next_due(), None);
+        assert!(finds.push(find(1, 50)));
+        assert!(finds.push(find(2, 20)));
+        assert_eq!(finds.next_due(), Some(Duration::from_millis(20)));
+        assert_eq!(finds.take_due(Duration::from_millis(10)), None);
+        assert_eq!(
+            finds
+                .take_due(Duration::from_millis(30))
+                .map(|f| f.service_id),
+            Some(2)
+        );
+        assert_eq!(finds.next_due(), Some(Duration::from_millis(50)));
+        assert_eq!(
+            finds
+                .take_due(Duration::from_millis(50))
+                .map(|f| f.service_id),
+            Some(1)
+        );
+        assert_eq!(finds.take_due(Duration::MAX), None);
+
+        for _ in 0..DELAYED_FINDS_CAP {
+            assert!(finds.push(find(1, 0)));
+        }
+        assert!(!finds.push(find(1, 0)));
+    }
+
+    #[test]
+    fn a_find_matches_its_service_or_any() {
+        assert!(find(0x5B, 0).finds(0x5B, 1));
+        assert!(!find(0x5B, 0).finds(0x5C, 1));
+        assert!(find(0xFFFF, 0).finds(0x5C, 1));
+    }
+}
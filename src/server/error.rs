@@ -30,7 +30,9 @@ pub enum Error {
     /// buffer smaller than the outgoing message). The argument is a
     /// lowercase `snake_case` tag naming the resource; grep the crate for
     /// the tag to find the compile-time constant that governs it. Current
-    /// tags: `"udp_buffer"` (→ `crate::UDP_BUFFER_SIZE`).
+    /// tags: `"udp_buffer"` (→ `crate::UDP_BUFFER_SIZE`),
+    /// `"tcp_recv_buffer"` (→ `crate::server::TCP_CONNECTIONS_CAP`),
+    /// `"hosted_services"` (→ `ServerConfig::ACCEPTED_OFFERS_CAP`).
     #[error("internal capacity exceeded: {0}")]
     Capacity(&'static str),
     /// A `Server` API was called in a way that violates its
@@ -44,8 +46,89 @@ pub enum Error {
     /// - `"announcement_loop_already_started"` — `announcement_loop`
     ///   was called twice on the same server. Two announcement
     ///   futures cannot share the same SD socket and session counter.
+    /// - `"duplicate_service_instance"` — `add_service` was given a
+    ///   service instance the server already offers.
     #[error("invalid server usage: {0}")]
     InvalidUsage(&'static str),
+    /// The [`ServerConfig`](super::ServerConfig) handed to a constructor
+    /// failed [`ServerConfig::validate`](super::ServerConfig::validate).
+    #[error(transparent)]
+    InvalidConfig(#[from] ConfigError),
+    /// The SD socket could not take the SD port at `addr`, typically
+    /// because another SD stack on the host holds it. `mode` is the
+    /// [`SdPortMode`](super::SdPortMode) that was tried; the message
+    /// names the modes that work around the conflict.
+    #[error(
+        "cannot bind SD port {addr} with {mode:?} ({cause}): share the port with \
+         SdPortMode::Shared if its owner sets SO_REUSEPORT, or leave service \
+         discovery to it with SdPortMode::External"
+    )]
+    SdPortUnavailable {
+        addr: core::net::SocketAddrV4,
+        mode: super::SdPortMode,
+        cause: crate::transport::TransportError,
+    },
+    /// A send's [`Deadline`](crate::Deadline) passed before it was
+    /// delivered to every recipient; the rest were dropped rather than
+    /// sent late. `sent` counts the recipients reached in time.
+    #[error("send deadline exceeded after {sent} recipients")]
+    DeadlineExceeded {
+        /// Recipients the message reached before the deadline.
+        sent: usize,
+    },
+    /// The validator set with
+    /// [`EventPublisher::set_event_validator`](super::EventPublisher::set_event_validator)
+    /// rejected the event's payload; nothing was sent.
+    #[error("event 0x{service_id:04X}.0x{event_id:04X} payload rejected: {reason}")]
+    PayloadRejected {
+        service_id: u16,
+        event_id: u16,
+        /// What the validator reported.
+        reason: &'static str,
+    },
+    /// The event group had no subscribers and the event's
+    /// [`NoSubscriberPolicy`](super::NoSubscriberPolicy) is `Fail`;
+    /// nothing was sent.
+    #[error(
+        "no subscribers for event 0x{service_id:04X}.0x{event_id:04X} \
+         (event group 0x{event_group_id:04X})"
+    )]
+    NoSubscribers {
+        service_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    },
+}
+
+/// Why [`ServerConfig::validate`](super::ServerConfig::validate) rejected a
+/// configuration.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The interface address is a multicast address. Sockets are bound
+    /// and offers advertised on the interface's unicast address; the SD
+    /// multicast group is joined separately.
+    #[error("interface {0} is a multicast address, not a local unicast address")]
+    MulticastInterface(core::net::Ipv4Addr),
+    /// The server announces an endpoint on port `0`, which no peer can
+    /// send to.
+    #[error(
+        "cannot announce {protocol:?} port 0: set the port explicitly, \
+         or disable announcements"
+    )]
+    AnnouncedPortZero {
+        protocol: crate::protocol::sd::TransportProtocol,
+    },
+    /// The SD TTL is `0`, which peers read as `StopOfferService`.
+    #[error("SD TTL of 0 would stop the offer it is sent with")]
+    ZeroTtl,
+    /// A service ID reserved by SOME/IP: `0x0000`, `0xFFFE` (non-SOME/IP
+    /// services) or `0xFFFF` (service discovery and wildcard finds).
+    #[error("service ID 0x{0:04X} is reserved")]
+    ReservedServiceId(u16),
+    /// An instance ID reserved by SOME/IP: `0x0000` or `0xFFFF` (any
+    /// instance).
+    #[error("instance ID 0x{instance_id:04X} of service 0x{service_id:04X} is reserved")]
+    ReservedInstanceId { service_id: u16, instance_id: u16 },
 }
 
 impl From<crate::protocol::sd::Error> for Error {
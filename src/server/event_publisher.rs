@@ -1,17 +1,59 @@
 //! Event publishing functionality
 
 use super::Error;
+use super::initial_events::{InitialEvents, SetError};
+use super::multicast_groups::MulticastGroups;
+use super::no_subscriber::{NoSubscriberEvents, NoSubscriberPolicy, keeps_datagrams};
+use super::ordered_groups::{OrderedGroups, Turn};
+use super::sd_state::LockCell;
+use super::standby_subscribers::StandbySubscribers;
+use super::sticky_events::{StickyCache, StickyKey};
 use super::subscription_manager::{SUBSCRIBERS_PER_GROUP, SubscriptionHandle};
 use crate::e2e::E2EKey;
+use crate::event_validation::{EventValidator, EventValidators};
+use crate::protocol::tp::{self, Segmenter};
 use crate::protocol::{Header, Message};
 use crate::traits::{PayloadWireFormat, WireFormat};
-use crate::transport::{E2ERegistryHandle, SharedHandle, TransportSocket};
-#[cfg(test)]
+use crate::transport::{Deadline, E2ERegistryHandle, SharedHandle, Timer, TransportSocket};
+#[cfg(any(test, feature = "_alloc"))]
 use alloc::sync::Arc;
 use core::marker::PhantomData;
 use core::net::SocketAddrV4;
 use heapless::Vec as HeaplessVec;
 
+/// Scratch buffers one [`EventPublisher`] keeps for the convenience
+/// publish methods that take no buffers, e.g.
+/// [`EventPublisher::publish_event`], and for the server's run loop,
+/// each [`UDP_BUFFER_SIZE`](crate::UDP_BUFFER_SIZE) bytes. A running
+/// server holds two for its send paths and a publish takes one or two;
+/// those beyond the pool allocate. Sized via
+/// `SIMPLE_SOMEIP_SCRATCH_BUFFERS_CAP`; defaults to 6.
+#[cfg(feature = "_alloc")]
+pub const SCRATCH_BUFFERS_CAP: usize =
+    crate::from_env_or(option_env!("SIMPLE_SOMEIP_SCRATCH_BUFFERS_CAP"), 6);
+
+#[cfg(feature = "_alloc")]
+type ScratchPool = crate::buffer_pool::BufferPool<SCRATCH_BUFFERS_CAP, { crate::UDP_BUFFER_SIZE }>;
+
+/// Receive buffers one [`EventPublisher`] keeps for the server's
+/// receive loops, each [`RECV_BUFFER_LEN`] bytes: the unicast and SD
+/// buffers of `Server::run`, and the per-connection receive buffers
+/// and the send buffer of `Server::serve_tcp`. Those beyond the pool
+/// allocate. Sized via `SIMPLE_SOMEIP_RECV_BUFFERS_CAP`; defaults to
+/// three more than [`TCP_CONNECTIONS_CAP`](super::TCP_CONNECTIONS_CAP).
+#[cfg(feature = "_alloc")]
+pub const RECV_BUFFERS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_RECV_BUFFERS_CAP"),
+    super::TCP_CONNECTIONS_CAP + 3,
+);
+
+/// Length of a [`RECV_BUFFERS_CAP`] buffer: the largest UDP datagram.
+#[cfg(feature = "_alloc")]
+pub const RECV_BUFFER_LEN: usize = 65535;
+
+#[cfg(feature = "_alloc")]
+type RecvPool = crate::buffer_pool::BufferPool<RECV_BUFFERS_CAP, RECV_BUFFER_LEN>;
+
 /// The publish snapshot buffer is sized to `SUBSCRIBERS_PER_GROUP` so
 /// `for_each_subscriber` can never overflow it. If a future refactor
 /// changes the manager's per-group cap independently, this assert
@@ -52,6 +94,36 @@ where
     subscriptions: S,
     socket: H,
     e2e_registry: R,
+    /// Last value of every event marked with
+    /// [`Self::set_sticky_event`].
+    sticky: LockCell<StickyCache>,
+    /// Events sent to each new subscriber of their event group, set with
+    /// [`Self::set_initial_event`].
+    initial: LockCell<InitialEvents>,
+    /// Event groups sent to a multicast address, set with
+    /// [`Self::set_multicast_group`].
+    multicast: LockCell<MulticastGroups>,
+    /// Standby subscribers sent a copy of each event, set with
+    /// [`Self::set_standby_subscriber`].
+    standby: LockCell<StandbySubscribers>,
+    /// Payload checks run before publishing, set with
+    /// [`Self::set_event_validator`].
+    validators: LockCell<EventValidators<[u8]>>,
+    /// Event groups whose publishes go out one at a time, in order, set
+    /// with [`Self::set_ordered_event_group`].
+    ordered: LockCell<OrderedGroups>,
+    /// What publishing each event does without subscribers, set with
+    /// [`Self::set_no_subscriber_policy`], and what it buffered.
+    no_subscriber: LockCell<NoSubscriberEvents>,
+    /// Buffers reused by the publish methods that take none, instead of
+    /// allocating two per call.
+    #[cfg(feature = "_alloc")]
+    scratch: Arc<ScratchPool>,
+    /// Buffers of the server's receive loops, created by the first
+    /// [`Self::recv_buffer`] so a server that never runs them does not
+    /// hold the pool's memory.
+    #[cfg(feature = "_alloc")]
+    recv: LockCell<Option<Arc<RecvPool>>>,
     /// `T` appears only in the bound `H: SharedHandle<T>`; the
     /// struct doesn't directly hold a `T`. `PhantomData<fn() -> T>`
     /// (rather than `PhantomData<T>`) carries the type without
@@ -79,10 +151,710 @@ where
             subscriptions,
             socket,
             e2e_registry,
+            sticky: LockCell::new(StickyCache::new()),
+            initial: LockCell::new(InitialEvents::new()),
+            multicast: LockCell::new(MulticastGroups::new()),
+            standby: LockCell::new(StandbySubscribers::new()),
+            validators: LockCell::new(EventValidators::new()),
+            ordered: LockCell::new(OrderedGroups::new()),
+            no_subscriber: LockCell::new(NoSubscriberEvents::new()),
+            #[cfg(feature = "_alloc")]
+            scratch: Arc::new(ScratchPool::new()),
+            #[cfg(feature = "_alloc")]
+            recv: LockCell::new(None),
             _phantom: PhantomData,
         }
     }
 
+    /// A zeroed [`UDP_BUFFER_SIZE`](crate::UDP_BUFFER_SIZE)-byte buffer
+    /// from the publisher's pool, or allocated if the pool is exhausted.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn scratch_buffer(&self) -> crate::buffer_pool::ScratchBuffer {
+        self.scratch.claim_or_alloc()
+    }
+
+    /// A zeroed [`RECV_BUFFER_LEN`]-byte buffer from the publisher's
+    /// receive pool, or allocated if the pool is exhausted.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn recv_buffer(&self) -> crate::buffer_pool::ScratchBuffer {
+        self.recv
+            .with(|pool| pool.get_or_insert_with(RecvPool::new_arc).clone())
+            .map_or_else(
+                || crate::buffer_pool::ScratchBuffer::Allocated(alloc::vec![0u8; RECV_BUFFER_LEN]),
+                |pool| pool.claim_or_alloc(),
+            )
+    }
+
+    /// Mark an event sticky: the publish paths keep the last datagram
+    /// sent for it (even with nobody subscribed yet), and a new
+    /// subscriber of its event group is sent that datagram right after
+    /// its `SubscribeAck`, so a late joiner gets the current state
+    /// without waiting for the next cycle. Fields usually want this, but
+    /// any event can be sticky.
+    ///
+    /// A sticky event has no value until it is published; to send a
+    /// value set up front, register it with [`Self::set_initial_event`]
+    /// instead.
+    ///
+    /// The server's own `run()` loop delivers the cached values. External
+    /// SD dispatchers call [`Self::deliver_sticky_events_with_buffers`]
+    /// after [`Self::register_subscriber`]. Notifications longer than
+    /// [`STICKY_EVENT_LEN`](super::STICKY_EVENT_LEN) are not cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("sticky_events")` if
+    /// [`STICKY_EVENTS_CAP`](super::STICKY_EVENTS_CAP) events are already
+    /// sticky, or always on builds with neither `std` nor `bare_metal`,
+    /// which keep no cache.
+    pub fn set_sticky_event(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    ) -> Result<(), Error> {
+        let key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        if self.sticky.with(|cache| cache.mark(key)) == Some(true) {
+            Ok(())
+        } else {
+            Err(Error::Capacity("sticky_events"))
+        }
+    }
+
+    /// Stop caching an event marked with [`Self::set_sticky_event`] and
+    /// drop its cached value.
+    pub fn clear_sticky_event(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    ) {
+        let key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        self.sticky.with(|cache| cache.unmark(key));
+    }
+
+    /// Register the initial value of an event: a notification carrying
+    /// `payload` that every new subscriber of the event group is sent
+    /// right after its `SubscribeAck`, as SOME/IP asks of fields.
+    /// Registering the event again replaces its value. Unlike a sticky
+    /// event (see [`Self::set_sticky_event`]), the value is only what is
+    /// registered here; publishing the event does not change it.
+    ///
+    /// `payload` is sent as given, so an E2E-protected event needs its
+    /// protection applied already, as with [`Self::publish_raw_event`].
+    /// The server's own `run()` loop delivers the registered events.
+    /// External SD dispatchers call
+    /// [`Self::deliver_initial_events_with_buffers`] after
+    /// [`Self::register_subscriber`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("initial_event_len")` if the
+    /// notification would be longer than
+    /// [`INITIAL_EVENT_LEN`](super::INITIAL_EVENT_LEN), and
+    /// [`Error::Capacity`]`("initial_events")` if
+    /// [`INITIAL_EVENTS_CAP`](super::INITIAL_EVENTS_CAP) other events are
+    /// already registered, or always on builds with neither `std` nor
+    /// `bare_metal`, which keep no registry.
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_initial_event(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+        request_id: u32,
+        protocol_version: u8,
+        interface_version: u8,
+        payload: &[u8],
+    ) -> Result<(), Error> {
+        // Checked before `Header::new_event`, which asserts on lengths
+        // past `u32::MAX - 8`.
+        if payload.len() > super::INITIAL_EVENT_LEN.saturating_sub(16) {
+            return Err(Error::Capacity("initial_event_len"));
+        }
+        let header = Header::new_event(
+            service_id,
+            event_id,
+            request_id,
+            protocol_version,
+            interface_version,
+            payload.len(),
+        );
+        let key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        match self
+            .initial
+            .with(|events| events.set(key, &header, payload))
+        {
+            Some(Ok(())) => Ok(()),
+            Some(Err(SetError::TooLong)) => Err(Error::Capacity("initial_event_len")),
+            Some(Err(SetError::Full)) | None => Err(Error::Capacity("initial_events")),
+        }
+    }
+
+    /// Forget the initial value registered with
+    /// [`Self::set_initial_event`]. Returns whether one was registered.
+    pub fn clear_initial_event(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    ) -> bool {
+        let key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        self.initial.with(|events| events.remove(key)) == Some(true)
+    }
+
+    fn sticky_key_if_marked(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    ) -> Option<StickyKey> {
+        let key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        self.sticky
+            .with(|cache| cache.is_sticky(key))
+            .unwrap_or(false)
+            .then_some(key)
+    }
+
+    fn cache_sticky(&self, key: StickyKey, datagram: &[u8]) {
+        if self.sticky.with(|cache| cache.store(key, datagram)) == Some(false) {
+            crate::log::warn!(
+                "Sticky event 0x{:04X}.0x{:04X} ({} bytes) exceeds STICKY_EVENT_LEN; not cached",
+                key.service,
+                key.event,
+                datagram.len()
+            );
+        }
+    }
+
+    /// Send the events of an event group to the multicast address
+    /// `group` (`Some`) instead of to each subscriber, or go back to
+    /// unicast (`None`). While the group has subscribers, every publish
+    /// sends one datagram to `group` and reports all of them served.
+    /// Subscribers still register as usual; they must have joined
+    /// `group`, which the server tells them with an `IPv4 Multicast`
+    /// option in its `SubscribeAck` (see
+    /// [`ServerConfig::with_event_group_multicast`](super::ServerConfig::with_event_group_multicast)).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("multicast_groups")` if
+    /// [`MULTICAST_GROUPS_CAP`](super::MULTICAST_GROUPS_CAP) event groups
+    /// already have one, or always on builds with neither `std` nor
+    /// `bare_metal`, which keep no table.
+    pub fn set_multicast_group(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        group: Option<SocketAddrV4>,
+    ) -> Result<(), Error> {
+        let key = (service_id, instance_id, event_group_id);
+        if self.multicast.with(|groups| groups.set(key, group)) == Some(true) {
+            Ok(())
+        } else {
+            Err(Error::Capacity("multicast_groups"))
+        }
+    }
+
+    /// Send every event published to the subscriber `primary` to
+    /// `standby` as well (`Some`), or stop doing so (`None`), for
+    /// fail-operational consumers that run a hot standby next to the
+    /// primary. The standby needs no subscription of its own; it gets the
+    /// events of every event group `primary` is subscribed to, once even
+    /// if it subscribed too. A client receiving both copies tells them
+    /// apart by the `local_port` of `ClientUpdate::Unicast`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("standby_subscribers")` if
+    /// [`STANDBY_SUBSCRIBERS_CAP`](super::STANDBY_SUBSCRIBERS_CAP)
+    /// primaries already have one, or always on builds with neither
+    /// `std` nor `bare_metal`, which keep no table.
+    pub fn set_standby_subscriber(
+        &self,
+        primary: SocketAddrV4,
+        standby: Option<SocketAddrV4>,
+    ) -> Result<(), Error> {
+        if self.standby.with(|pairs| pairs.set(primary, standby)) == Some(true) {
+            Ok(())
+        } else {
+            Err(Error::Capacity("standby_subscribers"))
+        }
+    }
+
+    /// Choose what publishing an event does while its event group has no
+    /// subscribers: nothing ([`NoSubscriberPolicy::Ignore`], the
+    /// default), fail ([`NoSubscriberPolicy::Fail`]), or keep the last
+    /// few datagrams for the next subscriber
+    /// ([`NoSubscriberPolicy::Buffer`]). Buffered datagrams go out with
+    /// the sticky events (see [`Self::set_sticky_event`]), before them,
+    /// and only to the first subscriber that arrives.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("no_subscriber_policies")` if
+    /// [`NO_SUBSCRIBER_POLICIES_CAP`](super::NO_SUBSCRIBER_POLICIES_CAP)
+    /// events already have a policy, or `Buffer` asks for more than
+    /// [`BUFFERED_EVENTS_CAP`](super::BUFFERED_EVENTS_CAP) datagrams, or
+    /// always on builds with neither `std` nor `bare_metal`, which keep no
+    /// table.
+    pub fn set_no_subscriber_policy(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+        policy: NoSubscriberPolicy,
+    ) -> Result<(), Error> {
+        let key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        if self.no_subscriber.with(|events| events.set(key, policy)) == Some(true) {
+            Ok(())
+        } else {
+            Err(Error::Capacity("no_subscriber_policies"))
+        }
+    }
+
+    /// The policy of the event `key` if the subscriber snapshot is
+    /// empty, else `Ignore`.
+    fn no_subscriber_policy(
+        &self,
+        key: StickyKey,
+        subscribers: &[SocketAddrV4],
+    ) -> NoSubscriberPolicy {
+        if !subscribers.is_empty() {
+            return NoSubscriberPolicy::Ignore;
+        }
+        self.no_subscriber
+            .with(|events| events.policy(key))
+            .unwrap_or_default()
+    }
+
+    /// Finish a publish that found no subscribers under `policy`,
+    /// buffering `datagram` if the policy keeps it. `None` is an event
+    /// that was not encoded into one datagram.
+    fn publish_unsent(
+        &self,
+        key: StickyKey,
+        policy: NoSubscriberPolicy,
+        datagram: Option<&[u8]>,
+    ) -> Result<usize, Error> {
+        match (policy, datagram) {
+            (NoSubscriberPolicy::Fail, _) => Err(Error::NoSubscribers {
+                service_id: key.service,
+                event_group_id: key.event_group,
+                event_id: key.event,
+            }),
+            (NoSubscriberPolicy::Buffer(_), Some(datagram)) => {
+                if self
+                    .no_subscriber
+                    .with(|events| events.buffer(key, datagram))
+                    == Some(false)
+                {
+                    crate::log::warn!(
+                        "Event 0x{:04X}.0x{:04X} ({} bytes) not buffered for the next subscriber",
+                        key.service,
+                        key.event,
+                        datagram.len()
+                    );
+                }
+                Ok(0)
+            }
+            (NoSubscriberPolicy::Buffer(n), None) if n > 0 => {
+                crate::log::warn!(
+                    "Segmented event 0x{:04X}.0x{:04X} not buffered for the next subscriber",
+                    key.service,
+                    key.event
+                );
+                Ok(0)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    /// Publish the events of an event group in order (`true`), or let
+    /// its publishes run side by side (`false`, the default).
+    ///
+    /// A publish to an ordered group waits until every publish to the
+    /// group that started before it has finished sending, so each
+    /// subscriber receives the group's notifications in the order the
+    /// publish calls were first polled, even when they come from several
+    /// tasks and the socket makes some of them wait. Without it, two
+    /// concurrent publishes can interleave their per-subscriber sends and
+    /// reach subscribers in different orders. A cancelled publish gives
+    /// up its place. Turning ordering off lets the waiting publishes go
+    /// ahead at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("ordered_groups")` if
+    /// [`ORDERED_GROUPS_CAP`](super::ORDERED_GROUPS_CAP) event groups are
+    /// already ordered, or always on builds with neither `std` nor
+    /// `bare_metal`, which keep no table. A publish to an ordered group
+    /// fails with [`Error::Capacity`]`("ordered_publishes")` while
+    /// [`ORDERED_PUBLISHES_CAP`](super::ORDERED_PUBLISHES_CAP) publishes
+    /// to it are in flight.
+    pub fn set_ordered_event_group(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        ordered: bool,
+    ) -> Result<(), Error> {
+        let key = (service_id, instance_id, event_group_id);
+        let Some(woken) = self
+            .ordered
+            .with(|groups| groups.set(key, ordered))
+            .flatten()
+        else {
+            return Err(Error::Capacity("ordered_groups"));
+        };
+        for waker in woken {
+            waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Wait for a publish's turn on an ordered event group. The turn is
+    /// held until the returned value is dropped; `None` if the group is
+    /// not ordered.
+    async fn take_turn(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> Result<Option<Turn<'_>>, Error> {
+        let turn = Turn::enqueue(&self.ordered, (service_id, instance_id, event_group_id))?;
+        if let Some(turn) = &turn {
+            turn.wait().await;
+        }
+        Ok(turn)
+    }
+
+    /// Check the payload of every publish of an event with `validator`
+    /// (`Some`), or stop checking it (`None`), to catch a producer bug
+    /// (a value out of range, an unknown enum value) before it reaches
+    /// the subscribers. A rejected payload is not sent: the publish fails
+    /// with [`Error::PayloadRejected`], the rejection is counted (see
+    /// [`Self::rejected_events`]) and logged, less often as rejections of
+    /// the event pile up. Publishes with neither a recipient nor a value
+    /// to keep (sticky or buffered) are not checked.
+    ///
+    /// The validator sees the payload as encoded, before E2E protection.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`]`("event_validators")` if
+    /// [`EVENT_VALIDATORS_CAP`](crate::event_validation::EVENT_VALIDATORS_CAP)
+    /// events already have one, or always on builds with neither `std`
+    /// nor `bare_metal`, which keep no table.
+    pub fn set_event_validator(
+        &self,
+        service_id: u16,
+        event_id: u16,
+        validator: Option<EventValidator<[u8]>>,
+    ) -> Result<(), Error> {
+        if self
+            .validators
+            .with(|v| v.set(service_id, event_id, validator))
+            == Some(true)
+        {
+            Ok(())
+        } else {
+            Err(Error::Capacity("event_validators"))
+        }
+    }
+
+    /// Payloads of an event the validator set with
+    /// [`Self::set_event_validator`] has rejected so far.
+    #[must_use]
+    pub fn rejected_events(&self, service_id: u16, event_id: u16) -> u64 {
+        self.validators
+            .with(|v| v.rejected(service_id, event_id))
+            .unwrap_or(0)
+    }
+
+    fn validate_event(&self, service_id: u16, event_id: u16, payload: &[u8]) -> Result<(), Error> {
+        match self
+            .validators
+            .with(|v| v.validate(service_id, event_id, payload))
+        {
+            Some(Err(reason)) => Err(Error::PayloadRejected {
+                service_id,
+                event_id,
+                reason,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Add the standbys of the primaries in a subscriber snapshot.
+    fn add_standby_subscribers(
+        &self,
+        subscribers: &mut HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP>,
+    ) {
+        let left_out = self
+            .standby
+            .with(|pairs| pairs.extend(subscribers))
+            .unwrap_or(0);
+        if left_out > 0 {
+            crate::log::warn!(
+                "{} standby subscribers left out: snapshot holds at most {}",
+                left_out,
+                SUBSCRIBERS_PER_GROUP
+            );
+        }
+    }
+
+    /// Swap a non-empty subscriber snapshot for the event group's
+    /// multicast address, if it has one. Returns the number of
+    /// subscribers one send to it serves.
+    fn use_multicast_group(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        subscribers: &mut HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP>,
+    ) -> Option<usize> {
+        if subscribers.is_empty() {
+            return None;
+        }
+        let group = self
+            .multicast
+            .with(|groups| groups.get((service_id, instance_id, event_group_id)))
+            .flatten()?;
+        let served = subscribers.len();
+        subscribers.clear();
+        // Cannot fail: the snapshot was non-empty, so it has room for one.
+        let _ = subscribers.push(group);
+        Some(served)
+    }
+
+    /// Send `payload` with `header` to every address in `targets` as
+    /// SOME/IP-TP segments of at most `buf.len()` bytes, built in `buf`.
+    /// Returns the number of targets that got every segment.
+    async fn send_segmented(
+        &self,
+        header: &Header,
+        payload: &[u8],
+        targets: &[SocketAddrV4],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let segmenter = match Segmenter::new(header, payload, buf.len()) {
+            Ok(segmenter) => segmenter,
+            Err(tp::Error::SegmentTooSmall(len)) => {
+                crate::log::error!(
+                    "raw event buffer ({} bytes) too small for a SOME/IP-TP segment; dropping publish",
+                    len
+                );
+                return Err(Error::Capacity("udp_buffer"));
+            }
+            Err(e) => return Err(crate::protocol::Error::from(e).into()),
+        };
+        crate::log::debug!(
+            "Sending {}-byte event payload as {} SOME/IP-TP segments",
+            payload.len(),
+            segmenter.segment_count()
+        );
+        let mut sent_count = 0usize;
+        let mut last_err: Option<crate::transport::TransportError> = None;
+        'targets: for addr in targets {
+            let mut segments = segmenter.clone();
+            // `buf` holds any segment: `Segmenter::new` sized them to it.
+            while let Some(len) = segments
+                .write_next(buf)
+                .map_err(crate::protocol::Error::from)?
+            {
+                if let Err(e) = self.socket.get().send_to(&buf[..len], *addr).await {
+                    crate::log::error!("Failed to send event segment to {}: {:?}", addr, e);
+                    last_err = Some(e);
+                    continue 'targets;
+                }
+            }
+            sent_count += 1;
+        }
+        match last_err {
+            Some(e) if sent_count == 0 => Err(Error::Transport(e)),
+            _ => Ok(sent_count),
+        }
+    }
+
+    /// Send `target` the datagrams buffered for the event group under
+    /// [`NoSubscriberPolicy::Buffer`], oldest first, which are then
+    /// forgotten, and the cached value of every sticky event in the
+    /// group, using `buf` as scratch. Returns the number of datagrams
+    /// sent; sticky events with nothing published yet are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Transport`] if every send failed, with the last
+    /// transport error. A buffered or cached datagram longer than `buf`
+    /// is skipped with a warning.
+    pub async fn deliver_sticky_events_with_buffers(
+        &self,
+        target: SocketAddrV4,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut sent_count = 0usize;
+        let mut last_err: Option<crate::transport::TransportError> = None;
+        while let Some(Some(len)) = self
+            .no_subscriber
+            .with(|events| events.take_buffered(service_id, instance_id, event_group_id, buf))
+        {
+            if len == 0 {
+                crate::log::warn!(
+                    "Buffered event for service 0x{:04X} does not fit the {}-byte buffer; dropped",
+                    service_id,
+                    buf.len()
+                );
+                continue;
+            }
+            match self.socket.get().send_to(&buf[..len], target).await {
+                Ok(()) => sent_count += 1,
+                Err(e) => {
+                    crate::log::error!("Failed to send buffered event to {}: {:?}", target, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        let mut index = 0;
+        // Copy one value at a time out of the lock, which must not be
+        // held across the send.
+        while let Some(Some(len)) = self
+            .sticky
+            .with(|cache| cache.copy_cached(service_id, instance_id, event_group_id, index, buf))
+        {
+            index += 1;
+            if len == 0 {
+                crate::log::warn!(
+                    "Sticky event for service 0x{:04X} does not fit the {}-byte buffer; skipped",
+                    service_id,
+                    buf.len()
+                );
+                continue;
+            }
+            match self.socket.get().send_to(&buf[..len], target).await {
+                Ok(()) => sent_count += 1,
+                Err(e) => {
+                    crate::log::error!("Failed to send sticky event to {}: {:?}", target, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        match last_err {
+            Some(e) if sent_count == 0 => Err(Error::Transport(e)),
+            _ => Ok(sent_count),
+        }
+    }
+
+    /// Send `target` the initial value of every event of the event group
+    /// registered with [`Self::set_initial_event`], using `buf` as
+    /// scratch. Returns the number of datagrams sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Transport`] if every send failed, with the last
+    /// transport error. A registered datagram longer than `buf` is
+    /// skipped with a warning.
+    pub async fn deliver_initial_events_with_buffers(
+        &self,
+        target: SocketAddrV4,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        let mut sent_count = 0usize;
+        let mut last_err: Option<crate::transport::TransportError> = None;
+        let mut index = 0;
+        // Copy one value at a time out of the lock, which must not be
+        // held across the send.
+        while let Some(Some(len)) = self.initial.with(|events| {
+            events.copy_registered(service_id, instance_id, event_group_id, index, buf)
+        }) {
+            index += 1;
+            if len == 0 {
+                crate::log::warn!(
+                    "Initial event for service 0x{:04X} does not fit the {}-byte buffer; skipped",
+                    service_id,
+                    buf.len()
+                );
+                continue;
+            }
+            match self.socket.get().send_to(&buf[..len], target).await {
+                Ok(()) => sent_count += 1,
+                Err(e) => {
+                    crate::log::error!("Failed to send initial event to {}: {:?}", target, e);
+                    last_err = Some(e);
+                }
+            }
+        }
+        match last_err {
+            Some(e) if sent_count == 0 => Err(Error::Transport(e)),
+            _ => Ok(sent_count),
+        }
+    }
+
+    /// [`Self::deliver_initial_events_with_buffers`] with a
+    /// `crate::UDP_BUFFER_SIZE` scratch buffer from the publisher's pool
+    /// (see [`SCRATCH_BUFFERS_CAP`]).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::deliver_initial_events_with_buffers`].
+    #[cfg(feature = "_alloc")]
+    pub async fn deliver_initial_events(
+        &self,
+        target: SocketAddrV4,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> Result<usize, Error> {
+        let mut buf = self.scratch_buffer();
+        self.deliver_initial_events_with_buffers(
+            target,
+            service_id,
+            instance_id,
+            event_group_id,
+            &mut buf,
+        )
+        .await
+    }
+
+    /// [`Self::deliver_sticky_events_with_buffers`] with a
+    /// `crate::UDP_BUFFER_SIZE` scratch buffer from the publisher's pool
+    /// (see [`SCRATCH_BUFFERS_CAP`]).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::deliver_sticky_events_with_buffers`].
+    #[cfg(feature = "_alloc")]
+    pub async fn deliver_sticky_events(
+        &self,
+        target: SocketAddrV4,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> Result<usize, Error> {
+        let mut buf = self.scratch_buffer();
+        self.deliver_sticky_events_with_buffers(
+            target,
+            service_id,
+            instance_id,
+            event_group_id,
+            &mut buf,
+        )
+        .await
+    }
+
     /// Publish an event to all subscribers of an event group using caller-provided scratch.
     ///
     /// The `msg_buf` and `protected_buf` slices are the two scratch areas
@@ -110,13 +882,15 @@ where
     ///
     /// Returns an error if the message fails to serialize, or
     /// [`Error::Capacity`]`("udp_buffer")` if either scratch buffer is too small
-    /// for the encoded or E2E-protected frame.
+    /// for the encoded or E2E-protected frame, or [`Error::PayloadRejected`]
+    /// if the event's validator rejects the payload, or
+    /// [`Error::NoSubscribers`] if the event group has no subscribers and
+    /// the event's policy is [`NoSubscriberPolicy::Fail`].
     ///
     /// # Panics
     ///
     /// May panic if the underlying [`E2ERegistryHandle`](crate::transport::E2ERegistryHandle)
     /// implementation panics (e.g., `Arc<Mutex<E2ERegistry>>` on mutex poison).
-    #[allow(clippy::too_many_lines)]
     pub async fn publish_event_with_buffers<P: PayloadWireFormat>(
         &self,
         service_id: u16,
@@ -126,32 +900,113 @@ where
         msg_buf: &mut [u8],
         protected_buf: &mut [u8],
     ) -> Result<usize, Error> {
+        self.publish_event_impl(
+            service_id,
+            instance_id,
+            event_group_id,
+            message,
+            msg_buf,
+            protected_buf,
+            || false,
+        )
+        .await
+    }
+
+    /// [`Self::publish_event_with_buffers`] with a send deadline on
+    /// `timer`'s clock.
+    ///
+    /// The deadline is checked before the event is encoded and again
+    /// before each subscriber's send. Once it has passed, the remaining
+    /// subscribers are skipped rather than sent stale data.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::publish_event_with_buffers`], plus
+    /// [`Error::DeadlineExceeded`] if the deadline passed before every
+    /// subscriber was served. Its `sent` field counts the subscribers
+    /// reached in time.
+    ///
+    /// # Panics
+    ///
+    /// Same as [`Self::publish_event_with_buffers`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn publish_event_with_buffers_before<P: PayloadWireFormat, Tm: Timer>(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        message: &Message<P>,
+        msg_buf: &mut [u8],
+        protected_buf: &mut [u8],
+        timer: &Tm,
+        deadline: Deadline,
+    ) -> Result<usize, Error> {
+        self.publish_event_impl(
+            service_id,
+            instance_id,
+            event_group_id,
+            message,
+            msg_buf,
+            protected_buf,
+            || deadline.has_passed(timer.now()),
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+    async fn publish_event_impl<P: PayloadWireFormat>(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        message: &Message<P>,
+        msg_buf: &mut [u8],
+        protected_buf: &mut [u8],
+        expired: impl Fn() -> bool,
+    ) -> Result<usize, Error> {
+        let _turn = self
+            .take_turn(service_id, instance_id, event_group_id)
+            .await?;
         // Snapshot subscriber addresses into a stack-allocated buffer so
         // we can release the subscription read lock before doing async
         // sends. This avoids a per-event heap allocation that the old
         // `get_subscribers -> Vec<Subscriber>` API forced.
         //
-        // The buffer cap matches the manager's per-group cap so push()
-        // is provably infallible — see the `const _` guard below.
-        let mut subscribers: HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP> = HeaplessVec::new();
-        let _total = self
-            .subscriptions
-            .for_each_subscriber(service_id, instance_id, event_group_id, |sub| {
-                // push() can never fail here: SUBSCRIBERS_PER_GROUP is
-                // both the manager's per-group cap and this buffer's
-                // cap, so the manager will never feed us more than fits.
-                let _ = subscribers.push(sub.address);
-            })
+        // The buffer cap matches the manager's per-group cap so the
+        // snapshot never drops anyone — see the `const _` guard above.
+        let mut subscribers = self
+            .subscriber_addresses(service_id, instance_id, event_group_id)
             .await;
+        self.add_standby_subscribers(&mut subscribers);
+        let multicast =
+            self.use_multicast_group(service_id, instance_id, event_group_id, &mut subscribers);
 
-        if subscribers.is_empty() {
+        // A sticky or buffered event is encoded even without subscribers,
+        // to keep its value for the next one.
+        let event_key = StickyKey::new(
+            service_id,
+            instance_id,
+            event_group_id,
+            message.header().message_id().method_id(),
+        );
+        let sticky =
+            self.sticky_key_if_marked(service_id, instance_id, event_group_id, event_key.event);
+        let policy = self.no_subscriber_policy(event_key, &subscribers);
+        if subscribers.is_empty() && sticky.is_none() && !keeps_datagrams(policy) {
             crate::log::trace!(
                 "No subscribers for service 0x{:04X}, instance {}, event group 0x{:04X}",
                 service_id,
                 instance_id,
                 event_group_id
             );
-            return Ok(0);
+            return self.publish_unsent(event_key, policy, None);
+        }
+        if !subscribers.is_empty() && expired() {
+            crate::log::warn!(
+                "Deadline passed before publishing to service 0x{:04X}; dropping event",
+                service_id
+            );
+            return Err(Error::DeadlineExceeded { sent: 0 });
         }
 
         // Fail fast with the capacity error rather than letting
@@ -172,6 +1027,11 @@ where
         // (PR-3 #125 change: no longer uses an in-future `[u8; UDP_BUFFER_SIZE]`;
         // the caller decides the buffer size and lifetime.)
         let mut message_length = message.encode_to_slice(msg_buf)?;
+        self.validate_event(
+            service_id,
+            message.header().message_id().method_id(),
+            &msg_buf[16..message_length],
+        )?;
 
         // Apply E2E protect if configured. `protected_buf` is disjoint from
         // `msg_buf`, so we can read the unprotected payload directly out of
@@ -222,12 +1082,27 @@ where
                         );
                         return Err(Error::Capacity("udp_buffer"));
                     }
+                    Some(Err(e @ crate::e2e::Error::PayloadShorterThanOffset { .. })) => {
+                        crate::log::error!("E2E protect error: {:?}; dropping publish", e);
+                        return Err(Error::E2e(e));
+                    }
+                    #[cfg(feature = "secoc")]
+                    Some(Err(e @ crate::e2e::Error::KeyUnavailable(_))) => {
+                        crate::log::error!("E2E protect error: {:?}; dropping publish", e);
+                        return Err(Error::E2e(e));
+                    }
                     None => unreachable!("contains_key was true"),
                 }
             }
         }
 
         let datagram = &msg_buf[..message_length];
+        if let Some(key) = sticky {
+            self.cache_sticky(key, datagram);
+        }
+        if subscribers.is_empty() {
+            return self.publish_unsent(event_key, policy, Some(datagram));
+        }
 
         // Send to all snapshotted subscribers. Track the last
         // transport error so we can surface "every send failed" as
@@ -236,7 +1111,16 @@ where
         // subscribers" to the caller.
         let mut sent_count = 0usize;
         let mut last_err: Option<crate::transport::TransportError> = None;
-        for addr in &subscribers {
+        for (index, addr) in subscribers.iter().enumerate() {
+            if expired() {
+                crate::log::warn!(
+                    "Deadline passed after {}/{} subscribers for service 0x{:04X}; dropping event",
+                    index,
+                    subscribers.len(),
+                    service_id
+                );
+                return Err(Error::DeadlineExceeded { sent: sent_count });
+            }
             match self.socket.get().send_to(datagram, *addr).await {
                 Ok(()) => {
                     sent_count += 1;
@@ -268,14 +1152,15 @@ where
                 last_err.unwrap_or(crate::transport::TransportError::Unsupported),
             ));
         }
-        Ok(sent_count)
+        Ok(multicast.unwrap_or(sent_count))
     }
 
     /// Publish an event to all subscribers of an event group.
     ///
     /// Convenience wrapper over [`Self::publish_event_with_buffers`] that
-    /// internally allocates the two scratch `Vec`s required for the send
-    /// path. Available only when an allocator is present (`_alloc` feature).
+    /// takes the two scratch buffers required for the send path from the
+    /// publisher's pool (see [`SCRATCH_BUFFERS_CAP`]), allocating them
+    /// only when the pool is exhausted. Available only when an allocator is present (`_alloc` feature).
     /// Bare-metal callers without an allocator must supply their own
     /// scratch via [`Self::publish_event_with_buffers`] directly.
     ///
@@ -293,7 +1178,7 @@ where
     /// # Errors
     ///
     /// Returns an error if serialization fails or the serialized frame
-    /// exceeds the internally-allocated scratch buffer (which is sized
+    /// exceeds the internal scratch buffer (which is sized
     /// to `crate::UDP_BUFFER_SIZE`). Callers that need to control the
     /// buffer length must use [`Self::publish_event_with_buffers`]
     /// directly.
@@ -305,8 +1190,8 @@ where
         event_group_id: u16,
         message: &Message<P>,
     ) -> Result<usize, Error> {
-        let mut msg_buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
-        let mut protected_buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
+        let mut msg_buf = self.scratch_buffer();
+        let mut protected_buf = self.scratch_buffer();
         self.publish_event_with_buffers(
             service_id,
             instance_id,
@@ -318,21 +1203,58 @@ where
         .await
     }
 
+    /// [`Self::publish_event`] with a send deadline on `timer`'s clock;
+    /// see [`Self::publish_event_with_buffers_before`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::publish_event`], plus [`Error::DeadlineExceeded`]
+    /// if the deadline passed before every subscriber was served.
+    #[cfg(feature = "_alloc")]
+    pub async fn publish_event_before<P: PayloadWireFormat, Tm: Timer>(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        message: &Message<P>,
+        timer: &Tm,
+        deadline: Deadline,
+    ) -> Result<usize, Error> {
+        let mut msg_buf = self.scratch_buffer();
+        let mut protected_buf = self.scratch_buffer();
+        self.publish_event_with_buffers_before(
+            service_id,
+            instance_id,
+            event_group_id,
+            message,
+            &mut msg_buf,
+            &mut protected_buf,
+            timer,
+            deadline,
+        )
+        .await
+    }
+
     /// Publish raw event data using a caller-provided scratch buffer.
     ///
     /// The `buf` slice receives the serialized SOME/IP header + payload
-    /// datagram before being sent to each subscriber. The caller must
-    /// supply a buffer large enough to hold `16 + payload.len()` bytes;
-    /// [`Error::Capacity`]`("udp_buffer")` is returned if the buffer is
-    /// too small, without writing any bytes. On the bare-metal path,
-    /// callers typically supply a `static [u8; N]`.
+    /// datagram before being sent to each subscriber. A payload that does
+    /// not fit `buf` after the 16-byte header is sent as SOME/IP-TP
+    /// segments of at most `buf.len()` bytes instead (see
+    /// [`crate::protocol::tp`]); a sticky event published that way is not
+    /// cached. On the bare-metal path, callers typically supply a
+    /// `static [u8; N]`.
     ///
     /// This is useful when you've already applied E2E protection to the payload.
     ///
     /// # Errors
     ///
     /// Returns an error if the SOME/IP header fails to serialize, or
-    /// [`Error::Capacity`]`("udp_buffer")` if `buf` is too small for the frame.
+    /// [`Error::Capacity`]`("udp_buffer")` if `buf` is too small for the
+    /// header or, for a segmented payload, for one segment, or
+    /// [`Error::PayloadRejected`] if the event's validator rejects
+    /// `payload`, or [`Error::NoSubscribers`] as in
+    /// [`Self::publish_event_with_buffers`].
     #[allow(clippy::too_many_arguments)]
     pub async fn publish_raw_event_with_buffers(
         &self,
@@ -346,19 +1268,25 @@ where
         payload: &[u8],
         buf: &mut [u8],
     ) -> Result<usize, Error> {
+        let _turn = self
+            .take_turn(service_id, instance_id, event_group_id)
+            .await?;
         // Snapshot subscriber addresses into a stack buffer (see
         // publish_event_with_buffers for rationale).
-        let mut subscribers: HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP> = HeaplessVec::new();
-        let _total = self
-            .subscriptions
-            .for_each_subscriber(service_id, instance_id, event_group_id, |sub| {
-                let _ = subscribers.push(sub.address);
-            })
+        let mut subscribers = self
+            .subscriber_addresses(service_id, instance_id, event_group_id)
             .await;
-
-        if subscribers.is_empty() {
-            return Ok(0);
+        self.add_standby_subscribers(&mut subscribers);
+        let multicast =
+            self.use_multicast_group(service_id, instance_id, event_group_id, &mut subscribers);
+
+        let event_key = StickyKey::new(service_id, instance_id, event_group_id, event_id);
+        let sticky = self.sticky_key_if_marked(service_id, instance_id, event_group_id, event_id);
+        let policy = self.no_subscriber_policy(event_key, &subscribers);
+        if subscribers.is_empty() && sticky.is_none() && !keeps_datagrams(policy) {
+            return self.publish_unsent(event_key, policy, None);
         }
+        self.validate_event(service_id, event_id, payload)?;
 
         // Pre-build size check. Fail fast with `Error::Capacity` BEFORE
         // calling `Header::new_event`, which `assert!`s on payloads
@@ -379,12 +1307,27 @@ where
             return Err(Error::Capacity("udp_buffer"));
         }
         if payload.len() > buf.len().saturating_sub(16) {
-            crate::log::error!(
-                "raw event payload ({} bytes) + 16-byte header exceeds buf.len() ({}); dropping publish",
-                payload.len(),
-                buf.len()
+            // Too large for one datagram: send SOME/IP-TP segments of at
+            // most `buf.len()` bytes instead. The value cannot be cached
+            // whole, so a sticky event forgets its older one.
+            if let Some(key) = sticky {
+                self.cache_sticky(key, &[]);
+            }
+            if subscribers.is_empty() {
+                return self.publish_unsent(event_key, policy, None);
+            }
+            let header = Header::new_event(
+                service_id,
+                event_id,
+                request_id,
+                protocol_version,
+                interface_version,
+                0,
             );
-            return Err(Error::Capacity("udp_buffer"));
+            return self
+                .send_segmented(&header, payload, &subscribers, buf)
+                .await
+                .map(|sent| multicast.filter(|_| sent > 0).unwrap_or(sent));
         }
 
         // Build SOME/IP header
@@ -422,6 +1365,12 @@ where
         }
         buf[header_len..total_len].copy_from_slice(payload);
         let datagram = &buf[..total_len];
+        if let Some(key) = sticky {
+            self.cache_sticky(key, datagram);
+        }
+        if subscribers.is_empty() {
+            return self.publish_unsent(event_key, policy, Some(datagram));
+        }
 
         // Send to all snapshotted subscribers; surface total-failure
         // as `Err(Transport(_))` rather than `Ok(0)` (see
@@ -445,13 +1394,15 @@ where
                 last_err.unwrap_or(crate::transport::TransportError::Unsupported),
             ));
         }
-        Ok(sent_count)
+        Ok(multicast.unwrap_or(sent_count))
     }
 
     /// Publish raw event data (already serialized with E2E protection).
     ///
     /// Convenience wrapper over [`Self::publish_raw_event_with_buffers`] that
-    /// internally allocates the scratch `Vec` required for the send path.
+    /// takes the scratch buffer required for the send path from the
+    /// publisher's pool (see [`SCRATCH_BUFFERS_CAP`]), allocating it only
+    /// when the pool is exhausted.
     /// Available only when an allocator is present (`_alloc` feature).
     /// Bare-metal callers without an allocator must supply their own scratch
     /// via [`Self::publish_raw_event_with_buffers`] directly.
@@ -462,11 +1413,11 @@ where
     ///
     /// # Errors
     ///
-    /// Returns an error if the SOME/IP header fails to serialize or the
-    /// frame exceeds the internally-allocated scratch buffer (which is
-    /// sized to `crate::UDP_BUFFER_SIZE`). Callers that need to control
-    /// the buffer length must use [`Self::publish_raw_event_with_buffers`]
-    /// directly.
+    /// Returns an error if the SOME/IP header fails to serialize. Payloads
+    /// that do not fit the internal scratch buffer (sized to
+    /// `crate::UDP_BUFFER_SIZE`) go out as SOME/IP-TP segments of that
+    /// size. Callers that need to control the buffer length must use
+    /// [`Self::publish_raw_event_with_buffers`] directly.
     #[cfg(feature = "_alloc")]
     #[allow(clippy::too_many_arguments)]
     pub async fn publish_raw_event(
@@ -480,7 +1431,7 @@ where
         interface_version: u8,
         payload: &[u8],
     ) -> Result<usize, Error> {
-        let mut buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
+        let mut buf = self.scratch_buffer();
         self.publish_raw_event_with_buffers(
             service_id,
             instance_id,
@@ -556,7 +1507,8 @@ where
     /// own `run()` loop does: emit a `SubscribeNack` (or equivalent
     /// upstream notification) so the peer does not assume it is
     /// subscribed. A duplicate registration for an already-subscribed
-    /// address returns `Ok(())` (deduplicated).
+    /// address returns `Ok(())` (deduplicated). Subscribers registered
+    /// here do not appear in `Server::subscription_events`.
     pub async fn register_subscriber(
         &self,
         service_id: u16,
@@ -567,6 +1519,7 @@ where
         self.subscriptions
             .subscribe(service_id, instance_id, event_group_id, subscriber_addr)
             .await
+            .map(|_| ())
     }
 
     /// Remove a previously-registered subscriber from an event group.
@@ -615,8 +1568,8 @@ where
     /// instance id).
     ///
     /// `buf` receives the serialized SOME/IP header + payload before the send,
-    /// exactly as in [`Self::publish_raw_event_with_buffers`]; the caller must
-    /// supply at least `16 + payload.len()` bytes.
+    /// exactly as in [`Self::publish_raw_event_with_buffers`], including the
+    /// fallback to SOME/IP-TP segments for payloads that do not fit it.
     ///
     /// Returns `Ok(1)` when `target` is a current subscriber of the event group
     /// and the datagram was sent, and `Ok(0)` when `target` is not subscribed
@@ -630,9 +1583,10 @@ where
     /// # Errors
     ///
     /// Returns [`Error::Capacity`]`("udp_buffer")` if `buf` is too small for the
-    /// 16-byte SOME/IP header plus `payload`, [`Error::Transport`] if `target`
-    /// is subscribed but the send fails, or a serialization error if the header
-    /// fails to encode.
+    /// 16-byte SOME/IP header or one segment, [`Error::Transport`] if `target`
+    /// is subscribed but the send fails, [`Error::PayloadRejected`] if the
+    /// event's validator rejects `payload`, or a serialization error if the
+    /// header fails to encode.
     #[allow(clippy::too_many_arguments)]
     pub async fn publish_raw_event_to_with_buffers(
         &self,
@@ -647,19 +1601,19 @@ where
         payload: &[u8],
         buf: &mut [u8],
     ) -> Result<usize, Error> {
+        let _turn = self
+            .take_turn(service_id, instance_id, event_group_id)
+            .await?;
         // Only deliver to a currently-subscribed endpoint, so a caller cannot
         // address a receiver that has not subscribed.
-        let mut is_subscribed = false;
-        self.subscriptions
-            .for_each_subscriber(service_id, instance_id, event_group_id, |sub| {
-                if sub.address == target {
-                    is_subscribed = true;
-                }
-            })
-            .await;
+        let is_subscribed = self
+            .subscriber_addresses(service_id, instance_id, event_group_id)
+            .await
+            .contains(&target);
         if !is_subscribed {
             return Ok(0);
         }
+        self.validate_event(service_id, event_id, payload)?;
 
         // Buffer guards, keyed off `buf.len()` (see
         // `publish_raw_event_with_buffers` for the `buf.len() < 16` rationale).
@@ -671,12 +1625,15 @@ where
             return Err(Error::Capacity("udp_buffer"));
         }
         if payload.len() > buf.len().saturating_sub(16) {
-            crate::log::error!(
-                "raw event payload ({} bytes) + 16-byte header exceeds buf.len() ({}); dropping publish",
-                payload.len(),
-                buf.len()
+            let header = Header::new_event(
+                service_id,
+                event_id,
+                request_id,
+                protocol_version,
+                interface_version,
+                0,
             );
-            return Err(Error::Capacity("udp_buffer"));
+            return self.send_segmented(&header, payload, &[target], buf).await;
         }
 
         let header = Header::new_event(
@@ -720,7 +1677,9 @@ where
     /// Publish raw event data to a SINGLE subscriber, addressed by endpoint.
     ///
     /// Convenience wrapper over [`Self::publish_raw_event_to_with_buffers`] that
-    /// internally allocates the scratch `Vec` required for the send path.
+    /// takes the scratch buffer required for the send path from the
+    /// publisher's pool (see [`SCRATCH_BUFFERS_CAP`]), allocating it only
+    /// when the pool is exhausted.
     /// Available only when an allocator is present (`_alloc` feature).
     /// Bare-metal callers without an allocator must supply their own scratch
     /// via [`Self::publish_raw_event_to_with_buffers`] directly.
@@ -730,11 +1689,12 @@ where
     ///
     /// # Errors
     ///
-    /// Returns an error if the SOME/IP header fails to serialize, the frame
-    /// exceeds the internally-allocated scratch buffer (sized to
-    /// `crate::UDP_BUFFER_SIZE`), or the send to a subscribed `target` fails
-    /// ([`Error::Transport`]). Callers that need to control the buffer length
-    /// must use [`Self::publish_raw_event_to_with_buffers`] directly.
+    /// Returns an error if the SOME/IP header fails to serialize or the send
+    /// to a subscribed `target` fails ([`Error::Transport`]). Payloads that do
+    /// not fit the internal scratch buffer (sized to
+    /// `crate::UDP_BUFFER_SIZE`) go out as SOME/IP-TP segments of that size.
+    /// Callers that need to control the buffer length must use
+    /// [`Self::publish_raw_event_to_with_buffers`] directly.
     #[cfg(feature = "_alloc")]
     #[allow(clippy::too_many_arguments)]
     pub async fn publish_raw_event_to(
@@ -749,7 +1709,7 @@ where
         interface_version: u8,
         payload: &[u8],
     ) -> Result<usize, Error> {
-        let mut buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
+        let mut buf = self.scratch_buffer();
         self.publish_raw_event_to_with_buffers(
             target,
             service_id,
@@ -779,15 +1739,9 @@ where
         instance_id: u16,
         event_group_id: u16,
     ) -> HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP> {
-        let mut addrs: HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP> = HeaplessVec::new();
         self.subscriptions
-            .for_each_subscriber(service_id, instance_id, event_group_id, |sub| {
-                // push() can never fail: this buffer's cap equals the manager's
-                // per-group cap, so it will never feed us more than fits.
-                let _ = addrs.push(sub.address);
-            })
-            .await;
-        addrs
+            .subscriber_addresses(service_id, instance_id, event_group_id)
+            .await
     }
 }
 
@@ -908,6 +1862,75 @@ mod tests {
         assert!(len > 0);
     }
 
+    /// With a multicast group set, a publish is one send to the group
+    /// that serves every subscriber. A loopback socket stands in for the
+    /// group so the test needs no multicast route.
+    #[tokio::test]
+    async fn test_publish_to_multicast_group_sends_once() {
+        let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
+        let group = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let subscriber = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let core::net::SocketAddr::V4(group_addr) = group.local_addr().unwrap() else {
+            panic!("expected v4 source address");
+        };
+        let core::net::SocketAddr::V4(subscriber_addr) = subscriber.local_addr().unwrap() else {
+            panic!("expected v4 source address");
+        };
+        {
+            let mut mgr = subscriptions.write().await;
+            mgr.subscribe(0x5B, 1, 0x01, subscriber_addr).unwrap();
+            mgr.subscribe(0x5B, 1, 0x01, SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9))
+                .unwrap();
+        }
+        let (publisher, _) = make_publisher(subscriptions).await;
+        publisher
+            .set_multicast_group(0x5B, 1, 0x01, Some(group_addr))
+            .unwrap();
+
+        let msg = make_test_message();
+        assert_eq!(
+            publisher.publish_event(0x5B, 1, 0x01, &msg).await.unwrap(),
+            2
+        );
+        let count = publisher
+            .publish_raw_event(0x5B, 1, 0x01, 0x8001, 0x0001, 0x01, 0x01, &[0xAA])
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            publisher.publish_event(0x5B, 1, 0x02, &msg).await.unwrap(),
+            0,
+            "no subscribers, nothing sent to the group"
+        );
+
+        let mut buf = [0u8; 1024];
+        for _ in 0..2 {
+            tokio::time::timeout(std::time::Duration::from_secs(2), group.recv_from(&mut buf))
+                .await
+                .expect("timeout receiving event")
+                .unwrap();
+        }
+        let recv = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            subscriber.recv_from(&mut buf),
+        )
+        .await;
+        assert!(recv.is_err(), "subscribers get no unicast copy");
+
+        publisher.set_multicast_group(0x5B, 1, 0x01, None).unwrap();
+        assert_eq!(
+            publisher.publish_event(0x5B, 1, 0x01, &msg).await.unwrap(),
+            2
+        );
+        tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            subscriber.recv_from(&mut buf),
+        )
+        .await
+        .expect("unicast again once the group is cleared")
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn test_publish_raw_event_no_subscribers() {
         let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
@@ -920,21 +1943,143 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_publish_raw_event_exceeds_udp_buffer_returns_capacity_error() {
+    async fn publish_without_subscribers_fails_under_fail_policy() {
         let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
-        let addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9999);
+        let (publisher, _) = make_publisher(subscriptions).await;
+        publisher
+            .set_no_subscriber_policy(0x5B, 1, 0x01, 0x8001, NoSubscriberPolicy::Fail)
+            .unwrap();
+        let err = publisher
+            .publish_raw_event(0x5B, 1, 0x01, 0x8001, 0x0001, 0x01, 0x01, &[0xAA])
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::NoSubscribers {
+                service_id: 0x5B,
+                event_group_id: 0x01,
+                event_id: 0x8001,
+            }
+        ));
+        // Other events keep the default.
+        let count = publisher
+            .publish_raw_event(0x5B, 1, 0x01, 0x8002, 0x0001, 0x01, 0x01, &[0xAA])
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[tokio::test]
+    async fn buffered_events_reach_the_next_subscriber_once() {
+        let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
+        let (publisher, _) = make_publisher(Arc::clone(&subscriptions)).await;
+        publisher
+            .set_no_subscriber_policy(0x5B, 1, 0x01, 0x8001, NoSubscriberPolicy::Buffer(2))
+            .unwrap();
+        for sample in 1..=3u8 {
+            let count = publisher
+                .publish_raw_event(0x5B, 1, 0x01, 0x8001, 0x0001, 0x01, 0x01, &[sample])
+                .await
+                .unwrap();
+            assert_eq!(count, 0);
+        }
+
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let core::net::SocketAddr::V4(recv_addr) = receiver.local_addr().unwrap() else {
+            panic!("expected v4 source address");
+        };
+        publisher
+            .register_subscriber(0x5B, 1, 0x01, recv_addr)
+            .await
+            .unwrap();
+        let sent = publisher
+            .deliver_sticky_events(recv_addr, 0x5B, 1, 0x01)
+            .await
+            .unwrap();
+        assert_eq!(sent, 2);
+        let mut buf = [0u8; 64];
+        for expected in [2u8, 3] {
+            let (len, _) = tokio::time::timeout(
+                std::time::Duration::from_secs(2),
+                receiver.recv_from(&mut buf),
+            )
+            .await
+            .expect("timeout receiving buffered event")
+            .unwrap();
+            assert_eq!(&buf[16..len], &[expected], "the last two, oldest first");
+        }
+
+        let sent = publisher
+            .deliver_sticky_events(recv_addr, 0x5B, 1, 0x01)
+            .await
+            .unwrap();
+        assert_eq!(sent, 0, "buffered events are delivered once");
+    }
+
+    #[tokio::test]
+    async fn test_publish_raw_event_larger_than_buffer_is_sent_segmented() {
+        use crate::protocol::MessageView;
+        use crate::protocol::tp::Reassembler;
+
+        let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let core::net::SocketAddr::V4(recv_addr) = receiver.local_addr().unwrap() else {
+            panic!("expected v4 source address");
+        };
         {
             let mut mgr = subscriptions.write().await;
-            mgr.subscribe(0x5B, 1, 0x01, addr).unwrap();
+            mgr.subscribe(0x5B, 1, 0x01, recv_addr).unwrap();
         }
         let (publisher, _) = make_publisher(subscriptions).await;
 
-        // Payload = UDP_BUFFER_SIZE forces total (header + payload) over the cap.
-        let too_big = vec![0u8; UDP_BUFFER_SIZE];
+        let payload: Vec<u8> = (0..4000u32).map(|i| i.to_le_bytes()[0]).collect();
+        let count = publisher
+            .publish_raw_event(0x5B, 1, 0x01, 0x8001, 0x0001, 0x01, 0x01, &payload)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let mut reassembler = Reassembler::<1, 8192>::new(core::time::Duration::from_secs(1));
+        let mut buf = [0u8; 2048];
+        let mut segments = 0;
+        let whole = loop {
+            let (len, source) = tokio::time::timeout(
+                std::time::Duration::from_secs(2),
+                receiver.recv_from(&mut buf),
+            )
+            .await
+            .expect("timeout receiving segment")
+            .unwrap();
+            assert!(len <= UDP_BUFFER_SIZE);
+            segments += 1;
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            if let Some(message) = reassembler
+                .feed(source, &view, core::time::Duration::ZERO)
+                .unwrap()
+            {
+                break message.to_vec();
+            }
+        };
+        assert_eq!(segments, 3);
+        let view = MessageView::parse(&whole).unwrap();
+        assert!(!view.header().message_type().is_tp());
+        assert_eq!(view.payload_bytes(), payload.as_slice());
+
+        // A buffer too small for one segment is still a capacity error.
         let err = publisher
-            .publish_raw_event(0x5B, 1, 0x01, 0x8001, 0x0001, 0x01, 0x01, &too_big)
+            .publish_raw_event_with_buffers(
+                0x5B,
+                1,
+                0x01,
+                0x8001,
+                0x0002,
+                0x01,
+                0x01,
+                &payload,
+                &mut [0u8; 32],
+            )
             .await
-            .expect_err("oversize payload must error, not report Ok(0)");
+            .expect_err("no room for a segment");
         match err {
             Error::Capacity(tag) => assert_eq!(tag, "udp_buffer"),
             other => panic!("expected Error::Capacity(\"udp_buffer\"), got {other:?}"),
@@ -1235,6 +2380,54 @@ mod tests {
         assert_eq!(&buf[16..18], &payload);
     }
 
+    /// Timer stuck at a fixed instant, for deadline tests.
+    struct FixedClock(core::time::Duration);
+
+    impl Timer for FixedClock {
+        type SleepFuture<'a> = core::future::Ready<()>;
+
+        fn sleep(&self, _duration: core::time::Duration) -> Self::SleepFuture<'_> {
+            core::future::ready(())
+        }
+
+        fn now(&self) -> Option<core::time::Duration> {
+            Some(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn publish_event_before_drops_stale_event() {
+        let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let std::net::SocketAddr::V4(addr) = receiver.local_addr().unwrap() else {
+            panic!("expected IPv4 address");
+        };
+        subscriptions
+            .write()
+            .await
+            .subscribe(0x5B, 1, 0x01, addr)
+            .unwrap();
+        let (publisher, _) = make_publisher(subscriptions).await;
+        let clock = FixedClock(core::time::Duration::from_secs(10));
+        let msg = make_test_message();
+
+        let late = Deadline::at(core::time::Duration::from_secs(5));
+        let result = publisher
+            .publish_event_before(0x5B, 1, 0x01, &msg, &clock, late)
+            .await;
+        assert!(matches!(result, Err(Error::DeadlineExceeded { sent: 0 })));
+
+        let in_time = Deadline::at(core::time::Duration::from_secs(20));
+        let sent = publisher
+            .publish_event_before(0x5B, 1, 0x01, &msg, &clock, in_time)
+            .await
+            .unwrap();
+        assert_eq!(sent, 1);
+        let mut buf = [0u8; 64];
+        let (_, from) = receiver.recv_from(&mut buf).await.unwrap();
+        assert!(from.ip().is_loopback());
+    }
+
     #[tokio::test]
     async fn test_subscriber_count() {
         let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
@@ -1528,4 +2721,240 @@ mod tests {
         assert!(addrs.contains(&a));
         assert!(addrs.contains(&b));
     }
+
+    /// Socket whose sends take longer the lower the last byte of the
+    /// datagram, and which logs each datagram's last byte and target
+    /// when its send completes.
+    struct SlowSocket {
+        sent: Mutex<Vec<(SocketAddrV4, u8)>>,
+    }
+
+    impl crate::transport::TransportSocket for SlowSocket {
+        type SendFuture<'a> = core::pin::Pin<
+            std::boxed::Box<
+                dyn core::future::Future<Output = Result<(), crate::transport::TransportError>>
+                    + Send
+                    + 'a,
+            >,
+        >;
+        type RecvFuture<'a> = core::future::Ready<
+            Result<crate::transport::ReceivedDatagram, crate::transport::TransportError>,
+        >;
+
+        fn send_to<'a>(&'a self, buf: &'a [u8], target: SocketAddrV4) -> Self::SendFuture<'a> {
+            let seq = buf[buf.len() - 1];
+            std::boxed::Box::pin(async move {
+                let delay = 2 * u64::from(ORDERING_PUBLISHES - seq);
+                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                self.sent.lock().unwrap().push((target, seq));
+                Ok(())
+            })
+        }
+        fn recv_from<'a>(&'a self, _buf: &'a mut [u8]) -> Self::RecvFuture<'a> {
+            core::future::ready(Err(crate::transport::TransportError::Unsupported))
+        }
+        fn local_addr(&self) -> Result<SocketAddrV4, crate::transport::TransportError> {
+            Ok(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+        }
+        fn join_multicast_v4(
+            &self,
+            _g: Ipv4Addr,
+            _i: Ipv4Addr,
+        ) -> Result<(), crate::transport::TransportError> {
+            Ok(())
+        }
+        fn leave_multicast_v4(
+            &self,
+            _g: Ipv4Addr,
+            _i: Ipv4Addr,
+        ) -> Result<(), crate::transport::TransportError> {
+            Ok(())
+        }
+    }
+
+    /// As many publishes as an ordered group queues at once.
+    #[allow(clippy::cast_possible_truncation)]
+    const ORDERING_PUBLISHES: u8 = crate::server::ORDERED_PUBLISHES_CAP as u8;
+
+    type SlowPublisher = EventPublisher<
+        Arc<Mutex<E2ERegistry>>,
+        Arc<RwLock<SubscriptionManager>>,
+        Arc<SlowSocket>,
+        SlowSocket,
+    >;
+
+    async fn slow_publisher(subscribers: &[SocketAddrV4]) -> (Arc<SlowPublisher>, Arc<SlowSocket>) {
+        let subscriptions = Arc::new(RwLock::new(SubscriptionManager::new()));
+        for addr in subscribers {
+            subscriptions
+                .write()
+                .await
+                .subscribe(0x5B, 1, 0x01, *addr)
+                .unwrap();
+        }
+        let socket = Arc::new(SlowSocket {
+            sent: Mutex::new(Vec::new()),
+        });
+        let publisher = EventPublisher::new(subscriptions, Arc::clone(&socket), test_registry());
+        (Arc::new(publisher), socket)
+    }
+
+    /// Publish `0..ORDERING_PUBLISHES` from one task each, started in
+    /// that order, and return the sequence each subscriber received.
+    async fn publish_from_tasks(
+        publisher: &Arc<SlowPublisher>,
+        socket: &SlowSocket,
+        subscribers: &[SocketAddrV4],
+    ) -> Vec<Vec<u8>> {
+        let tasks: Vec<_> = (0..ORDERING_PUBLISHES)
+            .map(|seq| {
+                let publisher = Arc::clone(publisher);
+                tokio::spawn(async move {
+                    publisher
+                        .publish_raw_event(0x5B, 1, 0x01, 0x8001, 0, 0x01, 0x01, &[seq])
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), subscribers.len());
+        }
+        let sent = socket.sent.lock().unwrap();
+        subscribers
+            .iter()
+            .map(|addr| {
+                sent.iter()
+                    .filter(|(target, _)| target == addr)
+                    .map(|(_, seq)| *seq)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn ordering_subscribers() -> Vec<SocketAddrV4> {
+        (30_501..30_504)
+            .take(SUBSCRIBERS_PER_GROUP)
+            .map(|port| SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+            .collect()
+    }
+
+    /// The current-thread runtime first polls spawned tasks in the order
+    /// they were spawned, which makes that the publish order. The socket
+    /// finishes later publishes' sends first, so without ordering the
+    /// subscribers see them out of order.
+    #[tokio::test]
+    async fn ordered_event_group_delivers_in_publish_order() {
+        let subscribers = ordering_subscribers();
+        let in_order: Vec<u8> = (0..ORDERING_PUBLISHES).collect();
+
+        let (publisher, socket) = slow_publisher(&subscribers).await;
+        let received = publish_from_tasks(&publisher, &socket, &subscribers).await;
+        assert!(received.iter().all(|seqs| *seqs != in_order));
+
+        let (publisher, socket) = slow_publisher(&subscribers).await;
+        publisher
+            .set_ordered_event_group(0x5B, 1, 0x01, true)
+            .unwrap();
+        let received = publish_from_tasks(&publisher, &socket, &subscribers).await;
+        for seqs in received {
+            assert_eq!(seqs, in_order);
+        }
+    }
+
+    /// On a multi-threaded runtime the order the tasks start in is up to
+    /// the scheduler, but every subscriber still sees the same order,
+    /// and each publish finishes sending before the next one starts.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn ordered_event_group_serializes_publishes_across_threads() {
+        let subscribers = ordering_subscribers();
+        let (publisher, socket) = slow_publisher(&subscribers).await;
+        publisher
+            .set_ordered_event_group(0x5B, 1, 0x01, true)
+            .unwrap();
+
+        let received = publish_from_tasks(&publisher, &socket, &subscribers).await;
+        assert!(received.iter().all(|seqs| *seqs == received[0]));
+        let mut all: Vec<u8> = received[0].clone();
+        all.sort_unstable();
+        assert_eq!(all, (0..ORDERING_PUBLISHES).collect::<Vec<_>>());
+
+        // The sends of one publish are contiguous in the socket's log.
+        let sent = socket.sent.lock().unwrap();
+        for publish in sent.chunks(subscribers.len()) {
+            assert!(publish.iter().all(|(_, seq)| *seq == publish[0].1));
+        }
+    }
+
+    #[tokio::test]
+    async fn a_cancelled_ordered_publish_gives_up_its_turn() {
+        let subscribers = ordering_subscribers();
+        let (publisher, socket) = slow_publisher(&subscribers[..1]).await;
+        publisher
+            .set_ordered_event_group(0x5B, 1, 0x01, true)
+            .unwrap();
+
+        // Cancel a publish while it holds the turn, mid-send.
+        let cancelled = tokio::time::timeout(
+            std::time::Duration::from_millis(1),
+            publisher.publish_raw_event(0x5B, 1, 0x01, 0x8001, 0, 0x01, 0x01, &[0]),
+        )
+        .await;
+        assert!(cancelled.is_err());
+
+        let sent = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            publisher.publish_raw_event(0x5B, 1, 0x01, 0x8001, 0, 0x01, 0x01, &[1]),
+        )
+        .await
+        .expect("the next publish must not wait for the cancelled one");
+        assert_eq!(sent.unwrap(), 1);
+        assert_eq!(*socket.sent.lock().unwrap(), [(subscribers[0], 1)]);
+    }
+
+    #[tokio::test]
+    async fn ordered_event_groups_are_capped() {
+        let (publisher, _) =
+            make_publisher(Arc::new(RwLock::new(SubscriptionManager::new()))).await;
+        for event_group in 0..crate::server::ORDERED_GROUPS_CAP {
+            let event_group = u16::try_from(event_group).unwrap();
+            publisher
+                .set_ordered_event_group(0x5B, 1, event_group, true)
+                .unwrap();
+        }
+        assert!(matches!(
+            publisher.set_ordered_event_group(0x5B, 2, 0x01, true),
+            Err(Error::Capacity("ordered_groups"))
+        ));
+        // Turning a group off frees its slot.
+        publisher
+            .set_ordered_event_group(0x5B, 1, 0, false)
+            .unwrap();
+        publisher
+            .set_ordered_event_group(0x5B, 2, 0x01, true)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn recv_buffers_are_reused_once_returned() {
+        let (publisher, _) =
+            make_publisher(Arc::new(RwLock::new(SubscriptionManager::new()))).await;
+        let mut held: std::vec::Vec<_> = (0..RECV_BUFFERS_CAP)
+            .map(|_| publisher.recv_buffer())
+            .collect();
+        assert!(
+            held.iter()
+                .all(crate::buffer_pool::ScratchBuffer::is_leased)
+        );
+        assert!(held.iter().all(|buf| buf.len() == RECV_BUFFER_LEN));
+        // Beyond the pool a buffer is allocated instead.
+        let extra = publisher.recv_buffer();
+        assert!(!extra.is_leased());
+        assert_eq!(extra.len(), RECV_BUFFER_LEN);
+        // A returned slot is handed out again, zeroed.
+        held[0][0] = 0xAA;
+        held.swap_remove(0);
+        let reused = publisher.recv_buffer();
+        assert!(reused.is_leased());
+        assert_eq!(reused[0], 0);
+    }
 }
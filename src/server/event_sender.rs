@@ -0,0 +1,186 @@
+//! Per-event publishing handles, created with
+//! [`Server::event_sender`](super::Server::event_sender).
+
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicU16, Ordering};
+
+use super::Error;
+use super::event_publisher::EventPublisher;
+use super::subscription_manager::SubscriptionHandle;
+use crate::transport::{E2ERegistryHandle, SharedHandle, TransportSocket};
+
+/// Publishing handle for one event, with its service, instance, event
+/// group and event IDs fixed when it is created.
+///
+/// Application code sending from many tasks clones the handle instead of
+/// passing the four `u16`s to every `EventPublisher::publish_raw_event`
+/// call, where two swapped IDs
+/// still type-check. Every clone shares one session counter, dedicated to
+/// this event, so the `request_id` of consecutive notifications counts
+/// up from 1 (skipping 0 on wrap) no matter which task sends them.
+///
+/// `P` is the [`EventPublisher`] type, `Hep` the handle it is shared
+/// through (as on [`Server`](super::Server)) and `C` the handle of the
+/// session counter: `Server::event_sender` (with an allocator) uses
+/// `Arc<AtomicU16>`; without one, build the sender with
+/// [`Self::new`] around a `&'static AtomicU16`.
+pub struct EventSender<P, Hep, C> {
+    publisher: Hep,
+    service_id: u16,
+    instance_id: u16,
+    event_group_id: u16,
+    event_id: u16,
+    interface_version: u8,
+    session: C,
+    /// `false` to send session ID 0 instead of counting.
+    session_handling: bool,
+    /// `P` only appears in the bound `Hep: SharedHandle<P>`.
+    _publisher: PhantomData<fn() -> P>,
+}
+
+impl<P, Hep: Clone, C: Clone> Clone for EventSender<P, Hep, C> {
+    fn clone(&self) -> Self {
+        Self {
+            publisher: self.publisher.clone(),
+            session: self.session.clone(),
+            _publisher: PhantomData,
+            ..*self
+        }
+    }
+}
+
+impl<P, Hep, C> core::fmt::Debug for EventSender<P, Hep, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EventSender")
+            .field("service_id", &self.service_id)
+            .field("instance_id", &self.instance_id)
+            .field("event_group_id", &self.event_group_id)
+            .field("event_id", &self.event_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, S, H, T, Hep, C> EventSender<EventPublisher<R, S, H, T>, Hep, C>
+where
+    R: E2ERegistryHandle,
+    S: SubscriptionHandle,
+    T: TransportSocket + 'static,
+    H: SharedHandle<T>,
+    Hep: SharedHandle<EventPublisher<R, S, H, T>>,
+    C: SharedHandle<AtomicU16>,
+{
+    /// Bind `publisher` to one event. `interface_version` goes into the
+    /// header of every notification; `session` holds the last session ID
+    /// sent and normally starts at 0.
+    #[must_use]
+    pub const fn new(
+        publisher: Hep,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+        interface_version: u8,
+        session: C,
+    ) -> Self {
+        Self {
+            publisher,
+            service_id,
+            instance_id,
+            event_group_id,
+            event_id,
+            interface_version,
+            session,
+            session_handling: true,
+            _publisher: PhantomData,
+        }
+    }
+
+    /// Send every notification with session ID `0`, for consumers whose
+    /// stacks run without session handling, when `session_handling` is
+    /// `false`. The session counter is then left untouched.
+    #[must_use]
+    pub const fn with_session_handling(mut self, session_handling: bool) -> Self {
+        self.session_handling = session_handling;
+        self
+    }
+
+    #[must_use]
+    pub const fn service_id(&self) -> u16 {
+        self.service_id
+    }
+
+    #[must_use]
+    pub const fn instance_id(&self) -> u16 {
+        self.instance_id
+    }
+
+    #[must_use]
+    pub const fn event_group_id(&self) -> u16 {
+        self.event_group_id
+    }
+
+    #[must_use]
+    pub const fn event_id(&self) -> u16 {
+        self.event_id
+    }
+
+    /// Advance the session counter, skipping 0 on wrap. Always 0 without
+    /// session handling.
+    fn next_session_id(&self) -> u16 {
+        if !self.session_handling {
+            return 0;
+        }
+        // See `SdStateManager::next_session_id_with_reboot_flag` for the
+        // `fetch_update` deprecation.
+        #[allow(deprecated)]
+        let prev = self
+            .session
+            .get()
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |sid| {
+                Some(sid.checked_add(1).unwrap_or(1))
+            })
+            .unwrap_or_else(|sid| sid);
+        prev.checked_add(1).unwrap_or(1)
+    }
+
+    /// Send `payload` (already E2E-protected, if the event uses E2E) to
+    /// every subscriber of the event group, using `buf` as scratch for the
+    /// datagram. See [`EventPublisher::publish_raw_event_with_buffers`];
+    /// each call takes the next session ID, whether or not it reaches
+    /// anyone.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`EventPublisher::publish_raw_event_with_buffers`].
+    pub async fn send_with_buffers(&self, payload: &[u8], buf: &mut [u8]) -> Result<usize, Error> {
+        let session_id = self.next_session_id();
+        self.publisher
+            .get()
+            .publish_raw_event_with_buffers(
+                self.service_id,
+                self.instance_id,
+                self.event_group_id,
+                self.event_id,
+                u32::from(session_id),
+                0x01,
+                self.interface_version,
+                payload,
+                buf,
+            )
+            .await
+    }
+
+    /// [`Self::send_with_buffers`] with a scratch buffer of
+    /// [`UDP_BUFFER_SIZE`](crate::UDP_BUFFER_SIZE) bytes from the
+    /// publisher's pool (see
+    /// [`SCRATCH_BUFFERS_CAP`](super::SCRATCH_BUFFERS_CAP)).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::send_with_buffers`].
+    #[cfg(feature = "_alloc")]
+    pub async fn send(&self, payload: &[u8]) -> Result<usize, Error> {
+        let mut buf = self.publisher.get().scratch_buffer();
+        self.send_with_buffers(payload, &mut buf).await
+    }
+}
@@ -0,0 +1,314 @@
+//! SOME/IP fields: a stored value with getter, setter and notifier,
+//! answered through [`MethodRouter::field`].
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+
+use crate::protocol::ReturnCode;
+
+use super::Error;
+use super::request_handler::{MethodError, MethodRouter, Request};
+use super::sd_state::LockCell;
+
+/// Wire format of a [`Field`] value: the getter and notifier payload, and
+/// what the setter accepts.
+///
+/// Implemented big-endian for the integer and float primitives, as one
+/// byte (`0` or `1`) for `bool`, and as the raw bytes for `Vec<u8>`.
+pub trait FieldCodec: Clone + Send + 'static {
+    /// The payload carrying `self`.
+    fn encode(&self) -> Vec<u8>;
+
+    /// The value carried by a setter payload, `None` if it is malformed.
+    fn decode(payload: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_field_codec {
+    ($($ty:ty),*) => {$(
+        impl FieldCodec for $ty {
+            fn encode(&self) -> Vec<u8> {
+                self.to_be_bytes().to_vec()
+            }
+
+            fn decode(payload: &[u8]) -> Option<Self> {
+                payload.try_into().ok().map(<$ty>::from_be_bytes)
+            }
+        }
+    )*};
+}
+
+impl_field_codec!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl FieldCodec for bool {
+    fn encode(&self) -> Vec<u8> {
+        alloc::vec![u8::from(*self)]
+    }
+
+    fn decode(payload: &[u8]) -> Option<Self> {
+        match payload {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl FieldCodec for Vec<u8> {
+    fn encode(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn decode(payload: &[u8]) -> Option<Self> {
+        Some(payload.to_vec())
+    }
+}
+
+/// Publishes the notification of a [`Field`] each time it is set.
+///
+/// Resolves to the number of subscribers reached, as
+/// [`EventSender::send`](super::EventSender::send) does. Implemented for
+/// every `Fn(Vec<u8>) -> impl Future<Output = Result<usize, Error>>`
+/// closure, typically one sending through a clone of an
+/// [`EventSender`](super::EventSender):
+///
+/// ```ignore
+/// let sender = server.event_sender(0x5B, 1, 0x01, 0x8001);
+/// let field = Field::new(0u32).with_notifier(move |payload: Vec<u8>| {
+///     let sender = sender.clone();
+///     async move { sender.send(&payload).await }
+/// });
+/// ```
+pub trait FieldNotifier: Send + Sync + 'static {
+    fn notify(&self, payload: Vec<u8>) -> impl Future<Output = Result<usize, Error>> + Send + '_;
+}
+
+impl<F, Fut> FieldNotifier for F
+where
+    F: Fn(Vec<u8>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<usize, Error>> + Send + 'static,
+{
+    fn notify(&self, payload: Vec<u8>) -> impl Future<Output = Result<usize, Error>> + Send + '_ {
+        self(payload)
+    }
+}
+
+type Notification<'a> = Pin<Box<dyn Future<Output = Result<usize, Error>> + Send + 'a>>;
+
+/// Object-safe form of [`FieldNotifier`].
+trait ErasedNotifier: Send + Sync {
+    fn call(&self, payload: Vec<u8>) -> Notification<'_>;
+}
+
+impl<N: FieldNotifier> ErasedNotifier for N {
+    fn call(&self, payload: Vec<u8>) -> Notification<'_> {
+        Box::pin(self.notify(payload))
+    }
+}
+
+struct Shared<T> {
+    value: LockCell<T>,
+    notifier: Option<Box<dyn ErasedNotifier>>,
+}
+
+/// A field of a service: a value of type `T` that clients read with the
+/// getter method, write with the setter method and follow through the
+/// notifier event.
+///
+/// Clones share the value, so the application keeps one to read and
+/// update the field while [`MethodRouter::field`] answers the getter and
+/// setter with another. Every [`Self::set`], by the application or
+/// through the setter, publishes the new value through the notifier.
+/// Mark the notifier event sticky with
+/// [`EventPublisher::set_sticky_event`](super::EventPublisher::set_sticky_event)
+/// and set the initial value once before anyone subscribes, so each new
+/// subscriber starts from the current value.
+pub struct Field<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for Field<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for Field<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Field")
+            .field("notifier", &self.shared.notifier.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: FieldCodec> Field<T> {
+    /// A field holding `initial`, without a notifier.
+    #[must_use]
+    pub fn new(initial: T) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                value: LockCell::new(initial),
+                notifier: None,
+            }),
+        }
+    }
+
+    /// Publish every value set through `notifier`. Call before cloning
+    /// the field: clones made earlier keep publishing through the previous
+    /// notifier.
+    #[must_use]
+    pub fn with_notifier(self, notifier: impl FieldNotifier) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                value: LockCell::new(self.get()),
+                notifier: Some(Box::new(notifier)),
+            }),
+        }
+    }
+
+    fn with_value<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        // The module is only built with a lock backend.
+        self.shared
+            .value
+            .with(f)
+            .unwrap_or_else(|| unreachable!("LockCell without a lock backend"))
+    }
+
+    /// The current value.
+    #[must_use]
+    pub fn get(&self) -> T {
+        self.with_value(|value| value.clone())
+    }
+
+    /// Store `value` and publish it through the notifier. Returns the
+    /// number of subscribers reached, `0` without a notifier.
+    ///
+    /// # Errors
+    ///
+    /// Whatever the notifier fails with. The value is stored either way.
+    pub async fn set(&self, value: T) -> Result<usize, Error> {
+        let payload = value.encode();
+        self.with_value(|stored| *stored = value);
+        match &self.shared.notifier {
+            Some(notifier) => notifier.call(payload).await,
+            None => Ok(0),
+        }
+    }
+
+    /// Set the value carried by the request and answer with it. A failed
+    /// notification does not fail the request: the value is set.
+    async fn answer_set(&self, request: Request) -> Result<Vec<u8>, MethodError> {
+        let value = T::decode(&request.payload).ok_or(ReturnCode::MalformedMessage)?;
+        let payload = value.encode();
+        if let Err(error) = self.set(value).await {
+            crate::log::warn!("field notification failed: {:?}", error);
+        }
+        Ok(payload)
+    }
+}
+
+impl MethodRouter {
+    /// Answer the getter (`Some(method_id)`) and the setter of `field`.
+    /// The getter replies with the current value; the setter stores the
+    /// value in its payload, publishes it through the field's notifier and
+    /// replies with it, or answers [`ReturnCode::MalformedMessage`] if the
+    /// payload does not decode. `None` leaves that method unrouted, for a
+    /// read-only field or one without a getter.
+    #[must_use]
+    pub fn field<T: FieldCodec>(
+        self,
+        field: &Field<T>,
+        getter: Option<u16>,
+        setter: Option<u16>,
+    ) -> Self {
+        let mut router = self;
+        if let Some(method_id) = getter {
+            let field = field.clone();
+            router = router.route(method_id, move |_: Request| {
+                let payload = field.get().encode();
+                async move { Ok(payload) }
+            });
+        }
+        if let Some(method_id) = setter {
+            let field = field.clone();
+            router = router.route(method_id, move |request: Request| {
+                let field = field.clone();
+                async move { field.answer_set(request).await }
+            });
+        }
+        router
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::e2e::E2ECheckStatus;
+    use core::net::{Ipv4Addr, SocketAddrV4};
+    use std::sync::Mutex;
+
+    fn request(payload: &[u8]) -> Request {
+        Request {
+            source: SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30509),
+            service_id: 0x5B,
+            method_id: 0x0002,
+            interface_version: 1,
+            fire_and_forget: false,
+            e2e_status: E2ECheckStatus::Unchecked,
+            payload: payload.to_vec(),
+        }
+    }
+
+    #[test]
+    fn codecs_round_trip_big_endian() {
+        assert_eq!(0x0102_0304u32.encode(), [1, 2, 3, 4]);
+        assert_eq!(u32::decode(&[1, 2, 3, 4]), Some(0x0102_0304));
+        assert_eq!(u32::decode(&[1, 2, 3]), None);
+        assert_eq!((-2i16).encode(), [0xFF, 0xFE]);
+        assert_eq!(f32::decode(&1.5f32.encode()), Some(1.5));
+        assert_eq!(bool::decode(&[1]), Some(true));
+        assert_eq!(bool::decode(&[2]), None);
+        assert_eq!(Vec::<u8>::decode(&[7, 8]), Some(alloc::vec![7, 8]));
+    }
+
+    #[tokio::test]
+    async fn setter_stores_notifies_and_getter_reads_back() {
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&notified);
+        let field = Field::new(7u16).with_notifier(move |payload: Vec<u8>| {
+            log.lock().unwrap().push(payload);
+            async { Ok(1) }
+        });
+
+        assert_eq!(field.get().encode(), [0, 7]);
+        assert_eq!(
+            field.answer_set(request(&[0x12, 0x34])).await,
+            Ok(alloc::vec![0x12, 0x34])
+        );
+        assert_eq!(field.get(), 0x1234);
+        assert_eq!(
+            field.answer_set(request(&[1])).await,
+            Err(ReturnCode::MalformedMessage.into())
+        );
+        assert_eq!(field.get(), 0x1234, "malformed payload leaves the value");
+        assert_eq!(field.set(9).await.unwrap(), 1);
+        assert_eq!(
+            *notified.lock().unwrap(),
+            [alloc::vec![0x12, 0x34], alloc::vec![0, 9]]
+        );
+        assert_eq!(Field::new(true).set(false).await.unwrap(), 0, "no notifier");
+    }
+
+    #[test]
+    fn router_routes_only_the_given_methods() {
+        let field = Field::new(0u8);
+        let router = MethodRouter::new().field(&field, Some(0x0001), Some(0x0002));
+        assert_eq!(router.methods().collect::<Vec<_>>(), [0x0001, 0x0002]);
+        let router = MethodRouter::new().field(&field, Some(0x0001), None);
+        assert_eq!(router.methods().collect::<Vec<_>>(), [0x0001]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Initial-events registry behind
+//! [`EventPublisher::set_initial_event`](super::EventPublisher::set_initial_event).
+
+use heapless::Vec;
+
+use super::sticky_events::StickyKey;
+use crate::protocol::Header;
+use crate::traits::WireFormat;
+
+/// Length of the SOME/IP header leading each registered datagram.
+const HEADER_LEN: usize = 16;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_INITIAL_EVENTS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_INITIAL_EVENTS_CAP: usize = 8;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_INITIAL_EVENT_LEN: usize = 64;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_INITIAL_EVENT_LEN: usize = crate::UDP_BUFFER_SIZE;
+
+/// Maximum number of initial events one
+/// [`EventPublisher`](super::EventPublisher) can hold. Sized via
+/// `SIMPLE_SOMEIP_INITIAL_EVENTS_CAP`; defaults to 2 on bare-metal, 8
+/// otherwise.
+pub const INITIAL_EVENTS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_INITIAL_EVENTS_CAP"),
+    _DEFAULT_INITIAL_EVENTS_CAP,
+);
+
+/// Largest initial event, SOME/IP header included. Sized via
+/// `SIMPLE_SOMEIP_INITIAL_EVENT_LEN`; defaults to 64 bytes on bare-metal,
+/// [`UDP_BUFFER_SIZE`](crate::UDP_BUFFER_SIZE) otherwise.
+pub const INITIAL_EVENT_LEN: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_INITIAL_EVENT_LEN"),
+    _DEFAULT_INITIAL_EVENT_LEN,
+);
+
+/// Why [`InitialEvents::set`] refused an event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SetError {
+    /// [`INITIAL_EVENTS_CAP`] other events are registered.
+    Full,
+    /// Header and payload are longer than [`INITIAL_EVENT_LEN`].
+    TooLong,
+}
+
+/// The notification datagram registered for each event, sent to every
+/// new subscriber of its event group.
+#[derive(Debug)]
+pub(super) struct InitialEvents {
+    entries: Vec<(StickyKey, Vec<u8, INITIAL_EVENT_LEN>), INITIAL_EVENTS_CAP>,
+}
+
+impl InitialEvents {
+    pub(super) const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Register `header` followed by `payload` as the initial event
+    /// `key`, replacing the one registered before. On error the registry
+    /// is left as it was.
+    pub(super) fn set(
+        &mut self,
+        key: StickyKey,
+        header: &Header,
+        payload: &[u8],
+    ) -> Result<(), SetError> {
+        let mut value = Vec::new();
+        value
+            .resize(HEADER_LEN + payload.len(), 0)
+            .map_err(|_| SetError::TooLong)?;
+        header
+            .encode_to_slice(&mut value[..HEADER_LEN])
+            .map_err(|_| SetError::TooLong)?;
+        value[HEADER_LEN..].copy_from_slice(payload);
+        if let Some((_, old)) = self.entries.iter_mut().find(|(k, _)| *k == key) {
+            *old = value;
+            return Ok(());
+        }
+        self.entries.push((key, value)).map_err(|_| SetError::Full)
+    }
+
+    /// Forget the initial event `key`. Returns whether it was registered.
+    pub(super) fn remove(&mut self, key: StickyKey) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.len() != before
+    }
+
+    /// Copy the `index`-th initial event of the event group into `out`,
+    /// returning its length. `None` once `index` is past the last one.
+    /// A datagram that does not fit `out` yields `Some(0)`.
+    pub(super) fn copy_registered(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        index: usize,
+        out: &mut [u8],
+    ) -> Option<usize> {
+        let (_, value) = self
+            .entries
+            .iter()
+            .filter(|(k, _)| k.in_group(service_id, instance_id, event_group_id))
+            .nth(index)?;
+        let Some(out) = out.get_mut(..value.len()) else {
+            return Some(0);
+        };
+        out.copy_from_slice(value);
+        Some(value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn key(event_group_id: u16, event_id: u16) -> StickyKey {
+        StickyKey::new(0x5B, 1, event_group_id, event_id)
+    }
+
+    fn set(events: &mut InitialEvents, key: StickyKey, payload: &[u8]) -> Result<(), SetError> {
+        let header = Header::new_event(key.service, key.event, 0, 0x01, 0x01, payload.len());
+        events.set(key, &header, payload)
+    }
+
+    #[test]
+    fn events_are_registered_per_group_and_replaced() {
+        let mut events = InitialEvents::new();
+        let mut out = [0u8; 32];
+        assert_eq!(events.copy_registered(0x5B, 1, 1, 0, &mut out), None);
+
+        assert_eq!(set(&mut events, key(1, 0x8001), &[1]), Ok(()));
+        assert_eq!(set(&mut events, key(1, 0x8002), &[2, 2]), Ok(()));
+        assert_eq!(set(&mut events, key(2, 0x8003), &[3, 3, 3]), Ok(()));
+        assert_eq!(set(&mut events, key(1, 0x8001), &[4]), Ok(()), "replaced");
+        assert_eq!(events.copy_registered(0x5B, 1, 1, 0, &mut out), Some(17));
+        let (header, payload) = crate::protocol::HeaderView::parse(&out[..17]).unwrap();
+        assert_eq!(header.message_id().method_id(), 0x8001);
+        assert_eq!(header.payload_size(), 1);
+        assert_eq!(payload, [4]);
+        assert_eq!(events.copy_registered(0x5B, 1, 1, 1, &mut out), Some(18));
+        assert_eq!(events.copy_registered(0x5B, 1, 1, 2, &mut out), None);
+        assert_eq!(
+            events.copy_registered(0x5B, 1, 2, 0, &mut [0u8; 16]),
+            Some(0),
+            "too long for out"
+        );
+
+        assert_eq!(
+            set(&mut events, key(1, 0x8001), &[0; INITIAL_EVENT_LEN]),
+            Err(SetError::TooLong)
+        );
+        assert_eq!(
+            events.copy_registered(0x5B, 1, 1, 0, &mut out),
+            Some(17),
+            "a refused value keeps the old one"
+        );
+
+        assert!(events.remove(key(1, 0x8001)));
+        assert!(!events.remove(key(1, 0x8001)));
+        assert_eq!(events.copy_registered(0x5B, 1, 1, 0, &mut out), Some(18));
+    }
+
+    #[test]
+    fn registry_is_capped() {
+        let mut events = InitialEvents::new();
+        for event in 0..INITIAL_EVENTS_CAP {
+            let event = u16::try_from(event).unwrap();
+            assert_eq!(set(&mut events, key(1, event), &[0]), Ok(()));
+        }
+        assert_eq!(set(&mut events, key(2, 0x8000), &[0]), Err(SetError::Full));
+        assert_eq!(set(&mut events, key(1, 0), &[1]), Ok(()), "replacing fits");
+    }
+}
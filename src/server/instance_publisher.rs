@@ -0,0 +1,215 @@
+//! Publishing handles bound to one service instance, created with
+//! [`Server::instance_publisher`](super::Server::instance_publisher).
+
+use core::marker::PhantomData;
+use core::net::SocketAddrV4;
+use core::sync::atomic::AtomicU16;
+
+use heapless::Vec as HeaplessVec;
+
+use super::Error;
+use super::event_publisher::EventPublisher;
+use super::event_sender::EventSender;
+use super::subscription_manager::{SUBSCRIBERS_PER_GROUP, SubscriptionHandle};
+use crate::protocol::Message;
+use crate::traits::PayloadWireFormat;
+use crate::transport::{E2ERegistryHandle, SharedHandle, TransportSocket};
+
+/// Publishing handle for one service instance of a server that offers
+/// several, with its service and instance IDs and interface version fixed
+/// when it is created.
+///
+/// Subscriptions are kept per instance, so every publish reaches the
+/// subscribers of that instance's event group only, never those of a
+/// sibling instance of the same service. Raw events and the
+/// [`EventSender`]s made from the handle carry the instance's own major
+/// version as interface version, which may differ from the configured
+/// service's.
+///
+/// `P` is the [`EventPublisher`] type and `Hep` the handle it is shared
+/// through, as on [`Server`](super::Server).
+pub struct InstancePublisher<P, Hep> {
+    publisher: Hep,
+    service_id: u16,
+    instance_id: u16,
+    interface_version: u8,
+    /// `P` only appears in the bound `Hep: SharedHandle<P>`.
+    _publisher: PhantomData<fn() -> P>,
+}
+
+impl<P, Hep: Clone> Clone for InstancePublisher<P, Hep> {
+    fn clone(&self) -> Self {
+        Self {
+            publisher: self.publisher.clone(),
+            _publisher: PhantomData,
+            ..*self
+        }
+    }
+}
+
+impl<P, Hep> core::fmt::Debug for InstancePublisher<P, Hep> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InstancePublisher")
+            .field("service_id", &self.service_id)
+            .field("instance_id", &self.instance_id)
+            .field("interface_version", &self.interface_version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R, S, H, T, Hep> InstancePublisher<EventPublisher<R, S, H, T>, Hep>
+where
+    R: E2ERegistryHandle,
+    S: SubscriptionHandle,
+    T: TransportSocket + 'static,
+    H: SharedHandle<T>,
+    Hep: SharedHandle<EventPublisher<R, S, H, T>>,
+{
+    /// Bind `publisher` to `service_id`/`instance_id`, whose major version
+    /// is `interface_version`.
+    #[must_use]
+    pub const fn new(
+        publisher: Hep,
+        service_id: u16,
+        instance_id: u16,
+        interface_version: u8,
+    ) -> Self {
+        Self {
+            publisher,
+            service_id,
+            instance_id,
+            interface_version,
+            _publisher: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub const fn service_id(&self) -> u16 {
+        self.service_id
+    }
+
+    #[must_use]
+    pub const fn instance_id(&self) -> u16 {
+        self.instance_id
+    }
+
+    #[must_use]
+    pub const fn interface_version(&self) -> u8 {
+        self.interface_version
+    }
+
+    /// [`EventPublisher::publish_event_with_buffers`] to the subscribers
+    /// of `event_group_id` of this instance.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`EventPublisher::publish_event_with_buffers`].
+    pub async fn publish_event_with_buffers<Pl: PayloadWireFormat>(
+        &self,
+        event_group_id: u16,
+        message: &Message<Pl>,
+        msg_buf: &mut [u8],
+        protected_buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.publisher
+            .get()
+            .publish_event_with_buffers(
+                self.service_id,
+                self.instance_id,
+                event_group_id,
+                message,
+                msg_buf,
+                protected_buf,
+            )
+            .await
+    }
+
+    /// [`EventPublisher::publish_event`] to the subscribers of
+    /// `event_group_id` of this instance.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`EventPublisher::publish_event`].
+    #[cfg(feature = "_alloc")]
+    pub async fn publish_event<Pl: PayloadWireFormat>(
+        &self,
+        event_group_id: u16,
+        message: &Message<Pl>,
+    ) -> Result<usize, Error> {
+        self.publisher
+            .get()
+            .publish_event(self.service_id, self.instance_id, event_group_id, message)
+            .await
+    }
+
+    /// [`EventPublisher::publish_raw_event_with_buffers`] of `event_id` to
+    /// the subscribers of `event_group_id` of this instance, with the
+    /// instance's interface version.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`EventPublisher::publish_raw_event_with_buffers`].
+    pub async fn publish_raw_event_with_buffers(
+        &self,
+        event_group_id: u16,
+        event_id: u16,
+        request_id: u32,
+        payload: &[u8],
+        buf: &mut [u8],
+    ) -> Result<usize, Error> {
+        self.publisher
+            .get()
+            .publish_raw_event_with_buffers(
+                self.service_id,
+                self.instance_id,
+                event_group_id,
+                event_id,
+                request_id,
+                0x01,
+                self.interface_version,
+                payload,
+                buf,
+            )
+            .await
+    }
+
+    /// `true` if `event_group_id` of this instance has subscribers.
+    pub async fn has_subscribers(&self, event_group_id: u16) -> bool {
+        self.publisher
+            .get()
+            .has_subscribers(self.service_id, self.instance_id, event_group_id)
+            .await
+    }
+
+    /// The endpoints subscribed to `event_group_id` of this instance.
+    pub async fn subscriber_addresses(
+        &self,
+        event_group_id: u16,
+    ) -> HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP> {
+        self.publisher
+            .get()
+            .subscriber_addresses(self.service_id, self.instance_id, event_group_id)
+            .await
+    }
+
+    /// An [`EventSender`] for `event_id` in `event_group_id` of this
+    /// instance, with the instance's interface version and `session` as
+    /// its session counter.
+    #[must_use]
+    pub fn event_sender<C: SharedHandle<AtomicU16>>(
+        &self,
+        event_group_id: u16,
+        event_id: u16,
+        session: C,
+    ) -> EventSender<EventPublisher<R, S, H, T>, Hep, C> {
+        EventSender::new(
+            self.publisher.clone(),
+            self.service_id,
+            self.instance_id,
+            event_group_id,
+            event_id,
+            self.interface_version,
+            session,
+        )
+    }
+}
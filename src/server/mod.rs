@@ -6,23 +6,76 @@
 //! - Event group management
 //! - Request/Response handling
 
+#[cfg(feature = "_alloc")]
+mod authentication;
+mod delayed_finds;
 mod error;
 mod event_publisher;
+mod event_sender;
+#[cfg(all(feature = "_alloc", any(feature = "std", feature = "bare_metal")))]
+mod field;
+mod initial_events;
+mod instance_publisher;
+mod multicast_groups;
+mod no_subscriber;
+mod offer_schedule;
+mod ordered_groups;
+mod request_check;
+#[cfg(feature = "_alloc")]
+mod request_handler;
 mod runtime;
+mod sd_responder;
 mod sd_state;
 mod service_info;
+mod standby_subscribers;
+mod state_snapshot;
+mod sticky_events;
+mod subscription_events;
 mod subscription_manager;
+#[cfg(feature = "server-tokio")]
+mod task;
+mod tcp;
 
-pub use error::Error;
+#[cfg(feature = "_alloc")]
+pub use authentication::{Authenticator, Handshake, PeerAuthenticator};
+pub use delayed_finds::DELAYED_FINDS_CAP;
+pub use error::{ConfigError, Error};
 pub use event_publisher::EventPublisher;
+#[cfg(feature = "_alloc")]
+pub use event_publisher::{RECV_BUFFER_LEN, RECV_BUFFERS_CAP, SCRATCH_BUFFERS_CAP};
+pub use event_sender::EventSender;
+#[cfg(all(feature = "_alloc", any(feature = "std", feature = "bare_metal")))]
+pub use field::{Field, FieldCodec, FieldNotifier};
+pub use initial_events::{INITIAL_EVENT_LEN, INITIAL_EVENTS_CAP};
+pub use instance_publisher::InstancePublisher;
+pub use multicast_groups::MULTICAST_GROUPS_CAP;
+pub use no_subscriber::{
+    BUFFERED_EVENT_LEN, BUFFERED_EVENTS_CAP, NO_SUBSCRIBER_POLICIES_CAP, NoSubscriberPolicy,
+};
+pub use offer_schedule::{OfferSchedule, OfferSlot};
+pub use ordered_groups::{ORDERED_GROUPS_CAP, ORDERED_PUBLISHES_CAP};
+#[cfg(feature = "_alloc")]
+pub use request_handler::{MethodError, MethodRouter, Request, RequestHandler};
 pub use service_info::Subscriber;
 #[cfg(feature = "std")]
 pub use service_info::{EventGroupInfo, ServiceInfo};
+pub use standby_subscribers::STANDBY_SUBSCRIBERS_CAP;
+pub use state_snapshot::{OfferedService, ServerSnapshot};
+pub use sticky_events::{STICKY_EVENT_LEN, STICKY_EVENTS_CAP};
+pub use subscription_events::{
+    SUBSCRIPTION_EVENTS_CAP, SubscriptionEvent, SubscriptionEventKind, SubscriptionEvents,
+};
+pub use subscription_manager::{
+    ExpiredSubscribers, SubscribeError, SubscribeOutcome, SubscriptionHandle, SubscriptionManager,
+};
 #[cfg(feature = "bare_metal")]
 pub use subscription_manager::{StaticSubscriptionHandle, StaticSubscriptionStorage};
-pub use subscription_manager::{SubscribeError, SubscriptionHandle, SubscriptionManager};
+pub use tcp::TCP_CONNECTIONS_CAP;
 
+pub use sd_responder::{NewSubscriptions, SdResponder};
 pub use sd_state::SdStateManager;
+#[cfg(feature = "server-tokio")]
+pub use task::ServerTask;
 
 use core::sync::atomic::{AtomicBool, Ordering};
 
@@ -36,12 +89,12 @@ use crate::protocol::sd::{Entry, Flags, ServiceEntry};
 use crate::transport::SocketOptions;
 #[cfg(feature = "_alloc")]
 use crate::transport::WrappableSharedHandle;
-use crate::transport::{E2ERegistryHandle, SharedHandle, TransportFactory, TransportSocket};
+use crate::transport::{
+    E2ERegistryHandle, SharedHandle, StreamListener, TransportFactory, TransportSocket,
+};
 #[cfg(feature = "_alloc")]
 use alloc::sync::Arc;
-use core::net::Ipv4Addr;
-#[cfg(feature = "_alloc")]
-use core::net::SocketAddrV4;
+use core::net::{Ipv4Addr, SocketAddrV4};
 #[cfg(test)]
 use std::vec::Vec;
 
@@ -75,6 +128,7 @@ const _SERVER_ACCEPTED_OFFERS_CAP: usize = crate::from_env_or(
 
 /// Configuration for a SOME/IP service provider
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // independent switches, not a state machine
 pub struct ServerConfig {
     /// Local interface IP address
     pub interface: Ipv4Addr,
@@ -119,12 +173,234 @@ pub struct ServerConfig {
     /// events never reach a subscriber. Populate via [`Self::with_accepted_offer`];
     /// empty preserves single-service behaviour.
     pub accepted_offers: heapless::Vec<AcceptedOffer, { ServerConfig::ACCEPTED_OFFERS_CAP }>,
+    /// Bounds applied to every inbound datagram before it is handled.
+    /// Datagrams that exceed them are logged and dropped. Defaults to
+    /// [`DecodeLimits::default`](crate::protocol::DecodeLimits::default).
+    pub decode_limits: crate::protocol::DecodeLimits,
+    /// Peers and services that get a [`CompatibilityProfile`] other than
+    /// the strict default, the first matching rule winning. Empty by
+    /// default; set via [`Self::with_compatibility_rules`].
+    ///
+    /// [`CompatibilityProfile`]: crate::protocol::CompatibilityProfile
+    pub compatibility_rules: &'static [crate::protocol::CompatibilityRule],
+    /// How to answer a `FindService` for every service (service ID
+    /// `0xFFFF`). Defaults to [`WildcardFindPolicy::Answer`].
+    pub wildcard_find: WildcardFindPolicy,
+    /// How `FindService`s for this service are answered. Set per
+    /// `ServerConfig`, so services hosted side by side choose
+    /// independently. Defaults to [`FindAnswer::Unicast`].
+    pub find_answer: FindAnswer,
+    /// TCP port advertised next to `local_port` in `OfferService`
+    /// entries, for a server that also serves requests over TCP via
+    /// [`Server::serve_tcp_with_buffers`]. `None` (the default) offers
+    /// UDP only.
+    pub tcp_port: Option<u16>,
+    /// Background sweeps of the receive loop and of
+    /// [`Server::serve_tcp_with_buffers`]: closing idle TCP connections
+    /// and dropping stale SOME/IP-TP reassembly state. `None` (the
+    /// default) disables them. What they collect is read through
+    /// [`Server::janitor_stats`].
+    pub janitor: Option<crate::JanitorConfig>,
+    /// Whether notifications of this service carry session IDs. Defaults
+    /// to `true`; set to `false` (via [`Self::with_session_handling`]) to
+    /// interoperate with consumers whose stacks run without session
+    /// handling, and senders from `Server::event_sender` write session
+    /// ID `0` into every notification.
+    pub session_handling: bool,
+    /// Bounds of the SOME/IP-SD initial wait: the announcement loop
+    /// holds its first `OfferService` for a random delay between
+    /// `initial_delay_min` and `initial_delay_max`, so ECUs that power
+    /// up together do not offer in lockstep. Both default to zero (offer
+    /// right away); set them via [`Self::with_initial_delay`].
+    pub initial_delay_min: core::time::Duration,
+    /// Upper bound of the initial wait. See [`Self::initial_delay_min`].
+    pub initial_delay_max: core::time::Duration,
+    /// Minimum gap between this server's first `OfferService` and the
+    /// first SD message of every other server, or `find_service`, of the
+    /// process starting before it, beyond the initial wait. Spreads the
+    /// SD frames of many services starting at once on one ECU. Defaults
+    /// to zero (no spacing); set it via [`Self::with_startup_spacing`].
+    /// Needs a clock from [`Timer::now`].
+    pub startup_spacing: core::time::Duration,
+    /// Offers sent in the SOME/IP-SD repetition phase that follows the
+    /// first offer, the `n`-th one `repetitions_base_delay * 2^(n-1)`
+    /// after the one before it, so a service becomes known quickly after
+    /// start-up. Defaults to `0` (no repetition phase); set with
+    /// [`Self::with_repetitions`].
+    pub repetitions_max: u32,
+    /// Delay before the first repetition, doubling for each further one.
+    /// See [`Self::repetitions_max`]. Defaults to 30 ms.
+    pub repetitions_base_delay: core::time::Duration,
+    /// Delay between the offers of the main phase, once the repetition
+    /// phase is over. Defaults to one second. Zero sends no cyclic offers:
+    /// the service is then only offered during the repetition phase and in
+    /// answer to `FindService`s. Set with [`Self::with_cyclic_offer_delay`].
+    pub cyclic_offer_delay: core::time::Duration,
+    /// Whether the main phase offers the configured and the hosted
+    /// services one at a time, spread evenly over
+    /// [`Self::cyclic_offer_delay`], instead of all in one message at the
+    /// start of each cycle. Defaults to `false`; set with
+    /// [`Self::with_staggered_offers`]. See [`Server::offer_schedule`].
+    pub staggered_offers: bool,
+    /// Bounds of the SOME/IP-SD request-response delay: a `FindService`
+    /// received on the SD socket, where clients' multicast finds arrive,
+    /// is answered after a random delay between `find_response_delay_min`
+    /// and `find_response_delay_max`, so the providers of a service do
+    /// not all answer in the same instant. Only `FindService`s for an
+    /// offered service wait, at most [`DELAYED_FINDS_CAP`] at a time; the
+    /// receive loop keeps running meanwhile. Both default to zero (answer
+    /// right away); set them via [`Self::with_find_response_delay`].
+    pub find_response_delay_min: core::time::Duration,
+    /// Upper bound of the request-response delay. See
+    /// [`Self::find_response_delay_min`].
+    pub find_response_delay_max: core::time::Duration,
+    /// Whether the SD messages of this server set the unicast flag,
+    /// telling peers it can receive unicast SD messages. Defaults to
+    /// `true`; set to `false` (via [`Self::with_sd_unicast`]) for a
+    /// server that only takes part in SD over multicast.
+    pub sd_unicast: bool,
+    /// Event groups of this service whose events go to a multicast
+    /// address instead of to each subscriber. The address is announced
+    /// to subscribers in the `SubscribeAck`. Empty (the default) sends
+    /// every event by unicast; populate via
+    /// [`Self::with_event_group_multicast`].
+    pub event_group_multicast:
+        heapless::Vec<EventGroupMulticast, { ServerConfig::EVENT_GROUP_IDS_CAP }>,
+    /// `key=value` items sent in a configuration option with every
+    /// `OfferService`, read by clients from
+    /// [`OfferedEndpoint::configuration`](crate::OfferedEndpoint::configuration).
+    /// Empty (the default) sends no configuration option; set via
+    /// [`Self::with_configuration`].
+    pub configuration: crate::protocol::sd::ConfigurationString,
+    /// `(priority, weight)` sent in a load-balancing option with every
+    /// `OfferService`, for clients choosing between several providers of
+    /// the service: a lower priority value is preferred, and among equal
+    /// priorities the weight is this provider's relative share. `None`
+    /// (the default) sends no such option; set via
+    /// [`Self::with_load_balancing`].
+    pub load_balancing: Option<(u16, u16)>,
+    /// How the server's SD socket takes the SD port (30490). Defaults to
+    /// [`SdPortMode::Shared`]; set via [`Self::with_sd_port_mode`].
+    pub sd_port_mode: SdPortMode,
+}
+
+/// How a server binds the SD port (30490). See
+/// [`ServerConfig::sd_port_mode`].
+///
+/// When the port cannot be bound, the constructors fail with
+/// [`Error::SdPortUnavailable`] instead of running a server whose offers
+/// and subscriptions go nowhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SdPortMode {
+    /// Bind with `SO_REUSEADDR` and `SO_REUSEPORT`, sharing the port with
+    /// other SD stacks on the host that set both, such as other servers
+    /// and clients of this crate.
+    #[default]
+    Shared,
+    /// Bind without reuse options, failing if anything else holds the
+    /// port. For hosts where this server must be the only SD endpoint.
+    Exclusive,
+    /// Do not bind the port: another component owns service discovery.
+    /// The server is constructed passive, as by `Server::new_passive`,
+    /// and leaves offers and subscriptions to that component.
+    External,
+}
+
+/// An event group distributed over multicast. See
+/// [`ServerConfig::event_group_multicast`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventGroupMulticast {
+    /// Event-group ID.
+    pub event_group_id: u16,
+    /// Multicast address and port the group's events are sent to.
+    pub group: SocketAddrV4,
+}
+
+/// How a server answers a `FindService` it decided to answer. See
+/// [`ServerConfig::find_answer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FindAnswer {
+    /// Send a unicast `OfferService` to the sender right away.
+    #[default]
+    Unicast,
+    /// Send nothing right away; the next cyclic multicast `OfferService`
+    /// answers. For deployments where unicast offers are disabled.
+    /// Relies on someone driving the offer cycle: the server's own
+    /// announcement loop, or the external component that does so when
+    /// [`ServerConfig::announce`] is `false`.
+    NextMulticastOffer,
+}
+
+/// How a server answers a wildcard `FindService` (service ID `0xFFFF`),
+/// i.e. a peer scanning for every service on the network. See
+/// [`ServerConfig::wildcard_find`].
+///
+/// `FindService`s naming the server's own service ID are always answered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WildcardFindPolicy {
+    /// Answer with a unicast offer, like a `FindService` for our service.
+    #[default]
+    Answer,
+    /// Do not answer; the peer learns about the service from the cyclic
+    /// multicast offers only.
+    Ignore,
+    /// Answer only if the `FindService` has the SD unicast flag set, i.e.
+    /// the sender declared it can receive unicast SD messages.
+    AnswerIfUnicast,
+}
+
+/// Whole seconds of `ttl`, saturated to
+/// [`TTL_INFINITE`](crate::protocol::sd::TTL_INFINITE).
+fn saturate_ttl(ttl: core::time::Duration) -> u32 {
+    u32::try_from(ttl.as_secs())
+        .unwrap_or(u32::MAX)
+        .min(crate::protocol::sd::TTL_INFINITE)
+}
+
+/// Reject the service and instance IDs SOME/IP reserves. See
+/// [`ConfigError::ReservedServiceId`] and
+/// [`ConfigError::ReservedInstanceId`].
+fn validate_service_instance(service_id: u16, instance_id: u16) -> Result<(), ConfigError> {
+    if matches!(service_id, 0x0000 | 0xFFFE | 0xFFFF) {
+        return Err(ConfigError::ReservedServiceId(service_id));
+    }
+    if matches!(instance_id, 0x0000 | 0xFFFF) {
+        return Err(ConfigError::ReservedInstanceId {
+            service_id,
+            instance_id,
+        });
+    }
+    Ok(())
+}
+
+/// Hand the [`ServerConfig::event_group_multicast`] addresses to the
+/// server's `publisher`.
+fn register_multicast_groups<R, S, H, T>(
+    config: &ServerConfig,
+    publisher: &EventPublisher<R, S, H, T>,
+) -> Result<(), Error>
+where
+    R: E2ERegistryHandle,
+    S: SubscriptionHandle,
+    T: TransportSocket + 'static,
+    H: SharedHandle<T>,
+{
+    for entry in &config.event_group_multicast {
+        publisher.set_multicast_group(
+            config.service_id,
+            config.instance_id,
+            entry.event_group_id,
+            Some(entry.group),
+        )?;
+    }
+    Ok(())
 }
 
 /// A `(service, instance, event_group)` tuple a receive loop will accept
 /// `SubscribeEventGroup` for in addition to its primary service. See
 /// [`ServerConfig::accepted_offers`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AcceptedOffer {
     /// Offered service ID.
     pub service_id: u16,
@@ -173,6 +449,24 @@ impl ServerConfig {
     /// | `minor_version` | `0` | [`Self::with_minor_version`] |
     /// | `ttl` | 3 seconds (typical for SOME/IP) | [`Self::with_ttl`] |
     /// | `event_group_ids` | empty (any group accepted) | [`Self::with_event_group`] |
+    /// | `decode_limits` | `DecodeLimits::default()` | [`Self::with_decode_limits`] |
+    /// | `compatibility_rules` | empty (strict for everyone) | [`Self::with_compatibility_rules`] |
+    /// | `wildcard_find` | [`WildcardFindPolicy::Answer`] | [`Self::with_wildcard_find`] |
+    /// | `find_answer` | [`FindAnswer::Unicast`] | [`Self::with_find_answer`] |
+    /// | `tcp_port` | `None` (UDP only) | [`Self::with_tcp_port`] |
+    /// | `janitor` | `None` (no sweeps) | [`Self::with_janitor`] |
+    /// | `session_handling` | `true` | [`Self::with_session_handling`] |
+    /// | `initial_delay_min`, `initial_delay_max` | zero (offer right away) | [`Self::with_initial_delay`] |
+    /// | `startup_spacing` | zero (no spacing) | [`Self::with_startup_spacing`] |
+    /// | `repetitions_max`, `repetitions_base_delay` | `0`, 30 ms (no repetition phase) | [`Self::with_repetitions`] |
+    /// | `cyclic_offer_delay` | 1 second | [`Self::with_cyclic_offer_delay`] |
+    /// | `staggered_offers` | `false` (one message per cycle) | [`Self::with_staggered_offers`] |
+    /// | `find_response_delay_min`, `find_response_delay_max` | zero (answer right away) | [`Self::with_find_response_delay`] |
+    /// | `sd_unicast` | `true` | [`Self::with_sd_unicast`] |
+    /// | `event_group_multicast` | empty (unicast only) | [`Self::with_event_group_multicast`] |
+    /// | `configuration` | empty (no configuration option) | [`Self::with_configuration`] |
+    /// | `load_balancing` | `None` (no load-balancing option) | [`Self::with_load_balancing`] |
+    /// | `sd_port_mode` | [`SdPortMode::Shared`] | [`Self::with_sd_port_mode`] |
     ///
     /// Production deployments almost always need a specific interface
     /// and port — `0.0.0.0` lets the kernel pick a binding that may
@@ -205,6 +499,27 @@ impl ServerConfig {
             event_group_ids: heapless::Vec::new(),
             announce: true,
             accepted_offers: heapless::Vec::new(),
+            decode_limits: crate::protocol::DecodeLimits::default(),
+            compatibility_rules: &[],
+            wildcard_find: WildcardFindPolicy::Answer,
+            find_answer: FindAnswer::Unicast,
+            tcp_port: None,
+            janitor: None,
+            session_handling: true,
+            initial_delay_min: core::time::Duration::ZERO,
+            initial_delay_max: core::time::Duration::ZERO,
+            startup_spacing: core::time::Duration::ZERO,
+            repetitions_max: 0,
+            repetitions_base_delay: core::time::Duration::from_millis(30),
+            cyclic_offer_delay: core::time::Duration::from_secs(1),
+            staggered_offers: false,
+            find_response_delay_min: core::time::Duration::ZERO,
+            find_response_delay_max: core::time::Duration::ZERO,
+            sd_unicast: true,
+            event_group_multicast: heapless::Vec::new(),
+            configuration: crate::protocol::sd::ConfigurationString::new(),
+            load_balancing: None,
+            sd_port_mode: SdPortMode::Shared,
         }
     }
 
@@ -299,7 +614,9 @@ impl ServerConfig {
     }
 
     /// Returns `true` if `(service_id, instance_id, major_version,
-    /// event_group_id)` is registered in [`Self::accepted_offers`].
+    /// event_group_id)` is registered in [`Self::accepted_offers`]. A
+    /// `major_version` of [`ANY_MAJOR_VERSION`](crate::protocol::sd::ANY_MAJOR_VERSION) matches any
+    /// registered major version.
     #[must_use]
     pub fn accepts_offer(
         &self,
@@ -308,14 +625,73 @@ impl ServerConfig {
         major_version: u8,
         event_group_id: u16,
     ) -> bool {
-        self.accepted_offers.iter().any(|o| {
+        self.accepted_offer(service_id, instance_id, major_version, event_group_id)
+            .is_some()
+    }
+
+    /// The [`AcceptedOffer`] matching `(service_id, instance_id,
+    /// major_version, event_group_id)`, if any. Same matching rules as
+    /// [`Self::accepts_offer`]; the returned entry carries the concrete
+    /// major version to answer a wildcard request with.
+    #[must_use]
+    pub fn accepted_offer(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        event_group_id: u16,
+    ) -> Option<&AcceptedOffer> {
+        self.accepted_offers.iter().find(|o| {
             o.service_id == service_id
                 && o.instance_id == instance_id
-                && o.major_version == major_version
+                && crate::protocol::sd::major_version_matches(o.major_version, major_version)
                 && o.event_group_id == event_group_id
         })
     }
 
+    /// Check the configuration for combinations that would bind or
+    /// announce a service nobody can reach: a multicast
+    /// [`Self::interface`], an announced port of `0`, a [`Self::ttl`] of
+    /// `0`, or a reserved service or instance ID here or in
+    /// [`Self::accepted_offers`].
+    ///
+    /// The server constructors call this after back-filling a
+    /// [`Self::local_port`] of `0` from the bound socket, so an ephemeral
+    /// unicast port is only rejected when validating a config that has
+    /// not been bound yet. Passive servers and servers with
+    /// [`Self::announce`] off announce nothing and may use port `0`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`ConfigError`] found.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        use crate::protocol::sd::TransportProtocol;
+
+        if self.interface.is_multicast() {
+            return Err(ConfigError::MulticastInterface(self.interface));
+        }
+        if self.ttl == 0 {
+            return Err(ConfigError::ZeroTtl);
+        }
+        validate_service_instance(self.service_id, self.instance_id)?;
+        for offer in &self.accepted_offers {
+            validate_service_instance(offer.service_id, offer.instance_id)?;
+        }
+        if self.announce && self.sd_port_mode != SdPortMode::External {
+            if self.local_port == 0 {
+                return Err(ConfigError::AnnouncedPortZero {
+                    protocol: TransportProtocol::Udp,
+                });
+            }
+            if self.tcp_port == Some(0) {
+                return Err(ConfigError::AnnouncedPortZero {
+                    protocol: TransportProtocol::Tcp,
+                });
+            }
+        }
+        Ok(())
+    }
+
     // ── Fluent builder ───────────────────────────────────────────────
     //
     // Each `with_*` setter consumes and returns `self` so callers can
@@ -342,15 +718,15 @@ impl ServerConfig {
     /// Set the SD announcement TTL. Defaults to 3 seconds from
     /// [`Self::new`] (typical for SOME/IP).
     ///
-    /// The SOME/IP-SD wire format encodes TTL as `u32` whole seconds;
+    /// The SOME/IP-SD wire format encodes TTL as 24-bit whole seconds;
     /// sub-second precision in the supplied `Duration` is truncated
-    /// (rounded down). Durations exceeding `u32::MAX` seconds (~136
-    /// years) saturate to `u32::MAX`. The reserved special value
-    /// `0xFFFFFF` ("until next reboot") can be requested by passing
-    /// `Duration::from_secs(0xFFFFFF)`.
+    /// (rounded down). The largest value,
+    /// [`TTL_INFINITE`](crate::protocol::sd::TTL_INFINITE) (`0xFFFFFF`,
+    /// ~194 days), means "until explicitly stopped": peers never expire
+    /// the offer. Longer durations saturate to it.
     #[must_use]
     pub fn with_ttl(mut self, ttl: core::time::Duration) -> Self {
-        self.ttl = u32::try_from(ttl.as_secs()).unwrap_or(u32::MAX);
+        self.ttl = saturate_ttl(ttl);
         self
     }
 
@@ -390,6 +766,67 @@ impl ServerConfig {
         }
     }
 
+    /// Send the events of `event_group_id` to the multicast address
+    /// `group` rather than to each subscriber, and announce `group` in
+    /// the `IPv4 Multicast` option of every `SubscribeAck` for the
+    /// group. Setting a group again replaces its address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than [`Self::EVENT_GROUP_IDS_CAP`] groups have
+    /// been given a multicast address. Use
+    /// [`Self::try_with_event_group_multicast`] for the fallible variant.
+    #[must_use]
+    pub fn with_event_group_multicast(self, event_group_id: u16, group: SocketAddrV4) -> Self {
+        self.try_with_event_group_multicast(event_group_id, group)
+            .unwrap_or_else(|_| panic!("event_group_multicast capacity exceeded"))
+    }
+
+    /// Fallible counterpart to [`Self::with_event_group_multicast`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the unmodified config (in `Err`) if registering would
+    /// exceed [`Self::EVENT_GROUP_IDS_CAP`].
+    #[allow(clippy::result_large_err)]
+    #[must_use = "the returned `Result` carries the (possibly-modified) config — drop is silent"]
+    pub fn try_with_event_group_multicast(
+        mut self,
+        event_group_id: u16,
+        group: SocketAddrV4,
+    ) -> Result<Self, Self> {
+        if let Some(entry) = self
+            .event_group_multicast
+            .iter_mut()
+            .find(|entry| entry.event_group_id == event_group_id)
+        {
+            entry.group = group;
+            return Ok(self);
+        }
+        if self
+            .event_group_multicast
+            .push(EventGroupMulticast {
+                event_group_id,
+                group,
+            })
+            .is_ok()
+        {
+            Ok(self)
+        } else {
+            Err(self)
+        }
+    }
+
+    /// The multicast address the events of `event_group_id` are sent
+    /// to, if any. See [`Self::with_event_group_multicast`].
+    #[must_use]
+    pub fn event_group_multicast(&self, event_group_id: u16) -> Option<SocketAddrV4> {
+        self.event_group_multicast
+            .iter()
+            .find(|entry| entry.event_group_id == event_group_id)
+            .map(|entry| entry.group)
+    }
+
     /// Set whether the run-future drives the SD `OfferService`
     /// announcement loop. Defaults to `true` from [`Self::new`].
     ///
@@ -403,6 +840,212 @@ impl ServerConfig {
         self.announce = announce;
         self
     }
+
+    /// Set the [`DecodeLimits`](crate::protocol::DecodeLimits) applied to
+    /// inbound datagrams. Defaults to `DecodeLimits::default()` from
+    /// [`Self::new`].
+    #[must_use]
+    pub fn with_decode_limits(mut self, decode_limits: crate::protocol::DecodeLimits) -> Self {
+        self.decode_limits = decode_limits;
+        self
+    }
+
+    /// Set the [`Self::compatibility_rules`] selecting a
+    /// [`CompatibilityProfile`](crate::protocol::CompatibilityProfile) for
+    /// legacy peers and services.
+    #[must_use]
+    pub fn with_compatibility_rules(
+        mut self,
+        compatibility_rules: &'static [crate::protocol::CompatibilityRule],
+    ) -> Self {
+        self.compatibility_rules = compatibility_rules;
+        self
+    }
+
+    /// The [`CompatibilityProfile`](crate::protocol::CompatibilityProfile)
+    /// of `service_id` of `peer` under [`Self::compatibility_rules`].
+    #[must_use]
+    pub fn compatibility_profile(
+        &self,
+        peer: core::net::IpAddr,
+        service_id: u16,
+    ) -> crate::protocol::CompatibilityProfile {
+        crate::protocol::CompatibilityRule::profile_for(self.compatibility_rules, peer, service_id)
+    }
+
+    /// Set how wildcard `FindService`s are answered. Defaults to
+    /// [`WildcardFindPolicy::Answer`] from [`Self::new`].
+    #[must_use]
+    pub fn with_wildcard_find(mut self, wildcard_find: WildcardFindPolicy) -> Self {
+        self.wildcard_find = wildcard_find;
+        self
+    }
+
+    /// Set how `FindService`s for this service are answered. Defaults to
+    /// [`FindAnswer::Unicast`] from [`Self::new`].
+    #[must_use]
+    pub fn with_find_answer(mut self, find_answer: FindAnswer) -> Self {
+        self.find_answer = find_answer;
+        self
+    }
+
+    /// Advertise a TCP endpoint on `tcp_port` in this service's offers.
+    /// The application binds a listener on `interface:tcp_port` and
+    /// drives it with [`Server::serve_tcp_with_buffers`]; an ephemeral
+    /// port cannot be offered.
+    #[must_use]
+    pub fn with_tcp_port(mut self, tcp_port: u16) -> Self {
+        self.tcp_port = Some(tcp_port);
+        self
+    }
+
+    /// Send `configuration` with this service's offers. See
+    /// [`Self::configuration`].
+    #[must_use]
+    pub fn with_configuration(
+        mut self,
+        configuration: crate::protocol::sd::ConfigurationString,
+    ) -> Self {
+        self.configuration = configuration;
+        self
+    }
+
+    /// Send a load-balancing option with `priority` and `weight` in this
+    /// service's offers. See [`Self::load_balancing`].
+    #[must_use]
+    pub fn with_load_balancing(mut self, priority: u16, weight: u16) -> Self {
+        self.load_balancing = Some((priority, weight));
+        self
+    }
+
+    /// Set how the SD port is bound. See [`Self::sd_port_mode`].
+    #[must_use]
+    pub fn with_sd_port_mode(mut self, sd_port_mode: SdPortMode) -> Self {
+        self.sd_port_mode = sd_port_mode;
+        self
+    }
+
+    /// Enable the janitor with `janitor`'s intervals. See
+    /// [`Self::janitor`].
+    #[must_use]
+    pub fn with_janitor(mut self, janitor: crate::JanitorConfig) -> Self {
+        self.janitor = Some(janitor);
+        self
+    }
+
+    /// Enable or disable session handling for notifications. See
+    /// [`Self::session_handling`].
+    #[must_use]
+    pub fn with_session_handling(mut self, session_handling: bool) -> Self {
+        self.session_handling = session_handling;
+        self
+    }
+
+    /// Wait a random delay between `min` and `max` before the first
+    /// `OfferService`. See [`Self::initial_delay_min`]; a `max` below
+    /// `min` waits exactly `min`.
+    #[must_use]
+    pub fn with_initial_delay(
+        mut self,
+        min: core::time::Duration,
+        max: core::time::Duration,
+    ) -> Self {
+        self.initial_delay_min = min;
+        self.initial_delay_max = max;
+        self
+    }
+
+    /// Hold the first `OfferService` until at least `spacing` after the
+    /// first SD message of every server or search of the process that
+    /// started before. See [`Self::startup_spacing`].
+    #[must_use]
+    pub fn with_startup_spacing(mut self, spacing: core::time::Duration) -> Self {
+        self.startup_spacing = spacing;
+        self
+    }
+
+    /// Follow the first offer with `max` repetitions, the first after
+    /// `base_delay` and each further one after twice the previous delay.
+    /// See [`Self::repetitions_max`].
+    #[must_use]
+    pub fn with_repetitions(mut self, max: u32, base_delay: core::time::Duration) -> Self {
+        self.repetitions_max = max;
+        self.repetitions_base_delay = base_delay;
+        self
+    }
+
+    /// Set the delay between the offers of the main phase. See
+    /// [`Self::cyclic_offer_delay`].
+    #[must_use]
+    pub fn with_cyclic_offer_delay(mut self, delay: core::time::Duration) -> Self {
+        self.cyclic_offer_delay = delay;
+        self
+    }
+
+    /// Set whether the main-phase offers are staggered. See
+    /// [`Self::staggered_offers`].
+    #[must_use]
+    pub fn with_staggered_offers(mut self, staggered: bool) -> Self {
+        self.staggered_offers = staggered;
+        self
+    }
+
+    /// Answer `FindService`s from the SD multicast group after a random
+    /// delay between `min` and `max`. See
+    /// [`Self::find_response_delay_min`]; a `max` below `min` waits
+    /// exactly `min`.
+    #[must_use]
+    pub fn with_find_response_delay(
+        mut self,
+        min: core::time::Duration,
+        max: core::time::Duration,
+    ) -> Self {
+        self.find_response_delay_min = min;
+        self.find_response_delay_max = max;
+        self
+    }
+
+    /// Set whether SD messages carry the unicast flag. See
+    /// [`Self::sd_unicast`].
+    #[must_use]
+    pub fn with_sd_unicast(mut self, sd_unicast: bool) -> Self {
+        self.sd_unicast = sd_unicast;
+        self
+    }
+
+    /// The flags byte of this server's SD messages.
+    fn sd_flags(&self, reboot_flag: crate::protocol::sd::RebootFlag) -> crate::protocol::sd::Flags {
+        crate::protocol::sd::Flags::new(reboot_flag.into(), self.sd_unicast)
+    }
+
+    /// The options of this service's offers: the UDP endpoint, then the
+    /// TCP one if [`Self::tcp_port`] is set, then the
+    /// [`Self::configuration`] if it is not empty and the
+    /// [`Self::load_balancing`] if set.
+    fn endpoint_options(&self) -> heapless::Vec<crate::protocol::sd::Options, 4> {
+        use crate::protocol::sd::{Options, TransportProtocol};
+
+        let mut options = heapless::Vec::new();
+        let _ = options.push(Options::IpV4Endpoint {
+            ip: self.interface,
+            port: self.local_port,
+            protocol: TransportProtocol::Udp,
+        });
+        if let Some(port) = self.tcp_port {
+            let _ = options.push(Options::IpV4Endpoint {
+                ip: self.interface,
+                port,
+                protocol: TransportProtocol::Tcp,
+            });
+        }
+        if !self.configuration.is_empty() {
+            let _ = options.push(Options::configuration(&self.configuration));
+        }
+        if let Some((priority, weight)) = self.load_balancing {
+            let _ = options.push(Options::LoadBalancing { priority, weight });
+        }
+        options
+    }
 }
 
 /// Bundle of pluggable infrastructure passed to `Server::new_with_deps`.
@@ -433,8 +1076,11 @@ where
     pub subscriptions: Sub,
     /// Optional `(callback, ctx)` pair invoked from the server's receive
     /// loop for every non-SD **unicast** datagram (method requests /
-    /// fire-and-forget calls to offered services). `None` reproduces the
-    /// historical "non-SD ignored" behavior. The callback receives the
+    /// fire-and-forget calls to offered services). A request for a service
+    /// the server does not offer, or with the wrong protocol or interface
+    /// version, is answered with an `ERROR` instead of reaching the
+    /// callback. `None` answers every `REQUEST` with an `ERROR`
+    /// (`UnknownMethod` if nothing else is wrong). The callback receives the
     /// opaque `ctx` word back verbatim, plus the full raw datagram bytes
     /// and the source `SocketAddrV4`; the consumer is responsible for
     /// re-parsing the SOME/IP header and any E2E check.
@@ -566,6 +1212,25 @@ where
         self.non_sd_observer = observer;
         self
     }
+
+    /// Wrap the `factory` so every datagram the server's sockets send or
+    /// receive is reported to `tap`, timestamped by the configured
+    /// `timer`. See [`TappedFactory`](crate::tap::TappedFactory).
+    pub fn with_tap<T: crate::tap::DatagramTap>(
+        self,
+        tap: T,
+    ) -> ServerDeps<crate::tap::TappedFactory<F, T, Tm>, Tm, R, Sub>
+    where
+        Tm: Clone,
+    {
+        ServerDeps {
+            factory: crate::tap::TappedFactory::new(self.factory, tap, self.timer.clone()),
+            timer: self.timer,
+            e2e_registry: self.e2e_registry,
+            subscriptions: self.subscriptions,
+            non_sd_observer: self.non_sd_observer,
+        }
+    }
 }
 
 /// Post-construction accessor bundle returned from `Server::new` (and
@@ -826,6 +1491,16 @@ pub type NonSdRequestCallback = fn(
     response_out: &mut [u8],
 ) -> i32;
 
+/// [`EventSender`] returned by [`Server::event_sender`], with an
+/// `Arc`-shared session counter.
+#[cfg(feature = "_alloc")]
+type AllocEventSender<R, Sub, H, T, Hep> =
+    EventSender<EventPublisher<R, Sub, H, T>, Hep, Arc<core::sync::atomic::AtomicU16>>;
+
+/// [`InstancePublisher`] returned by [`Server::instance_publisher`].
+type ServerInstancePublisher<R, Sub, H, T, Hep> =
+    InstancePublisher<EventPublisher<R, Sub, H, T>, Hep>;
+
 #[cfg(feature = "_alloc")]
 type StartedLatch = Arc<AtomicBool>;
 #[cfg(not(feature = "_alloc"))]
@@ -885,6 +1560,8 @@ impl
     ///
     /// Returns an error if binding the unicast or SD socket fails, or if joining the
     /// SD multicast group fails.
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub async fn new(
         config: ServerConfig,
     ) -> Result<
@@ -922,6 +1599,8 @@ impl
     ///
     /// Returns an error if binding the unicast or SD socket fails, or if joining the
     /// SD multicast group fails.
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub async fn new_with_loopback(
         config: ServerConfig,
         multicast_loopback: bool,
@@ -964,6 +1643,8 @@ impl
     /// # Errors
     ///
     /// Returns an error if binding either socket fails.
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub async fn new_passive(
         config: ServerConfig,
     ) -> Result<
@@ -1016,6 +1697,8 @@ where
     /// Returns an error if binding the unicast or SD socket via
     /// [`TransportFactory::bind`] fails, or if joining the SD multicast
     /// group fails.
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub async fn new_with_deps(
         deps: ServerDeps<F, Tm, R, Sub>,
         mut config: ServerConfig,
@@ -1048,6 +1731,7 @@ where
         // ephemeral port. Back-fill the config so SD offers and event
         // publishers advertise the actual bound port instead of 0.
         config.local_port = bound_port;
+        config.validate()?;
         crate::log::info!(
             "Server bound to {}:{} for service 0x{:04X}",
             config.interface,
@@ -1055,28 +1739,53 @@ where
             config.service_id
         );
 
-        // Bind SD socket for sending/receiving SD messages (must use SD port 30490).
-        let mut sd_opts = SocketOptions::new();
-        sd_opts.reuse_address = true;
-        sd_opts.reuse_port = true;
-        sd_opts.multicast_if_v4 = Some(config.interface);
-        sd_opts.multicast_loop_v4 = Some(multicast_loopback);
-        let sd_addr = SocketAddrV4::new(config.interface, sd::MULTICAST_PORT);
-        let sd_raw = factory.bind(sd_addr, &sd_opts).await?;
-        sd_raw.join_multicast_v4(sd::MULTICAST_IP, config.interface)?;
-        let sd_socket: H = H::wrap(sd_raw);
-        crate::log::info!(
-            "Server SD socket bound to {} (expected port {}), joined multicast {}",
-            sd_addr,
-            sd::MULTICAST_PORT,
-            sd::MULTICAST_IP
-        );
+        let is_passive = config.sd_port_mode == SdPortMode::External;
+        let sd_socket: H = if is_passive {
+            // Another component owns the SD port: placeholder socket as in
+            // `new_passive_with_deps`.
+            let sd_placeholder_addr = SocketAddrV4::new(config.interface, 0);
+            crate::log::info!(
+                "SD port owned externally, server for service 0x{:04X} is passive",
+                config.service_id
+            );
+            H::wrap(
+                factory
+                    .bind(sd_placeholder_addr, &SocketOptions::new())
+                    .await?,
+            )
+        } else {
+            // Bind SD socket for sending/receiving SD messages (must use SD port 30490).
+            let mut sd_opts = SocketOptions::new();
+            sd_opts.reuse_address = config.sd_port_mode == SdPortMode::Shared;
+            sd_opts.reuse_port = config.sd_port_mode == SdPortMode::Shared;
+            sd_opts.multicast_if_v4 = Some(config.interface);
+            sd_opts.multicast_loop_v4 = Some(multicast_loopback);
+            let sd_addr = SocketAddrV4::new(config.interface, sd::MULTICAST_PORT);
+            let unavailable = |cause| Error::SdPortUnavailable {
+                addr: sd_addr,
+                mode: config.sd_port_mode,
+                cause,
+            };
+            let sd_raw = factory.bind(sd_addr, &sd_opts).await.map_err(unavailable)?;
+            let bound = sd_raw.local_addr()?;
+            if bound.port() != sd::MULTICAST_PORT {
+                return Err(unavailable(crate::transport::TransportError::AddressInUse));
+            }
+            sd_raw.join_multicast_v4(sd::MULTICAST_IP, config.interface)?;
+            crate::log::info!(
+                "Server SD socket bound to {}, joined multicast {}",
+                bound,
+                sd::MULTICAST_IP
+            );
+            H::wrap(sd_raw)
+        };
 
         let publisher = Hep::wrap(EventPublisher::new(
             subscriptions.clone(),
             unicast_socket.clone(),
             e2e_registry.clone(),
         ));
+        register_multicast_groups(&config, publisher.get())?;
 
         let server = Self {
             config,
@@ -1088,14 +1797,14 @@ where
             e2e_registry,
             factory,
             timer,
-            is_passive: false,
+            is_passive,
             started: Arc::new(AtomicBool::new(false)),
             non_sd_observer: deps_non_sd_observer,
         };
         let handles = ServerHandles {
             publisher: server.publisher(),
         };
-        let run = server.run_inner();
+        let run = server.run_inner(core::future::pending());
         Ok((server, handles, run))
     }
 
@@ -1110,6 +1819,8 @@ where
     /// # Errors
     ///
     /// Returns an error if binding either socket fails.
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub async fn new_passive_with_deps(
         deps: ServerDeps<F, Tm, R, Sub>,
         mut config: ServerConfig,
@@ -1136,6 +1847,7 @@ where
         let unicast_socket: H = H::wrap(unicast_raw);
         // Back-fill the actual bound port if the caller passed 0.
         config.local_port = bound_port;
+        config.validate()?;
         crate::log::info!(
             "Passive server bound to {}:{} for service 0x{:04X}",
             config.interface,
@@ -1161,6 +1873,7 @@ where
             unicast_socket.clone(),
             e2e_registry.clone(),
         ));
+        register_multicast_groups(&config, publisher.get())?;
 
         let server = Self {
             config,
@@ -1179,7 +1892,7 @@ where
         let handles = ServerHandles {
             publisher: server.publisher(),
         };
-        let run = server.run_inner();
+        let run = server.run_inner(core::future::pending());
         Ok((server, handles, run))
     }
 }
@@ -1223,6 +1936,8 @@ where
     /// fails on the underlying transport, or
     /// [`Error::InvalidUsage`] if `config.local_port` is non-zero
     /// and does not equal the unicast socket's bound port.
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub fn new_with_handles(
         deps: ServerStorage<F, Tm, R, Sub, H, Hsd, Hep>,
         mut config: ServerConfig,
@@ -1240,6 +1955,7 @@ where
             );
             return Err(Error::InvalidUsage("new_with_handles_local_port_mismatch"));
         }
+        config.validate()?;
         crate::log::info!(
             "Server (handles) bound to {}:{} for service 0x{:04X}",
             config.interface,
@@ -1247,6 +1963,8 @@ where
             config.service_id
         );
 
+        register_multicast_groups(&config, deps.publisher.get())?;
+
         Ok(Self {
             config,
             unicast_socket: deps.unicast_socket,
@@ -1287,6 +2005,8 @@ where
     /// and does not equal the unicast socket's bound port (same
     /// back-fill-only-on-zero discipline as
     /// [`Self::new_with_handles`]).
+    /// Returns [`Error::InvalidConfig`] if `config` fails
+    /// [`ServerConfig::validate`].
     pub fn new_passive_with_handles(
         deps: ServerStorage<F, Tm, R, Sub, H, Hsd, Hep>,
         mut config: ServerConfig,
@@ -1306,6 +2026,7 @@ where
                 "new_passive_with_handles_local_port_mismatch",
             ));
         }
+        config.validate()?;
         crate::log::info!(
             "Passive server (handles) bound to {}:{} for service 0x{:04X}",
             config.interface,
@@ -1313,6 +2034,8 @@ where
             config.service_id
         );
 
+        register_multicast_groups(&config, deps.publisher.get())?;
+
         Ok(Self {
             config,
             unicast_socket: deps.unicast_socket,
@@ -1341,6 +2064,62 @@ where
         self.publisher.clone()
     }
 
+    /// An [`InstancePublisher`] for `service_id`/`instance_id`: the
+    /// configured service instance or one added with `add_service`.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidUsage`]`("unknown_service_instance")` if the server
+    /// offers no such service instance.
+    pub fn instance_publisher(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+    ) -> Result<ServerInstancePublisher<R, Sub, H, F::Socket, Hep>, Error> {
+        let major_version = self
+            .sd_state
+            .get()
+            .instance_major_version(&self.config, service_id, instance_id)
+            .ok_or(Error::InvalidUsage("unknown_service_instance"))?;
+        Ok(InstancePublisher::new(
+            self.publisher.clone(),
+            service_id,
+            instance_id,
+            major_version,
+        ))
+    }
+
+    /// A cloneable [`EventSender`] for `event_id` in `event_group_id` of
+    /// `service_id`/`instance_id`, with its own session counter and the
+    /// major version of that service instance as interface version (the
+    /// configured one if the server does not offer it). Without
+    /// [`ServerConfig::session_handling`] it sends session ID `0`. Create
+    /// one per event at startup and hand clones to the tasks that publish
+    /// it.
+    #[cfg(feature = "_alloc")]
+    #[must_use]
+    pub fn event_sender(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    ) -> AllocEventSender<R, Sub, H, F::Socket, Hep> {
+        EventSender::new(
+            self.publisher.clone(),
+            service_id,
+            instance_id,
+            event_group_id,
+            event_id,
+            self.sd_state
+                .get()
+                .instance_major_version(&self.config, service_id, instance_id)
+                .unwrap_or(self.config.major_version),
+            Arc::default(),
+        )
+        .with_session_handling(self.config.session_handling)
+    }
+
     /// Get the local address of the unicast socket.
     ///
     /// # Errors
@@ -1376,35 +2155,467 @@ where
         self.e2e_registry.unregister(key);
     }
 
-    /// Run the server event loop with caller-provided receive buffers.
-    ///
-    /// Drives the receive loop (handling incoming `Subscribe` /
-    /// `FindService` SD messages on the SD multicast socket and
-    /// unicast traffic on the unicast socket) concurrently with the
-    /// 1-Hz `OfferService` announcement loop. The two are combined
-    /// into a single future so callers cannot forget to spawn the
-    /// announcement side; passing
-    /// [`ServerConfig::with_announce`] with `false` suppresses the
-    /// announcement arm for dispatcher topologies where a co-located
-    /// `Client` drives SD on the server's behalf.
-    ///
-    /// `unicast_buf` and `sd_buf` are caller-supplied scratch buffers
-    /// for incoming datagrams. Each must be at least one MTU
-    /// (~1500 bytes) and ideally up to the IP datagram limit
-    /// (64 KiB - 1). On bare-metal targets, callers typically place
-    /// these in `static` storage; on std (or any alloc-using
-    /// target), `Self::run` is the convenience shim that
-    /// heap-allocates 64 KiB buffers and delegates here.
-    ///
-    /// The returned future is independent of `&self` — the cheap
-    /// shared-handle clones it captures own everything it needs to
-    /// drive both loops, so the caller can keep using `Server` to
-    /// register E2E profiles, query `unicast_local_addr`, etc. while
-    /// the future runs.
+    /// Change the SD TTL advertised by a running server without
+    /// restarting it. Applies to every subsequent `OfferService` and
+    /// `SubscribeAck`; the original [`ServerConfig::ttl`] is restored
+    /// with [`Self::reset_ttl`]. Same truncation and saturation rules as
+    /// [`ServerConfig::with_ttl`].
     ///
     /// # Errors
     ///
-    /// Returns [`Error::InvalidUsage`] (tag `"passive_server_run"`) if
+    /// Returns [`ConfigError::ZeroTtl`] for a TTL under one second, which
+    /// truncates to 0 and would turn every offer into a `StopOffer` and
+    /// every `SubscribeAck` into a Nack. The advertised TTL is unchanged.
+    pub fn set_ttl(&self, ttl: core::time::Duration) -> Result<(), ConfigError> {
+        let ttl = saturate_ttl(ttl);
+        if ttl == 0 {
+            return Err(ConfigError::ZeroTtl);
+        }
+        self.sd_state.get().set_ttl_override(Some(ttl));
+        Ok(())
+    }
+
+    /// Drop a TTL set via [`Self::set_ttl`], reverting to the
+    /// configured [`ServerConfig::ttl`].
+    pub fn reset_ttl(&self) {
+        self.sd_state.get().set_ttl_override(None);
+    }
+
+    /// The SD TTL currently advertised, accounting for [`Self::set_ttl`].
+    #[must_use]
+    pub fn ttl(&self) -> u32 {
+        self.sd_state.get().effective_ttl(self.config.ttl)
+    }
+
+    /// Change the delay between the cyclic offers of a running server
+    /// without restarting it, from the next offer on; zero stops them.
+    /// The original [`ServerConfig::cyclic_offer_delay`] is restored with
+    /// [`Self::reset_cyclic_offer_delay`]. Delays are kept in whole
+    /// milliseconds.
+    pub fn set_cyclic_offer_delay(&self, delay: core::time::Duration) {
+        self.sd_state
+            .get()
+            .set_cyclic_offer_delay_override(Some(delay));
+    }
+
+    /// Drop a delay set via [`Self::set_cyclic_offer_delay`], reverting
+    /// to the configured [`ServerConfig::cyclic_offer_delay`].
+    pub fn reset_cyclic_offer_delay(&self) {
+        self.sd_state.get().set_cyclic_offer_delay_override(None);
+    }
+
+    /// The delay between cyclic offers currently in effect, accounting
+    /// for [`Self::set_cyclic_offer_delay`].
+    #[must_use]
+    pub fn cyclic_offer_delay(&self) -> core::time::Duration {
+        self.sd_state.get().cyclic_offer_delay(&self.config)
+    }
+
+    /// Withdraw the offer: multicast a `StopOfferService` (TTL 0) so
+    /// clients drop the service at once instead of waiting for the TTL to
+    /// run out, and stop announcing it. Until [`Self::resume_announcing`],
+    /// the announcement loop sends nothing and `FindService`s go
+    /// unanswered; requests and events are still served. Call it before
+    /// dropping the server or cancelling its run-future to shut down
+    /// cleanly. Under `server-tokio`, `ServerTask::stop` does this for
+    /// a server started with `Self::start`.
+    ///
+    /// `buf` is the send scratch, as for [`Self::announce_only_with_buffer`].
+    /// Passive servers and servers configured with
+    /// [`ServerConfig::with_announce`]`(false)` never offered the service
+    /// themselves and send nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (tag `"udp_buffer"`) if the message
+    /// does not fit in `buf`, or [`Error::Transport`] if sending it fails.
+    /// The offer counts as withdrawn either way.
+    pub async fn stop_announcing_with_buffer(&self, buf: &mut [u8]) -> Result<(), Error> {
+        let sd_state = self.sd_state.get();
+        sd_state.set_withdrawn(true);
+        if !self.config.announce || self.is_passive {
+            return Ok(());
+        }
+        crate::log::info!(
+            "Withdrawing the offer of service 0x{:04X}",
+            self.config.service_id
+        );
+        sd_state
+            .send_stop_offer_service(buf, &self.config, self.sd_socket.get())
+            .await
+    }
+
+    /// [`Self::stop_announcing_with_buffer`] with a buffer from the
+    /// publisher's scratch pool (see [`SCRATCH_BUFFERS_CAP`]).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::stop_announcing_with_buffer`].
+    #[cfg(feature = "_alloc")]
+    pub async fn stop_announcing(&self) -> Result<(), Error> {
+        let mut buf = self.publisher.get().scratch_buffer();
+        self.stop_announcing_with_buffer(&mut buf).await
+    }
+
+    /// Offer the service again after
+    /// [`Self::stop_announcing_with_buffer`], from the announcement
+    /// loop's next tick on.
+    pub fn resume_announcing(&self) {
+        self.sd_state.get().set_withdrawn(false);
+    }
+
+    /// Host another service instance on this server, next to the one in
+    /// its [`ServerConfig`]. From the announcement loop's next offer on it
+    /// is offered in the same SD messages (and withdrawn with them), and
+    /// `FindService`s for it are answered. Subscriptions to its event
+    /// groups (any group if it lists none) are accepted, and requests for
+    /// its service ID reach the request router, checked against its major
+    /// version; a SOME/IP request carries no instance ID, so handlers
+    /// tell services apart by [`Request::service_id`]. Publish its events
+    /// with its own service and instance IDs.
+    ///
+    /// Hosted services share the server's endpoint, TTL and offer timing.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidUsage`]`("duplicate_service_instance")` if the
+    /// server already offers the service instance, and
+    /// [`Error::Capacity`]`("hosted_services")` if
+    /// [`ServerConfig::ACCEPTED_OFFERS_CAP`] services are hosted already
+    /// or `service` lists more than [`ServerConfig::EVENT_GROUP_IDS_CAP`]
+    /// event groups. [`Error::InvalidConfig`] if its service or instance ID
+    /// is reserved.
+    #[cfg(feature = "std")]
+    pub fn add_service(&self, service: &ServiceInfo) -> Result<(), Error> {
+        validate_service_instance(service.service_id, service.instance_id)?;
+        let mut event_group_ids = heapless::Vec::new();
+        for group in &service.event_groups {
+            event_group_ids
+                .push(group.event_group_id)
+                .map_err(|_| Error::Capacity("hosted_services"))?;
+        }
+        self.sd_state.get().add_service(
+            &self.config,
+            sd_state::HostedService {
+                service_id: service.service_id,
+                instance_id: service.instance_id,
+                major_version: service.major_version,
+                minor_version: service.minor_version,
+                event_group_ids,
+            },
+        )?;
+        crate::log::info!(
+            "Hosting service 0x{:04X} instance {} next to 0x{:04X}",
+            service.service_id,
+            service.instance_id,
+            self.config.service_id
+        );
+        Ok(())
+    }
+
+    /// Stop hosting a service instance added with [`Self::add_service`].
+    /// From the announcement loop's next offer on it is no longer offered
+    /// and new subscriptions to it are refused; peers drop it once the
+    /// TTL of its last offer runs out. Returns `false` if the server does
+    /// not host it; the service of the [`ServerConfig`] is never removed.
+    #[cfg(feature = "std")]
+    pub fn remove_service(&self, service_id: u16, instance_id: u16) -> bool {
+        let removed = self.sd_state.get().remove_service(service_id, instance_id);
+        if removed {
+            crate::log::info!(
+                "No longer hosting service 0x{:04X} instance {}",
+                service_id,
+                instance_id
+            );
+        }
+        removed
+    }
+
+    /// Apply the part of a configuration reload that concerns a running
+    /// server, e.g. the changes from
+    /// [`ConfigNotifier::next_changes`](crate::config::ConfigNotifier::next_changes):
+    ///
+    /// - [`ConfigChange::TtlChanged`](crate::config::ConfigChange::TtlChanged) sets the TTL with [`Self::set_ttl`],
+    ///   or drops the override with [`Self::reset_ttl`] when the file no
+    ///   longer sets one.
+    /// - [`ConfigChange::OfferAdded`](crate::config::ConfigChange::OfferAdded) hosts the service instance with
+    ///   [`Self::add_service`], at the configured major and minor version
+    ///   and accepting any event group. [`ConfigChange::OfferRemoved`](crate::config::ConfigChange::OfferRemoved)
+    ///   stops hosting it with [`Self::remove_service`].
+    /// - [`ConfigChange::CyclicOfferDelayChanged`](crate::config::ConfigChange::CyclicOfferDelayChanged)
+    ///   sets the delay with [`Self::set_cyclic_offer_delay`], or drops
+    ///   the override with [`Self::reset_cyclic_offer_delay`].
+    ///
+    /// Interface, port and `announce` changes, changes to the repetition
+    /// phase and the initial delay, which only time the offers after
+    /// startup, and removing the service of the [`ServerConfig`], need
+    /// the server rebuilt and are reported as rejected. Static endpoints are the client's and are left out
+    /// of the report.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidConfig`] for a TTL under one second or a
+    /// reserved service or instance ID, and [`Error::Capacity`] if no
+    /// more services can be hosted. Changes listed before the failing
+    /// one stay applied.
+    #[cfg(feature = "std")]
+    pub fn apply_config_changes(
+        &self,
+        changes: &[crate::config::ConfigChange],
+    ) -> Result<crate::config::ApplyReport, Error> {
+        use crate::config::ConfigChange;
+
+        let mut report = crate::config::ApplyReport::default();
+        for change in changes {
+            let applied = match *change {
+                ConfigChange::TtlChanged { to: Some(secs), .. } => {
+                    self.set_ttl(core::time::Duration::from_secs(u64::from(secs)))?;
+                    true
+                }
+                ConfigChange::TtlChanged { to: None, .. } => {
+                    self.reset_ttl();
+                    true
+                }
+                ConfigChange::OfferAdded(offer) => {
+                    if !self.is_configured_service(offer.service_id, offer.instance_id) {
+                        let service = ServiceInfo {
+                            service_id: offer.service_id,
+                            instance_id: offer.instance_id,
+                            major_version: self.config.major_version,
+                            minor_version: self.config.minor_version,
+                            event_groups: std::vec::Vec::new(),
+                        };
+                        match self.add_service(&service) {
+                            Ok(()) | Err(Error::InvalidUsage("duplicate_service_instance")) => {}
+                            Err(e) => return Err(e),
+                        }
+                    }
+                    true
+                }
+                ConfigChange::OfferRemoved(offer) => {
+                    if self.is_configured_service(offer.service_id, offer.instance_id) {
+                        false
+                    } else {
+                        self.remove_service(offer.service_id, offer.instance_id);
+                        true
+                    }
+                }
+                ConfigChange::CyclicOfferDelayChanged {
+                    to: Some(millis), ..
+                } => {
+                    self.set_cyclic_offer_delay(core::time::Duration::from_millis(u64::from(
+                        millis,
+                    )));
+                    true
+                }
+                ConfigChange::CyclicOfferDelayChanged { to: None, .. } => {
+                    self.reset_cyclic_offer_delay();
+                    true
+                }
+                ConfigChange::InterfaceChanged { .. }
+                | ConfigChange::LocalPortChanged { .. }
+                | ConfigChange::AnnounceChanged { .. }
+                | ConfigChange::RepetitionsMaxChanged { .. }
+                | ConfigChange::RepetitionsBaseDelayChanged { .. }
+                | ConfigChange::InitialDelayMinChanged { .. }
+                | ConfigChange::InitialDelayMaxChanged { .. } => false,
+                ConfigChange::StaticEndpointAdded(_) | ConfigChange::StaticEndpointRemoved(_) => {
+                    continue;
+                }
+            };
+            if applied {
+                report.applied.push(*change);
+            } else {
+                crate::log::warn!(
+                    "Configuration change needs the server rebuilt: {:?}",
+                    change
+                );
+                report.rejected.push(*change);
+            }
+        }
+        Ok(report)
+    }
+
+    /// `true` for the service instance of the [`ServerConfig`].
+    #[cfg(feature = "std")]
+    fn is_configured_service(&self, service_id: u16, instance_id: u16) -> bool {
+        service_id == self.config.service_id && instance_id == self.config.instance_id
+    }
+
+    /// Snapshot of the per-peer statistics kept by the receive loop:
+    /// datagrams received from and replies sent to each peer, decode
+    /// errors, detected reboots and when the peer was last heard from.
+    ///
+    /// `last_seen` needs a clock from [`Timer::now`]. Events sent through
+    /// the [`EventPublisher`] are not counted. Returns `None` on builds
+    /// with neither `std` nor `bare_metal`, which have no lock to share
+    /// the table with.
+    #[must_use]
+    pub fn peer_stats(&self) -> Option<crate::PeerTable> {
+        self.sd_state.get().with_peers(|peers| peers.clone())
+    }
+
+    /// What the server offers and who subscribed to it, for crash reports
+    /// and support bundles; serializable with the `serde` feature.
+    ///
+    /// Hosted services are missing on builds with neither `std` nor
+    /// `bare_metal`, as for [`Self::peer_stats`].
+    pub async fn state_snapshot(&self) -> ServerSnapshot {
+        let config = &self.config;
+        let mut offered = heapless::Vec::new();
+        // Never fails: one slot more than hosted services can take.
+        let _ = offered.push(OfferedService {
+            service_id: config.service_id,
+            instance_id: config.instance_id,
+            major_version: config.major_version,
+            minor_version: config.minor_version,
+            event_group_ids: config.event_group_ids.clone(),
+        });
+        for hosted in self.sd_state.get().hosted_services() {
+            let _ = offered.push(OfferedService {
+                service_id: hosted.service_id,
+                instance_id: hosted.instance_id,
+                major_version: hosted.major_version,
+                minor_version: hosted.minor_version,
+                event_group_ids: hosted.event_group_ids,
+            });
+        }
+        let mut subscribers = heapless::Vec::new();
+        self.subscriptions
+            .for_each_subscription(|sub| {
+                // Never fails: the store holds no more than this.
+                let _ = subscribers.push(sub.clone());
+            })
+            .await;
+        ServerSnapshot {
+            offered,
+            accepted_offers: config.accepted_offers.clone(),
+            ttl: self.ttl(),
+            withdrawn: self.sd_state.get().is_withdrawn(),
+            subscribers,
+        }
+    }
+
+    /// When in each main-phase cycle the configured and the hosted
+    /// services are offered; all at offset zero unless
+    /// [`ServerConfig::staggered_offers`] is set.
+    ///
+    /// Hosted services are missing on builds with neither `std` nor
+    /// `bare_metal`, as for [`Self::peer_stats`].
+    #[must_use]
+    pub fn offer_schedule(&self) -> OfferSchedule {
+        offer_schedule::offer_schedule(&self.config, self.sd_state.get())
+    }
+
+    /// What the janitor enabled by [`ServerConfig::janitor`] has
+    /// collected so far: idle TCP connections closed and incomplete
+    /// SOME/IP-TP messages dropped. Returns `None` on builds with
+    /// neither `std` nor `bare_metal`, as for [`Self::peer_stats`].
+    #[must_use]
+    pub fn janitor_stats(&self) -> Option<crate::JanitorStats> {
+        self.sd_state.get().with_janitor_stats(|stats| *stats)
+    }
+
+    /// Datagrams the OS dropped because the SD or unicast socket's
+    /// receive queue was full: the receive loop fell behind. Read from
+    /// the sockets on each call; a passive server reports no SD count.
+    #[must_use]
+    pub fn drop_stats(&self) -> crate::DropStats {
+        let mut stats = crate::DropStats::default();
+        if !self.is_passive {
+            stats.add_discovery(self.sd_socket.get().dropped_datagrams());
+        }
+        stats.add_unicast(self.unicast_socket.get().dropped_datagrams());
+        stats
+    }
+
+    /// Answer method requests with `router` (`Some`), or stop doing so
+    /// (`None`). Takes effect on the next request received over UDP or
+    /// TCP and takes precedence over the non-SD observer from
+    /// [`ServerDeps::with_non_sd_observer`], which answers again once the
+    /// router is removed.
+    ///
+    /// Each request is answered before the receive loop takes the next
+    /// datagram, so a slow handler delays everything behind it. Has no
+    /// effect on builds without `std` or `bare_metal`, which have no lock
+    /// to share the router with the receive loop.
+    #[cfg(feature = "_alloc")]
+    pub fn set_request_router(&self, router: Option<MethodRouter>) {
+        self.sd_state.get().set_request_router(router.map(Arc::new));
+    }
+
+    /// Require peers to authenticate through `authenticator` (`Some`)
+    /// before their subscriptions and requests are accepted, or stop
+    /// doing so (`None`). Installing one drops every peer authenticated
+    /// so far; subscriptions already granted stay until they lapse, and
+    /// are not renewed for a peer that has not authenticated. See
+    /// [`PeerAuthenticator`] for what the receive loops then do.
+    ///
+    /// Has no effect on builds without `std` or `bare_metal`, which have
+    /// no lock to share the authenticator with the receive loop.
+    #[cfg(feature = "_alloc")]
+    pub fn set_authenticator(&self, authenticator: Option<PeerAuthenticator>) {
+        self.sd_state
+            .get()
+            .set_authenticator(authenticator.map(Arc::new));
+    }
+
+    /// `true` if subscriptions and requests from `peer` are accepted:
+    /// no authenticator is installed, or `peer` completed its handshake.
+    #[must_use]
+    pub fn admits(&self, peer: Ipv4Addr) -> bool {
+        self.sd_state.get().admits(core::net::IpAddr::V4(peer))
+    }
+
+    /// Revoke the authentication of `peer`, which must complete the
+    /// handshake again before its next subscription or request.
+    #[cfg(feature = "_alloc")]
+    pub fn revoke_authentication(&self, peer: Ipv4Addr) {
+        self.sd_state.get().set_authenticated(peer, false);
+    }
+
+    /// Stream of subscription lifecycle events (subscribed, refreshed,
+    /// unsubscribed) with the peer and eventgroup each concerns. See
+    /// [`SubscriptionEvents`] for queueing and consumer semantics.
+    #[must_use]
+    pub fn subscription_events(&self) -> SubscriptionEvents<Hsd> {
+        SubscriptionEvents::new(self.sd_state.clone())
+    }
+
+    /// Run the server event loop with caller-provided receive buffers.
+    ///
+    /// Drives the receive loop (handling incoming `Subscribe` /
+    /// `FindService` SD messages on the SD multicast socket and
+    /// unicast traffic on the unicast socket) concurrently with the
+    /// 1-Hz `OfferService` announcement loop. The two are combined
+    /// into a single future so callers cannot forget to spawn the
+    /// announcement side; passing
+    /// [`ServerConfig::with_announce`] with `false` suppresses the
+    /// announcement arm for dispatcher topologies where a co-located
+    /// `Client` drives SD on the server's behalf.
+    ///
+    /// `unicast_buf` and `sd_buf` are caller-supplied scratch buffers
+    /// for incoming datagrams. Each must be at least one MTU
+    /// (~1500 bytes) and ideally up to the IP datagram limit
+    /// (64 KiB - 1). On bare-metal targets, callers typically place
+    /// these in `static` storage; on std (or any alloc-using
+    /// target), `Self::run` is the convenience shim that
+    /// heap-allocates 64 KiB buffers and delegates here.
+    ///
+    /// The returned future is independent of `&self` — the cheap
+    /// shared-handle clones it captures own everything it needs to
+    /// drive both loops, so the caller can keep using `Server` to
+    /// register E2E profiles, query `unicast_local_addr`, etc. while
+    /// the future runs.
+    ///
+    /// Requests sent as SOME/IP-TP segments are dropped: putting them
+    /// back together takes a
+    /// [`Reassembler`](crate::protocol::tp::Reassembler), which only
+    /// `Self::run` allocates.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidUsage`] (tag `"passive_server_run"`) if
     /// the server was constructed via `Server::new_passive*` — passive
     /// servers have no real SD socket to read from, so the run loop
     /// would block forever on the ephemeral placeholder socket.
@@ -1430,6 +2641,7 @@ where
         let subscriptions = self.subscriptions.clone();
         let e2e_registry = self.e2e_registry.clone();
         let sd_state = self.sd_state.clone();
+        let publisher = self.publisher.clone();
         let timer = self.timer.clone();
         let is_passive = self.is_passive;
         let non_sd_observer = self.non_sd_observer;
@@ -1452,12 +2664,13 @@ where
                 return Err(Error::InvalidUsage("server_already_running"));
             }
 
-            runtime::run_combined::<H, F::Socket, Sub, Hsd, Tm, R>(
+            runtime::run_combined::<H, F::Socket, Sub, Hsd, Hep, Tm, R, _>(
                 config,
                 unicast_socket,
                 sd_socket,
                 subscriptions,
                 sd_state,
+                publisher,
                 e2e_registry,
                 timer,
                 is_passive,
@@ -1466,6 +2679,8 @@ where
                 recv_send_buf,
                 announce_send_buf,
                 non_sd_observer,
+                None,
+                core::future::pending(),
             )
             .await
         }
@@ -1505,8 +2720,9 @@ where
     /// supplementary announce loops only ever *send* on the shared SD
     /// socket.
     ///
-    /// The returned future loops forever (1 s tick between
-    /// announcements); spawn it on your executor.
+    /// The returned future loops forever, timing its offers as
+    /// configured by [`ServerConfig::cyclic_offer_delay`] and the fields
+    /// around it; spawn it on your executor.
     pub fn announce_only_with_buffer<'a>(
         &self,
         announce_send_buf: &'a mut [u8],
@@ -1541,8 +2757,8 @@ where
     /// `OfferService` to the same SD multicast group without
     /// competing for inbound datagrams.
     ///
-    /// This is the `_alloc` convenience wrapper — it heap-allocates
-    /// the send scratch internally. Bare-metal callers that cannot
+    /// This is the `_alloc` convenience wrapper — it leases the send
+    /// scratch from the publisher's pool (see [`SCRATCH_BUFFERS_CAP`]). Bare-metal callers that cannot
     /// park a `[u8; UDP_BUFFER_SIZE]` (≈ 1500 B) on the heap should
     /// use [`Self::announce_only_with_buffer`] instead, which accepts
     /// a caller-provided buffer so the heap allocation is avoided
@@ -1557,8 +2773,9 @@ where
     /// supplementary announce loops only ever *send* on the shared SD
     /// socket.
     ///
-    /// The returned future loops forever (1 s tick between
-    /// announcements); spawn it on your executor.
+    /// The returned future loops forever, timing its offers as
+    /// configured by [`ServerConfig::cyclic_offer_delay`] and the fields
+    /// around it; spawn it on your executor.
     #[cfg(feature = "_alloc")]
     pub fn announce_only_future<'a>(
         &self,
@@ -1572,12 +2789,14 @@ where
         let sd_socket = self.sd_socket.clone();
         let sd_state = self.sd_state.clone();
         let timer = self.timer.clone();
+        let publisher = self.publisher.clone();
         async move {
-            // Heap-allocate the send scratch here so the caller does
-            // not need to manage the buffer lifetime. Bare-metal callers
-            // that cannot use the allocator should call
-            // `announce_only_with_buffer` with a static scratch buffer.
-            let mut announce_send_buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
+            // Lease the send scratch from the publisher's pool here so
+            // the caller does not need to manage the buffer lifetime.
+            // Bare-metal callers that cannot use the allocator should
+            // call `announce_only_with_buffer` with a static scratch
+            // buffer.
+            let mut announce_send_buf = publisher.get().scratch_buffer();
             runtime::announce_loop(
                 &config,
                 sd_socket.get(),
@@ -1589,12 +2808,126 @@ where
         }
     }
 
-    /// Run the server event loop with heap-allocated 64 KiB receive
-    /// buffers — the convenience entry point for std and alloc-using
+    /// Serve request/response over TCP with caller-provided buffers:
+    /// accept connections on `listener` and answer each request through
+    /// the non-SD observer, the same way requests arriving over UDP are
+    /// answered by [`Self::run_with_buffers`]. Advertise the listener's
+    /// port through [`ServerConfig::with_tcp_port`] so clients learn of
+    /// it from the `OfferService`.
+    ///
+    /// `recv_buf` is split evenly between up to [`TCP_CONNECTIONS_CAP`]
+    /// connections, each slice bounding the largest request its
+    /// connection can carry; `send_buf` holds one response at a time and
+    /// must be distinct from the run-future's buffers. Further clients
+    /// wait in the listener's backlog until a connection closes.
+    ///
+    /// The returned future runs alongside the run-future and is
+    /// independent of `&self`; spawn both.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Capacity`] (tag `"tcp_recv_buffer"`) if a
+    /// `recv_buf` slice would be shorter than a SOME/IP header, and
+    /// [`Error::Transport`] once accepting a connection fails.
+    pub fn serve_tcp_with_buffers<'a, L>(
+        &self,
+        listener: L,
+        recv_buf: &'a mut [u8],
+        send_buf: &'a mut [u8],
+    ) -> impl core::future::Future<Output = Result<(), Error>>
+    + 'a
+    + use<'a, L, F, Tm, R, Sub, H, Hsd, Hep>
+    where
+        L: StreamListener + 'a,
+        Tm: 'a,
+        Hsd: 'a,
+    {
+        let config = self.config.clone();
+        let sd_state = self.sd_state.clone();
+        let e2e_registry = self.e2e_registry.clone();
+        let timer = self.timer.clone();
+        let non_sd_observer = self.non_sd_observer;
+        async move {
+            tcp::serve_tcp(
+                &config,
+                &listener,
+                sd_state.get(),
+                &e2e_registry,
+                &timer,
+                non_sd_observer,
+                tcp::split_recv_buf(recv_buf)?,
+                send_buf,
+            )
+            .await
+        }
+    }
+
+    /// [`Self::serve_tcp_with_buffers`] with a
+    /// [`RECV_BUFFER_LEN`]-byte receive buffer per connection and a send
+    /// buffer of that size, leased from the publisher's receive pool (see
+    /// [`RECV_BUFFERS_CAP`]) when this is called and held for the life of
+    /// the returned future. The returned future is `Send + 'static`
+    /// under the bounds below, so it is suitable for `tokio::spawn`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::serve_tcp_with_buffers`].
+    #[cfg(feature = "_alloc")]
+    pub fn serve_tcp<L>(
+        &self,
+        listener: L,
+    ) -> impl core::future::Future<Output = Result<(), Error>>
+    + Send
+    + 'static
+    + use<L, F, Tm, R, Sub, H, Hsd, Hep>
+    where
+        L: StreamListener + Send + Sync + 'static,
+        L::Stream: Send + Sync,
+        for<'a> L::AcceptFuture<'a>: Send,
+        for<'a> <L::Stream as TransportSocket>::SendFuture<'a>: Send,
+        for<'a> <L::Stream as TransportSocket>::RecvFuture<'a>: Send,
+        R: Send + Sync,
+        Tm: Send + Sync,
+        for<'a> Tm::SleepFuture<'a>: Send,
+        Hsd: Send + Sync,
+    {
+        let config = self.config.clone();
+        let sd_state = self.sd_state.clone();
+        let e2e_registry = self.e2e_registry.clone();
+        let timer = self.timer.clone();
+        let non_sd_observer = self.non_sd_observer;
+        let publisher = self.publisher.get();
+        let mut recv_bufs: heapless::Vec<_, TCP_CONNECTIONS_CAP> =
+            core::iter::repeat_with(|| publisher.recv_buffer())
+                .take(TCP_CONNECTIONS_CAP)
+                .collect();
+        let mut send_buf = publisher.recv_buffer();
+        async move {
+            tcp::serve_tcp(
+                &config,
+                &listener,
+                sd_state.get(),
+                &e2e_registry,
+                &timer,
+                non_sd_observer,
+                recv_bufs.iter_mut().map(|buf| &mut buf[..]).collect(),
+                &mut send_buf,
+            )
+            .await
+        }
+    }
+
+    /// Run the server event loop with 64 KiB receive buffers leased from
+    /// the publisher's receive pool (see [`RECV_BUFFERS_CAP`]) — the
+    /// convenience entry point for std and alloc-using
     /// bare-metal builds. Drives both the receive loop and (unless
     /// suppressed via [`ServerConfig::with_announce`]) the
     /// announcement loop in a single future.
     ///
+    /// Also allocates a [`Reassembler`](crate::protocol::tp::Reassembler),
+    /// so requests sent as SOME/IP-TP segments reach the non-SD observer
+    /// whole.
+    ///
     /// The returned future is `Send + 'static` under the where-clause
     /// bounds spelled below, so it is suitable for `tokio::spawn`.
     /// Single-threaded executors that need a `!Send` future (e.g.
@@ -1625,13 +2958,77 @@ where
         Sub: Send + Sync,
         for<'a> Sub::SubscribeFuture<'a>: Send,
         for<'a> Sub::UnsubscribeFuture<'a>: Send,
+        for<'a> Sub::ExpireFuture<'a>: Send,
+        R: Send + Sync,
+        Tm: Send + Sync,
+        for<'a> Tm::SleepFuture<'a>: Send,
+        Hsd: Send + Sync,
+        Hep: Send + Sync,
+    {
+        self.run_inner(core::future::pending())
+    }
+
+    /// Spawn [`Self::run`] on the current tokio runtime and return a
+    /// [`ServerTask`] that owns it.
+    ///
+    /// The handle can be awaited for the run-loop's result, aborted, or
+    /// stopped gracefully with [`ServerTask::stop`], which also
+    /// withdraws the offer with a `StopOfferService`. The usual
+    /// single-run rule applies: if this server's run-future was already
+    /// polled, the task resolves to
+    /// `Err(Error::InvalidUsage("server_already_running"))`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a tokio runtime, as `tokio::spawn` does.
+    #[cfg(feature = "server-tokio")]
+    pub fn start(&self) -> ServerTask
+    where
+        F: Send + Sync,
+        F::Socket: Send + Sync,
+        for<'a> <F::Socket as TransportSocket>::SendFuture<'a>: Send,
+        for<'a> <F::Socket as TransportSocket>::RecvFuture<'a>: Send,
+        H: Send + Sync,
+        Sub: Send + Sync,
+        for<'a> Sub::SubscribeFuture<'a>: Send,
+        for<'a> Sub::UnsubscribeFuture<'a>: Send,
+        for<'a> Sub::ExpireFuture<'a>: Send,
         R: Send + Sync,
         Tm: Send + Sync,
         for<'a> Tm::SleepFuture<'a>: Send,
         Hsd: Send + Sync,
         Hep: Send + Sync,
     {
-        self.run_inner()
+        let stop_offer: Option<task::StopOfferFuture> = if self.config.announce && !self.is_passive
+        {
+            let config = self.config.clone();
+            let sd_socket = self.sd_socket.clone();
+            let sd_state = self.sd_state.clone();
+            let publisher = self.publisher.clone();
+            Some(std::boxed::Box::pin(async move {
+                let mut buf = publisher.get().scratch_buffer();
+                let sd_state = sd_state.get();
+                sd_state.set_withdrawn(true);
+                sd_state
+                    .send_stop_offer_service(&mut buf, &config, sd_socket.get())
+                    .await
+            }))
+        } else {
+            None
+        };
+        let (shutdown, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        // A dropped sender (the handle detached) leaves the loop running.
+        let shutdown_rx = async move {
+            if shutdown_rx.await.is_err() {
+                core::future::pending::<()>().await;
+            }
+        };
+        ServerTask::new(
+            tokio::spawn(self.run_inner(shutdown_rx)),
+            stop_offer,
+            shutdown,
+            self.config.service_id,
+        )
     }
 
     /// Auto-trait-inferred run-future used by the constructors and by
@@ -1640,10 +3037,18 @@ where
     /// declared bound — callers should prefer `run` (Send-checked at
     /// the API boundary) or `run_with_buffers` (explicitly no `Send`
     /// requirement).
+    ///
+    /// The future resolves to `Ok(())` once `shutdown` does; the public
+    /// entry points pass one that never resolves.
     #[cfg(feature = "_alloc")]
-    fn run_inner(
+    fn run_inner<S>(
         &self,
-    ) -> impl core::future::Future<Output = Result<(), Error>> + 'static + use<F, Tm, R, Sub, H, Hsd, Hep>
+        shutdown: S,
+    ) -> impl core::future::Future<Output = Result<(), Error>>
+    + 'static
+    + use<F, Tm, R, Sub, H, Hsd, Hep, S>
+    where
+        S: core::future::Future<Output = ()> + 'static,
     {
         let config = self.config.clone();
         let unicast_socket = self.unicast_socket.clone();
@@ -1651,6 +3056,7 @@ where
         let subscriptions = self.subscriptions.clone();
         let e2e_registry = self.e2e_registry.clone();
         let sd_state = self.sd_state.clone();
+        let publisher = self.publisher.clone();
         let timer = self.timer.clone();
         let is_passive = self.is_passive;
         let non_sd_observer = self.non_sd_observer;
@@ -1676,21 +3082,28 @@ where
                 return Err(Error::InvalidUsage("server_already_running"));
             }
 
-            let mut unicast_buf = alloc::vec![0u8; 65535];
-            let mut sd_buf = alloc::vec![0u8; 65535];
+            // Leased from the publisher's receive pool for the life of
+            // the loop, so a server run again reuses them.
+            let mut unicast_buf = publisher.get().recv_buffer();
+            let mut sd_buf = publisher.get().recv_buffer();
             // Two DISTINCT send-scratch buffers — `recv_loop` and
             // `announce_loop` run concurrently and can each be parked at a
             // `send_to().await`, so a shared buffer would mutably alias.
-            // Heap-backed here (this is the `_alloc` path); bare-metal
-            // callers pass their own via `run_with_buffers`.
-            let mut recv_send_buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
-            let mut announce_send_buf = alloc::vec![0u8; crate::UDP_BUFFER_SIZE];
-            runtime::run_combined::<H, F::Socket, Sub, Hsd, Tm, R>(
+            // Leased from the publisher's scratch pool for the life of the
+            // loop (this is the `_alloc` path); bare-metal callers pass
+            // their own via `run_with_buffers`.
+            let mut recv_send_buf = publisher.get().scratch_buffer();
+            let mut announce_send_buf = publisher.get().scratch_buffer();
+            let mut reassembler = alloc::boxed::Box::new(crate::protocol::tp::Reassembler::new(
+                crate::protocol::tp::TP_REASSEMBLY_TIMEOUT,
+            ));
+            runtime::run_combined::<H, F::Socket, Sub, Hsd, Hep, Tm, R, _>(
                 config,
                 unicast_socket,
                 sd_socket,
                 subscriptions,
                 sd_state,
+                publisher,
                 e2e_registry,
                 timer,
                 is_passive,
@@ -1699,6 +3112,8 @@ where
                 &mut recv_send_buf,
                 &mut announce_send_buf,
                 non_sd_observer,
+                Some(&mut reassembler),
+                shutdown,
             )
             .await
         }
@@ -1739,6 +3154,17 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn accepted_offer_resolves_wildcard_major_version() {
+        let cfg = ServerConfig::new(0x5B, 1).with_accepted_offer(0x5678, 1, 2, 0x0001);
+        assert!(cfg.accepts_offer(0x5678, 1, 2, 0x0001));
+        assert!(!cfg.accepts_offer(0x5678, 1, 3, 0x0001));
+        let offer = cfg
+            .accepted_offer(0x5678, 1, sd::ANY_MAJOR_VERSION, 0x0001)
+            .expect("wildcard major matches");
+        assert_eq!(offer.major_version, 2);
+    }
+
     #[test]
     fn server_config_builder_chain_overrides_each_field() {
         let cfg = ServerConfig::new(0x5B, 1)
@@ -1797,7 +3223,7 @@ mod tests {
     fn server_config_with_ttl_saturates_overflow() {
         let cfg = ServerConfig::new(0x5B, 1)
             .with_ttl(core::time::Duration::from_secs(u64::from(u32::MAX) + 1));
-        assert_eq!(cfg.ttl, u32::MAX);
+        assert_eq!(cfg.ttl, crate::protocol::sd::TTL_INFINITE);
     }
 
     #[test]
@@ -1816,27 +3242,114 @@ mod tests {
         assert!(!returned.accepts_event_group(0xFFFF));
     }
 
-    // ── new_with_handles / new_passive_with_handles tests ──────────────
-    //
-    // These constructors take pre-built socket handles instead of
-    // calling `factory.bind()` themselves, and validate that the
-    // caller-supplied `config.local_port` matches the actual bound
-    // port (back-fill-only-on-zero). The validation logic only
-    // exercises through these tests; the production code paths use
-    // `new` / `new_with_deps`.
+    #[test]
+    fn server_config_validate_rejects_unreachable_configs() {
+        let valid = ServerConfig::new(0x5B, 1)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(30684);
+        assert_eq!(valid.validate(), Ok(()));
 
-    /// Build a `ServerStorage<…>` whose unicast socket is bound to
-    /// the given port (port `0` for ephemeral) and whose other
-    /// fields are the std defaults a tokio consumer would assemble.
-    /// Used by the `new_with_handles` tests below.
-    async fn build_test_handles(
-        unicast_port: u16,
-    ) -> (
-        ServerStorage<
-            TokioTransport,
-            TokioTimer,
-            Arc<Mutex<E2ERegistry>>,
-            Arc<RwLock<SubscriptionManager>>,
+        let cfg = valid.clone().with_interface(Ipv4Addr::new(224, 0, 23, 0));
+        assert_eq!(
+            cfg.validate(),
+            Err(ConfigError::MulticastInterface(Ipv4Addr::new(
+                224, 0, 23, 0
+            )))
+        );
+        let cfg = valid.clone().with_ttl(core::time::Duration::ZERO);
+        assert_eq!(cfg.validate(), Err(ConfigError::ZeroTtl));
+        let mut cfg = valid.clone();
+        cfg.service_id = 0xFFFF;
+        assert_eq!(cfg.validate(), Err(ConfigError::ReservedServiceId(0xFFFF)));
+        let cfg = valid.clone().with_accepted_offer(0x5C, 0xFFFF, 1, 1);
+        assert_eq!(
+            cfg.validate(),
+            Err(ConfigError::ReservedInstanceId {
+                service_id: 0x5C,
+                instance_id: 0xFFFF
+            })
+        );
+        let cfg = valid.clone().with_tcp_port(0);
+        assert_eq!(
+            cfg.validate(),
+            Err(ConfigError::AnnouncedPortZero {
+                protocol: crate::protocol::sd::TransportProtocol::Tcp
+            })
+        );
+    }
+
+    #[test]
+    fn server_config_validate_allows_port_zero_without_announcements() {
+        let cfg = ServerConfig::new(0x5B, 1).with_interface(Ipv4Addr::LOCALHOST);
+        assert_eq!(
+            cfg.validate(),
+            Err(ConfigError::AnnouncedPortZero {
+                protocol: crate::protocol::sd::TransportProtocol::Udp
+            })
+        );
+        assert_eq!(cfg.clone().with_announce(false).validate(), Ok(()));
+        assert_eq!(
+            cfg.with_sd_port_mode(SdPortMode::External).validate(),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn set_ttl_rejects_ttls_under_a_second() {
+        let config = ServerConfig::new(0x5B, 1)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_announce(false)
+            .with_ttl(core::time::Duration::from_secs(3));
+        let (server, ..) = TestServer::new(config).await.unwrap();
+        for ttl in [
+            core::time::Duration::ZERO,
+            core::time::Duration::from_millis(999),
+        ] {
+            assert_eq!(server.set_ttl(ttl), Err(ConfigError::ZeroTtl));
+            assert_eq!(server.ttl(), 3, "a rejected TTL leaves the old one");
+        }
+        assert_eq!(
+            server.set_ttl(core::time::Duration::from_millis(1_500)),
+            Ok(())
+        );
+        assert_eq!(server.ttl(), 1);
+        server.reset_ttl();
+        assert_eq!(server.ttl(), 3);
+    }
+
+    #[tokio::test]
+    async fn new_rejects_invalid_config_with_descriptive_error() {
+        let config = ServerConfig::new(0x5B, 1)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_ttl(core::time::Duration::ZERO);
+        let Err(err) = TestServer::new(config).await else {
+            panic!("a TTL of 0 must be rejected");
+        };
+        assert!(matches!(err, Error::InvalidConfig(ConfigError::ZeroTtl)));
+        assert!(std::format!("{err}").contains("TTL"), "{err}");
+    }
+
+    // ── new_with_handles / new_passive_with_handles tests ──────────────
+    //
+    // These constructors take pre-built socket handles instead of
+    // calling `factory.bind()` themselves, and validate that the
+    // caller-supplied `config.local_port` matches the actual bound
+    // port (back-fill-only-on-zero). The validation logic only
+    // exercises through these tests; the production code paths use
+    // `new` / `new_with_deps`.
+
+    /// Build a `ServerStorage<…>` whose unicast socket is bound to
+    /// the given port (port `0` for ephemeral) and whose other
+    /// fields are the std defaults a tokio consumer would assemble.
+    /// Used by the `new_with_handles` tests below.
+    async fn build_test_handles(
+        unicast_port: u16,
+    ) -> (
+        ServerStorage<
+            TokioTransport,
+            TokioTimer,
+            Arc<Mutex<E2ERegistry>>,
+            Arc<RwLock<SubscriptionManager>>,
             Arc<crate::tokio_transport::TokioSocket>,
             Arc<SdStateManager>,
             Arc<
@@ -2047,7 +3560,7 @@ mod tests {
     /// `SubscribeAck` send fails (transient transport error), the
     /// just-committed subscription must be rolled back so the
     /// manager isn't left holding a slot for a peer that never
-    /// received its ACK. `handle_sd_message` must also NOT propagate
+    /// received its ACK. `SdResponder::respond` must also NOT propagate
     /// the error via `?` — a single SD-socket hiccup tearing down
     /// `run()` was the original bug.
     #[tokio::test]
@@ -2059,7 +3572,7 @@ mod tests {
         use std::pin::Pin as StdPin;
 
         // Socket whose `send_to` always fails. `recv_from` is never
-        // called by this test (we drive `handle_sd_message` directly).
+        // called by this test (we drive `SdResponder::respond` directly).
         struct FailingSocket {
             local: SocketAddrV4,
         }
@@ -2155,21 +3668,19 @@ mod tests {
         let sd_view = view.sd_header().expect("Subscribe has SD header");
         let sender = core::net::SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 45000));
 
-        // The H3 fix: handle_sd_message must NOT bubble the ACK send
+        // The H3 fix: `SdResponder::respond` must NOT bubble the ACK send
         // failure as Err — it logs and continues.
-        let result = runtime::handle_sd_message(
+        let result = SdResponder::new(
             &server.config,
             server.sd_socket.get(),
             server.sd_state.get(),
             &server.subscriptions,
-            &sd_view,
-            sender,
-            &mut [0u8; crate::UDP_BUFFER_SIZE],
         )
+        .respond(&sd_view, sender, &mut [0u8; crate::UDP_BUFFER_SIZE])
         .await;
         assert!(
             result.is_ok(),
-            "handle_sd_message must not propagate transient SD-socket I/O errors; got {result:?}"
+            "SdResponder::respond must not propagate transient SD-socket I/O errors; got {result:?}"
         );
 
         // The H3 fix: a committed-but-unacked subscription must be
@@ -2328,15 +3839,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
 
@@ -2363,6 +3872,196 @@ mod tests {
         server_handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_subscribe_until_stopped_is_acked_until_stopped() {
+        let (server, server_port) = create_test_server(0x5B, 1).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let message = make_subscription_header(
+            0x5B,
+            1,
+            1,
+            sd::TTL_INFINITE,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            server_port,
+        );
+        client_socket
+            .send_to(&message, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        let mut buf = vec![0u8; 65535];
+        let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+        let view = MessageView::parse(&buf[..datagram.bytes_received]).unwrap();
+        SdResponder::new(
+            &server.config,
+            server.sd_socket.get(),
+            server.sd_state.get(),
+            &server.subscriptions,
+        )
+        .respond(
+            &view.sd_header().unwrap(),
+            core::net::SocketAddr::V4(datagram.source),
+            &mut [0u8; crate::UDP_BUFFER_SIZE],
+        )
+        .await
+        .unwrap();
+
+        let (resp_len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            client_socket.recv_from(&mut buf),
+        )
+        .await
+        .expect("Timeout waiting for SubscribeAck")
+        .unwrap();
+        assert_eq!(parse_subscribe_ack_ttl(&buf[..resp_len]), sd::TTL_INFINITE);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_wildcard_major_acked_with_concrete_version() {
+        let (server, server_port) = create_test_server(0x5B, 1).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let message = make_subscription_header(
+            0x5B,
+            1,
+            sd::ANY_MAJOR_VERSION,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            server_port,
+        );
+        client_socket
+            .send_to(&message, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        let server_handle = tokio::spawn(async move {
+            let mut buf = vec![0u8; 65535];
+            let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+            let len = datagram.bytes_received;
+            let addr = core::net::SocketAddr::V4(datagram.source);
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            let sd_view = view.sd_header().unwrap();
+            SdResponder::new(
+                &server.config,
+                server.sd_socket.get(),
+                server.sd_state.get(),
+                &server.subscriptions,
+            )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
+            .await
+            .unwrap();
+        });
+
+        let mut resp_buf = vec![0u8; 65535];
+        let (resp_len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await
+        .expect("Timeout waiting for SubscribeAck")
+        .unwrap();
+
+        assert!(
+            parse_subscribe_ack_ttl(&resp_buf[..resp_len]) > 0,
+            "expected ACK"
+        );
+        let view = MessageView::parse(&resp_buf[..resp_len]).unwrap();
+        let sd_view = view.sd_header().unwrap();
+        let entry = sd_view.entries().next().unwrap();
+        assert_eq!(
+            entry.major_version(),
+            1,
+            "wildcard subscribe must be acked with the concrete major version"
+        );
+
+        server_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stop_subscribe_removes_subscriber_without_reply() {
+        let (server, server_port) = create_test_server(0x5B, 1).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let subscribe = make_subscription_header(
+            0x5B,
+            1,
+            1,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            server_port,
+        );
+        let stop_entries = [Entry::SubscribeEventGroup(sd::EventGroupEntry::new_stop(
+            0x5B, 1, 1, 0x01,
+        ))];
+        let stop_options = [sd::Options::IpV4Endpoint {
+            ip: Ipv4Addr::LOCALHOST,
+            protocol: sd::TransportProtocol::Udp,
+            port: server_port,
+        }];
+        let stop = build_sd_message(&sd::Header::new_stop_subscription(
+            sd::RebootFlag::RecentlyRebooted,
+            &stop_entries,
+            &stop_options,
+        ));
+
+        let server_handle = tokio::spawn(async move {
+            let mut buf = vec![0u8; 65535];
+            for expected_count in [1, 0] {
+                let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+                let len = datagram.bytes_received;
+                let addr = core::net::SocketAddr::V4(datagram.source);
+                let view = MessageView::parse(&buf[..len]).unwrap();
+                let sd_view = view.sd_header().unwrap();
+                SdResponder::new(
+                    &server.config,
+                    server.sd_socket.get(),
+                    server.sd_state.get(),
+                    &server.subscriptions,
+                )
+                .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
+                .await
+                .unwrap();
+                assert_eq!(
+                    server.subscriptions.read().await.subscription_count(),
+                    expected_count
+                );
+            }
+        });
+
+        let mut resp_buf = vec![0u8; 65535];
+        client_socket
+            .send_to(&subscribe, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+        let (resp_len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await
+        .expect("Timeout waiting for SubscribeAck")
+        .unwrap();
+        assert!(parse_subscribe_ack_ttl(&resp_buf[..resp_len]) > 0);
+
+        client_socket
+            .send_to(&stop, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+        server_handle.await.unwrap();
+        let reply = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await;
+        assert!(reply.is_err(), "StopSubscribe must not be acked");
+    }
+
     #[tokio::test]
     async fn test_subscribe_nack_wrong_service() {
         let (server, server_port) = create_test_server(0x5B, 1).await;
@@ -2392,15 +4091,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
 
@@ -2453,15 +4150,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
 
@@ -2512,15 +4207,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
         });
@@ -2547,6 +4240,209 @@ mod tests {
         server_handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn apply_config_changes_applies_the_safe_subset() {
+        use crate::config::{ConfigChange, OfferedService, StaticEndpoint};
+
+        let (server, _) = create_test_server(0x06B1, 1).await;
+        let hosted = OfferedService {
+            service_id: 0x06B2,
+            instance_id: 2,
+        };
+        let configured = OfferedService {
+            service_id: 0x06B1,
+            instance_id: 1,
+        };
+        let changes = [
+            ConfigChange::TtlChanged {
+                from: None,
+                to: Some(7),
+            },
+            ConfigChange::OfferAdded(hosted),
+            ConfigChange::OfferRemoved(configured),
+            ConfigChange::LocalPortChanged {
+                from: None,
+                to: Some(30_600),
+            },
+            ConfigChange::StaticEndpointAdded(StaticEndpoint {
+                service_id: 0x06B3,
+                instance_id: 1,
+                addr: SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30_601),
+            }),
+        ];
+        let report = server.apply_config_changes(&changes).unwrap();
+        assert_eq!(report.applied, changes[..2]);
+        assert_eq!(report.rejected, changes[2..4]);
+        assert_eq!(server.ttl(), 7);
+        let offered = |server: &TestServer| -> Vec<(u16, u16)> {
+            server
+                .sd_state
+                .get()
+                .service_entries(&server.config, sd::OptionsCount::ONE, true, |_, _| true)
+                .iter()
+                .map(|entry| match entry {
+                    Entry::OfferService(offer) => (offer.service_id, offer.instance_id),
+                    other => panic!("expected an offer, got {other:?}"),
+                })
+                .collect()
+        };
+        assert_eq!(offered(&server), [(0x06B1, 1), (0x06B2, 2)]);
+
+        let changes = [
+            ConfigChange::OfferRemoved(hosted),
+            ConfigChange::TtlChanged {
+                from: Some(7),
+                to: None,
+            },
+        ];
+        let report = server.apply_config_changes(&changes).unwrap();
+        assert_eq!(report.applied, changes);
+        assert_eq!(server.ttl(), 3);
+        assert_eq!(offered(&server), [(0x06B1, 1)]);
+
+        let zero_ttl = [ConfigChange::TtlChanged {
+            from: None,
+            to: Some(0),
+        }];
+        assert!(matches!(
+            server.apply_config_changes(&zero_ttl),
+            Err(Error::InvalidConfig(ConfigError::ZeroTtl))
+        ));
+    }
+
+    #[tokio::test]
+    async fn apply_config_changes_applies_the_cyclic_delay_and_rejects_startup_timing() {
+        use crate::config::ConfigChange;
+
+        let (server, _) = create_test_server(0x06B4, 1).await;
+        let configured = server.cyclic_offer_delay();
+        let changes = [
+            ConfigChange::CyclicOfferDelayChanged {
+                from: None,
+                to: Some(250),
+            },
+            ConfigChange::RepetitionsMaxChanged {
+                from: None,
+                to: Some(5),
+            },
+            ConfigChange::RepetitionsBaseDelayChanged {
+                from: None,
+                to: Some(30),
+            },
+            ConfigChange::InitialDelayMinChanged {
+                from: None,
+                to: Some(10),
+            },
+            ConfigChange::InitialDelayMaxChanged {
+                from: None,
+                to: Some(20),
+            },
+        ];
+        let report = server.apply_config_changes(&changes).unwrap();
+        assert_eq!(report.applied, changes[..1]);
+        assert_eq!(report.rejected, changes[1..]);
+        assert_eq!(
+            server.cyclic_offer_delay(),
+            core::time::Duration::from_millis(250)
+        );
+
+        let changes = [ConfigChange::CyclicOfferDelayChanged {
+            from: Some(250),
+            to: None,
+        }];
+        let report = server.apply_config_changes(&changes).unwrap();
+        assert_eq!(report.applied, changes);
+        assert_eq!(server.cyclic_offer_delay(), configured);
+    }
+
+    #[tokio::test]
+    async fn hosted_services_are_offered_found_and_subscribed_beside_the_configured_one() {
+        let (server, server_port) = create_test_server(0x06A1, 1).await;
+        let hosted = ServiceInfo {
+            service_id: 0x06A2,
+            instance_id: 3,
+            major_version: 2,
+            minor_version: 0,
+            event_groups: vec![EventGroupInfo::new(0x10, vec![0x8001])],
+        };
+        server.add_service(&hosted).unwrap();
+        assert!(matches!(
+            server.add_service(&hosted),
+            Err(Error::InvalidUsage("duplicate_service_instance"))
+        ));
+        let offered: Vec<(u16, u16)> = server
+            .sd_state
+            .get()
+            .service_entries(&server.config, sd::OptionsCount::ONE, true, |_, _| true)
+            .iter()
+            .map(|entry| match entry {
+                Entry::OfferService(offer) => (offer.service_id, offer.instance_id),
+                other => panic!("expected an offer, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(offered, [(0x06A1, 1), (0x06A2, 3)]);
+
+        let run = server.run();
+        let server_handle = tokio::spawn(async move {
+            run.await.ok();
+        });
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut resp_buf = vec![0u8; 65535];
+        let mut exchange = async |message: Vec<u8>| {
+            client_socket
+                .send_to(&message, format!("127.0.0.1:{server_port}"))
+                .await
+                .unwrap();
+            let (len, _) = tokio::time::timeout(
+                std::time::Duration::from_secs(2),
+                client_socket.recv_from(&mut resp_buf),
+            )
+            .await
+            .expect("timeout waiting for an SD answer")
+            .unwrap();
+            resp_buf[..len].to_vec()
+        };
+        let find = |service_id| {
+            let entries = [Entry::FindService(ServiceEntry::find(service_id))];
+            build_sd_message(&sd::Header::new(
+                Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+                &entries,
+                &[],
+            ))
+        };
+        let offered_services = |answer: &[u8]| -> Vec<u16> {
+            let view = MessageView::parse(answer).unwrap();
+            let sd_view = view.sd_header().unwrap();
+            sd_view.entries().map(|entry| entry.service_id()).collect()
+        };
+
+        assert_eq!(offered_services(&exchange(find(0x06A2)).await), [0x06A2]);
+        assert_eq!(
+            offered_services(&exchange(find(0xFFFF)).await),
+            [0x06A1, 0x06A2]
+        );
+        let subscribe = |event_group_id| {
+            make_subscription_header(
+                0x06A2,
+                3,
+                2,
+                3,
+                event_group_id,
+                Ipv4Addr::LOCALHOST,
+                sd::TransportProtocol::Udp,
+                40000,
+            )
+        };
+        assert_eq!(parse_subscribe_ack_ttl(&exchange(subscribe(0x10)).await), 3);
+        assert_eq!(
+            parse_subscribe_ack_ttl(&exchange(subscribe(0x11)).await),
+            0,
+            "groups the hosted service does not list are refused"
+        );
+
+        server_handle.abort();
+    }
+
     #[tokio::test]
     async fn test_find_service_wildcard() {
         let (server, server_port) = create_test_server(0x5B, 1).await;
@@ -2574,15 +4470,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
         });
@@ -2606,6 +4500,219 @@ mod tests {
         server_handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_find_service_wildcard_policy() {
+        async fn answered(policy: WildcardFindPolicy, unicast_flag: bool) -> bool {
+            let (mut server, server_port) = create_test_server(0x5B, 1).await;
+            server.config.wildcard_find = policy;
+            let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let find_entries = [Entry::FindService(ServiceEntry::find(0xFFFF))];
+            let sd_header = sd::Header::new(Flags::new(true, unicast_flag), &find_entries, &[]);
+            client_socket
+                .send_to(
+                    &build_sd_message(&sd_header),
+                    format!("127.0.0.1:{server_port}"),
+                )
+                .await
+                .unwrap();
+
+            let mut buf = vec![0u8; 65535];
+            let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+            let view = MessageView::parse(&buf[..datagram.bytes_received]).unwrap();
+            SdResponder::new(
+                &server.config,
+                server.sd_socket.get(),
+                server.sd_state.get(),
+                &server.subscriptions,
+            )
+            .respond(
+                &view.sd_header().unwrap(),
+                core::net::SocketAddr::V4(datagram.source),
+                &mut [0u8; crate::UDP_BUFFER_SIZE],
+            )
+            .await
+            .unwrap();
+
+            tokio::time::timeout(
+                std::time::Duration::from_millis(200),
+                client_socket.recv_from(&mut buf),
+            )
+            .await
+            .is_ok()
+        }
+
+        assert!(answered(WildcardFindPolicy::Answer, false).await);
+        assert!(!answered(WildcardFindPolicy::Ignore, true).await);
+        assert!(!answered(WildcardFindPolicy::AnswerIfUnicast, false).await);
+        assert!(answered(WildcardFindPolicy::AnswerIfUnicast, true).await);
+    }
+
+    #[tokio::test]
+    async fn test_find_service_answered_by_next_multicast_offer() {
+        let (mut server, server_port) = create_test_server(0x5B, 1).await;
+        server.config.find_answer = FindAnswer::NextMulticastOffer;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let find_entries = [Entry::FindService(ServiceEntry::find(0x5B))];
+        let sd_header = sd::Header::new(
+            Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            &find_entries,
+            &[],
+        );
+        client_socket
+            .send_to(
+                &build_sd_message(&sd_header),
+                format!("127.0.0.1:{server_port}"),
+            )
+            .await
+            .unwrap();
+
+        let mut buf = vec![0u8; 65535];
+        let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+        let view = MessageView::parse(&buf[..datagram.bytes_received]).unwrap();
+        SdResponder::new(
+            &server.config,
+            server.sd_socket.get(),
+            server.sd_state.get(),
+            &server.subscriptions,
+        )
+        .respond(
+            &view.sd_header().unwrap(),
+            core::net::SocketAddr::V4(datagram.source),
+            &mut [0u8; crate::UDP_BUFFER_SIZE],
+        )
+        .await
+        .unwrap();
+
+        let unicast = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            client_socket.recv_from(&mut buf),
+        )
+        .await;
+        assert!(unicast.is_err(), "no unicast offer");
+        assert_eq!(server.sd_state.get().take_queued_find_answers(), 1);
+        assert_eq!(server.sd_state.get().take_queued_find_answers(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_withdrawn_offer_leaves_find_service_unanswered() {
+        async fn answered(server: &TestServer, server_port: u16) -> bool {
+            let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let find_entries = [Entry::FindService(ServiceEntry::find(0x5C))];
+            let sd_header = sd::Header::new(
+                Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+                &find_entries,
+                &[],
+            );
+            client_socket
+                .send_to(
+                    &build_sd_message(&sd_header),
+                    format!("127.0.0.1:{server_port}"),
+                )
+                .await
+                .unwrap();
+
+            let mut buf = vec![0u8; 65535];
+            let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+            let view = MessageView::parse(&buf[..datagram.bytes_received]).unwrap();
+            SdResponder::new(
+                &server.config,
+                server.sd_socket.get(),
+                server.sd_state.get(),
+                &server.subscriptions,
+            )
+            .respond(
+                &view.sd_header().unwrap(),
+                core::net::SocketAddr::V4(datagram.source),
+                &mut [0u8; crate::UDP_BUFFER_SIZE],
+            )
+            .await
+            .unwrap();
+
+            tokio::time::timeout(
+                std::time::Duration::from_millis(200),
+                client_socket.recv_from(&mut buf),
+            )
+            .await
+            .is_ok()
+        }
+
+        let (server, server_port) = create_test_server(0x5C, 1).await;
+        server
+            .stop_announcing()
+            .await
+            .expect("StopOfferService sent");
+        assert!(!answered(&server, server_port).await);
+        server.resume_announcing();
+        assert!(answered(&server, server_port).await);
+    }
+
+    /// A tap installed through `ServerDeps::with_tap` sees the request
+    /// arriving on the unicast socket and the offer sent in reply.
+    #[tokio::test]
+    async fn test_tap_records_server_datagrams() {
+        use crate::tap::{Direction, TappedDatagram, TappedSocket};
+        use crate::tokio_transport::TokioSocket;
+
+        let seen: Arc<Mutex<Vec<(Direction, SocketAddrV4)>>> = Arc::default();
+        let log = Arc::clone(&seen);
+        let deps = ServerDeps::tokio().with_tap(move |d: &TappedDatagram<'_>| {
+            log.lock().unwrap().push((d.direction, d.peer));
+        });
+        let config = ServerConfig::new(0x5B, 1)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(0);
+        let (server, _handles, _run): (
+            Server<_, _, _, _, Arc<TappedSocket<TokioSocket, _, _>>>,
+            _,
+            _,
+        ) = Server::new_with_deps(deps, config, false).await.unwrap();
+        let server_port = server.config.local_port;
+
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client_addr = match client_socket.local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr,
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        let find_entries = [Entry::FindService(ServiceEntry::find(0x5B))];
+        let sd_header = sd::Header::new(
+            Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            &find_entries,
+            &[],
+        );
+        client_socket
+            .send_to(
+                &build_sd_message(&sd_header),
+                format!("127.0.0.1:{server_port}"),
+            )
+            .await
+            .unwrap();
+
+        let mut buf = vec![0u8; 65535];
+        let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+        let view = MessageView::parse(&buf[..datagram.bytes_received]).unwrap();
+        SdResponder::new(
+            &server.config,
+            server.sd_socket.get(),
+            server.sd_state.get(),
+            &server.subscriptions,
+        )
+        .respond(
+            &view.sd_header().unwrap(),
+            core::net::SocketAddr::V4(datagram.source),
+            &mut [0u8; crate::UDP_BUFFER_SIZE],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                (Direction::Inbound, client_addr),
+                (Direction::Outbound, client_addr)
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_find_service_wrong_service_ignored() {
         let (server, server_port) = create_test_server(0x5B, 1).await;
@@ -2633,15 +4740,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
         });
@@ -2661,6 +4766,60 @@ mod tests {
         server_handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_find_service_wrong_major_ignored() {
+        let (server, server_port) = create_test_server(0x5B, 1).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        // FindService for our service but major version 2 (we offer 1).
+        let find_entry = Entry::FindService(ServiceEntry {
+            major_version: 2,
+            ..ServiceEntry::find(0x5B)
+        });
+        let find_entries = [find_entry];
+        let sd_header = sd::Header::new(
+            Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            &find_entries,
+            &[],
+        );
+        let message = build_sd_message(&sd_header);
+        client_socket
+            .send_to(&message, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        let server_handle = tokio::spawn(async move {
+            let mut buf = vec![0u8; 65535];
+            let datagram = server.unicast_socket.recv_from(&mut buf).await.unwrap();
+            let len = datagram.bytes_received;
+            let addr = core::net::SocketAddr::V4(datagram.source);
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            let sd_view = view.sd_header().unwrap();
+            SdResponder::new(
+                &server.config,
+                server.sd_socket.get(),
+                server.sd_state.get(),
+                &server.subscriptions,
+            )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
+            .await
+            .unwrap();
+        });
+
+        let mut resp_buf = vec![0u8; 65535];
+        let result = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await;
+        assert!(
+            result.is_err(),
+            "Expected timeout (no response for a major version we do not offer)"
+        );
+
+        server_handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_subscribe_nack_no_endpoint() {
         let (server, server_port) = create_test_server(0x5B, 1).await;
@@ -2685,15 +4844,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
 
@@ -2734,6 +4891,7 @@ mod tests {
             server.sd_socket.get(),
             server.sd_state.get(),
             recv_addr,
+            |_, _| true,
         )
         .await
         .expect("send_unicast_offer failed");
@@ -2799,8 +4957,231 @@ mod tests {
             .await
             .unwrap();
 
-        // Small delay, then send valid subscribe
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        // The server does not offer service 0x1234 and says so.
+        let mut resp_buf = vec![0u8; 65535];
+        let (resp_len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await
+        .expect("Timeout waiting for the ERROR response")
+        .unwrap();
+        let error = MessageView::parse(&resp_buf[..resp_len]).unwrap();
+        assert_eq!(
+            error.header().message_type().message_type(),
+            MessageType::Error
+        );
+        assert_eq!(error.header().return_code(), ReturnCode::UnknownService);
+        assert_eq!(error.header().request_id(), 0x0001);
+
+        // Then send valid subscribe
+        let message = make_subscription_header(
+            0x5B,
+            1,
+            1,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            client_port,
+        );
+        client_socket
+            .send_to(&message, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        // Wait for ACK
+        let (resp_len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await
+        .expect("Timeout waiting for SubscribeAck")
+        .unwrap();
+
+        let ttl = parse_subscribe_ack_ttl(&resp_buf[..resp_len]);
+        assert!(ttl > 0, "Expected ACK (TTL > 0), got TTL={ttl}");
+
+        // Verify subscription was added (non-SD message was rejected)
+        let subs = subscriptions.read().await;
+        assert_eq!(subs.subscription_count(), 1);
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_run_answers_unserved_requests_with_error_codes() {
+        let (server, server_port) = create_test_server(0x5B, 1).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server_handle = tokio::spawn(async move {
+            server.run().await.ok();
+        });
+
+        let cases = [
+            (0x1234, 0x01, 0x01, ReturnCode::UnknownService),
+            (0x5B, 0x01, 0x02, ReturnCode::WrongInterfaceVersion),
+            (0x5B, 0x02, 0x01, ReturnCode::WrongProtocolVersion),
+            (0x5B, 0x01, 0x01, ReturnCode::UnknownMethod),
+        ];
+        for (request_id, (service_id, protocol_version, interface_version, expected)) in
+            (1..).zip(cases)
+        {
+            let header = SomeIpHeader::new(
+                crate::protocol::MessageId::new_from_service_and_method(service_id, 0x0001),
+                request_id,
+                0x01,
+                interface_version,
+                MessageTypeField::new(MessageType::Request, false),
+                ReturnCode::Ok,
+                0,
+            );
+            let mut request = Vec::new();
+            header.encode(&mut request).unwrap();
+            request[12] = protocol_version;
+            client_socket
+                .send_to(&request, format!("127.0.0.1:{server_port}"))
+                .await
+                .unwrap();
+
+            let mut resp_buf = vec![0u8; 1500];
+            let (resp_len, _) = tokio::time::timeout(
+                std::time::Duration::from_secs(2),
+                client_socket.recv_from(&mut resp_buf),
+            )
+            .await
+            .expect("Timeout waiting for the ERROR response")
+            .unwrap();
+            let response = &resp_buf[..resp_len];
+            assert_eq!(resp_len, 16, "{expected:?}: header only");
+            assert_eq!(response[..12], request[..12], "{expected:?}: IDs echoed");
+            assert_eq!(response[12], protocol_version);
+            assert_eq!(response[13], interface_version);
+            assert_eq!(
+                response[14],
+                u8::from(MessageTypeField::new(MessageType::Error, false))
+            );
+            assert_eq!(response[15], u8::from(expected));
+        }
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_run_malformed_data() {
+        let (server, server_port) = create_test_server(0x5B, 1).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client_port = match client_socket.local_addr().unwrap() {
+            core::net::SocketAddr::V4(a) => a.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected v4 source address"),
+        };
+
+        let subscriptions = Arc::clone(&server.subscriptions);
+
+        let server_handle = tokio::spawn(async move {
+            server.run().await.ok();
+        });
+
+        // Send garbage bytes
+        client_socket
+            .send_to(&[0xFF, 0xFE, 0xFD], format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        // Small delay, then send valid subscribe
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let message = make_subscription_header(
+            0x5B,
+            1,
+            1,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            client_port,
+        );
+        client_socket
+            .send_to(&message, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        // Wait for ACK
+        let mut resp_buf = vec![0u8; 65535];
+        let (resp_len, _) = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await
+        .expect("Timeout waiting for SubscribeAck")
+        .unwrap();
+
+        let ttl = parse_subscribe_ack_ttl(&resp_buf[..resp_len]);
+        assert!(ttl > 0, "Expected ACK (TTL > 0), got TTL={ttl}");
+
+        let subs = subscriptions.read().await;
+        assert_eq!(subs.subscription_count(), 1);
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn run_drops_datagrams_over_decode_limits() {
+        let config = ServerConfig::new(0x5B, 1)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_decode_limits(crate::protocol::DecodeLimits::default().with_max_sd_options(0));
+        let (server, _handles, _run) = TestServer::new(config).await.unwrap();
+        let server_port = match server.unicast_local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let subscriptions = Arc::clone(&server.subscriptions);
+        let server_handle = tokio::spawn(async move {
+            server.run().await.ok();
+        });
+
+        // A well-formed subscribe, but its endpoint option is over the limit.
+        let message = make_subscription_header(
+            0x5B,
+            1,
+            1,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            server_port,
+        );
+        client_socket
+            .send_to(&message, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+
+        let mut resp_buf = vec![0u8; 65535];
+        let reply = tokio::time::timeout(
+            std::time::Duration::from_millis(300),
+            client_socket.recv_from(&mut resp_buf),
+        )
+        .await;
+        assert!(reply.is_err(), "over-limit subscribe must not be answered");
+        assert_eq!(subscriptions.read().await.subscription_count(), 0);
+
+        server_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn peer_stats_count_subscribe_and_ack() {
+        let config = ServerConfig::new(0x5B, 1).with_interface(Ipv4Addr::LOCALHOST);
+        let (server, _handles, _run) = TestServer::new(config).await.unwrap();
+        let server_port = match server.unicast_local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        let run = server.run();
+        let server_handle = tokio::spawn(async move {
+            run.await.ok();
+        });
+
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client_addr = client_socket.local_addr().unwrap();
         let message = make_subscription_header(
             0x5B,
             1,
@@ -2809,61 +5190,135 @@ mod tests {
             0x01,
             Ipv4Addr::LOCALHOST,
             sd::TransportProtocol::Udp,
-            client_port,
+            client_addr.port(),
         );
         client_socket
             .send_to(&message, format!("127.0.0.1:{server_port}"))
             .await
             .unwrap();
+        // Garbage from the same peer counts as received and as an error.
+        client_socket
+            .send_to(&[0xFF; 4], format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
 
-        // Wait for ACK
         let mut resp_buf = vec![0u8; 65535];
-        let (resp_len, _) = tokio::time::timeout(
-            std::time::Duration::from_secs(2),
+        tokio::time::timeout(
+            std::time::Duration::from_secs(1),
             client_socket.recv_from(&mut resp_buf),
         )
         .await
-        .expect("Timeout waiting for SubscribeAck")
+        .expect("subscribe ack")
         .unwrap();
 
-        let ttl = parse_subscribe_ack_ttl(&resp_buf[..resp_len]);
-        assert!(ttl > 0, "Expected ACK (TTL > 0), got TTL={ttl}");
-
-        // Verify subscription was added (non-SD message was ignored)
-        let subs = subscriptions.read().await;
-        assert_eq!(subs.subscription_count(), 1);
+        let mut stats = None;
+        for _ in 0..50 {
+            let table = server.peer_stats().unwrap();
+            if let Some(peer) = table.get(&client_addr)
+                && peer.errors == 1
+            {
+                stats = Some(*peer);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let stats = stats.expect("peer tracked");
+        assert_eq!(stats.received, 2);
+        assert_eq!(stats.sent, 1);
+        assert!(stats.last_seen.is_some());
 
         server_handle.abort();
     }
 
     #[tokio::test]
-    async fn test_run_malformed_data() {
-        let (server, server_port) = create_test_server(0x5B, 1).await;
-        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
-        let client_port = match client_socket.local_addr().unwrap() {
-            core::net::SocketAddr::V4(a) => a.port(),
-            core::net::SocketAddr::V6(_) => panic!("expected v4 source address"),
+    async fn subscription_events_follow_subscribe_renew_and_stop() {
+        let config = ServerConfig::new(0x5B, 1).with_interface(Ipv4Addr::LOCALHOST);
+        let (server, _handles, _run) = TestServer::new(config).await.unwrap();
+        let server_port = match server.unicast_local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
         };
+        let mut events = server.subscription_events();
+        assert_eq!(events.try_recv(), None);
+        let run = server.run();
+        let server_handle = tokio::spawn(async move {
+            run.await.ok();
+        });
 
-        let subscriptions = Arc::clone(&server.subscriptions);
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client_port = client_socket.local_addr().unwrap().port();
+        let subscriber = SocketAddrV4::new(Ipv4Addr::LOCALHOST, client_port);
+        let mut resp_buf = vec![0u8; 65535];
+        for ttl in [3, 3, 0] {
+            let message = make_subscription_header(
+                0x5B,
+                1,
+                1,
+                ttl,
+                0x01,
+                Ipv4Addr::LOCALHOST,
+                sd::TransportProtocol::Udp,
+                client_port,
+            );
+            client_socket
+                .send_to(&message, format!("127.0.0.1:{server_port}"))
+                .await
+                .unwrap();
+            if ttl != 0 {
+                tokio::time::timeout(
+                    std::time::Duration::from_secs(1),
+                    client_socket.recv_from(&mut resp_buf),
+                )
+                .await
+                .expect("subscribe ack")
+                .unwrap();
+            }
+        }
+
+        for kind in [
+            SubscriptionEventKind::Subscribed,
+            SubscriptionEventKind::Refreshed,
+            SubscriptionEventKind::Unsubscribed,
+        ] {
+            let event = tokio::time::timeout(std::time::Duration::from_secs(1), events.recv())
+                .await
+                .expect("subscription event");
+            assert_eq!(
+                event,
+                SubscriptionEvent {
+                    kind,
+                    subscriber,
+                    service_id: 0x5B,
+                    instance_id: 1,
+                    event_group_id: 0x01,
+                }
+            );
+        }
+
+        server_handle.abort();
+    }
 
+    #[tokio::test]
+    async fn subscription_lapses_without_renewal() {
+        let config = ServerConfig::new(0x5B, 1).with_interface(Ipv4Addr::LOCALHOST);
+        let (server, handles, _run) = TestServer::new(config).await.unwrap();
+        let server_port = match server.unicast_local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        let mut events = server.subscription_events();
+        let run = server.run();
         let server_handle = tokio::spawn(async move {
-            server.run().await.ok();
+            run.await.ok();
         });
 
-        // Send garbage bytes
-        client_socket
-            .send_to(&[0xFF, 0xFE, 0xFD], format!("127.0.0.1:{server_port}"))
-            .await
-            .unwrap();
-
-        // Small delay, then send valid subscribe
-        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client_port = client_socket.local_addr().unwrap().port();
         let message = make_subscription_header(
             0x5B,
             1,
             1,
-            3,
+            1,
             0x01,
             Ipv4Addr::LOCALHOST,
             sd::TransportProtocol::Udp,
@@ -2873,23 +5328,182 @@ mod tests {
             .send_to(&message, format!("127.0.0.1:{server_port}"))
             .await
             .unwrap();
-
-        // Wait for ACK
         let mut resp_buf = vec![0u8; 65535];
-        let (resp_len, _) = tokio::time::timeout(
-            std::time::Duration::from_secs(2),
+        tokio::time::timeout(
+            std::time::Duration::from_secs(1),
             client_socket.recv_from(&mut resp_buf),
         )
         .await
-        .expect("Timeout waiting for SubscribeAck")
+        .expect("subscribe ack")
         .unwrap();
+        assert_eq!(handles.publisher.subscriber_count(0x5B, 1, 0x01).await, 1);
 
-        let ttl = parse_subscribe_ack_ttl(&resp_buf[..resp_len]);
-        assert!(ttl > 0, "Expected ACK (TTL > 0), got TTL={ttl}");
+        for kind in [
+            SubscriptionEventKind::Subscribed,
+            SubscriptionEventKind::Expired,
+        ] {
+            let event = tokio::time::timeout(std::time::Duration::from_secs(3), events.recv())
+                .await
+                .expect("subscription event");
+            assert_eq!(event.kind, kind);
+            assert_eq!(
+                event.subscriber,
+                SocketAddrV4::new(Ipv4Addr::LOCALHOST, client_port)
+            );
+        }
+        assert_eq!(handles.publisher.subscriber_count(0x5B, 1, 0x01).await, 0);
 
-        let subs = subscriptions.read().await;
-        assert_eq!(subs.subscription_count(), 1);
+        server_handle.abort();
+    }
+
+    /// A sticky event published before anyone subscribed reaches a new
+    /// subscriber right after its `SubscribeAck`; a renewal gets no
+    /// repeat.
+    #[tokio::test]
+    async fn sticky_event_is_delivered_to_new_subscribers_only() {
+        let config = ServerConfig::new(0x5B, 1).with_interface(Ipv4Addr::LOCALHOST);
+        let (server, handles, _run) = TestServer::new(config).await.unwrap();
+        let server_port = match server.unicast_local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        handles
+            .publisher
+            .set_sticky_event(0x5B, 1, 0x01, 0x8001)
+            .unwrap();
+        let sent = handles
+            .publisher
+            .publish_raw_event(0x5B, 1, 0x01, 0x8001, 7, 1, 1, &[0xAB, 0xCD])
+            .await
+            .unwrap();
+        assert_eq!(sent, 0, "nobody subscribed yet");
+        let run = server.run();
+        let server_handle = tokio::spawn(async move {
+            run.await.ok();
+        });
+
+        let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let client_port = client_socket.local_addr().unwrap().port();
+        let subscribe = make_subscription_header(
+            0x5B,
+            1,
+            1,
+            3,
+            0x01,
+            Ipv4Addr::LOCALHOST,
+            sd::TransportProtocol::Udp,
+            client_port,
+        );
+        let mut buf = vec![0u8; 65535];
+        let mut recv = async || {
+            let (len, _) = tokio::time::timeout(
+                std::time::Duration::from_millis(500),
+                client_socket.recv_from(&mut buf),
+            )
+            .await
+            .ok()?
+            .unwrap();
+            Some(buf[..len].to_vec())
+        };
+
+        client_socket
+            .send_to(&subscribe, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+        let ack = recv().await.expect("subscribe ack");
+        assert_eq!(parse_subscribe_ack_ttl(&ack), 3);
+        let event = recv().await.expect("sticky event");
+        let view = MessageView::parse(&event).unwrap();
+        assert_eq!(view.header().message_id().method_id(), 0x8001);
+        assert_eq!(view.payload_bytes(), [0xAB, 0xCD]);
+
+        client_socket
+            .send_to(&subscribe, format!("127.0.0.1:{server_port}"))
+            .await
+            .unwrap();
+        assert!(recv().await.is_some(), "renewal ack");
+        assert!(recv().await.is_none(), "no sticky event on renewal");
+
+        server_handle.abort();
+    }
+
+    /// A registered initial event reaches each new subscriber right after
+    /// its `SubscribeAck`, and keeps its value when the event is published.
+    #[tokio::test]
+    async fn initial_event_is_sent_after_subscribe_ack() {
+        let config = ServerConfig::new(0x5B, 1).with_interface(Ipv4Addr::LOCALHOST);
+        let (server, handles, _run) = TestServer::new(config).await.unwrap();
+        let server_port = match server.unicast_local_addr().unwrap() {
+            core::net::SocketAddr::V4(addr) => addr.port(),
+            core::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        handles
+            .publisher
+            .set_initial_event(0x5B, 1, 0x01, 0x8001, 0, 1, 1, &[0x01, 0x02])
+            .unwrap();
+        handles
+            .publisher
+            .set_initial_event(0x5B, 1, 0x02, 0x8002, 0, 1, 1, &[0x09])
+            .unwrap();
+        // Not sticky: publishing leaves the registered value as it was.
+        handles
+            .publisher
+            .publish_raw_event(0x5B, 1, 0x01, 0x8001, 7, 1, 1, &[0xAB])
+            .await
+            .unwrap();
+        let run = server.run();
+        let server_handle = tokio::spawn(async move {
+            run.await.ok();
+        });
+
+        let subscribe = |client_port| {
+            make_subscription_header(
+                0x5B,
+                1,
+                1,
+                3,
+                0x01,
+                Ipv4Addr::LOCALHOST,
+                sd::TransportProtocol::Udp,
+                client_port,
+            )
+        };
+        for _ in 0..2 {
+            let client_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let client_port = client_socket.local_addr().unwrap().port();
+            let mut buf = vec![0u8; 65535];
+            let mut recv = async || {
+                let (len, _) = tokio::time::timeout(
+                    std::time::Duration::from_millis(500),
+                    client_socket.recv_from(&mut buf),
+                )
+                .await
+                .ok()?
+                .unwrap();
+                Some(buf[..len].to_vec())
+            };
+            client_socket
+                .send_to(&subscribe(client_port), format!("127.0.0.1:{server_port}"))
+                .await
+                .unwrap();
+            let ack = recv().await.expect("subscribe ack");
+            assert_eq!(parse_subscribe_ack_ttl(&ack), 3);
+            let event = recv().await.expect("initial event");
+            let view = MessageView::parse(&event).unwrap();
+            assert_eq!(view.header().message_id().method_id(), 0x8001);
+            assert_eq!(
+                view.header().message_type().message_type(),
+                crate::protocol::MessageType::Notification
+            );
+            assert_eq!(view.payload_bytes(), [0x01, 0x02]);
+            assert!(
+                recv().await.is_none(),
+                "only the subscribed event group's initial events"
+            );
+        }
 
+        assert!(handles.publisher.clear_initial_event(0x5B, 1, 0x01, 0x8001));
+        assert!(!handles.publisher.clear_initial_event(0x5B, 1, 0x01, 0x8001));
         server_handle.abort();
     }
 
@@ -2901,7 +5515,7 @@ mod tests {
         let entry = sd::Entry::StopOfferService(sd::ServiceEntry {
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: sd::OptionsCount::new(0, 0),
+            options_count: sd::OptionsCount::NONE,
             service_id: 0x5B,
             instance_id: 1,
             major_version: 1,
@@ -2917,11 +5531,13 @@ mod tests {
         let sd_view = sd::SdHeaderView::parse(&buf[..n]).unwrap();
 
         // Should not panic or error
-        let result = runtime::handle_sd_message(
+        let result = SdResponder::new(
             &server.config,
             server.sd_socket.get(),
             server.sd_state.get(),
             &server.subscriptions,
+        )
+        .respond(
             &sd_view,
             "127.0.0.1:12345".parse().unwrap(),
             &mut [0u8; crate::UDP_BUFFER_SIZE],
@@ -2958,15 +5574,13 @@ mod tests {
             let data = &buf[..len];
             let view = MessageView::parse(data).unwrap();
             let sd_view = view.sd_header().unwrap();
-            runtime::handle_sd_message(
+            SdResponder::new(
                 &server.config,
                 server.sd_socket.get(),
                 server.sd_state.get(),
                 &server.subscriptions,
-                &sd_view,
-                addr,
-                &mut [0u8; crate::UDP_BUFFER_SIZE],
             )
+            .respond(&sd_view, addr, &mut [0u8; crate::UDP_BUFFER_SIZE])
             .await
             .unwrap();
 
@@ -3176,6 +5790,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn extract_endpoint_skips_ipv6_endpoints() {
+        // A dual-stack subscriber lists [IpV6Endpoint, IpV4Endpoint]: the
+        // IPv4 one is used. One listing only IPv6 cannot be served.
+        let mut buf = [0u8; 64];
+        let mut slot = &mut buf[..];
+        let v6 = sd::Options::IpV6Endpoint {
+            ip: core::net::Ipv6Addr::new(0xfd00, 0, 0, 0, 0, 0, 0, 1),
+            protocol: sd::TransportProtocol::Udp,
+            port: 30800,
+        };
+        let mut offset = v6.write(&mut slot).unwrap();
+        offset += write_ipv4_endpoint_option(
+            &mut buf[offset..],
+            Ipv4Addr::new(10, 0, 0, 1),
+            30801,
+            sd::TransportProtocol::Udp,
+        );
+        let iter = sd::OptionIter::new(&buf[..offset]);
+
+        assert_eq!(
+            runtime::extract_subscriber_endpoint(&iter, 0, 2, 0, 0),
+            Some(SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 30801))
+        );
+        assert_eq!(
+            runtime::extract_subscriber_endpoint(&iter, 0, 1, 0, 0),
+            None
+        );
+    }
+
     #[test]
     fn extract_endpoint_second_run_only() {
         // Two options, entry references only the second one via the
@@ -3191,7 +5835,7 @@ mod tests {
         );
     }
 
-    /// End-to-end regression: drive a real `Server::handle_sd_message` with
+    /// End-to-end regression: drive a real `SdResponder::respond` with
     /// a single SD packet that carries *two* entries — an `OfferService`
     /// referencing option index 0 and a `SubscribeEventGroup` referencing
     /// option index 1 — where each option is a different
@@ -3214,7 +5858,7 @@ mod tests {
         let offer_entry = Entry::OfferService(sd::ServiceEntry {
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: sd::OptionsCount::new(1, 0),
+            options_count: sd::OptionsCount::ONE,
             service_id: 0x5B,
             instance_id: 1,
             major_version: 1,
@@ -3226,7 +5870,7 @@ mod tests {
         let subscribe_entry = Entry::SubscribeEventGroup(sd::EventGroupEntry {
             index_first_options_run: 1,
             index_second_options_run: 0,
-            options_count: sd::OptionsCount::new(1, 0),
+            options_count: sd::OptionsCount::ONE,
             service_id: 0x5B,
             instance_id: 1,
             major_version: 1,
@@ -3255,7 +5899,7 @@ mod tests {
         let message = build_sd_message(&sd_header);
 
         // Parse the combined SD datagram in-memory and drive
-        // `handle_sd_message` directly rather than round-tripping `message`
+        // `SdResponder::respond` directly rather than round-tripping `message`
         // through the server's SD socket. Every test server binds the same
         // fixed SD port with `SO_REUSEADDR`/`SO_REUSEPORT`; under parallel test
         // execution the unicast datagram can be delivered to a different bound
@@ -3267,15 +5911,13 @@ mod tests {
         let sender = core::net::SocketAddr::from((Ipv4Addr::LOCALHOST, 54_321));
         let view = MessageView::parse(&message).unwrap();
         let sd_view = view.sd_header().unwrap();
-        runtime::handle_sd_message(
+        SdResponder::new(
             &server.config,
             server.sd_socket.get(),
             server.sd_state.get(),
             &server.subscriptions,
-            &sd_view,
-            sender,
-            &mut [0u8; crate::UDP_BUFFER_SIZE],
         )
+        .respond(&sd_view, sender, &mut [0u8; crate::UDP_BUFFER_SIZE])
         .await
         .unwrap();
 
@@ -3379,6 +6021,142 @@ mod tests {
         assert!(!publisher.has_subscribers(0x005C, 0x0001, 0x0001).await);
     }
 
+    #[tokio::test]
+    async fn event_sender_clones_share_ids_and_session_counter() {
+        let server = make_passive_server(0x005C, 0x0001).await;
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let subscriber = match receiver.local_addr().unwrap() {
+            std::net::SocketAddr::V4(addr) => addr,
+            std::net::SocketAddr::V6(_) => panic!("expected IPv4 address"),
+        };
+        server
+            .publisher()
+            .register_subscriber(0x005C, 0x0001, 0x0001, subscriber)
+            .await
+            .unwrap();
+
+        let sender = server.event_sender(0x005C, 0x0001, 0x0001, 0x8001);
+        let other_task = sender.clone();
+        assert_eq!(sender.send(&[1]).await.unwrap(), 1);
+        assert_eq!(other_task.send(&[2]).await.unwrap(), 1);
+        let unrelated = server.event_sender(0x005C, 0x0001, 0x0001, 0x8002);
+        assert_eq!(unrelated.send(&[3]).await.unwrap(), 1);
+
+        let mut buf = [0u8; 64];
+        for (event_id, request_id, payload) in [(0x8001, 1, 1), (0x8001, 2, 2), (0x8002, 1, 3)] {
+            let len = receiver.recv(&mut buf).await.unwrap();
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            let header = view.header();
+            assert_eq!(header.message_id().service_id(), 0x005C);
+            assert_eq!(header.message_id().method_id(), event_id);
+            assert_eq!(header.request_id(), request_id);
+            assert_eq!(view.payload_bytes(), [payload]);
+        }
+    }
+
+    #[tokio::test]
+    async fn event_sender_without_session_handling_sends_session_zero() {
+        let server = make_passive_server(0x005C, 0x0001).await;
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let std::net::SocketAddr::V4(subscriber) = receiver.local_addr().unwrap() else {
+            panic!("expected IPv4 address");
+        };
+        server
+            .publisher()
+            .register_subscriber(0x005C, 0x0001, 0x0001, subscriber)
+            .await
+            .unwrap();
+
+        let sender = server
+            .event_sender(0x005C, 0x0001, 0x0001, 0x8001)
+            .with_session_handling(false);
+        let mut buf = [0u8; 64];
+        for payload in [1, 2] {
+            assert_eq!(sender.send(&[payload]).await.unwrap(), 1);
+            let len = receiver.recv(&mut buf).await.unwrap();
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            assert_eq!(view.header().request_id(), 0);
+            assert_eq!(view.payload_bytes(), [payload]);
+        }
+    }
+
+    #[tokio::test]
+    async fn instance_publisher_reaches_only_its_instance_with_its_version() {
+        let server = make_passive_server(0x005C, 0x0001).await;
+        server
+            .add_service(&ServiceInfo {
+                service_id: 0x005C,
+                instance_id: 0x0002,
+                major_version: 3,
+                minor_version: 0,
+                event_groups: vec![EventGroupInfo::new(0x0001, vec![0x8001])],
+            })
+            .unwrap();
+        assert!(matches!(
+            server.instance_publisher(0x005C, 0x0003),
+            Err(Error::InvalidUsage("unknown_service_instance"))
+        ));
+
+        let mut receivers = Vec::new();
+        for instance_id in [0x0001, 0x0002] {
+            let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let std::net::SocketAddr::V4(subscriber) = receiver.local_addr().unwrap() else {
+                panic!("expected IPv4 address");
+            };
+            server
+                .publisher()
+                .register_subscriber(0x005C, instance_id, 0x0001, subscriber)
+                .await
+                .unwrap();
+            receivers.push(receiver);
+        }
+
+        let second = server.instance_publisher(0x005C, 0x0002).unwrap();
+        assert_eq!(second.interface_version(), 3);
+        assert_eq!(second.subscriber_addresses(0x0001).await.len(), 1);
+        let mut buf = [0u8; 64];
+        assert_eq!(
+            second
+                .publish_raw_event_with_buffers(0x0001, 0x8001, 1, &[7], &mut buf)
+                .await
+                .unwrap(),
+            1
+        );
+        let sender = second.event_sender(0x0001, 0x8001, Arc::default());
+        assert_eq!(sender.send(&[8]).await.unwrap(), 1);
+        for payload in [7, 8] {
+            let len = receivers[1].recv(&mut buf).await.unwrap();
+            let view = MessageView::parse(&buf[..len]).unwrap();
+            assert_eq!(view.header().interface_version(), 3);
+            assert_eq!(view.payload_bytes(), [payload]);
+        }
+        assert!(
+            tokio::time::timeout(
+                std::time::Duration::from_millis(100),
+                receivers[0].recv(&mut buf)
+            )
+            .await
+            .is_err(),
+            "the first instance's subscriber must not get the second's events"
+        );
+        assert_eq!(
+            server
+                .event_sender(0x005C, 0x0002, 0x0001, 0x8001)
+                .send(&[9])
+                .await
+                .unwrap(),
+            1
+        );
+        let len = receivers[1].recv(&mut buf).await.unwrap();
+        assert_eq!(
+            MessageView::parse(&buf[..len])
+                .unwrap()
+                .header()
+                .interface_version(),
+            3
+        );
+    }
+
     // The announcement loop is folded into the combined
     // `Server::run` future, so the `is_passive` check happens on
     // `run` itself — exercised by
@@ -3418,6 +6196,51 @@ mod tests {
     /// `Err(Error::InvalidUsage("server_already_running"))` rather
     /// than silently corrupt wire output. Tests both ordering and
     /// the buffer-supplied variant.
+    #[tokio::test]
+    async fn server_task_abort_ends_run_loop() {
+        let (server, _port) = create_test_server(0x005E, 0x0001).await;
+        let task = server.start();
+        tokio::task::yield_now().await;
+        assert!(!task.is_finished(), "run-loop blocks in recv");
+
+        task.abort();
+        assert!(task.await.is_ok(), "an aborted task resolves to Ok");
+    }
+
+    #[tokio::test]
+    async fn server_task_stop_withdraws_offer_and_releases_run_latch() {
+        let (server, _port) = create_test_server(0x005F, 0x0001).await;
+        let task = server.start();
+        tokio::task::yield_now().await;
+        task.stop().await.expect("graceful stop");
+
+        // The run-loop is gone but the latch still rejects a second run
+        // on the same sockets.
+        let again = server.start().await;
+        assert!(matches!(
+            again,
+            Err(Error::InvalidUsage("server_already_running"))
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_task_stop_ends_run_loop_without_aborting_it() {
+        let (server, _port) = create_test_server(0x0060, 0x0001).await;
+        let task = server.start();
+        tokio::task::yield_now().await;
+
+        let started = std::time::Instant::now();
+        task.stop().await.expect("graceful stop");
+        assert!(
+            started.elapsed() < core::time::Duration::from_millis(500),
+            "the loop ended on the shutdown signal, not the abort fallback"
+        );
+        assert!(
+            server.sd_state.get().is_withdrawn(),
+            "offer withdrawn first"
+        );
+    }
+
     #[tokio::test]
     async fn second_run_future_returns_already_running() {
         let (server, _port) = create_test_server(0x005D, 0x0001).await;
@@ -3718,6 +6541,38 @@ mod tests {
         drop(blocker);
     }
 
+    #[tokio::test]
+    async fn exclusive_sd_port_fails_with_remediation_when_taken() {
+        // The shared server holds 30490 on loopback for the whole test, so
+        // the exclusive bind below always collides and never holds the
+        // port against other tests.
+        let shared = ServerConfig::new(0x005D, 0x0001).with_interface(Ipv4Addr::LOCALHOST);
+        let (_holder, _handles, _run) = TestServer::new(shared).await.unwrap();
+
+        let exclusive = ServerConfig::new(0x005D, 0x0002)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_sd_port_mode(SdPortMode::Exclusive);
+        let Err(err) = TestServer::new(exclusive).await else {
+            panic!("exclusive SD bind must fail while the port is held");
+        };
+        let Error::SdPortUnavailable { addr, mode, .. } = &err else {
+            panic!("expected Error::SdPortUnavailable, got {err:?}");
+        };
+        assert_eq!(addr.port(), sd::MULTICAST_PORT);
+        assert_eq!(*mode, SdPortMode::Exclusive);
+        assert!(std::format!("{err}").contains("SdPortMode::External"));
+    }
+
+    #[tokio::test]
+    async fn external_sd_port_mode_constructs_a_passive_server() {
+        let config = ServerConfig::new(0x005D, 0x0003)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_sd_port_mode(SdPortMode::External);
+        let (server, _handles, _run) = TestServer::new(config).await.unwrap();
+        assert!(server.is_passive);
+        assert_ne!(server.sd_socket.local_addr().unwrap().port(), 30490);
+    }
+
     #[tokio::test]
     async fn new_passive_with_tracing_subscriber_evaluates_format_args() {
         // Coverage helper: with no global tracing subscriber, `crate::log::info!`
@@ -3883,7 +6738,7 @@ mod tests {
 
     /// Host-arch PROXY budget — see the twin constant in
     /// src/client/mod.rs for semantics and the update procedure.
-    const TOKIO_SERVER_RUN_FUTURE_BUDGET: usize = 9728; // = ceil64(7744 × 1.25)
+    const TOKIO_SERVER_RUN_FUTURE_BUDGET: usize = 9728 + 128 + 64 + 192 + 448 + 64 + 64; // = ceil64(7744 × 1.25) + SD offer phase state + payload ranges in decode limits + SD responder + subscription expiry loop + compatibility rules + staggered offers
 
     #[tokio::test]
     async fn future_size_witness_tokio_server() {
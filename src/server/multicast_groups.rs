@@ -0,0 +1,90 @@
+//! Per-event-group multicast addresses behind
+//! [`EventPublisher::set_multicast_group`](super::EventPublisher::set_multicast_group).
+
+use core::net::SocketAddrV4;
+use heapless::Vec;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_MULTICAST_GROUPS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_MULTICAST_GROUPS_CAP: usize = 8;
+
+/// Maximum number of event groups one
+/// [`EventPublisher`](super::EventPublisher) can distribute over
+/// multicast. Sized via `SIMPLE_SOMEIP_MULTICAST_GROUPS_CAP`; defaults to
+/// 2 on bare-metal, 8 otherwise.
+pub const MULTICAST_GROUPS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_MULTICAST_GROUPS_CAP"),
+    _DEFAULT_MULTICAST_GROUPS_CAP,
+);
+
+/// `(service, instance, event_group)` and the multicast address its
+/// events are sent to.
+#[derive(Debug)]
+pub(super) struct MulticastGroups {
+    entries: Vec<((u16, u16, u16), SocketAddrV4), MULTICAST_GROUPS_CAP>,
+}
+
+impl MulticastGroups {
+    pub(super) const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set (`Some`) or clear (`None`) the multicast address of an event
+    /// group. Returns `false` if the table is full.
+    pub(super) fn set(&mut self, key: (u16, u16, u16), group: Option<SocketAddrV4>) -> bool {
+        let existing = self.entries.iter().position(|(k, _)| *k == key);
+        match (existing, group) {
+            (Some(index), Some(group)) => self.entries[index].1 = group,
+            (Some(index), None) => {
+                self.entries.swap_remove(index);
+            }
+            (None, Some(group)) => return self.entries.push((key, group)).is_ok(),
+            (None, None) => {}
+        }
+        true
+    }
+
+    pub(super) fn get(&self, key: (u16, u16, u16)) -> Option<SocketAddrV4> {
+        self.entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, group)| *group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::Ipv4Addr;
+
+    #[test]
+    fn groups_are_set_replaced_and_cleared() {
+        let mut groups = MulticastGroups::new();
+        let first = SocketAddrV4::new(Ipv4Addr::new(239, 0, 0, 1), 30_501);
+        let second = SocketAddrV4::new(Ipv4Addr::new(239, 0, 0, 2), 30_502);
+        assert!(groups.set((0x5B, 1, 0x01), Some(first)));
+        assert_eq!(groups.get((0x5B, 1, 0x01)), Some(first));
+        assert_eq!(groups.get((0x5B, 1, 0x02)), None);
+
+        assert!(groups.set((0x5B, 1, 0x01), Some(second)));
+        assert_eq!(groups.get((0x5B, 1, 0x01)), Some(second));
+
+        assert!(groups.set((0x5B, 1, 0x01), None));
+        assert_eq!(groups.get((0x5B, 1, 0x01)), None);
+    }
+
+    #[test]
+    fn a_full_table_rejects_new_groups() {
+        let mut groups = MulticastGroups::new();
+        let group = SocketAddrV4::new(Ipv4Addr::new(239, 0, 0, 1), 30_501);
+        for event_group_id in 0..MULTICAST_GROUPS_CAP {
+            let event_group_id = u16::try_from(event_group_id).unwrap();
+            assert!(groups.set((0x5B, 1, event_group_id), Some(group)));
+        }
+        assert!(!groups.set((0x5B, 1, 0xFFFF), Some(group)));
+        assert!(groups.set((0x5B, 1, 0), None), "clearing always succeeds");
+    }
+}
@@ -0,0 +1,243 @@
+//! What a publish does without subscribers, set per event with
+//! [`EventPublisher::set_no_subscriber_policy`](super::EventPublisher::set_no_subscriber_policy),
+//! and the datagrams kept for the next subscriber.
+
+use heapless::Vec;
+
+use super::sticky_events::StickyKey;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_NO_SUBSCRIBER_POLICIES_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_NO_SUBSCRIBER_POLICIES_CAP: usize = 8;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_BUFFERED_EVENTS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_BUFFERED_EVENTS_CAP: usize = 8;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_BUFFERED_EVENT_LEN: usize = 64;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_BUFFERED_EVENT_LEN: usize = crate::UDP_BUFFER_SIZE;
+
+/// Maximum number of events one [`EventPublisher`](super::EventPublisher)
+/// has a [`NoSubscriberPolicy`] other than
+/// [`Ignore`](NoSubscriberPolicy::Ignore) for. Sized via
+/// `SIMPLE_SOMEIP_NO_SUBSCRIBER_POLICIES_CAP`; defaults to 2 on
+/// bare-metal, 8 otherwise.
+pub const NO_SUBSCRIBER_POLICIES_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_NO_SUBSCRIBER_POLICIES_CAP"),
+    _DEFAULT_NO_SUBSCRIBER_POLICIES_CAP,
+);
+
+/// Maximum number of datagrams one
+/// [`EventPublisher`](super::EventPublisher) keeps, over all events, under
+/// [`NoSubscriberPolicy::Buffer`]. Sized via
+/// `SIMPLE_SOMEIP_BUFFERED_EVENTS_CAP`; defaults to 2 on bare-metal, 8
+/// otherwise.
+pub const BUFFERED_EVENTS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_BUFFERED_EVENTS_CAP"),
+    _DEFAULT_BUFFERED_EVENTS_CAP,
+);
+
+/// Largest datagram, SOME/IP header and E2E header included, kept under
+/// [`NoSubscriberPolicy::Buffer`]. Sized via
+/// `SIMPLE_SOMEIP_BUFFERED_EVENT_LEN`; defaults to 64 bytes on
+/// bare-metal, [`UDP_BUFFER_SIZE`](crate::UDP_BUFFER_SIZE) otherwise.
+pub const BUFFERED_EVENT_LEN: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_BUFFERED_EVENT_LEN"),
+    _DEFAULT_BUFFERED_EVENT_LEN,
+);
+
+/// What publishing an event does while its event group has no
+/// subscribers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NoSubscriberPolicy {
+    /// Send nothing and report `Ok(0)`.
+    #[default]
+    Ignore,
+    /// Send nothing and fail with
+    /// [`Error::NoSubscribers`](super::Error::NoSubscribers), for
+    /// producers that must know a sample went nowhere.
+    Fail,
+    /// Keep the last `n` datagrams and send them, oldest first, to the
+    /// next subscriber of the event group right after its
+    /// `SubscribeAck`, so samples published in a startup race are not
+    /// lost. Report `Ok(0)`.
+    Buffer(usize),
+}
+
+/// Whether a publish without subscribers goes on to encode the event
+/// for [`NoSubscriberPolicy::Buffer`].
+pub(super) fn keeps_datagrams(policy: NoSubscriberPolicy) -> bool {
+    matches!(policy, NoSubscriberPolicy::Buffer(n) if n > 0)
+}
+
+/// Per-event policies and the datagrams buffered under them, oldest
+/// first.
+#[derive(Debug)]
+pub(super) struct NoSubscriberEvents {
+    policies: Vec<(StickyKey, NoSubscriberPolicy), NO_SUBSCRIBER_POLICIES_CAP>,
+    buffered: Vec<(StickyKey, Vec<u8, BUFFERED_EVENT_LEN>), BUFFERED_EVENTS_CAP>,
+}
+
+impl NoSubscriberEvents {
+    pub(super) const fn new() -> Self {
+        Self {
+            policies: Vec::new(),
+            buffered: Vec::new(),
+        }
+    }
+
+    /// Set the policy of `key`, dropping buffered datagrams the new
+    /// policy no longer keeps. Returns `false` if the policy table is
+    /// full or `Buffer` asks for more than [`BUFFERED_EVENTS_CAP`].
+    pub(super) fn set(&mut self, key: StickyKey, policy: NoSubscriberPolicy) -> bool {
+        if matches!(policy, NoSubscriberPolicy::Buffer(n) if n > BUFFERED_EVENTS_CAP) {
+            return false;
+        }
+        let existing = self.policies.iter().position(|(k, _)| *k == key);
+        match (existing, policy) {
+            (Some(index), NoSubscriberPolicy::Ignore) => {
+                self.policies.swap_remove(index);
+            }
+            (Some(index), _) => self.policies[index].1 = policy,
+            (None, NoSubscriberPolicy::Ignore) => {}
+            (None, _) => {
+                if self.policies.push((key, policy)).is_err() {
+                    return false;
+                }
+            }
+        }
+        let keep = match policy {
+            NoSubscriberPolicy::Buffer(n) => n,
+            _ => 0,
+        };
+        self.trim(key, keep);
+        true
+    }
+
+    pub(super) fn policy(&self, key: StickyKey) -> NoSubscriberPolicy {
+        self.policies
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map_or(NoSubscriberPolicy::Ignore, |(_, policy)| *policy)
+    }
+
+    /// Keep `datagram` for `key` under its `Buffer` policy, dropping the
+    /// oldest one of `key` past the policy's count. Returns `false` if
+    /// `datagram` is longer than [`BUFFERED_EVENT_LEN`] or the buffer is
+    /// full of other events' datagrams.
+    pub(super) fn buffer(&mut self, key: StickyKey, datagram: &[u8]) -> bool {
+        let NoSubscriberPolicy::Buffer(keep) = self.policy(key) else {
+            return true;
+        };
+        if keep == 0 {
+            return true;
+        }
+        let Ok(value) = Vec::from_slice(datagram) else {
+            return false;
+        };
+        self.trim(key, keep - 1);
+        self.buffered.push((key, value)).is_ok()
+    }
+
+    /// Drop the oldest datagrams of `key` until at most `keep` are left.
+    fn trim(&mut self, key: StickyKey, keep: usize) {
+        let mut held = self.buffered.iter().filter(|(k, _)| *k == key).count();
+        while held > keep {
+            if let Some(index) = self.buffered.iter().position(|(k, _)| *k == key) {
+                self.buffered.remove(index);
+            }
+            held -= 1;
+        }
+    }
+
+    /// Move the oldest datagram buffered for the event group into `out`,
+    /// returning its length. `None` once there are none. A datagram that
+    /// does not fit `out` is dropped and yields `Some(0)`.
+    pub(super) fn take_buffered(
+        &mut self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        out: &mut [u8],
+    ) -> Option<usize> {
+        let index = self.buffered.iter().position(|(k, _)| {
+            k.service == service_id && k.instance == instance_id && k.event_group == event_group_id
+        })?;
+        let (_, value) = self.buffered.remove(index);
+        let Some(out) = out.get_mut(..value.len()) else {
+            return Some(0);
+        };
+        out.copy_from_slice(&value);
+        Some(value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn key(event_group_id: u16, event_id: u16) -> StickyKey {
+        StickyKey::new(0x5B, 1, event_group_id, event_id)
+    }
+
+    #[test]
+    fn the_last_n_datagrams_are_kept_per_event() {
+        let mut events = NoSubscriberEvents::new();
+        let mut out = [0u8; 8];
+        assert!(events.buffer(key(1, 0x8001), &[1]), "Ignore keeps nothing");
+        assert_eq!(events.take_buffered(0x5B, 1, 1, &mut out), None);
+
+        assert!(events.set(key(1, 0x8001), NoSubscriberPolicy::Buffer(2)));
+        for sample in 1..=3 {
+            assert!(events.buffer(key(1, 0x8001), &[sample]));
+        }
+        assert_eq!(events.take_buffered(0x5B, 1, 2, &mut out), None);
+        assert_eq!(events.take_buffered(0x5B, 1, 1, &mut out), Some(1));
+        assert_eq!(out[0], 2, "the oldest sample was dropped");
+        assert_eq!(events.take_buffered(0x5B, 1, 1, &mut out), Some(1));
+        assert_eq!(out[0], 3);
+        assert_eq!(events.take_buffered(0x5B, 1, 1, &mut out), None);
+    }
+
+    #[test]
+    fn changing_the_policy_drops_what_it_no_longer_keeps() {
+        let mut events = NoSubscriberEvents::new();
+        assert!(events.set(key(1, 0x8001), NoSubscriberPolicy::Buffer(2)));
+        assert!(events.buffer(key(1, 0x8001), &[1]));
+        assert!(events.buffer(key(1, 0x8001), &[2]));
+
+        assert!(events.set(key(1, 0x8001), NoSubscriberPolicy::Fail));
+        assert_eq!(events.policy(key(1, 0x8001)), NoSubscriberPolicy::Fail);
+        assert_eq!(events.take_buffered(0x5B, 1, 1, &mut [0u8; 8]), None);
+
+        assert!(events.set(key(1, 0x8001), NoSubscriberPolicy::Ignore));
+        assert_eq!(events.policy(key(1, 0x8001)), NoSubscriberPolicy::Ignore);
+    }
+
+    #[test]
+    fn limits_are_reported() {
+        let mut events = NoSubscriberEvents::new();
+        assert!(!events.set(
+            key(1, 0x8001),
+            NoSubscriberPolicy::Buffer(BUFFERED_EVENTS_CAP + 1)
+        ));
+        assert!(events.set(key(1, 0x8001), NoSubscriberPolicy::Buffer(1)));
+        assert!(!events.buffer(key(1, 0x8001), &[0; BUFFERED_EVENT_LEN + 1]));
+        assert_eq!(
+            events.take_buffered(0x5B, 1, 1, &mut [0u8; 8]),
+            None,
+            "oversize is not kept"
+        );
+
+        assert!(events.buffer(key(1, 0x8001), &[1, 2, 3]));
+        assert_eq!(
+            events.take_buffered(0x5B, 1, 1, &mut [0u8; 2]),
+            Some(0),
+            "too long for out"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Main-phase offer slots behind
+//! [`ServerConfig::with_staggered_offers`](super::ServerConfig::with_staggered_offers).
+
+use core::time::Duration;
+
+use super::ServerConfig;
+use super::sd_state::SdStateManager;
+
+/// When in each main-phase cycle one service is offered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfferSlot {
+    pub service_id: u16,
+    pub instance_id: u16,
+    /// Time from the start of the cycle to the offer.
+    pub offset: Duration,
+}
+
+/// The offer slots of a server: the configured service, then the hosted
+/// ones in the order they were added.
+pub type OfferSchedule = heapless::Vec<OfferSlot, { ServerConfig::ACCEPTED_OFFERS_CAP + 1 }>;
+
+/// The main-phase schedule of the services offered under `config` and
+/// hosted in `sd_state`. Staggered, the `n` services are offered one at
+/// a time, [`SdStateManager::cyclic_offer_delay`]` / n` apart; otherwise all of them go out
+/// together at the start of the cycle.
+pub(super) fn offer_schedule(config: &ServerConfig, sd_state: &SdStateManager) -> OfferSchedule {
+    let mut schedule = OfferSchedule::new();
+    // Never fails: one slot more than hosted services can take.
+    let _ = schedule.push(OfferSlot {
+        service_id: config.service_id,
+        instance_id: config.instance_id,
+        offset: Duration::ZERO,
+    });
+    for hosted in sd_state.hosted_services() {
+        let _ = schedule.push(OfferSlot {
+            service_id: hosted.service_id,
+            instance_id: hosted.instance_id,
+            offset: Duration::ZERO,
+        });
+    }
+    if config.staggered_offers {
+        let cycle = sd_state.cyclic_offer_delay(config);
+        #[allow(clippy::cast_possible_truncation)]
+        let slots = schedule.len() as u32;
+        for (index, slot) in (0u32..).zip(schedule.iter_mut()) {
+            slot.offset = cycle * index / slots;
+        }
+    }
+    schedule
+}
+
+/// The service offered in slot `index` (counted across cycles) of a
+/// staggered main phase, and the delay to the next slot. `None` unless
+/// [`ServerConfig::staggered_offers`] is set and several services are
+/// offered.
+pub(super) fn staggered_slot(
+    config: &ServerConfig,
+    sd_state: &SdStateManager,
+    index: usize,
+) -> Option<(u16, Duration)> {
+    let schedule = offer_schedule(config, sd_state);
+    if !config.staggered_offers || schedule.len() < 2 {
+        return None;
+    }
+    let index = index % schedule.len();
+    let next = schedule
+        .get(index + 1)
+        .map_or(sd_state.cyclic_offer_delay(config), |slot| slot.offset);
+    let slot = schedule[index];
+    Some((slot.service_id, next.saturating_sub(slot.offset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(service_id: u16, offset_ms: u64) -> OfferSlot {
+        OfferSlot {
+            service_id,
+            instance_id: 1,
+            offset: Duration::from_millis(offset_ms),
+        }
+    }
+
+    #[test]
+    fn slots_split_the_cycle_evenly() {
+        let config = ServerConfig::new(0x5B, 1);
+        let sd_state = SdStateManager::new();
+        for service_id in [0x5C, 0x5D, 0x5E] {
+            sd_state
+                .add_service(
+                    &config,
+                    super::super::sd_state::HostedService {
+                        service_id,
+                        instance_id: 1,
+                        major_version: 1,
+                        minor_version: 0,
+                        event_group_ids: heapless::Vec::new(),
+                    },
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            offer_schedule(&config, &sd_state),
+            [slot(0x5B, 0), slot(0x5C, 0), slot(0x5D, 0), slot(0x5E, 0)],
+            "not staggered: all together"
+        );
+
+        let config = config.with_staggered_offers(true);
+        let schedule = offer_schedule(&config, &sd_state);
+        assert_eq!(
+            schedule,
+            [
+                slot(0x5B, 0),
+                slot(0x5C, 250),
+                slot(0x5D, 500),
+                slot(0x5E, 750)
+            ]
+        );
+        let ms = Duration::from_millis;
+        assert_eq!(staggered_slot(&config, &sd_state, 1), Some((0x5C, ms(250))));
+        assert_eq!(
+            staggered_slot(&config, &sd_state, 7),
+            Some((0x5E, ms(250))),
+            "wraps to the next cycle"
+        );
+        assert_eq!(
+            staggered_slot(&config.with_staggered_offers(false), &sd_state, 1),
+            None
+        );
+    }
+}
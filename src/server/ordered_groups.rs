@@ -0,0 +1,273 @@
+//! Per-event-group publish queues behind
+//! [`EventPublisher::set_ordered_event_group`](super::EventPublisher::set_ordered_event_group).
+//!
+//! Each publish to an ordered event group draws a ticket and waits until
+//! every earlier ticket of the group has been released, so the publishes
+//! send one after another, in the order they started.
+
+use core::task::{Context, Poll, Waker};
+use heapless::Vec;
+
+use super::Error;
+use super::sd_state::LockCell;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_ORDERED_GROUPS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_ORDERED_GROUPS_CAP: usize = 8;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_ORDERED_PUBLISHES_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_ORDERED_PUBLISHES_CAP: usize = 16;
+
+/// Maximum number of event groups one
+/// [`EventPublisher`](super::EventPublisher) publishes in order. Sized
+/// via `SIMPLE_SOMEIP_ORDERED_GROUPS_CAP`; defaults to 2 on bare-metal,
+/// 8 otherwise.
+pub const ORDERED_GROUPS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_ORDERED_GROUPS_CAP"),
+    _DEFAULT_ORDERED_GROUPS_CAP,
+);
+
+/// Maximum number of publishes in flight (sending or waiting their turn)
+/// on one ordered event group. Sized via
+/// `SIMPLE_SOMEIP_ORDERED_PUBLISHES_CAP`; defaults to 4 on bare-metal,
+/// 16 otherwise.
+pub const ORDERED_PUBLISHES_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_ORDERED_PUBLISHES_CAP"),
+    _DEFAULT_ORDERED_PUBLISHES_CAP,
+);
+
+/// `(service, instance, event_group)` of an ordered event group.
+pub(super) type GroupKey = (u16, u16, u16);
+
+/// The publishes in flight on one ordered event group, oldest first. The
+/// first one holds the turn; the others keep the waker to call once it
+/// is theirs.
+#[derive(Debug)]
+struct Queue {
+    key: GroupKey,
+    tickets: Vec<(u32, Option<Waker>), ORDERED_PUBLISHES_CAP>,
+}
+
+/// The event groups published in order and their publish queues.
+#[derive(Debug)]
+pub(super) struct OrderedGroups {
+    queues: Vec<Queue, ORDERED_GROUPS_CAP>,
+    /// Shared by every group, so a ticket drawn before a group was turned
+    /// off and on again cannot be mistaken for a new one.
+    next_ticket: u32,
+}
+
+impl OrderedGroups {
+    pub(super) const fn new() -> Self {
+        Self {
+            queues: Vec::new(),
+            next_ticket: 0,
+        }
+    }
+
+    /// Publish an event group in order (`true`) or not (`false`). Returns
+    /// `None` if the table is full, else the wakers of the publishes
+    /// waiting on a group turned off, which may now go ahead.
+    pub(super) fn set(
+        &mut self,
+        key: GroupKey,
+        ordered: bool,
+    ) -> Option<Vec<Waker, ORDERED_PUBLISHES_CAP>> {
+        let existing = self.queues.iter().position(|q| q.key == key);
+        match (existing, ordered) {
+            (None, true) => {
+                let queue = Queue {
+                    key,
+                    tickets: Vec::new(),
+                };
+                self.queues.push(queue).ok()?;
+                Some(Vec::new())
+            }
+            (Some(index), false) => {
+                let queue = self.queues.swap_remove(index);
+                Some(
+                    queue
+                        .tickets
+                        .into_iter()
+                        .filter_map(|(_, waker)| waker)
+                        .collect(),
+                )
+            }
+            _ => Some(Vec::new()),
+        }
+    }
+
+    /// Queue a publish to `key`. Returns its ticket, or `None` if the
+    /// group is not published in order.
+    pub(super) fn enqueue(&mut self, key: GroupKey) -> Result<Option<u32>, Error> {
+        let Some(queue) = self.queues.iter_mut().find(|q| q.key == key) else {
+            return Ok(None);
+        };
+        let ticket = self.next_ticket;
+        queue
+            .tickets
+            .push((ticket, None))
+            .map_err(|_| Error::Capacity("ordered_publishes"))?;
+        self.next_ticket = ticket.wrapping_add(1);
+        Ok(Some(ticket))
+    }
+
+    /// Whether `ticket` holds the turn of `key`, keeping `waker` to call
+    /// when it gets it if not. A ticket no longer queued (its group was
+    /// turned off) holds the turn.
+    fn is_turn(&mut self, key: GroupKey, ticket: u32, waker: &Waker) -> bool {
+        let Some(queue) = self.queues.iter_mut().find(|q| q.key == key) else {
+            return true;
+        };
+        match queue.tickets.iter().position(|(t, _)| *t == ticket) {
+            None | Some(0) => true,
+            Some(index) => {
+                queue.tickets[index].1 = Some(waker.clone());
+                false
+            }
+        }
+    }
+
+    /// Drop `ticket` from the queue of `key`. Returns the waker of the
+    /// ticket that gets the turn, if it changed hands.
+    fn release(&mut self, key: GroupKey, ticket: u32) -> Option<Waker> {
+        let queue = self.queues.iter_mut().find(|q| q.key == key)?;
+        let index = queue.tickets.iter().position(|(t, _)| *t == ticket)?;
+        queue.tickets.remove(index);
+        if index == 0 {
+            queue
+                .tickets
+                .first_mut()
+                .and_then(|(_, waker)| waker.take())
+        } else {
+            None
+        }
+    }
+}
+
+/// A publish's place in the queue of an ordered event group. Dropping it,
+/// whether the publish finished or was cancelled, passes the turn on.
+pub(super) struct Turn<'a> {
+    groups: &'a LockCell<OrderedGroups>,
+    key: GroupKey,
+    ticket: u32,
+}
+
+impl<'a> Turn<'a> {
+    /// Queue a publish to `key`. Returns `None` if the group is not
+    /// published in order.
+    pub(super) fn enqueue(
+        groups: &'a LockCell<OrderedGroups>,
+        key: GroupKey,
+    ) -> Result<Option<Self>, Error> {
+        let ticket = groups.with(|g| g.enqueue(key)).transpose()?.flatten();
+        Ok(ticket.map(|ticket| Self {
+            groups,
+            key,
+            ticket,
+        }))
+    }
+
+    /// Wait until every publish queued before this one has finished.
+    pub(super) async fn wait(&self) {
+        core::future::poll_fn(|cx: &mut Context<'_>| {
+            if self
+                .groups
+                .with(|g| g.is_turn(self.key, self.ticket, cx.waker()))
+                .unwrap_or(true)
+            {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+    }
+}
+
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        // Wake outside the lock: the woken task may be polled right away
+        // on another thread and take it.
+        if let Some(waker) = self
+            .groups
+            .with(|g| g.release(self.key, self.ticket))
+            .flatten()
+        {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tickets_take_turns_in_queue_order() {
+        let key = (0x5B, 1, 0x01);
+        let mut groups = OrderedGroups::new();
+        assert_eq!(groups.enqueue(key).unwrap(), None);
+        assert!(groups.set(key, true).is_some());
+
+        let first = groups.enqueue(key).unwrap().unwrap();
+        let second = groups.enqueue(key).unwrap().unwrap();
+        let waker = Waker::noop();
+        assert!(groups.is_turn(key, first, waker));
+        assert!(!groups.is_turn(key, second, waker));
+
+        // Releasing the turn hands the waiting ticket's waker back.
+        assert!(groups.release(key, first).is_some());
+        assert!(groups.is_turn(key, second, waker));
+    }
+
+    #[test]
+    fn a_cancelled_waiter_does_not_hold_up_the_queue() {
+        let key = (0x5B, 1, 0x01);
+        let mut groups = OrderedGroups::new();
+        groups.set(key, true);
+        let first = groups.enqueue(key).unwrap().unwrap();
+        let second = groups.enqueue(key).unwrap().unwrap();
+        let third = groups.enqueue(key).unwrap().unwrap();
+        let waker = Waker::noop();
+
+        assert!(groups.release(key, second).is_none());
+        groups.release(key, first);
+        assert!(groups.is_turn(key, third, waker));
+    }
+
+    #[test]
+    fn turning_a_group_off_releases_its_waiters() {
+        let key = (0x5B, 1, 0x01);
+        let mut groups = OrderedGroups::new();
+        groups.set(key, true);
+        let _first = groups.enqueue(key).unwrap().unwrap();
+        let second = groups.enqueue(key).unwrap().unwrap();
+        assert!(!groups.is_turn(key, second, Waker::noop()));
+
+        assert_eq!(groups.set(key, false).map(|woken| woken.len()), Some(1));
+        assert!(groups.is_turn(key, second, Waker::noop()));
+    }
+
+    #[test]
+    fn full_tables_are_reported() {
+        let mut groups = OrderedGroups::new();
+        for event_group in 0..ORDERED_GROUPS_CAP {
+            let event_group = u16::try_from(event_group).unwrap();
+            assert!(groups.set((0x5B, 1, event_group), true).is_some());
+        }
+        assert!(groups.set((0x5B, 2, 0x01), true).is_none());
+
+        let key = (0x5B, 1, 0);
+        for _ in 0..ORDERED_PUBLISHES_CAP {
+            groups.enqueue(key).unwrap();
+        }
+        assert!(matches!(
+            groups.enqueue(key),
+            Err(Error::Capacity("ordered_publishes"))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+//! Checks a request must pass before anything answers it, and the
+//! `ERROR` sent back when it fails one.
+
+use core::net::SocketAddrV4;
+
+use crate::protocol::{Header, MessageId, MessageType, MessageTypeField, MessageView, ReturnCode};
+use crate::traits::WireFormat;
+use crate::transport::TransportSocket;
+
+use super::ServerConfig;
+use super::sd_state::SdStateManager;
+
+/// SOME/IP protocol version this server speaks; requests carrying another
+/// are answered with [`ReturnCode::WrongProtocolVersion`].
+const PROTOCOL_VERSION: u8 = 0x01;
+
+/// Check a request for `service_id` against the services the server
+/// offers: the configured one, the co-offered ones from
+/// [`ServerConfig::accepted_offers`] and, with an allocator, the hosted
+/// ones. The interface
+/// version must be the offered major version.
+pub(super) fn check_request(
+    config: &ServerConfig,
+    sd_state: &SdStateManager,
+    protocol_version: u8,
+    service_id: u16,
+    interface_version: u8,
+) -> Result<(), ReturnCode> {
+    if protocol_version != PROTOCOL_VERSION {
+        return Err(ReturnCode::WrongProtocolVersion);
+    }
+    let major_version = if service_id == config.service_id {
+        Some(config.major_version)
+    } else {
+        config
+            .accepted_offers
+            .iter()
+            .find(|offer| offer.service_id == service_id)
+            .map(|offer| offer.major_version)
+    };
+    #[cfg(feature = "_alloc")]
+    let major_version = major_version.or_else(|| sd_state.hosted_major_version(service_id));
+    #[cfg(not(feature = "_alloc"))]
+    let _ = sd_state;
+    let major_version = major_version.ok_or(ReturnCode::UnknownService)?;
+    if interface_version != major_version {
+        return Err(ReturnCode::WrongInterfaceVersion);
+    }
+    Ok(())
+}
+
+/// Answer the `REQUEST` in `view`, for which the server has no handler,
+/// with the `ERROR` [`check_request`] fails it with, or
+/// [`ReturnCode::UnknownMethod`] if it passes. Other messages are
+/// ignored.
+pub(super) async fn reject_unserved<T: TransportSocket>(
+    config: &ServerConfig,
+    socket: &T,
+    sd_state: &SdStateManager,
+    view: &MessageView<'_>,
+    source: SocketAddrV4,
+    send_buf: &mut [u8],
+) {
+    let header = view.header();
+    if header.message_type() != MessageTypeField::new(MessageType::Request, false) {
+        crate::log::trace!("Non-SD message from {} and no handler, ignoring", source);
+        return;
+    }
+    let return_code = check_request(
+        config,
+        sd_state,
+        header.protocol_version(),
+        header.message_id().service_id(),
+        header.interface_version(),
+    )
+    .err()
+    .unwrap_or(ReturnCode::UnknownMethod);
+    send_error(
+        socket,
+        sd_state,
+        &header.to_owned(),
+        return_code,
+        source,
+        send_buf,
+    )
+    .await;
+}
+
+/// Whether the request in `view` may go on to the non-SD observer. One
+/// that fails [`check_request`] may not; a `REQUEST` is then answered with
+/// the `ERROR`. Messages other than requests always may.
+pub(super) async fn admit_observed<T: TransportSocket>(
+    config: &ServerConfig,
+    socket: &T,
+    sd_state: &SdStateManager,
+    view: &MessageView<'_>,
+    source: SocketAddrV4,
+    send_buf: &mut [u8],
+) -> bool {
+    let header = view.header();
+    let message_type = header.message_type().message_type();
+    if !matches!(
+        message_type,
+        MessageType::Request | MessageType::RequestNoReturn
+    ) {
+        return true;
+    }
+    let Err(return_code) = check_request(
+        config,
+        sd_state,
+        header.protocol_version(),
+        header.message_id().service_id(),
+        header.interface_version(),
+    ) else {
+        return true;
+    };
+    if message_type == MessageType::Request {
+        send_error(
+            socket,
+            sd_state,
+            &header.to_owned(),
+            return_code,
+            source,
+            send_buf,
+        )
+        .await;
+    }
+    false
+}
+
+/// The header of the `REQUEST` at the start of `data`, read field by
+/// field so that a protocol version the parser rejects still yields it.
+/// `None` for a shorter datagram or any other message type: only a
+/// `REQUEST` is answered with an `ERROR`.
+pub(super) fn raw_request_header(data: &[u8]) -> Option<Header> {
+    let header: &[u8; crate::sd_codec::SOMEIP_HEADER_LEN] = data
+        .get(..crate::sd_codec::SOMEIP_HEADER_LEN)?
+        .try_into()
+        .ok()?;
+    let message_type = MessageTypeField::try_from(header[14]).ok()?;
+    if message_type.message_type() != MessageType::Request || message_type.is_tp() {
+        return None;
+    }
+    Some(Header::new(
+        MessageId::new(u32::from_be_bytes([
+            header[0], header[1], header[2], header[3],
+        ])),
+        u32::from_be_bytes([header[8], header[9], header[10], header[11]]),
+        header[12],
+        header[13],
+        message_type,
+        ReturnCode::Ok,
+        0,
+    ))
+}
+
+/// Answer the `REQUEST` with header `request` with an `ERROR` carrying
+/// `return_code` and no payload, echoing its IDs and versions, sent to
+/// `source` using `send_buf`.
+pub(super) async fn send_error<T: TransportSocket>(
+    socket: &T,
+    sd_state: &SdStateManager,
+    request: &Header,
+    return_code: ReturnCode,
+    source: SocketAddrV4,
+    send_buf: &mut [u8],
+) {
+    crate::log::debug!(
+        "Answering request {:?} from {} with {:?}",
+        request.message_id(),
+        source,
+        return_code
+    );
+    let error = Header::new(
+        request.message_id(),
+        request.request_id(),
+        request.protocol_version(),
+        request.interface_version(),
+        MessageTypeField::new(MessageType::Error, false),
+        return_code,
+        0,
+    );
+    let Some(frame) = send_buf.get_mut(..crate::sd_codec::SOMEIP_HEADER_LEN) else {
+        return;
+    };
+    if error.encode_to_slice(frame).is_err() {
+        return;
+    }
+    match socket.send_to(frame, source).await {
+        Ok(()) => {
+            sd_state.with_peers(|peers| peers.record_sent(core::net::SocketAddr::V4(source)));
+        }
+        Err(e) => crate::log::warn!("Error response send failed: {:?}", e),
+    }
+}
@@ -0,0 +1,427 @@
+//! Per-method request routing, installed with
+//! [`Server::set_request_router`](super::Server::set_request_router).
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::net::SocketAddrV4;
+use core::pin::Pin;
+
+use crate::e2e::E2ECheckStatus;
+use crate::protocol::{
+    ApplicationError, Header, MessageId, MessageType, MessageTypeField, MessageView, ReturnCode,
+};
+use crate::traits::WireFormat;
+use crate::transport::TransportSocket;
+
+use super::ServerConfig;
+use super::request_check::check_request;
+use super::sd_state::SdStateManager;
+
+/// A method request handed to a [`RequestHandler`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    /// Address the request came from; the reply goes back there.
+    pub source: SocketAddrV4,
+    pub service_id: u16,
+    pub method_id: u16,
+    /// Interface version from the request header, echoed in the reply.
+    pub interface_version: u8,
+    /// `true` for `REQUEST_NO_RETURN`: whatever the handler returns is
+    /// dropped.
+    pub fire_and_forget: bool,
+    /// Result of the E2E check, [`E2ECheckStatus::Unchecked`] if the
+    /// method has no E2E profile. The handler decides what a failed check
+    /// means.
+    pub e2e_status: E2ECheckStatus,
+    /// Payload, without the SOME/IP header and any E2E header.
+    pub payload: Vec<u8>,
+}
+
+/// A failed request, answered with an `ERROR` message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodError {
+    /// Return code of the `ERROR` message.
+    pub return_code: ReturnCode,
+    /// Its payload, empty unless the error carries details such as an
+    /// [`ApplicationError`].
+    pub payload: Vec<u8>,
+}
+
+impl MethodError {
+    /// An error answered with `return_code` and `error` as the payload.
+    #[must_use]
+    pub fn application(return_code: ReturnCode, error: ApplicationError) -> Self {
+        Self {
+            return_code,
+            payload: error.to_bytes().to_vec(),
+        }
+    }
+}
+
+/// An `ERROR` with `return_code` and an empty payload.
+impl From<ReturnCode> for MethodError {
+    fn from(return_code: ReturnCode) -> Self {
+        Self {
+            return_code,
+            payload: Vec::new(),
+        }
+    }
+}
+
+/// An `ERROR` with [`ReturnCode::NotOk`] carrying `error`.
+impl From<ApplicationError> for MethodError {
+    fn from(error: ApplicationError) -> Self {
+        Self::application(ReturnCode::NotOk, error)
+    }
+}
+
+/// Answers the requests for one method.
+///
+/// Resolves to the response payload, sent back as a `RESPONSE` with
+/// [`ReturnCode::Ok`], or to the [`MethodError`] sent back as an `ERROR`.
+/// Implemented for every
+/// `Fn(Request) -> impl Future<Output = Result<Vec<u8>, MethodError>>`
+/// closure; a bare [`ReturnCode`] or an [`ApplicationError`] converts into
+/// a [`MethodError`] with `.into()`.
+pub trait RequestHandler: Send + Sync + 'static {
+    fn handle(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Vec<u8>, MethodError>> + Send + '_;
+}
+
+impl<F, Fut> RequestHandler for F
+where
+    F: Fn(Request) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Vec<u8>, MethodError>> + Send + 'static,
+{
+    fn handle(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<Vec<u8>, MethodError>> + Send + '_ {
+        self(request)
+    }
+}
+
+type Reply<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>, MethodError>> + Send + 'a>>;
+
+/// Object-safe form of [`RequestHandler`], so one router can hold
+/// handlers of different types.
+trait ErasedHandler: Send + Sync {
+    fn call(&self, request: Request) -> Reply<'_>;
+}
+
+impl<H: RequestHandler> ErasedHandler for H {
+    fn call(&self, request: Request) -> Reply<'_> {
+        Box::pin(self.handle(request))
+    }
+}
+
+/// Table of [`RequestHandler`]s by method ID.
+///
+/// The server checks each request before routing it: a service it does
+/// not offer is answered with [`ReturnCode::UnknownService`], an interface
+/// version other than the offered major version with
+/// [`ReturnCode::WrongInterfaceVersion`], and a method without a route
+/// with [`ReturnCode::UnknownMethod`]. Routes apply to every service the
+/// receive loop answers, co-offered ones included.
+///
+/// ```
+/// use simple_someip::protocol::ReturnCode;
+/// use simple_someip::server::{MethodRouter, Request};
+///
+/// let router = MethodRouter::new()
+///     .route(0x0001, |request: Request| async move { Ok(request.payload) })
+///     .route(0x0002, |_: Request| async { Err(ReturnCode::NotReady.into()) });
+/// assert_eq!(router.methods().count(), 2);
+/// ```
+#[derive(Default)]
+pub struct MethodRouter {
+    routes: Vec<(u16, Box<dyn ErasedHandler>)>,
+}
+
+impl MethodRouter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route requests for `method_id` to `handler`, replacing any earlier
+    /// route for it.
+    #[must_use]
+    pub fn route(mut self, method_id: u16, handler: impl RequestHandler) -> Self {
+        self.routes.retain(|(method, _)| *method != method_id);
+        self.routes.push((method_id, Box::new(handler)));
+        self
+    }
+
+    /// Route requests for `method_id` to a built-in echo handler, which
+    /// answers each request with its own payload. Clients measure round
+    /// trips against it with
+    /// `Client::measure_latency`.
+    #[must_use]
+    pub fn echo(self, method_id: u16) -> Self {
+        self.route(
+            method_id,
+            |request: Request| async move { Ok(request.payload) },
+        )
+    }
+
+    /// Method IDs with a route, in the order they were added.
+    pub fn methods(&self) -> impl Iterator<Item = u16> + '_ {
+        self.routes.iter().map(|(method, _)| *method)
+    }
+
+    fn handler(&self, method_id: u16) -> Option<&dyn ErasedHandler> {
+        self.routes
+            .iter()
+            .find(|(method, _)| *method == method_id)
+            .map(|(_, handler)| &**handler)
+    }
+
+    /// Check `request` against `config` and the services hosted in
+    /// `sd_state`, and run its handler.
+    async fn answer(
+        &self,
+        config: &ServerConfig,
+        sd_state: &SdStateManager,
+        protocol_version: u8,
+        request: Request,
+    ) -> Result<Vec<u8>, MethodError> {
+        check_request(
+            config,
+            sd_state,
+            protocol_version,
+            request.service_id,
+            request.interface_version,
+        )?;
+        let handler = self
+            .handler(request.method_id)
+            .ok_or(ReturnCode::UnknownMethod)?;
+        handler.call(request).await
+    }
+}
+
+impl core::fmt::Debug for MethodRouter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MethodRouter")
+            .field(
+                "methods",
+                &self.routes.iter().map(|(m, _)| m).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Answer the request in `view` through `router` and send the `RESPONSE`
+/// or `ERROR` back to `source`, using `send_buf` to frame it. Messages
+/// other than requests are ignored, and a `REQUEST_NO_RETURN` is never
+/// answered. `body` is the payload after the E2E check.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn dispatch<T: TransportSocket>(
+    router: &MethodRouter,
+    config: &ServerConfig,
+    socket: &T,
+    sd_state: &SdStateManager,
+    view: &MessageView<'_>,
+    e2e_status: E2ECheckStatus,
+    body: &[u8],
+    source: SocketAddrV4,
+    send_buf: &mut [u8],
+) {
+    let header = view.header();
+    let fire_and_forget = match header.message_type().message_type() {
+        MessageType::Request => false,
+        MessageType::RequestNoReturn => true,
+        other => {
+            crate::log::trace!("Ignoring {:?} message from {}", other, source);
+            return;
+        }
+    };
+    let id = header.message_id();
+    let request = Request {
+        source,
+        service_id: id.service_id(),
+        method_id: id.method_id(),
+        interface_version: header.interface_version(),
+        fire_and_forget,
+        e2e_status,
+        payload: body.to_vec(),
+    };
+    let reply = router
+        .answer(config, sd_state, header.protocol_version(), request)
+        .await;
+    if fire_and_forget {
+        if let Err(error) = reply {
+            crate::log::debug!(
+                "REQUEST_NO_RETURN {:?} from {} failed with {:?}",
+                id,
+                source,
+                error.return_code
+            );
+        }
+        return;
+    }
+
+    send_reply(socket, sd_state, view, &reply, source, send_buf).await;
+}
+
+/// Frame `reply` to the request in `view` as a `RESPONSE` or `ERROR`,
+/// echoing its IDs and versions, and send it to `source` using
+/// `send_buf`.
+pub(super) async fn send_reply<T: TransportSocket>(
+    socket: &T,
+    sd_state: &SdStateManager,
+    view: &MessageView<'_>,
+    reply: &Result<Vec<u8>, MethodError>,
+    source: SocketAddrV4,
+    send_buf: &mut [u8],
+) {
+    let header = view.header();
+    let id = header.message_id();
+    let (message_type, return_code, payload) = match reply {
+        Ok(payload) => (MessageType::Response, ReturnCode::Ok, payload.as_slice()),
+        Err(error) => (
+            MessageType::Error,
+            error.return_code,
+            error.payload.as_slice(),
+        ),
+    };
+    let total = crate::sd_codec::SOMEIP_HEADER_LEN + payload.len();
+    let Some(frame) = send_buf.get_mut(..total) else {
+        crate::log::warn!(
+            "Response of {} bytes to {:?} exceeds the {}-byte send buffer; dropped",
+            payload.len(),
+            id,
+            send_buf.len()
+        );
+        return;
+    };
+    let response_header = Header::new(
+        MessageId::new_from_service_and_method(id.service_id(), id.method_id()),
+        header.request_id(),
+        header.protocol_version(),
+        header.interface_version(),
+        MessageTypeField::new(message_type, false),
+        return_code,
+        payload.len(),
+    );
+    let (header_bytes, payload_bytes) = frame.split_at_mut(crate::sd_codec::SOMEIP_HEADER_LEN);
+    if response_header.encode_to_slice(header_bytes).is_err() {
+        return;
+    }
+    payload_bytes.copy_from_slice(payload);
+    match socket.send_to(frame, source).await {
+        Ok(()) => {
+            sd_state.with_peers(|peers| peers.record_sent(core::net::SocketAddr::V4(source)));
+        }
+        Err(e) => crate::log::warn!("Response send to {} failed: {:?}", source, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::Ipv4Addr;
+
+    fn request(service_id: u16, method_id: u16, interface_version: u8) -> Request {
+        Request {
+            source: SocketAddrV4::new(Ipv4Addr::LOCALHOST, 40000),
+            service_id,
+            method_id,
+            interface_version,
+            fire_and_forget: false,
+            e2e_status: E2ECheckStatus::Unchecked,
+            payload: alloc::vec![1, 2, 3],
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_by_method_after_checking_service_and_versions() {
+        let config = ServerConfig::new(0x1234, 1).with_accepted_offer(0x5678, 1, 2, 1);
+        let sd_state = SdStateManager::new();
+        sd_state
+            .add_service(
+                &config,
+                super::super::sd_state::HostedService {
+                    service_id: 0x4321,
+                    instance_id: 2,
+                    major_version: 3,
+                    minor_version: 0,
+                    event_group_ids: heapless::Vec::new(),
+                },
+            )
+            .unwrap();
+        let router = MethodRouter::new()
+            .route(0x0001, |_: Request| async {
+                Err(ReturnCode::NotReady.into())
+            })
+            .route(0x0002, |_: Request| async {
+                Err(ApplicationError::new(0x8000_0000_0000_0001, 7).into())
+            })
+            .route(0x0001, |request: Request| async move {
+                Ok(request.payload.into_iter().rev().collect())
+            });
+        assert_eq!(
+            router.methods().collect::<Vec<_>>(),
+            [0x0002, 0x0001],
+            "replaced"
+        );
+
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 1, request(0x1234, 0x0001, 1))
+                .await,
+            Ok(alloc::vec![3, 2, 1])
+        );
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 1, request(0x5678, 0x0001, 2))
+                .await,
+            Ok(alloc::vec![3, 2, 1]),
+            "co-offered service"
+        );
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 1, request(0x4321, 0x0001, 3))
+                .await,
+            Ok(alloc::vec![3, 2, 1]),
+            "hosted service"
+        );
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 1, request(0x1234, 0x0003, 1))
+                .await,
+            Err(ReturnCode::UnknownMethod.into())
+        );
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 1, request(0x9999, 0x0001, 1))
+                .await,
+            Err(ReturnCode::UnknownService.into())
+        );
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 1, request(0x1234, 0x0001, 2))
+                .await,
+            Err(ReturnCode::WrongInterfaceVersion.into())
+        );
+        assert_eq!(
+            router
+                .answer(&config, &sd_state, 2, request(0x1234, 0x0001, 1))
+                .await,
+            Err(ReturnCode::WrongProtocolVersion.into())
+        );
+
+        let error = router
+            .answer(&config, &sd_state, 1, request(0x1234, 0x0002, 1))
+            .await
+            .unwrap_err();
+        assert_eq!(error.return_code, ReturnCode::NotOk);
+        assert_eq!(
+            ApplicationError::parse(&error.payload).unwrap(),
+            ApplicationError::new(0x8000_0000_0000_0001, 7)
+        );
+    }
+}
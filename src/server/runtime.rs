@@ -17,15 +17,22 @@ use core::net::SocketAddrV4;
 use futures_util::{FutureExt, future::Either, pin_mut, select_biased};
 
 use crate::Timer;
-use crate::protocol::sd::{self, Entry, Flags, OptionsCount, ServiceEntry, TransportProtocol};
+use crate::janitor::Janitor;
+use crate::protocol::sd::{self, Entry, OptionsCount};
+use crate::protocol::tp::Reassembler;
 use crate::transport::{E2ERegistryHandle, SharedHandle, TransportSocket};
 
+use super::event_publisher::EventPublisher;
+use super::request_check;
+use super::sd_responder::SdResponder;
 use super::sd_state::SdStateManager;
-use super::subscription_manager::{SubscribeError, SubscriptionHandle};
+use super::subscription_events::{SubscriptionEvent, SubscriptionEventKind};
+use super::subscription_manager::SubscriptionHandle;
 use super::{Error, ServerConfig};
 
 /// Send a unicast `OfferService` to a specific address (typically in
-/// response to a `FindService`).
+/// response to a `FindService`), with an entry for the configured service
+/// and each hosted one that `wanted(service_id, major_version)` selects.
 ///
 /// `buf` is a caller-provided scratch buffer used for encoding the outgoing
 /// frame. Returns [`Error::Capacity`]`("udp_buffer")` if the encoded frame
@@ -36,6 +43,7 @@ pub(super) async fn send_unicast_offer<T>(
     sd_socket: &T,
     sd_state: &SdStateManager,
     target: core::net::SocketAddr,
+    wanted: impl Fn(u16, u8) -> bool,
 ) -> Result<(), Error>
 where
     T: TransportSocket,
@@ -43,47 +51,38 @@ where
     use crate::protocol::Header as SomeIpHeader;
     use crate::traits::WireFormat;
 
-    let entry = Entry::OfferService(ServiceEntry {
-        index_first_options_run: 0,
-        index_second_options_run: 0,
-        options_count: OptionsCount::new(1, 0),
-        service_id: config.service_id,
-        instance_id: config.instance_id,
-        major_version: config.major_version,
-        ttl: config.ttl,
-        minor_version: config.minor_version,
-    });
-
-    let option = sd::Options::IpV4Endpoint {
-        ip: config.interface,
-        port: config.local_port,
-        protocol: TransportProtocol::Udp,
+    let options = config.endpoint_options();
+    // Encode in a block so the entries are not held across the send.
+    let total_len = {
+        // At most two endpoint, a configuration and a load-balancing option.
+        #[allow(clippy::cast_possible_truncation)]
+        let options_count = OptionsCount::new(options.len() as u8, 0)?;
+        let entries = sd_state.service_entries(config, options_count, true, wanted);
+        let (sid, reboot_flag) = sd_state.next_session_id_with_reboot_flag();
+        let sd_payload = sd::Header::new(config.sd_flags(reboot_flag), &entries, &options);
+
+        // Guard: SOME/IP header needs 16 bytes; SD payload needs the rest.
+        if buf.len() < 16 {
+            return Err(Error::Capacity("udp_buffer"));
+        }
+        let sd_data_len = sd_payload
+            .encode_to_slice(&mut buf[16..])
+            .map_err(|_| Error::Capacity("udp_buffer"))?;
+        let total_len = 16 + sd_data_len;
+        // The `< 16` guard plus `encode_to_slice`'s own over-capacity error
+        // already cover the fit; this stays as a debug-only sanity check
+        // rather than a live (dead) branch.
+        debug_assert!(total_len <= buf.len());
+        let someip_header = SomeIpHeader::new_sd(sid, sd_data_len);
+        someip_header
+            .encode_to_slice(&mut buf[..16])
+            .map_err(|_| Error::Capacity("udp_buffer"))?;
+        total_len
     };
 
-    let entries = [entry];
-    let options = [option];
-    let (sid, reboot_flag) = sd_state.next_session_id_with_reboot_flag();
-    let sd_payload = sd::Header::new(Flags::new_sd(reboot_flag), &entries, &options);
-
-    // Guard: SOME/IP header needs 16 bytes; SD payload needs the rest.
-    if buf.len() < 16 {
-        return Err(Error::Capacity("udp_buffer"));
-    }
-    let sd_data_len = sd_payload
-        .encode_to_slice(&mut buf[16..])
-        .map_err(|_| Error::Capacity("udp_buffer"))?;
-    let total_len = 16 + sd_data_len;
-    // The `< 16` guard plus `encode_to_slice`'s own over-capacity error
-    // already cover the fit; this stays as a debug-only sanity check
-    // rather than a live (dead) branch.
-    debug_assert!(total_len <= buf.len());
-    let someip_header = SomeIpHeader::new_sd(sid, sd_data_len);
-    someip_header
-        .encode_to_slice(&mut buf[..16])
-        .map_err(|_| Error::Capacity("udp_buffer"))?;
-
     let target_v4 = socket_addr_v4(target)?;
     sd_socket.send_to(&buf[..total_len], target_v4).await?;
+    sd_state.with_peers(|peers| peers.record_sent(target));
     crate::log::debug!(
         "Sent unicast OfferService to {} for service 0x{:04X}",
         target,
@@ -95,6 +94,10 @@ where
 
 /// Send `SubscribeAck` derived from a peer's `Subscribe` entry view.
 ///
+/// `major_version` is the concrete version being acknowledged: a
+/// subscriber that asked for [`sd::ANY_MAJOR_VERSION`] learns the
+/// provider's actual major version from the ack.
+///
 /// `buf` is a caller-provided scratch buffer used for encoding the outgoing
 /// frame. Returns [`Error::Capacity`]`("udp_buffer")` if the encoded frame
 /// does not fit in `buf`.
@@ -104,6 +107,7 @@ pub(super) async fn send_subscribe_ack_from_view<T>(
     sd_socket: &T,
     sd_state: &SdStateManager,
     entry_view: &sd::EntryView<'_>,
+    major_version: u8,
     subscriber: core::net::SocketAddr,
 ) -> Result<(), Error>
 where
@@ -112,21 +116,48 @@ where
     use crate::protocol::Header as SomeIpHeader;
     use crate::traits::WireFormat;
 
+    // An event group distributed over multicast tells the subscriber
+    // the group to join, as SOME/IP-SD allows in a `SubscribeAck`, unless
+    // the subscriber is a legacy stack that takes no options there.
+    let multicast = (entry_view.service_id() == config.service_id
+        && entry_view.instance_id() == config.instance_id
+        && config
+            .compatibility_profile(subscriber.ip(), entry_view.service_id())
+            .ack_options())
+    .then(|| config.event_group_multicast(entry_view.event_group_id()))
+    .flatten();
+    let multicast_option = multicast.map(|group| sd::Options::IpV4Multicast {
+        ip: *group.ip(),
+        protocol: sd::TransportProtocol::Udp,
+        port: group.port(),
+    });
+    let options = multicast_option.as_slice();
+
     let ack_entry = Entry::SubscribeAckEventGroup(sd::EventGroupEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(0, 0),
+        options_count: if multicast.is_some() {
+            OptionsCount::ONE
+        } else {
+            OptionsCount::NONE
+        },
         service_id: entry_view.service_id(),
         instance_id: entry_view.instance_id(),
-        major_version: entry_view.major_version(),
-        ttl: config.ttl,
+        major_version,
+        // A subscription made "until explicitly stopped" is granted as
+        // such: the subscriber must not be told to renew it.
+        ttl: if entry_view.ttl() >= sd::TTL_INFINITE {
+            sd::TTL_INFINITE
+        } else {
+            sd_state.effective_ttl(config.ttl)
+        },
         counter: entry_view.counter(),
         event_group_id: entry_view.event_group_id(),
     });
 
     let entries = [ack_entry];
     let (sid, reboot_flag) = sd_state.next_session_id_with_reboot_flag();
-    let sd_payload = sd::Header::new(Flags::new_sd(reboot_flag), &entries, &[]);
+    let sd_payload = sd::Header::new(config.sd_flags(reboot_flag), &entries, options);
 
     // Guard: SOME/IP header needs 16 bytes; SD payload needs the rest.
     if buf.len() < 16 {
@@ -147,6 +178,7 @@ where
 
     let subscriber_v4 = socket_addr_v4(subscriber)?;
     sd_socket.send_to(&buf[..total_len], subscriber_v4).await?;
+    sd_state.with_peers(|peers| peers.record_sent(subscriber));
 
     crate::log::debug!(
         "Sent SubscribeAck to {} for service 0x{:04X}, eventgroup 0x{:04X}",
@@ -165,7 +197,7 @@ where
 /// does not fit in `buf`.
 pub(super) async fn send_subscribe_nack_from_view<T>(
     buf: &mut [u8],
-    _config: &ServerConfig,
+    config: &ServerConfig,
     sd_socket: &T,
     sd_state: &SdStateManager,
     entry_view: &sd::EntryView<'_>,
@@ -181,7 +213,7 @@ where
     let nack_entry = Entry::SubscribeAckEventGroup(sd::EventGroupEntry {
         index_first_options_run: 0,
         index_second_options_run: 0,
-        options_count: OptionsCount::new(0, 0),
+        options_count: OptionsCount::NONE,
         service_id: entry_view.service_id(),
         instance_id: entry_view.instance_id(),
         major_version: entry_view.major_version(),
@@ -192,7 +224,7 @@ where
 
     let entries = [nack_entry];
     let (sid, reboot_flag) = sd_state.next_session_id_with_reboot_flag();
-    let sd_payload = sd::Header::new(Flags::new_sd(reboot_flag), &entries, &[]);
+    let sd_payload = sd::Header::new(config.sd_flags(reboot_flag), &entries, &[]);
 
     // Guard: SOME/IP header needs 16 bytes; SD payload needs the rest.
     if buf.len() < 16 {
@@ -213,6 +245,7 @@ where
 
     let subscriber_v4 = socket_addr_v4(subscriber)?;
     sd_socket.send_to(&buf[..total_len], subscriber_v4).await?;
+    sd_state.with_peers(|peers| peers.record_sent(subscriber));
 
     crate::log::warn!(
         "Sent SubscribeNack to {} for service 0x{:04X}, eventgroup 0x{:04X} (reason: {})",
@@ -225,250 +258,85 @@ where
     Ok(())
 }
 
-/// Handle a Service Discovery message (Subscribe / `FindService` etc.).
-#[allow(clippy::too_many_lines)]
-pub(super) async fn handle_sd_message<T, Sub>(
+/// Random delay within the configured initial-wait bounds.
+///
+/// There is no entropy source in `no_std`, so the draw mixes the clock
+/// reading (when the timer has one) with the service's identity and
+/// address: servers that start together on different ECUs, or side by
+/// side on one, still spread their first offers apart.
+pub(super) fn initial_delay(
     config: &ServerConfig,
-    sd_socket: &T,
-    sd_state: &SdStateManager,
-    subscriptions: &Sub,
-    sd_view: &sd::SdHeaderView<'_>,
-    sender: core::net::SocketAddr,
-    send_buf: &mut [u8],
-) -> Result<(), Error>
-where
-    T: TransportSocket,
-    Sub: SubscriptionHandle,
-{
-    crate::log::trace!("Handling SD message from {}", sender);
-
-    // `send_buf` is the caller-owned send scratch threaded down from
-    // `recv_loop` (which holds exactly one — only one inbound SD message
-    // is handled at a time, so only one helper send is ever in flight).
-    // It replaces the former future-resident `[u8; UDP_BUFFER_SIZE]`,
-    // keeping that buffer out of the run future's frame.
-
-    for entry_view in sd_view.entries() {
-        let entry_type = entry_view.entry_type()?;
-        match entry_type {
-            sd::EntryType::Subscribe => {
-                crate::log::debug!(
-                    "Received Subscribe from {}: service=0x{:04X}, instance={}, eventgroup=0x{:04X}",
-                    sender,
-                    entry_view.service_id(),
-                    entry_view.instance_id(),
-                    entry_view.event_group_id()
-                );
-
-                // A co-offered `(service, instance, major_version,
-                // event_group)` registered via `with_accepted_offer` is
-                // accepted on this shared recv loop even though it is not the
-                // primary service — its tuple (major version included) is
-                // fully validated by the `accepts_offer` match, so the four
-                // single-service guards below are skipped for it. Empty
-                // `accepted_offers` ⇒ `co_offered` is always false ⇒ exact
-                // single-service behaviour.
-                let co_offered = config.accepts_offer(
-                    entry_view.service_id(),
-                    entry_view.instance_id(),
-                    entry_view.major_version(),
-                    entry_view.event_group_id(),
-                );
+    now: Option<core::time::Duration>,
+) -> core::time::Duration {
+    random_delay(
+        config,
+        now,
+        config.initial_delay_min,
+        config.initial_delay_max,
+    )
+}
 
-                if !co_offered && entry_view.service_id() != config.service_id {
-                    crate::log::warn!(
-                        "Subscribe for wrong service: expected 0x{:04X}, got 0x{:04X}",
-                        config.service_id,
-                        entry_view.service_id()
-                    );
-                    send_subscribe_nack_from_view(
-                        send_buf,
-                        config,
-                        sd_socket,
-                        sd_state,
-                        &entry_view,
-                        sender,
-                        "wrong_service_id",
-                    )
-                    .await?;
-                } else if !co_offered && entry_view.instance_id() != config.instance_id {
-                    crate::log::warn!(
-                        "Subscribe for wrong instance: expected {}, got {}",
-                        config.instance_id,
-                        entry_view.instance_id()
-                    );
-                    send_subscribe_nack_from_view(
-                        send_buf,
-                        config,
-                        sd_socket,
-                        sd_state,
-                        &entry_view,
-                        sender,
-                        "wrong_instance_id",
-                    )
-                    .await?;
-                } else if !co_offered && entry_view.major_version() != config.major_version {
-                    crate::log::warn!(
-                        "Subscribe for wrong major_version: expected {}, got {}",
-                        config.major_version,
-                        entry_view.major_version()
-                    );
-                    if let Err(e) = send_subscribe_nack_from_view(
-                        send_buf,
-                        config,
-                        sd_socket,
-                        sd_state,
-                        &entry_view,
-                        sender,
-                        "wrong_major_version",
-                    )
-                    .await
-                    {
-                        crate::log::warn!("SubscribeNack send failed: {e}");
-                    }
-                } else if !co_offered && !config.accepts_event_group(entry_view.event_group_id()) {
-                    crate::log::warn!(
-                        "Subscribe for unknown event_group_id 0x{:04X} (service 0x{:04X})",
-                        entry_view.event_group_id(),
-                        entry_view.service_id()
-                    );
-                    if let Err(e) = send_subscribe_nack_from_view(
-                        send_buf,
-                        config,
-                        sd_socket,
-                        sd_state,
-                        &entry_view,
-                        sender,
-                        "unknown_event_group",
-                    )
-                    .await
-                    {
-                        crate::log::warn!("SubscribeNack send failed: {e}");
-                    }
-                } else {
-                    let first_index = entry_view.index_first_options_run() as usize;
-                    let first_count = entry_view.options_count().first_options_count as usize;
-                    let second_index = entry_view.index_second_options_run() as usize;
-                    let second_count = entry_view.options_count().second_options_count as usize;
-                    if let Some(endpoint_addr) = extract_subscriber_endpoint(
-                        &sd_view.options(),
-                        first_index,
-                        first_count,
-                        second_index,
-                        second_count,
-                    ) {
-                        let subscribe_result = subscriptions
-                            .subscribe(
-                                entry_view.service_id(),
-                                entry_view.instance_id(),
-                                entry_view.event_group_id(),
-                                endpoint_addr,
-                            )
-                            .await;
+/// Random delay within the configured request-response delay bounds,
+/// drawn as [`initial_delay`] is.
+fn find_response_delay(
+    config: &ServerConfig,
+    now: Option<core::time::Duration>,
+) -> core::time::Duration {
+    random_delay(
+        config,
+        now,
+        config.find_response_delay_min,
+        config.find_response_delay_max,
+    )
+}
 
-                        match subscribe_result {
-                            Ok(()) => {
-                                if let Err(e) = send_subscribe_ack_from_view(
-                                    send_buf,
-                                    config,
-                                    sd_socket,
-                                    sd_state,
-                                    &entry_view,
-                                    sender,
-                                )
-                                .await
-                                {
-                                    crate::log::warn!(
-                                        "SubscribeAck send failed; rolling back subscription \
-                                         (service_id=0x{:04X}, instance_id={}, \
-                                         event_group_id=0x{:04X}, error={e})",
-                                        entry_view.service_id(),
-                                        entry_view.instance_id(),
-                                        entry_view.event_group_id(),
-                                    );
-                                    subscriptions
-                                        .unsubscribe(
-                                            entry_view.service_id(),
-                                            entry_view.instance_id(),
-                                            entry_view.event_group_id(),
-                                            endpoint_addr,
-                                        )
-                                        .await;
-                                }
-                            }
-                            Err(e) => {
-                                let reason: &'static str = match e {
-                                    SubscribeError::SubscribersPerGroupFull => {
-                                        "subscribers_per_group_full"
-                                    }
-                                    SubscribeError::EventGroupsFull => "event_groups_full",
-                                };
-                                crate::log::debug!("Subscription rejected: {reason}");
-                                if let Err(e) = send_subscribe_nack_from_view(
-                                    send_buf,
-                                    config,
-                                    sd_socket,
-                                    sd_state,
-                                    &entry_view,
-                                    sender,
-                                    reason,
-                                )
-                                .await
-                                {
-                                    crate::log::warn!("SubscribeNack send failed: {e}");
-                                }
-                            }
-                        }
-                    } else {
-                        crate::log::warn!("No endpoint found in Subscribe message options");
-                        if let Err(e) = send_subscribe_nack_from_view(
-                            send_buf,
-                            config,
-                            sd_socket,
-                            sd_state,
-                            &entry_view,
-                            sender,
-                            "no_endpoint_in_options",
-                        )
-                        .await
-                        {
-                            crate::log::warn!("SubscribeNack send failed: {e}");
-                        }
-                    }
-                }
-            }
-            sd::EntryType::FindService => {
-                let find_service_id = entry_view.service_id();
-                if find_service_id == config.service_id || find_service_id == 0xFFFF {
-                    crate::log::debug!(
-                        "Received FindService from {} for service 0x{:04X} (ours: 0x{:04X}), sending unicast offer",
-                        sender,
-                        find_service_id,
-                        config.service_id
-                    );
-                    if let Err(e) =
-                        send_unicast_offer(send_buf, config, sd_socket, sd_state, sender).await
-                    {
-                        crate::log::warn!("Unicast OfferService send failed: {e}");
-                    }
-                } else {
-                    crate::log::trace!(
-                        "Ignoring FindService for service 0x{:04X} (not ours)",
-                        find_service_id
-                    );
-                }
-            }
-            _ => {
-                crate::log::trace!("Ignoring SD entry type: {:?}", entry_type);
-            }
-        }
+/// Delay between `min` and `max`, or `min` if `max` is below it.
+fn random_delay(
+    config: &ServerConfig,
+    now: Option<core::time::Duration>,
+    min: core::time::Duration,
+    max: core::time::Duration,
+) -> core::time::Duration {
+    let Some(span) = max.checked_sub(min) else {
+        return min;
+    };
+    let span_micros = u64::try_from(span.as_micros()).unwrap_or(u64::MAX);
+    if span_micros == 0 {
+        return min;
     }
+    let identity = u64::from(config.interface.to_bits()) << 32
+        | u64::from(config.service_id) << 16
+        | u64::from(config.instance_id);
+    let x = crate::jitter::draw(now, identity ^ u64::from(config.local_port).rotate_left(48));
+    min + core::time::Duration::from_micros(x % span_micros.saturating_add(1))
+}
 
-    Ok(())
+/// Delay before the next multicast offer, after `sent` offers since the
+/// initial wait ended: doubling from
+/// [`ServerConfig::repetitions_base_delay`] for the
+/// [`ServerConfig::repetitions_max`] offers of the repetition phase, then
+/// [`SdStateManager::cyclic_offer_delay`] in the main phase. `None` once the
+/// main phase is reached with a zero cyclic delay, which sends no cyclic
+/// offers.
+pub(super) fn next_offer_delay(
+    config: &ServerConfig,
+    sd_state: &SdStateManager,
+    sent: u32,
+) -> Option<core::time::Duration> {
+    if (1..=config.repetitions_max).contains(&sent) {
+        let factor = 1u32.checked_shl(sent - 1).unwrap_or(u32::MAX);
+        return Some(config.repetitions_base_delay.saturating_mul(factor));
+    }
+    Some(sd_state.cyclic_offer_delay(config)).filter(|delay| !delay.is_zero())
 }
 
-/// Periodic SD `OfferService` announcement loop. Runs forever; intended
-/// to be combined with the receive loop via [`run_combined`].
+/// SD `OfferService` announcement loop, running the offer phases of
+/// SOME/IP-SD: the initial wait of [`initial_delay`], the repetition
+/// phase and the main phase, timed by [`next_offer_delay`]. Runs forever;
+/// intended to be combined with the receive loop via [`run_combined`].
+///
+/// While the offer is withdrawn through `Server::stop_announcing` nothing
+/// is sent; once offered again, the phases start over.
 pub(super) async fn announce_loop<T, Tm>(
     config: &ServerConfig,
     sd_socket: &T,
@@ -479,50 +347,197 @@ pub(super) async fn announce_loop<T, Tm>(
     T: TransportSocket,
     Tm: Timer,
 {
+    // How often a withdrawn offer, or a main phase without cyclic offers,
+    // checks whether there is anything to send again.
+    const IDLE_TICK: core::time::Duration = core::time::Duration::from_secs(1);
+
     let mut announcement_count = 0u32;
     loop {
-        match sd_state
-            .send_offer_service(announce_send_buf, config, sd_socket)
-            .await
-        {
-            Ok(()) => {
-                announcement_count += 1;
-                if announcement_count == 1 {
-                    crate::log::info!(
-                        "Sent first SD announcement for service 0x{:04X}",
-                        config.service_id
-                    );
-                } else {
-                    crate::log::debug!(
-                        "Sent {} SD announcements for service 0x{:04X}",
-                        announcement_count,
-                        config.service_id
-                    );
+        while sd_state.is_withdrawn() {
+            timer.sleep(IDLE_TICK).await;
+        }
+        let now = timer.now();
+        let delay =
+            crate::sd_startup::spread(config.startup_spacing, now, initial_delay(config, now));
+        if !delay.is_zero() {
+            crate::log::debug!(
+                "Holding the first SD announcement for service 0x{:04X} for {:?}",
+                config.service_id,
+                delay
+            );
+            timer.sleep(delay).await;
+        }
+        let mut sent = 0u32;
+        let mut due = true;
+        let mut slot = 0usize;
+        // The full offer is encoded once per offering period, and sent
+        // again with only its session ID patched while nothing changes.
+        let mut cached_offer = None;
+        while !sd_state.is_withdrawn() {
+            // A staggered main phase offers one service per slot: the
+            // service and the delay to the next slot.
+            let stagger = (sent > config.repetitions_max
+                && !sd_state.cyclic_offer_delay(config).is_zero())
+            .then(|| super::offer_schedule::staggered_slot(config, sd_state, slot))
+            .flatten();
+            let queued_finds = sd_state.has_queued_find_answers();
+            // Without cyclic offers, `FindService`s queued for the next
+            // multicast offer still get one.
+            if due || queued_finds {
+                let result = match stagger {
+                    // Queued `FindService`s get every service.
+                    Some((service_id, _)) if !queued_finds => {
+                        cached_offer = None;
+                        sd_state
+                            .send_offer_service_of(announce_send_buf, config, sd_socket, service_id)
+                            .await
+                    }
+                    _ => {
+                        sd_state
+                            .send_cached_offer_service(
+                                announce_send_buf,
+                                config,
+                                sd_socket,
+                                &mut cached_offer,
+                            )
+                            .await
+                    }
+                };
+                match result {
+                    Ok(()) => {
+                        let find_answers = sd_state.take_queued_find_answers();
+                        if find_answers > 0 {
+                            crate::log::debug!(
+                                "SD announcement for service 0x{:04X} answered {} queued FindService(s)",
+                                config.service_id,
+                                find_answers
+                            );
+                        }
+                        announcement_count += 1;
+                        if announcement_count == 1 {
+                            crate::log::info!(
+                                "Sent first SD announcement for service 0x{:04X}",
+                                config.service_id
+                            );
+                        } else {
+                            crate::log::debug!(
+                                "Sent {} SD announcements for service 0x{:04X}",
+                                announcement_count,
+                                config.service_id
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        crate::log::error!("Failed to send OfferService: {:?}", e);
+                    }
                 }
+                sent = sent.saturating_add(1);
             }
-            Err(e) => {
-                crate::log::error!("Failed to send OfferService: {:?}", e);
-            }
+            let next = match stagger {
+                Some((_, gap)) => {
+                    slot = slot.wrapping_add(1);
+                    Some(gap)
+                }
+                None => next_offer_delay(config, sd_state, sent),
+            };
+            due = next.is_some();
+            timer.sleep(next.unwrap_or(IDLE_TICK)).await;
+        }
+    }
+}
+
+/// Subscription expiry loop — removes subscribers whose lease ran out
+/// without a renewing `Subscribe` and reports each as an
+/// [`Expired`](SubscriptionEventKind::Expired) subscription event. Idles
+/// forever on a timer without a clock, whose subscriptions never lapse.
+pub(super) async fn expiry_loop<Sub, Tm>(sd_state: &SdStateManager, subscriptions: &Sub, timer: &Tm)
+where
+    Sub: SubscriptionHandle,
+    Tm: Timer,
+{
+    // Subscriber leases are whole seconds; checking once a second ends
+    // each within a second of its TTL.
+    const EXPIRY_TICK: core::time::Duration = core::time::Duration::from_secs(1);
+
+    loop {
+        timer.sleep(EXPIRY_TICK).await;
+        let Some(now) = timer.now() else {
+            return core::future::pending().await;
+        };
+        for sub in subscriptions.expire(now).await {
+            sd_state.push_subscription_event(SubscriptionEvent {
+                kind: SubscriptionEventKind::Expired,
+                subscriber: sub.address,
+                service_id: sub.service_id,
+                instance_id: sub.instance_id,
+                event_group_id: sub.event_group_id,
+            });
+        }
+    }
+}
+
+/// Whatever answers non-SD requests. Without one, requests are answered
+/// with an `ERROR` only.
+pub(super) enum Responder {
+    /// The router installed with `Server::set_request_router`. Takes
+    /// precedence over the observer.
+    #[cfg(feature = "_alloc")]
+    Router(alloc::sync::Arc<super::MethodRouter>),
+    Observer(super::NonSdRequestCallback, usize),
+    /// Neither of the above, but an authenticator answers handshakes;
+    /// other requests get an `ERROR`.
+    #[cfg(feature = "_alloc")]
+    Authenticator,
+}
+
+impl Responder {
+    /// The responder for the next request, if any.
+    #[cfg_attr(not(feature = "_alloc"), allow(unused_variables))]
+    pub(super) fn current(
+        sd_state: &SdStateManager,
+        observer: Option<(super::NonSdRequestCallback, usize)>,
+    ) -> Option<Self> {
+        #[cfg(feature = "_alloc")]
+        if let Some(router) = sd_state.request_router() {
+            return Some(Self::Router(router));
         }
-        timer.sleep(core::time::Duration::from_secs(1)).await;
+        if let Some((cb, ctx)) = observer {
+            return Some(Self::Observer(cb, ctx));
+        }
+        #[cfg(feature = "_alloc")]
+        if sd_state.authenticator().is_some() {
+            return Some(Self::Authenticator);
+        }
+        None
     }
 }
 
 /// Dispatch a non-SD unicast request (a method call to an offered service)
-/// to the observer. If the observer produces a getter response (a
+/// to `responder`. If the observer produces a getter response (a
 /// non-negative length), frame the SOME/IP RESPONSE — echoing the request's
-/// id and protocol/interface versions — and send it back to `source`.
+/// id and protocol/interface versions — and send it back to `source`; a
+/// router answers as described on [`super::MethodRouter`]. Requests the
+/// observer must not see get an `ERROR` instead (see
+/// [`request_check::admit_observed`]).
 /// `send_buf` must be distinct from the buffer `view` borrows: the handler
 /// writes its response payload after the header slot, so they don't alias.
-async fn dispatch_non_sd_request<T: TransportSocket, R: E2ERegistryHandle>(
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+#[cfg_attr(not(feature = "_alloc"), allow(unused_variables))]
+pub(super) async fn dispatch_non_sd_request<T: TransportSocket, R: E2ERegistryHandle>(
+    config: &ServerConfig,
     unicast_socket: &T,
-    observer: (super::NonSdRequestCallback, usize),
+    sd_state: &SdStateManager,
+    responder: &Responder,
     e2e: &R,
     view: &crate::protocol::MessageView<'_>,
     source: core::net::SocketAddrV4,
     send_buf: &mut [u8],
 ) {
-    let (cb, ctx) = observer;
+    #[cfg(feature = "_alloc")]
+    if super::authentication::screen_request(unicast_socket, sd_state, view, source, send_buf).await
+    {
+        return;
+    }
     let hdr = view.header();
     let id = hdr.message_id();
     let (service_id, method_id) = (id.service_id(), id.method_id());
@@ -538,6 +553,52 @@ async fn dispatch_non_sd_request<T: TransportSocket, R: E2ERegistryHandle>(
     };
     let (status, body) =
         crate::sd_codec::check_parsed_e2e(e2e, core::net::IpAddr::V4(*source.ip()), &parsed);
+    let (cb, ctx) = match responder {
+        #[cfg(feature = "_alloc")]
+        Responder::Router(router) => {
+            super::request_handler::dispatch(
+                router,
+                config,
+                unicast_socket,
+                sd_state,
+                view,
+                status,
+                body,
+                source,
+                send_buf,
+            )
+            .await;
+            return;
+        }
+        Responder::Observer(cb, ctx) => {
+            if !request_check::admit_observed(
+                config,
+                unicast_socket,
+                sd_state,
+                view,
+                source,
+                send_buf,
+            )
+            .await
+            {
+                return;
+            }
+            (*cb, *ctx)
+        }
+        #[cfg(feature = "_alloc")]
+        Responder::Authenticator => {
+            request_check::reject_unserved(
+                config,
+                unicast_socket,
+                sd_state,
+                view,
+                source,
+                send_buf,
+            )
+            .await;
+            return;
+        }
+    };
     let resp_len = cb(
         ctx,
         source,
@@ -577,35 +638,96 @@ async fn dispatch_non_sd_request<T: TransportSocket, R: E2ERegistryHandle>(
     .is_ok()
     {
         let total = crate::sd_codec::SOMEIP_HEADER_LEN + payload_len;
-        if let Err(e) = unicast_socket.send_to(&send_buf[..total], source).await {
-            crate::log::warn!("non-SD response send failed: {:?}", e);
+        match unicast_socket.send_to(&send_buf[..total], source).await {
+            Ok(()) => {
+                sd_state.with_peers(|peers| peers.record_sent(core::net::SocketAddr::V4(source)));
+            }
+            Err(e) => crate::log::warn!("non-SD response send failed: {:?}", e),
         }
     }
 }
 
+/// Parse the SOME/IP message in `data` from `source` within
+/// [`ServerConfig::decode_limits`], under the compatibility profile of
+/// the peer and the service the message is for.
+pub(super) fn parse_datagram<'a>(
+    config: &ServerConfig,
+    data: &'a [u8],
+    source: core::net::SocketAddr,
+) -> Result<crate::protocol::MessageView<'a>, crate::protocol::Error> {
+    let service_id = match data {
+        [high, low, ..] => u16::from_be_bytes([*high, *low]),
+        _ => 0,
+    };
+    crate::protocol::MessageView::parse_with_profile(
+        data,
+        &config.decode_limits,
+        config.compatibility_profile(source.ip(), service_id),
+    )
+}
+
+/// Answer a `REQUEST` in `data` that failed to parse with `error`
+/// because of its protocol version with
+/// [`ReturnCode::WrongProtocolVersion`](crate::protocol::ReturnCode::WrongProtocolVersion).
+pub(super) async fn reject_protocol_version<T: TransportSocket>(
+    socket: &T,
+    sd_state: &SdStateManager,
+    error: &crate::protocol::Error,
+    data: &[u8],
+    source: core::net::SocketAddr,
+    send_buf: &mut [u8],
+) {
+    let (crate::protocol::Error::InvalidProtocolVersion(_), core::net::SocketAddr::V4(source)) =
+        (error, source)
+    else {
+        return;
+    };
+    if let Some(request) = request_check::raw_request_header(data) {
+        request_check::send_error(
+            socket,
+            sd_state,
+            &request,
+            crate::protocol::ReturnCode::WrongProtocolVersion,
+            source,
+            send_buf,
+        )
+        .await;
+    }
+}
+
 /// Receive loop body — drives `recv_from` on both the unicast and SD
-/// sockets, dispatches SD messages to [`handle_sd_message`] and non-SD
-/// unicast requests to [`dispatch_non_sd_request`].
-#[allow(clippy::too_many_arguments)]
-async fn recv_loop<T, Sub, R>(
+/// sockets, dispatches SD messages to an [`SdResponder`] and non-SD
+/// unicast requests to [`dispatch_non_sd_request`]. SOME/IP-TP requests
+/// are put together in `reassembler` first, or dropped without one.
+///
+/// Returns `Ok(())` once `shutdown` resolves. It is only checked while
+/// waiting for the next datagram, so a request being handled is answered
+/// before the loop ends.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+async fn recv_loop<H, T, Sub, R, Tm, S>(
     config: &ServerConfig,
     unicast_socket: &T,
     sd_socket: &T,
     sd_state: &SdStateManager,
     subscriptions: &Sub,
+    publisher: &EventPublisher<R, Sub, H, T>,
     e2e: &R,
+    timer: &Tm,
     unicast_buf: &mut [u8],
     sd_buf: &mut [u8],
     send_buf: &mut [u8],
     non_sd_observer: Option<(super::NonSdRequestCallback, usize)>,
+    mut reassembler: Option<&mut Reassembler>,
+    shutdown: S,
 ) -> Result<(), Error>
 where
-    T: TransportSocket,
+    H: SharedHandle<T>,
+    T: TransportSocket + 'static,
     Sub: SubscriptionHandle,
     R: E2ERegistryHandle,
+    Tm: Timer,
+    S: core::future::Future<Output = ()>,
 {
-    use crate::protocol::MessageView;
-
     // Iteration counter used to flip `select_biased!` arm priority
     // each turn. We can't use the pseudo-random `select!` (it needs
     // `std`), so flipping arm order each iteration approximates the
@@ -613,6 +735,14 @@ where
     // one-sided load (only-unicast or only-sd) cannot starve the
     // other arm.
     let mut prefer_sd_first = false;
+    // Only reassembly state is swept here; idle TCP connections are the
+    // TCP loop's.
+    let mut janitor = config
+        .janitor
+        .filter(|_| reassembler.is_some())
+        .map(Janitor::new);
+    let shutdown = shutdown.fuse();
+    pin_mut!(shutdown);
     loop {
         // Both arms call `TransportSocket::recv_from`, whose contract
         // (see the trait docs) requires the returned future be
@@ -627,23 +757,56 @@ where
         // select macro returns, freeing the buffer we index into
         // below. Each arm returns just `(datagram, from_unicast)`;
         // the `(len, addr, source)` derivation lives once below the
-        // select so the arm-flip pattern doesn't duplicate it.
-        let (datagram, from_unicast) = {
+        // select so the arm-flip pattern doesn't duplicate it. The
+        // janitor tick yields `None`.
+        let received = {
             let unicast_fut = unicast_socket.recv_from(&mut *unicast_buf).fuse();
             let sd_fut = sd_socket.recv_from(&mut *sd_buf).fuse();
-            pin_mut!(unicast_fut, sd_fut);
+            let tick_interval = janitor.as_ref().map(|janitor| janitor.config.interval);
+            // Also wake for the next held-back `FindService` answer.
+            let find_wait = sd_state
+                .next_delayed_find_due()
+                .zip(timer.now())
+                .map(|(due, now)| due.saturating_sub(now));
+            let tick_interval = match (tick_interval, find_wait) {
+                (Some(interval), Some(wait)) => Some(interval.min(wait)),
+                (interval, wait) => interval.or(wait),
+            };
+            let tick_fut = async {
+                match tick_interval {
+                    Some(interval) => timer.sleep(interval).await,
+                    None => core::future::pending().await,
+                }
+            }
+            .fuse();
+            pin_mut!(unicast_fut, sd_fut, tick_fut);
             if prefer_sd_first {
                 select_biased! {
-                    result = sd_fut => (result?, false),
-                    result = unicast_fut => (result?, true),
+                    () = shutdown => return Ok(()),
+                    result = sd_fut => Some((result?, false)),
+                    result = unicast_fut => Some((result?, true)),
+                    () = tick_fut => None,
                 }
             } else {
                 select_biased! {
-                    result = unicast_fut => (result?, true),
-                    result = sd_fut => (result?, false),
+                    () = shutdown => return Ok(()),
+                    result = unicast_fut => Some((result?, true)),
+                    result = sd_fut => Some((result?, false)),
+                    () = tick_fut => None,
                 }
             }
         };
+        // Swept on every turn, so a steady stream of datagrams cannot
+        // hold off the tick.
+        if let (Some(janitor), Some(reassembler), Some(now)) =
+            (janitor.as_mut(), reassembler.as_deref_mut(), timer.now())
+        {
+            sweep_reassembler(janitor, reassembler, sd_state, now);
+        }
+        answer_due_finds(config, sd_socket, sd_state, timer, send_buf).await;
+        let Some((datagram, from_unicast)) = received else {
+            continue;
+        };
         prefer_sd_first = !prefer_sd_first;
         let len = datagram.bytes_received;
         let addr = core::net::SocketAddr::V4(datagram.source);
@@ -662,10 +825,13 @@ where
             &sd_buf[..len]
         };
 
+        let now = timer.now();
+        sd_state.with_peers(|peers| peers.record_received(addr, now));
+
         crate::log::trace!("Received {} bytes from {} on {} socket", len, addr, source);
         crate::log::trace!("Raw data: {:02X?}", &data[..len.min(64_usize)]);
 
-        match MessageView::parse(data) {
+        match parse_datagram(config, data, addr) {
             Ok(view) => {
                 crate::log::trace!(
                     "SOME/IP Header: service=0x{:04X}, method=0x{:04X}, type={:?}",
@@ -679,52 +845,194 @@ where
                     match view.sd_header() {
                         Ok(sd_view) => {
                             crate::log::trace!("SD message has {} entries", sd_view.entry_count());
-                            handle_sd_message(
-                                config,
-                                sd_socket,
-                                sd_state,
-                                subscriptions,
-                                &sd_view,
-                                addr,
-                                send_buf,
-                            )
-                            .await?;
+                            let session_id = (view.header().request_id() & 0xFFFF) as u16;
+                            let rebooted = sd_state.with_peers(|peers| {
+                                peers.record_sd_session(
+                                    addr,
+                                    from_unicast,
+                                    session_id,
+                                    sd_view.flags().reboot(),
+                                )
+                            });
+                            if rebooted == Some(true) {
+                                crate::log::info!("Peer {} rebooted", addr);
+                                #[cfg(feature = "_alloc")]
+                                if let core::net::SocketAddr::V4(peer) = addr {
+                                    sd_state.set_authenticated(*peer.ip(), false);
+                                }
+                            }
+                            // Multicast `FindService`s, which arrive on the
+                            // SD socket, are answered after the
+                            // request-response delay by `answer_due_finds`.
+                            let find_delay = if from_unicast {
+                                core::time::Duration::ZERO
+                            } else {
+                                find_response_delay(config, now)
+                            };
+                            let new_subscriptions =
+                                SdResponder::new(config, sd_socket, sd_state, subscriptions)
+                                    .with_now(now)
+                                    .with_find_delay(find_delay)
+                                    .respond(&sd_view, addr, send_buf)
+                                    .await?;
+                            // Initial values follow the SubscribeAck, from
+                            // the socket events are published on: the
+                            // registered ones, then the sticky ones.
+                            for sub in &new_subscriptions {
+                                if let Err(e) = publisher
+                                    .deliver_initial_events_with_buffers(
+                                        sub.subscriber,
+                                        sub.service_id,
+                                        sub.instance_id,
+                                        sub.event_group_id,
+                                        send_buf,
+                                    )
+                                    .await
+                                {
+                                    crate::log::warn!(
+                                        "Initial event delivery to {} failed: {e}",
+                                        sub.subscriber
+                                    );
+                                }
+                                if let Err(e) = publisher
+                                    .deliver_sticky_events_with_buffers(
+                                        sub.subscriber,
+                                        sub.service_id,
+                                        sub.instance_id,
+                                        sub.event_group_id,
+                                        send_buf,
+                                    )
+                                    .await
+                                {
+                                    crate::log::warn!(
+                                        "Sticky event delivery to {} failed: {e}",
+                                        sub.subscriber
+                                    );
+                                }
+                            }
                         }
                         Err(e) => {
+                            sd_state.with_peers(|peers| peers.record_error(addr));
                             crate::log::warn!("Failed to parse SD message: {:?}", e);
                         }
                     }
                 } else if from_unicast {
                     // Non-SD unicast = a method request to an offered service.
-                    if let Some(observer) = non_sd_observer {
+                    if let Some(responder) = Responder::current(sd_state, non_sd_observer) {
                         if let core::net::SocketAddr::V4(src_v4) = addr {
-                            dispatch_non_sd_request(
-                                unicast_socket,
-                                observer,
-                                e2e,
-                                &view,
-                                src_v4,
-                                send_buf,
-                            )
-                            .await;
+                            if !view.header().message_type().is_tp() {
+                                dispatch_non_sd_request(
+                                    config,
+                                    unicast_socket,
+                                    sd_state,
+                                    &responder,
+                                    e2e,
+                                    &view,
+                                    src_v4,
+                                    send_buf,
+                                )
+                                .await;
+                                continue;
+                            }
+                            let Some(reassembler) = reassembler.as_deref_mut() else {
+                                crate::log::warn!(
+                                    "SOME/IP-TP segment from {} but no reassembler; dropped",
+                                    addr
+                                );
+                                continue;
+                            };
+                            // Without a clock nothing times out; stale
+                            // messages then hold their slot until a segment
+                            // aborts them.
+                            match reassembler.feed(addr, &view, now.unwrap_or_default()) {
+                                Ok(Some(message)) => match parse_datagram(config, message, addr) {
+                                    Ok(whole) => {
+                                        dispatch_non_sd_request(
+                                            config,
+                                            unicast_socket,
+                                            sd_state,
+                                            &responder,
+                                            e2e,
+                                            &whole,
+                                            src_v4,
+                                            send_buf,
+                                        )
+                                        .await;
+                                    }
+                                    Err(e) => {
+                                        sd_state.with_peers(|peers| peers.record_error(addr));
+                                        crate::log::warn!(
+                                            "Failed to parse reassembled message from {}: {:?}",
+                                            addr,
+                                            e
+                                        );
+                                    }
+                                },
+                                Ok(None) => {}
+                                Err(e) => {
+                                    sd_state.with_peers(|peers| peers.record_error(addr));
+                                    crate::log::warn!(
+                                        "Dropped SOME/IP-TP segment from {}: {}",
+                                        addr,
+                                        e
+                                    );
+                                }
+                            }
                         }
-                    } else {
-                        crate::log::trace!(
-                            "Non-SD unicast SOME/IP message, no observer registered — ignoring"
-                        );
+                    } else if let core::net::SocketAddr::V4(src_v4) = addr {
+                        // No observer or router: requests are answered with
+                        // the error return code they earn.
+                        request_check::reject_unserved(
+                            config,
+                            unicast_socket,
+                            sd_state,
+                            &view,
+                            src_v4,
+                            send_buf,
+                        )
+                        .await;
                     }
                 } else {
                     crate::log::trace!("Non-SD multicast SOME/IP message, ignoring");
                 }
             }
             Err(e) => {
+                sd_state.with_peers(|peers| peers.record_decode_error(addr, &e));
                 crate::log::warn!("Failed to parse SOME/IP header from {}: {:?}", addr, e);
                 crate::log::trace!("Data: {:02X?}", &data[..len.min(32)]);
+                if from_unicast {
+                    reject_protocol_version(unicast_socket, sd_state, &e, data, addr, send_buf)
+                        .await;
+                }
             }
         }
     }
 }
 
+/// Janitor sweep of the receive loop, if one is due at `now`: drop the
+/// SOME/IP-TP messages whose segments stopped coming.
+fn sweep_reassembler(
+    janitor: &mut Janitor,
+    reassembler: &mut Reassembler,
+    sd_state: &SdStateManager,
+    now: core::time::Duration,
+) {
+    if !janitor.start_sweep(now) {
+        return;
+    }
+    let dropped = reassembler.expire(now);
+    if dropped > 0 {
+        crate::log::debug!(
+            "Janitor: dropped {} incomplete SOME/IP-TP messages",
+            dropped
+        );
+    }
+    sd_state.with_janitor_stats(|stats| {
+        stats.sweeps += 1;
+        stats.tp_messages_dropped += dropped as u64;
+    });
+}
+
 /// Combined receive + announce loop. The single future returned from
 /// `Server::new` (and friends) drives this; it is also what
 /// [`Server::run_with_buffers`] resolves to once buffers are
@@ -738,13 +1046,17 @@ where
 /// and only the receive loop drives — used by the dispatcher topology
 /// where a co-located `Client` emits `OfferService` on the server's
 /// behalf.
+///
+/// Resolves to `Ok(())` once `shutdown` does, after the request being
+/// handled (if any) has been answered.
 #[allow(clippy::too_many_arguments)]
-pub(super) async fn run_combined<H, T, Sub, Hsd, Tm, R>(
+pub(super) async fn run_combined<H, T, Sub, Hsd, Hep, Tm, R, S>(
     config: ServerConfig,
     unicast_socket: H,
     sd_socket: H,
     subscriptions: Sub,
     sd_state: Hsd,
+    publisher: Hep,
     e2e: R,
     timer: Tm,
     is_passive: bool,
@@ -753,14 +1065,18 @@ pub(super) async fn run_combined<H, T, Sub, Hsd, Tm, R>(
     recv_send_buf: &mut [u8],
     announce_send_buf: &mut [u8],
     non_sd_observer: Option<(super::NonSdRequestCallback, usize)>,
+    reassembler: Option<&mut Reassembler>,
+    shutdown: S,
 ) -> Result<(), Error>
 where
     H: SharedHandle<T>,
     T: TransportSocket + 'static,
     Sub: SubscriptionHandle,
     Hsd: SharedHandle<SdStateManager>,
+    Hep: SharedHandle<EventPublisher<R, Sub, H, T>>,
     Tm: Timer,
     R: E2ERegistryHandle,
+    S: core::future::Future<Output = ()>,
 {
     if is_passive {
         crate::log::warn!(
@@ -783,26 +1099,79 @@ where
         sd,
         sd_state_ref,
         &subscriptions,
+        publisher.get(),
         &e2e,
+        &timer,
         unicast_buf,
         sd_buf,
         recv_send_buf,
         non_sd_observer,
+        reassembler,
+        shutdown,
     );
 
+    let expiry_fut = expiry_loop(sd_state_ref, &subscriptions, &timer);
+
     if config.announce {
         // Two DISTINCT send buffers: `recv_loop` and `announce_loop` run
         // concurrently under the `select` below, and both can be suspended
         // at a `send_to().await` simultaneously. Sharing one buffer would
         // mutably alias it across the two live futures — UB / corruption.
         let announce_fut = announce_loop(&config, sd, sd_state_ref, &timer, announce_send_buf);
-        pin_mut!(recv_fut, announce_fut);
-        match futures_util::future::select(recv_fut, announce_fut).await {
+        let background_fut = futures_util::future::join(announce_fut, expiry_fut);
+        pin_mut!(recv_fut, background_fut);
+        match futures_util::future::select(recv_fut, background_fut).await {
             Either::Left((recv_result, _)) => recv_result,
-            Either::Right(((), recv_pending)) => recv_pending.await,
+            Either::Right((_, recv_pending)) => recv_pending.await,
         }
     } else {
-        recv_fut.await
+        pin_mut!(recv_fut, expiry_fut);
+        match futures_util::future::select(recv_fut, expiry_fut).await {
+            Either::Left((recv_result, _)) => recv_result,
+            Either::Right(((), recv_pending)) => recv_pending.await,
+        }
+    }
+}
+
+/// Send the unicast offers answering the `FindService`s held back by
+/// [`SdResponder::with_find_delay`] that are due now. The time is taken
+/// per answer, and a `FindService` still queued when the offer was
+/// withdrawn goes unanswered.
+async fn answer_due_finds<T, Tm>(
+    config: &ServerConfig,
+    sd_socket: &T,
+    sd_state: &SdStateManager,
+    timer: &Tm,
+    send_buf: &mut [u8],
+) where
+    T: TransportSocket,
+    Tm: Timer,
+{
+    while let Some(find) = timer.now().and_then(|now| sd_state.take_due_find(now)) {
+        if sd_state.is_withdrawn() {
+            crate::log::trace!(
+                "Dropping FindService answer to {}: offer withdrawn",
+                find.target
+            );
+            continue;
+        }
+        crate::log::debug!(
+            "Sending delayed unicast offer to {} for service 0x{:04X}",
+            find.target,
+            find.service_id
+        );
+        if let Err(e) = send_unicast_offer(
+            send_buf,
+            config,
+            sd_socket,
+            sd_state,
+            find.target,
+            |service_id, major_version| find.finds(service_id, major_version),
+        )
+        .await
+        {
+            crate::log::warn!("Unicast OfferService send failed: {e}");
+        }
     }
 }
 
@@ -824,6 +1193,9 @@ pub(super) fn extract_subscriber_endpoint(
 ) -> Option<SocketAddrV4> {
     let mut first_endpoint: Option<SocketAddrV4> = None;
     let mut endpoint_count: usize = 0;
+    // IPv6 endpoints are skipped: the transport is IPv4-only. A
+    // dual-stack subscriber also lists an IPv4 one.
+    let mut first_ipv6_endpoint: Option<core::net::SocketAddrV6> = None;
     let mut ignored_other: usize = 0;
 
     let mut walk_run = |index: usize, count: usize| {
@@ -840,6 +1212,12 @@ pub(super) fn extract_subscriber_endpoint(
                         }
                     }
                 }
+                Ok(sd::OptionType::IpV6Endpoint) => {
+                    if let Ok((ip, _, port)) = option_view.as_ipv6() {
+                        first_ipv6_endpoint
+                            .get_or_insert(core::net::SocketAddrV6::new(ip, port, 0, 0));
+                    }
+                }
                 Ok(_) | Err(_) => ignored_other += 1,
             }
         }
@@ -849,6 +1227,13 @@ pub(super) fn extract_subscriber_endpoint(
     walk_run(second_index, second_count);
 
     match endpoint_count {
+        0 if first_ipv6_endpoint.is_some() => {
+            crate::log::warn!(
+                "Subscriber endpoint {} is IPv6 only; this server's transport is IPv4-only",
+                first_ipv6_endpoint.expect("guarded by is_some")
+            );
+            None
+        }
         0 => {
             crate::log::warn!(
                 "No IPv4 endpoint in options runs \
@@ -973,7 +1358,15 @@ mod tests {
         let socket = NullSocket;
         let target = subscriber_addr();
 
-        let result = send_unicast_offer(&mut [0u8; 24], &config, &socket, &sd_state, target).await;
+        let result = send_unicast_offer(
+            &mut [0u8; 24],
+            &config,
+            &socket,
+            &sd_state,
+            target,
+            |_, _| true,
+        )
+        .await;
 
         assert!(
             matches!(result, Err(Error::Capacity("udp_buffer"))),
@@ -990,7 +1383,15 @@ mod tests {
         let socket = NullSocket;
         let target = subscriber_addr();
 
-        let result = send_unicast_offer(&mut [0u8; 8], &config, &socket, &sd_state, target).await;
+        let result = send_unicast_offer(
+            &mut [0u8; 8],
+            &config,
+            &socket,
+            &sd_state,
+            target,
+            |_, _| true,
+        )
+        .await;
 
         assert!(
             matches!(result, Err(Error::Capacity("udp_buffer"))),
@@ -1012,6 +1413,7 @@ mod tests {
             &socket,
             &sd_state,
             target,
+            |_, _| true,
         )
         .await;
 
@@ -1028,7 +1430,7 @@ mod tests {
         let entry = sd::Entry::SubscribeEventGroup(sd::EventGroupEntry {
             index_first_options_run: 0,
             index_second_options_run: 0,
-            options_count: sd::OptionsCount::new(1, 0),
+            options_count: sd::OptionsCount::ONE,
             service_id: 0x1234,
             instance_id: 1,
             major_version: 1,
@@ -1073,6 +1475,7 @@ mod tests {
             &socket,
             &sd_state,
             &entry_view,
+            1,
             subscriber,
         )
         .await;
@@ -1101,6 +1504,7 @@ mod tests {
             &socket,
             &sd_state,
             &entry_view,
+            1,
             subscriber,
         )
         .await;
@@ -1163,4 +1567,372 @@ mod tests {
 
         assert!(result.is_ok(), "full-size buf must succeed, got {result:?}");
     }
+
+    /// A `SubscribeAck` for an event group distributed over multicast
+    /// carries the group in an `IPv4 Multicast` option, unless it goes to
+    /// a legacy peer; others carry none.
+    #[tokio::test]
+    async fn send_subscribe_ack_carries_the_multicast_group() {
+        use crate::protocol::{CompatibilityProfile, CompatibilityRule};
+
+        static LEGACY: [CompatibilityRule; 1] = [CompatibilityRule::for_peer(
+            core::net::IpAddr::V4(Ipv4Addr::LOCALHOST),
+            CompatibilityProfile::Legacy,
+        )];
+        let group = SocketAddrV4::new(Ipv4Addr::new(239, 0, 0, 1), 30_501);
+        let sd_state = make_sd_state();
+        let (wire, sd_len) = subscribe_wire_bytes();
+        let sd_view = sd::SdHeaderView::parse(&wire[..sd_len]).expect("parse");
+        let entry_view = sd_view.entries().next().expect("one entry");
+
+        for (config, expected) in [
+            (
+                make_config().with_event_group_multicast(0x0001, group),
+                Some(group),
+            ),
+            (
+                make_config().with_event_group_multicast(0x0002, group),
+                None,
+            ),
+            (
+                make_config()
+                    .with_event_group_multicast(0x0001, group)
+                    .with_compatibility_rules(&LEGACY),
+                None,
+            ),
+        ] {
+            let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+            send_subscribe_ack_from_view(
+                &mut buf,
+                &config,
+                &NullSocket,
+                &sd_state,
+                &entry_view,
+                1,
+                subscriber_addr(),
+            )
+            .await
+            .expect("ack sent");
+
+            let ack = sd::SdHeaderView::parse(&buf[16..]).expect("parse ack");
+            let entry = ack.entries().next().expect("one entry");
+            let options: std::vec::Vec<_> = ack
+                .options()
+                .map(|option| option.to_owned().expect("valid option"))
+                .collect();
+            match expected {
+                Some(group) => {
+                    assert_eq!(entry.options_count().first_options_count, 1);
+                    assert_eq!(
+                        options,
+                        [sd::Options::IpV4Multicast {
+                            ip: *group.ip(),
+                            protocol: sd::TransportProtocol::Udp,
+                            port: group.port(),
+                        }]
+                    );
+                }
+                None => assert!(options.is_empty()),
+            }
+        }
+    }
+
+    #[test]
+    fn initial_delay_stays_within_bounds_and_spreads_servers() {
+        use core::time::Duration;
+
+        assert_eq!(initial_delay(&make_config(), None), Duration::ZERO);
+
+        let (min, max) = (Duration::from_millis(10), Duration::from_millis(100));
+        let config = make_config().with_initial_delay(min, max);
+        let delays: std::vec::Vec<Duration> = (0..32u64)
+            .map(|i| initial_delay(&config, Some(Duration::from_nanos(i * 7919))))
+            .collect();
+        assert!(delays.iter().all(|d| (min..=max).contains(d)));
+        assert!(delays.iter().any(|d| *d != delays[0]), "draws vary");
+
+        // Without a clock, servers still differ by identity.
+        let sibling = config.clone().with_local_port(30501);
+        assert_ne!(initial_delay(&config, None), initial_delay(&sibling, None));
+
+        let inverted = make_config().with_initial_delay(max, min);
+        assert_eq!(initial_delay(&inverted, Some(Duration::from_secs(1))), max);
+    }
+
+    #[test]
+    fn find_response_delay_stays_within_bounds() {
+        use core::time::Duration;
+
+        assert_eq!(find_response_delay(&make_config(), None), Duration::ZERO);
+
+        let (min, max) = (Duration::from_millis(10), Duration::from_millis(50));
+        let config = make_config().with_find_response_delay(min, max);
+        let delays: std::vec::Vec<Duration> = (0..32u64)
+            .map(|i| find_response_delay(&config, Some(Duration::from_nanos(i * 7919))))
+            .collect();
+        assert!(delays.iter().all(|d| (min..=max).contains(d)));
+        assert!(delays.iter().any(|d| *d != delays[0]), "draws vary");
+        assert_eq!(
+            initial_delay(&config, None),
+            Duration::ZERO,
+            "the initial wait keeps its own bounds"
+        );
+    }
+
+    #[test]
+    fn offers_repeat_with_doubling_delays_then_settle_into_the_main_phase() {
+        use core::time::Duration;
+
+        let ms = Duration::from_millis;
+        let config = make_config().with_repetitions(3, ms(30));
+        let sd_state = make_sd_state();
+        let delays: std::vec::Vec<_> = (1..=5)
+            .map(|sent| next_offer_delay(&config, &sd_state, sent))
+            .collect();
+        assert_eq!(
+            delays,
+            [
+                Some(ms(30)),
+                Some(ms(60)),
+                Some(ms(120)),
+                Some(ms(1000)),
+                Some(ms(1000)),
+            ]
+        );
+
+        assert_eq!(
+            next_offer_delay(&make_config(), &sd_state, 1),
+            Some(ms(1000))
+        );
+        let silent = make_config()
+            .with_repetitions(1, ms(10))
+            .with_cyclic_offer_delay(Duration::ZERO);
+        assert_eq!(next_offer_delay(&silent, &sd_state, 1), Some(ms(10)));
+        assert_eq!(next_offer_delay(&silent, &sd_state, 2), None);
+
+        sd_state.set_cyclic_offer_delay_override(Some(ms(250)));
+        assert_eq!(
+            next_offer_delay(&silent, &sd_state, 2),
+            Some(ms(250)),
+            "the override replaces the configured delay"
+        );
+        assert_eq!(next_offer_delay(&config, &sd_state, 2), Some(ms(60)));
+        sd_state.set_cyclic_offer_delay_override(None);
+        assert_eq!(next_offer_delay(&silent, &sd_state, 2), None);
+    }
+
+    /// Socket recording the service IDs of the offers sent through it.
+    #[derive(Default)]
+    struct OfferLog(std::sync::Mutex<std::vec::Vec<std::vec::Vec<u16>>>);
+
+    impl TransportSocket for OfferLog {
+        type SendFuture<'a> = NullSend;
+        type RecvFuture<'a> = NullRecv;
+
+        fn send_to<'a>(&'a self, buf: &'a [u8], _target: SocketAddrV4) -> Self::SendFuture<'a> {
+            let sd_view = sd::SdHeaderView::parse(&buf[16..]).expect("SD message");
+            let services = sd_view.entries().map(|entry| entry.service_id()).collect();
+            self.0.lock().unwrap().push(services);
+            NullSend
+        }
+        fn recv_from<'a>(&'a self, _buf: &'a mut [u8]) -> Self::RecvFuture<'a> {
+            NullRecv
+        }
+        fn local_addr(&self) -> Result<SocketAddrV4, TransportError> {
+            Ok(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+        }
+        fn join_multicast_v4(
+            &self,
+            _group: Ipv4Addr,
+            _iface: Ipv4Addr,
+        ) -> Result<(), TransportError> {
+            Ok(())
+        }
+        fn leave_multicast_v4(
+            &self,
+            _group: Ipv4Addr,
+            _iface: Ipv4Addr,
+        ) -> Result<(), TransportError> {
+            Ok(())
+        }
+    }
+
+    /// Timer recording the sleeps asked of it, the first `limit` of which
+    /// end at once; the rest never do.
+    struct SleepLog {
+        sleeps: std::sync::Mutex<std::vec::Vec<core::time::Duration>>,
+        limit: usize,
+    }
+
+    impl Timer for SleepLog {
+        type SleepFuture<'a> = Either<core::future::Ready<()>, core::future::Pending<()>>;
+
+        fn sleep(&self, duration: core::time::Duration) -> Self::SleepFuture<'_> {
+            let mut sleeps = self.sleeps.lock().unwrap();
+            sleeps.push(duration);
+            if sleeps.len() <= self.limit {
+                Either::Left(core::future::ready(()))
+            } else {
+                Either::Right(core::future::pending())
+            }
+        }
+    }
+
+    #[test]
+    fn staggered_main_phase_offers_one_service_per_slot() {
+        use core::time::Duration;
+
+        let config = make_config()
+            .with_repetitions(1, Duration::from_millis(30))
+            .with_staggered_offers(true);
+        let sd_state = make_sd_state();
+        for service_id in [0x1235, 0x1236, 0x1237] {
+            sd_state
+                .add_service(
+                    &config,
+                    super::super::sd_state::HostedService {
+                        service_id,
+                        instance_id: 1,
+                        major_version: 1,
+                        minor_version: 0,
+                        event_group_ids: heapless::Vec::new(),
+                    },
+                )
+                .unwrap();
+        }
+        let socket = OfferLog::default();
+        let timer = SleepLog {
+            sleeps: std::sync::Mutex::new(std::vec::Vec::new()),
+            limit: 6,
+        };
+        let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+        assert!(
+            announce_loop(&config, &socket, &sd_state, &timer, &mut buf)
+                .now_or_never()
+                .is_none()
+        );
+
+        let all = std::vec![0x1234, 0x1235, 0x1236, 0x1237];
+        assert_eq!(
+            *socket.0.lock().unwrap(),
+            [
+                all.clone(),
+                all,
+                std::vec![0x1234],
+                std::vec![0x1235],
+                std::vec![0x1236],
+                std::vec![0x1237],
+                std::vec![0x1234],
+            ],
+            "first offer and repetition together, then one service per slot"
+        );
+        let ms = Duration::from_millis;
+        assert_eq!(
+            *timer.sleeps.lock().unwrap(),
+            [
+                ms(30),
+                ms(1000),
+                ms(250),
+                ms(250),
+                ms(250),
+                ms(250),
+                ms(250)
+            ]
+        );
+    }
+
+    /// Timer whose clock reads the time set in it and never moves on
+    /// its own.
+    struct ClockAt(std::sync::Mutex<core::time::Duration>);
+
+    impl Timer for ClockAt {
+        type SleepFuture<'a> = core::future::Pending<()>;
+
+        fn sleep(&self, _duration: core::time::Duration) -> Self::SleepFuture<'_> {
+            core::future::pending()
+        }
+
+        fn now(&self) -> Option<core::time::Duration> {
+            Some(*self.0.lock().unwrap())
+        }
+    }
+
+    #[cfg(feature = "server-tokio")]
+    #[test]
+    fn delayed_find_is_answered_once_due_and_only_for_our_services() {
+        use crate::traits::WireFormat;
+        use core::time::Duration;
+
+        let config = make_config();
+        let sd_state = make_sd_state();
+        let subscriptions = std::sync::Arc::new(tokio::sync::RwLock::new(
+            super::super::subscription_manager::SubscriptionManager::new(),
+        ));
+        let socket = OfferLog::default();
+        let entries = [
+            Entry::FindService(sd::ServiceEntry::find(0x1234)),
+            Entry::FindService(sd::ServiceEntry::find(0x9999)),
+        ];
+        let sd_payload = sd::Header::new(
+            sd::Flags::new_sd(sd::RebootFlag::RecentlyRebooted),
+            &entries,
+            &[],
+        );
+        let mut wire = [0u8; 512];
+        let sd_len = sd_payload.encode_to_slice(&mut wire).expect("encode");
+        let sd_view = sd::SdHeaderView::parse(&wire[..sd_len]).unwrap();
+
+        let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+        SdResponder::new(&config, &socket, &sd_state, &subscriptions)
+            .with_now(Some(Duration::from_millis(100)))
+            .with_find_delay(Duration::from_millis(50))
+            .respond(&sd_view, subscriber_addr(), &mut buf)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(socket.0.lock().unwrap().is_empty(), "not answered yet");
+        assert_eq!(
+            sd_state.next_delayed_find_due(),
+            Some(Duration::from_millis(150)),
+            "only the Find for our service is held back"
+        );
+
+        let timer = ClockAt(std::sync::Mutex::new(Duration::from_millis(149)));
+        answer_due_finds(&config, &socket, &sd_state, &timer, &mut buf)
+            .now_or_never()
+            .unwrap();
+        assert!(socket.0.lock().unwrap().is_empty());
+
+        *timer.0.lock().unwrap() = Duration::from_millis(150);
+        answer_due_finds(&config, &socket, &sd_state, &timer, &mut buf)
+            .now_or_never()
+            .unwrap();
+        assert_eq!(*socket.0.lock().unwrap(), [std::vec![0x1234]]);
+        assert_eq!(sd_state.next_delayed_find_due(), None);
+    }
+
+    #[test]
+    fn delayed_find_goes_unanswered_once_the_offer_is_withdrawn() {
+        use core::time::Duration;
+
+        let config = make_config();
+        let sd_state = make_sd_state();
+        assert!(
+            sd_state.delay_find_answer(super::super::delayed_finds::DelayedFind {
+                target: subscriber_addr(),
+                service_id: 0x1234,
+                major_version: 0xFF,
+                due: Duration::ZERO,
+            })
+        );
+        sd_state.set_withdrawn(true);
+        let socket = OfferLog::default();
+        let timer = ClockAt(std::sync::Mutex::new(Duration::ZERO));
+        let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+        answer_due_finds(&config, &socket, &sd_state, &timer, &mut buf)
+            .now_or_never()
+            .unwrap();
+        assert!(socket.0.lock().unwrap().is_empty());
+        assert_eq!(sd_state.next_delayed_find_due(), None);
+    }
 }
@@ -0,0 +1,537 @@
+//! The SOME/IP-SD responder: the part of a server that answers
+//! `FindService`s and handles `Subscribe` / `StopSubscribe` entries.
+//!
+//! [`Server`](super::Server) runs one from its receive loop. It only
+//! borrows its collaborators, so a custom server or a routing component
+//! with its own receive path can run the same SD logic over its own
+//! socket and subscription store.
+
+use super::delayed_finds::DelayedFind;
+use super::runtime::{
+    extract_subscriber_endpoint, send_subscribe_ack_from_view, send_subscribe_nack_from_view,
+    send_unicast_offer,
+};
+use super::sd_state::SdStateManager;
+use super::subscription_events::{
+    SUBSCRIPTION_EVENTS_CAP, SubscriptionEvent, SubscriptionEventKind,
+};
+use super::subscription_manager::{SubscribeError, SubscribeOutcome, SubscriptionHandle};
+use super::{Error, FindAnswer, ServerConfig, WildcardFindPolicy};
+use crate::protocol::sd;
+use crate::transport::TransportSocket;
+
+/// Subscriptions one SD message added and acknowledged, for the caller to
+/// send the sticky events of their event groups to.
+pub type NewSubscriptions = heapless::Vec<SubscriptionEvent, SUBSCRIPTION_EVENTS_CAP>;
+
+/// Answers the SD messages addressed to the services described by a
+/// [`ServerConfig`] and the ones hosted in its [`SdStateManager`].
+///
+/// `FindService`s are answered following
+/// [`ServerConfig::find_answer`] and [`ServerConfig::wildcard_find`].
+/// `Subscribe`s are checked against the offered services, their event
+/// groups and the authenticated peers, recorded in the subscription
+/// store for the TTL of the entry, and acknowledged; a failed
+/// acknowledgement rolls the subscription back, and a repeated
+/// `Subscribe` renews the lease. `StopSubscribe`s remove the subscriber. Every
+/// change is also queued as a subscription event in the state manager.
+/// Answers go out of `sd_socket`, with session IDs from the state
+/// manager.
+pub struct SdResponder<'a, T, Sub> {
+    config: &'a ServerConfig,
+    sd_socket: &'a T,
+    sd_state: &'a SdStateManager,
+    subscriptions: &'a Sub,
+    now: Option<core::time::Duration>,
+    find_delay: core::time::Duration,
+}
+
+impl<'a, T, Sub> SdResponder<'a, T, Sub>
+where
+    T: TransportSocket,
+    Sub: SubscriptionHandle,
+{
+    #[must_use]
+    pub const fn new(
+        config: &'a ServerConfig,
+        sd_socket: &'a T,
+        sd_state: &'a SdStateManager,
+        subscriptions: &'a Sub,
+    ) -> Self {
+        Self {
+            config,
+            sd_socket,
+            sd_state,
+            subscriptions,
+            now: None,
+            find_delay: core::time::Duration::ZERO,
+        }
+    }
+
+    /// Time the SD message was received, from
+    /// [`Timer::now`](crate::Timer::now). Subscriptions it adds or renews
+    /// lapse the entry's TTL after it; without one they do not lapse.
+    #[must_use]
+    pub const fn with_now(mut self, now: Option<core::time::Duration>) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// Hold back the unicast offers answering `FindService`s for `delay`
+    /// after [`Self::with_now`], queued in the state manager for the
+    /// receive loop to send once due. A `FindService` is answered right
+    /// away if `delay` is zero, the time is unknown or the queue is full.
+    #[must_use]
+    pub const fn with_find_delay(mut self, delay: core::time::Duration) -> Self {
+        self.find_delay = delay;
+        self
+    }
+
+    /// Handle every entry of the SD message `sd_view` received from
+    /// `sender`, framing answers in `send_buf`. Returns the subscriptions
+    /// it added and acknowledged. Entries other than `FindService` and
+    /// `Subscribe` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry has an unknown type or a
+    /// `SubscribeNack` for a wrong service or instance cannot be sent.
+    /// Entries before it have been handled.
+    #[allow(clippy::too_many_lines)]
+    pub async fn respond(
+        &self,
+        sd_view: &sd::SdHeaderView<'_>,
+        sender: core::net::SocketAddr,
+        send_buf: &mut [u8],
+    ) -> Result<NewSubscriptions, Error> {
+        crate::log::trace!("Handling SD message from {}", sender);
+        let mut new_subscriptions = NewSubscriptions::new();
+
+        // `send_buf` is the caller-owned send scratch threaded down from
+        // `recv_loop` (which holds exactly one — only one inbound SD message
+        // is handled at a time, so only one helper send is ever in flight).
+        // It replaces the former future-resident `[u8; UDP_BUFFER_SIZE]`,
+        // keeping that buffer out of the run future's frame.
+
+        for entry_view in sd_view.entries() {
+            let entry_type = entry_view.entry_type()?;
+            match entry_type {
+                sd::EntryType::Subscribe if entry_view.ttl() == 0 => {
+                    // StopSubscribeEventgroup: removes the subscriber keyed by
+                    // the endpoint option, through the same `unsubscribe` path
+                    // a rolled-back subscription takes. Per SD no ack is
+                    // sent, and an unknown subscriber is a no-op.
+                    let options_count = entry_view.options_count();
+                    if let Some(endpoint_addr) = extract_subscriber_endpoint(
+                        &sd_view.options(),
+                        entry_view.index_first_options_run() as usize,
+                        options_count.first_options_count as usize,
+                        entry_view.index_second_options_run() as usize,
+                        options_count.second_options_count as usize,
+                    ) {
+                        crate::log::debug!(
+                            "Received StopSubscribe from {}: service=0x{:04X}, instance={}, eventgroup=0x{:04X}",
+                            sender,
+                            entry_view.service_id(),
+                            entry_view.instance_id(),
+                            entry_view.event_group_id()
+                        );
+                        self.subscriptions
+                            .unsubscribe(
+                                entry_view.service_id(),
+                                entry_view.instance_id(),
+                                entry_view.event_group_id(),
+                                endpoint_addr,
+                            )
+                            .await;
+                        self.sd_state.push_subscription_event(SubscriptionEvent {
+                            kind: SubscriptionEventKind::Unsubscribed,
+                            subscriber: endpoint_addr,
+                            service_id: entry_view.service_id(),
+                            instance_id: entry_view.instance_id(),
+                            event_group_id: entry_view.event_group_id(),
+                        });
+                    } else {
+                        crate::log::warn!(
+                            "No endpoint found in StopSubscribe message options from {}",
+                            sender
+                        );
+                    }
+                }
+                sd::EntryType::Subscribe => {
+                    crate::log::debug!(
+                        "Received Subscribe from {}: service=0x{:04X}, instance={}, eventgroup=0x{:04X}",
+                        sender,
+                        entry_view.service_id(),
+                        entry_view.instance_id(),
+                        entry_view.event_group_id()
+                    );
+
+                    // A co-offered `(service, instance, major_version,
+                    // event_group)` registered via `with_accepted_offer` is
+                    // accepted on this shared recv loop even though it is not the
+                    // primary service — its tuple (major version included) is
+                    // fully validated by the `accepts_offer` match, so the four
+                    // single-service guards below are skipped for it. Empty
+                    // `accepted_offers` ⇒ `co_offered` is always false ⇒ exact
+                    // single-service behaviour.
+                    // Services hosted through `Server::add_service` are
+                    // validated the same way.
+                    let co_offered = self
+                        .config
+                        .accepted_offer(
+                            entry_view.service_id(),
+                            entry_view.instance_id(),
+                            entry_view.major_version(),
+                            entry_view.event_group_id(),
+                        )
+                        .map(|o| o.major_version)
+                        .or_else(|| {
+                            self.sd_state.hosted_subscription(
+                                entry_view.service_id(),
+                                entry_view.instance_id(),
+                                entry_view.major_version(),
+                                entry_view.event_group_id(),
+                            )
+                        });
+                    // The version the ack carries: a wildcard (0xFF) request
+                    // is answered with the concrete offered major version.
+                    let major_version = co_offered.unwrap_or(self.config.major_version);
+                    let co_offered = co_offered.is_some();
+
+                    if !co_offered && entry_view.service_id() != self.config.service_id {
+                        crate::log::warn!(
+                            "Subscribe for wrong service: expected 0x{:04X}, got 0x{:04X}",
+                            self.config.service_id,
+                            entry_view.service_id()
+                        );
+                        send_subscribe_nack_from_view(
+                            send_buf,
+                            self.config,
+                            self.sd_socket,
+                            self.sd_state,
+                            &entry_view,
+                            sender,
+                            "wrong_service_id",
+                        )
+                        .await?;
+                    } else if !co_offered && entry_view.instance_id() != self.config.instance_id {
+                        crate::log::warn!(
+                            "Subscribe for wrong instance: expected {}, got {}",
+                            self.config.instance_id,
+                            entry_view.instance_id()
+                        );
+                        send_subscribe_nack_from_view(
+                            send_buf,
+                            self.config,
+                            self.sd_socket,
+                            self.sd_state,
+                            &entry_view,
+                            sender,
+                            "wrong_instance_id",
+                        )
+                        .await?;
+                    } else if !co_offered
+                        && !sd::major_version_matches(
+                            self.config.major_version,
+                            entry_view.major_version(),
+                        )
+                    {
+                        crate::log::warn!(
+                            "Subscribe for wrong major_version: expected {}, got {}",
+                            self.config.major_version,
+                            entry_view.major_version()
+                        );
+                        if let Err(e) = send_subscribe_nack_from_view(
+                            send_buf,
+                            self.config,
+                            self.sd_socket,
+                            self.sd_state,
+                            &entry_view,
+                            sender,
+                            "wrong_major_version",
+                        )
+                        .await
+                        {
+                            crate::log::warn!("SubscribeNack send failed: {e}");
+                        }
+                    } else if !co_offered
+                        && !self.config.accepts_event_group(entry_view.event_group_id())
+                    {
+                        crate::log::warn!(
+                            "Subscribe for unknown event_group_id 0x{:04X} (service 0x{:04X})",
+                            entry_view.event_group_id(),
+                            entry_view.service_id()
+                        );
+                        if let Err(e) = send_subscribe_nack_from_view(
+                            send_buf,
+                            self.config,
+                            self.sd_socket,
+                            self.sd_state,
+                            &entry_view,
+                            sender,
+                            "unknown_event_group",
+                        )
+                        .await
+                        {
+                            crate::log::warn!("SubscribeNack send failed: {e}");
+                        }
+                    } else if !self.sd_state.admits(sender.ip()) {
+                        crate::log::warn!(
+                            "Subscribe from unauthenticated peer {} (service 0x{:04X})",
+                            sender,
+                            entry_view.service_id()
+                        );
+                        if let Err(e) = send_subscribe_nack_from_view(
+                            send_buf,
+                            self.config,
+                            self.sd_socket,
+                            self.sd_state,
+                            &entry_view,
+                            sender,
+                            "unauthenticated",
+                        )
+                        .await
+                        {
+                            crate::log::warn!("SubscribeNack send failed: {e}");
+                        }
+                    } else {
+                        let first_index = entry_view.index_first_options_run() as usize;
+                        let first_count = entry_view.options_count().first_options_count as usize;
+                        let second_index = entry_view.index_second_options_run() as usize;
+                        let second_count = entry_view.options_count().second_options_count as usize;
+                        if let Some(endpoint_addr) = extract_subscriber_endpoint(
+                            &sd_view.options(),
+                            first_index,
+                            first_count,
+                            second_index,
+                            second_count,
+                        ) {
+                            let subscribe_result = self
+                                .subscriptions
+                                .subscribe_for(
+                                    entry_view.service_id(),
+                                    entry_view.instance_id(),
+                                    entry_view.event_group_id(),
+                                    endpoint_addr,
+                                    entry_view.ttl(),
+                                    self.now,
+                                )
+                                .await;
+
+                            match subscribe_result {
+                                Ok(outcome) => {
+                                    if let Err(e) = send_subscribe_ack_from_view(
+                                        send_buf,
+                                        self.config,
+                                        self.sd_socket,
+                                        self.sd_state,
+                                        &entry_view,
+                                        major_version,
+                                        sender,
+                                    )
+                                    .await
+                                    {
+                                        crate::log::warn!(
+                                            "SubscribeAck send failed; rolling back subscription \
+                                             (service_id=0x{:04X}, instance_id={}, \
+                                             event_group_id=0x{:04X}, error={e})",
+                                            entry_view.service_id(),
+                                            entry_view.instance_id(),
+                                            entry_view.event_group_id(),
+                                        );
+                                        self.subscriptions
+                                            .unsubscribe(
+                                                entry_view.service_id(),
+                                                entry_view.instance_id(),
+                                                entry_view.event_group_id(),
+                                                endpoint_addr,
+                                            )
+                                            .await;
+                                    } else {
+                                        let event = SubscriptionEvent {
+                                            kind: match outcome {
+                                                SubscribeOutcome::New => {
+                                                    SubscriptionEventKind::Subscribed
+                                                }
+                                                SubscribeOutcome::Refreshed => {
+                                                    SubscriptionEventKind::Refreshed
+                                                }
+                                            },
+                                            subscriber: endpoint_addr,
+                                            service_id: entry_view.service_id(),
+                                            instance_id: entry_view.instance_id(),
+                                            event_group_id: entry_view.event_group_id(),
+                                        };
+                                        self.sd_state.push_subscription_event(event);
+                                        if outcome == SubscribeOutcome::New
+                                            && new_subscriptions.push(event).is_err()
+                                        {
+                                            crate::log::warn!(
+                                                "More than {} self.subscriptions in one SD message; \
+                                                 no sticky events for {}",
+                                                SUBSCRIPTION_EVENTS_CAP,
+                                                endpoint_addr
+                                            );
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    let reason: &'static str = match e {
+                                        SubscribeError::SubscribersPerGroupFull => {
+                                            "subscribers_per_group_full"
+                                        }
+                                        SubscribeError::EventGroupsFull => "event_groups_full",
+                                    };
+                                    crate::log::debug!("Subscription rejected: {reason}");
+                                    if let Err(e) = send_subscribe_nack_from_view(
+                                        send_buf,
+                                        self.config,
+                                        self.sd_socket,
+                                        self.sd_state,
+                                        &entry_view,
+                                        sender,
+                                        reason,
+                                    )
+                                    .await
+                                    {
+                                        crate::log::warn!("SubscribeNack send failed: {e}");
+                                    }
+                                }
+                            }
+                        } else {
+                            crate::log::warn!("No endpoint found in Subscribe message options");
+                            if let Err(e) = send_subscribe_nack_from_view(
+                                send_buf,
+                                self.config,
+                                self.sd_socket,
+                                self.sd_state,
+                                &entry_view,
+                                sender,
+                                "no_endpoint_in_options",
+                            )
+                            .await
+                            {
+                                crate::log::warn!("SubscribeNack send failed: {e}");
+                            }
+                        }
+                    }
+                }
+                sd::EntryType::FindService => {
+                    let find_service_id = entry_view.service_id();
+                    let wildcard_answered = match self.config.wildcard_find {
+                        WildcardFindPolicy::Answer => true,
+                        WildcardFindPolicy::Ignore => false,
+                        WildcardFindPolicy::AnswerIfUnicast => sd_view.flags().unicast(),
+                    };
+                    if find_service_id == 0xFFFF && !wildcard_answered {
+                        crate::log::trace!(
+                            "Ignoring wildcard FindService from {} ({:?})",
+                            sender,
+                            self.config.wildcard_find
+                        );
+                    } else if self.sd_state.is_withdrawn() {
+                        crate::log::trace!("Ignoring FindService from {}: offer withdrawn", sender);
+                    } else if !self
+                        .sd_state
+                        .service_entries(
+                            self.config,
+                            sd::OptionsCount::NONE,
+                            true,
+                            |service_id, major_version| {
+                                finds(entry_view, service_id, major_version)
+                            },
+                        )
+                        .is_empty()
+                    {
+                        match self.config.find_answer {
+                            FindAnswer::Unicast
+                                if self.delay_find_answer(
+                                    sender,
+                                    find_service_id,
+                                    entry_view.major_version(),
+                                ) =>
+                            {
+                                crate::log::debug!(
+                                    "Received FindService from {} for service 0x{:04X}, answering after {:?}",
+                                    sender,
+                                    find_service_id,
+                                    self.find_delay
+                                );
+                            }
+                            FindAnswer::Unicast => {
+                                crate::log::debug!(
+                                    "Received FindService from {} for service 0x{:04X} (ours: 0x{:04X}), sending unicast offer",
+                                    sender,
+                                    find_service_id,
+                                    self.config.service_id
+                                );
+                                if let Err(e) = send_unicast_offer(
+                                    send_buf,
+                                    self.config,
+                                    self.sd_socket,
+                                    self.sd_state,
+                                    sender,
+                                    |service_id, major_version| {
+                                        finds(entry_view, service_id, major_version)
+                                    },
+                                )
+                                .await
+                                {
+                                    crate::log::warn!("Unicast OfferService send failed: {e}");
+                                }
+                            }
+                            FindAnswer::NextMulticastOffer => {
+                                crate::log::debug!(
+                                    "Received FindService from {} for service 0x{:04X}, answering with the next multicast offer",
+                                    sender,
+                                    find_service_id
+                                );
+                                self.sd_state.queue_find_answer();
+                            }
+                        }
+                    } else {
+                        crate::log::trace!(
+                            "Ignoring FindService for service 0x{:04X} v{} (not ours)",
+                            find_service_id,
+                            entry_view.major_version()
+                        );
+                    }
+                }
+                _ => {
+                    crate::log::trace!("Ignoring SD entry type: {:?}", entry_type);
+                }
+            }
+        }
+
+        Ok(new_subscriptions)
+    }
+
+    /// Queue the unicast answer to a `FindService` from `sender` until
+    /// the find delay has passed. `false` if it is to be sent now.
+    fn delay_find_answer(
+        &self,
+        sender: core::net::SocketAddr,
+        service_id: u16,
+        major_version: u8,
+    ) -> bool {
+        let Some(now) = self.now else {
+            return false;
+        };
+        !self.find_delay.is_zero()
+            && self.sd_state.delay_find_answer(DelayedFind {
+                target: sender,
+                service_id,
+                major_version,
+                due: now.saturating_add(self.find_delay),
+            })
+    }
+}
+
+/// `true` if the `FindService` in `find` looks for `service_id` at
+/// `major_version`: by service ID or the wildcard `0xFFFF`, and by a
+/// matching or wildcard major version.
+fn finds(find: sd::EntryView<'_>, service_id: u16, major_version: u8) -> bool {
+    (find.service_id() == service_id || find.service_id() == 0xFFFF)
+        && sd::major_version_matches(major_version, find.major_version())
+}
@@ -11,13 +11,15 @@
 //! migration point for the announcement path.
 
 use core::net::SocketAddrV4;
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
-use crate::protocol::sd::{
-    self, Entry, Flags, OptionsCount, RebootFlag, ServiceEntry, TransportProtocol,
-};
+use crate::janitor::JanitorStats;
+use crate::peer_stats::PeerTable;
+use crate::protocol::sd::{self, Entry, OptionsCount, RebootFlag, ServiceEntry};
+use crate::server::subscription_events::{SubscriptionEvent, SubscriptionEventQueue};
 use crate::transport::TransportSocket;
 
+use super::delayed_finds::{DelayedFind, DelayedFinds};
 use super::{Error, ServerConfig};
 
 /// Tracks the SD session-ID counter and emits `OfferService` announcements.
@@ -36,7 +38,8 @@ use super::{Error, ServerConfig};
 pub struct SdStateManager {
     /// Packed `(has_wrapped, session_id)` state.
     ///
-    /// - bits 0..16: current session id (1..=0xFFFF, never 0).
+    /// - bits 0..16: last emitted session id (1..=0xFFFF; 0 before the
+    ///   first emission).
     /// - bit 16: `has_wrapped` flag — once set, never cleared.
     /// - bits 17..32: reserved, must remain 0.
     ///
@@ -47,14 +50,158 @@ pub struct SdStateManager {
     /// between the increment and the wrap-flag latch; with one atomic,
     /// the pair is computed in one CAS step.
     session_state: AtomicU32,
+    /// Runtime TTL override applied to every emitted offer and
+    /// `SubscribeAck`, set via [`Self::set_ttl_override`] (e.g. from a
+    /// configuration hot-reload). [`NO_TTL_OVERRIDE`] means "use
+    /// `ServerConfig::ttl`". Lives here rather than on the config
+    /// because the run-future holds its own `ServerConfig` clone, while
+    /// this manager is shared between the `Server` handle and the
+    /// run-future.
+    ttl_override: AtomicU32,
+    /// Runtime override of `ServerConfig::cyclic_offer_delay`, in
+    /// milliseconds, set via [`Self::set_cyclic_offer_delay_override`];
+    /// [`NO_DELAY_OVERRIDE`] means "use the configured delay". Shared
+    /// for the same reason as `ttl_override`.
+    cyclic_offer_delay_override: AtomicU32,
+    /// Bumped whenever what the multicast offer carries changes (the TTL
+    /// override, the hosted services), so that a cached [`OfferFrame`]
+    /// encoded before is not sent again.
+    offer_generation: AtomicU32,
+    /// `FindService`s waiting for the next multicast offer to answer
+    /// them, under [`FindAnswer::NextMulticastOffer`](super::FindAnswer).
+    queued_find_answers: AtomicU32,
+    /// `FindService`s answered by unicast once their request-response
+    /// delay has passed, queued by the receive loop and sent from it.
+    delayed_finds: LockCell<DelayedFinds>,
+    /// Set by `Server::stop_announcing`: the offer was withdrawn, so the
+    /// announcement loop sends nothing and `FindService`s go unanswered
+    /// until `Server::resume_announcing`.
+    withdrawn: AtomicBool,
+    /// Service instances hosted next to the configured one, added through
+    /// `Server::add_service`: offered in the same SD messages and accepted
+    /// for subscriptions and requests by the same receive loop.
+    services: LockCell<HostedServices>,
+    /// Per-peer traffic statistics, updated by the receive loop and read
+    /// through `Server::peer_stats`. Shared here for the same reason as
+    /// `ttl_override`.
+    peers: LockCell<PeerTable>,
+    /// Subscription lifecycle events not yet taken by
+    /// `Server::subscription_events`, pushed by the receive loop.
+    subscription_events: LockCell<SubscriptionEventQueue>,
+    /// What the janitor has collected, updated by the receive and TCP
+    /// loops and read through `Server::janitor_stats`.
+    janitor_stats: LockCell<JanitorStats>,
+    /// Router answering method requests, installed through
+    /// `Server::set_request_router` and read by the receive loops.
+    #[cfg(feature = "_alloc")]
+    request_router: LockCell<Option<alloc::sync::Arc<super::MethodRouter>>>,
+    /// Authenticator installed through `Server::set_authenticator`, and
+    /// the peers that completed its handshake.
+    #[cfg(feature = "_alloc")]
+    authentication: LockCell<super::authentication::Authentication>,
 }
 
+/// A service instance hosted by a server next to the one in its
+/// [`ServerConfig`], added through `Server::add_service`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct HostedService {
+    pub(super) service_id: u16,
+    pub(super) instance_id: u16,
+    pub(super) major_version: u8,
+    pub(super) minor_version: u32,
+    /// Event groups accepted for subscription; empty accepts any, as with
+    /// [`ServerConfig::event_group_ids`].
+    pub(super) event_group_ids: heapless::Vec<u16, { ServerConfig::EVENT_GROUP_IDS_CAP }>,
+}
+
+/// At most [`ServerConfig::ACCEPTED_OFFERS_CAP`] hosted services.
+pub(super) type HostedServices =
+    heapless::Vec<HostedService, { ServerConfig::ACCEPTED_OFFERS_CAP }>;
+
+/// Entries of one SD message offering the configured service and its
+/// hosted ones.
+pub(super) type ServiceEntries = heapless::Vec<Entry, { ServerConfig::ACCEPTED_OFFERS_CAP + 1 }>;
+
+/// Lock around state shared between the `Server` handle and the
+/// run-future: a `std::sync::Mutex` on std, an embassy-sync
+/// critical-section mutex on bare-metal. Builds with neither have no lock
+/// to share the state with, and keep none.
+pub(super) struct LockCell<T> {
+    #[cfg(feature = "std")]
+    value: std::sync::Mutex<T>,
+    #[cfg(all(feature = "bare_metal", not(feature = "std")))]
+    value: embassy_sync::blocking_mutex::Mutex<
+        embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex,
+        core::cell::RefCell<T>,
+    >,
+    #[cfg(not(any(feature = "std", feature = "bare_metal")))]
+    value: core::marker::PhantomData<T>,
+}
+
+impl<T> LockCell<T> {
+    // `value` is dropped by builds without a lock backend.
+    #[allow(clippy::needless_pass_by_value)]
+    pub(super) const fn new(value: T) -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            value: std::sync::Mutex::new(value),
+            #[cfg(all(feature = "bare_metal", not(feature = "std")))]
+            value: embassy_sync::blocking_mutex::Mutex::new(core::cell::RefCell::new(value)),
+            #[cfg(not(any(feature = "std", feature = "bare_metal")))]
+            value: {
+                core::mem::forget(value);
+                core::marker::PhantomData
+            },
+        }
+    }
+
+    // `Option` and `self` are only needed by builds without a lock backend.
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    pub(super) fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        #[cfg(feature = "std")]
+        {
+            // A panic while holding the lock leaves plain counters and
+            // queued events behind; nothing to repair.
+            let mut value = self
+                .value
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            Some(f(&mut value))
+        }
+        #[cfg(all(feature = "bare_metal", not(feature = "std")))]
+        {
+            Some(self.value.lock(|value| f(&mut value.borrow_mut())))
+        }
+        #[cfg(not(any(feature = "std", feature = "bare_metal")))]
+        {
+            let _ = f;
+            None
+        }
+    }
+}
+
+impl<T> core::fmt::Debug for LockCell<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LockCell").finish_non_exhaustive()
+    }
+}
+
+/// Sentinel for "no override". SD TTLs are 24-bit on the wire, so
+/// `u32::MAX` can never be a real override value.
+const NO_TTL_OVERRIDE: u32 = u32::MAX;
+
+/// Sentinel for "no cyclic offer delay override"; overrides saturate
+/// just below it.
+const NO_DELAY_OVERRIDE: u32 = u32::MAX;
+
 const SID_MASK: u32 = 0xFFFF;
 const WRAPPED_BIT: u32 = 1 << 16;
 
 impl SdStateManager {
-    /// Construct an `SdStateManager` with a fresh session counter
-    /// (starts at `1`, reboot flag = `RecentlyRebooted`).
+    /// Construct an `SdStateManager` with a fresh session counter: the
+    /// first SD message carries session ID `1` and, like every message
+    /// until the counter wraps, the `RecentlyRebooted` flag, so peers
+    /// detect the restart.
     ///
     /// `const fn` so consumers can declare a `static`-storage instance
     /// without an allocator:
@@ -66,7 +213,7 @@ impl SdStateManager {
     /// ```
     #[must_use]
     pub const fn new() -> Self {
-        Self::with_initial(1)
+        Self::with_initial(0)
     }
 }
 
@@ -86,12 +233,407 @@ impl SdStateManager {
     /// `tests/vsomeip_sd_compat.rs`'s wire-format checks — can
     /// pre-seed counter state to validate wrap-around behaviour
     /// without driving a full Server lifecycle. Production callers
-    /// should use [`Self::new`].
+    /// should use [`Self::new`]. The first SD message carries session
+    /// ID `initial + 1`.
     #[must_use]
     pub const fn with_initial(initial: u16) -> Self {
         Self {
             // has_wrapped starts false; session_id starts at `initial`.
             session_state: AtomicU32::new(initial as u32),
+            ttl_override: AtomicU32::new(NO_TTL_OVERRIDE),
+            cyclic_offer_delay_override: AtomicU32::new(NO_DELAY_OVERRIDE),
+            offer_generation: AtomicU32::new(0),
+            queued_find_answers: AtomicU32::new(0),
+            delayed_finds: LockCell::new(DelayedFinds::new()),
+            withdrawn: AtomicBool::new(false),
+            services: LockCell::new(heapless::Vec::new()),
+            peers: LockCell::new(PeerTable::new()),
+            subscription_events: LockCell::new(SubscriptionEventQueue::new()),
+            janitor_stats: LockCell::new(JanitorStats {
+                sweeps: 0,
+                tcp_connections_closed: 0,
+                discovery_entries_pruned: 0,
+                tp_messages_dropped: 0,
+            }),
+            #[cfg(feature = "_alloc")]
+            request_router: LockCell::new(None),
+            #[cfg(feature = "_alloc")]
+            authentication: LockCell::new(super::authentication::Authentication::new()),
+        }
+    }
+
+    /// Run `f` against the peer table. Returns `None` on builds with
+    /// neither `std` nor `bare_metal`, which keep no peer statistics.
+    pub fn with_peers<R>(&self, f: impl FnOnce(&mut PeerTable) -> R) -> Option<R> {
+        self.peers.with(f)
+    }
+
+    /// Run `f` against the janitor counters. Returns `None` on builds
+    /// with neither `std` nor `bare_metal`, as [`Self::with_peers`].
+    pub fn with_janitor_stats<R>(&self, f: impl FnOnce(&mut JanitorStats) -> R) -> Option<R> {
+        self.janitor_stats.with(f)
+    }
+
+    /// Install (`Some`) or remove (`None`) the request router.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn set_request_router(&self, router: Option<alloc::sync::Arc<super::MethodRouter>>) {
+        self.request_router.with(|current| *current = router);
+    }
+
+    /// The installed request router, if any.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn request_router(&self) -> Option<alloc::sync::Arc<super::MethodRouter>> {
+        self.request_router.with(|router| router.clone()).flatten()
+    }
+
+    /// Install (`Some`) or remove (`None`) the peer authenticator.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn set_authenticator(
+        &self,
+        authenticator: Option<alloc::sync::Arc<super::PeerAuthenticator>>,
+    ) {
+        self.authentication
+            .with(|authentication| authentication.set_authenticator(authenticator));
+    }
+
+    /// The installed peer authenticator, if any.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn authenticator(&self) -> Option<alloc::sync::Arc<super::PeerAuthenticator>> {
+        self.authentication
+            .with(|authentication| authentication.authenticator())
+            .flatten()
+    }
+
+    /// Mark `peer` as having completed (`true`) or failed (`false`) the
+    /// authenticator's handshake.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn set_authenticated(&self, peer: core::net::Ipv4Addr, authenticated: bool) {
+        self.authentication
+            .with(|authentication| authentication.set_authenticated(peer, authenticated));
+    }
+
+    /// `true` if subscriptions and requests from `peer` are accepted: no
+    /// authenticator is installed, or `peer` completed its handshake.
+    /// Always `true` on builds without an allocator.
+    #[cfg_attr(not(feature = "_alloc"), allow(clippy::unused_self, unused_variables))]
+    pub(super) fn admits(&self, peer: core::net::IpAddr) -> bool {
+        #[cfg(feature = "_alloc")]
+        {
+            let core::net::IpAddr::V4(peer) = peer else {
+                return self.authenticator().is_none();
+            };
+            self.authentication
+                .with(|authentication| authentication.admits(peer))
+                .unwrap_or(true)
+        }
+        #[cfg(not(feature = "_alloc"))]
+        {
+            true
+        }
+    }
+
+    /// Record a `FindService` left for the next multicast offer to
+    /// answer.
+    pub(super) fn queue_find_answer(&self) {
+        self.queued_find_answers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// `true` if a `FindService` is waiting for the next multicast offer.
+    pub(super) fn has_queued_find_answers(&self) -> bool {
+        self.queued_find_answers.load(Ordering::Relaxed) > 0
+    }
+
+    /// Number of `FindService`s queued since the last call, which the
+    /// multicast offer just sent has answered.
+    pub(super) fn take_queued_find_answers(&self) -> u32 {
+        self.queued_find_answers.swap(0, Ordering::Relaxed)
+    }
+
+    /// Hold back the unicast answer to a `FindService` until `find.due`.
+    /// Returns `false` if it must be answered right away: the queue is
+    /// full, or the build keeps no shared state.
+    pub(super) fn delay_find_answer(&self, find: DelayedFind) -> bool {
+        self.delayed_finds
+            .with(|finds| finds.push(find))
+            .unwrap_or(false)
+    }
+
+    /// When the earliest held-back `FindService` is due.
+    pub(super) fn next_delayed_find_due(&self) -> Option<core::time::Duration> {
+        self.delayed_finds.with(|finds| finds.next_due()).flatten()
+    }
+
+    /// Remove and return one held-back `FindService` due at `now`.
+    pub(super) fn take_due_find(&self, now: core::time::Duration) -> Option<DelayedFind> {
+        self.delayed_finds
+            .with(|finds| finds.take_due(now))
+            .flatten()
+    }
+
+    /// Mark the offer withdrawn (`true`) or offered again (`false`).
+    pub(super) fn set_withdrawn(&self, withdrawn: bool) {
+        self.withdrawn.store(withdrawn, Ordering::Release);
+    }
+
+    /// `true` while the offer is withdrawn.
+    pub(super) fn is_withdrawn(&self) -> bool {
+        self.withdrawn.load(Ordering::Acquire)
+    }
+
+    /// Host `service` next to the configured one.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidUsage`]`("duplicate_service_instance")` if the
+    /// service instance is already offered, and
+    /// [`Error::Capacity`]`("hosted_services")` if
+    /// [`ServerConfig::ACCEPTED_OFFERS_CAP`] services are hosted already
+    /// or the build keeps no shared state.
+    #[cfg(feature = "std")]
+    pub(super) fn add_service(
+        &self,
+        config: &ServerConfig,
+        service: HostedService,
+    ) -> Result<(), Error> {
+        let is_configured =
+            service.service_id == config.service_id && service.instance_id == config.instance_id;
+        self.services
+            .with(|services| {
+                if is_configured
+                    || services.iter().any(|hosted| {
+                        hosted.service_id == service.service_id
+                            && hosted.instance_id == service.instance_id
+                    })
+                {
+                    return Err(Error::InvalidUsage("duplicate_service_instance"));
+                }
+                services
+                    .push(service)
+                    .map_err(|_| Error::Capacity("hosted_services"))?;
+                self.offer_generation.fetch_add(1, Ordering::AcqRel);
+                Ok(())
+            })
+            .unwrap_or(Err(Error::Capacity("hosted_services")))
+    }
+
+    /// Stop hosting the service instance `(service_id, instance_id)`.
+    /// Returns `false` if it is not hosted.
+    #[cfg(feature = "std")]
+    pub(super) fn remove_service(&self, service_id: u16, instance_id: u16) -> bool {
+        self.services
+            .with(|services| {
+                let before = services.len();
+                services.retain(|hosted| {
+                    hosted.service_id != service_id || hosted.instance_id != instance_id
+                });
+                let removed = services.len() != before;
+                if removed {
+                    self.offer_generation.fetch_add(1, Ordering::AcqRel);
+                }
+                removed
+            })
+            .unwrap_or(false)
+    }
+
+    /// The service instances hosted next to the configured one; none on
+    /// builds that keep no shared state.
+    pub(super) fn hosted_services(&self) -> HostedServices {
+        self.services
+            .with(|services| services.clone())
+            .unwrap_or_default()
+    }
+
+    /// The major version to acknowledge a subscription to
+    /// `(service_id, instance_id, major_version, event_group_id)` with, if
+    /// a hosted service accepts it.
+    pub(super) fn hosted_subscription(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        event_group_id: u16,
+    ) -> Option<u8> {
+        self.services
+            .with(|services| {
+                services
+                    .iter()
+                    .find(|hosted| {
+                        hosted.service_id == service_id
+                            && hosted.instance_id == instance_id
+                            && sd::major_version_matches(hosted.major_version, major_version)
+                            && (hosted.event_group_ids.is_empty()
+                                || hosted.event_group_ids.contains(&event_group_id))
+                    })
+                    .map(|hosted| hosted.major_version)
+            })
+            .flatten()
+    }
+
+    /// Major version of the service instance `service_id`/`instance_id`,
+    /// configured or hosted. `None` if the server does not offer it.
+    pub(super) fn instance_major_version(
+        &self,
+        config: &ServerConfig,
+        service_id: u16,
+        instance_id: u16,
+    ) -> Option<u8> {
+        if service_id == config.service_id && instance_id == config.instance_id {
+            return Some(config.major_version);
+        }
+        self.services
+            .with(|services| {
+                services
+                    .iter()
+                    .find(|hosted| {
+                        hosted.service_id == service_id && hosted.instance_id == instance_id
+                    })
+                    .map(|hosted| hosted.major_version)
+            })
+            .flatten()
+    }
+
+    /// Major version of the hosted service `service_id`, if one is hosted.
+    #[cfg(feature = "_alloc")]
+    pub(super) fn hosted_major_version(&self, service_id: u16) -> Option<u8> {
+        self.services
+            .with(|services| {
+                services
+                    .iter()
+                    .find(|hosted| hosted.service_id == service_id)
+                    .map(|hosted| hosted.major_version)
+            })
+            .flatten()
+    }
+
+    /// Offer entries (stop-offer entries unless `is_offer`) for the
+    /// configured service and every hosted one that `wanted(service_id,
+    /// major_version)` selects, all pointing at the first `options_count`
+    /// options of the message.
+    pub(super) fn service_entries(
+        &self,
+        config: &ServerConfig,
+        options_count: OptionsCount,
+        is_offer: bool,
+        wanted: impl Fn(u16, u8) -> bool,
+    ) -> ServiceEntries {
+        let ttl = if is_offer {
+            self.effective_ttl(config.ttl)
+        } else {
+            0
+        };
+        let entry = |service_id, instance_id, major_version, minor_version| {
+            let service_entry = ServiceEntry {
+                index_first_options_run: 0,
+                index_second_options_run: 0,
+                options_count,
+                service_id,
+                instance_id,
+                major_version,
+                ttl,
+                minor_version,
+            };
+            if is_offer {
+                Entry::OfferService(service_entry)
+            } else {
+                Entry::StopOfferService(service_entry)
+            }
+        };
+        let mut entries = ServiceEntries::new();
+        if wanted(config.service_id, config.major_version) {
+            let _ = entries.push(entry(
+                config.service_id,
+                config.instance_id,
+                config.major_version,
+                config.minor_version,
+            ));
+        }
+        self.services.with(|services| {
+            for hosted in services
+                .iter()
+                .filter(|hosted| wanted(hosted.service_id, hosted.major_version))
+            {
+                // One slot per hosted service plus the configured one.
+                let _ = entries.push(entry(
+                    hosted.service_id,
+                    hosted.instance_id,
+                    hosted.major_version,
+                    hosted.minor_version,
+                ));
+            }
+        });
+        entries
+    }
+
+    /// Queue a subscription lifecycle event for
+    /// `Server::subscription_events`, dropping the oldest queued event if
+    /// the queue is full.
+    pub(super) fn push_subscription_event(&self, event: SubscriptionEvent) {
+        self.subscription_events.with(|queue| queue.push(event));
+    }
+
+    /// Take the oldest queued subscription event, or register `cx` to be
+    /// woken by the next push. Stays pending forever on builds with
+    /// neither `std` nor `bare_metal`, which queue no events.
+    pub(super) fn poll_subscription_event(
+        &self,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<SubscriptionEvent> {
+        self.subscription_events
+            .with(|queue| queue.poll_pop(cx))
+            .unwrap_or(core::task::Poll::Pending)
+    }
+
+    /// Take the oldest queued subscription event without waiting.
+    pub(super) fn try_subscription_event(&self) -> Option<SubscriptionEvent> {
+        self.subscription_events
+            .with(SubscriptionEventQueue::pop)
+            .flatten()
+    }
+
+    /// Replace the TTL advertised by subsequent SD emissions, or clear
+    /// the override with `None` to fall back to `ServerConfig::ttl`.
+    /// Takes effect from the next offer / `SubscribeAck`; nothing
+    /// already on the wire is affected.
+    pub fn set_ttl_override(&self, ttl: Option<u32>) {
+        // Saturating to the 24-bit wire maximum also keeps an override
+        // clear of the sentinel.
+        self.ttl_override.store(
+            ttl.map_or(NO_TTL_OVERRIDE, |ttl| ttl.min(sd::TTL_INFINITE)),
+            Ordering::Release,
+        );
+        self.offer_generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// The TTL to advertise: the runtime override if one is set,
+    /// otherwise `configured`.
+    #[must_use]
+    pub fn effective_ttl(&self, configured: u32) -> u32 {
+        match self.ttl_override.load(Ordering::Acquire) {
+            NO_TTL_OVERRIDE => configured,
+            ttl => ttl,
+        }
+    }
+
+    /// Replace the delay between cyclic offers of the main phase, or
+    /// clear the override with `None` to fall back to
+    /// `ServerConfig::cyclic_offer_delay`. Takes effect from the next
+    /// offer; zero stops the cyclic offers.
+    pub fn set_cyclic_offer_delay_override(&self, delay: Option<core::time::Duration>) {
+        let millis = delay.map_or(NO_DELAY_OVERRIDE, |delay| {
+            u32::try_from(delay.as_millis())
+                .map_or(NO_DELAY_OVERRIDE - 1, |ms| ms.min(NO_DELAY_OVERRIDE - 1))
+        });
+        self.cyclic_offer_delay_override
+            .store(millis, Ordering::Release);
+    }
+
+    /// The delay between cyclic offers: the runtime override if one is
+    /// set, otherwise the one of `config`.
+    #[must_use]
+    pub fn cyclic_offer_delay(&self, config: &ServerConfig) -> core::time::Duration {
+        match self.cyclic_offer_delay_override.load(Ordering::Acquire) {
+            NO_DELAY_OVERRIDE => config.cyclic_offer_delay,
+            millis => core::time::Duration::from_millis(u64::from(millis)),
         }
     }
 
@@ -186,83 +728,182 @@ impl SdStateManager {
         }
     }
 
-    /// Send a multicast `OfferService` announcement for the given config.
+    /// Send a multicast `OfferService` announcement for the given config,
+    /// with one entry per hosted service after the configured one.
     ///
     /// `buf` is a caller-provided scratch buffer used for encoding the
     /// outgoing frame. Returns [`Error::Capacity`]`("udp_buffer")` if the
-    /// encoded frame does not fit in `buf`.
+    /// encoded frame does not fit in `buf`. The announce loop goes
+    /// through [`Self::send_cached_offer_service`] instead.
+    #[cfg(test)]
     pub(super) async fn send_offer_service<T: TransportSocket>(
         &self,
         buf: &mut [u8],
         config: &ServerConfig,
         socket: &T,
     ) -> Result<(), Error> {
+        self.send_offer_entry(buf, config, socket, true, |_, _| true)
+            .await
+    }
+
+    /// [`Self::send_offer_service`], re-using the frame `cache` describes
+    /// in `buf` if nothing the offer carries changed since it was
+    /// encoded: only its session ID and reboot flag are patched in place.
+    /// Otherwise the frame is encoded afresh and `cache` updated.
+    /// Nothing else may write to `buf` while `cache` is `Some`.
+    pub(super) async fn send_cached_offer_service<T: TransportSocket>(
+        &self,
+        buf: &mut [u8],
+        config: &ServerConfig,
+        socket: &T,
+        cache: &mut Option<OfferFrame>,
+    ) -> Result<(), Error> {
+        let generation = self.offer_generation.load(Ordering::Acquire);
+        let len = match *cache {
+            Some(frame) if frame.generation == generation => {
+                let (sid, reboot_flag) = self.next_session_id_with_reboot_flag();
+                buf[8..12].copy_from_slice(&sid.to_be_bytes());
+                buf[16] = u8::from(config.sd_flags(reboot_flag));
+                frame.len
+            }
+            _ => {
+                *cache = None;
+                let len = self.encode_offer_entry(buf, config, true, |_, _| true)?;
+                *cache = Some(OfferFrame { len, generation });
+                len
+            }
+        };
+        self.send_offer_frame(&buf[..len], config, socket, true)
+            .await
+    }
+
+    /// [`Self::send_offer_service`] for `service_id` alone, the configured
+    /// service or a hosted one.
+    pub(super) async fn send_offer_service_of<T: TransportSocket>(
+        &self,
+        buf: &mut [u8],
+        config: &ServerConfig,
+        socket: &T,
+        service_id: u16,
+    ) -> Result<(), Error> {
+        self.send_offer_entry(buf, config, socket, true, |id, _| id == service_id)
+            .await
+    }
+
+    /// Send a multicast `StopOfferService` (TTL 0) withdrawing the
+    /// service described by `config` and every hosted one. Same buffer
+    /// contract as [`Self::send_offer_service`].
+    pub(super) async fn send_stop_offer_service<T: TransportSocket>(
+        &self,
+        buf: &mut [u8],
+        config: &ServerConfig,
+        socket: &T,
+    ) -> Result<(), Error> {
+        self.send_offer_entry(buf, config, socket, false, |_, _| true)
+            .await
+    }
+
+    async fn send_offer_entry<T: TransportSocket>(
+        &self,
+        buf: &mut [u8],
+        config: &ServerConfig,
+        socket: &T,
+        is_offer: bool,
+        wanted: impl Fn(u16, u8) -> bool,
+    ) -> Result<(), Error> {
+        let len = self.encode_offer_entry(buf, config, is_offer, wanted)?;
+        self.send_offer_frame(&buf[..len], config, socket, is_offer)
+            .await
+    }
+
+    /// Encode the multicast offer (stop-offer unless `is_offer`) of the
+    /// services `wanted` selects into `buf`, returning its length.
+    fn encode_offer_entry(
+        &self,
+        buf: &mut [u8],
+        config: &ServerConfig,
+        is_offer: bool,
+        wanted: impl Fn(u16, u8) -> bool,
+    ) -> Result<usize, Error> {
         use crate::protocol::Header as SomeIpHeader;
         use crate::traits::WireFormat;
 
-        let entry = Entry::OfferService(ServiceEntry {
-            index_first_options_run: 0,
-            index_second_options_run: 0,
-            options_count: OptionsCount::new(1, 0),
-            service_id: config.service_id,
-            instance_id: config.instance_id,
-            major_version: config.major_version,
-            ttl: config.ttl,
-            minor_version: config.minor_version,
-        });
-
-        let option = sd::Options::IpV4Endpoint {
-            ip: config.interface,
-            port: config.local_port,
-            protocol: TransportProtocol::Udp,
+        let options = config.endpoint_options();
+        // Encode in a block so the entries are not held across the send.
+        let total_len = {
+            // At most two endpoint, a configuration and a load-balancing option.
+            #[allow(clippy::cast_possible_truncation)]
+            let options_count = OptionsCount::new(options.len() as u8, 0)?;
+            let entries = self.service_entries(config, options_count, is_offer, wanted);
+            // Atomic (sid, reboot_flag) pair so that concurrent emissions
+            // around the wrap boundary cannot disagree about whether this
+            // very message advertises `RecentlyRebooted` or `Continuous`.
+            // See `next_session_id_with_reboot_flag` docs for the race.
+            let (sid, reboot_flag) = self.next_session_id_with_reboot_flag();
+            let sd_payload = sd::Header::new(config.sd_flags(reboot_flag), &entries, &options);
+
+            // Caller-provided send scratch — keeps the per-tick path
+            // alloc-free without parking a `[u8; UDP_BUFFER_SIZE]` in the
+            // announce future. 16-byte SOME/IP header + the SD payload.
+            if buf.len() < 16 {
+                return Err(Error::Capacity("udp_buffer"));
+            }
+            let sd_data_len = sd_payload
+                .encode_to_slice(&mut buf[16..])
+                .map_err(|_| Error::Capacity("udp_buffer"))?;
+            let total_len = 16 + sd_data_len;
+            // The `< 16` guard plus `encode_to_slice`'s own over-capacity
+            // error already cover the fit; this stays as a debug-only
+            // sanity check rather than a live branch.
+            debug_assert!(total_len <= buf.len());
+            let someip_header = SomeIpHeader::new_sd(sid, sd_data_len);
+            someip_header
+                .encode_to_slice(&mut buf[..16])
+                .map_err(|_| Error::Capacity("udp_buffer"))?;
+            total_len
         };
+        Ok(total_len)
+    }
 
-        let entries = [entry];
-        let options = [option];
-        // Atomic (sid, reboot_flag) pair so that concurrent emissions
-        // around the wrap boundary cannot disagree about whether this
-        // very message advertises `RecentlyRebooted` or `Continuous`.
-        // See `next_session_id_with_reboot_flag` docs for the race.
-        let (sid, reboot_flag) = self.next_session_id_with_reboot_flag();
-        let sd_payload = sd::Header::new(Flags::new_sd(reboot_flag), &entries, &options);
-
-        // Caller-provided send scratch — keeps the per-tick path
-        // alloc-free without parking a `[u8; UDP_BUFFER_SIZE]` in the
-        // announce future. 16-byte SOME/IP header + the SD payload.
-        if buf.len() < 16 {
-            return Err(Error::Capacity("udp_buffer"));
-        }
-        let sd_data_len = sd_payload
-            .encode_to_slice(&mut buf[16..])
-            .map_err(|_| Error::Capacity("udp_buffer"))?;
-        let total_len = 16 + sd_data_len;
-        // The `< 16` guard plus `encode_to_slice`'s own over-capacity
-        // error already cover the fit; this stays as a debug-only
-        // sanity check rather than a live branch.
-        debug_assert!(total_len <= buf.len());
-        let someip_header = SomeIpHeader::new_sd(sid, sd_data_len);
-        someip_header
-            .encode_to_slice(&mut buf[..16])
-            .map_err(|_| Error::Capacity("udp_buffer"))?;
-
+    async fn send_offer_frame<T: TransportSocket>(
+        &self,
+        frame: &[u8],
+        config: &ServerConfig,
+        socket: &T,
+        is_offer: bool,
+    ) -> Result<(), Error> {
         let multicast_addr = SocketAddrV4::new(sd::MULTICAST_IP, sd::MULTICAST_PORT);
 
         crate::log::trace!(
-            "Sending OfferService: service=0x{:04X}, instance={}, port={}, size={} bytes",
+            "Sending {}: service=0x{:04X}, instance={}, port={}, size={} bytes",
+            if is_offer {
+                "OfferService"
+            } else {
+                "StopOfferService"
+            },
             config.service_id,
             config.instance_id,
             config.local_port,
-            total_len
+            frame.len()
         );
-        crate::log::trace!("OfferService data: {:02X?}", &buf[..total_len.min(64)]);
+        crate::log::trace!("OfferService data: {:02X?}", &frame[..frame.len().min(64)]);
 
-        socket.send_to(&buf[..total_len], multicast_addr).await?;
+        socket.send_to(frame, multicast_addr).await?;
         crate::log::trace!("Sent to {}", multicast_addr);
 
         Ok(())
     }
 }
 
+/// A multicast offer left encoded in the announcement buffer by
+/// [`SdStateManager::send_cached_offer_service`]: its length, and the
+/// offer generation it was encoded at.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct OfferFrame {
+    len: usize,
+    generation: u32,
+}
+
 // `SdStateHandle` / `WrappableSdStateHandle` were collapsed into the
 // unified `crate::transport::SharedHandle<SdStateManager>` /
 // `WrappableSharedHandle<SdStateManager>` traits. The blanket impls
@@ -304,9 +945,12 @@ mod tests {
     }
 
     #[test]
-    fn next_session_id_starts_at_two_from_default_new() {
+    fn next_session_id_starts_at_one_from_default_new() {
         let sd = SdStateManager::new();
-        // new() seeds at 1; first next_session_id increments to 2
+        assert_eq!(
+            sd.next_session_id_with_reboot_flag(),
+            (1, RebootFlag::RecentlyRebooted)
+        );
         assert_eq!(sd.next_session_id(), 2);
     }
 
@@ -686,6 +1330,84 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn offers_carry_the_configured_unicast_flag() {
+        let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(TEST_ADVERTISED_PORT);
+        let sd_state = SdStateManager::with_initial(0xFFFE);
+        let sock = CapturingSocket::new();
+        let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+        sd_state
+            .send_offer_service(&mut buf, &config, &sock)
+            .await
+            .unwrap();
+        let multicast_only = config.with_sd_unicast(false);
+        // The second offer is patched from the cached frame.
+        let mut cache = None;
+        for _ in 0..2 {
+            sd_state
+                .send_cached_offer_service(&mut buf, &multicast_only, &sock, &mut cache)
+                .await
+                .unwrap();
+        }
+
+        let unicast: std::vec::Vec<bool> = sock
+            .drain_sent()
+            .iter()
+            .map(|(_, frame)| {
+                let view = MessageView::parse(frame).unwrap();
+                view.sd_header().unwrap().flags().unicast()
+            })
+            .collect();
+        assert_eq!(unicast, [true, false, false]);
+    }
+
+    #[tokio::test]
+    async fn cached_offer_patches_session_id_until_the_offer_changes() {
+        let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(TEST_ADVERTISED_PORT);
+        // The second send wraps, so the reboot flag is patched as well.
+        let sd_state = SdStateManager::with_initial(0xFFFE);
+        let sock = CapturingSocket::new();
+        let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+        let mut cache = None;
+        for _ in 0..2 {
+            sd_state
+                .send_cached_offer_service(&mut buf, &config, &sock, &mut cache)
+                .await
+                .unwrap();
+        }
+        let sent = sock.drain_sent();
+        assert_captured_offer_matches(
+            &sent[1].1,
+            sent[1].0,
+            &config,
+            0x0000_0001,
+            RebootFlag::Continuous,
+        );
+        let fresh = SdStateManager::with_initial(0);
+        fresh
+            .send_offer_service(&mut [0u8; crate::UDP_BUFFER_SIZE], &config, &sock)
+            .await
+            .unwrap();
+        let (_, mut expected) = sock.drain_sent().remove(0);
+        expected[16] = sent[1].1[16];
+        assert_eq!(sent[1].1, expected, "only session ID and flags differ");
+
+        sd_state.set_ttl_override(Some(42));
+        sd_state
+            .send_cached_offer_service(&mut buf, &config, &sock, &mut cache)
+            .await
+            .unwrap();
+        let sent = sock.drain_sent();
+        let view = MessageView::parse(&sent[0].1).unwrap();
+        let entry = view.sd_header().unwrap().entries().next().unwrap();
+        assert_eq!(entry.ttl(), 42, "re-encoded after the change");
+        assert_eq!(view.header().request_id(), 0x0000_0002);
+    }
+
     #[tokio::test]
     async fn send_offer_service_through_mock_preserves_zero_ttl() {
         let mut config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
@@ -705,6 +1427,125 @@ mod tests {
         assert_eq!(entry.ttl(), 0, "TTL=0 must round-trip end-to-end");
     }
 
+    #[tokio::test]
+    async fn send_stop_offer_service_emits_zero_ttl_stop_entry() {
+        let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(TEST_ADVERTISED_PORT);
+        let sd_state = SdStateManager::new();
+        let sock = CapturingSocket::new();
+        sd_state
+            .send_stop_offer_service(&mut [0u8; crate::UDP_BUFFER_SIZE], &config, &sock)
+            .await
+            .unwrap();
+
+        let sent = sock.drain_sent();
+        let view = MessageView::parse(&sent[0].1).unwrap();
+        let entry = view.sd_header().unwrap().entries().next().unwrap();
+        assert_eq!(entry.entry_type().unwrap(), sd::EntryType::StopOfferService);
+        assert_eq!(entry.service_id(), TEST_SERVICE_ID);
+        assert_eq!(entry.ttl(), 0);
+    }
+
+    #[tokio::test]
+    async fn send_offer_service_advertises_tcp_endpoint_when_configured() {
+        let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(TEST_ADVERTISED_PORT)
+            .with_tcp_port(TEST_ADVERTISED_PORT + 1);
+        let sd_state = SdStateManager::new();
+        let sock = CapturingSocket::new();
+        sd_state
+            .send_offer_service(&mut [0u8; crate::UDP_BUFFER_SIZE], &config, &sock)
+            .await
+            .unwrap();
+
+        let sent = sock.drain_sent();
+        let view = MessageView::parse(&sent[0].1).unwrap();
+        let sd_view = view.sd_header().unwrap();
+        let entry = sd_view.entries().next().unwrap();
+        assert_eq!(entry.options_count().first_options_count, 2);
+        let endpoints: Vec<_> = sd_view
+            .options()
+            .map(|option| option.as_ipv4().unwrap())
+            .collect();
+        assert_eq!(
+            endpoints,
+            [
+                (
+                    Ipv4Addr::LOCALHOST,
+                    TransportProtocol::Udp,
+                    TEST_ADVERTISED_PORT
+                ),
+                (
+                    Ipv4Addr::LOCALHOST,
+                    TransportProtocol::Tcp,
+                    TEST_ADVERTISED_PORT + 1
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn send_offer_service_carries_configuration_and_load_balancing() {
+        let mut configuration = sd::ConfigurationString::new();
+        configuration.push("profile", Some("high")).unwrap();
+        let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(TEST_ADVERTISED_PORT)
+            .with_configuration(configuration.clone())
+            .with_load_balancing(1, 10);
+        let sd_state = SdStateManager::new();
+        let sock = CapturingSocket::new();
+        sd_state
+            .send_offer_service(&mut [0u8; crate::UDP_BUFFER_SIZE], &config, &sock)
+            .await
+            .unwrap();
+
+        let sent = sock.drain_sent();
+        let view = MessageView::parse(&sent[0].1).unwrap();
+        let sd_view = view.sd_header().unwrap();
+        let entry = sd_view.entries().next().unwrap();
+        assert_eq!(entry.options_count().first_options_count, 3);
+        let options: Vec<_> = sd_view
+            .options()
+            .map(|option| option.to_owned().unwrap())
+            .collect();
+        assert_eq!(sd::extract_configuration(&options), configuration);
+        assert_eq!(sd::extract_load_balancing(&options), Some((1, 10)));
+    }
+
+    #[tokio::test]
+    async fn send_offer_service_honours_ttl_override_until_cleared() {
+        let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
+            .with_interface(Ipv4Addr::LOCALHOST)
+            .with_local_port(TEST_ADVERTISED_PORT);
+        let sd_state = SdStateManager::new();
+        let sock = CapturingSocket::new();
+        let mut buf = [0u8; crate::UDP_BUFFER_SIZE];
+
+        sd_state.set_ttl_override(Some(42));
+        sd_state
+            .send_offer_service(&mut buf, &config, &sock)
+            .await
+            .unwrap();
+        sd_state.set_ttl_override(None);
+        sd_state
+            .send_offer_service(&mut buf, &config, &sock)
+            .await
+            .unwrap();
+
+        let ttls: Vec<u32> = sock
+            .drain_sent()
+            .iter()
+            .map(|(_, bytes)| {
+                let view = MessageView::parse(bytes).unwrap();
+                view.sd_header().unwrap().entries().next().unwrap().ttl()
+            })
+            .collect();
+        assert_eq!(ttls, [42, config.ttl]);
+    }
+
     #[tokio::test]
     async fn send_offer_service_through_mock_propagates_socket_errors() {
         let config = ServerConfig::new(TEST_SERVICE_ID, TEST_INSTANCE_ID)
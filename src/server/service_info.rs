@@ -1,6 +1,9 @@
 //! Service and event group information
 
 use core::net::SocketAddrV4;
+use core::time::Duration;
+
+use crate::protocol::sd;
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
@@ -52,6 +55,7 @@ impl EventGroupInfo {
 
 /// A subscriber to an event group
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Subscriber {
     /// Remote address of the subscriber
     pub address: SocketAddrV4,
@@ -61,10 +65,17 @@ pub struct Subscriber {
     pub service_id: u16,
     /// Instance ID
     pub instance_id: u16,
+    /// TTL of the last subscribe, in seconds; [`sd::TTL_INFINITE`] for a
+    /// subscription that does not lapse
+    pub ttl: u32,
+    /// When the subscription was last made or renewed, from
+    /// [`Timer::now`](crate::Timer::now); `None` without a clock, in
+    /// which case it does not lapse either
+    pub renewed_at: Option<Duration>,
 }
 
 impl Subscriber {
-    /// Create a new subscriber
+    /// Create a new subscriber that does not lapse
     #[must_use]
     pub fn new(
         address: SocketAddrV4,
@@ -77,8 +88,26 @@ impl Subscriber {
             event_group_id,
             service_id,
             instance_id,
+            ttl: sd::TTL_INFINITE,
+            renewed_at: None,
         }
     }
+
+    /// Set the lease: the subscription lapses `ttl` seconds after
+    /// `renewed_at`
+    #[must_use]
+    pub const fn with_lease(mut self, ttl: u32, renewed_at: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self.renewed_at = renewed_at;
+        self
+    }
+
+    /// When the subscription lapses unless renewed, or `None` if it does
+    /// not
+    #[must_use]
+    pub fn expires_at(&self) -> Option<Duration> {
+        self.renewed_at?.checked_add(sd::ttl_lifetime(self.ttl)?)
+    }
 }
 
 #[cfg(test)]
@@ -102,5 +131,26 @@ mod tests {
         assert_eq!(subscriber.service_id, 0x5B);
         assert_eq!(subscriber.instance_id, 1);
         assert_eq!(subscriber.event_group_id, 0x01);
+        assert_eq!(subscriber.expires_at(), None);
+    }
+
+    #[test]
+    fn subscriber_lease_expires_ttl_after_renewal() {
+        let addr = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 100), 30500);
+        let subscriber = Subscriber::new(addr, 0x5B, 1, 0x01);
+        assert_eq!(
+            subscriber
+                .clone()
+                .with_lease(3, Some(Duration::from_secs(10)))
+                .expires_at(),
+            Some(Duration::from_secs(13))
+        );
+        assert_eq!(subscriber.clone().with_lease(3, None).expires_at(), None);
+        assert_eq!(
+            subscriber
+                .with_lease(sd::TTL_INFINITE, Some(Duration::from_secs(10)))
+                .expires_at(),
+            None
+        );
     }
 }
@@ -0,0 +1,116 @@
+//! Primary/standby subscriber pairs behind
+//! [`EventPublisher::set_standby_subscriber`](super::EventPublisher::set_standby_subscriber).
+
+use core::net::SocketAddrV4;
+use heapless::Vec;
+
+use super::subscription_manager::SUBSCRIBERS_PER_GROUP;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_STANDBY_SUBSCRIBERS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_STANDBY_SUBSCRIBERS_CAP: usize = 8;
+
+/// Maximum number of primary/standby subscriber pairs one
+/// [`EventPublisher`](super::EventPublisher) sends to. Sized via
+/// `SIMPLE_SOMEIP_STANDBY_SUBSCRIBERS_CAP`; defaults to 2 on bare-metal,
+/// 8 otherwise.
+pub const STANDBY_SUBSCRIBERS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_STANDBY_SUBSCRIBERS_CAP"),
+    _DEFAULT_STANDBY_SUBSCRIBERS_CAP,
+);
+
+/// Each primary subscriber address and the standby address that gets
+/// a copy of every event sent to it.
+#[derive(Debug)]
+pub(super) struct StandbySubscribers {
+    pairs: Vec<(SocketAddrV4, SocketAddrV4), STANDBY_SUBSCRIBERS_CAP>,
+}
+
+impl StandbySubscribers {
+    pub(super) const fn new() -> Self {
+        Self { pairs: Vec::new() }
+    }
+
+    /// Set (`Some`) or clear (`None`) the standby of `primary`. Returns
+    /// `false` if the table is full.
+    pub(super) fn set(&mut self, primary: SocketAddrV4, standby: Option<SocketAddrV4>) -> bool {
+        let existing = self.pairs.iter().position(|(p, _)| *p == primary);
+        match (existing, standby) {
+            (Some(index), Some(standby)) => self.pairs[index].1 = standby,
+            (Some(index), None) => {
+                self.pairs.swap_remove(index);
+            }
+            (None, Some(standby)) => return self.pairs.push((primary, standby)).is_ok(),
+            (None, None) => {}
+        }
+        true
+    }
+
+    /// Add the standby of every primary in `subscribers` that is not
+    /// already there. Returns the number of standbys left out because
+    /// `subscribers` is full.
+    pub(super) fn extend(
+        &self,
+        subscribers: &mut Vec<SocketAddrV4, SUBSCRIBERS_PER_GROUP>,
+    ) -> usize {
+        let mut left_out = 0;
+        for (primary, standby) in &self.pairs {
+            if subscribers.contains(primary)
+                && !subscribers.contains(standby)
+                && subscribers.push(*standby).is_err()
+            {
+                left_out += 1;
+            }
+        }
+        left_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::Ipv4Addr;
+
+    fn addr(last: u8, port: u16) -> SocketAddrV4 {
+        SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, last), port)
+    }
+
+    #[test]
+    fn standbys_follow_their_primary_once() {
+        let mut pairs = StandbySubscribers::new();
+        assert!(pairs.set(addr(20, 40_000), Some(addr(21, 40_000))));
+        assert!(pairs.set(addr(30, 40_000), Some(addr(31, 40_000))));
+
+        let mut subscribers = Vec::new();
+        subscribers.push(addr(20, 40_000)).unwrap();
+        subscribers.push(addr(21, 40_000)).unwrap();
+        subscribers.push(addr(40, 40_000)).unwrap();
+        assert_eq!(pairs.extend(&mut subscribers), 0);
+        assert_eq!(
+            subscribers,
+            [addr(20, 40_000), addr(21, 40_000), addr(40, 40_000)],
+            "standby already subscribed, other primary absent"
+        );
+
+        assert!(pairs.set(addr(20, 40_000), Some(addr(22, 40_000))));
+        pairs.extend(&mut subscribers);
+        assert_eq!(subscribers.last(), Some(&addr(22, 40_000)));
+
+        assert!(pairs.set(addr(20, 40_000), None));
+        subscribers.truncate(1);
+        pairs.extend(&mut subscribers);
+        assert_eq!(subscribers, [addr(20, 40_000)]);
+    }
+
+    #[test]
+    fn a_full_table_rejects_new_pairs() {
+        let mut pairs = StandbySubscribers::new();
+        for port in 0..STANDBY_SUBSCRIBERS_CAP {
+            let port = u16::try_from(port).unwrap();
+            assert!(pairs.set(addr(20, port), Some(addr(21, port))));
+        }
+        assert!(!pairs.set(addr(20, u16::MAX), Some(addr(21, 0))));
+        assert!(pairs.set(addr(20, 0), None), "clearing always succeeds");
+    }
+}
@@ -0,0 +1,43 @@
+//! What a server offers and who subscribed, as returned by
+//! [`Server::state_snapshot`](super::Server::state_snapshot).
+
+use heapless::Vec;
+
+use super::subscription_manager::SUBSCRIPTIONS_CAP;
+use super::{AcceptedOffer, ServerConfig, Subscriber};
+
+/// A service instance a server offers: the one in its [`ServerConfig`]
+/// or one added through `Server::add_service`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct OfferedService {
+    /// Service ID
+    pub service_id: u16,
+    /// Instance ID
+    pub instance_id: u16,
+    /// Major version
+    pub major_version: u8,
+    /// Minor version
+    pub minor_version: u32,
+    /// Event groups accepted for subscription; empty accepts any.
+    pub event_group_ids: Vec<u16, { ServerConfig::EVENT_GROUP_IDS_CAP }>,
+}
+
+/// Offers and subscribers of a server at one instant, for crash reports
+/// and support bundles. Serializable with the `serde` feature.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ServerSnapshot {
+    /// The configured service first, then the hosted ones.
+    pub offered: Vec<OfferedService, { ServerConfig::ACCEPTED_OFFERS_CAP + 1 }>,
+    /// Co-offered event groups from [`ServerConfig::accepted_offers`].
+    pub accepted_offers: Vec<AcceptedOffer, { ServerConfig::ACCEPTED_OFFERS_CAP }>,
+    /// The SD TTL currently advertised.
+    pub ttl: u32,
+    /// `true` while the offer is withdrawn by `Server::stop_announcing`.
+    pub withdrawn: bool,
+    /// Every subscriber of every event group. Empty for a
+    /// [`SubscriptionHandle`](super::SubscriptionHandle) that does not
+    /// implement `for_each_subscription`.
+    pub subscribers: Vec<Subscriber, SUBSCRIPTIONS_CAP>,
+}
@@ -0,0 +1,177 @@
+//! Last-value cache behind
+//! [`EventPublisher::set_sticky_event`](super::EventPublisher::set_sticky_event).
+
+use heapless::Vec;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_STICKY_EVENTS_CAP: usize = 2;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_STICKY_EVENTS_CAP: usize = 8;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_STICKY_EVENT_LEN: usize = 64;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_STICKY_EVENT_LEN: usize = crate::UDP_BUFFER_SIZE;
+
+/// Maximum number of events one [`EventPublisher`](super::EventPublisher)
+/// can mark sticky. Sized via `SIMPLE_SOMEIP_STICKY_EVENTS_CAP`; defaults
+/// to 2 on bare-metal, 8 otherwise.
+pub const STICKY_EVENTS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_STICKY_EVENTS_CAP"),
+    _DEFAULT_STICKY_EVENTS_CAP,
+);
+
+/// Largest datagram, SOME/IP header and E2E header included, kept for a
+/// sticky event; longer notifications are sent but not cached. Sized via
+/// `SIMPLE_SOMEIP_STICKY_EVENT_LEN`; defaults to 64 bytes on bare-metal,
+/// [`UDP_BUFFER_SIZE`](crate::UDP_BUFFER_SIZE) otherwise.
+pub const STICKY_EVENT_LEN: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_STICKY_EVENT_LEN"),
+    _DEFAULT_STICKY_EVENT_LEN,
+);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct StickyKey {
+    pub(super) service: u16,
+    pub(super) instance: u16,
+    pub(super) event_group: u16,
+    pub(super) event: u16,
+}
+
+impl StickyKey {
+    pub(super) const fn new(
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        event_id: u16,
+    ) -> Self {
+        Self {
+            service: service_id,
+            instance: instance_id,
+            event_group: event_group_id,
+            event: event_id,
+        }
+    }
+
+    pub(super) const fn in_group(
+        self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> bool {
+        self.service == service_id
+            && self.instance == instance_id
+            && self.event_group == event_group_id
+    }
+}
+
+/// Sticky events and the last datagram published for each. An empty
+/// datagram means nothing was published since the event was marked.
+#[derive(Debug)]
+pub(super) struct StickyCache {
+    entries: Vec<(StickyKey, Vec<u8, STICKY_EVENT_LEN>), STICKY_EVENTS_CAP>,
+}
+
+impl StickyCache {
+    pub(super) const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Mark `key` sticky. Marking it again keeps its cached value.
+    /// Returns `false` if the cache is full.
+    pub(super) fn mark(&mut self, key: StickyKey) -> bool {
+        self.is_sticky(key) || self.entries.push((key, Vec::new())).is_ok()
+    }
+
+    /// Stop caching `key` and forget its value.
+    pub(super) fn unmark(&mut self, key: StickyKey) {
+        self.entries.retain(|(k, _)| *k != key);
+    }
+
+    pub(super) fn is_sticky(&self, key: StickyKey) -> bool {
+        self.entries.iter().any(|(k, _)| *k == key)
+    }
+
+    /// Keep `datagram` as the value of `key`, if `key` is sticky. A
+    /// datagram longer than [`STICKY_EVENT_LEN`] clears the value rather
+    /// than leave an older one behind; returns `false` then.
+    pub(super) fn store(&mut self, key: StickyKey, datagram: &[u8]) -> bool {
+        let Some((_, value)) = self.entries.iter_mut().find(|(k, _)| *k == key) else {
+            return true;
+        };
+        value.clear();
+        value.extend_from_slice(datagram).is_ok()
+    }
+
+    /// Copy the `index`-th cached datagram of the event group into `out`,
+    /// returning its length. `None` once `index` is past the last one.
+    /// A datagram that does not fit `out` yields `Some(0)`.
+    pub(super) fn copy_cached(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        index: usize,
+        out: &mut [u8],
+    ) -> Option<usize> {
+        let (_, value) = self
+            .entries
+            .iter()
+            .filter(|(k, v)| !v.is_empty() && k.in_group(service_id, instance_id, event_group_id))
+            .nth(index)?;
+        let Some(out) = out.get_mut(..value.len()) else {
+            return Some(0);
+        };
+        out.copy_from_slice(value);
+        Some(value.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn key(event_group_id: u16, event_id: u16) -> StickyKey {
+        StickyKey::new(0x5B, 1, event_group_id, event_id)
+    }
+
+    #[test]
+    fn only_sticky_events_are_cached_per_group() {
+        let mut cache = StickyCache::new();
+        let mut out = [0u8; 8];
+        assert!(cache.mark(key(1, 0x8001)));
+        assert!(cache.mark(key(1, 0x8002)));
+        assert!(cache.mark(key(2, 0x8003)));
+        assert_eq!(
+            cache.copy_cached(0x5B, 1, 1, 0, &mut out),
+            None,
+            "nothing published yet"
+        );
+
+        assert!(cache.store(key(1, 0x8002), &[2, 2]));
+        assert!(cache.store(key(1, 0x8002), &[3]), "latest value wins");
+        assert!(cache.store(key(1, 0x9000), &[9]), "not sticky, ignored");
+        assert!(cache.store(key(2, 0x8003), &[4, 4, 4]));
+        assert_eq!(cache.copy_cached(0x5B, 1, 1, 0, &mut out), Some(1));
+        assert_eq!(out[0], 3);
+        assert_eq!(cache.copy_cached(0x5B, 1, 1, 1, &mut out), None);
+        assert_eq!(
+            cache.copy_cached(0x5B, 1, 2, 0, &mut [0u8; 2]),
+            Some(0),
+            "too long"
+        );
+
+        assert!(!cache.store(key(2, 0x8003), &[0; STICKY_EVENT_LEN + 1]));
+        assert_eq!(
+            cache.copy_cached(0x5B, 1, 2, 0, &mut out),
+            None,
+            "oversize clears"
+        );
+
+        cache.unmark(key(1, 0x8002));
+        assert!(!cache.is_sticky(key(1, 0x8002)));
+        assert!(cache.is_sticky(key(1, 0x8001)));
+    }
+}
@@ -0,0 +1,176 @@
+//! Subscription lifecycle events, exposed through
+//! [`Server::subscription_events`](super::Server::subscription_events).
+
+use core::net::SocketAddrV4;
+use core::task::{Context, Poll, Waker};
+
+use heapless::Deque;
+
+use crate::transport::SharedHandle;
+
+use super::SdStateManager;
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_SUBSCRIPTION_EVENTS_CAP: usize = 4;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_SUBSCRIPTION_EVENTS_CAP: usize = 16;
+
+/// Maximum number of subscription events queued for
+/// [`SubscriptionEvents`]. When the queue is full the oldest event is
+/// dropped. Sized via `SIMPLE_SOMEIP_SUBSCRIPTION_EVENTS_CAP`; defaults
+/// to 4 on bare-metal, 16 otherwise.
+pub const SUBSCRIPTION_EVENTS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_SUBSCRIPTION_EVENTS_CAP"),
+    _DEFAULT_SUBSCRIPTION_EVENTS_CAP,
+);
+
+/// What happened to a subscription.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubscriptionEventKind {
+    /// A new subscriber was added and acknowledged.
+    Subscribed,
+    /// An existing subscriber renewed its subscription and was
+    /// acknowledged again.
+    Refreshed,
+    /// A subscription lapsed because its TTL ran out without renewal.
+    /// Only produced with a timer whose [`Timer::now`](crate::Timer::now)
+    /// returns a time.
+    Expired,
+    /// The subscriber sent a `StopSubscribeEventgroup`.
+    Unsubscribed,
+}
+
+/// One subscription lifecycle change, with the peer and eventgroup it
+/// concerns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionEvent {
+    pub kind: SubscriptionEventKind,
+    /// Endpoint events are delivered to, from the subscribe entry's
+    /// endpoint option.
+    pub subscriber: SocketAddrV4,
+    pub service_id: u16,
+    pub instance_id: u16,
+    pub event_group_id: u16,
+}
+
+/// Bounded queue behind [`SubscriptionEvents`], living in the shared
+/// [`SdStateManager`].
+#[derive(Debug)]
+pub(super) struct SubscriptionEventQueue {
+    events: Deque<SubscriptionEvent, SUBSCRIPTION_EVENTS_CAP>,
+    waker: Option<Waker>,
+}
+
+impl SubscriptionEventQueue {
+    pub(super) const fn new() -> Self {
+        Self {
+            events: Deque::new(),
+            waker: None,
+        }
+    }
+
+    pub(super) fn push(&mut self, event: SubscriptionEvent) {
+        if self.events.is_full() {
+            let _ = self.events.pop_front();
+        }
+        // Cannot fail: a slot was freed above when full.
+        let _ = self.events.push_back(event);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub(super) fn pop(&mut self) -> Option<SubscriptionEvent> {
+        self.events.pop_front()
+    }
+
+    pub(super) fn poll_pop(&mut self, cx: &Context<'_>) -> Poll<SubscriptionEvent> {
+        if let Some(event) = self.events.pop_front() {
+            return Poll::Ready(event);
+        }
+        match &mut self.waker {
+            Some(waker) => waker.clone_from(cx.waker()),
+            None => self.waker = Some(cx.waker().clone()),
+        }
+        Poll::Pending
+    }
+}
+
+/// Stream of subscription lifecycle events from a running server, returned
+/// by [`Server::subscription_events`](super::Server::subscription_events).
+///
+/// Lets applications log and react to subscribers coming and going
+/// without polling subscriber counts. Events are queued from the moment
+/// the server is constructed, up to [`SUBSCRIPTION_EVENTS_CAP`]; older
+/// ones are dropped if nobody keeps up. The queue has a single consumer:
+/// every `SubscriptionEvents` handle of one server takes from the same
+/// queue, and only the most recent waiter is woken.
+///
+/// Builds with neither `std` nor `bare_metal` have no lock to share the
+/// queue with and never yield an event.
+pub struct SubscriptionEvents<Hsd> {
+    sd_state: Hsd,
+}
+
+impl<Hsd> core::fmt::Debug for SubscriptionEvents<Hsd> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SubscriptionEvents").finish_non_exhaustive()
+    }
+}
+
+impl<Hsd: SharedHandle<SdStateManager>> SubscriptionEvents<Hsd> {
+    pub(super) const fn new(sd_state: Hsd) -> Self {
+        Self { sd_state }
+    }
+
+    /// Wait for the next subscription event. Cancel-safe: an event is
+    /// only dequeued by the poll that returns it.
+    pub async fn recv(&mut self) -> SubscriptionEvent {
+        core::future::poll_fn(|cx| self.sd_state.get().poll_subscription_event(cx)).await
+    }
+
+    /// Take the next queued event without waiting.
+    pub fn try_recv(&mut self) -> Option<SubscriptionEvent> {
+        self.sd_state.get().try_subscription_event()
+    }
+}
+
+/// Never ends: the queue outlives the server's run-future.
+impl<Hsd: SharedHandle<SdStateManager>> futures_util::Stream for SubscriptionEvents<Hsd> {
+    type Item = SubscriptionEvent;
+
+    fn poll_next(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.sd_state.get().poll_subscription_event(cx).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::net::Ipv4Addr;
+
+    fn event(event_group_id: u16) -> SubscriptionEvent {
+        SubscriptionEvent {
+            kind: SubscriptionEventKind::Subscribed,
+            subscriber: SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30_000),
+            service_id: 0x5B,
+            instance_id: 1,
+            event_group_id,
+        }
+    }
+
+    #[test]
+    fn full_queue_drops_oldest() {
+        let mut queue = SubscriptionEventQueue::new();
+        let cap = u16::try_from(SUBSCRIPTION_EVENTS_CAP).unwrap();
+        for group in 0..=cap {
+            queue.push(event(group));
+        }
+        assert_eq!(queue.pop(), Some(event(1)), "oldest dropped");
+        let rest = core::iter::from_fn(|| queue.pop()).count();
+        assert_eq!(rest, SUBSCRIPTION_EVENTS_CAP - 1);
+    }
+}
@@ -1,8 +1,10 @@
 //! Manages event group subscriptions
 
 use super::service_info::Subscriber;
+use super::subscription_events::SUBSCRIPTION_EVENTS_CAP;
 use core::future::Future;
 use core::net::SocketAddrV4;
+use core::time::Duration;
 use heapless::{Vec as HeaplessVec, index_map::FnvIndexMap};
 #[cfg(feature = "server-tokio")]
 use std::sync::Arc;
@@ -36,6 +38,9 @@ const EVENT_GROUPS_CAP: usize = crate::from_env_or(
 pub(crate) const SUBSCRIBERS_PER_GROUP: usize =
     crate::from_env_or(option_env!("SIMPLE_SOMEIP_MAX_SUBS"), DEFAULT_SUBSCRIBERS);
 
+/// Max number of subscribers across all event groups.
+pub(crate) const SUBSCRIPTIONS_CAP: usize = EVENT_GROUPS_CAP * SUBSCRIBERS_PER_GROUP;
+
 // Compile-time invariants. Trip these at `cargo build` so that retuning
 // the constants above can't quietly produce a `subscribe` impl that
 // panics on first push (zero `SUBSCRIBERS_PER_GROUP`) or that fails the
@@ -78,8 +83,20 @@ impl core::fmt::Display for SubscribeError {
     }
 }
 
+/// What a successful [`SubscriptionManager::subscribe`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscribeOutcome {
+    /// The subscriber was not subscribed before and has been added.
+    New,
+    /// The subscriber was already subscribed; its lease was renewed.
+    Refreshed,
+}
+
 type SubscribersList = HeaplessVec<Subscriber, SUBSCRIBERS_PER_GROUP>;
 
+/// Subscribers removed by one [`SubscriptionHandle::expire`].
+pub type ExpiredSubscribers = HeaplessVec<Subscriber, SUBSCRIPTION_EVENTS_CAP>;
+
 /// Manages subscriptions to event groups.
 ///
 /// Capacity is bounded at compile time: up to `EVENT_GROUPS_CAP` distinct
@@ -101,15 +118,40 @@ impl SubscriptionManager {
         }
     }
 
-    /// Add a subscriber to an event group.
+    /// Add a subscriber to an event group that does not lapse: a
+    /// [`Self::subscribe_for`] with [`sd::TTL_INFINITE`](crate::protocol::sd::TTL_INFINITE)
+    /// and no clock.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::subscribe_for`].
+    pub fn subscribe(
+        &mut self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        subscriber_addr: SocketAddrV4,
+    ) -> Result<SubscribeOutcome, SubscribeError> {
+        self.subscribe_for(
+            service_id,
+            instance_id,
+            event_group_id,
+            subscriber_addr,
+            crate::protocol::sd::TTL_INFINITE,
+            None,
+        )
+    }
+
+    /// Add a subscriber to an event group for `ttl` seconds from `now`,
+    /// or renew its lease.
     ///
-    /// Returns `Ok(())` both when a new subscriber is added and when the
-    /// given `(service_id, instance_id, event_group_id, subscriber_addr)`
-    /// is already subscribed — the call is idempotent / deduplicated, and
-    /// no stored subscriber state is modified on a duplicate. There is no
-    /// TTL bump or other refresh side-effect today; if TTL-refresh
-    /// semantics are added later, this docstring and the duplicate-log
-    /// wording will be updated together.
+    /// Returns `Ok(SubscribeOutcome::New)` when a new subscriber is added
+    /// and `Ok(SubscribeOutcome::Refreshed)` when the given
+    /// `(service_id, instance_id, event_group_id, subscriber_addr)` is
+    /// already subscribed — the call is deduplicated and only replaces
+    /// the subscriber's TTL and renewal time. [`Self::expire`] removes
+    /// subscribers whose lease ran out; one with a `now` of `None` or a
+    /// TTL of `TTL_INFINITE` never runs out.
     ///
     /// Returns `Err(SubscribeError)` when the request could not be
     /// recorded because a bounded capacity was hit — the caller
@@ -132,35 +174,41 @@ impl SubscriptionManager {
     /// Panics if `SUBSCRIBERS_PER_GROUP == 0`, a compile-time constant that
     /// must be at least one for a newly-allocated subscriber list to accept
     /// its first entry.
-    pub fn subscribe(
+    pub fn subscribe_for(
         &mut self,
         service_id: u16,
         instance_id: u16,
         event_group_id: u16,
         subscriber_addr: SocketAddrV4,
-    ) -> Result<(), SubscribeError> {
+        ttl: u32,
+        now: Option<Duration>,
+    ) -> Result<SubscribeOutcome, SubscribeError> {
         let key = (service_id, instance_id, event_group_id);
 
         if let Some(subscribers) = self.subscriptions.get_mut(&key) {
-            // Deduplicate: if this address is already subscribed, skip adding
-            // it again. No stored subscriber state is modified — the log
-            // message reflects that. If real refresh semantics (e.g. TTL
-            // bump on re-subscribe) are wanted later, update the per-
-            // subscriber record here and rename the log accordingly.
-            if subscribers.iter().any(|s| s.address == subscriber_addr) {
+            // Deduplicate: an address that is already subscribed renews
+            // its lease instead of being added again.
+            if let Some(existing) = subscribers
+                .iter_mut()
+                .find(|s| s.address == subscriber_addr)
+            {
+                existing.ttl = ttl;
+                existing.renewed_at = now;
                 crate::log::debug!(
-                    "Subscriber {} already subscribed for service 0x{:04X}, instance {}, \
-                     event group 0x{:04X}; skipping duplicate",
+                    "Subscriber {} renewed for service 0x{:04X}, instance {}, \
+                     event group 0x{:04X} (TTL {})",
                     subscriber_addr,
                     service_id,
                     instance_id,
-                    event_group_id
+                    event_group_id,
+                    ttl
                 );
-                return Ok(());
+                return Ok(SubscribeOutcome::Refreshed);
             }
 
             let subscriber =
-                Subscriber::new(subscriber_addr, service_id, instance_id, event_group_id);
+                Subscriber::new(subscriber_addr, service_id, instance_id, event_group_id)
+                    .with_lease(ttl, now);
             if subscribers.push(subscriber).is_err() {
                 crate::log::warn!(
                     "Subscribers-per-group at capacity ({}); dropping new subscriber {} \
@@ -181,7 +229,7 @@ impl SubscriptionManager {
                 instance_id,
                 event_group_id
             );
-            return Ok(());
+            return Ok(SubscribeOutcome::New);
         }
 
         // New event group — allocate the list and insert.
@@ -191,12 +239,10 @@ impl SubscriptionManager {
         // definition). Use `expect` here — a future refactor setting the
         // cap to 0 would trip this at test time instead of silently
         // dropping the only subscriber for a new event group.
-        list.push(Subscriber::new(
-            subscriber_addr,
-            service_id,
-            instance_id,
-            event_group_id,
-        ))
+        list.push(
+            Subscriber::new(subscriber_addr, service_id, instance_id, event_group_id)
+                .with_lease(ttl, now),
+        )
         .expect(
             "new SubscribersList must accept the first subscriber; \
              SUBSCRIBERS_PER_GROUP must be >= 1",
@@ -222,10 +268,16 @@ impl SubscriptionManager {
             instance_id,
             event_group_id
         );
-        Ok(())
+        Ok(SubscribeOutcome::New)
     }
 
-    /// Remove a subscriber from an event group
+    /// Remove a subscriber from an event group.
+    ///
+    /// The removal path for a peer's `StopSubscribeEventgroup`, a
+    /// rolled-back subscribe whose ack could not be sent, and external
+    /// cleanup via `EventPublisher::remove_subscriber`; lapsed leases are
+    /// removed by [`Self::expire`]. Removing an address that is not
+    /// subscribed is a no-op.
     pub fn unsubscribe(
         &mut self,
         service_id: u16,
@@ -252,6 +304,33 @@ impl SubscriptionManager {
         }
     }
 
+    /// Remove every subscriber whose lease ran out at `now`, calling
+    /// `on_expired` with each. Returns how many were removed.
+    pub fn expire(&mut self, now: Duration, mut on_expired: impl FnMut(&Subscriber)) -> usize {
+        let mut expired = 0;
+        self.subscriptions.retain(|_, subscribers| {
+            subscribers.retain(|s| {
+                if s.expires_at().is_some_and(|at| at <= now) {
+                    crate::log::info!(
+                        "Subscription of {} to service 0x{:04X}, instance {}, \
+                         event group 0x{:04X} expired",
+                        s.address,
+                        s.service_id,
+                        s.instance_id,
+                        s.event_group_id
+                    );
+                    on_expired(s);
+                    expired += 1;
+                    false
+                } else {
+                    true
+                }
+            });
+            !subscribers.is_empty()
+        });
+        expired
+    }
+
     /// Get all subscribers for an event group as a heap-allocated `Vec`.
     ///
     /// Convenience accessor for `std` consumers (testing, ad-hoc tooling).
@@ -286,6 +365,11 @@ impl SubscriptionManager {
     pub fn subscription_count(&self) -> usize {
         self.subscriptions.values().map(|v| v.len()).sum()
     }
+
+    /// Every subscriber of every event group, group by group.
+    pub fn subscribers(&self) -> impl Iterator<Item = &Subscriber> {
+        self.subscriptions.values().flatten()
+    }
 }
 
 impl Default for SubscriptionManager {
@@ -326,7 +410,7 @@ pub trait SubscriptionHandle: Clone + 'static {
     /// (`Arc<RwLock<SubscriptionManager>>`) box a `Send` future so
     /// `Server::run`'s `Send` where clause is satisfiable; bare-metal
     /// implementations are free to leave it `!Send`.
-    type SubscribeFuture<'a>: Future<Output = Result<(), SubscribeError>> + 'a
+    type SubscribeFuture<'a>: Future<Output = Result<SubscribeOutcome, SubscribeError>> + 'a
     where
         Self: 'a;
 
@@ -336,11 +420,18 @@ pub trait SubscriptionHandle: Clone + 'static {
     where
         Self: 'a;
 
-    /// Add a subscriber to an event group.
+    /// Future returned by [`Self::expire`]. Same `Send`-or-not freedom
+    /// as [`Self::SubscribeFuture`].
+    type ExpireFuture<'a>: Future<Output = ExpiredSubscribers> + 'a
+    where
+        Self: 'a;
+
+    /// Add a subscriber to an event group that does not lapse.
     ///
-    /// Idempotent: if the subscriber is already present, this is a no-op
-    /// returning `Ok(())`. Returns `Err(SubscribeError)` if a capacity
-    /// limit would be exceeded.
+    /// Idempotent: if the subscriber is already present, this returns
+    /// `Ok(SubscribeOutcome::Refreshed)`; a newly added one returns
+    /// `Ok(SubscribeOutcome::New)`. Returns `Err(SubscribeError)` if a
+    /// capacity limit would be exceeded.
     ///
     /// Timing note: implementations whose critical section is fully
     /// synchronous (e.g. `StaticSubscriptionHandle`) may perform the
@@ -355,6 +446,26 @@ pub trait SubscriptionHandle: Clone + 'static {
         subscriber_addr: SocketAddrV4,
     ) -> Self::SubscribeFuture<'_>;
 
+    /// Add a subscriber to an event group for `ttl` seconds from `now`,
+    /// or renew the lease of one already present. See
+    /// [`SubscriptionManager::subscribe_for`]; same timing note as
+    /// [`Self::subscribe`].
+    ///
+    /// The default ignores the lease and calls [`Self::subscribe`], for
+    /// stores that keep subscribers until they are removed.
+    fn subscribe_for(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        subscriber_addr: SocketAddrV4,
+        ttl: u32,
+        now: Option<Duration>,
+    ) -> Self::SubscribeFuture<'_> {
+        let _ = (ttl, now);
+        self.subscribe(service_id, instance_id, event_group_id, subscriber_addr)
+    }
+
     /// Remove a subscriber from an event group.
     ///
     /// Same construction-time-mutation caveat as [`Self::subscribe`].
@@ -387,6 +498,51 @@ pub trait SubscriptionHandle: Clone + 'static {
     ) -> impl Future<Output = usize> + 'a
     where
         F: FnMut(&Subscriber) + 'a;
+
+    /// Addresses of the subscribers of an event group, copied out so the
+    /// caller can send to them without holding the store's lock.
+    ///
+    /// The default collects them through [`Self::for_each_subscriber`].
+    /// Stores shared with `Send` tasks override it with a future that
+    /// captures no visitor closure: the borrowing closure keeps rustc from
+    /// proving the enclosing publish future `Send`.
+    fn subscriber_addresses(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> impl Future<Output = HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP>> + '_ {
+        async move {
+            let mut addrs = HeaplessVec::new();
+            self.for_each_subscriber(service_id, instance_id, event_group_id, |sub| {
+                // Never fails: the store's per-group cap is the buffer's.
+                let _ = addrs.push(sub.address);
+            })
+            .await;
+            addrs
+        }
+    }
+
+    /// Remove every subscriber whose lease ran out at `now` and return
+    /// them, up to [`SUBSCRIPTION_EVENTS_CAP`]; further ones are removed
+    /// as well. See [`SubscriptionManager::expire`]. Stores that ignore
+    /// leases in [`Self::subscribe_for`] return an empty list.
+    fn expire(&self, now: Duration) -> Self::ExpireFuture<'_>;
+
+    /// Visit every subscriber of every event group with `f`, under the
+    /// same rules as [`Self::for_each_subscriber`]. Returns the number
+    /// visited.
+    ///
+    /// The default visits none, for stores that can only look
+    /// subscribers up by event group; their subscribers are missing from
+    /// [`Server::state_snapshot`](super::Server::state_snapshot).
+    fn for_each_subscription<'a, F>(&'a self, f: F) -> impl Future<Output = usize> + 'a
+    where
+        F: FnMut(&Subscriber) + 'a,
+    {
+        let _ = f;
+        core::future::ready(0)
+    }
 }
 
 #[cfg(feature = "server-tokio")]
@@ -396,10 +552,14 @@ impl SubscriptionHandle for Arc<RwLock<SubscriptionManager>> {
     /// (~1 Hz subscribes during steady state), small cost relative to
     /// the wire-side activity it gates.
     type SubscribeFuture<'a> = core::pin::Pin<
-        alloc::boxed::Box<dyn Future<Output = Result<(), SubscribeError>> + Send + 'a>,
+        alloc::boxed::Box<
+            dyn Future<Output = Result<SubscribeOutcome, SubscribeError>> + Send + 'a,
+        >,
     >;
     type UnsubscribeFuture<'a> =
         core::pin::Pin<alloc::boxed::Box<dyn Future<Output = ()> + Send + 'a>>;
+    type ExpireFuture<'a> =
+        core::pin::Pin<alloc::boxed::Box<dyn Future<Output = ExpiredSubscribers> + Send + 'a>>;
 
     fn subscribe(
         &self,
@@ -407,12 +567,36 @@ impl SubscriptionHandle for Arc<RwLock<SubscriptionManager>> {
         instance_id: u16,
         event_group_id: u16,
         subscriber_addr: SocketAddrV4,
+    ) -> Self::SubscribeFuture<'_> {
+        self.subscribe_for(
+            service_id,
+            instance_id,
+            event_group_id,
+            subscriber_addr,
+            crate::protocol::sd::TTL_INFINITE,
+            None,
+        )
+    }
+
+    fn subscribe_for(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+        subscriber_addr: SocketAddrV4,
+        ttl: u32,
+        now: Option<Duration>,
     ) -> Self::SubscribeFuture<'_> {
         let this = self.clone();
         alloc::boxed::Box::pin(async move {
-            this.write()
-                .await
-                .subscribe(service_id, instance_id, event_group_id, subscriber_addr)
+            this.write().await.subscribe_for(
+                service_id,
+                instance_id,
+                event_group_id,
+                subscriber_addr,
+                ttl,
+                now,
+            )
         })
     }
 
@@ -459,6 +643,50 @@ impl SubscriptionHandle for Arc<RwLock<SubscriptionManager>> {
             }
         }
     }
+
+    fn subscriber_addresses(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        event_group_id: u16,
+    ) -> impl Future<Output = HeaplessVec<SocketAddrV4, SUBSCRIBERS_PER_GROUP>> + '_ {
+        let this = self.clone();
+        async move {
+            let guard = this.read().await;
+            guard
+                .subscriptions
+                .get(&(service_id, instance_id, event_group_id))
+                .map(|list| list.iter().map(|sub| sub.address).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    fn expire(&self, now: Duration) -> Self::ExpireFuture<'_> {
+        let this = self.clone();
+        alloc::boxed::Box::pin(async move {
+            let mut expired = ExpiredSubscribers::new();
+            this.write().await.expire(now, |sub| {
+                let _ = expired.push(sub.clone());
+            });
+            expired
+        })
+    }
+
+    fn for_each_subscription<'a, F>(&'a self, mut f: F) -> impl Future<Output = usize> + 'a
+    where
+        F: FnMut(&Subscriber) + 'a,
+    {
+        let this = self.clone();
+        async move {
+            let guard = this.read().await;
+            let mut visited = 0;
+            for sub in guard.subscribers() {
+                f(sub);
+                visited += 1;
+            }
+            visited
+        }
+    }
 }
 
 /// No-alloc [`SubscriptionHandle`] backed by a `&'static`
@@ -489,10 +717,14 @@ impl SubscriptionHandle for Arc<RwLock<SubscriptionManager>> {
 /// ```
 #[cfg(feature = "bare_metal")]
 pub mod bare_metal_subscription_impl {
-    use super::{SubscribeError, Subscriber, SubscriptionHandle, SubscriptionManager};
+    use super::{
+        ExpiredSubscribers, SubscribeError, SubscribeOutcome, Subscriber, SubscriptionHandle,
+        SubscriptionManager,
+    };
     use core::cell::RefCell;
     use core::future::Future;
     use core::net::SocketAddrV4;
+    use core::time::Duration;
     use embassy_sync::blocking_mutex::Mutex;
     use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 
@@ -532,8 +764,9 @@ pub mod bare_metal_subscription_impl {
         // through the poll. The in-tree caller awaits immediately, so
         // the difference from the lazy boxed impls is unobservable
         // there.
-        type SubscribeFuture<'a> = core::future::Ready<Result<(), SubscribeError>>;
+        type SubscribeFuture<'a> = core::future::Ready<Result<SubscribeOutcome, SubscribeError>>;
         type UnsubscribeFuture<'a> = core::future::Ready<()>;
+        type ExpireFuture<'a> = core::future::Ready<ExpiredSubscribers>;
 
         fn subscribe(
             &self,
@@ -541,14 +774,35 @@ pub mod bare_metal_subscription_impl {
             instance_id: u16,
             event_group_id: u16,
             subscriber_addr: SocketAddrV4,
+        ) -> Self::SubscribeFuture<'_> {
+            self.subscribe_for(
+                service_id,
+                instance_id,
+                event_group_id,
+                subscriber_addr,
+                crate::protocol::sd::TTL_INFINITE,
+                None,
+            )
+        }
+
+        fn subscribe_for(
+            &self,
+            service_id: u16,
+            instance_id: u16,
+            event_group_id: u16,
+            subscriber_addr: SocketAddrV4,
+            ttl: u32,
+            now: Option<Duration>,
         ) -> Self::SubscribeFuture<'_> {
             let storage = self.0;
             core::future::ready(storage.lock(|cell| {
-                cell.borrow_mut().subscribe(
+                cell.borrow_mut().subscribe_for(
                     service_id,
                     instance_id,
                     event_group_id,
                     subscriber_addr,
+                    ttl,
+                    now,
                 )
             }))
         }
@@ -599,6 +853,34 @@ pub mod bare_metal_subscription_impl {
                 })
             }
         }
+
+        fn expire(&self, now: Duration) -> Self::ExpireFuture<'_> {
+            let storage = self.0;
+            let mut expired = ExpiredSubscribers::new();
+            storage.lock(|cell| {
+                cell.borrow_mut().expire(now, |sub| {
+                    let _ = expired.push(sub.clone());
+                });
+            });
+            core::future::ready(expired)
+        }
+
+        fn for_each_subscription<'a, F>(&'a self, mut f: F) -> impl Future<Output = usize> + 'a
+        where
+            F: FnMut(&Subscriber) + 'a,
+        {
+            let storage = self.0;
+            async move {
+                storage.lock(|cell| {
+                    let mut visited = 0;
+                    for sub in cell.borrow().subscribers() {
+                        f(sub);
+                        visited += 1;
+                    }
+                    visited
+                })
+            }
+        }
     }
 }
 
@@ -635,14 +917,57 @@ mod tests {
         let mut manager = SubscriptionManager::new();
         let addr = SocketAddrV4::new(Ipv4Addr::new(192, 168, 1, 1), 8080);
 
-        manager.subscribe(0x5B, 1, 0x01, addr).unwrap();
+        assert_eq!(
+            manager.subscribe(0x5B, 1, 0x01, addr),
+            Ok(SubscribeOutcome::New)
+        );
         assert_eq!(manager.subscription_count(), 1);
 
         // Subscribe same address again — should deduplicate
-        manager.subscribe(0x5B, 1, 0x01, addr).unwrap();
+        assert_eq!(
+            manager.subscribe(0x5B, 1, 0x01, addr),
+            Ok(SubscribeOutcome::Refreshed)
+        );
         assert_eq!(manager.subscription_count(), 1);
     }
 
+    #[test]
+    fn resubscribe_renews_the_lease_and_expire_drops_lapsed_ones() {
+        let mut manager = SubscriptionManager::new();
+        let a1 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 8001);
+        let a2 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 8002);
+        let at = Duration::from_secs;
+
+        manager
+            .subscribe_for(0x5B, 1, 0x01, a1, 3, Some(at(10)))
+            .unwrap();
+        manager
+            .subscribe_for(0x5B, 1, 0x02, a2, 3, Some(at(10)))
+            .unwrap();
+        manager.subscribe(0x5B, 1, 0x03, a1).unwrap();
+        assert_eq!(
+            manager.subscribe_for(0x5B, 1, 0x02, a2, 5, Some(at(12))),
+            Ok(SubscribeOutcome::Refreshed)
+        );
+        assert_eq!(manager.expire(at(12), |_| panic!("nothing lapsed yet")), 0);
+
+        let mut expired = Vec::new();
+        assert_eq!(manager.expire(at(13), |s| expired.push(s.address)), 1);
+        assert_eq!(expired, [a1]);
+        assert!(manager.get_subscribers(0x5B, 1, 0x01).is_empty());
+        let renewed = manager.get_subscribers(0x5B, 1, 0x02);
+        assert_eq!(renewed.len(), 1);
+        assert_eq!(renewed[0].expires_at(), Some(at(17)));
+
+        assert_eq!(manager.expire(at(17), |_| {}), 1);
+        assert!(manager.get_subscribers(0x5B, 1, 0x02).is_empty());
+        assert_eq!(
+            manager.subscription_count(),
+            1,
+            "a subscription without a lease never lapses"
+        );
+    }
+
     #[test]
     fn test_unsubscribe_nonexistent_key() {
         let mut manager = SubscriptionManager::new();
@@ -766,6 +1091,11 @@ mod tests {
         manager.subscribe(0x5B, 1, 0x02, a).unwrap();
         manager.subscribe(0x5C, 1, 0x01, a).unwrap();
         assert_eq!(manager.subscription_count(), 3);
+        let groups: Vec<_> = manager
+            .subscribers()
+            .map(|s| (s.service_id, s.event_group_id))
+            .collect();
+        assert_eq!(groups, [(0x5B, 0x01), (0x5B, 0x02), (0x5C, 0x01)]);
     }
 
     #[test]
@@ -0,0 +1,131 @@
+//! Owned handle for a server run-loop spawned on tokio.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use std::boxed::Box;
+
+use super::Error;
+
+/// Future that withdraws the offer when a [`ServerTask`] stops.
+pub(super) type StopOfferFuture = Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>;
+
+/// How long [`ServerTask::stop`] waits for the run-loop to finish the
+/// request it is handling before aborting it.
+const STOP_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Handle to a server run-loop spawned with
+/// [`Server::start`](super::Server::start).
+///
+/// Owns the spawned task that drives both the receive loop and the
+/// `OfferService` announcements, so the application no longer has to keep
+/// [`Server::run`](super::Server::run)'s future alive in its own select
+/// loop. Dropping the handle detaches the task, as with
+/// [`tokio::task::JoinHandle`]; call [`Self::stop`] or [`Self::abort`] to
+/// end it.
+///
+/// Awaiting the handle waits for the run-loop to finish and yields its
+/// result. A task ended through [`Self::abort`] resolves to `Ok(())`; a
+/// panic inside the run-loop is resumed on the awaiting task.
+pub struct ServerTask {
+    run: tokio::task::JoinHandle<Result<(), Error>>,
+    stop_offer: Option<StopOfferFuture>,
+    shutdown: Option<tokio::sync::oneshot::Sender<()>>,
+    service_id: u16,
+}
+
+impl core::fmt::Debug for ServerTask {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ServerTask")
+            .field("service_id", &self.service_id)
+            .field("finished", &self.run.is_finished())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerTask {
+    pub(super) fn new(
+        run: tokio::task::JoinHandle<Result<(), Error>>,
+        stop_offer: Option<StopOfferFuture>,
+        shutdown: tokio::sync::oneshot::Sender<()>,
+        service_id: u16,
+    ) -> Self {
+        Self {
+            run,
+            stop_offer,
+            shutdown: Some(shutdown),
+            service_id,
+        }
+    }
+
+    /// `true` once the run-loop has exited, for whatever reason.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.run.is_finished()
+    }
+
+    /// Cancel the run-loop immediately. No `StopOfferService` is sent:
+    /// clients keep the offer until its TTL lapses. Use [`Self::stop`]
+    /// to withdraw the offer explicitly.
+    pub fn abort(&self) {
+        self.run.abort();
+    }
+
+    /// Stop the server gracefully: multicast a `StopOfferService` so
+    /// clients drop the service at once instead of waiting for the TTL
+    /// to run out, then tell the run-loop to finish. The loop answers
+    /// the request it is handling, if any, before it ends; one still
+    /// busy after a second is aborted.
+    ///
+    /// Passive servers and servers configured with
+    /// [`ServerConfig::with_announce`](super::ServerConfig::with_announce)
+    /// `(false)` never offered the service themselves and send no
+    /// `StopOfferService`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the run-loop's own error if it failed, otherwise any
+    /// error from sending the `StopOfferService`. The offer is withdrawn
+    /// in either case.
+    pub async fn stop(mut self) -> Result<(), Error> {
+        let stop_offer = match self.stop_offer.take() {
+            Some(stop_offer) => stop_offer.await,
+            None => Ok(()),
+        };
+        if let Some(shutdown) = self.shutdown.take() {
+            // Fails only if the loop already ended, which the join below reports.
+            let _ = shutdown.send(());
+        }
+        let outcome =
+            if let Ok(result) = tokio::time::timeout(STOP_DRAIN_TIMEOUT, &mut self.run).await {
+                map_join_result(result)
+            } else {
+                crate::log::warn!(
+                    "Server for service 0x{:04X} still busy after {:?}; aborting",
+                    self.service_id,
+                    STOP_DRAIN_TIMEOUT
+                );
+                self.run.abort();
+                map_join_result((&mut self.run).await)
+            };
+        crate::log::info!("Server for service 0x{:04X} stopped", self.service_id);
+        outcome.and(stop_offer)
+    }
+}
+
+impl Future for ServerTask {
+    type Output = Result<(), Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.run).poll(cx).map(map_join_result)
+    }
+}
+
+fn map_join_result(result: Result<Result<(), Error>, tokio::task::JoinError>) -> Result<(), Error> {
+    match result {
+        Ok(outcome) => outcome,
+        Err(err) if err.is_cancelled() => Ok(()),
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
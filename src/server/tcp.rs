@@ -0,0 +1,249 @@
+//! Request/response over TCP, driven by
+//! [`Server::serve_tcp_with_buffers`](super::Server::serve_tcp_with_buffers).
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use core::time::Duration;
+
+use futures_util::pin_mut;
+use heapless::Vec;
+
+use crate::Timer;
+use crate::janitor::Janitor;
+use crate::transport::{
+    E2ERegistryHandle, ReceivedDatagram, StreamListener, TransportError, TransportSocket,
+};
+
+use super::sd_state::SdStateManager;
+use super::{Error, NonSdRequestCallback, ServerConfig, request_check, runtime};
+
+#[cfg(feature = "bare_metal")]
+const _DEFAULT_TCP_CONNECTIONS_CAP: usize = 1;
+#[cfg(not(feature = "bare_metal"))]
+const _DEFAULT_TCP_CONNECTIONS_CAP: usize = 4;
+
+/// Maximum number of TCP connections one server serves at a time; further
+/// clients wait in the listener's backlog until one closes. Sized via
+/// `SIMPLE_SOMEIP_SERVER_TCP_CONNECTIONS_CAP`; defaults to 1 on
+/// bare-metal, 4 otherwise.
+pub const TCP_CONNECTIONS_CAP: usize = crate::from_env_or(
+    option_env!("SIMPLE_SOMEIP_SERVER_TCP_CONNECTIONS_CAP"),
+    _DEFAULT_TCP_CONNECTIONS_CAP,
+);
+
+enum Event<S> {
+    Accepted(Result<S, TransportError>),
+    Received(usize, Result<ReceivedDatagram, TransportError>),
+    /// The janitor interval elapsed.
+    Tick,
+}
+
+/// One open connection: the stream, its slice of the receive buffer, and
+/// [`Timer::now`] when it last carried a message.
+type Connection<'b, S> = (S, &'b mut [u8], Option<Duration>);
+
+/// Close the connections that have been idle too long at `now`,
+/// returning their buffer slices to `free`. Returns how many were
+/// closed.
+fn close_idle<'b, S>(
+    janitor: &Janitor,
+    connections: &mut Vec<Connection<'b, S>, TCP_CONNECTIONS_CAP>,
+    free: &mut Vec<&'b mut [u8], TCP_CONNECTIONS_CAP>,
+    now: Duration,
+) -> u64 {
+    let mut closed = 0;
+    let mut index = 0;
+    while index < connections.len() {
+        if connections[index]
+            .2
+            .is_some_and(|last_active| janitor.is_idle(last_active, now))
+        {
+            crate::log::debug!("Janitor: closing idle TCP connection");
+            // Dropping the stream closes it.
+            let (_, buf, _) = connections.swap_remove(index);
+            let _ = free.push(buf);
+            closed += 1;
+        } else {
+            index += 1;
+        }
+    }
+    closed
+}
+
+/// Split `recv_buf` evenly into the [`TCP_CONNECTIONS_CAP`] receive
+/// buffers [`serve_tcp`] takes.
+///
+/// # Errors
+///
+/// Returns [`Error::Capacity`] (tag `"tcp_recv_buffer"`) if a slice
+/// would be shorter than a SOME/IP header.
+pub(super) fn split_recv_buf(
+    recv_buf: &mut [u8],
+) -> Result<Vec<&mut [u8], TCP_CONNECTIONS_CAP>, Error> {
+    let slot_len = recv_buf.len() / TCP_CONNECTIONS_CAP;
+    if slot_len < crate::sd_codec::SOMEIP_HEADER_LEN {
+        return Err(Error::Capacity("tcp_recv_buffer"));
+    }
+    Ok(recv_buf.chunks_exact_mut(slot_len).collect())
+}
+
+/// Accept connections on `listener` and answer the requests they carry
+/// through the non-SD observer, as the UDP receive loop does.
+///
+/// Each connection receives into one of the `free` buffers, which bounds
+/// the largest message it can receive; only as many connections as there
+/// are buffers are served at a time. A connection that closes or fails is
+/// dropped and its buffer reused. SD messages are ignored: SD runs over
+/// UDP only.
+///
+/// With [`ServerConfig::janitor`] set, connections silent for its
+/// `tcp_idle_timeout` are closed as well.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+pub(super) async fn serve_tcp<L, R, Tm>(
+    config: &ServerConfig,
+    listener: &L,
+    sd_state: &SdStateManager,
+    e2e: &R,
+    timer: &Tm,
+    non_sd_observer: Option<(NonSdRequestCallback, usize)>,
+    mut free: Vec<&mut [u8], TCP_CONNECTIONS_CAP>,
+    send_buf: &mut [u8],
+) -> Result<(), Error>
+where
+    L: StreamListener,
+    R: E2ERegistryHandle,
+    Tm: Timer,
+{
+    let mut connections: Vec<Connection<'_, L::Stream>, TCP_CONNECTIONS_CAP> = Vec::new();
+    // Rotates the connection polled first, so one busy peer cannot starve
+    // the others.
+    let mut first = 0usize;
+    let mut janitor = config
+        .janitor
+        .filter(|janitor| janitor.tcp_idle_timeout.is_some())
+        .map(Janitor::new);
+    loop {
+        // Swept before the futures below borrow `connections`, whose
+        // indices the sweep shifts.
+        if let (Some(janitor), Some(now)) = (janitor.as_mut(), timer.now())
+            && janitor.start_sweep(now)
+        {
+            let closed = close_idle(janitor, &mut connections, &mut free, now);
+            sd_state.with_janitor_stats(|stats| {
+                stats.sweeps += 1;
+                stats.tcp_connections_closed += closed;
+            });
+        }
+        let event = {
+            // Only accept while a buffer slice is free; further clients
+            // queue in the listener's backlog.
+            let accept = (!free.is_empty()).then(|| listener.accept());
+            pin_mut!(accept);
+            let tick = janitor
+                .as_ref()
+                .map(|janitor| timer.sleep(janitor.config.interval));
+            pin_mut!(tick);
+            let mut receives: Vec<_, TCP_CONNECTIONS_CAP> = connections
+                .iter_mut()
+                .map(|(stream, buf, _)| stream.recv_from(buf))
+                .collect();
+            let count = receives.len();
+            core::future::poll_fn(|cx| {
+                for offset in 0..count {
+                    let index = (first + offset) % count;
+                    // SAFETY: `receives` is neither moved nor resized while
+                    // its futures are alive; they are dropped in place with
+                    // it at the end of this block.
+                    let receive = unsafe { Pin::new_unchecked(&mut receives[index]) };
+                    if let Poll::Ready(result) = receive.poll(cx) {
+                        return Poll::Ready(Event::Received(index, result));
+                    }
+                }
+                if let Some(accept) = accept.as_mut().as_pin_mut()
+                    && let Poll::Ready(result) = accept.poll(cx)
+                {
+                    return Poll::Ready(Event::Accepted(result));
+                }
+                if let Some(tick) = tick.as_mut().as_pin_mut()
+                    && tick.poll(cx).is_ready()
+                {
+                    return Poll::Ready(Event::Tick);
+                }
+                Poll::Pending
+            })
+            .await
+        };
+        first = first.wrapping_add(1);
+
+        match event {
+            Event::Accepted(accepted) => {
+                let stream = accepted?;
+                crate::log::debug!(
+                    "Accepted TCP connection for service 0x{:04X}",
+                    config.service_id
+                );
+                if let Some(buf) = free.pop() {
+                    // Cannot fail: a free slice means a free connection slot.
+                    let _ = connections.push((stream, buf, timer.now()));
+                }
+            }
+            Event::Received(index, Err(e)) => {
+                if matches!(e, TransportError::ConnectionClosed) {
+                    crate::log::debug!("TCP connection closed");
+                } else {
+                    crate::log::warn!("TCP connection failed: {:?}", e);
+                }
+                let (_, buf, _) = connections.swap_remove(index);
+                let _ = free.push(buf);
+            }
+            Event::Tick => {}
+            Event::Received(index, Ok(datagram)) => {
+                let now = timer.now();
+                connections[index].2 = now;
+                let (stream, buf, _) = &connections[index];
+                let source = datagram.source;
+                let addr = core::net::SocketAddr::V4(source);
+                sd_state.with_peers(|peers| peers.record_received(addr, now));
+                if datagram.truncated {
+                    crate::log::warn!(
+                        "Dropped {}-byte TCP message from {}: larger than the {}-byte buffer",
+                        datagram.bytes_received,
+                        addr,
+                        buf.len()
+                    );
+                    continue;
+                }
+                let data = &buf[..datagram.bytes_received];
+                match runtime::parse_datagram(config, data, addr) {
+                    Ok(view) if view.is_sd() => {
+                        crate::log::trace!("SD message over TCP from {}, ignoring", addr);
+                    }
+                    Ok(view) => {
+                        if let Some(responder) =
+                            runtime::Responder::current(sd_state, non_sd_observer)
+                        {
+                            runtime::dispatch_non_sd_request(
+                                config, stream, sd_state, &responder, e2e, &view, source, send_buf,
+                            )
+                            .await;
+                        } else {
+                            request_check::reject_unserved(
+                                config, stream, sd_state, &view, source, send_buf,
+                            )
+                            .await;
+                        }
+                    }
+                    Err(e) => {
+                        sd_state.with_peers(|peers| peers.record_decode_error(addr, &e));
+                        crate::log::warn!("Failed to parse SOME/IP header from {}: {:?}", addr, e);
+                        runtime::reject_protocol_version(
+                            stream, sd_state, &e, data, addr, send_buf,
+                        )
+                        .await;
+                    }
+                }
+            }
+        }
+    }
+}
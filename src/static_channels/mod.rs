@@ -599,6 +599,15 @@ impl<T: Send + 'static, const SLOT_CAP: usize> MpscSend<T> for StaticBoundedSend
         })
         .await
     }
+
+    fn try_send(&self, value: T) -> Result<(), T> {
+        if self.slot.closed.load(Ordering::Acquire) {
+            return Err(value);
+        }
+        self.slot.chan.try_send(value).map_err(|e| match e {
+            embassy_sync::channel::TrySendError::Full(value) => value,
+        })
+    }
 }
 
 /// Bounded receiver backed by a [`MpscPool`].
@@ -685,6 +694,10 @@ impl<T: Send + 'static, const SLOT_CAP: usize> UnboundedSend<T>
             embassy_sync::channel::TrySendError::Full(v) => v,
         })
     }
+
+    fn is_closed(&self) -> bool {
+        self.slot.closed.load(Ordering::Acquire)
+    }
 }
 
 /// Unbounded receiver.
@@ -714,6 +727,10 @@ impl<T: Send + 'static, const SLOT_CAP: usize> UnboundedRecv<T>
         let slot = self.slot;
         async move { mpsc_recv_inner(slot).await }
     }
+
+    fn poll_recv(&mut self, cx: &mut core::task::Context<'_>) -> core::task::Poll<Option<T>> {
+        mpsc_poll_recv(self.slot, cx)
+    }
 }
 
 // ── Shared MPSC recv plumbing ─────────────────────────────────────────
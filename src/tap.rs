@@ -0,0 +1,328 @@
+use core::future::Future;
+use core::marker::PhantomData;
+use core::net::{Ipv4Addr, SocketAddrV4};
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::task::{Context, Poll};
+use core::time::Duration;
+
+use crate::transport::{
+    ReceivedDatagram, SocketOptions, Timer, TransportError, TransportFactory, TransportSocket,
+};
+
+/// Whether a tapped datagram was received or sent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// One datagram seen by a [`DatagramTap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TappedDatagram<'a> {
+    pub direction: Direction,
+    /// Address of the socket that carried the datagram.
+    pub local: SocketAddrV4,
+    /// Sender of an inbound datagram, target of an outbound one.
+    pub peer: SocketAddrV4,
+    /// [`Timer::now`] when the datagram was seen. `None` if the timer has
+    /// no clock.
+    pub timestamp: Option<Duration>,
+    /// The raw datagram, starting at the SOME/IP header. An inbound
+    /// datagram larger than the receive buffer is cut to the buffer.
+    pub bytes: &'a [u8],
+}
+
+/// Observer of every datagram a [`TappedFactory`]'s sockets send or
+/// receive.
+///
+/// Called inline from the socket loops, so an implementation must be
+/// quick and must not block: copy the bytes out to a channel or a ring
+/// buffer and process them elsewhere. Implemented for every
+/// `Fn(&TappedDatagram)` closure that is `Clone`.
+pub trait DatagramTap: Clone {
+    fn on_datagram(&self, datagram: &TappedDatagram<'_>);
+}
+
+impl<T: Fn(&TappedDatagram<'_>) + Clone> DatagramTap for T {
+    fn on_datagram(&self, datagram: &TappedDatagram<'_>) {
+        self(datagram);
+    }
+}
+
+/// [`TransportFactory`] wrapper whose sockets report every datagram to a
+/// [`DatagramTap`], for packet analyzers and custom recorders.
+///
+/// Installed with `ClientDeps::with_tap` / `ServerDeps::with_tap`, which
+/// wrap the configured factory and timestamp datagrams with the
+/// configured timer; no socket loop changes. Only successfully sent and
+/// received datagrams are reported.
+///
+/// ```no_run
+/// # #[cfg(feature = "client-tokio")]
+/// # async fn demo() {
+/// use simple_someip::tap::TappedDatagram;
+/// use simple_someip::{Client, ClientDeps, RawPayload, TokioChannels};
+/// use std::net::Ipv4Addr;
+///
+/// let deps = ClientDeps::tokio(Ipv4Addr::LOCALHOST).with_tap(|d: &TappedDatagram<'_>| {
+///     println!("{:?} {} {} bytes", d.direction, d.peer, d.bytes.len());
+/// });
+/// let (_client, _updates, run) =
+///     Client::<RawPayload, _, _, TokioChannels>::new_with_deps(deps, false);
+/// tokio::spawn(run);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TappedFactory<F, T, Tm> {
+    inner: F,
+    tap: T,
+    clock: Tm,
+}
+
+impl<F, T, Tm> TappedFactory<F, T, Tm> {
+    /// Wrap `inner`, reporting to `tap` with timestamps from `clock`.
+    pub const fn new(inner: F, tap: T, clock: Tm) -> Self {
+        Self { inner, tap, clock }
+    }
+
+    /// The wrapped factory.
+    pub const fn inner(&self) -> &F {
+        &self.inner
+    }
+}
+
+impl<F, T, Tm> TransportFactory for TappedFactory<F, T, Tm>
+where
+    F: TransportFactory,
+    T: DatagramTap,
+    Tm: Timer + Clone,
+{
+    type Socket = TappedSocket<F::Socket, T, Tm>;
+    type BindFuture<'a>
+        = TappedBind<'a, F, T, Tm>
+    where
+        Self: 'a;
+
+    fn bind<'a>(&'a self, addr: SocketAddrV4, options: &'a SocketOptions) -> Self::BindFuture<'a> {
+        TappedBind {
+            inner: self.inner.bind(addr, options),
+            addr,
+            factory: self,
+        }
+    }
+}
+
+/// Named future returned by [`TappedFactory::bind`].
+pub struct TappedBind<'a, F: TransportFactory + 'a, T, Tm> {
+    inner: F::BindFuture<'a>,
+    addr: SocketAddrV4,
+    factory: &'a TappedFactory<F, T, Tm>,
+}
+
+impl<F: TransportFactory, T: DatagramTap, Tm: Timer + Clone> Future for TappedBind<'_, F, T, Tm> {
+    type Output = Result<TappedSocket<F::Socket, T, Tm>, TransportError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: structural pinning of `inner`; the other fields are
+        // plain data never pinned, and nothing is moved out.
+        let me = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut me.inner) };
+        inner.poll(cx).map(|bound| {
+            let socket = bound?;
+            Ok(TappedSocket {
+                // The bind address still has port 0 for an ephemeral bind.
+                local: socket.local_addr().unwrap_or(me.addr),
+                inner: socket,
+                tap: me.factory.tap.clone(),
+                clock: me.factory.clock.clone(),
+            })
+        })
+    }
+}
+
+/// Socket produced by a [`TappedFactory`].
+#[derive(Debug)]
+pub struct TappedSocket<S, T, Tm> {
+    inner: S,
+    local: SocketAddrV4,
+    tap: T,
+    clock: Tm,
+}
+
+impl<S, T: DatagramTap, Tm: Timer> TappedSocket<S, T, Tm> {
+    fn report(&self, direction: Direction, peer: SocketAddrV4, bytes: &[u8]) {
+        self.tap.on_datagram(&TappedDatagram {
+            direction,
+            local: self.local,
+            peer,
+            timestamp: self.clock.now(),
+            bytes,
+        });
+    }
+}
+
+impl<S: TransportSocket, T: DatagramTap, Tm: Timer> TransportSocket for TappedSocket<S, T, Tm> {
+    type SendFuture<'a>
+        = TappedSend<'a, S, T, Tm>
+    where
+        Self: 'a;
+    type RecvFuture<'a>
+        = TappedRecv<'a, S, T, Tm>
+    where
+        Self: 'a;
+
+    fn send_to<'a>(&'a self, buf: &'a [u8], target: SocketAddrV4) -> Self::SendFuture<'a> {
+        TappedSend {
+            inner: self.inner.send_to(buf, target),
+            socket: self,
+            buf,
+            target,
+        }
+    }
+
+    fn recv_from<'a>(&'a self, buf: &'a mut [u8]) -> Self::RecvFuture<'a> {
+        let buf = NonNull::from(buf);
+        TappedRecv {
+            // SAFETY: `buf` came from a `&'a mut [u8]`; the inner future
+            // gets the only live reborrow until it is dropped.
+            inner: Some(self.inner.recv_from(unsafe { &mut *buf.as_ptr() })),
+            socket: self,
+            buf,
+            _buf: PhantomData,
+        }
+    }
+
+    fn local_addr(&self) -> Result<SocketAddrV4, TransportError> {
+        self.inner.local_addr()
+    }
+
+    fn join_multicast_v4(&self, group: Ipv4Addr, iface: Ipv4Addr) -> Result<(), TransportError> {
+        self.inner.join_multicast_v4(group, iface)
+    }
+
+    fn leave_multicast_v4(&self, group: Ipv4Addr, iface: Ipv4Addr) -> Result<(), TransportError> {
+        self.inner.leave_multicast_v4(group, iface)
+    }
+
+    fn max_datagram_size(&self) -> usize {
+        self.inner.max_datagram_size()
+    }
+
+    fn dropped_datagrams(&self) -> Option<u64> {
+        self.inner.dropped_datagrams()
+    }
+}
+
+/// Named future returned by [`TappedSocket::send_to`].
+pub struct TappedSend<'a, S: TransportSocket + 'a, T, Tm> {
+    inner: S::SendFuture<'a>,
+    socket: &'a TappedSocket<S, T, Tm>,
+    buf: &'a [u8],
+    target: SocketAddrV4,
+}
+
+impl<S: TransportSocket, T: DatagramTap, Tm: Timer> Future for TappedSend<'_, S, T, Tm> {
+    type Output = Result<(), TransportError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: structural pinning of `inner`, as in `TappedBind`.
+        let me = unsafe { self.get_unchecked_mut() };
+        let inner = unsafe { Pin::new_unchecked(&mut me.inner) };
+        let sent = core::task::ready!(inner.poll(cx));
+        if sent.is_ok() {
+            me.socket.report(Direction::Outbound, me.target, me.buf);
+        }
+        Poll::Ready(sent)
+    }
+}
+
+/// Named future returned by [`TappedSocket::recv_from`].
+///
+/// Holds the receive buffer as a pointer next to the inner future that
+/// borrows it, and reads it only after that future has been dropped.
+pub struct TappedRecv<'a, S: TransportSocket + 'a, T, Tm> {
+    inner: Option<S::RecvFuture<'a>>,
+    socket: &'a TappedSocket<S, T, Tm>,
+    buf: NonNull<[u8]>,
+    _buf: PhantomData<&'a mut [u8]>,
+}
+
+// SAFETY: `buf` stands for the `&'a mut [u8]` handed to `recv_from`,
+// which is `Send`; every other field is covered by the bound.
+unsafe impl<'a, S: TransportSocket + 'a, T, Tm> Send for TappedRecv<'a, S, T, Tm>
+where
+    S::RecvFuture<'a>: Send,
+    TappedSocket<S, T, Tm>: Sync,
+{
+}
+
+impl<S: TransportSocket, T: DatagramTap, Tm: Timer> Future for TappedRecv<'_, S, T, Tm> {
+    type Output = Result<ReceivedDatagram, TransportError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: structural pinning of `inner`, as in `TappedBind`. The
+        // `Option` is only ever cleared in place, which drops the future
+        // without moving it.
+        let me = unsafe { self.get_unchecked_mut() };
+        let Some(inner) = me.inner.as_mut() else {
+            panic!("TappedRecv polled after completion");
+        };
+        let received = core::task::ready!(unsafe { Pin::new_unchecked(inner) }.poll(cx));
+        me.inner = None;
+        if let Ok(datagram) = &received {
+            // SAFETY: the inner future and its borrow of the buffer are
+            // gone, so this is the only reference for the rest of `'a`.
+            let buf = unsafe { me.buf.as_ref() };
+            let len = datagram.bytes_received.min(buf.len());
+            me.socket
+                .report(Direction::Inbound, datagram.source, &buf[..len]);
+        }
+        Poll::Ready(received)
+    }
+}
+
+#[cfg(all(test, any(feature = "client-tokio", feature = "server-tokio")))]
+mod tests {
+    use super::*;
+    use crate::tokio_transport::{TokioTimer, TokioTransport};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    #[tokio::test]
+    async fn tap_sees_sent_and_received_datagrams() {
+        type Log = Vec<(Direction, SocketAddrV4, Vec<u8>)>;
+        let seen: Arc<Mutex<Log>> = Arc::default();
+        let log = Arc::clone(&seen);
+        let factory = TappedFactory::new(
+            TokioTransport,
+            move |d: &TappedDatagram<'_>| {
+                assert!(d.timestamp.is_some(), "TokioTimer has a clock");
+                log.lock()
+                    .unwrap()
+                    .push((d.direction, d.peer, d.bytes.to_vec()));
+            },
+            TokioTimer,
+        );
+        let any = SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0);
+        let options = SocketOptions::default();
+        let a = factory.bind(any, &options).await.unwrap();
+        let b = factory.bind(any, &options).await.unwrap();
+        let (a_addr, b_addr) = (a.local_addr().unwrap(), b.local_addr().unwrap());
+        assert_eq!(a.local, a_addr, "ephemeral port resolved at bind");
+
+        a.send_to(&[1, 2, 3, 4], b_addr).await.unwrap();
+        let mut buf = [0u8; 2];
+        let received = b.recv_from(&mut buf).await.unwrap();
+        assert_eq!(received.source, a_addr);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            *seen,
+            [
+                (Direction::Outbound, b_addr, std::vec![1, 2, 3, 4]),
+                (Direction::Inbound, a_addr, std::vec![1, 2]),
+            ]
+        );
+    }
+}
@@ -38,14 +38,17 @@ use core::net::{Ipv4Addr, SocketAddrV4};
 use core::pin::Pin;
 use core::task::{Context, Poll};
 use core::time::Duration;
+use std::boxed::Box;
 use std::net::{IpAddr, SocketAddr};
+use std::sync::{Mutex, PoisonError};
+use std::vec::Vec;
 use tokio::io::ReadBuf;
-use tokio::net::UdpSocket;
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
 
 use crate::transport::{
     ChannelFactory, IoErrorKind, MpscRecv, MpscSend, OneshotCancelled, OneshotRecv, OneshotSend,
-    ReceivedDatagram, SocketOptions, Timer, TransportError, TransportFactory, TransportSocket,
-    UnboundedRecv, UnboundedSend,
+    ReceivedDatagram, SocketOptions, StreamFactory, StreamListener, Timer, TransportError,
+    TransportFactory, TransportSocket, UnboundedRecv, UnboundedSend,
 };
 
 /// Factory that binds [`TokioSocket`]s configured via `socket2`.
@@ -95,7 +98,8 @@ pub struct TokioTimer;
 /// pays nothing for the abstraction (the `Inner` carries the spawner
 /// generic; `Client<P>` is a thin handle that forwards to it).
 /// Bare-metal consumers substitute their own `Spawner` via the
-/// `crate::Client::new_with_spawner_and_loopback` constructor.
+/// `crate::Client::new_with_spawner_and_loopback` constructor; tokio
+/// users who want to own the spawned tasks use [`JoinSetSpawner`].
 #[derive(Debug, Default, Clone, Copy)]
 pub struct TokioSpawner;
 
@@ -242,6 +246,311 @@ impl TransportSocket for TokioSocket {
             .leave_multicast_v4(group, iface)
             .map_err(|e| map_io_error(&e))
     }
+
+    /// Read from the `drops` column of `/proc/net/udp` on Linux: the
+    /// counter `SO_RXQ_OVFL` reports, without a `recvmsg` path. `None`
+    /// elsewhere.
+    fn dropped_datagrams(&self) -> Option<u64> {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::fd::AsRawFd;
+            let fd = std::format!("/proc/self/fd/{}", self.inner.as_raw_fd());
+            let link = std::fs::read_link(fd).ok()?;
+            let inode = link
+                .to_str()?
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok()?;
+            udp_table_drops(&std::fs::read_to_string("/proc/net/udp").ok()?, inode)
+        }
+        #[cfg(not(target_os = "linux"))]
+        None
+    }
+}
+
+/// The `drops` column of the `/proc/net/udp` row for socket `inode`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn udp_table_drops(table: &str, inode: u64) -> Option<u64> {
+    // sl local rem st tx:rx tr:when retrnsmt uid timeout inode ref pointer drops
+    table.lines().skip(1).find_map(|row| {
+        let mut columns = row.split_whitespace().skip(9);
+        if columns.next()?.parse::<u64>().ok()? != inode {
+            return None;
+        }
+        columns.nth(2)?.parse().ok()
+    })
+}
+
+/// A TCP connection backed by [`tokio::net::TcpStream`], produced by
+/// [`TokioTransport`]'s [`StreamFactory`] impl. Carries whole SOME/IP
+/// messages; see [`StreamFactory`] for the contract.
+#[derive(Debug)]
+pub struct TokioStream {
+    inner: TcpStream,
+    peer: SocketAddrV4,
+    /// Bytes read from the stream but not yet returned as a message.
+    /// Behind a mutex so `recv_from` can take `&self`; only the socket
+    /// loop reads, so it is never contended.
+    rx: Mutex<StreamRx>,
+}
+
+#[derive(Debug, Default)]
+struct StreamRx {
+    buffered: Vec<u8>,
+    /// Bytes of an oversize message still to be read and thrown away.
+    discard: usize,
+}
+
+impl StreamRx {
+    /// Take the next whole message out of `buffered` into `buf`.
+    fn take_message(&mut self, buf: &mut [u8]) -> Option<ReceivedDatagram> {
+        let len = crate::transport::someip_message_len(&self.buffered)?;
+        if len > buf.len() {
+            // Too large to hand out: drop what is here and skip the rest
+            // as it arrives, keeping the stream in step.
+            let have = len.min(self.buffered.len());
+            self.buffered.drain(..have);
+            self.discard = len - have;
+            return Some(ReceivedDatagram {
+                bytes_received: 0,
+                source: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0),
+                truncated: true,
+            });
+        }
+        if self.buffered.len() < len {
+            return None;
+        }
+        buf[..len].copy_from_slice(&self.buffered[..len]);
+        self.buffered.drain(..len);
+        Some(ReceivedDatagram {
+            bytes_received: len,
+            source: SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0),
+            truncated: false,
+        })
+    }
+
+    /// Append freshly read bytes, skipping what is left of a discarded
+    /// message.
+    fn extend(&mut self, bytes: &[u8]) {
+        let skip = self.discard.min(bytes.len());
+        self.discard -= skip;
+        self.buffered.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+/// Stream errors that mean the connection is gone.
+fn map_stream_error(e: &std::io::Error) -> TransportError {
+    use std::io::ErrorKind as K;
+    match e.kind() {
+        K::ConnectionReset | K::ConnectionAborted | K::BrokenPipe | K::UnexpectedEof => {
+            TransportError::ConnectionClosed
+        }
+        _ => map_io_error(e),
+    }
+}
+
+/// Named future returned by [`TokioTransport::connect`](StreamFactory::connect).
+///
+/// `tokio::net::TcpStream::connect` is an `async fn` whose future cannot
+/// be named, so it is boxed; one allocation per connection.
+pub struct TokioConnectFuture {
+    inner: Pin<Box<dyn Future<Output = Result<TokioStream, TransportError>> + Send>>,
+}
+
+impl Future for TokioConnectFuture {
+    type Output = Result<TokioStream, TransportError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+impl StreamFactory for TokioTransport {
+    type Stream = TokioStream;
+    type ConnectFuture<'a> = TokioConnectFuture;
+
+    fn connect(&self, peer: SocketAddrV4) -> Self::ConnectFuture<'_> {
+        TokioConnectFuture {
+            inner: Box::pin(async move {
+                let inner = TcpStream::connect(peer)
+                    .await
+                    .map_err(|e| map_io_error(&e))?;
+                TokioStream::new(inner, peer)
+            }),
+        }
+    }
+}
+
+impl TokioStream {
+    fn new(inner: TcpStream, peer: SocketAddrV4) -> Result<Self, TransportError> {
+        // SOME/IP messages are small and latency-bound.
+        inner.set_nodelay(true).map_err(|e| map_io_error(&e))?;
+        Ok(Self {
+            inner,
+            peer,
+            rx: Mutex::default(),
+        })
+    }
+}
+
+/// A TCP listener backed by [`tokio::net::TcpListener`], accepting
+/// [`TokioStream`]s for a server serving requests over TCP.
+#[derive(Debug)]
+pub struct TokioListener {
+    inner: TcpListener,
+}
+
+impl TokioListener {
+    /// Listen on `addr`; port 0 picks an ephemeral port, readable with
+    /// [`StreamListener::local_addr`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::AddressInUse`] if `addr` is taken, other
+    /// failures as [`TransportError::Io`].
+    pub async fn bind(addr: SocketAddrV4) -> Result<Self, TransportError> {
+        let inner = TcpListener::bind(addr)
+            .await
+            .map_err(|e| map_io_error(&e))?;
+        Ok(Self { inner })
+    }
+}
+
+/// Named future returned by [`TokioListener::accept`](StreamListener::accept).
+pub struct TokioAccept<'a> {
+    listener: &'a TcpListener,
+}
+
+impl Future for TokioAccept<'_> {
+    type Output = Result<TokioStream, TransportError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (stream, peer) =
+            core::task::ready!(self.listener.poll_accept(cx)).map_err(|e| map_io_error(&e))?;
+        let std::net::SocketAddr::V4(peer) = peer else {
+            return Poll::Ready(Err(TransportError::Unsupported));
+        };
+        Poll::Ready(TokioStream::new(stream, peer))
+    }
+}
+
+impl StreamListener for TokioListener {
+    type Stream = TokioStream;
+    type AcceptFuture<'a> = TokioAccept<'a>;
+
+    fn accept(&self) -> Self::AcceptFuture<'_> {
+        TokioAccept {
+            listener: &self.inner,
+        }
+    }
+
+    fn local_addr(&self) -> Result<SocketAddrV4, TransportError> {
+        match self.inner.local_addr().map_err(|e| map_io_error(&e))? {
+            std::net::SocketAddr::V4(addr) => Ok(addr),
+            std::net::SocketAddr::V6(_) => Err(TransportError::Unsupported),
+        }
+    }
+}
+
+/// Named future returned by [`TokioStream::send_to`].
+pub struct StreamWrite<'a> {
+    stream: &'a TcpStream,
+    buf: &'a [u8],
+    written: usize,
+}
+
+impl Future for StreamWrite<'_> {
+    type Output = Result<(), TransportError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = self.get_mut();
+        while me.written < me.buf.len() {
+            if let Err(e) = core::task::ready!(me.stream.poll_write_ready(cx)) {
+                return Poll::Ready(Err(map_stream_error(&e)));
+            }
+            match me.stream.try_write(&me.buf[me.written..]) {
+                Ok(0) => return Poll::Ready(Err(TransportError::ConnectionClosed)),
+                Ok(n) => me.written += n,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => return Poll::Ready(Err(map_stream_error(&e))),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Named future returned by [`TokioStream::recv_from`].
+pub struct StreamRead<'a> {
+    stream: &'a TokioStream,
+    buf: &'a mut [u8],
+}
+
+impl Future for StreamRead<'_> {
+    type Output = Result<ReceivedDatagram, TransportError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let me = self.get_mut();
+        let stream = me.stream;
+        let mut chunk = [0u8; 1500];
+        loop {
+            {
+                let mut rx = stream.rx.lock().unwrap_or_else(PoisonError::into_inner);
+                if let Some(mut message) = rx.take_message(me.buf) {
+                    message.source = stream.peer;
+                    return Poll::Ready(Ok(message));
+                }
+            }
+            if let Err(e) = core::task::ready!(stream.inner.poll_read_ready(cx)) {
+                return Poll::Ready(Err(map_stream_error(&e)));
+            }
+            // Bytes read here are stored before returning, so dropping
+            // the future between polls loses nothing.
+            match stream.inner.try_read(&mut chunk) {
+                Ok(0) => return Poll::Ready(Err(TransportError::ConnectionClosed)),
+                Ok(n) => stream
+                    .rx
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .extend(&chunk[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => return Poll::Ready(Err(map_stream_error(&e))),
+            }
+        }
+    }
+}
+
+impl TransportSocket for TokioStream {
+    type SendFuture<'a> = StreamWrite<'a>;
+    type RecvFuture<'a> = StreamRead<'a>;
+
+    /// Writes `buf` to the connection; `target` is ignored.
+    fn send_to<'a>(&'a self, buf: &'a [u8], _target: SocketAddrV4) -> Self::SendFuture<'a> {
+        StreamWrite {
+            stream: &self.inner,
+            buf,
+            written: 0,
+        }
+    }
+
+    fn recv_from<'a>(&'a self, buf: &'a mut [u8]) -> Self::RecvFuture<'a> {
+        StreamRead { stream: self, buf }
+    }
+
+    fn local_addr(&self) -> Result<SocketAddrV4, TransportError> {
+        match self.inner.local_addr().map_err(|e| map_io_error(&e))? {
+            SocketAddr::V4(v4) => Ok(v4),
+            SocketAddr::V6(_) => Err(TransportError::Unsupported),
+        }
+    }
+
+    fn join_multicast_v4(&self, _group: Ipv4Addr, _iface: Ipv4Addr) -> Result<(), TransportError> {
+        Err(TransportError::Unsupported)
+    }
+
+    fn leave_multicast_v4(&self, _group: Ipv4Addr, _iface: Ipv4Addr) -> Result<(), TransportError> {
+        Err(TransportError::Unsupported)
+    }
 }
 
 /// Named future returned by [`TokioTimer::sleep`].
@@ -271,6 +580,15 @@ impl Timer for TokioTimer {
             inner: tokio::time::sleep(duration),
         }
     }
+
+    /// Time since the first call in this process, on tokio's clock so
+    /// paused-time tests (`tokio::time::pause`) see it advance with
+    /// `sleep`.
+    fn now(&self) -> Option<Duration> {
+        static EPOCH: std::sync::OnceLock<tokio::time::Instant> = std::sync::OnceLock::new();
+        let epoch = *EPOCH.get_or_init(tokio::time::Instant::now);
+        Some(tokio::time::Instant::now().saturating_duration_since(epoch))
+    }
 }
 
 /// Wraps a `Future` so that any panic during `poll` is logged via
@@ -326,6 +644,104 @@ impl crate::transport::Spawner for TokioSpawner {
     }
 }
 
+/// [`crate::transport::Spawner`] / [`crate::transport::LocalSpawner`] impl
+/// that keeps every submitted future in a caller-owned
+/// [`tokio::task::JoinSet`] instead of detaching it.
+///
+/// [`TokioSpawner`] drops each `JoinHandle`, so the per-socket I/O loops
+/// a `Client` starts are owned by nobody and outlive whatever scope
+/// created them. Passing a `JoinSetSpawner` (it is a cheap `Clone` of a
+/// shared handle) to `Client::new_with_spawner_and_loopback` keeps those
+/// tasks inspectable and lets the application tear them down together
+/// with the run-loop future it already drives:
+///
+/// ```no_run
+/// # #[cfg(feature = "client-tokio")]
+/// # async fn demo() {
+/// use simple_someip::{Client, RawPayload, tokio_transport::JoinSetSpawner};
+/// use std::net::Ipv4Addr;
+///
+/// let tasks = JoinSetSpawner::new();
+/// let (client, _updates, run) = Client::<RawPayload, _, _, _>::new_with_spawner_and_loopback(
+///     Ipv4Addr::LOCALHOST,
+///     false,
+///     tasks.clone(),
+/// );
+/// let run = tokio::spawn(run);
+/// // ... use `client` ...
+/// client.shut_down();
+/// let _ = run.await;
+/// tasks.shutdown().await;
+/// # }
+/// ```
+///
+/// Finished tasks are reaped on the next `spawn`, so the set only grows
+/// with live tasks. Like `tokio::spawn`, `spawn` must be called from
+/// within a runtime — which holds for every crate-internal call, as they
+/// all happen while the caller polls the run-loop future.
+#[derive(Debug, Default, Clone)]
+pub struct JoinSetSpawner {
+    tasks: std::sync::Arc<std::sync::Mutex<tokio::task::JoinSet<()>>>,
+}
+
+impl JoinSetSpawner {
+    /// An empty task set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of tasks in the set that have not been reaped yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// `true` if no task is in the set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Abort every task in the set without waiting for them to stop.
+    pub fn abort_all(&self) {
+        self.lock().abort_all();
+    }
+
+    /// Abort every task in the set and wait until all of them have
+    /// stopped. Tasks spawned while this runs land in a fresh set.
+    pub async fn shutdown(&self) {
+        let mut tasks = core::mem::take(&mut *self.lock());
+        tasks.shutdown().await;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, tokio::task::JoinSet<()>> {
+        // A panic while holding the lock leaves the `JoinSet` itself
+        // consistent, so poisoning is safe to ignore.
+        self.tasks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl crate::transport::Spawner for JoinSetSpawner {
+    fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        let mut tasks = self.lock();
+        while tasks.try_join_next().is_some() {}
+        tasks.spawn(PanicLoggingFut { inner: future });
+    }
+}
+
+impl crate::transport::LocalSpawner for JoinSetSpawner {
+    /// Requires a [`tokio::task::LocalSet`] context, as
+    /// `tokio::task::spawn_local` does.
+    fn spawn_local(&self, future: impl Future<Output = ()> + 'static) {
+        let mut tasks = self.lock();
+        while tasks.try_join_next().is_some() {}
+        tasks.spawn_local(PanicLoggingFut { inner: future });
+    }
+}
+
 /// Best-effort extraction of a printable message from a panic payload.
 fn panic_payload_str(payload: &std::boxed::Box<dyn std::any::Any + Send>) -> &str {
     if let Some(s) = payload.downcast_ref::<&'static str>() {
@@ -367,6 +783,9 @@ fn bind_with_options(addr: SocketAddrV4, options: SocketOptions) -> std::io::Res
     if let Some(loop_v4) = options.multicast_loop_v4 {
         raw.set_multicast_loop_v4(loop_v4)?;
     }
+    if let Some(size) = options.recv_buffer_size {
+        raw.set_recv_buffer_size(size)?;
+    }
     let bind_addr = SocketAddr::new(IpAddr::V4(*addr.ip()), addr.port());
     raw.bind(&bind_addr.into())?;
     raw.set_nonblocking(true)?;
@@ -472,6 +891,11 @@ impl<T: Send + 'static> MpscSend<T> for tokio::sync::mpsc::Sender<T> {
             .await
             .map_err(|_| ())
     }
+
+    fn try_send(&self, value: T) -> Result<(), T> {
+        tokio::sync::mpsc::Sender::try_send(self, value)
+            .map_err(tokio::sync::mpsc::error::TrySendError::into_inner)
+    }
 }
 
 impl<T: Send + 'static> MpscRecv<T> for tokio::sync::mpsc::Receiver<T> {
@@ -488,12 +912,20 @@ impl<T: Send + 'static> UnboundedSend<T> for tokio::sync::mpsc::UnboundedSender<
     fn send_now(&self, value: T) -> Result<(), T> {
         self.send(value).map_err(|e| e.0)
     }
+
+    fn is_closed(&self) -> bool {
+        tokio::sync::mpsc::UnboundedSender::is_closed(self)
+    }
 }
 
 impl<T: Send + 'static> UnboundedRecv<T> for TokioUnboundedReceiver<T> {
     fn recv(&mut self) -> impl Future<Output = Option<T>> + Send + '_ {
         self.0.recv()
     }
+
+    fn poll_recv(&mut self, cx: &mut core::task::Context<'_>) -> core::task::Poll<Option<T>> {
+        self.0.poll_recv(cx)
+    }
 }
 
 impl ChannelFactory for TokioChannels {
@@ -537,6 +969,15 @@ impl<T: Send + 'static, const N: usize> crate::transport::BoundedPooled<TokioCha
     ) {
         tokio::sync::mpsc::channel(N)
     }
+
+    fn bounded_pair_with_capacity(
+        capacity: usize,
+    ) -> (
+        <TokioChannels as ChannelFactory>::BoundedSender<T, N>,
+        <TokioChannels as ChannelFactory>::BoundedReceiver<T, N>,
+    ) {
+        tokio::sync::mpsc::channel(capacity.max(1))
+    }
 }
 
 impl<T: Send + 'static> crate::transport::UnboundedPooled<TokioChannels> for T {
@@ -616,6 +1057,43 @@ mod tests {
         assert_ne!(addr.port(), 0, "kernel must assign a non-zero port");
     }
 
+    #[test]
+    fn udp_table_drops_reads_the_row_of_the_inode() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  411: 0100007F:9C41 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 81234 2 0000000000000000 0
+  730: 00000000:776A 00000000:0000 07 00000000:00034000 00:00000000 00000000     0        0 81240 2 0000000000000000 17
+";
+        assert_eq!(udp_table_drops(table, 81234), Some(0));
+        assert_eq!(udp_table_drops(table, 81240), Some(17));
+        assert_eq!(udp_table_drops(table, 1), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn dropped_datagrams_counts_receive_queue_overflow() {
+        let factory = TokioTransport;
+        let opts = SocketOptions::default();
+        let recv = factory
+            .bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0), &opts)
+            .await
+            .unwrap();
+        let send = factory
+            .bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0), &opts)
+            .await
+            .unwrap();
+        assert_eq!(recv.dropped_datagrams(), Some(0));
+
+        // Nothing reads `recv`, so its receive queue fills and the
+        // kernel drops the rest.
+        let datagram = [0u8; 1024];
+        for _ in 0..4096 {
+            send.send_to(&datagram, recv.local_addr().unwrap())
+                .await
+                .unwrap();
+        }
+        assert!(recv.dropped_datagrams().unwrap() > 0);
+    }
+
     #[tokio::test]
     async fn round_trip_send_recv_between_two_sockets() {
         let factory = TokioTransport;
@@ -879,4 +1357,52 @@ mod tests {
              PanicLoggingFut wrapper missing or broken",
         );
     }
+
+    /// Messages coalesced into one write, split across writes, or too
+    /// large for the buffer each come out of `recv_from` on their own,
+    /// and the peer hanging up reads as `ConnectionClosed`.
+    #[tokio::test]
+    async fn stream_recv_frames_messages_and_reports_close() {
+        use std::io::Write as _;
+
+        let listener = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let std::net::SocketAddr::V4(addr) = listener.local_addr().unwrap() else {
+            panic!("expected IPv4");
+        };
+        let stream = TokioTransport.connect(addr).await.unwrap();
+        let (mut peer, _) = listener.accept().unwrap();
+
+        // Header fields past the length do not matter to the framing.
+        let message = |payload: &[u8]| {
+            let mut bytes = std::vec![0u8; 16];
+            let length = u32::try_from(8 + payload.len()).unwrap();
+            bytes[4..8].copy_from_slice(&length.to_be_bytes());
+            bytes.extend_from_slice(payload);
+            bytes
+        };
+        let mut wire = message(&[1]);
+        wire.extend(message(&[2, 2]));
+        wire.extend(message(&[3; 32]));
+        wire.extend(message(&[4]));
+        peer.write_all(&wire[..30]).unwrap();
+
+        let mut buf = [0u8; 24];
+        let first = stream.recv_from(&mut buf).await.unwrap();
+        assert_eq!((first.bytes_received, first.source), (17, addr));
+        assert_eq!(buf[16], 1);
+        peer.write_all(&wire[30..]).unwrap();
+        let second = stream.recv_from(&mut buf).await.unwrap();
+        assert_eq!(second.bytes_received, 18);
+        assert_eq!(&buf[16..18], &[2, 2]);
+        let oversize = stream.recv_from(&mut buf).await.unwrap();
+        assert!(oversize.truncated);
+        let last = stream.recv_from(&mut buf).await.unwrap();
+        assert_eq!((last.bytes_received, buf[16]), (17, 4));
+
+        drop(peer);
+        assert!(matches!(
+            stream.recv_from(&mut buf).await,
+            Err(TransportError::ConnectionClosed)
+        ));
+    }
 }
@@ -11,11 +11,37 @@ pub struct OfferedEndpoint {
     pub major_version: u8,
     /// The minor version of the offered service interface.
     pub minor_version: u32,
-    /// The full endpoint (IPv4 socket + transport protocol) extracted
-    /// from the SD options, if present.
+    /// The full endpoint (socket + transport protocol) extracted from the
+    /// SD options, if present: the first IPv4 endpoint option, else the
+    /// first IPv6 one.
     pub endpoint: Option<crate::NetEndpoint>,
     /// `true` for `OfferService`, `false` for `StopOfferService`.
     pub is_offer: bool,
+    /// Offer lifetime in seconds, as carried by the entry (24-bit;
+    /// `0xFF_FFFF` means "until further notice").
+    pub ttl: u32,
+    /// The `key=value` items of the first configuration option, empty if
+    /// the message carries none.
+    pub configuration: sd::ConfigurationString,
+    /// `(priority, weight)` of the first load-balancing option, if any. A
+    /// lower priority value is preferred; among equal priorities, the
+    /// weight is the relative share of clients the provider asks for.
+    pub load_balancing: Option<(u16, u16)>,
+}
+
+/// A `SubscribeEventgroupAck` entry extracted from an SD message. With
+/// a TTL of 0 it is a `SubscribeEventgroupNack`.
+pub struct SubscriptionAck {
+    /// The SOME/IP service ID.
+    pub service_id: u16,
+    /// The SOME/IP instance ID.
+    pub instance_id: u16,
+    /// The major version of the service interface.
+    pub major_version: u8,
+    /// The acknowledged event group.
+    pub event_group_id: u16,
+    /// Granted subscription lifetime in seconds; `0` for a Nack.
+    pub ttl: u32,
 }
 
 /// A trait for types that can be serialized to a [`Writer`](embedded_io::Write).
@@ -103,6 +129,38 @@ pub trait PayloadWireFormat: core::fmt::Debug + Send + Sized + Sync {
         reboot_flag: sd::RebootFlag,
     ) -> Self::SdHeader;
 
+    /// Append a `SubscribeEventgroup` entry to `header`, built by
+    /// [`Self::new_subscription_sd_header`], sharing its endpoint option.
+    ///
+    /// Returns `false` if the entry was not added, in which case
+    /// `Client::subscribe_many` sends it in a message of its own. The
+    /// default adds nothing, so every entry goes out on its own.
+    fn push_subscription_entry(
+        _header: &mut Self::SdHeader,
+        _service_id: u16,
+        _instance_id: u16,
+        _major_version: u8,
+        _ttl: u32,
+        _event_group_id: u16,
+    ) -> bool {
+        false
+    }
+
+    /// Construct an SD header with a single `FindService` entry for
+    /// `service_id`/`instance_id` (`0xFFFF` for any instance), any
+    /// version.
+    ///
+    /// Returns `None` by default; `Client::wait_for_service` then only
+    /// watches for offers without asking for them.
+    #[must_use]
+    fn new_find_service_sd_header(
+        _service_id: u16,
+        _instance_id: u16,
+        _reboot_flag: sd::RebootFlag,
+    ) -> Option<Self::SdHeader> {
+        None
+    }
+
     /// Override the reboot flag on an SD header in-place.
     ///
     /// Used by `Client::sd_announcements_loop` to refresh the reboot
@@ -146,6 +204,18 @@ pub trait PayloadWireFormat: core::fmt::Debug + Send + Sized + Sync {
     {
     }
 
+    /// Visit every `SubscribeEventgroupAck` / `Nack` entry in this
+    /// payload with `f`.
+    ///
+    /// Used by the `Client` run loop to resolve the subscriptions it
+    /// tracks. Visitor pattern for the same `no_std` reason as
+    /// [`Self::for_each_offered_endpoint`]; default visits nothing.
+    fn for_each_subscription_ack<F>(&self, _f: F)
+    where
+        F: FnMut(SubscriptionAck),
+    {
+    }
+
     /// Convenience accessor returning all offered endpoints as a heap
     /// `Vec`. Wraps [`Self::for_each_offered_endpoint`] so std users
     /// get the original ergonomic shape; bare-metal users use the
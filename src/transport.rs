@@ -334,6 +334,10 @@ pub enum TransportError {
     /// A generic I/O error, classified by a portable [`IoErrorKind`].
     #[error("transport i/o: {0}")]
     Io(IoErrorKind),
+    /// The peer closed a stream connection, or the stream can no longer
+    /// be split into messages. The connection is unusable.
+    #[error("connection closed")]
+    ConnectionClosed,
 }
 
 /// Socket-level options applied by [`TransportFactory::bind`].
@@ -375,6 +379,10 @@ pub struct SocketOptions {
     /// turn loopback OFF on hosts where the OS default was ON, even
     /// when the caller had no opinion on loopback.
     pub multicast_loop_v4: Option<bool>,
+    /// OS receive buffer size (`SO_RCVBUF`) in bytes. `None` keeps the
+    /// OS default. Backends without a configurable receive buffer ignore
+    /// it.
+    pub recv_buffer_size: Option<usize>,
 }
 
 impl SocketOptions {
@@ -386,6 +394,7 @@ impl SocketOptions {
             reuse_port: false,
             multicast_if_v4: None,
             multicast_loop_v4: None,
+            recv_buffer_size: None,
         }
     }
 }
@@ -583,6 +592,18 @@ pub trait TransportSocket {
     fn max_datagram_size(&self) -> usize {
         crate::UDP_BUFFER_SIZE
     }
+
+    /// Datagrams the OS has dropped for this socket since it was bound
+    /// because its receive queue was full, or `None` if the backend
+    /// cannot tell (the default). Tells receiver overload apart from
+    /// loss on the network when datagrams go missing.
+    ///
+    /// The receive loops sample it every so many datagrams rather than
+    /// per datagram, so an implementation may make a system call.
+    #[must_use]
+    fn dropped_datagrams(&self) -> Option<u64> {
+        None
+    }
 }
 
 /// Constructs [`TransportSocket`] instances from a bind address and
@@ -623,6 +644,133 @@ pub trait TransportFactory {
     fn bind<'a>(&'a self, addr: SocketAddrV4, options: &'a SocketOptions) -> Self::BindFuture<'a>;
 }
 
+/// Opens connection-oriented (TCP) sockets for SOME/IP over TCP.
+///
+/// A connected stream is exposed as a [`TransportSocket`] carrying whole
+/// SOME/IP messages, so the crate drives it with the same socket loop as
+/// a UDP socket:
+///
+/// - `send_to` writes one message and ignores its target (the stream is
+///   already connected); the future must be driven to completion, since
+///   dropping it mid-write would leave a partial message on the stream.
+/// - `recv_from` returns one message per call, framed by the length
+///   field of its header (see [`someip_message_len`]), with the peer as
+///   source. It must be cancel-safe: bytes read by a dropped future are
+///   kept for the next call. A message larger than the buffer is
+///   discarded and reported as `truncated`.
+/// - End of stream, and any framing the stream cannot recover from, is
+///   [`TransportError::ConnectionClosed`].
+///
+/// Backends without TCP use [`NoStreams`].
+pub trait StreamFactory {
+    /// The connected socket type produced by this factory.
+    type Stream: TransportSocket;
+
+    /// Future returned by [`Self::connect`]. A GAT for the same reason
+    /// as [`TransportFactory::BindFuture`].
+    type ConnectFuture<'a>: Future<Output = Result<Self::Stream, TransportError>>
+    where
+        Self: 'a;
+
+    /// Connect to `peer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::Io`] with
+    /// [`IoErrorKind::ConnectionRefused`] if nothing listens on `peer`,
+    /// [`TransportError::Unsupported`] if the backend has no streams.
+    fn connect(&self, peer: SocketAddrV4) -> Self::ConnectFuture<'_>;
+}
+
+/// Accepts incoming SOME/IP over TCP connections, for a server serving
+/// requests over TCP.
+///
+/// Accepted streams follow the same contract as
+/// [`StreamFactory::Stream`], with the connecting client as the source of
+/// every message.
+pub trait StreamListener {
+    /// The connected socket type produced by this listener.
+    type Stream: TransportSocket;
+
+    /// Future returned by [`Self::accept`]. Must be cancel-safe: a
+    /// connection is not lost when the future is dropped before it
+    /// resolves.
+    type AcceptFuture<'a>: Future<Output = Result<Self::Stream, TransportError>>
+    where
+        Self: 'a;
+
+    /// Wait for the next connection.
+    ///
+    /// # Errors
+    ///
+    /// Backend-level failures surface as [`TransportError::Io`].
+    fn accept(&self) -> Self::AcceptFuture<'_>;
+
+    /// The address the listener is bound to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend cannot report it.
+    fn local_addr(&self) -> Result<SocketAddrV4, TransportError>;
+}
+
+/// Total length of the SOME/IP message at the start of `bytes`, read
+/// from its header's length field, or `None` while fewer than the 8
+/// bytes up to and including that field are available. For
+/// [`StreamFactory`] implementations splitting a byte stream into
+/// messages.
+#[must_use]
+pub fn someip_message_len(bytes: &[u8]) -> Option<usize> {
+    let length: [u8; 4] = bytes.get(4..8)?.try_into().ok()?;
+    usize::try_from(u32::from_be_bytes(length))
+        .ok()?
+        .checked_add(8)
+}
+
+/// [`StreamFactory`] for backends without TCP: every
+/// [`connect`](StreamFactory::connect) fails with
+/// [`TransportError::Unsupported`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoStreams;
+
+/// The [`StreamFactory::Stream`] of [`NoStreams`]; never constructed.
+#[derive(Debug)]
+pub enum NoStream {}
+
+impl StreamFactory for NoStreams {
+    type Stream = NoStream;
+    type ConnectFuture<'a> = core::future::Ready<Result<NoStream, TransportError>>;
+
+    fn connect(&self, _peer: SocketAddrV4) -> Self::ConnectFuture<'_> {
+        core::future::ready(Err(TransportError::Unsupported))
+    }
+}
+
+impl TransportSocket for NoStream {
+    type SendFuture<'a> = core::future::Ready<Result<(), TransportError>>;
+    type RecvFuture<'a> = core::future::Ready<Result<ReceivedDatagram, TransportError>>;
+
+    fn send_to<'a>(&'a self, _buf: &'a [u8], _target: SocketAddrV4) -> Self::SendFuture<'a> {
+        match *self {}
+    }
+
+    fn recv_from<'a>(&'a self, _buf: &'a mut [u8]) -> Self::RecvFuture<'a> {
+        match *self {}
+    }
+
+    fn local_addr(&self) -> Result<SocketAddrV4, TransportError> {
+        match *self {}
+    }
+
+    fn join_multicast_v4(&self, _group: Ipv4Addr, _iface: Ipv4Addr) -> Result<(), TransportError> {
+        match *self {}
+    }
+
+    fn leave_multicast_v4(&self, _group: Ipv4Addr, _iface: Ipv4Addr) -> Result<(), TransportError> {
+        match *self {}
+    }
+}
+
 /// Executor-agnostic sleep primitive.
 ///
 /// `simple-someip` needs timed waits in two places: the Service Discovery
@@ -646,6 +794,55 @@ pub trait Timer {
     /// Wait for at least `duration` before resolving. Implementations MAY
     /// overshoot but MUST NOT undershoot.
     fn sleep(&self, duration: Duration) -> Self::SleepFuture<'_>;
+
+    /// Monotonic time elapsed since an arbitrary, fixed epoch, used for
+    /// deadline bookkeeping such as the client's offer TTL countdown.
+    ///
+    /// The default returns `None`: a backend without a clock keeps
+    /// working, with the time-based features reporting "unknown" instead.
+    /// Override it (e.g. with `embassy_time::Instant::now()`) to enable
+    /// them.
+    fn now(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Point in time by which an outgoing message must have been sent, on a
+/// [`Timer::now`] clock.
+///
+/// Sends tagged with a deadline are dropped and reported instead of being
+/// delivered late once the deadline has passed: for control-loop signals
+/// stale data is worse than none. Without a clock a deadline can never be
+/// seen to pass, so the message is always sent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Deadline(Duration);
+
+impl Deadline {
+    /// A deadline at `instant` on the [`Timer::now`] clock.
+    #[must_use]
+    pub const fn at(instant: Duration) -> Self {
+        Self(instant)
+    }
+
+    /// A deadline `budget` from now on `timer`'s clock. `None` if the
+    /// timer has no clock.
+    #[must_use]
+    pub fn after<Tm: Timer + ?Sized>(timer: &Tm, budget: Duration) -> Option<Self> {
+        timer.now().map(|now| Self(now.saturating_add(budget)))
+    }
+
+    /// The deadline as a [`Timer::now`] instant.
+    #[must_use]
+    pub const fn instant(&self) -> Duration {
+        self.0
+    }
+
+    /// `true` if `now` (a [`Timer::now`] reading) is past the deadline.
+    /// Always `false` for `None`.
+    #[must_use]
+    pub fn has_passed(&self, now: Option<Duration>) -> bool {
+        now.is_some_and(|now| now > self.0)
+    }
 }
 
 /// Executor-agnostic task-spawning primitive.
@@ -1252,6 +1449,15 @@ pub trait MpscSend<T: Send + 'static>: Clone + Send + 'static {
     /// Send `value`, waiting if the channel is full. Returns `Err(())` if the
     /// receiver was dropped.
     fn send(&self, value: T) -> impl core::future::Future<Output = Result<(), ()>> + Send + '_;
+
+    /// Send `value` without waiting, for callers that cannot await
+    /// (e.g. a `Drop` impl).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(value)` if the channel is full or the receiver was
+    /// dropped.
+    fn try_send(&self, value: T) -> Result<(), T>;
 }
 
 /// The receive half of a bounded MPSC channel.
@@ -1277,6 +1483,10 @@ pub trait UnboundedSend<T: Send + 'static>: Clone + Send + 'static {
     ///
     /// Returns `Err(value)` if the receiver was dropped.
     fn send_now(&self, value: T) -> Result<(), T>;
+
+    /// `true` once the receiver was dropped. Tells a closed channel from
+    /// a full one after [`Self::send_now`] failed.
+    fn is_closed(&self) -> bool;
 }
 
 /// The receive half of an unbounded MPSC channel.
@@ -1284,6 +1494,12 @@ pub trait UnboundedRecv<T: Send + 'static>: Send + 'static {
     /// Receive the next value, waiting if the channel is empty. Returns `None`
     /// if all senders were dropped and the channel is empty.
     fn recv(&mut self) -> impl core::future::Future<Output = Option<T>> + Send + '_;
+
+    /// Poll the channel without blocking. Backs the cancel-safe
+    /// `ClientUpdates::recv` and its `Stream` impl: a value is only
+    /// dequeued by a poll that returns it, so dropping a pending receive
+    /// never loses one.
+    fn poll_recv(&mut self, cx: &mut core::task::Context<'_>) -> core::task::Poll<Option<T>>;
 }
 
 /// A zero-sized factory that creates channel pairs used by the client's
@@ -1363,6 +1579,21 @@ pub trait ChannelFactory: Clone + Send + Sync + 'static {
         T::bounded_pair()
     }
 
+    /// Create a bounded channel holding `capacity` values, for backends
+    /// that size channels at runtime; the others hold `N`.
+    ///
+    /// Default body delegates to
+    /// [`BoundedPooled::bounded_pair_with_capacity`].
+    #[must_use]
+    fn bounded_with_capacity<T, const N: usize>(
+        capacity: usize,
+    ) -> (Self::BoundedSender<T, N>, Self::BoundedReceiver<T, N>)
+    where
+        T: BoundedPooled<Self, N>,
+    {
+        T::bounded_pair_with_capacity(capacity)
+    }
+
     /// Unbounded-channel sender type.
     type UnboundedSender<T: Send + 'static>: UnboundedSend<T>;
     /// Unbounded-channel receiver type.
@@ -1402,6 +1633,17 @@ pub trait BoundedPooled<C: ChannelFactory, const N: usize>: Send + Sized + 'stat
     /// Build a `(sender, receiver)` pair through `C`'s bounded family
     /// with capacity `N`.
     fn bounded_pair() -> (C::BoundedSender<Self, N>, C::BoundedReceiver<Self, N>);
+
+    /// Like [`Self::bounded_pair`], holding `capacity` values instead of
+    /// `N` where the backend can. The default ignores `capacity`, as a
+    /// static pool's depth is fixed where it is declared.
+    #[must_use]
+    fn bounded_pair_with_capacity(
+        capacity: usize,
+    ) -> (C::BoundedSender<Self, N>, C::BoundedReceiver<Self, N>) {
+        let _ = capacity;
+        Self::bounded_pair()
+    }
 }
 
 /// Per-`T` opt-in for [`ChannelFactory::unbounded`]. See
@@ -1678,6 +1920,15 @@ mod tests {
         block_on_ready(timer.sleep(Duration::from_secs(1)));
     }
 
+    #[test]
+    fn deadline_passes_only_with_a_clock() {
+        assert!(Deadline::after(&NullTimer, Duration::from_secs(1)).is_none());
+        let deadline = Deadline::at(Duration::from_secs(5));
+        assert!(!deadline.has_passed(None));
+        assert!(!deadline.has_passed(Some(Duration::from_secs(5))));
+        assert!(deadline.has_passed(Some(Duration::from_secs(6))));
+    }
+
     #[test]
     fn received_datagram_construct_and_field_access() {
         let d = ReceivedDatagram {
@@ -31,6 +31,13 @@ define_static_channels! {
         (Result<(), ClientError>, 4),
         (Result<RawPayload, ClientError>, 2),
         (Result<RebootFlag, ClientError>, 2),
+        (Result<Option<Duration>, ClientError>, 2),
+        (Result<simple_someip::PeerTable, ClientError>, 2),
+        (Result<simple_someip::ServiceEndpointKey, ClientError>, 2),
+        (Result<simple_someip::JanitorStats, ClientError>, 2),
+        (Result<simple_someip::DropStats, ClientError>, 2),
+        (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+        (Result<simple_someip::client::SubscriptionState, ClientError>, 2),
     ],
     bounded: [
         ((ControlMessage<RawPayload, LocalChannels>, 4), 2),
@@ -212,6 +219,7 @@ async fn client_constructible_with_local_spawner() {
                     e2e_registry: e2e_handle,
                     interface: interface_handle,
                     buffer_provider: StaticBufferProvider(&POOL),
+                    stream_factory: simple_someip::NoStreams,
                 },
                 false,
             );
@@ -38,8 +38,8 @@ use simple_someip::protocol::{
     Header, Message, MessageId, MessageType, MessageTypeField, ReturnCode,
 };
 use simple_someip::server::{
-    Error as ServerError, EventPublisher, ServerConfig, SubscribeError, Subscriber,
-    SubscriptionHandle,
+    Error as ServerError, EventPublisher, ExpiredSubscribers, ServerConfig, SubscribeError,
+    SubscribeOutcome, Subscriber, SubscriptionHandle,
 };
 use simple_someip::static_channels::BufferPool;
 use simple_someip::transport::{
@@ -70,6 +70,13 @@ define_static_channels! {
         (Result<(), ClientError>, 16),
         (Result<RawPayload, ClientError>, 8),
         (Result<RebootFlag, ClientError>, 8),
+        (Result<Option<Duration>, ClientError>, 8),
+        (Result<simple_someip::PeerTable, ClientError>, 8),
+        (Result<simple_someip::ServiceEndpointKey, ClientError>, 8),
+        (Result<simple_someip::JanitorStats, ClientError>, 8),
+        (Result<simple_someip::DropStats, ClientError>, 8),
+        (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+        (Result<simple_someip::client::SubscriptionState, ClientError>, 8),
     ],
     bounded: [
         ((ControlMessage<RawPayload, E2ETestChannels>, 4), 8),
@@ -286,9 +293,11 @@ type SubKey = (u16, u16, u16, SocketAddrV4);
 struct MockSubscriptions(Arc<Mutex<Vec<SubKey>>>);
 
 impl SubscriptionHandle for MockSubscriptions {
-    type SubscribeFuture<'a> =
-        core::pin::Pin<Box<dyn Future<Output = Result<(), SubscribeError>> + Send + 'a>>;
+    type SubscribeFuture<'a> = core::pin::Pin<
+        Box<dyn Future<Output = Result<SubscribeOutcome, SubscribeError>> + Send + 'a>,
+    >;
     type UnsubscribeFuture<'a> = core::pin::Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+    type ExpireFuture<'a> = core::future::Ready<ExpiredSubscribers>;
 
     fn subscribe(
         &self,
@@ -301,10 +310,11 @@ impl SubscriptionHandle for MockSubscriptions {
         Box::pin(async move {
             let mut guard = this.lock().unwrap();
             let key = (service_id, instance_id, event_group_id, subscriber_addr);
-            if !guard.contains(&key) {
-                guard.push(key);
+            if guard.contains(&key) {
+                return Ok(SubscribeOutcome::Refreshed);
             }
-            Ok(())
+            guard.push(key);
+            Ok(SubscribeOutcome::New)
         })
     }
 
@@ -346,6 +356,10 @@ impl SubscriptionHandle for MockSubscriptions {
             count
         }
     }
+
+    fn expire(&self, _now: core::time::Duration) -> Self::ExpireFuture<'_> {
+        core::future::ready(ExpiredSubscribers::new())
+    }
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────
@@ -409,6 +423,7 @@ async fn client_receives_server_sd_announcement() {
         e2e_registry: client_e2e,
         interface: client_iface,
         buffer_provider: StaticBufferProvider(&POOL_SD),
+        stream_factory: simple_someip::NoStreams,
     };
 
     let (client, mut updates, run_fut) = Client::<
@@ -510,6 +525,7 @@ async fn client_send_request_server_runloop_stable() {
         e2e_registry: client_e2e,
         interface: client_iface,
         buffer_provider: StaticBufferProvider(&POOL_REQ),
+        stream_factory: simple_someip::NoStreams,
     };
 
     let (client, mut updates, client_run_fut) = Client::<
@@ -616,9 +632,77 @@ async fn client_send_request_server_runloop_stable() {
 // (ServiceEndpointKey now embeds a full NetEndpoint — SocketAddr +
 // TransportProtocol — so the 64 inline registry entries and every
 // queued ControlMessage carry the larger key).
-const BM_CLIENT_RUN_FUTURE_BUDGET: usize = 44224; // = ceil64(35376 × 1.25)
-const BM_CLIENT_SOCKET_LOOP_BUDGET: usize = 1024; // = ceil64(776 × 1.25); receive buffer moved to BufferProvider pool (Tasks 3+4)
-const BM_SERVER_RUN_FUTURE_BUDGET: usize = 4416; // = ceil64(3528 × 1.25); send buffers moved to caller scratch (PR3 T2+T3)
+// Raised since by the state each change added, as measured on the
+// host; the last client item is the cut from the bare-metal defaults
+// of the client's tables.
+const BM_CLIENT_RUN_FUTURE_BUDGET: usize = 44_224 // = ceil64(35376 × 1.25)
+    + 528 // version policies
+    + 3104 // offer lifetime countdown and expiry margins
+    + 816 // peer statistics table
+    + 5152 // duplicate notification filter
+    + 240 // round-robin control queue
+    + 1056 // request send deadlines
+    + 1152 // reachability probing
+    + 1216 // service waiters
+    + 752 // TCP connection map and connect timeout
+    + 1216 // first-event waiters
+    + 688 // TCP in-flight table
+    + 400 // janitor state
+    + 1544 // message IDs in pending-request keys
+    + 5296 // request policies and the timed-request table
+    + 48 // session-less services
+    + 3344 // tracked subscriptions
+    + 576 // tracked `subscribe`s
+    + 1680 // event streams
+    + 26_656 // `subscribe_many` batches
+    + 280 // receive ports
+    + 448 // sampled drop counts
+    + 1024 // load-balancing options of registry entries
+    + 288 // find policies of service waiters
+    + 144 // payload ranges in decode limits
+    + 1952 // provider selections
+    + 176 // event validators
+    + 1024 // per-peer request sessions
+    + 16 // startup spacing of find policies
+    + 1680 // remembered endpoint resolutions
+    + 272 // channel settings and overflow counts of sockets
+    + 48 // discovery tuning options
+    - 35_960; // bare-metal caps of the subscription, policy, retry and dedup tables
+const BM_CLIENT_SOCKET_LOOP_BUDGET: usize = 1024 // = ceil64(776 × 1.25); receive buffer moved to BufferProvider pool (Tasks 3+4)
+    + 152 // SOME/IP-TP reassembly
+    + 200 // drop counts
+    + 80 // decode limits
+    + 128 // channel settings
+    + 16; // pooled send scratch
+const BM_SERVER_RUN_FUTURE_BUDGET: usize = 4416 // = ceil64(3528 × 1.25); send buffers moved to caller scratch (PR3 T2+T3)
+    + 16 // wildcard major versions
+    + 64 // ServerTask shutdown
+    + 72 // decode limits
+    + 96 // peer statistics table
+    + 16 // subscription lifecycle events
+    + 168 // sticky event cache
+    + 864 // TCP listener
+    + 24 // SOME/IP-TP segmenter
+    + 48 // SOME/IP-TP reassembly
+    + 352 // janitor state
+    + 80 // method handler routing
+    + 112 // randomized initial SD wait
+    + 48 // per-eventgroup multicast
+    + 16 // peer authentication
+    + 1608 // DNS-SD configuration strings
+    + 840 // load-balancing options
+    + 104 // SD offer phase state
+    + 48 // payload ranges in decode limits
+    - 248 // hosted service instances
+    + 96 // SD responder
+    + 504 // subscription expiry loop
+    + 48 // startup spacing
+    + 48 // compatibility rules
+    + 48 // staggered offers
+    - 1040 // cached offer frame
+    + 176 // find response delay and delayed finds
+    + 64 // pooled receive-loop buffers
+    + 48; // initial-events registry
 
 #[tokio::test]
 async fn future_size_witness_bare_metal_channels() {
@@ -683,6 +767,7 @@ async fn future_size_witness_bare_metal_channels() {
         e2e_registry: Arc::new(Mutex::new(E2ERegistry::new())),
         interface: Arc::new(RwLock::new(Ipv4Addr::LOCALHOST)),
         buffer_provider: StaticBufferProvider(&POOL_WITNESS),
+        stream_factory: simple_someip::NoStreams,
     };
     let (client, _updates, run_fut) = Client::<
         RawPayload,
@@ -877,6 +962,7 @@ async fn inbound_datagram_larger_than_claimed_buffer_is_dropped_not_fatal() {
         e2e_registry: client_e2e,
         interface: client_iface,
         buffer_provider: StaticBufferProvider(&POOL),
+        stream_factory: simple_someip::NoStreams,
     };
     let (client, mut updates, run_fut) = Client::<
         RawPayload,
@@ -942,6 +1028,7 @@ async fn binding_sockets_claims_one_buffer_each_until_pool_exhausted() {
         e2e_registry: client_e2e,
         interface: client_iface,
         buffer_provider: StaticBufferProvider(&POOL),
+        stream_factory: simple_someip::NoStreams,
     };
     let (client, _updates, run_fut) = Client::<
         RawPayload,
@@ -1049,6 +1136,7 @@ async fn e2e_protect_expanding_payload_beyond_leased_buffer_returns_capacity_err
         e2e_registry: client_e2e,
         interface: client_iface,
         buffer_provider: StaticBufferProvider(&POOL),
+        stream_factory: simple_someip::NoStreams,
     };
     let (client, _updates, run_fut) = Client::<
         RawPayload,
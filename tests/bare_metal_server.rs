@@ -34,7 +34,9 @@ use std::vec::Vec;
 use simple_someip::e2e::E2ERegistry;
 use simple_someip::server::NonSdRequestCallback;
 use simple_someip::server::ServerConfig;
-use simple_someip::server::{SubscribeError, Subscriber, SubscriptionHandle};
+use simple_someip::server::{
+    ExpiredSubscribers, SubscribeError, SubscribeOutcome, Subscriber, SubscriptionHandle,
+};
 use simple_someip::transport::{
     ReceivedDatagram, SocketOptions, Timer, TransportError, TransportFactory, TransportSocket,
 };
@@ -220,9 +222,11 @@ type SubKey = (u16, u16, u16, SocketAddrV4);
 struct MockSubscriptions(Arc<Mutex<Vec<SubKey>>>);
 
 impl SubscriptionHandle for MockSubscriptions {
-    type SubscribeFuture<'a> =
-        core::pin::Pin<Box<dyn Future<Output = Result<(), SubscribeError>> + Send + 'a>>;
+    type SubscribeFuture<'a> = core::pin::Pin<
+        Box<dyn Future<Output = Result<SubscribeOutcome, SubscribeError>> + Send + 'a>,
+    >;
     type UnsubscribeFuture<'a> = core::pin::Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+    type ExpireFuture<'a> = core::future::Ready<ExpiredSubscribers>;
 
     fn subscribe(
         &self,
@@ -235,10 +239,11 @@ impl SubscriptionHandle for MockSubscriptions {
         Box::pin(async move {
             let mut guard = this.lock().unwrap();
             let key = (service_id, instance_id, event_group_id, subscriber_addr);
-            if !guard.contains(&key) {
-                guard.push(key);
+            if guard.contains(&key) {
+                return Ok(SubscribeOutcome::Refreshed);
             }
-            Ok(())
+            guard.push(key);
+            Ok(SubscribeOutcome::New)
         })
     }
 
@@ -280,6 +285,10 @@ impl SubscriptionHandle for MockSubscriptions {
             count
         }
     }
+
+    fn expire(&self, _now: core::time::Duration) -> Self::ExpireFuture<'_> {
+        core::future::ready(ExpiredSubscribers::new())
+    }
 }
 
 // ── Test ──────────────────────────────────────────────────────────────
@@ -118,3 +118,22 @@ fn concurrent_claim_hands_out_distinct_non_aliasing_slots() {
         "an {N}-slot pool with {N} leases outstanding must refuse another claim",
     );
 }
+
+#[cfg(feature = "_alloc")]
+#[test]
+fn claim_or_alloc_falls_back_to_a_zeroed_allocation() {
+    let pool = std::sync::Arc::new(BufferPool::<1, 16>::new());
+    let mut leased = pool.claim_or_alloc();
+    assert!(leased.is_leased());
+    leased[0] = 0xAB;
+
+    let allocated = pool.claim_or_alloc();
+    assert!(!allocated.is_leased(), "an exhausted pool allocates");
+    assert_eq!(&*allocated, &[0u8; 16]);
+
+    drop(leased);
+    assert!(
+        pool.claim_or_alloc().is_leased(),
+        "a returned slot is reused"
+    );
+}
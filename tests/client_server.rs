@@ -31,8 +31,8 @@ use simple_someip::e2e::{E2ECheckStatus, E2EKey, E2EProfile, Profile4Config};
 use simple_someip::protocol::{Header, Message, MessageId, sd};
 use simple_someip::server::ServerConfig;
 use simple_someip::{
-    Client, ClientUpdate, ClientUpdates, PayloadWireFormat, RawPayload, Server, TokioChannels,
-    VecSdHeader,
+    Client, ClientUpdate, ClientUpdates, EventgroupLayout, PayloadWireFormat, RawPayload, Server,
+    TokioChannels, VecSdHeader,
 };
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::atomic::{AtomicU16, Ordering};
@@ -185,7 +185,7 @@ async fn test_client_server_subscribe_and_receive_event() {
         )
         .await
         .unwrap();
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -219,12 +219,63 @@ async fn test_client_server_subscribe_and_receive_event() {
         "expected Unicast, got {update:?}"
     );
 
+    // The client tracked the subscribe it sent and the event it received.
+    // Subscribe goes to the SD port, the event comes from the unicast port.
+    let peers = client.peer_stats().await.unwrap();
+    let server_total = |stats: fn(&simple_someip::PeerStats) -> u64| {
+        peers
+            .iter()
+            .filter(|(addr, _)| addr.ip() == SERVER_IP)
+            .map(|(_, s)| stats(s))
+            .sum::<u64>()
+    };
+    assert!(server_total(|s| s.sent) >= 1);
+    assert!(server_total(|s| s.received) >= 1);
+
     // Tear down
     client.unbind_discovery().await.unwrap();
     client.shut_down();
     server_handle.abort();
 }
 
+#[tokio::test]
+async fn test_subscribe_events_subscribes_covering_eventgroups() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let layout = EventgroupLayout::new()
+        .with_eventgroup(0x01, &[0x8001])
+        .unwrap()
+        .with_eventgroup(0x02, &[0x8002])
+        .unwrap()
+        .with_eventgroup(0x03, &[0x8001, 0x8002])
+        .unwrap();
+    let subscribed = client
+        .subscribe_events(key, 1, 3, &layout, &[0x8001, 0x8002], 0)
+        .await
+        .unwrap();
+    assert_eq!(subscribed, [0x03]);
+    assert!(
+        wait_for_subscribers(&publisher, service_id, 1, 0x03).await,
+        "server should have registered the covering eventgroup"
+    );
+    assert!(!publisher.has_subscribers(service_id, 1, 0x01).await);
+
+    client.unbind_discovery().await.unwrap();
+    client.shut_down();
+    server_handle.abort();
+}
+
 #[tokio::test]
 async fn test_client_send_sd_auto_binds_discovery() {
     // Create server so there is something to send to
@@ -280,7 +331,7 @@ async fn test_client_bind_unbind_lifecycle_with_server() {
         )
         .await
         .unwrap();
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -328,7 +379,7 @@ async fn test_add_endpoint_and_send_to_service() {
         .unwrap();
 
     // Subscribe to server's event group (auto-binds unicast internally)
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -411,7 +462,7 @@ async fn test_subscribe_auto_binds_discovery() {
         .await
         .unwrap();
     // Subscribe should auto-bind discovery internally
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -468,7 +519,7 @@ async fn test_client_request_resolves_via_unicast_reply() {
         )
         .await
         .unwrap();
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -558,7 +609,7 @@ async fn test_e2e_protect_on_publish_and_check_on_receive() {
         )
         .await
         .unwrap();
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -633,7 +684,7 @@ async fn test_multiple_subscribers_receive_events() {
         )
         .await
         .unwrap();
-    client1
+    let _subscription = client1
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -655,7 +706,7 @@ async fn test_multiple_subscribers_receive_events() {
         )
         .await
         .unwrap();
-    client2
+    let _subscription = client2
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -759,7 +810,7 @@ async fn test_cloned_client_works() {
         )
         .await
         .unwrap();
-    client2
+    let _subscription = client2
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -775,6 +826,139 @@ async fn test_cloned_client_works() {
     server_handle.abort();
 }
 
+/// With the duplicate filter on, a notification repeated with the same
+/// session ID is delivered once; a new session ID gets through.
+#[tokio::test]
+async fn test_duplicate_filter_drops_repeated_notification() {
+    use simple_someip::WireFormat;
+
+    let service_id = next_service_id();
+    let provider = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    client.set_duplicate_filter(true).await.unwrap();
+    let key = ServiceEndpointKey::udp(service_id, provider.local_addr().unwrap());
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let client_port = {
+        let probe = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        probe.local_addr().unwrap().port()
+    };
+    let _subscription = client
+        .subscribe(key, 1, 3, 0x01, client_port)
+        .await
+        .unwrap();
+
+    let notify = |session: u32| {
+        let mut datagram = std::vec::Vec::new();
+        Header::new_event(service_id, 0x8001, session, 1, 1, 0)
+            .encode(&mut datagram)
+            .unwrap();
+        datagram
+    };
+    for session in [1, 1, 2] {
+        provider
+            .send_to(&notify(session), ("127.0.0.1", client_port))
+            .await
+            .unwrap();
+    }
+
+    let mut sessions = std::vec::Vec::new();
+    while let Ok(Some(update)) =
+        tokio::time::timeout(std::time::Duration::from_millis(300), updates.recv()).await
+    {
+        if let ClientUpdate::Unicast { message, .. } = update {
+            sessions.push(message.header().request_id());
+        }
+    }
+    assert_eq!(sessions, [1, 2]);
+
+    client.shut_down();
+}
+
+/// A provider that stops answering probes is reported degraded, and
+/// reported reachable again once it answers; probe replies are not
+/// forwarded as unicast updates.
+#[tokio::test]
+async fn test_reachability_probe_marks_silent_endpoint_degraded() {
+    use simple_someip::ProbeConfig;
+    use simple_someip::WireFormat;
+    use simple_someip::protocol::{HeaderView, MessageType, MessageTypeField, ReturnCode};
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let provider = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, provider.local_addr().unwrap());
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    // Probes reuse an existing unicast socket; subscribing binds one.
+    let client_port = {
+        let probe = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        probe.local_addr().unwrap().port()
+    };
+    let _subscription = client
+        .subscribe(key, 1, 3, 0x01, client_port)
+        .await
+        .unwrap();
+    client
+        .set_reachability_probe(Some(
+            ProbeConfig::new(0x7F00)
+                .with_interval(Duration::from_millis(150))
+                .with_timeout(Duration::from_millis(100))
+                .with_failures_before_degraded(2),
+        ))
+        .await
+        .unwrap();
+
+    async fn next_reachability(
+        updates: &mut ClientUpdates<RawPayload, TokioChannels>,
+        key: ServiceEndpointKey,
+    ) -> bool {
+        loop {
+            let update = tokio::time::timeout(Duration::from_secs(3), updates.recv())
+                .await
+                .expect("no reachability update")
+                .unwrap();
+            match update {
+                ClientUpdate::Reachability { key: k, reachable } if k == key => return reachable,
+                ClientUpdate::Unicast { .. } => panic!("probe reply forwarded"),
+                _ => {}
+            }
+        }
+    }
+    assert!(
+        !next_reachability(&mut updates, key).await,
+        "silent provider degraded"
+    );
+
+    // Skip the probes already missed, then answer the next one with an
+    // error reply: still proof of life.
+    let mut buf = [0u8; 64];
+    while provider.try_recv_from(&mut buf).is_ok() {}
+    let (len, from) = provider.recv_from(&mut buf).await.unwrap();
+    let (probe, _) = HeaderView::parse(&buf[..len]).unwrap();
+    assert_eq!(probe.message_id().method_id(), 0x7F00);
+    let mut reply = std::vec::Vec::new();
+    Header::new(
+        probe.message_id(),
+        probe.request_id(),
+        1,
+        1,
+        MessageTypeField::new(MessageType::Error, false),
+        ReturnCode::UnknownMethod,
+        0,
+    )
+    .encode(&mut reply)
+    .unwrap();
+    provider.send_to(&reply, from).await.unwrap();
+    assert!(
+        next_reachability(&mut updates, key).await,
+        "answering provider recovered"
+    );
+
+    client.shut_down();
+}
+
 /// Subscribe with a specific client_port, then subscribe again reusing the same port.
 /// Exercises the port-reuse path in Subscribe handling.
 #[tokio::test]
@@ -797,7 +981,7 @@ async fn test_subscribe_specific_port_reuse() {
 
     // Use specific port
     let specific_port = 44444;
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -808,7 +992,7 @@ async fn test_subscribe_specific_port_reuse() {
         .await
         .unwrap();
     // Second subscribe reuses the port
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr)),
             1,
@@ -823,6 +1007,148 @@ async fn test_subscribe_specific_port_reuse() {
     server_handle.abort();
 }
 
+/// A standby subscriber gets a copy of every event sent to its primary,
+/// and the client tells the two paths apart by the receiving port.
+#[tokio::test]
+async fn test_standby_subscriber_receives_the_primary_events() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let (primary_port, standby_port) = (44_461, 44_462);
+    let _primary = client
+        .subscribe(key, 1, 3, 0x01, primary_port)
+        .await
+        .unwrap();
+    // Binds the standby port; its own event group stays silent.
+    let _standby = client
+        .subscribe(key, 1, 3, 0x02, standby_port)
+        .await
+        .unwrap();
+    assert!(wait_for_subscribers(&publisher, service_id, 1, 0x01).await);
+    let primary = publisher.subscriber_addresses(service_id, 1, 0x01).await[0];
+    publisher
+        .set_standby_subscriber(
+            primary,
+            Some(SocketAddrV4::new(*primary.ip(), standby_port)),
+        )
+        .unwrap();
+
+    let sent = publisher
+        .publish_raw_event(service_id, 1, 0x01, 0x8001, 1, 1, 1, &[0x42])
+        .await
+        .unwrap();
+    assert_eq!(sent, 2, "primary and standby");
+    let mut ports = Vec::new();
+    for _ in 0..2 {
+        match recv_unicast(&mut updates).await {
+            ClientUpdate::Unicast {
+                message,
+                local_port,
+                ..
+            } => {
+                assert_eq!(message.payload().raw_bytes(), Some(&[0x42][..]));
+                ports.push(local_port);
+            }
+            other => panic!("expected Unicast, got {other:?}"),
+        }
+    }
+    ports.sort_unstable();
+    assert_eq!(ports, [primary_port, standby_port]);
+
+    publisher.set_standby_subscriber(primary, None).unwrap();
+    let sent = publisher
+        .publish_raw_event(service_id, 1, 0x01, 0x8001, 2, 1, 1, &[0x43])
+        .await
+        .unwrap();
+    assert_eq!(sent, 1);
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_event_validators_stop_bad_payloads_on_both_sides() {
+    fn server_check(payload: &[u8]) -> Result<(), &'static str> {
+        if payload == [0xFF] {
+            Err("invalid marker")
+        } else {
+            Ok(())
+        }
+    }
+    fn client_check(payload: &RawPayload) -> Result<(), &'static str> {
+        match payload.raw_bytes() {
+            Some([0..=3]) => Ok(()),
+            _ => Err("gear out of range"),
+        }
+    }
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    client
+        .set_event_validator(service_id, 0x8001, Some(client_check))
+        .await
+        .unwrap();
+    let _subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(wait_for_subscribers(&publisher, service_id, 1, 0x01).await);
+    publisher
+        .set_event_validator(service_id, 0x8001, Some(server_check))
+        .unwrap();
+
+    let rejected = publisher
+        .publish_raw_event(service_id, 1, 0x01, 0x8001, 1, 1, 1, &[0xFF])
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(simple_someip::server::Error::PayloadRejected {
+            reason: "invalid marker",
+            ..
+        })
+    ));
+    assert_eq!(publisher.rejected_events(service_id, 0x8001), 1);
+
+    for (session, value) in [(2, 7), (3, 2)] {
+        let sent = publisher
+            .publish_raw_event(service_id, 1, 0x01, 0x8001, session, 1, 1, &[value])
+            .await
+            .unwrap();
+        assert_eq!(sent, 1);
+    }
+    match recv_unicast(&mut updates).await {
+        ClientUpdate::Unicast { message, .. } => {
+            assert_eq!(message.payload().raw_bytes(), Some(&[2][..]));
+        }
+        other => panic!("expected Unicast, got {other:?}"),
+    }
+    let rejected: u64 = client
+        .peer_stats()
+        .await
+        .unwrap()
+        .iter()
+        .map(|(_, stats)| stats.rejected_payloads)
+        .sum();
+    assert_eq!(rejected, 1);
+    client.shut_down();
+    server_handle.abort();
+}
+
 /// Regression for the source-keyed registry (the fix this test suite gates):
 /// once firmware assigns a fixed (ECU-Extract) instance id, every device on
 /// the subnet advertises the identical `(service_id, instance_id)`. Two real
@@ -873,7 +1199,7 @@ async fn test_two_devices_same_service_instance_addressed_independently() {
 
     // Distinct event groups so `has_subscribers`/`publish_event` on each
     // server can be checked independently.
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(addr_a)),
             1,
@@ -883,7 +1209,7 @@ async fn test_two_devices_same_service_instance_addressed_independently() {
         )
         .await
         .unwrap();
-    client
+    let _subscription = client
         .subscribe(
             ServiceEndpointKey::udp(service_id, SocketAddr::V4(addr_b)),
             1,
@@ -973,3 +1299,2037 @@ async fn test_two_devices_same_service_instance_addressed_independently() {
     server_a_handle.abort();
     server_b_handle.abort();
 }
+
+/// `wait_for_service` resolves from the server's answer to its
+/// `FindService`, returns at once for a known service and times out for
+/// one nobody offers.
+#[tokio::test]
+async fn test_wait_for_service_resolves_offer_and_times_out() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+
+    let key = client
+        .wait_for_service(service_id, 1, std::time::Duration::from_secs(5))
+        .await
+        .expect("server should answer the FindService");
+    assert_eq!(
+        key,
+        ServiceEndpointKey::udp(
+            service_id,
+            SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port))
+        )
+    );
+
+    let again = client
+        .find_service(service_id)
+        .await
+        .expect("already known");
+    assert_eq!(again, key);
+
+    let result = client
+        .wait_for_service(next_service_id(), 1, std::time::Duration::from_millis(300))
+        .await;
+    assert!(
+        matches!(result, Err(simple_someip::client::Error::Timeout)),
+        "expected Timeout, got {result:?}"
+    );
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// `find_service_with` holds its first `FindService` for the initial wait
+/// and still resolves from the answer.
+#[tokio::test]
+async fn test_find_service_with_initial_wait_resolves_offer() {
+    use simple_someip::client::FindPolicy;
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+
+    let policy = FindPolicy::new()
+        .with_initial_delay(Duration::from_millis(20), Duration::from_millis(50))
+        .with_repetitions(2, Duration::from_millis(50))
+        .with_timeout(Duration::from_secs(5));
+    let key = client
+        .find_service_with(service_id, 0xFFFF, policy)
+        .await
+        .expect("server should answer the FindService");
+    assert_eq!(
+        key,
+        ServiceEndpointKey::udp(
+            service_id,
+            SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port))
+        )
+    );
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// `cancel_find` fails a pending search with `FindCancelled` and frees
+/// its slot; searches for other services go on.
+#[tokio::test]
+async fn test_cancel_find_stops_pending_search() {
+    use simple_someip::client::FindPolicy;
+    use std::time::Duration;
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+
+    let wanted = next_service_id();
+    let other = next_service_id();
+    let search = client.find_service_with(wanted, 1, FindPolicy::new());
+    let other_search = client.find_service_with(
+        other,
+        0xFFFF,
+        FindPolicy::new().with_timeout(Duration::from_millis(300)),
+    );
+    let cancel = async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        client.cancel_find(wanted, 0xFFFF).await.unwrap();
+    };
+    let (result, other_result, ()) = tokio::join!(search, other_search, cancel);
+    assert!(
+        matches!(result, Err(simple_someip::client::Error::FindCancelled)),
+        "expected FindCancelled, got {result:?}"
+    );
+    assert!(
+        matches!(other_result, Err(simple_someip::client::Error::Timeout)),
+        "the other search must run to its timeout, got {other_result:?}"
+    );
+
+    client.cancel_find(wanted, 1).await.unwrap();
+    client.shut_down();
+}
+
+/// `subscribe_and_wait_first` resolves on the first notification after
+/// subscribing, and times out while none arrives.
+#[tokio::test]
+async fn test_subscribe_and_wait_first_resolves_on_first_event() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let publish = async {
+        assert!(
+            wait_for_subscribers(&publisher, service_id, 1, 0x01).await,
+            "server should have registered the subscriber"
+        );
+        let payload = [0xAA];
+        let msg_id = MessageId::new_from_service_and_method(service_id, 0x8001);
+        let header = Header::new_event(service_id, 0x8001, 1, 0x01, 0x01, payload.len());
+        let event = Message::new(
+            header,
+            RawPayload::from_payload_bytes(msg_id, &payload).unwrap(),
+        );
+        publisher
+            .publish_event(service_id, 1, 0x01, &event)
+            .await
+            .expect("publish_event failed");
+    };
+    let (first, ()) = tokio::join!(
+        client.subscribe_and_wait_first(key, 1, 3, 0x01, 0, std::time::Duration::from_secs(2)),
+        publish
+    );
+    first.expect("first event");
+
+    let result = client
+        .subscribe_and_wait_first(key, 1, 3, 0x01, 0, std::time::Duration::from_millis(300))
+        .await;
+    assert!(
+        matches!(result, Err(simple_someip::client::Error::Timeout)),
+        "no further events, got {result:?}"
+    );
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// A request to a TCP endpoint opens a connection, its reply comes back
+/// on it, and once the provider closes the connection the next request
+/// reconnects.
+#[tokio::test]
+async fn test_send_to_tcp_service_connects_and_reconnects() {
+    use simple_someip::protocol::{MessageType, MessageTypeField, ReturnCode};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let service_id = next_service_id();
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let server_addr = match listener.local_addr().unwrap() {
+        SocketAddr::V4(a) => a,
+        SocketAddr::V6(_) => panic!("expected IPv4"),
+    };
+    // Answer one request per connection, then hang up.
+    let provider = tokio::spawn(async move {
+        for _ in 0..2 {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut header = [0u8; 8];
+            stream.read_exact(&mut header).await.unwrap();
+            let length = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+            let mut rest = vec![0u8; length];
+            stream.read_exact(&mut rest).await.unwrap();
+            // Message type 0x80 (Response), same request ID.
+            rest[6] = 0x80;
+            stream.write_all(&header).await.unwrap();
+            stream.write_all(&rest).await.unwrap();
+        }
+    });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::new(
+        service_id,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(server_addr)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    for _ in 0..2 {
+        let payload = [0xAA, 0xBB];
+        let msg_id = MessageId::new_from_service_and_method(service_id, 0x0001);
+        let header = Header::new(
+            msg_id,
+            0,
+            1,
+            1,
+            MessageTypeField::new(MessageType::Request, false),
+            ReturnCode::Ok,
+            payload.len(),
+        );
+        let msg = Message::new(
+            header,
+            RawPayload::from_payload_bytes(msg_id, &payload).unwrap(),
+        );
+        let pending = client
+            .send_to_service(key, msg)
+            .await
+            .expect("send over TCP");
+        tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+            .await
+            .expect("reply over TCP")
+            .expect("response");
+    }
+    provider.await.unwrap();
+    client.shut_down();
+}
+
+/// A TCP connect to a peer that never completes the handshake times out
+/// instead of holding up the run loop: a request to another provider
+/// still gets its reply.
+#[tokio::test]
+async fn test_tcp_connect_to_unresponsive_peer_times_out() {
+    use simple_someip::ClientConfig;
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+
+    // A listener that never accepts, with its accept queue filled by one
+    // connection: Linux then drops further SYNs, so a connect hangs.
+    let silent = socket2::Socket::new(
+        socket2::Domain::IPV4,
+        socket2::Type::STREAM,
+        Some(socket2::Protocol::TCP),
+    )
+    .unwrap();
+    silent
+        .bind(&SocketAddr::from((Ipv4Addr::LOCALHOST, 0)).into())
+        .unwrap();
+    silent.listen(0).unwrap();
+    let SocketAddr::V4(silent_addr) = silent.local_addr().unwrap().as_socket().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let _filler = std::net::TcpStream::connect(silent_addr).unwrap();
+    if std::net::TcpStream::connect_timeout(&silent_addr.into(), Duration::from_millis(200)).is_ok()
+    {
+        eprintln!("SKIP tcp_connect_timeout: the OS completed a connect to a full accept queue");
+        return;
+    }
+
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(server_addr) = listener.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let provider = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut reply = read_tcp_message(&mut stream).await;
+        reply[14] = 0x80;
+        stream.write_all(&reply).await.unwrap();
+    });
+
+    let config = ClientConfig::new()
+        .with_interface(Ipv4Addr::LOCALHOST)
+        .with_tcp_connect_timeout(Duration::from_millis(300));
+    let (client, _updates, run_fut) = TestClient::new(config);
+    let _run_handle = tokio::spawn(run_fut);
+    let silent_service = next_service_id();
+    let silent_key = ServiceEndpointKey::new(
+        silent_service,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(silent_addr)),
+    );
+    client.add_endpoint(silent_key, 1, 0).await.unwrap();
+    let service_id = next_service_id();
+    let key = ServiceEndpointKey::new(
+        service_id,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(server_addr)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let stalled = {
+        let client = client.clone();
+        tokio::spawn(async move {
+            client
+                .send_to_service(silent_key, raw_request(silent_service, &[1]))
+                .await
+                .map(|_| ())
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let pending = client
+        .send_to_service(key, raw_request(service_id, &[2, 2]))
+        .await
+        .expect("send over TCP");
+    let reply = tokio::time::timeout(Duration::from_secs(2), pending.response())
+        .await
+        .expect("reply despite the unresponsive peer")
+        .expect("response");
+    assert_eq!(reply.raw_bytes(), Some(&[2u8, 2][..]));
+    let stalled = tokio::time::timeout(Duration::from_secs(2), stalled)
+        .await
+        .expect("the stalled connect gave up")
+        .unwrap();
+    assert!(
+        matches!(stalled, Err(simple_someip::client::Error::Timeout)),
+        "got {stalled:?}"
+    );
+
+    provider.await.unwrap();
+    client.shut_down();
+}
+
+/// Read one length-framed SOME/IP message from a TCP stream.
+async fn read_tcp_message(stream: &mut tokio::net::TcpStream) -> Vec<u8> {
+    use tokio::io::AsyncReadExt;
+
+    let mut message = vec![0u8; 8];
+    stream.read_exact(&mut message).await.unwrap();
+    let length = u32::from_be_bytes(message[4..8].try_into().unwrap()) as usize;
+    message.resize(8 + length, 0);
+    stream.read_exact(&mut message[8..]).await.unwrap();
+    message
+}
+
+fn raw_request(service_id: u16, payload: &[u8]) -> Message<RawPayload> {
+    raw_request_to(service_id, 0x0001, payload)
+}
+
+fn raw_request_to(service_id: u16, method_id: u16, payload: &[u8]) -> Message<RawPayload> {
+    use simple_someip::protocol::{MessageType, MessageTypeField, ReturnCode};
+
+    let msg_id = MessageId::new_from_service_and_method(service_id, method_id);
+    let header = Header::new(
+        msg_id,
+        0,
+        1,
+        1,
+        MessageTypeField::new(MessageType::Request, false),
+        ReturnCode::Ok,
+        payload.len(),
+    );
+    Message::new(
+        header,
+        RawPayload::from_payload_bytes(msg_id, payload).unwrap(),
+    )
+}
+
+/// Requests to a TCP endpoint go out without waiting for earlier replies,
+/// and replies arriving in another order still reach their own request.
+#[tokio::test]
+async fn test_tcp_requests_are_pipelined_and_demultiplexed() {
+    use tokio::io::AsyncWriteExt;
+
+    let service_id = next_service_id();
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(server_addr) = listener.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    // Read all three requests before answering any, then echo them back
+    // last first.
+    let provider = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut requests = Vec::new();
+        for _ in 0..3 {
+            requests.push(read_tcp_message(&mut stream).await);
+        }
+        for mut reply in requests.into_iter().rev() {
+            reply[14] = 0x80;
+            stream.write_all(&reply).await.unwrap();
+        }
+        let _ = read_tcp_message(&mut stream).await;
+    });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::new(
+        service_id,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(server_addr)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let mut pending = Vec::new();
+    for payload in [[1u8, 1], [2, 2], [3, 3]] {
+        let response = client
+            .send_to_service(key, raw_request(service_id, &payload))
+            .await
+            .expect("send over TCP");
+        pending.push((payload, response));
+    }
+    for (payload, response) in pending {
+        let reply = tokio::time::timeout(std::time::Duration::from_secs(2), response.response())
+            .await
+            .expect("reply over TCP")
+            .expect("response");
+        assert_eq!(reply.raw_bytes(), Some(&payload[..]));
+    }
+    client.shut_down();
+    provider.abort();
+}
+
+/// At most `TCP_IN_FLIGHT_CAP` requests wait on one connection, and those
+/// still waiting when the provider closes it fail instead of hanging.
+#[tokio::test]
+async fn test_tcp_in_flight_limit_and_close_fail_pending_requests() {
+    use simple_someip::client::{Error as ClientError, TCP_IN_FLIGHT_CAP};
+
+    let service_id = next_service_id();
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(server_addr) = listener.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let (close_tx, close_rx) = tokio::sync::oneshot::channel::<()>();
+    let provider = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        for _ in 0..TCP_IN_FLIGHT_CAP {
+            let _ = read_tcp_message(&mut stream).await;
+        }
+        let _ = close_rx.await;
+    });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::new(
+        service_id,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(server_addr)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let mut pending = Vec::new();
+    for _ in 0..TCP_IN_FLIGHT_CAP {
+        pending.push(
+            client
+                .send_to_service(key, raw_request(service_id, &[0]))
+                .await
+                .expect("send over TCP"),
+        );
+    }
+    assert!(matches!(
+        client
+            .send_to_service(key, raw_request(service_id, &[0]))
+            .await,
+        Err(ClientError::Capacity("tcp_in_flight"))
+    ));
+
+    close_tx.send(()).unwrap();
+    provider.await.unwrap();
+    for response in pending {
+        let result = tokio::time::timeout(std::time::Duration::from_secs(2), response.response())
+            .await
+            .expect("request failed on close");
+        assert!(matches!(result, Err(ClientError::SocketClosedUnexpectedly)));
+    }
+    client.shut_down();
+}
+
+/// A server with a TCP listener answers requests sent to its TCP
+/// endpoint through the non-SD observer.
+#[tokio::test]
+async fn test_server_serves_requests_over_tcp() {
+    use simple_someip::protocol::{MessageType, MessageTypeField, ReturnCode};
+    use simple_someip::{ServerDeps, StreamListener, TokioListener};
+
+    // Reply with the request payload reversed.
+    fn reverse(
+        _ctx: usize,
+        _source: SocketAddrV4,
+        _service_id: u16,
+        _method_id: u16,
+        payload: &[u8],
+        _e2e_status: u8,
+        response_out: &mut [u8],
+    ) -> i32 {
+        for (out, byte) in response_out.iter_mut().zip(payload.iter().rev()) {
+            *out = *byte;
+        }
+        i32::try_from(payload.len()).unwrap()
+    }
+
+    let service_id = next_service_id();
+    let listener = TokioListener::bind(SocketAddrV4::new(SERVER_IP, 0))
+        .await
+        .unwrap();
+    let tcp_addr = listener.local_addr().unwrap();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_tcp_port(tcp_addr.port());
+    let deps = ServerDeps::tokio().with_non_sd_observer(Some((reverse, 0)));
+    let (server, _handles, _run): (TestServer, _, _) =
+        TestServer::new_with_deps(deps, config, false)
+            .await
+            .expect("Server::new_with_deps failed");
+    let serve_handle = tokio::spawn(server.serve_tcp(listener));
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::new(
+        service_id,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(tcp_addr)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    for payload in [[1, 2, 3], [4, 5, 6]] {
+        let msg_id = MessageId::new_from_service_and_method(service_id, 0x0001);
+        let header = Header::new(
+            msg_id,
+            0,
+            1,
+            1,
+            MessageTypeField::new(MessageType::Request, false),
+            ReturnCode::Ok,
+            payload.len(),
+        );
+        let msg = Message::new(
+            header,
+            RawPayload::from_payload_bytes(msg_id, &payload).unwrap(),
+        );
+        let pending = client
+            .send_to_service(key, msg)
+            .await
+            .expect("send over TCP");
+        let response = tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+            .await
+            .expect("reply over TCP")
+            .expect("response");
+        let mut reversed = payload;
+        reversed.reverse();
+        assert_eq!(response.raw_bytes(), Some(&reversed[..]));
+    }
+    client.shut_down();
+    serve_handle.abort();
+}
+
+/// Requests and events larger than one datagram travel as SOME/IP-TP
+/// segments and arrive whole on the other side.
+#[tokio::test]
+async fn test_large_messages_are_segmented_over_udp() {
+    use simple_someip::ServerDeps;
+
+    // Reply with the request's payload length.
+    fn length(
+        _ctx: usize,
+        _source: SocketAddrV4,
+        _service_id: u16,
+        _method_id: u16,
+        payload: &[u8],
+        _e2e_status: u8,
+        response_out: &mut [u8],
+    ) -> i32 {
+        let len = u32::try_from(payload.len()).unwrap();
+        response_out[..4].copy_from_slice(&len.to_be_bytes());
+        4
+    }
+
+    let service_id = next_service_id();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0);
+    let deps = ServerDeps::tokio().with_non_sd_observer(Some((length, 0)));
+    let (server, _handles, _run): (TestServer, _, _) =
+        TestServer::new_with_deps(deps, config, false)
+            .await
+            .expect("Server::new_with_deps failed");
+    let std::net::SocketAddr::V4(server_addr) = server.unicast_local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(server.run());
+
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let request: Vec<u8> = (0..5000u32).map(|i| i.to_le_bytes()[0]).collect();
+    let pending = client
+        .send_to_service(key, raw_request(service_id, &request))
+        .await
+        .expect("segmented send");
+    let response = tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+        .await
+        .expect("reply to the segmented request")
+        .expect("response");
+    assert_eq!(response.raw_bytes(), Some(&5000u32.to_be_bytes()[..]));
+
+    let _subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(
+        wait_for_subscribers(&publisher, service_id, 1, 0x01).await,
+        "server should have registered the subscriber"
+    );
+    let event: Vec<u8> = (0..4000u32).map(|i| i.to_be_bytes()[3] ^ 0x5A).collect();
+    publisher
+        .publish_raw_event(service_id, 1, 0x01, 0x8001, 1, 1, 1, &event)
+        .await
+        .expect("segmented publish");
+    let ClientUpdate::Unicast { message, .. } = recv_unicast(&mut updates).await else {
+        unreachable!();
+    };
+    assert!(!message.header().message_type().is_tp());
+    assert_eq!(message.payload().raw_bytes(), Some(event.as_slice()));
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// With the janitor enabled, the server closes TCP connections that went
+/// silent, and so does the client.
+#[tokio::test]
+async fn test_janitor_closes_idle_tcp_connections() {
+    use simple_someip::{JanitorConfig, ServerDeps, StreamListener, TokioListener};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn echo(
+        _ctx: usize,
+        _source: SocketAddrV4,
+        _service_id: u16,
+        _method_id: u16,
+        payload: &[u8],
+        _e2e_status: u8,
+        response_out: &mut [u8],
+    ) -> i32 {
+        response_out[..payload.len()].copy_from_slice(payload);
+        i32::try_from(payload.len()).unwrap()
+    }
+
+    let janitor = JanitorConfig::new()
+        .with_interval(Duration::from_millis(50))
+        .with_tcp_idle_timeout(Some(Duration::from_millis(200)));
+
+    // Server side: a silent peer is hung up on.
+    let service_id = next_service_id();
+    let listener = TokioListener::bind(SocketAddrV4::new(SERVER_IP, 0))
+        .await
+        .unwrap();
+    let tcp_addr = listener.local_addr().unwrap();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_tcp_port(tcp_addr.port())
+        .with_janitor(janitor);
+    let deps = ServerDeps::tokio().with_non_sd_observer(Some((echo, 0)));
+    let (server, _handles, _run): (TestServer, _, _) =
+        TestServer::new_with_deps(deps, config, false)
+            .await
+            .expect("Server::new_with_deps failed");
+    let serve_handle = tokio::spawn(server.serve_tcp(listener));
+
+    let mut stream = tokio::net::TcpStream::connect(tcp_addr).await.unwrap();
+    let mut request = vec![0u8; 16];
+    request[0..2].copy_from_slice(&service_id.to_be_bytes());
+    request[4..8].copy_from_slice(&8u32.to_be_bytes());
+    request[12] = 0x01;
+    stream.write_all(&request).await.unwrap();
+    let _reply = read_tcp_message(&mut stream).await;
+    let mut rest = [0u8; 1];
+    let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut rest))
+        .await
+        .expect("server closed the idle connection")
+        .unwrap();
+    assert_eq!(read, 0, "EOF");
+    let stats = server.janitor_stats().unwrap();
+    assert_eq!(stats.tcp_connections_closed, 1);
+    assert!(stats.sweeps > 0);
+    serve_handle.abort();
+
+    // Client side: the connection to a provider that stopped talking is
+    // closed, and reopened by the next request.
+    let provider = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(provider_addr) = provider.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    client.set_janitor(Some(janitor)).await.unwrap();
+    let key = ServiceEndpointKey::new(
+        service_id,
+        simple_someip::NetEndpoint::tcp(SocketAddr::V4(provider_addr)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    for _ in 0..2 {
+        let pending = client
+            .send_to_service(key, raw_request(service_id, &[7]))
+            .await
+            .expect("send over TCP");
+        let (mut stream, _) = provider.accept().await.unwrap();
+        let mut reply = read_tcp_message(&mut stream).await;
+        reply[14] = 0x80;
+        stream.write_all(&reply).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(2), pending.response())
+            .await
+            .expect("reply over TCP")
+            .expect("response");
+        let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut rest))
+            .await
+            .expect("client closed the idle connection")
+            .unwrap();
+        assert_eq!(read, 0, "EOF");
+    }
+    let stats = client.janitor_stats().await.unwrap();
+    assert_eq!(stats.tcp_connections_closed, 2);
+    client.shut_down();
+}
+
+#[tokio::test]
+async fn test_request_router_answers_with_response_or_error() {
+    use simple_someip::protocol::ReturnCode;
+    use simple_someip::server::{MethodRouter, Request};
+    use simple_someip::{ServerDeps, StreamListener, TokioListener};
+    use tokio::io::AsyncWriteExt;
+
+    let service_id = next_service_id();
+    let listener = TokioListener::bind(SocketAddrV4::new(SERVER_IP, 0))
+        .await
+        .unwrap();
+    let tcp_addr = listener.local_addr().unwrap();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_tcp_port(tcp_addr.port());
+    let (server, _handles, _run): (TestServer, _, _) =
+        TestServer::new_with_deps(ServerDeps::tokio(), config, false)
+            .await
+            .expect("Server::new_with_deps failed");
+    server.set_request_router(Some(
+        MethodRouter::new().route(0x0001, |request: Request| async move {
+            Ok(request.payload.into_iter().rev().collect())
+        }),
+    ));
+    let serve_handle = tokio::spawn(server.serve_tcp(listener));
+
+    let mut stream = tokio::net::TcpStream::connect(tcp_addr).await.unwrap();
+    let request = |method_id: u16| {
+        let mut request = vec![0u8; 19];
+        request[0..2].copy_from_slice(&service_id.to_be_bytes());
+        request[2..4].copy_from_slice(&method_id.to_be_bytes());
+        request[4..8].copy_from_slice(&11u32.to_be_bytes());
+        request[8..12].copy_from_slice(&0x0001_0042u32.to_be_bytes());
+        request[12] = 0x01;
+        request[13] = 0x01;
+        request[16..].copy_from_slice(&[1, 2, 3]);
+        request
+    };
+
+    stream.write_all(&request(0x0001)).await.unwrap();
+    let reply = read_tcp_message(&mut stream).await;
+    assert_eq!(reply[0..4], request(0x0001)[0..4], "message ID echoed");
+    assert_eq!(
+        reply[8..12],
+        0x0001_0042u32.to_be_bytes(),
+        "request ID echoed"
+    );
+    assert_eq!(reply[14], 0x80, "RESPONSE");
+    assert_eq!(reply[15], u8::from(ReturnCode::Ok));
+    assert_eq!(reply[16..], [3, 2, 1]);
+
+    stream.write_all(&request(0x0002)).await.unwrap();
+    let reply = read_tcp_message(&mut stream).await;
+    assert_eq!(reply[14], 0x81, "ERROR");
+    assert_eq!(reply[15], u8::from(ReturnCode::UnknownMethod));
+    assert_eq!(reply.len(), 16, "empty payload");
+    serve_handle.abort();
+}
+
+#[tokio::test]
+async fn test_reply_failing_e2e_check_resolves_to_an_error() {
+    use simple_someip::server::{MethodRouter, Request};
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    // The server has no profile, so its reply goes out unprotected.
+    server.set_request_router(Some(
+        MethodRouter::new().route(0x0001, |_: Request| async { Ok(vec![1, 2, 3]) }),
+    ));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    client
+        .register_e2e(
+            E2EKey {
+                service_id,
+                method_or_event_id: 0x0001,
+            },
+            E2EProfile::Profile4(Profile4Config::new(0x12345678, 15)),
+        )
+        .expect("E2E registry has capacity for one entry");
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let pending = client
+        .send_to_service(key, raw_request(service_id, &[1]))
+        .await
+        .unwrap();
+    let error = tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+        .await
+        .expect("reply")
+        .expect_err("unprotected reply");
+    assert!(
+        matches!(
+            error,
+            simple_someip::client::Error::E2ECheckFailed(status) if status.is_failure()
+        ),
+        "expected E2ECheckFailed, got {error:?}"
+    );
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_application_error_reaches_client_as_typed_error() {
+    use simple_someip::protocol::{ApplicationError, ReturnCode};
+    use simple_someip::server::{MethodRouter, Request};
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    server.set_request_router(Some(
+        MethodRouter::new().route(0x0001, |_: Request| async {
+            Err(ApplicationError::new(0x8000_0000_0000_0042, -1).into())
+        }),
+    ));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let pending = client
+        .send_to_service(key, raw_request(service_id, &[1]))
+        .await
+        .unwrap();
+    let error = tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+        .await
+        .expect("reply")
+        .expect_err("ERROR message");
+    match error {
+        simple_someip::client::Error::ErrorResponse {
+            return_code,
+            application_error,
+        } => {
+            assert_eq!(return_code, ReturnCode::NotOk);
+            assert_eq!(
+                application_error,
+                Some(ApplicationError::new(0x8000_0000_0000_0042, -1))
+            );
+        }
+        other => panic!("expected ErrorResponse, got {other:?}"),
+    }
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_field_getter_and_setter_share_the_value() {
+    use simple_someip::protocol::ReturnCode;
+    use simple_someip::server::{Field, MethodRouter};
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let sender = server.event_sender(service_id, 1, 0x01, 0x8001);
+    let field = Field::new(0x0102u16).with_notifier(move |payload: Vec<u8>| {
+        let sender = sender.clone();
+        async move { sender.send(&payload).await }
+    });
+    server.set_request_router(Some(MethodRouter::new().field(
+        &field,
+        Some(0x0001),
+        Some(0x0002),
+    )));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let call = |method_id: u16, payload: &'static [u8]| {
+        let client = client.clone();
+        async move {
+            let pending = client
+                .send_to_service(key, raw_request_to(service_id, method_id, payload))
+                .await
+                .unwrap();
+            tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+                .await
+                .expect("reply")
+        }
+    };
+
+    let reply = call(0x0001, &[]).await.unwrap();
+    assert_eq!(reply.raw_bytes(), Some(&[0x01, 0x02][..]));
+    let reply = call(0x0002, &[0xBE, 0xEF]).await.unwrap();
+    assert_eq!(reply.raw_bytes(), Some(&[0xBE, 0xEF][..]));
+    assert_eq!(field.get(), 0xBEEF);
+    let reply = call(0x0001, &[]).await.unwrap();
+    assert_eq!(reply.raw_bytes(), Some(&[0xBE, 0xEF][..]));
+    match call(0x0002, &[0xBE]).await {
+        Err(simple_someip::client::Error::ErrorResponse { return_code, .. }) => {
+            assert_eq!(return_code, ReturnCode::MalformedMessage);
+        }
+        other => panic!("expected ErrorResponse, got {other:?}"),
+    }
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_measure_latency_against_echo_method() {
+    use simple_someip::client::LatencyProbe;
+    use simple_someip::server::MethodRouter;
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    server.set_request_router(Some(MethodRouter::new().echo(0x0001)));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let mut samples = [std::time::Duration::ZERO; 8];
+    let stats = client
+        .measure_latency(
+            &simple_someip::TokioTimer,
+            key,
+            LatencyProbe::new(0x0001).with_payload(&[0xAB; 32]),
+            &mut samples,
+        )
+        .await
+        .unwrap()
+        .expect("echo answered");
+    assert_eq!((stats.answered, stats.lost), (8, 0));
+    assert!(stats.min <= stats.median && stats.median <= stats.p99);
+    assert_eq!(stats.p99, stats.max);
+
+    let stats = client
+        .measure_latency(
+            &simple_someip::TokioTimer,
+            key,
+            LatencyProbe::new(0x0002),
+            &mut samples[..1],
+        )
+        .await;
+    assert!(matches!(
+        stats,
+        Err(simple_someip::client::Error::ErrorResponse { .. })
+    ));
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// Requests sent before any reply arrives each resolve to their own reply.
+#[tokio::test]
+async fn test_concurrent_requests_resolve_to_their_own_replies() {
+    use simple_someip::server::{MethodRouter, Request};
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    server.set_request_router(Some(MethodRouter::new().route(
+        0x0001,
+        |request: Request| async move { Ok(request.payload) },
+    )));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let mut pending = Vec::new();
+    for tag in 0..8u8 {
+        pending.push(
+            client
+                .send_to_service(key, raw_request(service_id, &[tag]))
+                .await
+                .unwrap(),
+        );
+    }
+    for (tag, pending) in (0..8u8).zip(pending) {
+        let reply = tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+            .await
+            .expect("reply")
+            .expect("RESPONSE");
+        assert_eq!(reply.raw_bytes(), Some(&[tag][..]));
+    }
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// An unanswered request is resent with a fresh request ID, and resolves
+/// to a timeout once the retries are spent.
+#[tokio::test]
+async fn test_request_policy_resends_then_times_out() {
+    use simple_someip::RequestPolicy;
+    use simple_someip::protocol::HeaderView;
+    use std::time::Duration;
+
+    let provider = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(provider_addr) = provider.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let service_id = next_service_id();
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(provider_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let policy =
+        RequestPolicy::new(Duration::from_millis(200)).with_retries(1, Duration::from_millis(100));
+    let pending = client
+        .send_to_service_with_policy(key, raw_request(service_id, &[1]), policy)
+        .await
+        .unwrap();
+    let mut buf = [0u8; 64];
+    let mut request_ids = Vec::new();
+    for _ in 0..2 {
+        let (len, _) = tokio::time::timeout(Duration::from_secs(2), provider.recv_from(&mut buf))
+            .await
+            .expect("request")
+            .unwrap();
+        let (header, _) = HeaderView::parse(&buf[..len]).unwrap();
+        request_ids.push(header.request_id());
+    }
+    assert_ne!(request_ids[0], request_ids[1], "resent with a fresh ID");
+    let result = tokio::time::timeout(Duration::from_secs(2), pending.response())
+        .await
+        .expect("resolves");
+    assert!(matches!(result, Err(simple_someip::client::Error::Timeout)));
+    client.shut_down();
+}
+
+/// With redundant providers, a request that times out at the selected
+/// one is answered by the next.
+#[tokio::test]
+async fn test_request_with_failover_moves_to_the_next_provider() {
+    use simple_someip::server::{MethodRouter, Request};
+    use simple_someip::{ProviderSelection, RequestPolicy};
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let silent = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let (server, server_port) = create_server(service_id, 1).await;
+    server.set_request_router(Some(MethodRouter::new().route(
+        0x0001,
+        |request: Request| async move { Ok(request.payload) },
+    )));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let request = || raw_request(service_id, &[7]);
+    let policy = RequestPolicy::new(Duration::from_millis(200));
+    assert!(matches!(
+        client
+            .request_with_failover(service_id, 1, request(), policy)
+            .await,
+        Err(simple_someip::client::Error::ServiceNotFound)
+    ));
+
+    let silent_key = ServiceEndpointKey::udp(service_id, silent.local_addr().unwrap());
+    let live_key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(silent_key, 1, 0).await.unwrap();
+    client.add_endpoint(live_key, 1, 0).await.unwrap();
+    client
+        .set_provider_selection(service_id, Some(ProviderSelection::FirstSeen))
+        .await
+        .unwrap();
+
+    let reply = tokio::time::timeout(
+        Duration::from_secs(3),
+        client.request_with_failover(service_id, 1, request(), policy),
+    )
+    .await
+    .expect("resolves")
+    .expect("answered by the second provider");
+    assert_eq!(reply.raw_bytes(), Some(&[7][..]));
+    let mut buf = [0u8; 64];
+    assert!(
+        silent.try_recv_from(&mut buf).is_ok(),
+        "the first-seen provider was tried first"
+    );
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_sessionless_service_fire_and_forget_carries_session_zero() {
+    use simple_someip::protocol::{HeaderView, MessageType, MessageTypeField, ReturnCode};
+    use std::time::Duration;
+
+    let provider = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(provider_addr) = provider.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let service_id = next_service_id();
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(provider_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    client
+        .set_session_handling(service_id, false)
+        .await
+        .unwrap();
+    let fire_and_forget = |payload: u8| {
+        let msg_id = MessageId::new_from_service_and_method(service_id, 0x0001);
+        let header = Header::new(
+            msg_id,
+            0,
+            1,
+            1,
+            MessageTypeField::new(MessageType::RequestNoReturn, false),
+            ReturnCode::Ok,
+            1,
+        );
+        Message::new(
+            header,
+            RawPayload::from_payload_bytes(msg_id, &[payload]).unwrap(),
+        )
+    };
+
+    let mut buf = [0u8; 64];
+    let mut receive = async || {
+        let (len, _) = tokio::time::timeout(Duration::from_secs(2), provider.recv_from(&mut buf))
+            .await
+            .expect("message")
+            .unwrap();
+        let (header, _) = HeaderView::parse(&buf[..len]).unwrap();
+        header.request_id() & 0xFFFF
+    };
+    for payload in [1u8, 2] {
+        client
+            .send_to_service(key, fire_and_forget(payload))
+            .await
+            .unwrap();
+        assert_eq!(receive().await, 0, "session ID 0");
+    }
+
+    // Session handling back on: fire-and-forget messages count again.
+    client.set_session_handling(service_id, true).await.unwrap();
+    client
+        .send_to_service(key, fire_and_forget(3))
+        .await
+        .unwrap();
+    assert_ne!(receive().await, 0);
+    client.shut_down();
+}
+
+/// Two requests to the same method of a session-less service are pending
+/// at once; each reply reaches its own request.
+#[tokio::test]
+async fn test_sessionless_service_concurrent_requests_stay_apart() {
+    use simple_someip::protocol::HeaderView;
+    use std::time::Duration;
+
+    let provider = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let SocketAddr::V4(provider_addr) = provider.local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let service_id = next_service_id();
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(provider_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    client
+        .set_session_handling(service_id, false)
+        .await
+        .unwrap();
+
+    let first = client
+        .send_to_service(key, raw_request(service_id, &[1]))
+        .await
+        .unwrap();
+    let second = client
+        .send_to_service(key, raw_request(service_id, &[2]))
+        .await
+        .unwrap();
+    let mut requests = std::vec::Vec::new();
+    for _ in 0..2 {
+        let mut buf = [0u8; 64];
+        let (len, source) =
+            tokio::time::timeout(Duration::from_secs(2), provider.recv_from(&mut buf))
+                .await
+                .expect("request")
+                .unwrap();
+        let (header, _) = HeaderView::parse(&buf[..len]).unwrap();
+        assert_ne!(header.request_id() & 0xFFFF, 0, "requests keep session IDs");
+        requests.push((buf, len, source));
+    }
+    // Echo them back as RESPONSEs, the second one first.
+    for (mut buf, len, source) in requests.into_iter().rev() {
+        buf[14] = 0x80;
+        provider.send_to(&buf[..len], source).await.unwrap();
+    }
+    for (pending, payload) in [(first, 1u8), (second, 2)] {
+        let reply = tokio::time::timeout(Duration::from_secs(2), pending.response())
+            .await
+            .expect("resolves")
+            .unwrap();
+        assert_eq!(reply.raw_bytes(), Some(&[payload][..]));
+    }
+    client.shut_down();
+}
+
+#[tokio::test]
+async fn test_subscribe_eventgroup_waits_for_ack_and_refreshes() {
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_event_group(0x01);
+    let (server, _handles, _run): (TestServer, _, _) = TestServer::new(config).await.unwrap();
+    let SocketAddr::V4(server_addr) = server.unicast_local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    tokio::time::timeout(
+        Duration::from_secs(3),
+        client.subscribe_eventgroup(service_id, 1, 0x01, 1),
+    )
+    .await
+    .expect("answered")
+    .expect("acknowledged");
+    assert!(publisher.has_subscribers(service_id, 1, 0x01).await);
+
+    // Refreshed halfway through the 1 s TTL.
+    let sent_to_server = || async {
+        client
+            .peer_stats()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|(addr, _)| addr.ip() == SERVER_IP)
+            .map(|(_, stats)| stats.sent)
+            .sum::<u64>()
+    };
+    let before = sent_to_server().await;
+    tokio::time::sleep(Duration::from_millis(900)).await;
+    assert!(sent_to_server().await > before, "subscription refreshed");
+
+    client
+        .unsubscribe_eventgroup(service_id, 1, 0x01)
+        .await
+        .unwrap();
+    let mut stopped = false;
+    for _ in 0..20 {
+        if !publisher.has_subscribers(service_id, 1, 0x01).await {
+            stopped = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(stopped, "subscription stopped");
+    assert!(matches!(
+        client.unsubscribe_eventgroup(service_id, 1, 0x01).await,
+        Err(simple_someip::client::Error::NotSubscribed)
+    ));
+
+    // An eventgroup the server does not know is rejected.
+    let rejected = tokio::time::timeout(
+        Duration::from_secs(3),
+        client.subscribe_eventgroup(service_id, 1, 0x02, 1),
+    )
+    .await
+    .expect("answered");
+    assert!(matches!(
+        rejected,
+        Err(simple_someip::client::Error::SubscriptionRejected {
+            event_group_id: 0x02,
+            ..
+        })
+    ));
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_subscription_handle_reports_ack_and_unsubscribes_on_drop() {
+    use simple_someip::client::SubscriptionState;
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_event_group(0x01)
+        .with_event_group(0x02);
+    let (server, _handles, _run): (TestServer, _, _) = TestServer::new(config).await.unwrap();
+    let SocketAddr::V4(server_addr) = server.unicast_local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let mut subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(3), subscription.acknowledged())
+        .await
+        .expect("answered")
+        .expect("acknowledged");
+    assert_eq!(
+        subscription.state().await.unwrap(),
+        SubscriptionState::Acknowledged
+    );
+    // The answer is kept for later calls.
+    subscription.acknowledged().await.unwrap();
+    assert!(publisher.has_subscribers(service_id, 1, 0x01).await);
+
+    // A detached subscription outlives its handle.
+    client.subscribe(key, 1, 3, 0x02, 0).await.unwrap().detach();
+    assert!(wait_for_subscribers(&publisher, service_id, 1, 0x02).await);
+
+    drop(subscription);
+    let mut stopped = false;
+    for _ in 0..20 {
+        if !publisher.has_subscribers(service_id, 1, 0x01).await {
+            stopped = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(stopped, "dropping the handle stops the subscription");
+    assert!(publisher.has_subscribers(service_id, 1, 0x02).await);
+
+    // An eventgroup the server does not know is rejected.
+    let mut rejected = client.subscribe(key, 1, 3, 0x03, 0).await.unwrap();
+    assert!(matches!(
+        tokio::time::timeout(Duration::from_secs(3), rejected.acknowledged())
+            .await
+            .expect("answered"),
+        Err(simple_someip::client::Error::SubscriptionRejected {
+            event_group_id: 0x03,
+            ..
+        })
+    ));
+    assert_eq!(rejected.state().await.unwrap(), SubscriptionState::Inactive);
+    assert!(matches!(
+        rejected.unsubscribe().await,
+        Err(simple_someip::client::Error::NotSubscribed)
+    ));
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// A second subscription to an eventgroup replaces the first: the first
+/// handle's waiter learns so, and dropping that handle leaves the
+/// replacement alone.
+#[tokio::test]
+async fn test_replaced_subscription_handle_does_not_stop_its_replacement() {
+    use simple_someip::client::SubscriptionState;
+    use std::time::Duration;
+
+    // Never answers, so both subscriptions stay pending.
+    let provider = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let service_id = next_service_id();
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, provider.local_addr().unwrap());
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let mut first = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    let second = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(matches!(
+        tokio::time::timeout(Duration::from_secs(1), first.acknowledged())
+            .await
+            .expect("answered"),
+        Err(simple_someip::client::Error::SubscriptionReplaced)
+    ));
+    assert_eq!(first.state().await.unwrap(), SubscriptionState::Inactive);
+
+    drop(first);
+    assert_eq!(
+        second.state().await.unwrap(),
+        SubscriptionState::Pending,
+        "the replacement is still tracked"
+    );
+    second.unsubscribe().await.unwrap();
+    client.shut_down();
+}
+
+/// One `subscribe_many` call subscribes to several eventgroups, and each
+/// handle reports the provider's answer for its own eventgroup.
+#[tokio::test]
+async fn test_subscribe_many_tracks_each_ack() {
+    use simple_someip::client::{SUBSCRIBE_MANY_CAP, SubscriptionState};
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_event_group(0x01)
+        .with_event_group(0x02);
+    let (server, _handles, _run): (TestServer, _, _) = TestServer::new(config).await.unwrap();
+    let SocketAddr::V4(server_addr) = server.unicast_local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let too_many = [0x01; SUBSCRIBE_MANY_CAP + 1];
+    assert!(matches!(
+        client.subscribe_many(key, 1, 3, &too_many, 0).await,
+        Err(simple_someip::client::Error::Capacity("subscribe_many"))
+    ));
+
+    let mut handles = client
+        .subscribe_many(key, 1, 3, &[0x01, 0x02, 0x03], 0)
+        .await
+        .unwrap();
+    assert_eq!(handles.len(), 3);
+    for handle in &mut handles[..2] {
+        tokio::time::timeout(Duration::from_secs(3), handle.acknowledged())
+            .await
+            .expect("answered")
+            .expect("acknowledged");
+    }
+    assert!(matches!(
+        tokio::time::timeout(Duration::from_secs(3), handles[2].acknowledged())
+            .await
+            .expect("answered"),
+        Err(simple_someip::client::Error::SubscriptionRejected {
+            event_group_id: 0x03,
+            ..
+        })
+    ));
+    assert_eq!(
+        handles[1].state().await.unwrap(),
+        SubscriptionState::Acknowledged
+    );
+    assert!(publisher.has_subscribers(service_id, 1, 0x01).await);
+    assert!(publisher.has_subscribers(service_id, 1, 0x02).await);
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// Datagrams flooding a server that is not reading its unicast socket
+/// overflow the receive queue and show up in `Server::drop_stats`.
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_server_drop_stats_count_receive_queue_overflow() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    assert_eq!(server.drop_stats().unicast, Some(0));
+
+    let flood = std::net::UdpSocket::bind((SERVER_IP, 0)).unwrap();
+    let datagram = [0u8; 1024];
+    for _ in 0..4096 {
+        flood.send_to(&datagram, (SERVER_IP, server_port)).unwrap();
+    }
+    let stats = server.drop_stats();
+    assert!(stats.unicast.unwrap() > 0, "{stats:?}");
+    assert!(stats.discovery.is_some());
+}
+
+/// The client samples the drop count of a socket from its first
+/// datagram on.
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn test_client_drop_stats_sampled_from_received_datagrams() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    assert_eq!(
+        client.drop_stats().await.unwrap(),
+        simple_someip::DropStats::default(),
+        "no socket has received anything"
+    );
+
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let _subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(wait_for_subscribers(&publisher, service_id, 1, 0x01).await);
+    let event_msg = Message::<RawPayload>::new_sd(0x0001, &empty_sd_header());
+    publisher
+        .publish_event(service_id, 1, 0x01, &event_msg)
+        .await
+        .unwrap();
+    recv_unicast(&mut updates).await;
+
+    assert_eq!(client.drop_stats().await.unwrap().unicast, Some(0));
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// A reloaded configuration file reaches the running server and client:
+/// the new TTL is advertised and the new static endpoint becomes
+/// available, while the interface change is left for a rebuild.
+#[tokio::test]
+async fn test_config_reload_is_applied_to_running_components() {
+    use simple_someip::config::{ConfigChange, ConfigNotifier};
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+
+    let path = std::env::temp_dir().join(format!(
+        "simple-someip-reload-{}-{service_id}.conf",
+        std::process::id()
+    ));
+    std::fs::write(&path, "ttl_secs = 3\n").unwrap();
+    let mut notifier = ConfigNotifier::new(&path).unwrap();
+    let server_addr = SocketAddrV4::new(SERVER_IP, server_port);
+    std::fs::write(
+        &path,
+        format!(
+            "interface = 127.0.0.3\nttl_secs = 9\n\
+             static_endpoint = {service_id}/1@{server_addr}\n"
+        ),
+    )
+    .unwrap();
+    let changes = tokio::time::timeout(
+        Duration::from_secs(2),
+        notifier.next_changes(&simple_someip::TokioTimer, Duration::from_millis(10)),
+    )
+    .await
+    .expect("the change is noticed");
+    std::fs::remove_file(&path).unwrap();
+
+    let server_report = server.apply_config_changes(&changes).unwrap();
+    assert!(matches!(
+        server_report.applied[..],
+        [ConfigChange::TtlChanged { to: Some(9), .. }]
+    ));
+    assert!(matches!(
+        server_report.rejected[..],
+        [ConfigChange::InterfaceChanged { .. }]
+    ));
+    assert_eq!(server.ttl(), 9);
+
+    let client_report = client.apply_config_changes(&changes).await.unwrap();
+    assert!(matches!(
+        client_report.applied[..],
+        [ConfigChange::StaticEndpointAdded(_)]
+    ));
+    assert_eq!(client_report.rejected, server_report.rejected);
+    match tokio::time::timeout(Duration::from_secs(1), updates.recv()).await {
+        Ok(Some(ClientUpdate::ServiceAvailable {
+            key,
+            instance_id: 1,
+        })) => assert_eq!(
+            key,
+            ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr))
+        ),
+        other => panic!("expected ServiceAvailable, got {other:?}"),
+    }
+    client.shut_down();
+}
+
+/// A static endpoint is usable without discovery and reported through
+/// the same availability updates as discovered ones.
+#[tokio::test]
+async fn test_static_endpoint_joins_the_availability_model() {
+    use simple_someip::UnavailableReason;
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+
+    let server_addr = SocketAddrV4::new(SERVER_IP, server_port);
+    let key = client
+        .add_static_endpoint(
+            service_id,
+            1,
+            server_addr,
+            simple_someip::TransportProtocol::Udp,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        key,
+        ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr))
+    );
+    match tokio::time::timeout(Duration::from_secs(1), updates.recv()).await {
+        Ok(Some(ClientUpdate::ServiceAvailable {
+            key: got,
+            instance_id: 1,
+        })) => assert_eq!(got, key),
+        other => panic!("expected ServiceAvailable, got {other:?}"),
+    }
+
+    let mut subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(3), subscription.acknowledged())
+        .await
+        .expect("answered")
+        .expect("acknowledged");
+    assert!(publisher.has_subscribers(service_id, 1, 0x01).await);
+    subscription.detach();
+
+    client.remove_endpoint(key).await.unwrap();
+    loop {
+        match tokio::time::timeout(Duration::from_secs(1), updates.recv()).await {
+            Ok(Some(ClientUpdate::ServiceUnavailable {
+                key: got, reason, ..
+            })) => {
+                assert_eq!((got, reason), (key, UnavailableReason::Removed));
+                break;
+            }
+            Ok(Some(_)) => {}
+            other => panic!("expected ServiceUnavailable, got {other:?}"),
+        }
+    }
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// An eventgroup with a receive port of its own is subscribed from it,
+/// apart from the port the rest of the service shares.
+#[tokio::test]
+async fn test_receive_port_isolates_an_eventgroup() {
+    use std::time::Duration;
+
+    let service_id = next_service_id();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_event_group(0x01)
+        .with_event_group(0x02);
+    let (server, _handles, _run): (TestServer, _, _) = TestServer::new(config).await.unwrap();
+    let SocketAddr::V4(server_addr) = server.unicast_local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    client
+        .set_receive_port(service_id, Some(0x02), Some(0))
+        .await
+        .unwrap();
+
+    let mut handles = client
+        .subscribe_many(key, 1, 3, &[0x01, 0x02], 0)
+        .await
+        .unwrap();
+    for handle in &mut handles {
+        tokio::time::timeout(Duration::from_secs(3), handle.acknowledged())
+            .await
+            .expect("answered")
+            .expect("acknowledged");
+    }
+    let shared = publisher.subscriber_addresses(service_id, 1, 0x01).await;
+    let dedicated = publisher.subscriber_addresses(service_id, 1, 0x02).await;
+    assert_eq!((shared.len(), dedicated.len()), (1, 1));
+    assert_ne!(shared[0].port(), dedicated[0].port());
+
+    // Subscribing again reuses the port bound for the eventgroup.
+    drop(handles);
+    let mut again = client.subscribe(key, 1, 3, 0x02, 0).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(3), again.acknowledged())
+        .await
+        .expect("answered")
+        .expect("acknowledged");
+    let resubscribed = publisher.subscriber_addresses(service_id, 1, 0x02).await;
+    assert!(resubscribed.contains(&dedicated[0]));
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// Notifications of an eventgroup's events go to its event stream; the
+/// rest, and everything once the stream is dropped, to the update stream.
+#[tokio::test]
+async fn test_event_stream_receives_its_eventgroup_notifications() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, mut updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+
+    let layout = EventgroupLayout::new()
+        .with_eventgroup(0x01, &[0x8001])
+        .unwrap();
+    assert!(matches!(
+        client.event_stream(service_id, 1, 0x02, &layout).await,
+        Err(simple_someip::client::Error::EventgroupNotInLayout(0x02))
+    ));
+    let mut events = client
+        .event_stream(service_id, 1, 0x01, &layout)
+        .await
+        .unwrap();
+    let _subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(
+        wait_for_subscribers(&publisher, service_id, 1, 0x01).await,
+        "server should have registered the subscriber"
+    );
+
+    let publish = |event_id: u16| {
+        let msg_id = MessageId::new_from_service_and_method(service_id, event_id);
+        let header = Header::new_event(service_id, event_id, 1, 0x01, 0x01, 1);
+        let event = Message::new(
+            header,
+            RawPayload::from_payload_bytes(msg_id, &[0xAA]).unwrap(),
+        );
+        let publisher = &publisher;
+        async move {
+            publisher
+                .publish_event(service_id, 1, 0x01, &event)
+                .await
+                .expect("publish_event failed");
+        }
+    };
+
+    publish(0x8001).await;
+    let event = tokio::time::timeout(std::time::Duration::from_secs(2), events.recv())
+        .await
+        .expect("event on the stream")
+        .expect("stream open");
+    assert_eq!(event.header().message_id().method_id(), 0x8001);
+
+    // Not a member of the eventgroup: left to the update stream.
+    publish(0x8002).await;
+    let ClientUpdate::Unicast { message, .. } = recv_unicast(&mut updates).await else {
+        unreachable!()
+    };
+    assert_eq!(message.header().message_id().method_id(), 0x8002);
+
+    drop(events);
+    publish(0x8001).await;
+    let ClientUpdate::Unicast { message, .. } = recv_unicast(&mut updates).await else {
+        unreachable!()
+    };
+    assert_eq!(message.header().message_id().method_id(), 0x8001);
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// With an authenticator installed, a peer's subscriptions and requests
+/// are refused until it completes the handshake.
+#[tokio::test]
+async fn test_subscriptions_and_requests_wait_for_authentication() {
+    use simple_someip::protocol::ReturnCode;
+    use simple_someip::server::{Handshake, MethodRouter, PeerAuthenticator, Request};
+    use std::time::Duration;
+
+    const HANDSHAKE: u16 = 0x7F00;
+
+    let service_id = next_service_id();
+    let config = ServerConfig::new(service_id, 1)
+        .with_interface(SERVER_IP)
+        .with_local_port(0)
+        .with_event_group(0x01);
+    let (server, _handles, _run): (TestServer, _, _) = TestServer::new(config).await.unwrap();
+    let SocketAddr::V4(server_addr) = server.unicast_local_addr().unwrap() else {
+        panic!("expected IPv4");
+    };
+    server.set_request_router(Some(MethodRouter::new().route(
+        0x0001,
+        |request: Request| async move { Ok(request.payload) },
+    )));
+    server.set_authenticator(Some(PeerAuthenticator::new(
+        HANDSHAKE,
+        |_, message: &[u8]| match message {
+            b"hello" => Handshake::Continue(b"challenge".to_vec()),
+            b"challenge answered" => Handshake::Accept(Vec::new()),
+            _ => Handshake::Reject,
+        },
+    )));
+    let server = std::sync::Arc::new(server);
+    let server_handle = tokio::spawn({
+        let server = std::sync::Arc::clone(&server);
+        async move { server.run().await }
+    });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(service_id, SocketAddr::V4(server_addr));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    let request = |method_id: u16, payload: &[u8]| {
+        let message = raw_request_to(service_id, method_id, payload);
+        let client = client.clone();
+        async move {
+            let pending = client.send_to_service(key, message).await.unwrap();
+            tokio::time::timeout(Duration::from_secs(2), pending.response())
+                .await
+                .expect("reply")
+        }
+    };
+
+    let mut refused = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(matches!(
+        tokio::time::timeout(Duration::from_secs(3), refused.acknowledged())
+            .await
+            .expect("answered"),
+        Err(simple_someip::client::Error::SubscriptionRejected { .. })
+    ));
+    assert!(matches!(
+        request(0x0001, &[1]).await,
+        Err(simple_someip::client::Error::ErrorResponse {
+            return_code: ReturnCode::NotReachable,
+            ..
+        })
+    ));
+    assert!(matches!(
+        request(HANDSHAKE, b"guess").await,
+        Err(simple_someip::client::Error::ErrorResponse {
+            return_code: ReturnCode::NotOk,
+            ..
+        })
+    ));
+
+    let challenge = request(HANDSHAKE, b"hello").await.expect("challenge");
+    assert_eq!(challenge.raw_bytes(), Some(&b"challenge"[..]));
+    assert!(!server.admits(Ipv4Addr::LOCALHOST));
+    request(HANDSHAKE, b"challenge answered")
+        .await
+        .expect("accepted");
+    assert!(server.admits(Ipv4Addr::LOCALHOST));
+
+    let echo = request(0x0001, &[1, 2]).await.expect("answered");
+    assert_eq!(echo.raw_bytes(), Some(&[1, 2][..]));
+    let mut subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(3), subscription.acknowledged())
+        .await
+        .expect("answered")
+        .expect("acknowledged");
+
+    server.revoke_authentication(Ipv4Addr::LOCALHOST);
+    assert!(!server.admits(Ipv4Addr::LOCALHOST));
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+/// The server's snapshot lists its offer and the client's subscription;
+/// the client's lists the offers it knows, its subscription and a request
+/// a silent provider leaves unanswered.
+#[tokio::test]
+async fn test_state_snapshots_list_offers_subscribers_and_pending_requests() {
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    let publisher = server.publisher();
+    let server_handle = tokio::spawn(server.run());
+
+    let silent_service_id = next_service_id();
+    let silent = tokio::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let silent_key = ServiceEndpointKey::udp(silent_service_id, silent.local_addr().unwrap());
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    client.add_endpoint(silent_key, 1, 0).await.unwrap();
+    let _subscription = client.subscribe(key, 1, 3, 0x01, 0).await.unwrap();
+    assert!(wait_for_subscribers(&publisher, service_id, 1, 0x01).await);
+    let _pending = client
+        .send_to_service(silent_key, raw_request_to(silent_service_id, 0x0002, &[1]))
+        .await
+        .unwrap();
+
+    let snapshot = server.state_snapshot().await;
+    assert_eq!(snapshot.offered.len(), 1);
+    assert_eq!(snapshot.offered[0].service_id, service_id);
+    assert!(!snapshot.withdrawn);
+    assert_eq!(snapshot.subscribers.len(), 1);
+    assert_eq!(snapshot.subscribers[0].event_group_id, 0x01);
+
+    let snapshot = client.state_snapshot().await.unwrap();
+    assert_eq!(snapshot.interface, Ipv4Addr::LOCALHOST);
+    let mut offered: Vec<_> = snapshot.offers.iter().map(|offer| offer.key).collect();
+    offered.sort_by_key(|key| key.service_id);
+    assert_eq!(offered, [key, silent_key]);
+    assert_eq!(snapshot.subscriptions.len(), 1);
+    assert_eq!(snapshot.subscriptions[0].key, key);
+    assert_eq!(snapshot.pending_requests.len(), 1);
+    let pending = snapshot.pending_requests[0];
+    assert_eq!(pending.peer, Ipv4Addr::LOCALHOST);
+    assert_eq!(
+        (pending.service_id, pending.method_id),
+        (silent_service_id, 0x0002)
+    );
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_value(server.state_snapshot().await).unwrap();
+        assert_eq!(json["offered"][0]["service_id"], service_id);
+        assert_eq!(json["subscribers"][0]["event_group_id"], 0x01);
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["interface"], "127.0.0.1");
+        assert_eq!(json["pending_requests"][0]["method_id"], 0x0002);
+    }
+
+    client.shut_down();
+    server_handle.abort();
+}
+
+#[tokio::test]
+async fn test_send_to_instance_resolves_and_forgets_the_provider() {
+    use simple_someip::server::{MethodRouter, Request};
+
+    let service_id = next_service_id();
+    let (server, server_port) = create_server(service_id, 1).await;
+    server.set_request_router(Some(MethodRouter::new().route(
+        0x0001,
+        |request: Request| async move { Ok(request.payload) },
+    )));
+    let server_handle = tokio::spawn(async move { server.run().await });
+
+    let (client, _updates, run_fut) = TestClient::new(Ipv4Addr::LOCALHOST);
+    let _run_handle = tokio::spawn(run_fut);
+    let key = ServiceEndpointKey::udp(
+        service_id,
+        SocketAddr::V4(SocketAddrV4::new(SERVER_IP, server_port)),
+    );
+    assert!(matches!(
+        client
+            .send_to_instance(service_id, 1, raw_request(service_id, &[1]))
+            .await,
+        Err(simple_someip::client::Error::ServiceNotFound)
+    ));
+    client.add_endpoint(key, 1, 0).await.unwrap();
+    for payload in [2, 3] {
+        let pending = client
+            .send_to_instance(service_id, 1, raw_request(service_id, &[payload]))
+            .await
+            .unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(2), pending.response())
+            .await
+            .expect("reply")
+            .expect("RESPONSE");
+    }
+
+    client.remove_endpoint(key).await.unwrap();
+    assert!(
+        matches!(
+            client
+                .send_to_instance(service_id, 1, raw_request(service_id, &[4]))
+                .await,
+            Err(simple_someip::client::Error::ServiceNotFound)
+        ),
+        "the remembered provider is gone"
+    );
+    client.shut_down();
+    server_handle.abort();
+}
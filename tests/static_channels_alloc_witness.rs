@@ -110,6 +110,13 @@ define_static_channels! {
         (Result<(), ClientError>, 8),
         (Result<RawPayload, ClientError>, 4),
         (Result<RebootFlag, ClientError>, 4),
+        (Result<Option<Duration>, ClientError>, 4),
+        (Result<simple_someip::PeerTable, ClientError>, 4),
+        (Result<simple_someip::ServiceEndpointKey, ClientError>, 4),
+        (Result<simple_someip::JanitorStats, ClientError>, 4),
+        (Result<simple_someip::DropStats, ClientError>, 4),
+        (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+        (Result<simple_someip::client::SubscriptionState, ClientError>, 4),
     ],
     bounded: [
         ((ControlMessage<RawPayload, WitnessChannels>, 4), 1),
@@ -315,6 +322,7 @@ async fn client_interface_read_after_construction_does_not_allocate() {
             e2e_registry: e2e_handle,
             interface: interface_handle,
             buffer_provider: StaticBufferProvider(&POOL),
+            stream_factory: simple_someip::NoStreams,
         },
         false,
     );
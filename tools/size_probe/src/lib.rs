@@ -386,6 +386,13 @@ mod client_future_probe {
             (Result<(), ClientError>, 16),
             (Result<ProbePayload, ClientError>, 8),
             (Result<RebootFlag, ClientError>, 8),
+            (Result<Option<core::time::Duration>, ClientError>, 8),
+            (Result<simple_someip::PeerTable, ClientError>, 8),
+            (Result<simple_someip::ServiceEndpointKey, ClientError>, 8),
+            (Result<simple_someip::JanitorStats, ClientError>, 8),
+            (Result<simple_someip::DropStats, ClientError>, 8),
+            (Result<simple_someip::client::ClientSnapshot, ClientError>, 1),
+            (Result<simple_someip::client::SubscriptionState, ClientError>, 8),
         ],
         bounded: [
             ((ControlMessage<ProbePayload, ProbeChannels>, 4), 4),
@@ -407,6 +414,7 @@ mod client_future_probe {
             e2e_registry: NullE2ERegistry,
             interface: NullInterface(core::net::Ipv4Addr::LOCALHOST),
             buffer_provider: StaticBufferProvider(&POOL),
+            stream_factory: simple_someip::NoStreams,
         };
         let (_client, _updates, run_fut) = Client::<
             ProbePayload,